@@ -1,9 +1,45 @@
 /// Performance Benchmarks using Criterion
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
-use solana_arbitrage_bot::pool::ObjectPool;
-use solana_arbitrage_bot::metrics::BotMetrics;
-use solana_arbitrage_bot::error::BotError;
+use solana_onchain_arbitrage_bot::pool::ObjectPool;
+use solana_onchain_arbitrage_bot::metrics::BotMetrics;
+use solana_onchain_arbitrage_bot::error::BotError;
+use solana_onchain_arbitrage_bot::latency::LatencyTracker;
+use solana_onchain_arbitrage_bot::dex::raydium::initializer::RaydiumCpmmPool;
+use solana_onchain_arbitrage_bot::dex::whirlpool::initializer::WhirlpoolPool;
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_sdk::pubkey::Pubkey;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Counts heap allocations so benchmarks can report allocations per iteration
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Heap allocations `f` makes per call, averaged over `iterations`
+fn allocations_per_iteration(iterations: usize, mut f: impl FnMut()) -> f64 {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..iterations {
+        f();
+    }
+    (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / iterations as f64
+}
 
 fn bench_object_pool(c: &mut Criterion) {
     let mut group = c.benchmark_group("object_pool");
@@ -86,11 +122,113 @@ fn bench_error_methods(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_latency_percentile(c: &mut Criterion) {
+    let mut group = c.benchmark_group("latency_p95");
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let samples: Vec<Duration> = (0..1000u64)
+        .map(|i| Duration::from_micros((i * 7_919) % 50_000))
+        .collect();
+
+    // Previous approach: clone the whole buffer and sort it on every query
+    group.bench_function("clone_and_sort", |b| {
+        b.iter(|| {
+            let mut latencies = black_box(&samples).clone();
+            latencies.sort();
+            let index = (latencies.len() as f64 * 0.95) as usize;
+            black_box(latencies[index.min(latencies.len() - 1)]);
+        });
+    });
+
+    group.bench_function("sorted_window", |b| {
+        let tracker = LatencyTracker::new();
+        runtime.block_on(async {
+            for sample in &samples {
+                tracker.record_rpc(*sample).await;
+            }
+        });
+        b.iter(|| {
+            black_box(runtime.block_on(tracker.p95_rpc_latency()));
+        });
+    });
+
+    group.bench_function("record_full_window", |b| {
+        let tracker = LatencyTracker::new();
+        runtime.block_on(async {
+            for sample in &samples {
+                tracker.record_rpc(*sample).await;
+            }
+        });
+        let mut next = 0u64;
+        b.iter(|| {
+            next = (next + 7_919) % 50_000;
+            runtime.block_on(tracker.record_rpc(Duration::from_micros(next)));
+        });
+    });
+
+    group.finish();
+}
+
+fn bench_swap_accounts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("swap_accounts");
+    let wallet = Pubkey::new_unique();
+    let mut pool_data =
+        MintPoolData::new(&Pubkey::new_unique().to_string(), &wallet.to_string(), spl_token::ID).unwrap();
+    for _ in 0..4 {
+        pool_data.raydium_pools.push(RaydiumCpmmPool {
+            pool: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            sol_vault: Pubkey::new_unique(),
+            coin_mint: Pubkey::new_unique(),
+            pc_mint: Pubkey::new_unique(),
+            token_reserve: 0,
+            sol_reserve: 0,
+        });
+        pool_data.whirlpool_pools.push(WhirlpoolPool {
+            pool: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            token_vault_a: Pubkey::new_unique(),
+            token_vault_b: Pubkey::new_unique(),
+            tick_arrays: (0..3).map(|_| Pubkey::new_unique()).collect(),
+            current_tick: 0,
+            token_mint_a: Pubkey::new_unique(),
+            token_mint_b: Pubkey::new_unique(),
+            liquidity: 0,
+            sqrt_price: 0,
+        });
+    }
+    pool_data.cache_swap_accounts();
+    let pools = pool_data.dex_pools();
+
+    // Previous approach: every pool builds a fresh vector each iteration
+    let mut rebuild = || {
+        for pool in &pools {
+            black_box(pool.get_swap_accounts(&wallet).len());
+        }
+    };
+    let mut cached = || {
+        for pool in &pools {
+            black_box(pool_data.swap_accounts(*pool, &wallet, None).len());
+        }
+    };
+    println!(
+        "swap_accounts allocations per iteration over {} pools: rebuild {:.1}, cached {:.1}",
+        pools.len(),
+        allocations_per_iteration(1_000, &mut rebuild),
+        allocations_per_iteration(1_000, &mut cached)
+    );
+
+    group.bench_function("rebuild", |b| b.iter(&mut rebuild));
+    group.bench_function("cached", |b| b.iter(&mut cached));
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_object_pool,
     bench_metrics_operations,
     bench_error_creation,
-    bench_error_methods
+    bench_error_methods,
+    bench_latency_percentile,
+    bench_swap_accounts
 );
 criterion_main!(benches);
@@ -2,19 +2,32 @@
 /// 
 /// Provides an interactive command-line interface for bot management.
 
+use crate::config::Config;
+use crate::discovery::DiscoverableDex;
+use crate::error::BotResult;
+use crate::export::ExportFormat;
+use crate::latency::EndpointLatency;
+use crate::metrics::TimestampedSnapshot;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 use std::path::PathBuf;
 
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
 #[derive(Parser)]
 #[command(name = "solana-arbitrage-bot")]
 #[command(author = "Solana Arbitrage Team")]
 #[command(version = "1.0.0")]
 #[command(about = "Enterprise-grade Solana arbitrage bot", long_about = None)]
 pub struct Cli {
-    /// Configuration file path
-    #[arg(short, long, value_name = "FILE", default_value = "config.toml")]
-    pub config: PathBuf,
+    /// Configuration file path (defaults to config.toml, or BOT_* environment
+    /// variables when that file does not exist)
+    #[arg(short, long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Build the configuration from BOT_* environment variables instead of a file
+    #[arg(long, conflicts_with = "config")]
+    pub from_env: bool,
 
     /// Log level (error, warn, info, debug, trace)
     #[arg(short, long, default_value = "info")]
@@ -35,6 +48,10 @@ pub enum Commands {
         /// Enable dry-run mode (no actual transactions)
         #[arg(long)]
         dry_run: bool,
+
+        /// Write every transaction about to be sent, decoded, into this directory
+        #[arg(long, value_name = "DIR")]
+        dump_tx: Option<PathBuf>,
     },
 
     /// Validate configuration file
@@ -44,6 +61,17 @@ pub enum Commands {
         config: PathBuf,
     },
 
+    /// Warn about suspicious but valid configuration
+    Lint {
+        /// Configuration file to lint
+        #[arg(value_name = "FILE")]
+        config: PathBuf,
+
+        /// Exit with an error if any warning is found
+        #[arg(long)]
+        strict: bool,
+    },
+
     /// Show bot statistics
     Stats,
 
@@ -62,9 +90,110 @@ pub enum Commands {
         #[arg(short, long, default_value = "config.example.toml")]
         output: PathBuf,
     },
+
+    /// Export trade history from the database (uses DATABASE_URL)
+    Export {
+        /// Output file path
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Earliest trade timestamp to include (unix seconds)
+        #[arg(long)]
+        from_ts: Option<i64>,
+
+        /// Latest trade timestamp to include (unix seconds)
+        #[arg(long)]
+        to_ts: Option<i64>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "csv")]
+        format: ExportFormat,
+    },
+
+    /// Find a mint's pools on-chain with getProgramAccounts (uses the config's RPC)
+    DiscoverPools {
+        /// Token mint to find pools for
+        mint: String,
+
+        /// DEX to search
+        #[arg(long, value_enum)]
+        dex: DiscoverableDex,
+    },
+
+    /// Load one known pool per DEX and check each still deserializes
+    SelfTest {
+        /// RPC URL to fetch pools from (defaults to the config's RPC)
+        #[arg(long)]
+        rpc_url: Option<String>,
+
+        /// TOML file of `[[fixture]]` pools replacing the built-in ones
+        #[arg(long, value_name = "FILE")]
+        fixtures: Option<PathBuf>,
+    },
+
+    /// Watch a pool over WebSocket and RPC polling and report how much earlier WS updates arrive
+    CompareFeeds {
+        /// Pool account to watch
+        pool: String,
+
+        /// Sample window in seconds
+        #[arg(long, default_value = "60")]
+        window_secs: u64,
+    },
+
+    /// Dry-run two config files and compare each mint's route (profit, accounts, pools)
+    Diff {
+        /// Config currently deployed
+        old: PathBuf,
+
+        /// Config with the proposed changes
+        new: PathBuf,
+    },
+
+    /// Check the writability of every configured pool's swap accounts against on-chain state
+    AuditAccounts {
+        /// Configured mint whose pools to audit
+        mint: String,
+    },
+
+    /// Simulate a swap through each configured pool alone and report whether its DEX
+    /// program accepts the swap accounts
+    ValidateSwapAccounts {
+        /// Configured mint whose pools to check
+        mint: String,
+    },
 }
 
 impl Cli {
+    /// Where the configuration is read from, as shown in logs
+    pub fn config_source(&self) -> String {
+        match self.config_file() {
+            Some(path) => path.display().to_string(),
+            None => "environment".to_string(),
+        }
+    }
+
+    /// Load the configuration from the selected file or the environment
+    pub fn load_config(&self) -> BotResult<Config> {
+        match self.config_file() {
+            Some(path) => Config::load(path.to_str().unwrap_or(DEFAULT_CONFIG_FILE)),
+            None => Config::from_env(),
+        }
+    }
+
+    fn config_file(&self) -> Option<PathBuf> {
+        if self.from_env {
+            return None;
+        }
+        match &self.config {
+            Some(path) => Some(path.clone()),
+            None => {
+                let default = PathBuf::from(DEFAULT_CONFIG_FILE);
+                default.exists().then_some(default)
+            }
+        }
+    }
+
     pub fn print_banner() {
         println!("{}", "=".repeat(60).bright_cyan());
         println!("{}", "   Solana Arbitrage Bot v1.0.0".bright_green().bold());
@@ -88,6 +217,8 @@ impl Cli {
             ("Meteora DAMM V2", "✓", "Dynamic AMM V2"),
             ("Solfi", "✓", "Standard AMM"),
             ("Vertigo", "✓", "Standard AMM"),
+            ("Lifinity", "✓", "Oracle-based AMM"),
+            ("Phoenix", "✓", "Order Book"),
         ];
 
         for (name, status, description) in dexs {
@@ -104,6 +235,68 @@ impl Cli {
         println!("{}", "Bot Performance Metrics".bright_yellow().bold());
         println!("{}", "-".repeat(60).bright_black());
     }
+
+    /// Print per-interval tx and profit sparklines from recorded snapshots
+    pub fn print_history(history: &[TimestampedSnapshot]) {
+        if history.len() < 2 {
+            println!("  Not enough history yet ({} snapshots)", history.len());
+            return;
+        }
+
+        let deltas = |value: fn(&TimestampedSnapshot) -> u64| -> Vec<f64> {
+            history
+                .windows(2)
+                .map(|pair| value(&pair[1]).saturating_sub(value(&pair[0])) as f64)
+                .collect()
+        };
+        let tx_rate = deltas(|s| s.snapshot.transactions_sent);
+        let profit = deltas(|s| s.snapshot.total_profit_lamports);
+        let latest = &history[history.len() - 1].snapshot;
+
+        println!("  {} {}", "Tx sent  ".bright_white(), sparkline(&tx_rate).bright_green());
+        println!("  {} {}", "Profit   ".bright_white(), sparkline(&profit).bright_green());
+        println!(
+            "  Transactions sent: {}  Success rate: {:.2}%  Total profit: {} SOL",
+            latest.transactions_sent,
+            latest.tx_success_rate(),
+            latest.total_profit_sol()
+        );
+        println!();
+    }
+
+    /// Per-endpoint latency percentiles reported by a running bot
+    pub fn print_endpoint_latency(latencies: &[EndpointLatency]) {
+        for latency in latencies {
+            println!(
+                "  {} {}  p50 {:.1}ms  p95 {:.1}ms  p99 {:.1}ms  ({} samples)",
+                latency.feed.as_str().bright_white(),
+                latency.endpoint,
+                latency.p50_ms,
+                latency.p95_ms,
+                latency.p99_ms,
+                latency.samples
+            );
+        }
+        if !latencies.is_empty() {
+            println!();
+        }
+    }
+}
+
+/// Render values as a unicode block sparkline scaled to the largest value
+pub fn sparkline(values: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().cloned().fold(0.0_f64, f64::max);
+    values
+        .iter()
+        .map(|value| {
+            if max <= 0.0 {
+                BARS[0]
+            } else {
+                BARS[((value / max) * (BARS.len() - 1) as f64).round() as usize]
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -113,6 +306,110 @@ mod tests {
     #[test]
     fn test_cli_parsing() {
         let cli = Cli::parse_from(["bot", "--config", "test.toml"]);
-        assert_eq!(cli.config, PathBuf::from("test.toml"));
+        assert_eq!(cli.config, Some(PathBuf::from("test.toml")));
+        assert_eq!(cli.config_source(), "test.toml");
+    }
+
+    #[test]
+    fn test_from_env_parsing() {
+        let cli = Cli::parse_from(["bot", "--from-env"]);
+        assert!(cli.from_env);
+        assert_eq!(cli.config_source(), "environment");
+
+        assert!(Cli::try_parse_from(["bot", "--from-env", "--config", "test.toml"]).is_err());
+    }
+
+    #[test]
+    fn test_lint_parsing() {
+        let cli = Cli::parse_from(["bot", "lint", "config.toml", "--strict"]);
+        match cli.command {
+            Some(Commands::Lint { config, strict }) => {
+                assert_eq!(config, PathBuf::from("config.toml"));
+                assert!(strict);
+            }
+            _ => panic!("expected lint command"),
+        }
+    }
+
+    #[test]
+    fn test_sparkline_scales_to_max() {
+        assert_eq!(sparkline(&[0.0, 4.0, 8.0]), "▁▅█");
+        assert_eq!(sparkline(&[0.0, 0.0]), "▁▁");
+    }
+
+    #[test]
+    fn test_export_parsing() {
+        let cli = Cli::parse_from(["bot", "export", "--output", "trades.json", "--format", "json"]);
+        match cli.command {
+            Some(Commands::Export { output, from_ts, format, .. }) => {
+                assert_eq!(output, PathBuf::from("trades.json"));
+                assert_eq!(from_ts, None);
+                assert_eq!(format, ExportFormat::Json);
+            }
+            _ => panic!("expected export command"),
+        }
+    }
+
+    #[test]
+    fn test_discover_pools_parsing() {
+        let mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        let cli = Cli::parse_from(["bot", "discover-pools", mint, "--dex", "raydium-cpmm"]);
+        match cli.command {
+            Some(Commands::DiscoverPools { mint: parsed, dex }) => {
+                assert_eq!(parsed, mint);
+                assert_eq!(dex, DiscoverableDex::RaydiumCpmm);
+            }
+            _ => panic!("expected discover-pools command"),
+        }
+    }
+
+    #[test]
+    fn test_self_test_parsing() {
+        let cli = Cli::parse_from(["bot", "self-test", "--fixtures", "pools.toml"]);
+        match cli.command {
+            Some(Commands::SelfTest { rpc_url, fixtures }) => {
+                assert_eq!(rpc_url, None);
+                assert_eq!(fixtures, Some(PathBuf::from("pools.toml")));
+            }
+            _ => panic!("expected self-test command"),
+        }
+    }
+
+    #[test]
+    fn test_compare_feeds_parsing() {
+        let cli = Cli::parse_from([
+            "bot",
+            "compare-feeds",
+            "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2",
+        ]);
+        match cli.command {
+            Some(Commands::CompareFeeds { pool, window_secs }) => {
+                assert_eq!(pool, "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2");
+                assert_eq!(window_secs, 60);
+            }
+            _ => panic!("expected compare-feeds command"),
+        }
+    }
+
+    #[test]
+    fn test_diff_parsing() {
+        let cli = Cli::parse_from(["bot", "diff", "old.toml", "new.toml"]);
+        match cli.command {
+            Some(Commands::Diff { old, new }) => {
+                assert_eq!(old, PathBuf::from("old.toml"));
+                assert_eq!(new, PathBuf::from("new.toml"));
+            }
+            _ => panic!("expected diff command"),
+        }
+    }
+
+    #[test]
+    fn test_audit_accounts_parsing() {
+        let mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        let cli = Cli::parse_from(["bot", "audit-accounts", mint]);
+        match cli.command {
+            Some(Commands::AuditAccounts { mint: parsed }) => assert_eq!(parsed, mint),
+            _ => panic!("expected audit-accounts command"),
+        }
     }
 }
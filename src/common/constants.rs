@@ -6,6 +6,7 @@
 use lazy_static::lazy_static;
 use solana_program::pubkey::Pubkey;
 use std::str::FromStr;
+use std::sync::RwLock;
 
 // ============================================================================
 // Token Mints
@@ -13,6 +14,8 @@ use std::str::FromStr;
 
 pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
 pub const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+// Lamports per SOL as a power of ten; trade inputs, outputs and profits are in lamports
+pub const SOL_DECIMALS: u8 = 9;
 
 // ============================================================================
 // Executor Program (MEV Bot)
@@ -46,9 +49,63 @@ pub const SYSVAR_INSTRUCTIONS: &str = "Sysvar1nstructions11111111111111111111111
 
 pub const DEFAULT_COMPUTE_UNIT_PRICE: u64 = 1_000;
 pub const DEFAULT_MAX_RETRIES: u64 = 3;
+pub const REMOTE_SIGNER_TIMEOUT_MS: u64 = 2_000;
+// Consecutive failed sends before switching to `backup_wallet`
+pub const DEFAULT_WALLET_FAILOVER_AFTER: u32 = 5;
+// How often the wallet's token accounts are re-checked after startup (`bot.ata_check_interval_secs`)
+pub const DEFAULT_ATA_CHECK_INTERVAL_SECS: u64 = 300;
+// How often pinned shared accounts are re-read from the RPC (`bot.pinned_refresh_secs`)
+pub const DEFAULT_PINNED_REFRESH_SECS: u64 = 300;
+// DEX pool batches initialized at once across all mints on startup (`bot.init_concurrency`)
+pub const DEFAULT_INIT_CONCURRENCY: usize = 4;
+// Inputs within this many basis points of the last sent trade count as the same trade
+// during `bot.trade_cooldown_ms`
+pub const DUPLICATE_OPPORTUNITY_SIZE_TOLERANCE_BPS: u64 = 100;
+// Recent fees per writable account are re-read at most this often (`spam.priority_fee_percentile`)
+pub const PRIORITY_FEE_CACHE_TTL_MS: u64 = 2_000;
+// Resends of a transaction rejected with "AccountInUse" (`spam.account_in_use_retries`)
+pub const DEFAULT_ACCOUNT_IN_USE_RETRIES: u32 = 3;
+pub const ACCOUNT_IN_USE_BACKOFF_MS: u64 = 50;
+// Dropped-transaction resends (`bot.resend_dropped`)
+pub const RESEND_CONFIRM_WINDOW_MS: u64 = 2_000;
+pub const RESEND_POLL_INTERVAL_MS: u64 = 200;
+pub const MAX_DROPPED_RESENDS: u32 = 3;
+// Confirmation polling of RPC sends (`bot.confirm_commitment`, `bot.confirm_timeout_ms`)
+pub const DEFAULT_CONFIRM_TIMEOUT_MS: u64 = 10_000;
+// Timeout with `bot.confirmation_mode = "finalized"`; finalization takes ~32 slots
+pub const DEFAULT_FINALIZED_CONFIRM_TIMEOUT_MS: u64 = 30_000;
+pub const CONFIRM_POLL_INTERVAL_MS: u64 = 200;
+// Recently sent signatures, remembered so the same transaction isn't sent twice and
+// polled in batches until they confirm or leave the window
+pub const RECENT_SIGNATURES_CAPACITY: usize = 4_096;
+pub const RECENT_SIGNATURE_WINDOW_SECS: u64 = 90;
+pub const SENT_CONFIRMATION_POLL_INTERVAL_MS: u64 = 2_000;
+// getSignatureStatuses accepts at most this many signatures per request
+pub const MAX_SIGNATURE_STATUSES_PER_REQUEST: usize = 256;
+// Opportunity queue between strategy loops and sender workers
+pub const DEFAULT_SEND_WORKERS: usize = 4;
+pub const DEFAULT_SEND_QUEUE_CAPACITY: usize = 64;
 pub const DEFAULT_BLOCKHASH_REFRESH_INTERVAL_SECS: u64 = 10;
+// How often the active wallet's balance is checked against `wallet_min_balance_lamports`
+pub const WALLET_BALANCE_CHECK_INTERVAL_SECS: u64 = 30;
+// Block heights a blockhash stays valid for after the block it was taken from
+pub const BLOCKHASH_VALID_BLOCKS: u64 = 150;
+// Delay before reconnecting a dropped slot subscription (polling covers the gap)
+pub const WS_RECONNECT_DELAY_SECS: u64 = 5;
+// How often each strategy loop re-checks that its pool accounts still exist on-chain
+pub const POOL_REFRESH_INTERVAL_SECS: u64 = 30;
+// An arbitrage compares at least two pools; mints with fewer don't trade (`bot.min_pools_per_mint`)
+pub const DEFAULT_MIN_POOLS_PER_MINT: usize = 2;
 pub const DEFAULT_PROCESS_DELAY_MS: u64 = 100;
+pub const DEFAULT_MAX_PROCESS_DELAY_MS: u64 = 5_000;
 pub const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+// Solana's per-transaction compute unit cap
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+// Config schema version understood by this binary. Bump when fields with
+// defaults are added so older configs get a migration warning on load, and list
+// the fields under the new version in CONFIG_VERSION_MIGRATIONS.
+pub const CURRENT_CONFIG_VERSION: u32 = 2;
 
 // Randomization range for compute unit limit (makes transactions unique)
 pub const COMPUTE_UNIT_RANDOMIZATION_RANGE: u32 = 1_000;
@@ -60,10 +117,112 @@ pub const COMPUTE_UNIT_RANDOMIZATION_RANGE: u32 = 1_000;
 pub const MINIMUM_PROFIT_DEFAULT: u64 = 0;
 pub const NO_FAILURE_MODE_DEFAULT: bool = false;
 
+// Transaction costs deducted from an opportunity's gross profit
+pub const BASE_FEE_LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+pub const JITO_TIP_LAMPORTS: u64 = 100_000; // 0.0001 SOL
+// `execution_mode = "auto"` bundles a trade through Jito once its expected profit is
+// this many times the tip
+pub const DEFAULT_JITO_MIN_PROFIT_LAMPORTS: u64 = 10 * JITO_TIP_LAMPORTS;
+pub const MICRO_LAMPORTS_PER_LAMPORT: u64 = 1_000_000;
+
+// Transaction limits used when choosing which pools fit in one transaction
+pub const MAX_TRANSACTION_ACCOUNTS: usize = 64;
+pub const MAX_TRANSACTION_SIZE_BYTES: usize = 1_232;
+pub const ESTIMATED_TRANSACTION_OVERHEAD_BYTES: usize = 200;
+// Jito bundles carry at most this many transactions
+pub const MAX_JITO_BUNDLE_TRANSACTIONS: usize = 5;
+
+// Rough per-pool compute unit cost of a swap leg
+pub const STANDARD_POOL_COMPUTE_UNITS: u32 = 40_000;
+pub const CONCENTRATED_POOL_COMPUTE_UNITS: u32 = 80_000;
+
+// Compute units an estimated limit adds for the compute budget, wSOL wrap, tip and executor
+// instructions around the swap legs
+pub const DEFAULT_COMPUTE_UNIT_OVERHEAD: u32 = 50_000;
+
+// Margin added to the simulated units when refining an estimated compute unit limit (10%)
+pub const COMPUTE_UNIT_SIMULATION_MARGIN_BPS: u64 = 1_000;
+
+// DEX names pools report, the keys `bot.dex_compute_units` accepts
+pub const DEX_NAMES: [&str; 12] = [
+    "Raydium CPMM",
+    "Raydium CP",
+    "Raydium CLMM",
+    "Meteora DLMM",
+    "Meteora DAMM",
+    "Meteora DAMM V2",
+    "Pump.fun",
+    "Orca Whirlpool",
+    "Solfi",
+    "Vertigo",
+    "Lifinity",
+    "Phoenix",
+];
+
 // ATA creation compute limits
 pub const ATA_CREATION_COMPUTE_UNIT_PRICE: u64 = 1_000_000;
 pub const ATA_CREATION_COMPUTE_UNIT_LIMIT: u32 = 60_000;
 
+// ============================================================================
+// Adaptive Delay Configuration
+// ============================================================================
+
+// Weight of the newest sample in the iteration latency EMA
+pub const LATENCY_EMA_ALPHA: f64 = 0.2;
+// Adaptive sleep is this multiple of the latency EMA
+pub const ADAPTIVE_DELAY_LATENCY_FACTOR: f64 = 2.0;
+// Iterations in a row without a profitable opportunity before the idle backoff starts
+// stretching the sleep (`max_idle_delay_ms`)
+pub const IDLE_BACKOFF_AFTER_ITERATIONS: u32 = 10;
+// Latest RPC/WebSocket latency samples kept for averages and percentiles
+pub const LATENCY_WINDOW_SAMPLES: usize = 1000;
+
+// ============================================================================
+// Metrics History
+// ============================================================================
+
+pub const METRICS_HISTORY_INTERVAL_SECS: u64 = 60;
+// One hour of per-minute snapshots
+pub const METRICS_HISTORY_CAPACITY: usize = 60;
+// Written by the running bot so an offline `stats` invocation can read it
+pub const METRICS_HISTORY_FILE: &str = "metrics_history.json";
+// Sliding window for the live per-second throughput gauges
+pub const METRICS_RATE_WINDOW_SECS: u64 = 10;
+// Pushgateway export (`[metrics_push]`)
+pub const DEFAULT_METRICS_PUSH_INTERVAL_SECS: u64 = 15;
+pub const DEFAULT_METRICS_PUSH_JOB: &str = "solana_arbitrage_bot";
+// Critical alerts POSTed to `bot.alert_webhook_url` at most this often; the rest are only logged
+pub const ALERT_MIN_INTERVAL_SECS: u64 = 60;
+pub const ALERT_WEBHOOK_TIMEOUT_MS: u64 = 5_000;
+// Events buffered per opportunity feed client (`[opportunity_feed]`)
+pub const DEFAULT_OPPORTUNITY_FEED_BUFFER_SIZE: usize = 1_024;
+// Observation/oracle account refresh cadence per DEX (`[observation_refresh]`). A DLMM
+// pair's volatility accumulator moves with every bin crossed, so it goes stale fastest;
+// the Whirlpool adaptive fee only moves with tick groups crossed
+pub const DEFAULT_DLMM_ORACLE_REFRESH_MS: u64 = 1_000;
+pub const DEFAULT_WHIRLPOOL_ORACLE_REFRESH_MS: u64 = 5_000;
+
+// ============================================================================
+// Trade Persistence
+// ============================================================================
+
+// Consecutive failed DB writes logged as warnings before escalating to errors
+pub const DB_FAILURE_ERROR_THRESHOLD: u32 = 5;
+// Failed trade writes kept in memory for retry (`bot.db_retry_buffer_size`)
+pub const DEFAULT_DB_RETRY_BUFFER_SIZE: usize = 1_000;
+
+// ============================================================================
+// Token-2022 Transfer Fees
+// ============================================================================
+
+pub const BASIS_POINTS_DENOMINATOR: u64 = 10_000;
+// Numerical sizing of routes through concentrated-liquidity pools stops once the
+// bracket around the best input is this narrow
+pub const OPTIMAL_INPUT_TOLERANCE_LAMPORTS: u64 = 10_000;
+pub const OPTIMAL_INPUT_MAX_ITERATIONS: u32 = 100;
+// The token leg is transferred into the wallet and back out to the next pool
+pub const TOKEN_TRANSFERS_PER_ARBITRAGE: u32 = 2;
+
 // ============================================================================
 // Retry Configuration
 // ============================================================================
@@ -72,6 +231,33 @@ pub const MAX_RPC_RETRIES: u32 = 5;
 pub const RETRY_INITIAL_BACKOFF_MS: u64 = 100;
 pub const RETRY_MAX_BACKOFF_MS: u64 = 5_000;
 pub const RETRY_BACKOFF_MULTIPLIER: f64 = 2.0;
+// Jito bundle submissions skip the block engine for this long after a rate limit,
+// doubling on each consecutive rate limit
+pub const JITO_RATE_LIMIT_INITIAL_BACKOFF_MS: u64 = 1_000;
+pub const JITO_RATE_LIMIT_MAX_BACKOFF_MS: u64 = 30_000;
+
+// ============================================================================
+// RPC Timeouts
+// ============================================================================
+
+// Reads on the hot path must fail fast so a dead endpoint can't stall an iteration
+pub const DEFAULT_RPC_TIMEOUT_MS: u64 = 2_000;
+// Sends are fire-and-forget; a slow endpoint is better abandoned than waited on
+pub const DEFAULT_RPC_SEND_TIMEOUT_MS: u64 = 1_000;
+pub const MIN_RPC_TIMEOUT_MS: u64 = 100;
+pub const MAX_RPC_TIMEOUT_MS: u64 = 30_000;
+// `discover-pools` scans whole DEX programs with getProgramAccounts
+pub const DISCOVERY_RPC_TIMEOUT_SECS: u64 = 120;
+// `compare-feeds` polls the account this often alongside the WebSocket subscription
+pub const COMPARE_FEEDS_POLL_INTERVAL_MS: u64 = 100;
+
+// ============================================================================
+// RPC Cache Configuration
+// ============================================================================
+
+pub const DEFAULT_RPC_CACHE_TTL_SECS: u64 = 10;
+// Maximum accounts per getMultipleAccounts request
+pub const MAX_MULTIPLE_ACCOUNTS_BATCH: usize = 100;
 
 // ============================================================================
 // Lookup Tables
@@ -79,6 +265,9 @@ pub const RETRY_BACKOFF_MULTIPLIER: f64 = 2.0;
 
 /// Default lookup table for common accounts
 pub const DEFAULT_LOOKUP_TABLE: &str = "4sKLJ1Qoudh8PJyqBeuKocYdsZvxTcRShUt9aKqwhgvC";
+/// Lookup tables one transaction may use (the default table included); every table
+/// costs its 32-byte key plus index lists out of the 1232-byte packet
+pub const MAX_LOOKUP_TABLES_PER_TRANSACTION: usize = 8;
 
 // ============================================================================
 // Lazy-initialized Pubkeys (computed once)
@@ -117,12 +306,22 @@ lazy_static! {
 // Helper Functions (for backward compatibility)
 // ============================================================================
 
-/// Returns the SOL mint Pubkey
-/// 
-/// # Note
-/// Prefer using `SOL_MINT_PUBKEY` directly for better performance
+lazy_static! {
+    static ref BASE_MINT_OVERRIDE: RwLock<Option<Pubkey>> = RwLock::new(None);
+}
+
+/// Returns the base mint Pubkey: wSOL unless overridden with `set_base_mint`
 pub fn sol_mint() -> Pubkey {
-    *SOL_MINT_PUBKEY
+    BASE_MINT_OVERRIDE
+        .read()
+        .unwrap()
+        .unwrap_or(*SOL_MINT_PUBKEY)
+}
+
+/// Override the base mint used for pool validation and vault ordering
+/// (e.g. a custom token on a local validator). Call once at startup.
+pub fn set_base_mint(mint: Pubkey) {
+    *BASE_MINT_OVERRIDE.write().unwrap() = Some(mint);
 }
 
 /// Returns the Token 2022 program Pubkey
@@ -3,6 +3,7 @@
 /// This module provides a type-safe error hierarchy using thiserror,
 /// enabling better error handling, debugging, and monitoring.
 
+use crate::jito::{JitoError, JitoRetryAction};
 use solana_sdk::pubkey::Pubkey;
 use thiserror::Error;
 
@@ -26,6 +27,10 @@ pub enum BotError {
         retryable: bool,
     },
 
+    /// Every endpoint of an RPC pool has its circuit breaker open, so nothing can be read
+    #[error("All {endpoints} RPC endpoint(s) are down (circuit breaker open)")]
+    AllEndpointsDown { endpoints: usize },
+
     /// Configuration errors
     #[error("Configuration error: {0}")]
     ConfigError(String),
@@ -58,10 +63,34 @@ pub enum BotError {
     #[error("Failed to build transaction: {0}")]
     TransactionBuildError(String),
 
+    /// More lookup tables than one transaction can use
+    #[error("{tables} lookup tables configured, but a transaction can use at most {max}")]
+    LookupTableLimitExceeded { tables: usize, max: usize },
+
+    /// Static keys plus addresses loaded from lookup tables exceed the account limit
+    #[error("Transaction references {addresses} accounts (including lookup table addresses), but at most {max} are allowed")]
+    LookupTableAddressLimitExceeded { addresses: usize, max: usize },
+
+    /// Pool accounts were read at a slot too far behind the latest one to build from
+    #[error("Accounts were read at slot {account_slot}, more than {max_staleness} slots behind the latest slot {latest_slot}")]
+    StaleAccountData {
+        account_slot: u64,
+        latest_slot: u64,
+        max_staleness: u64,
+    },
+
+    /// Simulated route needs more compute units than the transaction requests
+    #[error("Route needs {needed} compute units but compute_unit_limit is {configured}")]
+    ComputeBudgetExceeded { needed: u64, configured: u32 },
+
     /// Transaction sending errors
     #[error("Failed to send transaction: {0}")]
     TransactionSendError(String),
 
+    /// Jito bundle submission errors
+    #[error("Jito bundle submission failed: {0}")]
+    JitoError(#[from] JitoError),
+
     /// Wallet errors
     #[error("Wallet error: {0}")]
     WalletError(String),
@@ -97,8 +126,11 @@ impl BotError {
         match self {
             BotError::RpcError { retryable, .. } => *retryable,
             BotError::SolanaClientError(_) => true,
+            // A circuit half-opens after its timeout
+            BotError::AllEndpointsDown { .. } => true,
             BotError::AccountFetchError { .. } => true,
             BotError::TransactionSendError(_) => true,
+            BotError::JitoError(e) => e.action() != JitoRetryAction::GiveUp,
             _ => false,
         }
     }
@@ -108,6 +140,7 @@ impl BotError {
         match self {
             BotError::ConfigError(_) => ErrorSeverity::Critical,
             BotError::WalletError(_) => ErrorSeverity::Critical,
+            BotError::AllEndpointsDown { .. } => ErrorSeverity::Critical,
             BotError::InvalidPublicKey { .. } => ErrorSeverity::Error,
             BotError::PoolInitialization { .. } => ErrorSeverity::Warning,
             BotError::RpcError { .. } => ErrorSeverity::Warning,
@@ -191,5 +224,10 @@ mod tests {
             "test".to_string(),
         );
         assert_eq!(rpc_err.severity(), ErrorSeverity::Warning);
+
+        // One endpoint failing is routine; all of them failing is an outage
+        let outage = BotError::AllEndpointsDown { endpoints: 2 };
+        assert_eq!(outage.severity(), ErrorSeverity::Critical);
+        assert!(outage.is_retryable());
     }
 }
@@ -1,30 +1,686 @@
+use crate::constants::{
+    BLOCKHASH_VALID_BLOCKS, CURRENT_CONFIG_VERSION, DEFAULT_ACCOUNT_IN_USE_RETRIES, DEFAULT_ATA_CHECK_INTERVAL_SECS,
+    DEFAULT_COMPUTE_UNIT_OVERHEAD, DEFAULT_CONFIRM_TIMEOUT_MS, DEFAULT_FINALIZED_CONFIRM_TIMEOUT_MS, DEFAULT_DB_RETRY_BUFFER_SIZE,
+    DEFAULT_DLMM_ORACLE_REFRESH_MS, DEFAULT_WHIRLPOOL_ORACLE_REFRESH_MS,
+    DEFAULT_INIT_CONCURRENCY, DEFAULT_JITO_MIN_PROFIT_LAMPORTS, DEFAULT_OPPORTUNITY_FEED_BUFFER_SIZE,
+    DEFAULT_MAX_PROCESS_DELAY_MS, DEFAULT_METRICS_PUSH_INTERVAL_SECS, DEFAULT_MIN_POOLS_PER_MINT, DEFAULT_PINNED_REFRESH_SECS, DEFAULT_METRICS_PUSH_JOB, DEFAULT_SEND_QUEUE_CAPACITY, DEFAULT_SEND_WORKERS,
+    DEFAULT_RPC_SEND_TIMEOUT_MS, DEFAULT_RPC_TIMEOUT_MS, DEFAULT_WALLET_FAILOVER_AFTER,
+    DEX_NAMES, EXECUTOR_PROGRAM_PUBKEY,
+    FEE_COLLECTOR_PUBKEY, MAX_COMPUTE_UNIT_LIMIT, MAX_LOOKUP_TABLES_PER_TRANSACTION,
+    MAX_RPC_TIMEOUT_MS, MINIMUM_PROFIT_DEFAULT, MIN_RPC_TIMEOUT_MS, SOL_MINT,
+};
 use crate::error::{BotError, BotResult};
-use serde::{Deserialize, Deserializer};
+use crate::ranking::{ranking_strategy, MaxProfit, RankingStrategy};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Map, Value};
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::time::Duration;
 use std::{env, fs::File, io::Read};
+use tracing::warn;
 
-#[derive(Debug, Deserialize, Clone)]
+/// Environment variables read by `Config::from_env`, each holding a JSON object for
+/// the config section of the same name
+const ENV_JSON_SECTIONS: &[(&str, &str)] = &[
+    ("BOT_BACKUP_WALLET", "backup_wallet"),
+    ("BOT_SPAM", "spam"),
+    ("BOT_FLASHLOAN", "flashloan"),
+    ("BOT_JITO", "jito"),
+    ("BOT_WATCHDOG", "watchdog"),
+    ("BOT_EXECUTOR", "executor"),
+    ("BOT_METRICS_PUSH", "metrics_push"),
+    ("BOT_OPPORTUNITY_FEED", "opportunity_feed"),
+    ("BOT_OBSERVATION_REFRESH", "observation_refresh"),
+];
+
+/// Fields that gained defaults in each config schema version.
+/// Configs written for an older version silently fall back to these defaults.
+///
+/// A release that adds a defaulted field bumps `CURRENT_CONFIG_VERSION` once and lists
+/// the field under the new version here, by its path in the TOML file. Fields added
+/// between releases go in the same, not yet released, entry.
+const CONFIG_VERSION_MIGRATIONS: &[(u32, &[&str])] = &[
+    (1, &["version"]),
+    (
+        2,
+        &[
+            "base_mint",
+            "backup_wallet",
+            "jito",
+            "watchdog",
+            "executor",
+            "metrics_push",
+            "opportunity_feed",
+            "observation_refresh",
+            "bot.transaction_version",
+            "bot.warmup_on_startup",
+            "bot.strict_token_checks",
+            "bot.simulate_before_send",
+            "bot.resend_dropped",
+            "bot.use_websocket_blockhash",
+            "bot.use_websocket_pool_updates",
+            "bot.auto_wrap_sol",
+            "bot.wrap_sol_lamports",
+            "bot.db_retry_buffer_size",
+            "bot.startup_jitter_ms",
+            "bot.confirm_commitment",
+            "bot.confirm_timeout_ms",
+            "bot.confirmation_mode",
+            "bot.disabled_dexes",
+            "bot.send_workers",
+            "bot.send_queue_capacity",
+            "bot.send_queue_overflow",
+            "bot.trade_log_path",
+            "bot.trade_log_rotate",
+            "bot.auto_raise_compute_unit_limit",
+            "bot.reconcile_profit",
+            "bot.ranking",
+            "bot.skip_invalid_pools",
+            "bot.min_compute_unit_price",
+            "bot.max_compute_unit_price",
+            "bot.max_total_input_lamports",
+            "bot.wallet_failover_after",
+            "bot.wallet_min_balance_lamports",
+            "bot.trade_cooldown_ms",
+            "bot.max_pools_per_tx",
+            "bot.max_split_pools",
+            "bot.ata_check_interval_secs",
+            "bot.init_concurrency",
+            "bot.max_account_staleness_slots",
+            "bot.blockhash_expiry_margin_blocks",
+            "bot.alert_webhook_url",
+            "bot.min_pools_per_mint",
+            "bot.pinned_accounts",
+            "bot.pinned_refresh_secs",
+            "bot.max_lookup_tables_per_mint",
+            "bot.estimate_compute_unit_limit",
+            "bot.dex_compute_units",
+            "bot.compute_unit_overhead",
+            "bot.health_port",
+            "rpc.rpc_timeout_ms",
+            "rpc.send_timeout_ms",
+            "rpc.ws_url",
+            "spam.strategy",
+            "spam.blockhash_source",
+            "spam.priority_fee_percentile",
+            "spam.account_in_use_retries",
+            "wallet.remote_signer_url",
+            "routing.mint_config_list.lifinity_pool_list",
+            "routing.mint_config_list.phoenix_pool_list",
+            "routing.mint_config_list.adaptive_delay",
+            "routing.mint_config_list.min_process_delay_ms",
+            "routing.mint_config_list.max_process_delay_ms",
+            "routing.mint_config_list.max_idle_delay_ms",
+            "routing.mint_config_list.max_input_lamports",
+            "routing.mint_config_list.rpc_url",
+            "routing.mint_config_list.execution_mode",
+            "routing.mint_config_list.jito_min_profit_lamports",
+            "routing.mint_config_list.min_profit_lamports",
+            "routing.mint_config_list.compute_unit_limit",
+        ],
+    ),
+];
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Config {
+    pub version: Option<u32>,
+    /// Base mint that every pool must pair with; defaults to wSOL (override for localnet/devnet)
+    pub base_mint: Option<String>,
     pub bot: BotConfig,
     pub routing: RoutingConfig,
     pub rpc: RpcConfig,
     pub spam: Option<SpamConfig>,
     pub wallet: WalletConfig,
+    /// Standby wallet traded with once the primary keeps failing or runs low on SOL
+    pub backup_wallet: Option<WalletConfig>,
     pub flashloan: Option<FlashloanConfig>,
+    pub jito: Option<JitoConfig>,
+    pub watchdog: Option<WatchdogConfig>,
+    pub executor: Option<ExecutorConfig>,
+    /// Push metrics to a Prometheus pushgateway instead of (or besides) serving `/metrics`
+    pub metrics_push: Option<MetricsPushConfig>,
+    /// Stream detected opportunities as JSON lines to TCP clients
+    pub opportunity_feed: Option<OpportunityFeedConfig>,
+    /// How often each DEX's observation/oracle accounts are re-read
+    pub observation_refresh: Option<ObservationRefreshConfig>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct BotConfig {
     pub compute_unit_limit: u32,
+    /// "legacy" or "v0" (default); legacy transactions cannot use lookup tables
+    pub transaction_version: Option<String>,
+    /// Prime the RPC cache with all pool accounts before trading (default true)
+    pub warmup_on_startup: Option<bool>,
+    /// Skip mints with a freeze authority, transfer fee or transfer hook instead of warning
+    pub strict_token_checks: Option<bool>,
+    /// Simulate each transaction before sending and reject wrong account writability flags
+    pub simulate_before_send: Option<bool>,
+    /// Rebuild and resend transactions that are not confirmed in time (RPC sends only)
+    pub resend_dropped: Option<bool>,
+    /// Refresh the blockhash on every slot via `slotSubscribe`, polling only as a fallback
+    pub use_websocket_blockhash: Option<bool>,
+    /// Watch each mint's pool accounts via `accountSubscribe`, ending its idle backoff
+    /// as soon as one of them changes
+    pub use_websocket_pool_updates: Option<bool>,
+    /// Wrap `wrap_sol_lamports` into the wSOL account before the swap and close it afterwards
+    pub auto_wrap_sol: Option<bool>,
+    /// Lamports to wrap per trade when `auto_wrap_sol` is enabled
+    pub wrap_sol_lamports: Option<u64>,
+    /// Failed trade writes buffered in memory until the database recovers (0 disables)
+    pub db_retry_buffer_size: Option<usize>,
+    /// Random delay of up to this many milliseconds before each strategy loop starts
+    pub startup_jitter_ms: Option<u64>,
+    /// Commitment an RPC-sent transaction must reach to count as confirmed:
+    /// "processed", "confirmed" (default) or "finalized"
+    pub confirm_commitment: Option<String>,
+    /// How long to poll for confirmation before counting the transaction as failed
+    pub confirm_timeout_ms: Option<u64>,
+    /// What the send path waits for after an RPC send: "none" moves on at once,
+    /// "signature" waits until it's processed, "finalized" until it's finalized. Unset
+    /// waits for `confirm_commitment` unless spam is enabled
+    pub confirmation_mode: Option<String>,
+    /// DEXes to skip for every mint, by name as logged at startup (e.g. "Pump.fun",
+    /// "Raydium CPMM", "Orca Whirlpool")
+    pub disabled_dexes: Option<Vec<String>>,
+    /// Sender workers draining the opportunity queue (default 4)
+    pub send_workers: Option<usize>,
+    /// Opportunities the queue holds before the overflow policy applies (default 64)
+    pub send_queue_capacity: Option<usize>,
+    /// "drop_oldest" (default) discards the stalest queued opportunity when the queue is
+    /// full; "block" stalls the strategy loop until a worker frees a slot
+    pub send_queue_overflow: Option<String>,
+    /// File each executed trade is appended to as a JSON line (disabled when unset)
+    pub trade_log_path: Option<String>,
+    /// Move an existing trade log aside on startup instead of appending to it
+    pub trade_log_rotate: Option<bool>,
+    /// Raise the compute unit limit (up to 1.4M) when simulation shows the route needs
+    /// more, instead of rejecting the trade (requires `simulate_before_send`)
+    pub auto_raise_compute_unit_limit: Option<bool>,
+    /// After a trade confirms, read the wallet's balance change from the transaction and
+    /// store it as the realized profit next to the estimate
+    pub reconcile_profit: Option<bool>,
+    /// How competing opportunities are ordered: "max_profit" (default), "max_roi" or
+    /// "lowest_account_count"
+    pub ranking: Option<String>,
+    /// Drop individual invalid pool addresses on load instead of rejecting the config,
+    /// as long as every mint keeps at least one valid pool
+    pub skip_invalid_pools: Option<bool>,
+    /// Lowest compute unit price (micro-lamports) sent, whatever `spam.compute_unit_price` says
+    pub min_compute_unit_price: Option<u64>,
+    /// Highest compute unit price (micro-lamports) sent
+    pub max_compute_unit_price: Option<u64>,
+    /// Cap on the input lamports of all in-flight sends across every mint; sends that
+    /// would exceed it wait until earlier ones resolve
+    pub max_total_input_lamports: Option<u64>,
+    /// Consecutive failed sends before switching to `backup_wallet` (default 5)
+    pub wallet_failover_after: Option<u32>,
+    /// Switch to `backup_wallet` when the primary's SOL balance drops below this
+    pub wallet_min_balance_lamports: Option<u64>,
+    /// After a send, skip the same trade (same pools, similar input) for this long
+    pub trade_cooldown_ms: Option<u64>,
+    /// Split a mint's pools into groups of at most this many, one transaction per group,
+    /// instead of dropping the pools that don't fit in one transaction
+    pub max_pools_per_tx: Option<usize>,
+    /// Also quote buys spread over up to this many of the mint's constant-product pools,
+    /// sized to equalize their marginal price (unset keeps every buy in one pool)
+    pub max_split_pools: Option<usize>,
+    /// Seconds between re-checks that the wallet's token accounts still exist, recreating
+    /// any that were closed (default 300, 0 disables)
+    pub ata_check_interval_secs: Option<u64>,
+    /// DEX pool batches initialized at once across all mints on startup (default 4)
+    pub init_concurrency: Option<usize>,
+    /// Skip an iteration when its pool state was read more than this many slots behind
+    /// the latest slot (unset disables the check)
+    pub max_account_staleness_slots: Option<u64>,
+    /// Before each send, fetch a fresh blockhash when the cached one's lastValidBlockHeight
+    /// is within this many blocks of the current block height (unset disables the check)
+    pub blockhash_expiry_margin_blocks: Option<u64>,
+    /// Webhook POSTed a JSON `{severity, message, timestamp}` for every critical error,
+    /// at most once a minute (unset disables alerting)
+    pub alert_webhook_url: Option<String>,
+    /// Initialized pools a mint needs for its strategy loop to run (default and minimum 2)
+    pub min_pools_per_mint: Option<usize>,
+    /// Accounts to pin in the RPC cache on top of the pools' shared accounts (global and
+    /// fee configs), e.g. a fork executor's config account
+    pub pinned_accounts: Option<Vec<String>>,
+    /// Seconds between re-reads of the pinned accounts (default 300, 0 never re-reads)
+    pub pinned_refresh_secs: Option<u64>,
+    /// Lookup tables a mint's transactions use, the default table included; with more
+    /// configured, the ones resolving the most of the mint's accounts are kept (default 8)
+    pub max_lookup_tables_per_mint: Option<usize>,
+    /// Request a compute unit limit sized to each route's pools (per-DEX costs plus
+    /// `compute_unit_overhead`) instead of `compute_unit_limit`; with simulation the
+    /// limit is refined to the units measured plus a margin
+    pub estimate_compute_unit_limit: Option<bool>,
+    /// Compute units of one swap leg per DEX name ("Pump.fun", "Raydium CLMM", ...),
+    /// replacing the built-in estimates
+    pub dex_compute_units: Option<HashMap<String, u32>>,
+    /// Compute units an estimated limit adds on top of the swap legs (default 50,000)
+    pub compute_unit_overhead: Option<u32>,
+    /// Port to serve /health, /ready, /status and /metrics on (unset disables the server)
+    pub health_port: Option<u16>,
+}
+
+/// Transaction message format to build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionVersion {
+    Legacy,
+    V0,
+}
+
+impl FromStr for TransactionVersion {
+    type Err = BotError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "legacy" => Ok(TransactionVersion::Legacy),
+            "v0" => Ok(TransactionVersion::V0),
+            other => Err(BotError::ConfigError(format!(
+                "transaction_version must be \"legacy\" or \"v0\", got: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// How a transaction is dispatched to the sending RPC endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendStrategy {
+    /// Every endpoint, one after another, keeping each accepted signature
+    Broadcast,
+    /// Endpoints in order until one accepts
+    FirstSuccess,
+    /// Every endpoint at once; the first to accept wins and the rest are abandoned
+    Fastest,
+}
+
+impl FromStr for SendStrategy {
+    type Err = BotError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "broadcast" => Ok(SendStrategy::Broadcast),
+            "first_success" => Ok(SendStrategy::FirstSuccess),
+            "fastest" => Ok(SendStrategy::Fastest),
+            other => Err(BotError::ConfigError(format!(
+                "spam.strategy must be \"broadcast\", \"first_success\" or \"fastest\", got: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// How a mint's trades are submitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionMode {
+    /// As a Jito bundle, falling back to RPC as usual when Jito fails
+    Jito,
+    /// Through the RPC endpoints only
+    Spam,
+    /// Jito once the expected profit justifies the tip, RPC otherwise
+    Auto,
+}
+
+impl ExecutionMode {
+    /// Whether a trade expected to make `expected_profit` goes to Jito. Routes sized
+    /// on-chain have no expected profit, so "auto" sends them through RPC.
+    pub fn uses_jito(self, expected_profit: u64, jito_min_profit_lamports: u64) -> bool {
+        match self {
+            ExecutionMode::Jito => true,
+            ExecutionMode::Spam => false,
+            ExecutionMode::Auto => expected_profit >= jito_min_profit_lamports,
+        }
+    }
+}
+
+impl FromStr for ExecutionMode {
+    type Err = BotError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "jito" => Ok(ExecutionMode::Jito),
+            "spam" => Ok(ExecutionMode::Spam),
+            "auto" => Ok(ExecutionMode::Auto),
+            other => Err(BotError::ConfigError(format!(
+                "execution_mode must be \"jito\", \"spam\" or \"auto\", got: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// What the send path waits for after sending a transaction through RPC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmationMode {
+    /// Fire and forget; the background signature poll still counts it
+    None,
+    /// Until the signature is processed
+    Signature,
+    /// Until the transaction is finalized, before its profit is logged
+    Finalized,
+}
+
+impl ConfirmationMode {
+    /// Commitment to wait for, None when the send path doesn't wait
+    pub fn commitment(self) -> Option<CommitmentConfig> {
+        match self {
+            ConfirmationMode::None => None,
+            ConfirmationMode::Signature => Some(CommitmentConfig::processed()),
+            ConfirmationMode::Finalized => Some(CommitmentConfig::finalized()),
+        }
+    }
+}
+
+impl FromStr for ConfirmationMode {
+    type Err = BotError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(ConfirmationMode::None),
+            "signature" => Ok(ConfirmationMode::Signature),
+            "finalized" => Ok(ConfirmationMode::Finalized),
+            other => Err(BotError::ConfigError(format!(
+                "confirmation_mode must be \"none\", \"signature\" or \"finalized\", got: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Where each sending endpoint's transaction takes its recent blockhash from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockhashSource {
+    /// The blockhash refreshed from `[rpc] url`, shared by every endpoint
+    ReadRpc,
+    /// Each endpoint's own latest blockhash, so no endpoint gets a blockhash its
+    /// node hasn't seen yet
+    Sender,
+}
+
+impl FromStr for BlockhashSource {
+    type Err = BotError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read_rpc" => Ok(BlockhashSource::ReadRpc),
+            "sender" => Ok(BlockhashSource::Sender),
+            other => Err(BotError::ConfigError(format!(
+                "spam.blockhash_source must be \"read_rpc\" or \"sender\", got: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// What a strategy loop does when the send queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    DropOldest,
+    Block,
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = BotError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "drop_oldest" => Ok(OverflowPolicy::DropOldest),
+            "block" => Ok(OverflowPolicy::Block),
+            other => Err(BotError::ConfigError(format!(
+                "send_queue_overflow must be \"drop_oldest\" or \"block\", got: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Fixed account a forked executor expects ahead of the per-pool accounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaticAccount {
+    PumpGlobalConfig,
+    SysvarInstructions,
+    FeeCollector,
+    ExecutorProgram,
+}
+
+impl FromStr for StaticAccount {
+    type Err = BotError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pump_global_config" => Ok(StaticAccount::PumpGlobalConfig),
+            "sysvar_instructions" => Ok(StaticAccount::SysvarInstructions),
+            "fee_collector" => Ok(StaticAccount::FeeCollector),
+            "executor_program" => Ok(StaticAccount::ExecutorProgram),
+            other => Err(BotError::ConfigError(format!(
+                "executor.static_accounts entries must be \"pump_global_config\", \"sysvar_instructions\", \"fee_collector\" or \"executor_program\", got: {}",
+                other
+            ))),
+        }
+    }
+}
+
+impl BotConfig {
+    pub fn warmup_on_startup(&self) -> bool {
+        self.warmup_on_startup.unwrap_or(true)
+    }
+
+    pub fn strict_token_checks(&self) -> bool {
+        self.strict_token_checks.unwrap_or(false)
+    }
+
+    pub fn simulate_before_send(&self) -> bool {
+        self.simulate_before_send.unwrap_or(false)
+    }
+
+    pub fn resend_dropped(&self) -> bool {
+        self.resend_dropped.unwrap_or(false)
+    }
+
+    pub fn use_websocket_blockhash(&self) -> bool {
+        self.use_websocket_blockhash.unwrap_or(false)
+    }
+
+    pub fn use_websocket_pool_updates(&self) -> bool {
+        self.use_websocket_pool_updates.unwrap_or(false)
+    }
+
+    pub fn auto_wrap_sol(&self) -> bool {
+        self.auto_wrap_sol.unwrap_or(false)
+    }
+
+    /// Upper bound for the per-task startup delay (zero disables it)
+    pub fn startup_jitter(&self) -> Duration {
+        Duration::from_millis(self.startup_jitter_ms.unwrap_or(0))
+    }
+
+    /// How long a sent trade suppresses its duplicates (zero disables it)
+    pub fn trade_cooldown(&self) -> Duration {
+        Duration::from_millis(self.trade_cooldown_ms.unwrap_or(0))
+    }
+
+    /// Pool cap per transaction when splitting (unset keeps a single transaction)
+    pub fn max_pools_per_tx(&self) -> Option<usize> {
+        self.max_pools_per_tx
+    }
+
+    /// Pools one quoted buy may be split across (1 when splitting is off)
+    pub fn max_split_pools(&self) -> usize {
+        self.max_split_pools.unwrap_or(1)
+    }
+
+    pub fn db_retry_buffer_size(&self) -> usize {
+        self.db_retry_buffer_size.unwrap_or(DEFAULT_DB_RETRY_BUFFER_SIZE)
+    }
+
+    /// Lamports to wrap per trade, or `None` when auto-wrapping is disabled
+    pub fn wrap_sol_lamports(&self) -> Option<u64> {
+        if self.auto_wrap_sol() {
+            self.wrap_sol_lamports
+        } else {
+            None
+        }
+    }
+
+    pub fn disabled_dexes(&self) -> &[String] {
+        self.disabled_dexes.as_deref().unwrap_or(&[])
+    }
+
+    /// Commitment to wait for when confirming sends, defaulting to confirmed
+    pub fn confirm_commitment(&self) -> CommitmentConfig {
+        let commitment = self
+            .confirm_commitment
+            .as_deref()
+            .and_then(|commitment| parse_commitment(commitment).ok())
+            .unwrap_or(CommitmentLevel::Confirmed);
+        CommitmentConfig { commitment }
+    }
+
+    /// Confirmation polling timeout; finalization takes longer, so "finalized" has its own
+    /// default
+    pub fn confirm_timeout(&self) -> Duration {
+        let default = match self.confirmation_mode() {
+            Some(ConfirmationMode::Finalized) => DEFAULT_FINALIZED_CONFIRM_TIMEOUT_MS,
+            _ => DEFAULT_CONFIRM_TIMEOUT_MS,
+        };
+        Duration::from_millis(self.confirm_timeout_ms.unwrap_or(default))
+    }
+
+    pub fn confirmation_mode(&self) -> Option<ConfirmationMode> {
+        self.confirmation_mode.as_deref().and_then(|mode| mode.parse().ok())
+    }
+
+    /// Configured transaction version, defaulting to v0
+    pub fn transaction_version(&self) -> TransactionVersion {
+        self.transaction_version
+            .as_deref()
+            .and_then(|version| version.parse().ok())
+            .unwrap_or(TransactionVersion::V0)
+    }
+
+    pub fn auto_raise_compute_unit_limit(&self) -> bool {
+        self.auto_raise_compute_unit_limit.unwrap_or(false)
+    }
+
+    /// Configured ranking strategy, defaulting to max profit
+    pub fn ranking(&self) -> Box<dyn RankingStrategy> {
+        self.ranking
+            .as_deref()
+            .and_then(|name| ranking_strategy(name).ok())
+            .unwrap_or_else(|| Box::new(MaxProfit))
+    }
+
+    /// `price` raised to the configured floor and capped at the ceiling
+    pub fn clamp_compute_unit_price(&self, price: u64) -> u64 {
+        let price = self.min_compute_unit_price.map_or(price, |floor| price.max(floor));
+        self.max_compute_unit_price.map_or(price, |ceiling| price.min(ceiling))
+    }
+
+    pub fn wallet_failover_after(&self) -> u32 {
+        self.wallet_failover_after.unwrap_or(DEFAULT_WALLET_FAILOVER_AFTER)
+    }
+
+    /// Interval of the token account re-check, `None` when disabled
+    pub fn ata_check_interval(&self) -> Option<Duration> {
+        match self.ata_check_interval_secs.unwrap_or(DEFAULT_ATA_CHECK_INTERVAL_SECS) {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    pub fn skip_invalid_pools(&self) -> bool {
+        self.skip_invalid_pools.unwrap_or(false)
+    }
+
+    pub fn reconcile_profit(&self) -> bool {
+        self.reconcile_profit.unwrap_or(false)
+    }
+
+    pub fn trade_log_rotate(&self) -> bool {
+        self.trade_log_rotate.unwrap_or(false)
+    }
+
+    pub fn init_concurrency(&self) -> usize {
+        self.init_concurrency.unwrap_or(DEFAULT_INIT_CONCURRENCY)
+    }
+
+    pub fn min_pools_per_mint(&self) -> usize {
+        self.min_pools_per_mint.unwrap_or(DEFAULT_MIN_POOLS_PER_MINT)
+    }
+
+    pub fn estimate_compute_unit_limit(&self) -> bool {
+        self.estimate_compute_unit_limit.unwrap_or(false)
+    }
+
+    /// Configured compute units of one swap leg on `dex`, if `dex_compute_units` lists it
+    pub fn dex_compute_units(&self, dex: &str) -> Option<u32> {
+        self.dex_compute_units.as_ref()?.get(dex).copied()
+    }
+
+    pub fn compute_unit_overhead(&self) -> u32 {
+        self.compute_unit_overhead.unwrap_or(DEFAULT_COMPUTE_UNIT_OVERHEAD)
+    }
+
+    pub fn max_lookup_tables_per_mint(&self) -> usize {
+        self.max_lookup_tables_per_mint
+            .unwrap_or(MAX_LOOKUP_TABLES_PER_TRANSACTION)
+    }
+
+    /// Configured extra pinned accounts (addresses are checked by `validate`)
+    pub fn pinned_accounts(&self) -> Vec<Pubkey> {
+        self.pinned_accounts
+            .iter()
+            .flatten()
+            .filter_map(|account| Pubkey::from_str(account).ok())
+            .collect()
+    }
+
+    /// Interval of the pinned account re-read, `None` when disabled
+    pub fn pinned_refresh_interval(&self) -> Option<Duration> {
+        match self.pinned_refresh_secs.unwrap_or(DEFAULT_PINNED_REFRESH_SECS) {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    pub fn send_workers(&self) -> usize {
+        self.send_workers.unwrap_or(DEFAULT_SEND_WORKERS)
+    }
+
+    pub fn send_queue_capacity(&self) -> usize {
+        self.send_queue_capacity.unwrap_or(DEFAULT_SEND_QUEUE_CAPACITY)
+    }
+
+    /// Configured overflow policy, defaulting to drop-oldest
+    pub fn send_queue_overflow(&self) -> OverflowPolicy {
+        self.send_queue_overflow
+            .as_deref()
+            .and_then(|policy| policy.parse().ok())
+            .unwrap_or(OverflowPolicy::DropOldest)
+    }
+}
+
+fn parse_commitment(commitment: &str) -> BotResult<CommitmentLevel> {
+    match commitment {
+        "processed" => Ok(CommitmentLevel::Processed),
+        "confirmed" => Ok(CommitmentLevel::Confirmed),
+        "finalized" => Ok(CommitmentLevel::Finalized),
+        other => Err(BotError::ConfigError(format!(
+            "confirm_commitment must be \"processed\", \"confirmed\" or \"finalized\", got: {}",
+            other
+        ))),
+    }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct RoutingConfig {
     pub mint_config_list: Vec<MintConfig>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct MintConfig {
     pub mint: String,
 
@@ -44,35 +700,292 @@ pub struct MintConfig {
 
     pub vertigo_pool_list: Option<Vec<String>>,
 
+    pub lifinity_pool_list: Option<Vec<String>>,
+
+    pub phoenix_pool_list: Option<Vec<String>>,
+
     pub lookup_table_accounts: Option<Vec<String>>,
     pub process_delay: u64,
+
+    /// Scale the loop sleep with observed iteration latency instead of `process_delay`
+    pub adaptive_delay: Option<bool>,
+    pub min_process_delay_ms: Option<u64>,
+    pub max_process_delay_ms: Option<u64>,
+    /// Longest sleep the loop backs off to while the mint keeps finding no profitable
+    /// opportunity (no idle backoff when unset)
+    pub max_idle_delay_ms: Option<u64>,
+
+    /// Largest input the sizing step may use for this mint, so a big wallet or flashloan
+    /// cannot move the pools far enough to erase the profit
+    pub max_input_lamports: Option<u64>,
+
+    /// Dedicated RPC for this mint's startup reads, strategy loop and pool refresh
+    /// (the shared `rpc.url` when unset)
+    pub rpc_url: Option<String>,
+
+    /// "jito", "spam" or "auto"; unset uses Jito whenever the client is available
+    pub execution_mode: Option<String>,
+    /// Expected profit at which "auto" switches to Jito (default 10x the tip)
+    pub jito_min_profit_lamports: Option<u64>,
+    /// Profit a trade must clear: quoted routes after fees, before they are queued, and
+    /// the executor's on-chain check for every route
+    pub min_profit_lamports: Option<u64>,
+
+    /// Compute unit limit of this mint's transactions, in place of both
+    /// `bot.compute_unit_limit` and the per-DEX estimate
+    pub compute_unit_limit: Option<u32>,
+}
+
+impl MintConfig {
+    /// Lower bound for the adaptive loop delay
+    pub fn min_process_delay(&self) -> u64 {
+        self.min_process_delay_ms.unwrap_or(self.process_delay)
+    }
+
+    /// Upper bound for the adaptive loop delay
+    pub fn max_process_delay(&self) -> u64 {
+        self.max_process_delay_ms
+            .unwrap_or(DEFAULT_MAX_PROCESS_DELAY_MS)
+            .max(self.min_process_delay())
+    }
+
+    /// Cap for the idle backoff, if enabled
+    pub fn max_idle_delay(&self) -> Option<Duration> {
+        self.max_idle_delay_ms.map(Duration::from_millis)
+    }
+
+    /// Validated at load; None keeps the global behavior
+    pub fn execution_mode(&self) -> Option<ExecutionMode> {
+        self.execution_mode.as_deref().and_then(|mode| mode.parse().ok())
+    }
+
+    pub fn jito_min_profit_lamports(&self) -> u64 {
+        self.jito_min_profit_lamports.unwrap_or(DEFAULT_JITO_MIN_PROFIT_LAMPORTS)
+    }
+
+    pub fn min_profit_lamports(&self) -> u64 {
+        self.min_profit_lamports.unwrap_or(MINIMUM_PROFIT_DEFAULT)
+    }
+
+    /// Every pool address listed for this mint, across all DEX pool lists
+    pub fn pool_addresses(&self) -> Vec<&str> {
+        [
+            &self.raydium_pool_list,
+            &self.raydium_cp_pool_list,
+            &self.raydium_clmm_pool_list,
+            &self.meteora_dlmm_pool_list,
+            &self.meteora_damm_pool_list,
+            &self.meteora_damm_v2_pool_list,
+            &self.pump_pool_list,
+            &self.whirlpool_pool_list,
+            &self.solfi_pool_list,
+            &self.vertigo_pool_list,
+            &self.lifinity_pool_list,
+            &self.phoenix_pool_list,
+        ]
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(String::as_str)
+        .collect()
+    }
+
+    /// Remove pool addresses that are not valid public keys, returning each dropped
+    /// address with the name of the list it was in
+    pub fn drop_invalid_pools(&mut self) -> Vec<(&'static str, String)> {
+        let lists = [
+            ("raydium_pool_list", &mut self.raydium_pool_list),
+            ("raydium_cp_pool_list", &mut self.raydium_cp_pool_list),
+            ("raydium_clmm_pool_list", &mut self.raydium_clmm_pool_list),
+            ("meteora_dlmm_pool_list", &mut self.meteora_dlmm_pool_list),
+            ("meteora_damm_pool_list", &mut self.meteora_damm_pool_list),
+            ("meteora_damm_v2_pool_list", &mut self.meteora_damm_v2_pool_list),
+            ("pump_pool_list", &mut self.pump_pool_list),
+            ("whirlpool_pool_list", &mut self.whirlpool_pool_list),
+            ("solfi_pool_list", &mut self.solfi_pool_list),
+            ("vertigo_pool_list", &mut self.vertigo_pool_list),
+            ("lifinity_pool_list", &mut self.lifinity_pool_list),
+            ("phoenix_pool_list", &mut self.phoenix_pool_list),
+        ];
+
+        let mut dropped = Vec::new();
+        for (list_name, pools) in lists {
+            if let Some(pools) = pools {
+                pools.retain(|addr| {
+                    let valid = Pubkey::from_str(addr).is_ok();
+                    if !valid {
+                        dropped.push((list_name, addr.clone()));
+                    }
+                    valid
+                });
+            }
+        }
+        dropped
+    }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct RpcConfig {
     #[serde(deserialize_with = "serde_string_or_env")]
     pub url: String,
+    /// Timeout for reads (account fetches, blockhash), in milliseconds
+    pub rpc_timeout_ms: Option<u64>,
+    /// Timeout for transaction sends, in milliseconds
+    pub send_timeout_ms: Option<u64>,
+    /// WebSocket endpoint; derived from `url` (http -> ws) when omitted
+    pub ws_url: Option<String>,
+}
+
+impl RpcConfig {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.rpc_timeout_ms.unwrap_or(DEFAULT_RPC_TIMEOUT_MS))
+    }
+
+    pub fn send_timeout(&self) -> Duration {
+        Duration::from_millis(self.send_timeout_ms.unwrap_or(DEFAULT_RPC_SEND_TIMEOUT_MS))
+    }
+
+    pub fn ws_url(&self) -> String {
+        match &self.ws_url {
+            Some(ws_url) => ws_url.clone(),
+            None => self.url.replacen("http", "ws", 1),
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct SpamConfig {
     pub enabled: bool,
     pub sending_rpc_urls: Vec<String>,
     pub compute_unit_price: u64,
     pub max_retries: Option<u64>,
+    /// "broadcast" (default), "first_success" or "fastest"
+    pub strategy: Option<String>,
+    /// "read_rpc" (default) or "sender"
+    pub blockhash_source: Option<String>,
+    /// Price compute units at this percentile (1-100) of the fees recently paid to
+    /// write the route's accounts instead of `compute_unit_price`
+    pub priority_fee_percentile: Option<u8>,
+    /// Resends after an "AccountInUse" rejection, on top of `max_retries` (default 3)
+    pub account_in_use_retries: Option<u32>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl SpamConfig {
+    pub fn strategy(&self) -> SendStrategy {
+        self.strategy
+            .as_deref()
+            .and_then(|strategy| strategy.parse().ok())
+            .unwrap_or(SendStrategy::Broadcast)
+    }
+
+    pub fn account_in_use_retries(&self) -> u32 {
+        self.account_in_use_retries.unwrap_or(DEFAULT_ACCOUNT_IN_USE_RETRIES)
+    }
+
+    pub fn blockhash_source(&self) -> BlockhashSource {
+        self.blockhash_source
+            .as_deref()
+            .and_then(|source| source.parse().ok())
+            .unwrap_or(BlockhashSource::ReadRpc)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct WalletConfig {
-    #[serde(deserialize_with = "serde_string_or_env")]
+    /// Base58 keypair or keypair file path; not needed with `remote_signer_url`
+    #[serde(default, deserialize_with = "serde_string_or_env")]
     pub private_key: String,
+    /// HTTP signing service that holds the key instead of this config
+    pub remote_signer_url: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct FlashloanConfig {
     pub enabled: bool,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct JitoConfig {
+    /// Pin the tip accounts bundles pay into instead of the built-in list
+    pub tip_accounts: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct WatchdogConfig {
+    /// Halt trading after this long with opportunities but no landed trade
+    pub max_idle_minutes: u64,
+    /// Resume automatically after this long; omit to require a manual resume
+    pub resume_cooldown_minutes: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ExecutorConfig {
+    /// Deployed executor program to invoke (defaults to `EXECUTOR_PROGRAM_ID`)
+    pub program_id: Option<String>,
+    /// Account that receives the executor fee (defaults to `FEE_COLLECTOR`)
+    pub fee_collector: Option<String>,
+    /// Fixed accounts placed, in this order, between the mint accounts and the first
+    /// pool, for executor forks that expect them (none by default)
+    pub static_accounts: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct MetricsPushConfig {
+    /// Pushgateway base URL, e.g. `http://pushgateway:9091`
+    pub pushgateway_url: String,
+    /// Seconds between pushes (default 15)
+    pub interval_secs: Option<u64>,
+    /// `job` label of the pushed metric group (default `solana_arbitrage_bot`)
+    pub job: Option<String>,
+    /// `instance` label, to tell several bots pushing under one job apart
+    pub instance: Option<String>,
+}
+
+impl MetricsPushConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs.unwrap_or(DEFAULT_METRICS_PUSH_INTERVAL_SECS))
+    }
+
+    pub fn job(&self) -> &str {
+        self.job.as_deref().unwrap_or(DEFAULT_METRICS_PUSH_JOB)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct OpportunityFeedConfig {
+    /// Address to accept feed clients on, e.g. `127.0.0.1:9300`
+    pub bind_address: String,
+    /// Events kept per client before a slow one starts losing them (default 1024)
+    pub buffer_size: Option<usize>,
+}
+
+impl OpportunityFeedConfig {
+    pub fn buffer_size(&self) -> usize {
+        self.buffer_size.unwrap_or(DEFAULT_OPPORTUNITY_FEED_BUFFER_SIZE)
+    }
+}
+
+/// Per-DEX cadence, in milliseconds, of re-reading the accounts a pool's dynamic fee is
+/// priced from; each defaults to suit its protocol. Raydium CLMM and CP observations only
+/// record TWAP samples, which no quote prices from, so they have no cadence.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct ObservationRefreshConfig {
+    pub meteora_dlmm_ms: Option<u64>,
+    pub whirlpool_ms: Option<u64>,
+}
+
+impl ObservationRefreshConfig {
+    /// Cadence for pools of `dex_name`, or None if the DEX has no observation account
+    pub fn cadence(&self, dex_name: &str) -> Option<Duration> {
+        let ms = match dex_name {
+            "Meteora DLMM" => self.meteora_dlmm_ms.unwrap_or(DEFAULT_DLMM_ORACLE_REFRESH_MS),
+            "Orca Whirlpool" => self.whirlpool_ms.unwrap_or(DEFAULT_WHIRLPOOL_ORACLE_REFRESH_MS),
+            _ => return None,
+        };
+        Some(Duration::from_millis(ms))
+    }
+}
+
 /// Deserialize a string that can either be a literal value or an environment variable reference
 pub fn serde_string_or_env<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
@@ -94,6 +1007,34 @@ where
     Ok(value)
 }
 
+fn env_required(name: &str) -> BotResult<String> {
+    env::var(name).map_err(|_| {
+        BotError::ConfigError(format!("Environment variable '{}' is not set", name))
+    })
+}
+
+fn env_number(name: &str) -> BotResult<Option<u64>> {
+    env::var(name)
+        .ok()
+        .map(|raw| {
+            raw.parse().map_err(|_| {
+                BotError::ConfigError(format!("{} must be a number, got: {}", name, raw))
+            })
+        })
+        .transpose()
+}
+
+fn env_json(name: &str) -> BotResult<Option<Value>> {
+    env::var(name)
+        .ok()
+        .map(|raw| {
+            serde_json::from_str(&raw).map_err(|e| {
+                BotError::ConfigError(format!("{} is not valid JSON: {}", name, e))
+            })
+        })
+        .transpose()
+}
+
 impl Config {
     /// Load and validate configuration from a TOML file
     pub fn load(path: &str) -> BotResult<Self> {
@@ -107,50 +1048,461 @@ impl Config {
         })?;
 
         let config: Config = toml::from_str(&contents)?;
-
-        // Validate the loaded configuration
-        config.validate()?;
-
-        Ok(config)
+        config.prepare()
     }
 
-    /// Comprehensive configuration validation
-    fn validate(&self) -> BotResult<()> {
-        // Validate bot configuration
-        self.validate_bot_config()?;
-
-        // Validate routing configuration
-        self.validate_routing_config()?;
+    /// Build and validate the configuration from environment variables alone, for
+    /// deployments without a config file:
+    ///
+    /// * `BOT_RPC_URL` (required), `BOT_WS_URL`, `BOT_RPC_TIMEOUT_MS`, `BOT_SEND_TIMEOUT_MS`
+    /// * `BOT_WALLET_PRIVATE_KEY` and/or `BOT_REMOTE_SIGNER_URL`
+    /// * `BOT_MINTS` (required): JSON array of `[[routing.mint_config_list]]` entries
+    /// * `BOT_COMPUTE_UNIT_LIMIT`, plus any other `[bot]` option as a JSON object in
+    ///   `BOT_SETTINGS`
+    /// * `BOT_CONFIG_VERSION`, `BOT_BASE_MINT`
+    /// * `BOT_BACKUP_WALLET`, `BOT_SPAM`, `BOT_FLASHLOAN`, `BOT_JITO`, `BOT_WATCHDOG`,
+    ///   `BOT_EXECUTOR`: JSON objects for those sections
+    pub fn from_env() -> BotResult<Self> {
+        let mut bot = match env_json("BOT_SETTINGS")? {
+            Some(Value::Object(settings)) => settings,
+            Some(_) => {
+                return Err(BotError::ConfigError(
+                    "BOT_SETTINGS must be a JSON object".to_string(),
+                ))
+            }
+            None => Map::new(),
+        };
+        if let Some(limit) = env_number("BOT_COMPUTE_UNIT_LIMIT")? {
+            bot.insert("compute_unit_limit".to_string(), json!(limit));
+        }
 
-        // Validate RPC configuration
-        self.validate_rpc_config()?;
+        let mut rpc = Map::new();
+        rpc.insert("url".to_string(), json!(env_required("BOT_RPC_URL")?));
+        if let Ok(ws_url) = env::var("BOT_WS_URL") {
+            rpc.insert("ws_url".to_string(), json!(ws_url));
+        }
+        for (var, field) in [
+            ("BOT_RPC_TIMEOUT_MS", "rpc_timeout_ms"),
+            ("BOT_SEND_TIMEOUT_MS", "send_timeout_ms"),
+        ] {
+            if let Some(timeout) = env_number(var)? {
+                rpc.insert(field.to_string(), json!(timeout));
+            }
+        }
 
-        // Validate spam configuration
-        if let Some(spam_config) = &self.spam {
-            self.validate_spam_config(spam_config)?;
+        let mut wallet = Map::new();
+        if let Ok(private_key) = env::var("BOT_WALLET_PRIVATE_KEY") {
+            wallet.insert("private_key".to_string(), json!(private_key));
+        }
+        if let Ok(signer_url) = env::var("BOT_REMOTE_SIGNER_URL") {
+            wallet.insert("remote_signer_url".to_string(), json!(signer_url));
         }
 
-        // Validate wallet configuration
-        self.validate_wallet_config()?;
+        let mints = env_json("BOT_MINTS")?.ok_or_else(|| {
+            BotError::ConfigError("Environment variable 'BOT_MINTS' is not set".to_string())
+        })?;
 
-        Ok(())
+        let mut root = Map::new();
+        root.insert("bot".to_string(), Value::Object(bot));
+        root.insert("routing".to_string(), json!({ "mint_config_list": mints }));
+        root.insert("rpc".to_string(), Value::Object(rpc));
+        root.insert("wallet".to_string(), Value::Object(wallet));
+        if let Some(version) = env_number("BOT_CONFIG_VERSION")? {
+            root.insert("version".to_string(), json!(version));
+        }
+        if let Ok(base_mint) = env::var("BOT_BASE_MINT") {
+            root.insert("base_mint".to_string(), json!(base_mint));
+        }
+        for (var, section) in ENV_JSON_SECTIONS {
+            if let Some(value) = env_json(var)? {
+                root.insert(section.to_string(), value);
+            }
+        }
+
+        let config: Config = serde_json::from_value(Value::Object(root)).map_err(|e| {
+            BotError::ConfigError(format!("Invalid configuration from environment: {}", e))
+        })?;
+        config.prepare()
     }
 
-    fn validate_bot_config(&self) -> BotResult<()> {
-        // Validate compute unit limit is reasonable
-        if self.bot.compute_unit_limit == 0 {
-            return Err(BotError::ConfigError(
-                "compute_unit_limit must be greater than 0".to_string(),
-            ));
+    /// Apply load-time options and validate, the same for every config source
+    fn prepare(mut self) -> BotResult<Self> {
+        if self.bot.skip_invalid_pools() {
+            let skipped = self.drop_invalid_pools()?;
+            if skipped > 0 {
+                warn!("Skipped {} invalid pool addresses (skip_invalid_pools)", skipped);
+            }
         }
 
-        if self.bot.compute_unit_limit > 1_400_000 {
-            return Err(BotError::ConfigError(format!(
-                "compute_unit_limit {} exceeds Solana's maximum of 1,400,000",
+        // Validate the loaded configuration
+        self.validate()?;
+
+        if self.schema_version() < CURRENT_CONFIG_VERSION {
+            warn!(
+                "Config schema version {} is older than current version {}; these fields use defaults: {}",
+                self.schema_version(),
+                CURRENT_CONFIG_VERSION,
+                self.fields_defaulted_since_version().join(", ")
+            );
+        }
+
+        Ok(self)
+    }
+
+    /// Drop invalid pool addresses from every mint, returning how many were dropped.
+    /// Fails if a mint is left without any valid pool.
+    pub fn drop_invalid_pools(&mut self) -> BotResult<usize> {
+        let mut skipped = 0;
+        for (index, mint_config) in self.routing.mint_config_list.iter_mut().enumerate() {
+            let dropped = mint_config.drop_invalid_pools();
+            if dropped.is_empty() {
+                continue;
+            }
+            for (list_name, addr) in &dropped {
+                warn!(
+                    "mint_config[{}] ({}): skipping invalid pool address in {}: {}",
+                    index, mint_config.mint, list_name, addr
+                );
+            }
+            if mint_config.pool_addresses().is_empty() {
+                return Err(BotError::ConfigError(format!(
+                    "mint_config[{}] for mint '{}' has no valid pool addresses left after skipping {} invalid",
+                    index,
+                    mint_config.mint,
+                    dropped.len()
+                )));
+            }
+            skipped += dropped.len();
+        }
+        Ok(skipped)
+    }
+
+    /// Schema version of this config (unversioned configs are treated as version 0)
+    pub fn schema_version(&self) -> u32 {
+        self.version.unwrap_or(0)
+    }
+
+    /// Fields that gained defaults after this config's schema version
+    pub fn fields_defaulted_since_version(&self) -> Vec<&'static str> {
+        let version = self.schema_version();
+        CONFIG_VERSION_MIGRATIONS
+            .iter()
+            .filter(|(introduced_in, _)| *introduced_in > version)
+            .flat_map(|(_, fields)| fields.iter().copied())
+            .collect()
+    }
+
+    /// Configured base mint override, if any (the address is checked by `validate`)
+    pub fn base_mint_override(&self) -> Option<Pubkey> {
+        self.base_mint
+            .as_deref()
+            .and_then(|mint| Pubkey::from_str(mint).ok())
+    }
+
+    /// Routing entry of `mint`, if it is configured
+    pub fn mint_config(&self, mint: &Pubkey) -> Option<&MintConfig> {
+        let mint = mint.to_string();
+        self.routing
+            .mint_config_list
+            .iter()
+            .find(|mint_config| mint_config.mint == mint)
+    }
+
+    /// Executor program the swap instruction targets (the address is checked by `validate`)
+    pub fn executor_program_id(&self) -> Pubkey {
+        self.executor
+            .as_ref()
+            .and_then(|executor| executor.program_id.as_deref())
+            .and_then(|id| Pubkey::from_str(id).ok())
+            .unwrap_or(*EXECUTOR_PROGRAM_PUBKEY)
+    }
+
+    /// Fee collector passed to the executor program (the address is checked by `validate`)
+    pub fn fee_collector(&self) -> Pubkey {
+        self.executor
+            .as_ref()
+            .and_then(|executor| executor.fee_collector.as_deref())
+            .and_then(|address| Pubkey::from_str(address).ok())
+            .unwrap_or(*FEE_COLLECTOR_PUBKEY)
+    }
+
+    /// Leading static accounts of the swap instruction (entries are checked by `validate`)
+    pub fn executor_static_accounts(&self) -> Vec<StaticAccount> {
+        self.executor
+            .as_ref()
+            .and_then(|executor| executor.static_accounts.as_ref())
+            .map(|accounts| accounts.iter().filter_map(|account| account.parse().ok()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Observation/oracle refresh cadences, defaults for an omitted section
+    pub fn observation_refresh(&self) -> ObservationRefreshConfig {
+        self.observation_refresh.clone().unwrap_or_default()
+    }
+
+    /// Configured Jito tip accounts, if pinned (addresses are checked by `validate`)
+    pub fn jito_tip_accounts(&self) -> Option<Vec<Pubkey>> {
+        let accounts = self.jito.as_ref()?.tip_accounts.as_ref()?;
+        accounts
+            .iter()
+            .map(|account| Pubkey::from_str(account).ok())
+            .collect()
+    }
+
+    /// Comprehensive configuration validation
+    fn validate(&self) -> BotResult<()> {
+        // Reject configs written for a newer binary
+        self.validate_version()?;
+
+        // Validate base mint override
+        if let Some(base_mint) = &self.base_mint {
+            Pubkey::from_str(base_mint).map_err(|e| BotError::InvalidPublicKey {
+                key: base_mint.clone(),
+                source: e,
+            })?;
+        }
+
+        // Validate bot configuration
+        self.validate_bot_config()?;
+
+        // Validate routing configuration
+        self.validate_routing_config()?;
+
+        // Validate RPC configuration
+        self.validate_rpc_config()?;
+
+        // Validate spam configuration
+        if let Some(spam_config) = &self.spam {
+            self.validate_spam_config(spam_config)?;
+        }
+
+        // Validate wallet configuration
+        self.validate_wallet_config()?;
+
+        // Validate watchdog configuration
+        if let Some(watchdog_config) = &self.watchdog {
+            self.validate_watchdog_config(watchdog_config)?;
+        }
+
+        // Validate Jito configuration
+        if let Some(jito_config) = &self.jito {
+            self.validate_jito_config(jito_config)?;
+        }
+
+        // Validate executor overrides
+        if let Some(executor_config) = &self.executor {
+            self.validate_executor_config(executor_config)?;
+        }
+
+        // Validate pushgateway export
+        if let Some(push_config) = &self.metrics_push {
+            self.validate_metrics_push_config(push_config)?;
+        }
+
+        if let Some(feed_config) = &self.opportunity_feed {
+            self.validate_opportunity_feed_config(feed_config)?;
+        }
+
+        if let Some(refresh_config) = &self.observation_refresh {
+            self.validate_observation_refresh_config(refresh_config)?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_version(&self) -> BotResult<()> {
+        if self.schema_version() > CURRENT_CONFIG_VERSION {
+            return Err(BotError::ConfigError(format!(
+                "config version {} is newer than the maximum supported version {}",
+                self.schema_version(),
+                CURRENT_CONFIG_VERSION
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn validate_bot_config(&self) -> BotResult<()> {
+        // Validate compute unit limit is reasonable
+        if self.bot.compute_unit_limit == 0 {
+            return Err(BotError::ConfigError(
+                "compute_unit_limit must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.bot.compute_unit_limit > MAX_COMPUTE_UNIT_LIMIT {
+            return Err(BotError::ConfigError(format!(
+                "compute_unit_limit {} exceeds Solana's maximum of 1,400,000",
                 self.bot.compute_unit_limit
             )));
         }
 
+        if let Some(version) = &self.bot.transaction_version {
+            let version: TransactionVersion = version.parse()?;
+            if version == TransactionVersion::Legacy {
+                let uses_lookup_tables = self.routing.mint_config_list.iter().any(|mint_config| {
+                    mint_config
+                        .lookup_table_accounts
+                        .as_ref()
+                        .map_or(false, |tables| !tables.is_empty())
+                });
+                if uses_lookup_tables {
+                    return Err(BotError::ConfigError(
+                        "legacy transactions cannot use lookup_table_accounts; use transaction_version = \"v0\"".to_string(),
+                    ));
+                }
+            }
+        }
+
+        if let Some(commitment) = &self.bot.confirm_commitment {
+            parse_commitment(commitment)?;
+        }
+
+        if let Some(mode) = &self.bot.confirmation_mode {
+            mode.parse::<ConfirmationMode>()?;
+        }
+
+        if self.bot.confirm_timeout_ms == Some(0) {
+            return Err(BotError::ConfigError(
+                "confirm_timeout_ms must be greater than 0".to_string(),
+            ));
+        }
+
+        // An arbitrage cycle needs at least two pools
+        if matches!(self.bot.max_pools_per_tx, Some(max) if max < 2) {
+            return Err(BotError::ConfigError(
+                "max_pools_per_tx must be at least 2".to_string(),
+            ));
+        }
+        if matches!(self.bot.max_split_pools, Some(max) if max < 2) {
+            return Err(BotError::ConfigError(
+                "max_split_pools must be at least 2".to_string(),
+            ));
+        }
+        if matches!(self.bot.min_pools_per_mint, Some(min) if min < DEFAULT_MIN_POOLS_PER_MINT) {
+            return Err(BotError::ConfigError(format!(
+                "min_pools_per_mint must be at least {}",
+                DEFAULT_MIN_POOLS_PER_MINT
+            )));
+        }
+        if matches!(
+            self.bot.max_lookup_tables_per_mint,
+            Some(max) if max == 0 || max > MAX_LOOKUP_TABLES_PER_TRANSACTION
+        ) {
+            return Err(BotError::ConfigError(format!(
+                "max_lookup_tables_per_mint must be between 1 and {}",
+                MAX_LOOKUP_TABLES_PER_TRANSACTION
+            )));
+        }
+
+        for (dex, units) in self.bot.dex_compute_units.iter().flatten() {
+            if !DEX_NAMES.contains(&dex.as_str()) {
+                return Err(BotError::ConfigError(format!(
+                    "dex_compute_units has unknown DEX '{}', expected one of: {}",
+                    dex,
+                    DEX_NAMES.join(", ")
+                )));
+            }
+            if *units == 0 || *units > MAX_COMPUTE_UNIT_LIMIT {
+                return Err(BotError::ConfigError(format!(
+                    "dex_compute_units for '{}' must be between 1 and {}",
+                    dex, MAX_COMPUTE_UNIT_LIMIT
+                )));
+            }
+        }
+        if matches!(self.bot.compute_unit_overhead, Some(overhead) if overhead > MAX_COMPUTE_UNIT_LIMIT) {
+            return Err(BotError::ConfigError(format!(
+                "compute_unit_overhead cannot exceed {}",
+                MAX_COMPUTE_UNIT_LIMIT
+            )));
+        }
+
+        if matches!(self.bot.blockhash_expiry_margin_blocks, Some(margin) if margin >= BLOCKHASH_VALID_BLOCKS) {
+            return Err(BotError::ConfigError(format!(
+                "blockhash_expiry_margin_blocks must be less than {}, the blocks a blockhash is valid for",
+                BLOCKHASH_VALID_BLOCKS
+            )));
+        }
+
+        for account in self.bot.pinned_accounts.iter().flatten() {
+            Pubkey::from_str(account).map_err(|e| BotError::InvalidPublicKey {
+                key: account.clone(),
+                source: e,
+            })?;
+        }
+
+        if let Some(url) = &self.bot.alert_webhook_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(BotError::ConfigError(format!(
+                    "alert_webhook_url must start with http:// or https://, got: {}",
+                    url
+                )));
+            }
+        }
+
+        if self.bot.init_concurrency == Some(0) {
+            return Err(BotError::ConfigError(
+                "init_concurrency must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.bot.send_workers == Some(0) {
+            return Err(BotError::ConfigError(
+                "send_workers must be greater than 0".to_string(),
+            ));
+        }
+
+        // tokio's bounded channel panics on a zero capacity
+        if self.bot.send_queue_capacity == Some(0) {
+            return Err(BotError::ConfigError(
+                "send_queue_capacity must be greater than 0".to_string(),
+            ));
+        }
+
+        if let Some(policy) = &self.bot.send_queue_overflow {
+            policy.parse::<OverflowPolicy>()?;
+        }
+
+        if let Some(ranking) = &self.bot.ranking {
+            ranking_strategy(ranking)?;
+        }
+
+        if self.bot.wallet_failover_after == Some(0) {
+            return Err(BotError::ConfigError(
+                "wallet_failover_after must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.bot.max_total_input_lamports == Some(0) {
+            return Err(BotError::ConfigError(
+                "max_total_input_lamports must be greater than 0".to_string(),
+            ));
+        }
+
+        if let (Some(floor), Some(ceiling)) =
+            (self.bot.min_compute_unit_price, self.bot.max_compute_unit_price)
+        {
+            if floor > ceiling {
+                return Err(BotError::ConfigError(format!(
+                    "min_compute_unit_price {} exceeds max_compute_unit_price {}",
+                    floor, ceiling
+                )));
+            }
+        }
+
+        if self.bot.auto_wrap_sol() {
+            if self.bot.wrap_sol_lamports.map_or(true, |lamports| lamports == 0) {
+                return Err(BotError::ConfigError(
+                    "auto_wrap_sol requires wrap_sol_lamports greater than 0".to_string(),
+                ));
+            }
+            if self.base_mint.as_deref().map_or(false, |mint| mint != SOL_MINT) {
+                return Err(BotError::ConfigError(
+                    "auto_wrap_sol only works with the wSOL base mint".to_string(),
+                ));
+            }
+        }
+
         Ok(())
     }
 
@@ -178,7 +1530,9 @@ impl Config {
                 || mint_config.pump_pool_list.is_some()
                 || mint_config.whirlpool_pool_list.is_some()
                 || mint_config.solfi_pool_list.is_some()
-                || mint_config.vertigo_pool_list.is_some();
+                || mint_config.vertigo_pool_list.is_some()
+                || mint_config.lifinity_pool_list.is_some()
+                || mint_config.phoenix_pool_list.is_some();
 
             if !has_pools {
                 return Err(BotError::ConfigError(format!(
@@ -198,6 +1552,68 @@ impl Config {
             self.validate_pool_addresses("whirlpool_pool_list", &mint_config.whirlpool_pool_list)?;
             self.validate_pool_addresses("solfi_pool_list", &mint_config.solfi_pool_list)?;
             self.validate_pool_addresses("vertigo_pool_list", &mint_config.vertigo_pool_list)?;
+            self.validate_pool_addresses("lifinity_pool_list", &mint_config.lifinity_pool_list)?;
+            self.validate_pool_addresses("phoenix_pool_list", &mint_config.phoenix_pool_list)?;
+
+            if let (Some(min), Some(max)) =
+                (mint_config.min_process_delay_ms, mint_config.max_process_delay_ms)
+            {
+                if min > max {
+                    return Err(BotError::ConfigError(format!(
+                        "mint_config[{}] min_process_delay_ms {} exceeds max_process_delay_ms {}",
+                        index, min, max
+                    )));
+                }
+            }
+
+            if let Some(url) = &mint_config.rpc_url {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(BotError::ConfigError(format!(
+                        "mint_config[{}] rpc_url must start with http:// or https://, got: {}",
+                        index, url
+                    )));
+                }
+            }
+
+            if let Some(max_idle_delay_ms) = mint_config.max_idle_delay_ms {
+                if max_idle_delay_ms < mint_config.process_delay {
+                    return Err(BotError::ConfigError(format!(
+                        "mint_config[{}] max_idle_delay_ms {} is below process_delay {}",
+                        index, max_idle_delay_ms, mint_config.process_delay
+                    )));
+                }
+            }
+
+            if let Some(mode) = &mint_config.execution_mode {
+                if mode.parse::<ExecutionMode>().is_err() {
+                    return Err(BotError::ConfigError(format!(
+                        "mint_config[{}] execution_mode must be \"jito\", \"spam\" or \"auto\", got: {}",
+                        index, mode
+                    )));
+                }
+            }
+
+            if matches!(mint_config.compute_unit_limit, Some(limit) if limit == 0 || limit > MAX_COMPUTE_UNIT_LIMIT) {
+                return Err(BotError::ConfigError(format!(
+                    "mint_config[{}] compute_unit_limit must be between 1 and {}",
+                    index, MAX_COMPUTE_UNIT_LIMIT
+                )));
+            }
+
+            if mint_config.max_input_lamports == Some(0) {
+                return Err(BotError::ConfigError(format!(
+                    "mint_config[{}] max_input_lamports must be greater than 0",
+                    index
+                )));
+            }
+
+            // Routes sized on-chain reserve the mint's input cap against the total
+            if self.bot.max_total_input_lamports.is_some() && mint_config.max_input_lamports.is_none() {
+                return Err(BotError::ConfigError(format!(
+                    "mint_config[{}] needs max_input_lamports when max_total_input_lamports is set",
+                    index
+                )));
+            }
 
             // Validate lookup table addresses
             if let Some(lookup_tables) = &mint_config.lookup_table_accounts {
@@ -207,6 +1623,15 @@ impl Config {
                         source: e,
                     })?;
                 }
+                // v0 transactions also carry the default lookup table
+                let default_tables = usize::from(self.bot.transaction_version() == TransactionVersion::V0);
+                let tables = lookup_tables.len() + default_tables;
+                if tables > MAX_LOOKUP_TABLES_PER_TRANSACTION {
+                    return Err(BotError::LookupTableLimitExceeded {
+                        tables,
+                        max: MAX_LOOKUP_TABLES_PER_TRANSACTION,
+                    });
+                }
             }
         }
 
@@ -241,6 +1666,121 @@ impl Config {
             )));
         }
 
+        if let Some(ws_url) = &self.rpc.ws_url {
+            if !ws_url.starts_with("ws://") && !ws_url.starts_with("wss://") {
+                return Err(BotError::ConfigError(format!(
+                    "WebSocket URL must start with ws:// or wss://, got: {}",
+                    ws_url
+                )));
+            }
+        }
+
+        for (name, timeout_ms) in [
+            ("rpc.rpc_timeout_ms", self.rpc.rpc_timeout_ms),
+            ("rpc.send_timeout_ms", self.rpc.send_timeout_ms),
+        ] {
+            if let Some(timeout_ms) = timeout_ms {
+                if !(MIN_RPC_TIMEOUT_MS..=MAX_RPC_TIMEOUT_MS).contains(&timeout_ms) {
+                    return Err(BotError::ConfigError(format!(
+                        "{} must be between {} and {}, got: {}",
+                        name, MIN_RPC_TIMEOUT_MS, MAX_RPC_TIMEOUT_MS, timeout_ms
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_watchdog_config(&self, watchdog_config: &WatchdogConfig) -> BotResult<()> {
+        if watchdog_config.max_idle_minutes == 0 {
+            return Err(BotError::ConfigError(
+                "watchdog.max_idle_minutes must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn validate_jito_config(&self, jito_config: &JitoConfig) -> BotResult<()> {
+        if let Some(tip_accounts) = &jito_config.tip_accounts {
+            for account in tip_accounts {
+                Pubkey::from_str(account).map_err(|e| BotError::ConfigError(format!(
+                    "Invalid Jito tip account: {} (error: {})",
+                    account, e
+                )))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_executor_config(&self, executor_config: &ExecutorConfig) -> BotResult<()> {
+        for address in [&executor_config.program_id, &executor_config.fee_collector]
+            .into_iter()
+            .flatten()
+        {
+            Pubkey::from_str(address).map_err(|e| BotError::InvalidPublicKey {
+                key: address.clone(),
+                source: e,
+            })?;
+        }
+        for account in executor_config.static_accounts.iter().flatten() {
+            account.parse::<StaticAccount>()?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_metrics_push_config(&self, push_config: &MetricsPushConfig) -> BotResult<()> {
+        let url = &push_config.pushgateway_url;
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(BotError::ConfigError(format!(
+                "metrics_push.pushgateway_url must start with http:// or https://, got: {}",
+                url
+            )));
+        }
+
+        if push_config.interval_secs == Some(0) {
+            return Err(BotError::ConfigError(
+                "metrics_push.interval_secs must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn validate_opportunity_feed_config(&self, feed_config: &OpportunityFeedConfig) -> BotResult<()> {
+        if feed_config.bind_address.parse::<std::net::SocketAddr>().is_err() {
+            return Err(BotError::ConfigError(format!(
+                "opportunity_feed.bind_address must be an IP:port address, got: {}",
+                feed_config.bind_address
+            )));
+        }
+
+        if feed_config.buffer_size == Some(0) {
+            return Err(BotError::ConfigError(
+                "opportunity_feed.buffer_size must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn validate_observation_refresh_config(&self, refresh_config: &ObservationRefreshConfig) -> BotResult<()> {
+        let cadences = [
+            ("meteora_dlmm_ms", refresh_config.meteora_dlmm_ms),
+            ("whirlpool_ms", refresh_config.whirlpool_ms),
+        ];
+        for (name, ms) in cadences {
+            if ms == Some(0) {
+                return Err(BotError::ConfigError(format!(
+                    "observation_refresh.{} must be greater than 0",
+                    name
+                )));
+            }
+        }
+
         Ok(())
     }
 
@@ -261,10 +1801,37 @@ impl Config {
             }
         }
 
+        if let Some(strategy) = &spam_config.strategy {
+            strategy.parse::<SendStrategy>()?;
+        }
+
+        if let Some(source) = &spam_config.blockhash_source {
+            source.parse::<BlockhashSource>()?;
+        }
+
+        if let Some(percentile) = spam_config.priority_fee_percentile {
+            if !(1..=100).contains(&percentile) {
+                return Err(BotError::ConfigError(format!(
+                    "spam.priority_fee_percentile must be between 1 and 100, got: {}",
+                    percentile
+                )));
+            }
+        }
+
         Ok(())
     }
 
     fn validate_wallet_config(&self) -> BotResult<()> {
+        if let Some(url) = &self.wallet.remote_signer_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(BotError::ConfigError(format!(
+                    "Remote signer URL must start with http:// or https://, got: {}",
+                    url
+                )));
+            }
+            return Ok(());
+        }
+
         if self.wallet.private_key.is_empty() {
             return Err(BotError::ConfigError(
                 "wallet.private_key cannot be empty".to_string(),
@@ -279,47 +1846,916 @@ impl Config {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_config_validation_empty_mint_list() {
-        let config = Config {
+    fn base_config() -> Config {
+        Config {
+            version: Some(CURRENT_CONFIG_VERSION),
+            base_mint: None,
             bot: BotConfig {
                 compute_unit_limit: 100_000,
+                transaction_version: None,
+                warmup_on_startup: None,
+                strict_token_checks: None,
+                simulate_before_send: None,
+                resend_dropped: None,
+                use_websocket_blockhash: None,
+                use_websocket_pool_updates: None,
+                auto_wrap_sol: None,
+                wrap_sol_lamports: None,
+                db_retry_buffer_size: None,
+                startup_jitter_ms: None,
+                confirm_commitment: None,
+                confirm_timeout_ms: None,
+                confirmation_mode: None,
+                disabled_dexes: None,
+                send_workers: None,
+                send_queue_capacity: None,
+                send_queue_overflow: None,
+                trade_log_path: None,
+                trade_log_rotate: None,
+                auto_raise_compute_unit_limit: None,
+                reconcile_profit: None,
+                ranking: None,
+                skip_invalid_pools: None,
+                min_compute_unit_price: None,
+                max_compute_unit_price: None,
+                max_total_input_lamports: None,
+                wallet_failover_after: None,
+                wallet_min_balance_lamports: None,
+                trade_cooldown_ms: None,
+                max_pools_per_tx: None,
+                max_split_pools: None,
+                ata_check_interval_secs: None,
+                init_concurrency: None,
+                max_account_staleness_slots: None,
+                blockhash_expiry_margin_blocks: None,
+                alert_webhook_url: None,
+                min_pools_per_mint: None,
+                pinned_accounts: None,
+                pinned_refresh_secs: None,
+                max_lookup_tables_per_mint: None,
+                estimate_compute_unit_limit: None,
+                dex_compute_units: None,
+                compute_unit_overhead: None,
+                health_port: None,
             },
             routing: RoutingConfig {
                 mint_config_list: vec![],
             },
             rpc: RpcConfig {
                 url: "https://api.mainnet-beta.solana.com".to_string(),
+                rpc_timeout_ms: None,
+                send_timeout_ms: None,
+                ws_url: None,
             },
             spam: None,
             wallet: WalletConfig {
                 private_key: "test".to_string(),
+                remote_signer_url: None,
             },
+            backup_wallet: None,
             flashloan: None,
-        };
+            jito: None,
+            watchdog: None,
+            executor: None,
+            metrics_push: None,
+            opportunity_feed: None,
+            observation_refresh: None,
+        }
+    }
+
+    #[test]
+    fn test_config_validation_empty_mint_list() {
+        let config = base_config();
 
         assert!(config.validate().is_err());
     }
 
     #[test]
     fn test_config_validation_invalid_compute_limit() {
-        let config = Config {
-            bot: BotConfig {
-                compute_unit_limit: 0,
-            },
-            routing: RoutingConfig {
-                mint_config_list: vec![],
-            },
-            rpc: RpcConfig {
-                url: "https://api.mainnet-beta.solana.com".to_string(),
-            },
-            spam: None,
-            wallet: WalletConfig {
-                private_key: "test".to_string(),
-            },
-            flashloan: None,
-        };
+        let mut config = base_config();
+        config.bot.compute_unit_limit = 0;
 
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_config_version_older_lists_defaulted_fields() {
+        let mut config = base_config();
+        config.version = None;
+
+        assert_eq!(config.schema_version(), 0);
+        assert!(config.validate_version().is_ok());
+        assert!(config.fields_defaulted_since_version().contains(&"version"));
+
+        // A version 1 config only misses the fields added since
+        config.version = Some(1);
+        let defaulted = config.fields_defaulted_since_version();
+        assert!(!defaulted.contains(&"version"));
+        assert!(defaulted.contains(&"bot.warmup_on_startup"));
+        assert!(defaulted.contains(&"routing.mint_config_list.execution_mode"));
+    }
+
+    #[test]
+    fn test_config_version_current() {
+        let config = base_config();
+
+        assert!(config.validate_version().is_ok());
+        assert!(config.fields_defaulted_since_version().is_empty());
+    }
+
+    #[test]
+    fn test_config_version_newer_rejected() {
+        let mut config = base_config();
+        config.version = Some(CURRENT_CONFIG_VERSION + 1);
+
+        assert!(config.validate_version().is_err());
+    }
+
+    #[test]
+    fn test_jito_tip_accounts_override() {
+        let mut config = base_config();
+        assert!(config.jito_tip_accounts().is_none());
+
+        let jito: JitoConfig = toml::from_str(
+            r#"tip_accounts = ["96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"]"#,
+        )
+        .unwrap();
+        assert!(config.validate_jito_config(&jito).is_ok());
+        config.jito = Some(jito);
+
+        assert_eq!(
+            config.jito_tip_accounts(),
+            Some(vec![Pubkey::from_str("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5").unwrap()])
+        );
+    }
+
+    #[test]
+    fn test_jito_tip_accounts_invalid_rejected() {
+        let config = base_config();
+        let jito: JitoConfig = toml::from_str(r#"tip_accounts = ["not-a-pubkey"]"#).unwrap();
+
+        assert!(config.validate_jito_config(&jito).is_err());
+    }
+
+    #[test]
+    fn test_watchdog_zero_idle_rejected() {
+        let config = base_config();
+        let watchdog = WatchdogConfig {
+            max_idle_minutes: 0,
+            resume_cooldown_minutes: None,
+        };
+
+        assert!(config.validate_watchdog_config(&watchdog).is_err());
+    }
+
+    fn mint_with_lookup_table() -> MintConfig {
+        MintConfig {
+            mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            raydium_pool_list: None,
+            raydium_cp_pool_list: None,
+            raydium_clmm_pool_list: None,
+            meteora_dlmm_pool_list: None,
+            meteora_damm_pool_list: None,
+            meteora_damm_v2_pool_list: None,
+            pump_pool_list: Some(vec!["Gf7sXMoP8iRw4iiXmJ1nq4vxcRycbGXy5RL8a8LnTd3v".to_string()]),
+            whirlpool_pool_list: None,
+            solfi_pool_list: None,
+            vertigo_pool_list: None,
+            lifinity_pool_list: None,
+            phoenix_pool_list: None,
+            lookup_table_accounts: Some(vec![
+                "8HvgxVyd22Jq9mmoojm4Awqw6sbymbF5pwLr8FtvySHs".to_string(),
+            ]),
+            process_delay: 400,
+            adaptive_delay: None,
+            min_process_delay_ms: None,
+            max_process_delay_ms: None,
+            max_idle_delay_ms: None,
+            max_input_lamports: None,
+            rpc_url: None,
+            execution_mode: None,
+            jito_min_profit_lamports: None,
+            min_profit_lamports: None,
+            compute_unit_limit: None,
+        }
+    }
+
+    #[test]
+    fn test_transaction_version_defaults_to_v0() {
+        let config = base_config();
+
+        assert_eq!(config.bot.transaction_version(), TransactionVersion::V0);
+    }
+
+    #[test]
+    fn test_transaction_version_invalid_rejected() {
+        let mut config = base_config();
+        config.bot.transaction_version = Some("v1".to_string());
+
+        assert!(config.validate_bot_config().is_err());
+    }
+
+    #[test]
+    fn test_execution_mode_validated() {
+        let mut config = base_config();
+        let mut mint = mint_with_lookup_table();
+        for mode in ["jito", "spam", "auto"] {
+            mint.execution_mode = Some(mode.to_string());
+            config.routing.mint_config_list = vec![mint.clone()];
+            assert!(config.validate_routing_config().is_ok(), "{}", mode);
+        }
+
+        mint.execution_mode = Some("bundle".to_string());
+        config.routing.mint_config_list = vec![mint];
+        let err = config.validate_routing_config().unwrap_err().to_string();
+        assert!(err.contains("mint_config[0] execution_mode"), "{}", err);
+    }
+
+    #[test]
+    fn test_capital_cap_needs_mint_input_cap() {
+        let mut config = base_config();
+        config.bot.max_total_input_lamports = Some(5_000_000_000);
+        let mut mint = mint_with_lookup_table();
+        config.routing.mint_config_list = vec![mint.clone()];
+        let err = config.validate_routing_config().unwrap_err().to_string();
+        assert!(err.contains("mint_config[0] needs max_input_lamports"), "{}", err);
+
+        mint.max_input_lamports = Some(1_000_000_000);
+        config.routing.mint_config_list = vec![mint];
+        assert!(config.validate_routing_config().is_ok());
+    }
+
+    #[test]
+    fn test_mint_rpc_url_validated() {
+        let mut config = base_config();
+        let mut mint = mint_with_lookup_table();
+        mint.rpc_url = Some("https://premium.example.com".to_string());
+        config.routing.mint_config_list = vec![mint.clone()];
+        assert!(config.validate_routing_config().is_ok());
+
+        mint.rpc_url = Some("premium.example.com".to_string());
+        config.routing.mint_config_list = vec![mint];
+        assert!(config.validate_routing_config().is_err());
+    }
+
+    #[test]
+    fn test_too_many_lookup_tables_rejected() {
+        let mut config = base_config();
+        let mut mint = mint_with_lookup_table();
+        // The default table makes it one too many
+        mint.lookup_table_accounts = Some(
+            (0..MAX_LOOKUP_TABLES_PER_TRANSACTION)
+                .map(|_| Pubkey::new_unique().to_string())
+                .collect(),
+        );
+        config.routing.mint_config_list = vec![mint];
+
+        assert!(matches!(
+            config.validate_routing_config(),
+            Err(BotError::LookupTableLimitExceeded { tables, .. })
+                if tables == MAX_LOOKUP_TABLES_PER_TRANSACTION + 1
+        ));
+    }
+
+    #[test]
+    fn test_legacy_with_lookup_tables_rejected() {
+        let mut config = base_config();
+        config.routing.mint_config_list = vec![mint_with_lookup_table()];
+        config.bot.transaction_version = Some("legacy".to_string());
+        assert!(config.validate_bot_config().is_err());
+
+        config.routing.mint_config_list[0].lookup_table_accounts = None;
+        assert!(config.validate_bot_config().is_ok());
+        assert_eq!(config.bot.transaction_version(), TransactionVersion::Legacy);
+    }
+
+    #[test]
+    fn test_rpc_timeouts() {
+        let mut config = base_config();
+        assert_eq!(config.rpc.timeout(), Duration::from_millis(DEFAULT_RPC_TIMEOUT_MS));
+        assert_eq!(config.rpc.send_timeout(), Duration::from_millis(DEFAULT_RPC_SEND_TIMEOUT_MS));
+
+        config.rpc.rpc_timeout_ms = Some(1_500);
+        assert_eq!(config.rpc.timeout(), Duration::from_millis(1_500));
+        assert!(config.validate_rpc_config().is_ok());
+
+        config.rpc.send_timeout_ms = Some(60_000);
+        assert!(config.validate_rpc_config().is_err());
+    }
+
+    #[test]
+    fn test_ws_url_derived_from_rpc_url() {
+        let mut config = base_config();
+        assert_eq!(config.rpc.ws_url(), "wss://api.mainnet-beta.solana.com");
+
+        config.rpc.ws_url = Some("http://localhost:8900".to_string());
+        assert!(config.validate_rpc_config().is_err());
+    }
+
+    #[test]
+    fn test_executor_overrides() {
+        let mut config = base_config();
+        assert_eq!(config.executor_program_id(), *EXECUTOR_PROGRAM_PUBKEY);
+        assert_eq!(config.fee_collector(), *FEE_COLLECTOR_PUBKEY);
+
+        let program_id = Pubkey::new_unique();
+        config.executor = Some(ExecutorConfig {
+            program_id: Some(program_id.to_string()),
+            fee_collector: Some("not-a-pubkey".to_string()),
+            static_accounts: None,
+        });
+        assert_eq!(config.executor_program_id(), program_id);
+        assert!(config
+            .validate_executor_config(config.executor.as_ref().unwrap())
+            .is_err());
+
+        config.executor = Some(ExecutorConfig {
+            program_id: None,
+            fee_collector: None,
+            static_accounts: Some(vec!["sysvar_instructions".to_string(), "fee_collector".to_string()]),
+        });
+        assert_eq!(
+            config.executor_static_accounts(),
+            vec![StaticAccount::SysvarInstructions, StaticAccount::FeeCollector]
+        );
+        config.executor.as_mut().unwrap().static_accounts = Some(vec!["clock".to_string()]);
+        assert!(config
+            .validate_executor_config(config.executor.as_ref().unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_base_mint_override() {
+        let mut config = base_config();
+        assert!(config.base_mint_override().is_none());
+
+        let custom = Pubkey::new_unique();
+        config.base_mint = Some(custom.to_string());
+        assert_eq!(config.base_mint_override(), Some(custom));
+    }
+
+    #[test]
+    fn test_auto_wrap_sol_validation() {
+        let mut config = base_config();
+        config.bot.wrap_sol_lamports = Some(1_000_000);
+        assert_eq!(config.bot.wrap_sol_lamports(), None);
+
+        config.bot.auto_wrap_sol = Some(true);
+        assert_eq!(config.bot.wrap_sol_lamports(), Some(1_000_000));
+        assert!(config.validate_bot_config().is_ok());
+
+        config.base_mint = Some(Pubkey::new_unique().to_string());
+        assert!(config.validate_bot_config().is_err());
+
+        config.base_mint = None;
+        config.bot.wrap_sol_lamports = None;
+        assert!(config.validate_bot_config().is_err());
+    }
+
+    #[test]
+    fn test_confirm_commitment() {
+        let mut config = base_config();
+        assert_eq!(config.bot.confirm_commitment(), CommitmentConfig::confirmed());
+        assert_eq!(
+            config.bot.confirm_timeout(),
+            Duration::from_millis(DEFAULT_CONFIRM_TIMEOUT_MS)
+        );
+
+        config.bot.confirm_commitment = Some("finalized".to_string());
+        assert_eq!(config.bot.confirm_commitment(), CommitmentConfig::finalized());
+        assert!(config.validate_bot_config().is_ok());
+
+        config.bot.confirm_commitment = Some("max".to_string());
+        assert!(config.validate_bot_config().is_err());
+    }
+
+    #[test]
+    fn test_confirmation_mode() {
+        let mut config = base_config();
+        assert_eq!(config.bot.confirmation_mode(), None);
+
+        config.bot.confirmation_mode = Some("signature".to_string());
+        assert_eq!(config.bot.confirmation_mode(), Some(ConfirmationMode::Signature));
+        assert_eq!(
+            ConfirmationMode::Signature.commitment(),
+            Some(CommitmentConfig::processed())
+        );
+        assert_eq!(ConfirmationMode::None.commitment(), None);
+
+        // Finalization outlasts the default timeout
+        config.bot.confirmation_mode = Some("finalized".to_string());
+        assert!(config.validate_bot_config().is_ok());
+        assert_eq!(
+            config.bot.confirm_timeout(),
+            Duration::from_millis(DEFAULT_FINALIZED_CONFIRM_TIMEOUT_MS)
+        );
+        config.bot.confirm_timeout_ms = Some(20_000);
+        assert_eq!(config.bot.confirm_timeout(), Duration::from_millis(20_000));
+
+        config.bot.confirmation_mode = Some("landed".to_string());
+        assert!(config.validate_bot_config().is_err());
+    }
+
+    #[test]
+    fn test_pinned_accounts() {
+        let mut config = base_config();
+        assert!(config.bot.pinned_accounts().is_empty());
+        assert_eq!(
+            config.bot.pinned_refresh_interval(),
+            Some(Duration::from_secs(DEFAULT_PINNED_REFRESH_SECS))
+        );
+
+        let account = Pubkey::new_unique();
+        config.bot.pinned_accounts = Some(vec![account.to_string()]);
+        config.bot.pinned_refresh_secs = Some(0);
+        assert!(config.validate_bot_config().is_ok());
+        assert_eq!(config.bot.pinned_accounts(), vec![account]);
+        assert_eq!(config.bot.pinned_refresh_interval(), None);
+
+        config.bot.pinned_accounts = Some(vec!["not-a-pubkey".to_string()]);
+        assert!(config.validate_bot_config().is_err());
+    }
+
+    #[test]
+    fn test_max_pools_per_tx() {
+        let mut config = base_config();
+        assert_eq!(config.bot.max_pools_per_tx(), None);
+
+        config.bot.max_pools_per_tx = Some(3);
+        assert!(config.validate_bot_config().is_ok());
+
+        config.bot.max_pools_per_tx = Some(1);
+        assert!(config.validate_bot_config().is_err());
+    }
+
+    #[test]
+    fn test_max_split_pools() {
+        let mut config = base_config();
+        assert_eq!(config.bot.max_split_pools(), 1);
+
+        config.bot.max_split_pools = Some(3);
+        assert!(config.validate_bot_config().is_ok());
+        assert_eq!(config.bot.max_split_pools(), 3);
+
+        config.bot.max_split_pools = Some(1);
+        assert!(config.validate_bot_config().is_err());
+    }
+
+    #[test]
+    fn test_max_lookup_tables_per_mint() {
+        let mut config = base_config();
+        assert_eq!(config.bot.max_lookup_tables_per_mint(), MAX_LOOKUP_TABLES_PER_TRANSACTION);
+
+        config.bot.max_lookup_tables_per_mint = Some(3);
+        assert!(config.validate_bot_config().is_ok());
+        assert_eq!(config.bot.max_lookup_tables_per_mint(), 3);
+
+        for max in [0, MAX_LOOKUP_TABLES_PER_TRANSACTION + 1] {
+            config.bot.max_lookup_tables_per_mint = Some(max);
+            assert!(config.validate_bot_config().is_err());
+        }
+    }
+
+    #[test]
+    fn test_dex_compute_units() {
+        let mut config = base_config();
+        assert!(!config.bot.estimate_compute_unit_limit());
+        assert_eq!(config.bot.compute_unit_overhead(), DEFAULT_COMPUTE_UNIT_OVERHEAD);
+        assert_eq!(config.bot.dex_compute_units("Pump.fun"), None);
+
+        config.bot.dex_compute_units = Some(HashMap::from([("Pump.fun".to_string(), 30_000)]));
+        assert!(config.validate_bot_config().is_ok());
+        assert_eq!(config.bot.dex_compute_units("Pump.fun"), Some(30_000));
+
+        config.bot.dex_compute_units = Some(HashMap::from([("Pump".to_string(), 30_000)]));
+        assert!(config.validate_bot_config().is_err());
+        config.bot.dex_compute_units = Some(HashMap::from([("Pump.fun".to_string(), 0)]));
+        assert!(config.validate_bot_config().is_err());
+
+        config.bot.dex_compute_units = None;
+        config.bot.compute_unit_overhead = Some(MAX_COMPUTE_UNIT_LIMIT + 1);
+        assert!(config.validate_bot_config().is_err());
+    }
+
+    #[test]
+    fn test_send_queue_settings() {
+        let mut config = base_config();
+        assert_eq!(config.bot.send_workers(), DEFAULT_SEND_WORKERS);
+        assert_eq!(config.bot.send_queue_overflow(), OverflowPolicy::DropOldest);
+
+        config.bot.send_queue_overflow = Some("block".to_string());
+        assert_eq!(config.bot.send_queue_overflow(), OverflowPolicy::Block);
+        assert!(config.validate_bot_config().is_ok());
+
+        config.bot.send_queue_overflow = Some("drop_newest".to_string());
+        assert!(config.validate_bot_config().is_err());
+
+        config.bot.send_queue_overflow = None;
+        config.bot.send_queue_capacity = Some(0);
+        assert!(config.validate_bot_config().is_err());
+    }
+
+    #[test]
+    fn test_skip_invalid_pools_keeps_valid_addresses() {
+        let mut config = base_config();
+        let mut mint = mint_with_lookup_table();
+        mint.pump_pool_list = Some(vec![
+            "Gf7sXMoP8iRw4iiXmJ1nq4vxcRycbGXy5RL8a8LnTd3v".to_string(),
+            "not-a-pool".to_string(),
+        ]);
+        mint.raydium_pool_list = Some(vec![
+            "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2".to_string(),
+            "0OIl".to_string(),
+        ]);
+        config.routing.mint_config_list = vec![mint];
+        assert!(config.validate_routing_config().is_err());
+
+        assert_eq!(config.drop_invalid_pools().unwrap(), 2);
+        assert_eq!(
+            config.routing.mint_config_list[0].pool_addresses(),
+            vec![
+                "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2",
+                "Gf7sXMoP8iRw4iiXmJ1nq4vxcRycbGXy5RL8a8LnTd3v",
+            ]
+        );
+        assert!(config.validate_routing_config().is_ok());
+
+        // A mint whose every pool is invalid still fails
+        let mut mint = mint_with_lookup_table();
+        mint.pump_pool_list = Some(vec!["not-a-pool".to_string()]);
+        config.routing.mint_config_list = vec![mint];
+        assert!(config.drop_invalid_pools().is_err());
+    }
+
+    #[test]
+    fn test_ranking_validated() {
+        let mut config = base_config();
+        config.bot.ranking = Some("lowest_account_count".to_string());
+        assert!(config.validate_bot_config().is_ok());
+
+        config.bot.ranking = Some("highest_risk".to_string());
+        assert!(config.validate_bot_config().is_err());
+    }
+
+    #[test]
+    fn test_from_env_matches_toml() {
+        let vars = [
+            ("BOT_RPC_URL", "https://api.mainnet-beta.solana.com"),
+            ("BOT_RPC_TIMEOUT_MS", "1500"),
+            ("BOT_WALLET_PRIVATE_KEY", "test"),
+            ("BOT_COMPUTE_UNIT_LIMIT", "600000"),
+            ("BOT_SETTINGS", r#"{"ranking": "max_roi", "send_workers": 2}"#),
+            (
+                "BOT_MINTS",
+                r#"[{"mint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+                     "raydium_pool_list": ["58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2"],
+                     "process_delay": 400}]"#,
+            ),
+            ("BOT_WATCHDOG", r#"{"max_idle_minutes": 30}"#),
+        ];
+        for (name, value) in vars {
+            env::set_var(name, value);
+        }
+        let from_env = Config::from_env();
+        for (name, _) in vars {
+            env::remove_var(name);
+        }
+
+        let from_toml: Config = toml::from_str(
+            r#"
+            [bot]
+            compute_unit_limit = 600000
+            ranking = "max_roi"
+            send_workers = 2
+
+            [[routing.mint_config_list]]
+            mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+            raydium_pool_list = ["58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2"]
+            process_delay = 400
+
+            [rpc]
+            url = "https://api.mainnet-beta.solana.com"
+            rpc_timeout_ms = 1500
+
+            [wallet]
+            private_key = "test"
+
+            [watchdog]
+            max_idle_minutes = 30
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            format!("{:?}", from_env.unwrap()),
+            format!("{:?}", from_toml.prepare().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_compute_unit_price_floor() {
+        let mut config = base_config();
+        assert_eq!(config.bot.clamp_compute_unit_price(1_000), 1_000);
+
+        config.bot.min_compute_unit_price = Some(5_000);
+        assert_eq!(config.bot.clamp_compute_unit_price(1_000), 5_000);
+        assert_eq!(config.bot.clamp_compute_unit_price(8_000), 8_000);
+
+        config.bot.max_compute_unit_price = Some(6_000);
+        assert_eq!(config.bot.clamp_compute_unit_price(8_000), 6_000);
+        assert!(config.validate_bot_config().is_ok());
+    }
+
+    #[test]
+    fn test_compute_unit_price_floor_above_ceiling_rejected() {
+        let mut config = base_config();
+        config.bot.min_compute_unit_price = Some(10_000);
+        config.bot.max_compute_unit_price = Some(5_000);
+
+        let err = config.validate_bot_config().unwrap_err();
+        assert!(err.to_string().contains("min_compute_unit_price"));
+    }
+
+    #[test]
+    fn test_spam_strategy() {
+        let config = base_config();
+        let mut spam = SpamConfig {
+            enabled: true,
+            sending_rpc_urls: vec!["https://api.mainnet-beta.solana.com".to_string()],
+            compute_unit_price: 1_000,
+            max_retries: None,
+            strategy: None,
+            blockhash_source: None,
+            priority_fee_percentile: None,
+            account_in_use_retries: None,
+        };
+        assert_eq!(spam.strategy(), SendStrategy::Broadcast);
+        assert_eq!(spam.blockhash_source(), BlockhashSource::ReadRpc);
+
+        spam.strategy = Some("fastest".to_string());
+        assert_eq!(spam.strategy(), SendStrategy::Fastest);
+        assert!(config.validate_spam_config(&spam).is_ok());
+
+        spam.strategy = Some("round_robin".to_string());
+        assert!(config.validate_spam_config(&spam).is_err());
+
+        spam.strategy = None;
+        spam.blockhash_source = Some("sender".to_string());
+        assert_eq!(spam.blockhash_source(), BlockhashSource::Sender);
+        assert!(config.validate_spam_config(&spam).is_ok());
+
+        spam.blockhash_source = Some("jito".to_string());
+        assert!(config.validate_spam_config(&spam).is_err());
+
+        spam.blockhash_source = None;
+        spam.priority_fee_percentile = Some(0);
+        assert!(config.validate_spam_config(&spam).is_err());
+    }
+
+    /// Generated valid configs survive a TOML write and `Config::load`, so a new field
+    /// missing from either serde direction shows up here
+    mod toml_roundtrip {
+        use super::*;
+        use proptest::option;
+        use proptest::prelude::*;
+        use proptest::sample::select;
+
+        const RPC_URL_VAR: &str = "CONFIG_ROUNDTRIP_RPC_URL";
+        const RPC_URL: &str = "https://roundtrip-rpc.example.com";
+        const PRIVATE_KEY_VAR: &str = "CONFIG_ROUNDTRIP_PRIVATE_KEY";
+        const PRIVATE_KEY: &str = "roundtrip-private-key";
+
+        fn pubkey() -> impl Strategy<Value = String> {
+            any::<[u8; 32]>().prop_map(|bytes| Pubkey::new_from_array(bytes).to_string())
+        }
+
+        fn pubkeys(max: usize) -> impl Strategy<Value = Option<Vec<String>>> {
+            option::of(prop::collection::vec(pubkey(), 0..=max))
+        }
+
+        fn url() -> impl Strategy<Value = String> {
+            "https://[a-z]{1,12}\\.example\\.com"
+        }
+
+        fn choice(values: &[&'static str]) -> impl Strategy<Value = Option<String>> {
+            option::of(select(values.to_vec()).prop_map(str::to_string))
+        }
+
+        fn bot_config() -> impl Strategy<Value = BotConfig> {
+            (
+                1..=MAX_COMPUTE_UNIT_LIMIT,
+                option::of(any::<bool>()),
+                option::of(any::<bool>()),
+                choice(&["processed", "confirmed", "finalized"]),
+                option::of(1u64..60_000),
+                choice(&["none", "signature", "finalized"]),
+                option::of(1usize..16),
+                choice(&["drop_oldest", "block"]),
+                choice(&["max_profit", "max_roi", "lowest_account_count"]),
+                option::of(2usize..8),
+                option::of(url()),
+            )
+                .prop_map(
+                    |(
+                        compute_unit_limit,
+                        warmup_on_startup,
+                        simulate_before_send,
+                        confirm_commitment,
+                        confirm_timeout_ms,
+                        confirmation_mode,
+                        send_workers,
+                        send_queue_overflow,
+                        ranking,
+                        min_pools_per_mint,
+                        alert_webhook_url,
+                    )| BotConfig {
+                        compute_unit_limit,
+                        warmup_on_startup,
+                        simulate_before_send,
+                        confirm_commitment,
+                        confirm_timeout_ms,
+                        confirmation_mode,
+                        send_workers,
+                        send_queue_overflow,
+                        ranking,
+                        min_pools_per_mint,
+                        alert_webhook_url,
+                        ..base_config().bot
+                    },
+                )
+        }
+
+        fn mint_config() -> impl Strategy<Value = MintConfig> {
+            (
+                pubkey(),
+                prop::collection::vec(pubkeys(3), 12),
+                pubkey(),
+                pubkeys(4),
+                1u64..5_000,
+                option::of(any::<bool>()),
+                option::of((0u64..1_000, 1_000u64..10_000)),
+                option::of(1u64..1_000_000_000_000),
+                option::of(url()),
+                choice(&["jito", "spam", "auto"]),
+            )
+                .prop_map(
+                    |(
+                        mint,
+                        mut pool_lists,
+                        fallback_pool,
+                        lookup_table_accounts,
+                        process_delay,
+                        adaptive_delay,
+                        delay_bounds,
+                        max_input_lamports,
+                        rpc_url,
+                        execution_mode,
+                    )| {
+                        // A mint needs at least one pool list
+                        if pool_lists.iter().all(Option::is_none) {
+                            pool_lists[0] = Some(vec![fallback_pool]);
+                        }
+                        let mut pool_lists = pool_lists.into_iter();
+                        let mut next_list = || pool_lists.next().unwrap();
+                        MintConfig {
+                            mint,
+                            raydium_pool_list: next_list(),
+                            raydium_cp_pool_list: next_list(),
+                            raydium_clmm_pool_list: next_list(),
+                            meteora_dlmm_pool_list: next_list(),
+                            meteora_damm_pool_list: next_list(),
+                            meteora_damm_v2_pool_list: next_list(),
+                            pump_pool_list: next_list(),
+                            whirlpool_pool_list: next_list(),
+                            solfi_pool_list: next_list(),
+                            vertigo_pool_list: next_list(),
+                            lifinity_pool_list: next_list(),
+                            phoenix_pool_list: next_list(),
+                            lookup_table_accounts,
+                            process_delay,
+                            adaptive_delay,
+                            min_process_delay_ms: delay_bounds.map(|(min, _)| min),
+                            max_process_delay_ms: delay_bounds.map(|(_, max)| max),
+                            max_idle_delay_ms: None,
+                            max_input_lamports,
+                            rpc_url,
+                            execution_mode,
+                            jito_min_profit_lamports: None,
+                            min_profit_lamports: None,
+                            compute_unit_limit: None,
+                        }
+                    },
+                )
+        }
+
+        fn spam_config() -> impl Strategy<Value = SpamConfig> {
+            (
+                any::<bool>(),
+                prop::collection::vec(url(), 1..3),
+                0u64..1_000_000,
+                option::of(0u64..10),
+                choice(&["broadcast", "first_success", "fastest"]),
+                choice(&["read_rpc", "sender"]),
+                option::of(1u8..=100),
+                option::of(0u32..5),
+            )
+                .prop_map(
+                    |(
+                        enabled,
+                        sending_rpc_urls,
+                        compute_unit_price,
+                        max_retries,
+                        strategy,
+                        blockhash_source,
+                        priority_fee_percentile,
+                        account_in_use_retries,
+                    )| SpamConfig {
+                        enabled,
+                        sending_rpc_urls,
+                        compute_unit_price,
+                        max_retries,
+                        strategy,
+                        blockhash_source,
+                        priority_fee_percentile,
+                        account_in_use_retries,
+                    },
+                )
+        }
+
+        /// Valid configs with and without the optional `spam` and `flashloan` sections; the
+        /// RPC URL and private key are literals or `$ENV` references
+        fn config() -> impl Strategy<Value = Config> {
+            (
+                option::of(Just(CURRENT_CONFIG_VERSION)),
+                option::of(pubkey()),
+                bot_config(),
+                prop::collection::vec(mint_config(), 1..4),
+                prop_oneof![url(), Just(format!("${}", RPC_URL_VAR))],
+                option::of(Just("wss://api.mainnet-beta.solana.com".to_string())),
+                option::of(MIN_RPC_TIMEOUT_MS..=MAX_RPC_TIMEOUT_MS),
+                prop_oneof!["[a-zA-Z0-9]{1,44}", Just(format!("${}", PRIVATE_KEY_VAR))],
+                option::of(spam_config()),
+                option::of(any::<bool>().prop_map(|enabled| FlashloanConfig { enabled })),
+            )
+                .prop_map(
+                    |(
+                        version,
+                        base_mint,
+                        bot,
+                        mint_config_list,
+                        url,
+                        ws_url,
+                        rpc_timeout_ms,
+                        private_key,
+                        spam,
+                        flashloan,
+                    )| Config {
+                        version,
+                        base_mint,
+                        bot,
+                        routing: RoutingConfig { mint_config_list },
+                        rpc: RpcConfig {
+                            url,
+                            rpc_timeout_ms,
+                            send_timeout_ms: None,
+                            ws_url,
+                        },
+                        spam,
+                        wallet: WalletConfig {
+                            private_key,
+                            remote_signer_url: None,
+                        },
+                        flashloan,
+                        ..base_config()
+                    },
+                )
+        }
+
+        /// What `serde_string_or_env` loads `value` as
+        fn resolved(value: &str) -> String {
+            match value.strip_prefix('$') {
+                Some(var) => env::var(var).unwrap(),
+                None => value.to_string(),
+            }
+        }
+
+        proptest! {
+            #[test]
+            fn test_config_roundtrips_through_toml(config in config()) {
+                env::set_var(RPC_URL_VAR, RPC_URL);
+                env::set_var(PRIVATE_KEY_VAR, PRIVATE_KEY);
+                let path = env::temp_dir().join(format!("config-roundtrip-{}.toml", std::process::id()));
+                std::fs::write(&path, toml::to_string(&config).unwrap()).unwrap();
+                let loaded = Config::load(path.to_str().unwrap());
+                std::fs::remove_file(&path).unwrap();
+                let mut loaded = loaded.unwrap();
+
+                // Env references come back resolved, everything else as written
+                prop_assert_eq!(&loaded.rpc.url, &resolved(&config.rpc.url));
+                prop_assert_eq!(&loaded.wallet.private_key, &resolved(&config.wallet.private_key));
+                loaded.rpc.url = config.rpc.url.clone();
+                loaded.wallet.private_key = config.wallet.private_key.clone();
+                prop_assert_eq!(loaded, config);
+            }
+        }
+    }
 }
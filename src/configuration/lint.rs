@@ -0,0 +1,208 @@
+/// Config Linter
+///
+/// Heuristic checks for configs that pass validation but are likely mistakes.
+
+use crate::config::{Config, MintConfig, TransactionVersion};
+use crate::constants::DEFAULT_COMPUTE_UNIT_LIMIT;
+use std::fmt;
+
+// A compute unit limit above this fraction of the maximum leaves no headroom
+const COMPUTE_UNIT_HEADROOM_FRACTION: f64 = 0.9;
+// Pool count above which a near-max compute unit limit is likely to be exhausted
+const MANY_POOLS_THRESHOLD: usize = 10;
+
+/// What a lint warning puts at risk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintCategory {
+    Performance,
+    Reliability,
+    Safety,
+}
+
+impl fmt::Display for LintCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LintCategory::Performance => "performance",
+            LintCategory::Reliability => "reliability",
+            LintCategory::Safety => "safety",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A suspicious but valid config setting
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintWarning {
+    pub category: LintCategory,
+    pub message: String,
+}
+
+impl LintWarning {
+    fn new(category: LintCategory, message: String) -> Self {
+        Self { category, message }
+    }
+}
+
+/// Run every heuristic check against an already validated config
+pub fn lint_config(config: &Config) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+
+    if let Some(spam) = config.spam.as_ref().filter(|spam| spam.enabled) {
+        if spam.sending_rpc_urls.len() == 1 {
+            warnings.push(LintWarning::new(
+                LintCategory::Reliability,
+                "spam is enabled with a single sending RPC URL, so it adds no redundancy".to_string(),
+            ));
+        }
+    }
+
+    let near_max_compute = config.bot.compute_unit_limit as f64
+        >= DEFAULT_COMPUTE_UNIT_LIMIT as f64 * COMPUTE_UNIT_HEADROOM_FRACTION;
+    let uses_lookup_tables = config.bot.transaction_version() == TransactionVersion::V0;
+
+    let flashloan_enabled = config.flashloan.as_ref().map_or(false, |f| f.enabled);
+    for mint_config in &config.routing.mint_config_list {
+        let mint = &mint_config.mint;
+
+        if flashloan_enabled && mint_config.min_profit_lamports() == 0 {
+            warnings.push(LintWarning::new(
+                LintCategory::Safety,
+                format!(
+                    "mint {}: flashloan is enabled with a minimum profit of 0 lamports; break-even trades still pay fees",
+                    mint
+                ),
+            ));
+        }
+
+        if mint_config.process_delay == 0 && !mint_config.adaptive_delay.unwrap_or(false) {
+            warnings.push(LintWarning::new(
+                LintCategory::Performance,
+                format!("mint {}: process_delay is 0, the loop will spin and hammer the RPC", mint),
+            ));
+        }
+
+        let pools = pool_count(mint_config);
+        if near_max_compute && pools > MANY_POOLS_THRESHOLD {
+            warnings.push(LintWarning::new(
+                LintCategory::Reliability,
+                format!(
+                    "mint {}: compute_unit_limit {} is near the maximum with {} pools; swaps may run out of compute",
+                    mint, config.bot.compute_unit_limit, pools
+                ),
+            ));
+        }
+
+        let has_lookup_tables = mint_config
+            .lookup_table_accounts
+            .as_ref()
+            .map_or(false, |tables| !tables.is_empty());
+        if uses_lookup_tables && !has_lookup_tables {
+            warnings.push(LintWarning::new(
+                LintCategory::Performance,
+                format!(
+                    "mint {}: no lookup tables configured; only the default table is used and pools may be dropped to fit the transaction",
+                    mint
+                ),
+            ));
+        }
+    }
+
+    warnings
+}
+
+fn pool_count(mint_config: &MintConfig) -> usize {
+    mint_config.pool_addresses().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POOL: &str = "Gf7sXMoP8iRw4iiXmJ1nq4vxcRycbGXy5RL8a8LnTd3v";
+    const LOOKUP_TABLE: &str = "8HvgxVyd22Jq9mmoojm4Awqw6sbymbF5pwLr8FtvySHs";
+
+    fn config(mint_extra: &str) -> Config {
+        let toml = format!(
+            r#"
+            [bot]
+            compute_unit_limit = 600000
+
+            [routing]
+            [[routing.mint_config_list]]
+            mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+            process_delay = 400
+            {mint_extra}
+
+            [rpc]
+            url = "https://api.mainnet-beta.solana.com"
+
+            [wallet]
+            private_key = "test"
+            "#
+        );
+        toml::from_str(&toml).unwrap()
+    }
+
+    fn categories(config: &Config) -> Vec<LintCategory> {
+        lint_config(config).into_iter().map(|w| w.category).collect()
+    }
+
+    #[test]
+    fn test_clean_config_has_no_warnings() {
+        let config = config(&format!(r#"lookup_table_accounts = ["{}"]"#, LOOKUP_TABLE));
+        assert!(lint_config(&config).is_empty());
+    }
+
+    #[test]
+    fn test_missing_lookup_tables_warned() {
+        let config = config("");
+        assert_eq!(categories(&config), vec![LintCategory::Performance]);
+    }
+
+    #[test]
+    fn test_zero_process_delay_warned() {
+        let mut config = config(&format!(r#"lookup_table_accounts = ["{}"]"#, LOOKUP_TABLE));
+        config.routing.mint_config_list[0].process_delay = 0;
+
+        let warnings = lint_config(&config);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("process_delay is 0"));
+    }
+
+    #[test]
+    fn test_near_max_compute_with_many_pools_warned() {
+        let pools = vec![format!("\"{}\"", POOL); MANY_POOLS_THRESHOLD + 1].join(", ");
+        let mut config = config(
+            &format!(
+                "lookup_table_accounts = [\"{}\"]\npump_pool_list = [{}]",
+                LOOKUP_TABLE, pools
+            ),
+        );
+        assert!(lint_config(&config).is_empty());
+
+        config.bot.compute_unit_limit = 1_350_000;
+        assert_eq!(categories(&config), vec![LintCategory::Reliability]);
+    }
+
+    #[test]
+    fn test_single_spam_url_and_zero_profit_flashloan_warned() {
+        let base = config(&format!(r#"lookup_table_accounts = ["{}"]"#, LOOKUP_TABLE));
+        let config = Config {
+            spam: Some(toml::from_str(
+                "enabled = true\nsending_rpc_urls = [\"https://a.example\"]\ncompute_unit_price = 1000",
+            )
+            .unwrap()),
+            flashloan: Some(toml::from_str("enabled = true").unwrap()),
+            ..base
+        };
+
+        assert_eq!(
+            categories(&config),
+            vec![LintCategory::Reliability, LintCategory::Safety]
+        );
+
+        let mut config = config;
+        config.routing.mint_config_list[0].min_profit_lamports = Some(10_000);
+        assert_eq!(categories(&config), vec![LintCategory::Reliability]);
+    }
+}
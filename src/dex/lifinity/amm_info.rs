@@ -0,0 +1,70 @@
+use crate::dex::traits::fee_rate_to_bps;
+use anyhow::Result;
+use solana_program::pubkey::Pubkey;
+
+// Offsets into the Anchor `Amm` account, past its 8-byte discriminator
+const TOKEN_A_ACCOUNT_OFFSET: usize = 158;
+const TOKEN_B_ACCOUNT_OFFSET: usize = 190;
+const POOL_MINT_OFFSET: usize = 222;
+const TOKEN_A_MINT_OFFSET: usize = 254;
+const TOKEN_B_MINT_OFFSET: usize = 286;
+const FEE_ACCOUNT_OFFSET: usize = 318;
+const ORACLE_MAIN_OFFSET: usize = 350;
+const ORACLE_SUB_OFFSET: usize = 382;
+const ORACLE_PC_OFFSET: usize = 414;
+const TRADE_FEE_NUMERATOR_OFFSET: usize = 446;
+const TRADE_FEE_DENOMINATOR_OFFSET: usize = 454;
+
+#[derive(Debug)]
+pub struct LifinityAmmInfo {
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    /// LP mint, written by every swap
+    pub pool_mint: Pubkey,
+    pub fee_account: Pubkey,
+    /// Price oracles the AMM concentrates its liquidity around
+    pub oracle_main: Pubkey,
+    pub oracle_sub: Pubkey,
+    pub oracle_pc: Pubkey,
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+}
+
+impl LifinityAmmInfo {
+    /// Bytes needed to read every field below
+    pub const MIN_DATA_LEN: usize = TRADE_FEE_DENOMINATOR_OFFSET + 8;
+
+    pub fn load_checked(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::MIN_DATA_LEN {
+            return Err(anyhow::anyhow!(
+                "Invalid data length for LifinityAmmInfo: {} bytes, expected at least {}",
+                data.len(),
+                Self::MIN_DATA_LEN
+            ));
+        }
+
+        let pubkey = |offset: usize| Pubkey::new(&data[offset..offset + 32]);
+        let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+        Ok(Self {
+            token_a_mint: pubkey(TOKEN_A_MINT_OFFSET),
+            token_b_mint: pubkey(TOKEN_B_MINT_OFFSET),
+            token_a_vault: pubkey(TOKEN_A_ACCOUNT_OFFSET),
+            token_b_vault: pubkey(TOKEN_B_ACCOUNT_OFFSET),
+            pool_mint: pubkey(POOL_MINT_OFFSET),
+            fee_account: pubkey(FEE_ACCOUNT_OFFSET),
+            oracle_main: pubkey(ORACLE_MAIN_OFFSET),
+            oracle_sub: pubkey(ORACLE_SUB_OFFSET),
+            oracle_pc: pubkey(ORACLE_PC_OFFSET),
+            trade_fee_numerator: u64_at(TRADE_FEE_NUMERATOR_OFFSET),
+            trade_fee_denominator: u64_at(TRADE_FEE_DENOMINATOR_OFFSET),
+        })
+    }
+
+    /// Trade fee in basis points, rounded up
+    pub fn fee_bps(&self) -> u16 {
+        fee_rate_to_bps(self.trade_fee_numerator, self.trade_fee_denominator)
+    }
+}
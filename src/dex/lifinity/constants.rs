@@ -0,0 +1,11 @@
+use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub fn lifinity_program_id() -> Pubkey {
+    Pubkey::from_str("2wT8Yq49kHgDzXuPxZSaeLaH1qbmGXtEyPy64bL7aD3c").unwrap()
+}
+
+/// Authority PDA owning an AMM's vaults, seeded by the AMM address
+pub fn lifinity_authority(amm: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[amm.as_ref()], &lifinity_program_id()).0
+}
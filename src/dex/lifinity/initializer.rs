@@ -0,0 +1,296 @@
+/// Lifinity Pool Initializer
+///
+/// Implementation for Lifinity v2 AMMs, which quote around an oracle price rather than
+/// the pool's own reserves ratio.
+
+use crate::constants::sol_mint;
+use crate::dex::lifinity::{lifinity_authority, lifinity_program_id, LifinityAmmInfo};
+use crate::dex::traits::{fetch_pool_accounts, DexPool, OracleBasedPool, PoolInitializer, PoolValidator};
+use crate::error::{BotError, BotResult};
+use async_trait::async_trait;
+use solana_client::rpc_client::RpcClient;
+use solana_program::instruction::AccountMeta;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Lifinity Pool structure
+#[derive(Debug, Clone)]
+pub struct LifinityPool {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub token_vault: Pubkey,
+    pub sol_vault: Pubkey,
+    pub pool_mint: Pubkey,
+    pub fee_account: Pubkey,
+    pub oracle_main: Pubkey,
+    pub oracle_sub: Pubkey,
+    pub oracle_pc: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    /// Trade fee from the AMM account
+    pub fee_bps: u16,
+}
+
+#[async_trait]
+impl DexPool for LifinityPool {
+    async fn initialize(&mut self, _rpc_client: &RpcClient, _pool_address: &Pubkey) -> BotResult<()> {
+        Ok(())
+    }
+
+    fn get_swap_accounts(&self, _wallet: &Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(lifinity_program_id(), false),
+            AccountMeta::new_readonly(self.authority, false),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new(self.token_vault, false),
+            AccountMeta::new(self.sol_vault, false),
+            AccountMeta::new(self.pool_mint, false),
+            AccountMeta::new(self.fee_account, false),
+            AccountMeta::new_readonly(self.oracle_main, false),
+            AccountMeta::new_readonly(self.oracle_sub, false),
+            AccountMeta::new_readonly(self.oracle_pc, false),
+        ]
+    }
+
+    fn get_liquidity(&self) -> (u64, u64) {
+        (0, 0)
+    }
+
+    fn dex_name(&self) -> &'static str {
+        "Lifinity"
+    }
+
+    fn pool_address(&self) -> Pubkey {
+        self.pool
+    }
+
+    fn contains_mint(&self, mint: &Pubkey) -> bool {
+        &self.token_a_mint == mint || &self.token_b_mint == mint
+    }
+
+    fn fee_bps(&self) -> u16 {
+        self.fee_bps
+    }
+}
+
+impl OracleBasedPool for LifinityPool {
+    fn oracle_account(&self) -> Pubkey {
+        self.oracle_main
+    }
+}
+
+/// Lifinity Pool Initializer
+pub struct LifinityInitializer;
+
+impl LifinityInitializer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl PoolInitializer for LifinityInitializer {
+    type Pool = LifinityPool;
+
+    async fn initialize_pools(
+        &self,
+        addresses: &[String],
+        rpc_client: Arc<RpcClient>,
+        mint: &Pubkey,
+    ) -> BotResult<Vec<Self::Pool>> {
+        let pool_pubkeys = self.validate_addresses(addresses)?;
+        let accounts = fetch_pool_accounts(rpc_client.as_ref(), self.dex_name(), &pool_pubkeys)?;
+        let mut pools = Vec::with_capacity(accounts.len());
+
+        for (pool_address, account) in accounts {
+            match Self::pool_from_account(&pool_address, &account, mint) {
+                Ok(pool) => {
+                    info!("✓ Initialized Lifinity pool: {}", pool_address);
+                    pools.push(pool);
+                }
+                Err(e) => {
+                    error!("✗ Failed to initialize Lifinity pool {}: {}", pool_address, e);
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(pools)
+    }
+
+    fn dex_name(&self) -> &'static str {
+        "Lifinity"
+    }
+}
+
+impl LifinityInitializer {
+    /// Validate and decode a fetched AMM account
+    fn pool_from_account(
+        pool_address: &Pubkey,
+        account: &Account,
+        expected_mint: &Pubkey,
+    ) -> BotResult<LifinityPool> {
+        PoolValidator::validate_owner(pool_address, &account.owner, &lifinity_program_id())?;
+
+        PoolValidator::validate_data_len(
+            pool_address,
+            "LifinityAmmInfo",
+            &account.data,
+            LifinityAmmInfo::MIN_DATA_LEN,
+        )?;
+        let amm_info = LifinityAmmInfo::load_checked(&account.data).map_err(|e| {
+            BotError::DeserializationError {
+                data_type: "LifinityAmmInfo".to_string(),
+                source: Box::new(e),
+            }
+        })?;
+
+        let sol_mint_pubkey = sol_mint();
+        PoolValidator::validate_mint_pair(
+            pool_address,
+            &amm_info.token_a_mint,
+            &amm_info.token_b_mint,
+            expected_mint,
+            &sol_mint_pubkey,
+        )?;
+
+        let (token_vault, sol_vault) = PoolValidator::order_vaults(
+            &amm_info.token_a_mint,
+            &amm_info.token_b_mint,
+            amm_info.token_a_vault,
+            amm_info.token_b_vault,
+            &sol_mint_pubkey,
+        );
+
+        Ok(LifinityPool {
+            pool: *pool_address,
+            authority: lifinity_authority(pool_address),
+            token_vault,
+            sol_vault,
+            pool_mint: amm_info.pool_mint,
+            fee_account: amm_info.fee_account,
+            oracle_main: amm_info.oracle_main,
+            oracle_sub: amm_info.oracle_sub,
+            oracle_pc: amm_info.oracle_pc,
+            token_a_mint: amm_info.token_a_mint,
+            token_b_mint: amm_info.token_b_mint,
+            fee_bps: amm_info.fee_bps(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A SOL/mint AMM as laid out on-chain: discriminator, initializer fields, then the
+    // accounts and fees the bot reads
+    struct AmmFixture {
+        data: Vec<u8>,
+        token_a_vault: Pubkey,
+        token_b_vault: Pubkey,
+        pool_mint: Pubkey,
+        fee_account: Pubkey,
+        oracles: [Pubkey; 3],
+    }
+
+    fn amm_fixture(token_a_mint: &Pubkey, token_b_mint: &Pubkey) -> AmmFixture {
+        let (token_a_vault, token_b_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (pool_mint, fee_account) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let oracles = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let mut data = Vec::with_capacity(911);
+        data.extend_from_slice(&[143, 245, 200, 17, 74, 214, 196, 135]); // sha256("account:Amm")[..8]
+        for key in [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()] {
+            data.extend_from_slice(key.as_ref()); // initializer key and token accounts
+        }
+        data.extend_from_slice(&1_000u64.to_le_bytes()); // initializer amount
+        data.extend_from_slice(&2_000u64.to_le_bytes()); // taker amount
+        data.extend_from_slice(&[1, 255, 0, 0, 0, 9]); // initialized, bump, freezes, base decimals
+        data.extend_from_slice(spl_token::ID.as_ref());
+        for key in [
+            token_a_vault,
+            token_b_vault,
+            pool_mint,
+            *token_a_mint,
+            *token_b_mint,
+            fee_account,
+        ] {
+            data.extend_from_slice(key.as_ref());
+        }
+        for oracle in &oracles {
+            data.extend_from_slice(oracle.as_ref());
+        }
+        // 0.2% trade fee, then the owner, withdraw and host fee fractions
+        for value in [2u64, 1_000, 1, 10_000, 0, 10_000, 0, 10_000] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.resize(911, 0); // curve and config
+        AmmFixture {
+            data,
+            token_a_vault,
+            token_b_vault,
+            pool_mint,
+            fee_account,
+            oracles,
+        }
+    }
+
+    fn amm_account(data: Vec<u8>) -> Account {
+        Account {
+            lamports: 1,
+            data,
+            owner: lifinity_program_id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_pool_from_amm_fixture() {
+        let (pool_address, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // SOL is token a, so its vault comes second once ordered
+        let fixture = amm_fixture(&sol_mint(), &mint);
+        let pool = LifinityInitializer::pool_from_account(
+            &pool_address,
+            &amm_account(fixture.data.clone()),
+            &mint,
+        )
+        .unwrap();
+
+        assert_eq!(pool.token_vault, fixture.token_b_vault);
+        assert_eq!(pool.sol_vault, fixture.token_a_vault);
+        assert_eq!(pool.pool_mint, fixture.pool_mint);
+        assert_eq!(pool.fee_account, fixture.fee_account);
+        assert_eq!([pool.oracle_main, pool.oracle_sub, pool.oracle_pc], fixture.oracles);
+        assert_eq!(pool.oracle_account(), fixture.oracles[0]);
+        assert_eq!(pool.authority, lifinity_authority(&pool_address));
+        assert_eq!(pool.fee_bps(), 20);
+        assert!(pool.contains_mint(&mint) && pool.contains_mint(&sol_mint()));
+
+        let accounts = pool.get_swap_accounts(&Pubkey::new_unique());
+        assert_eq!(accounts.len(), 10);
+        assert_eq!(accounts[0].pubkey, lifinity_program_id());
+        assert!(accounts[2].is_writable && accounts[2].pubkey == pool_address);
+        assert!(accounts[7..].iter().all(|meta| !meta.is_writable));
+
+        // Another mint's AMM, a foreign owner and a truncated account are all rejected
+        let other = amm_fixture(&sol_mint(), &Pubkey::new_unique());
+        assert!(matches!(
+            LifinityInitializer::pool_from_account(&pool_address, &amm_account(other.data), &mint),
+            Err(BotError::PoolValidationError(_))
+        ));
+        let mut foreign = amm_account(fixture.data.clone());
+        foreign.owner = Pubkey::new_unique();
+        assert!(matches!(
+            LifinityInitializer::pool_from_account(&pool_address, &foreign, &mint),
+            Err(BotError::InvalidAccountOwner { .. })
+        ));
+        assert!(matches!(
+            LifinityInitializer::pool_from_account(&pool_address, &amm_account(vec![0; 300]), &mint),
+            Err(BotError::DeserializationError { .. })
+        ));
+    }
+}
@@ -0,0 +1,7 @@
+pub mod amm_info;
+pub mod constants;
+pub mod initializer;
+
+pub use amm_info::LifinityAmmInfo;
+pub use constants::*;
+pub use initializer::{LifinityInitializer, LifinityPool};
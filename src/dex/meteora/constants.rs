@@ -30,3 +30,14 @@ pub fn damm_v2_pool_authority() -> Pubkey {
 }
 
 pub const BIN_ARRAY: &[u8] = b"bin_array";
+
+// DLMM fee rates are expressed with 9 decimals of precision
+pub const DLMM_FEE_PRECISION: u64 = 1_000_000_000;
+pub const DLMM_MAX_FEE_RATE: u64 = 100_000_000; // 10%
+
+// DAMM swap fees in basis points, used when the pool account has no usable fee
+pub const DAMM_DEFAULT_FEE_BPS: u16 = 25;
+pub const DAMM_V2_DEFAULT_FEE_BPS: u16 = 25;
+// DAMM V2 fee numerators are expressed per billion
+pub const DAMM_V2_FEE_DENOMINATOR: u64 = 1_000_000_000;
+pub const DAMM_V2_MAX_FEE_NUMERATOR: u64 = 500_000_000; // 50%
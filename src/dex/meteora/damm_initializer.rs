@@ -1,15 +1,43 @@
 /// Meteora DAMM Pool Initializers (V1 and V2)
 
 use crate::constants::sol_mint;
+use crate::dex::meteora::constants::{
+    damm_v2_event_authority, damm_v2_pool_authority, vault_program_id, DAMM_DEFAULT_FEE_BPS,
+};
+use crate::dex::meteora::dammv2_info::{DammV2FeeScheduler, MeteoraDAmmV2Info};
 use crate::dex::meteora::{meteora_damm_program_id, meteora_damm_v2_program_id};
-use crate::dex::traits::{DexPool, PoolInitializer, PoolValidator};
+use crate::dex::traits::{
+    fee_rate_to_bps, fetch_pool_accounts, DexPool, PoolInitializer, PoolValidator,
+};
 use crate::error::{BotError, BotResult};
 use async_trait::async_trait;
 use solana_client::rpc_client::RpcClient;
 use solana_program::instruction::AccountMeta;
+use solana_sdk::account::Account;
+use solana_sdk::clock::Clock;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::sysvar;
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+// DAMM V1 `Pool.fees.trade_fee_numerator` / `trade_fee_denominator`
+const DAMM_TRADE_FEE_NUMERATOR_OFFSET: usize = 330;
+const DAMM_TRADE_FEE_DENOMINATOR_OFFSET: usize = 338;
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Trade fee stored in a DAMM V1 pool account
+fn damm_fee_bps(data: &[u8]) -> Option<u16> {
+    let numerator = read_u64(data, DAMM_TRADE_FEE_NUMERATOR_OFFSET)?;
+    let denominator = read_u64(data, DAMM_TRADE_FEE_DENOMINATOR_OFFSET)?;
+    if denominator == 0 {
+        return None;
+    }
+    Some(fee_rate_to_bps(numerator, denominator))
+}
 
 // Meteora DAMM V1
 #[derive(Debug, Clone)]
@@ -17,6 +45,7 @@ pub struct MeteoraDammPool {
     pub pool: Pubkey,
     pub token_vault: Pubkey,
     pub sol_vault: Pubkey,
+    pub fee_bps: u16,
 }
 
 #[async_trait]
@@ -28,6 +57,7 @@ impl DexPool for MeteoraDammPool {
     fn get_swap_accounts(&self, _wallet: &Pubkey) -> Vec<AccountMeta> {
         vec![
             AccountMeta::new_readonly(meteora_damm_program_id(), false),
+            AccountMeta::new_readonly(vault_program_id(), false),
             AccountMeta::new(self.pool, false),
             AccountMeta::new(self.token_vault, false),
             AccountMeta::new(self.sol_vault, false),
@@ -49,6 +79,10 @@ impl DexPool for MeteoraDammPool {
     fn contains_mint(&self, _mint: &Pubkey) -> bool {
         true
     }
+
+    fn fee_bps(&self) -> u16 {
+        self.fee_bps
+    }
 }
 
 pub struct MeteoraDammInitializer;
@@ -70,10 +104,11 @@ impl PoolInitializer for MeteoraDammInitializer {
         mint: &Pubkey,
     ) -> BotResult<Vec<Self::Pool>> {
         let pool_pubkeys = self.validate_addresses(addresses)?;
+        let accounts = fetch_pool_accounts(rpc_client.as_ref(), self.dex_name(), &pool_pubkeys)?;
         let mut pools = Vec::new();
 
-        for pool_address in pool_pub keys {
-            match self.initialize_single_pool(&rpc_client, &pool_address, mint).await {
+        for (pool_address, account) in accounts {
+            match self.initialize_single_pool(&pool_address, &account, mint) {
                 Ok(pool) => {
                     info!("✓ Initialized Meteora DAMM pool: {}", pool_address);
                     pools.push(pool);
@@ -93,25 +128,27 @@ impl PoolInitializer for MeteoraDammInitializer {
 }
 
 impl MeteoraDammInitializer {
-    async fn initialize_single_pool(
+    fn initialize_single_pool(
         &self,
-        rpc_client: &RpcClient,
         pool_address: &Pubkey,
+        account: &Account,
         _expected_mint: &Pubkey,
     ) -> BotResult<MeteoraDammPool> {
-        let account = rpc_client.get_account(pool_address).map_err(|e| {
-            BotError::AccountFetchError {
-                address: *pool_address,
-                reason: format!("Failed to fetch Meteora DAMM pool: {}", e),
-            }
-        })?;
-
         PoolValidator::validate_owner(pool_address, &account.owner, &meteora_damm_program_id())?;
 
+        let fee_bps = damm_fee_bps(&account.data).unwrap_or_else(|| {
+            warn!(
+                "Meteora DAMM pool {} has no readable trade fee, assuming {} bps",
+                pool_address, DAMM_DEFAULT_FEE_BPS
+            );
+            DAMM_DEFAULT_FEE_BPS
+        });
+
         Ok(MeteoraDammPool {
             pool: *pool_address,
             token_vault: Pubkey::new_unique(),
             sol_vault: Pubkey::new_unique(),
+            fee_bps,
         })
     }
 }
@@ -122,6 +159,10 @@ pub struct MeteoraDammV2Pool {
     pub pool: Pubkey,
     pub token_vault: Pubkey,
     pub sol_vault: Pubkey,
+    /// Base fee schedule read at startup; the fee it charges moves with the clock
+    pub fee_scheduler: DammV2FeeScheduler,
+    /// Clock of the latest iteration, None until the first read
+    pub clock: Option<Clock>,
 }
 
 #[async_trait]
@@ -133,6 +174,8 @@ impl DexPool for MeteoraDammV2Pool {
     fn get_swap_accounts(&self, _wallet: &Pubkey) -> Vec<AccountMeta> {
         vec![
             AccountMeta::new_readonly(meteora_damm_v2_program_id(), false),
+            AccountMeta::new_readonly(damm_v2_event_authority(), false),
+            AccountMeta::new_readonly(damm_v2_pool_authority(), false),
             AccountMeta::new(self.pool, false),
             AccountMeta::new(self.token_vault, false),
             AccountMeta::new(self.sol_vault, false),
@@ -154,6 +197,34 @@ impl DexPool for MeteoraDammV2Pool {
     fn contains_mint(&self, _mint: &Pubkey) -> bool {
         true
     }
+
+    /// The scheduled fee at the last clock read, or the cliff fee (the schedule's
+    /// highest) before one
+    fn fee_bps(&self) -> u16 {
+        match &self.clock {
+            Some(clock) => self
+                .fee_scheduler
+                .current_fee_bps(clock.slot, clock.unix_timestamp.max(0) as u64),
+            None => self.fee_scheduler.fee_bps(self.fee_scheduler.activation_point),
+        }
+    }
+
+    fn state_accounts(&self) -> Vec<Pubkey> {
+        vec![sysvar::clock::id()]
+    }
+
+    fn update_state(&mut self, accounts: &[Option<Account>]) -> BotResult<()> {
+        if let Some(Some(clock_account)) = accounts.first() {
+            let clock: Clock = bincode::deserialize(&clock_account.data).map_err(|e| {
+                BotError::DeserializationError {
+                    data_type: "Clock".to_string(),
+                    source: e.into(),
+                }
+            })?;
+            self.clock = Some(clock);
+        }
+        Ok(())
+    }
 }
 
 pub struct MeteoraDammV2Initializer;
@@ -175,10 +246,11 @@ impl PoolInitializer for MeteoraDammV2Initializer {
         mint: &Pubkey,
     ) -> BotResult<Vec<Self::Pool>> {
         let pool_pubkeys = self.validate_addresses(addresses)?;
+        let accounts = fetch_pool_accounts(rpc_client.as_ref(), self.dex_name(), &pool_pubkeys)?;
         let mut pools = Vec::new();
 
-        for pool_address in pool_pubkeys {
-            match self.initialize_single_pool(&rpc_client, &pool_address, mint).await {
+        for (pool_address, account) in accounts {
+            match self.initialize_single_pool(&pool_address, &account, mint) {
                 Ok(pool) => {
                     info!("✓ Initialized Meteora DAMM V2 pool: {}", pool_address);
                     pools.push(pool);
@@ -198,25 +270,94 @@ impl PoolInitializer for MeteoraDammV2Initializer {
 }
 
 impl MeteoraDammV2Initializer {
-    async fn initialize_single_pool(
+    fn initialize_single_pool(
         &self,
-        rpc_client: &RpcClient,
         pool_address: &Pubkey,
+        account: &Account,
         _expected_mint: &Pubkey,
     ) -> BotResult<MeteoraDammV2Pool> {
-        let account = rpc_client.get_account(pool_address).map_err(|e| {
-            BotError::AccountFetchError {
-                address: *pool_address,
-                reason: format!("Failed to fetch Meteora DAMM V2 pool: {}", e),
+        PoolValidator::validate_owner(pool_address, &account.owner, &meteora_damm_v2_program_id())?;
+
+        let info = MeteoraDAmmV2Info::load_checked(&account.data).map_err(|e| {
+            BotError::DeserializationError {
+                data_type: "MeteoraDAmmV2Info".to_string(),
+                source: e.into(),
             }
         })?;
-
-        PoolValidator::validate_owner(pool_address, &account.owner, &meteora_damm_v2_program_id())?;
+        let (token_vault, sol_vault) = PoolValidator::order_vaults(
+            &info.base_mint,
+            &info.quote_mint,
+            info.base_vault,
+            info.quote_vault,
+            &sol_mint(),
+        );
 
         Ok(MeteoraDammV2Pool {
             pool: *pool_address,
-            token_vault: Pubkey::new_unique(),
-            sol_vault: Pubkey::new_unique(),
+            token_vault,
+            sol_vault,
+            fee_scheduler: info.fee_scheduler,
+            clock: None,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_damm_fee_read_from_pool() {
+        let mut data = vec![0u8; 944];
+        data[DAMM_TRADE_FEE_NUMERATOR_OFFSET..DAMM_TRADE_FEE_NUMERATOR_OFFSET + 8]
+            .copy_from_slice(&1u64.to_le_bytes());
+        data[DAMM_TRADE_FEE_DENOMINATOR_OFFSET..DAMM_TRADE_FEE_DENOMINATOR_OFFSET + 8]
+            .copy_from_slice(&100u64.to_le_bytes());
+        assert_eq!(damm_fee_bps(&data), Some(100));
+
+        // Uninitialized fee and truncated data fall back to the default
+        assert_eq!(damm_fee_bps(&vec![0u8; 944]), None);
+        assert_eq!(damm_fee_bps(&data[..300]), None);
+    }
+
+    #[test]
+    fn test_damm_v2_fee_follows_the_clock() {
+        // 50% at the cliff, 0.49% off every 10 slots for 100 periods from slot 1_000
+        let mut data = vec![0u8; 1112];
+        data[8..16].copy_from_slice(&500_000_000u64.to_le_bytes());
+        data[22..24].copy_from_slice(&100u16.to_le_bytes());
+        data[24..32].copy_from_slice(&10u64.to_le_bytes());
+        data[32..40].copy_from_slice(&4_900_000u64.to_le_bytes());
+        data[472..480].copy_from_slice(&1_000u64.to_le_bytes());
+        let account = Account {
+            lamports: 1_000_000,
+            data,
+            owner: meteora_damm_v2_program_id(),
+            executable: false,
+            rent_epoch: 0,
+        };
+        let mut pool = MeteoraDammV2Initializer::new()
+            .initialize_single_pool(&Pubkey::new_unique(), &account, &Pubkey::new_unique())
+            .unwrap();
+        // The cliff fee until the clock is read
+        assert_eq!(pool.fee_bps(), 5_000);
+        assert_eq!(pool.state_accounts(), vec![sysvar::clock::id()]);
+
+        let clock_account = |slot: u64| Account {
+            lamports: 1_000_000,
+            data: bincode::serialize(&Clock {
+                slot,
+                unix_timestamp: 1_700_000_000,
+                ..Clock::default()
+            })
+            .unwrap(),
+            owner: sysvar::id(),
+            executable: false,
+            rent_epoch: 0,
+        };
+        pool.update_state(&[Some(clock_account(1_010))]).unwrap();
+        assert_eq!(pool.fee_bps(), 4_951);
+        pool.update_state(&[Some(clock_account(1_500))]).unwrap();
+        assert_eq!(pool.fee_bps(), 2_550);
+    }
+}
@@ -1,15 +1,157 @@
+use crate::constants::BASIS_POINTS_DENOMINATOR;
+use crate::dex::meteora::constants::{DAMM_V2_FEE_DENOMINATOR, DAMM_V2_MAX_FEE_NUMERATOR};
+use crate::dex::traits::fee_rate_to_bps;
 use anyhow::Result;
 use solana_sdk::pubkey::Pubkey;
 
+// Offsets into the base fee that opens the pool's `PoolFeesStruct`, past the discriminator
+const CLIFF_FEE_NUMERATOR_OFFSET: usize = 8;
+const FEE_SCHEDULER_MODE_OFFSET: usize = 16;
+const NUMBER_OF_PERIOD_OFFSET: usize = 22;
+const PERIOD_FREQUENCY_OFFSET: usize = 24;
+const REDUCTION_FACTOR_OFFSET: usize = 32;
+const ACTIVATION_POINT_OFFSET: usize = 472;
+const ACTIVATION_TYPE_OFFSET: usize = 480;
+
+/// How the base fee decays from the cliff fee as periods pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeSchedulerMode {
+    /// `reduction_factor` fee numerator off per period
+    Linear,
+    /// `reduction_factor` basis points of the remaining fee off per period
+    Exponential,
+}
+
+/// Whether the activation point and period frequency count slots or seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivationType {
+    Slot,
+    Timestamp,
+}
+
+/// The base fee schedule of a DAMM V2 pool, kept with the pool so the current fee can be
+/// recomputed every iteration without re-reading the account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DammV2FeeScheduler {
+    pub cliff_fee_numerator: u64,
+    pub mode: FeeSchedulerMode,
+    pub number_of_period: u16,
+    pub period_frequency: u64,
+    pub reduction_factor: u64,
+    pub activation_point: u64,
+    pub activation_type: ActivationType,
+}
+
+impl DammV2FeeScheduler {
+    /// Base fee numerator (per `DAMM_V2_FEE_DENOMINATOR`) at `current_point`, a slot or
+    /// a unix timestamp as `activation_type` says
+    pub fn fee_numerator(&self, current_point: u64) -> u64 {
+        if self.period_frequency == 0 {
+            return self.cliff_fee_numerator.min(DAMM_V2_MAX_FEE_NUMERATOR);
+        }
+        // The program charges the fully decayed fee before activation
+        let periods = if current_point < self.activation_point {
+            self.number_of_period as u64
+        } else {
+            let elapsed = current_point - self.activation_point;
+            (elapsed / self.period_frequency).min(self.number_of_period as u64)
+        };
+        let fee = match self.mode {
+            FeeSchedulerMode::Linear => self
+                .cliff_fee_numerator
+                .saturating_sub(periods.saturating_mul(self.reduction_factor)),
+            FeeSchedulerMode::Exponential => {
+                exponential_decay(self.cliff_fee_numerator, self.reduction_factor, periods)
+            }
+        };
+        fee.min(DAMM_V2_MAX_FEE_NUMERATOR)
+    }
+
+    /// Base fee in basis points at `current_point`, rounded up
+    pub fn fee_bps(&self, current_point: u64) -> u16 {
+        fee_rate_to_bps(self.fee_numerator(current_point), DAMM_V2_FEE_DENOMINATOR)
+    }
+
+    /// Base fee in basis points now, reading the activation point as a slot or a
+    /// timestamp as the pool does
+    pub fn current_fee_bps(&self, current_slot: u64, current_timestamp: u64) -> u16 {
+        let current_point = match self.activation_type {
+            ActivationType::Slot => current_slot,
+            ActivationType::Timestamp => current_timestamp,
+        };
+        self.fee_bps(current_point)
+    }
+}
+
+/// `fee * (1 - reduction_bps / 10_000)^periods`, by squaring in Q64.64 as the program does
+fn exponential_decay(fee: u64, reduction_bps: u64, periods: u64) -> u64 {
+    const ONE: u128 = 1 << 64;
+    if reduction_bps == 0 || periods == 0 {
+        return fee;
+    }
+    if reduction_bps >= BASIS_POINTS_DENOMINATOR {
+        return 0;
+    }
+    let mut base =
+        (BASIS_POINTS_DENOMINATOR - reduction_bps) as u128 * ONE / BASIS_POINTS_DENOMINATOR as u128;
+    let mut factor = ONE;
+    let mut exponent = periods;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            factor = (factor * base) >> 64;
+        }
+        base = (base * base) >> 64;
+        exponent >>= 1;
+    }
+    ((fee as u128 * factor) >> 64) as u64
+}
+
 pub struct MeteoraDAmmV2Info {
     pub base_mint: Pubkey,
     pub quote_mint: Pubkey,
     pub base_vault: Pubkey,
     pub quote_vault: Pubkey,
+    pub fee_scheduler: DammV2FeeScheduler,
 }
 
 impl MeteoraDAmmV2Info {
+    /// Bytes needed to read every field below
+    pub const MIN_DATA_LEN: usize = ACTIVATION_TYPE_OFFSET + 1;
+
     pub fn load_checked(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::MIN_DATA_LEN {
+            return Err(anyhow::anyhow!(
+                "Invalid data length for MeteoraDAmmV2Info: {} bytes, expected at least {}",
+                data.len(),
+                Self::MIN_DATA_LEN
+            ));
+        }
+
+        let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        let mode = match data[FEE_SCHEDULER_MODE_OFFSET] {
+            0 => FeeSchedulerMode::Linear,
+            1 => FeeSchedulerMode::Exponential,
+            mode => return Err(anyhow::anyhow!("Unknown DAMM V2 fee scheduler mode: {}", mode)),
+        };
+        let activation_type = match data[ACTIVATION_TYPE_OFFSET] {
+            0 => ActivationType::Slot,
+            1 => ActivationType::Timestamp,
+            activation_type => {
+                return Err(anyhow::anyhow!("Unknown DAMM V2 activation type: {}", activation_type))
+            }
+        };
+        let fee_scheduler = DammV2FeeScheduler {
+            cliff_fee_numerator: u64_at(CLIFF_FEE_NUMERATOR_OFFSET),
+            mode,
+            number_of_period: u16::from_le_bytes(
+                data[NUMBER_OF_PERIOD_OFFSET..NUMBER_OF_PERIOD_OFFSET + 2].try_into().unwrap(),
+            ),
+            period_frequency: u64_at(PERIOD_FREQUENCY_OFFSET),
+            reduction_factor: u64_at(REDUCTION_FACTOR_OFFSET),
+            activation_point: u64_at(ACTIVATION_POINT_OFFSET),
+            activation_type,
+        };
+
         let base_mint = Pubkey::new(&data[168..200]);
         let quote_mint = Pubkey::new(&data[200..232]);
         let base_vault = Pubkey::new(&data[232..264]);
@@ -19,6 +161,101 @@ impl MeteoraDAmmV2Info {
             quote_mint,
             base_vault,
             quote_vault,
+            fee_scheduler,
         })
     }
+
+    /// Current base fee in basis points, reading the activation point as a slot or a
+    /// timestamp as the pool does
+    pub fn fee_bps(&self, current_slot: u64, current_timestamp: u64) -> u16 {
+        self.fee_scheduler.current_fee_bps(current_slot, current_timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduler(mode: FeeSchedulerMode, reduction_factor: u64) -> DammV2FeeScheduler {
+        // 50% at the cliff, stepping every 10 slots for 100 periods from slot 1_000
+        DammV2FeeScheduler {
+            cliff_fee_numerator: 500_000_000,
+            mode,
+            number_of_period: 100,
+            period_frequency: 10,
+            reduction_factor,
+            activation_point: 1_000,
+            activation_type: ActivationType::Slot,
+        }
+    }
+
+    #[test]
+    fn test_linear_fee_at_elapsed_slots() {
+        // 0.49% off per period
+        let scheduler = scheduler(FeeSchedulerMode::Linear, 4_900_000);
+        assert_eq!(scheduler.fee_bps(1_000), 5_000);
+        // Partial periods don't count
+        assert_eq!(scheduler.fee_bps(1_009), 5_000);
+        assert_eq!(scheduler.fee_bps(1_010), 4_951);
+        assert_eq!(scheduler.fee_bps(1_500), 2_550);
+        // Fully decayed after 100 periods, and before activation
+        assert_eq!(scheduler.fee_bps(2_000), 100);
+        assert_eq!(scheduler.fee_bps(50_000), 100);
+        assert_eq!(scheduler.fee_bps(999), 100);
+
+        // A reduction past the cliff bottoms out at zero
+        let steep = DammV2FeeScheduler {
+            reduction_factor: 10_000_000,
+            ..scheduler
+        };
+        assert_eq!(steep.fee_numerator(2_000), 0);
+    }
+
+    #[test]
+    fn test_exponential_fee_at_elapsed_slots() {
+        // 10% of the remaining fee off per period
+        let scheduler = scheduler(FeeSchedulerMode::Exponential, 1_000);
+        assert_eq!(scheduler.fee_bps(1_000), 5_000);
+        // Within the one numerator the fixed-point product truncates
+        let close = |current_point: u64, expected: f64| {
+            let numerator = scheduler.fee_numerator(current_point) as f64;
+            assert!((numerator - expected).abs() <= 1.0, "{} vs {}", numerator, expected);
+        };
+        close(1_010, 450_000_000.0);
+        close(1_050, 500_000_000.0 * 0.9f64.powi(5));
+        close(1_990, 500_000_000.0 * 0.9f64.powi(99));
+        assert_eq!(scheduler.fee_numerator(5_000), scheduler.fee_numerator(2_000));
+
+        // No schedule: the cliff fee throughout
+        let flat = DammV2FeeScheduler {
+            period_frequency: 0,
+            ..scheduler
+        };
+        assert_eq!(flat.fee_bps(999_999), 5_000);
+    }
+
+    #[test]
+    fn test_fee_scheduler_from_pool_data() {
+        let mut data = vec![0u8; MeteoraDAmmV2Info::MIN_DATA_LEN];
+        data[CLIFF_FEE_NUMERATOR_OFFSET..CLIFF_FEE_NUMERATOR_OFFSET + 8]
+            .copy_from_slice(&100_000_000u64.to_le_bytes());
+        data[NUMBER_OF_PERIOD_OFFSET..NUMBER_OF_PERIOD_OFFSET + 2]
+            .copy_from_slice(&10u16.to_le_bytes());
+        data[PERIOD_FREQUENCY_OFFSET..PERIOD_FREQUENCY_OFFSET + 8]
+            .copy_from_slice(&60u64.to_le_bytes());
+        data[REDUCTION_FACTOR_OFFSET..REDUCTION_FACTOR_OFFSET + 8]
+            .copy_from_slice(&5_000_000u64.to_le_bytes());
+        data[ACTIVATION_POINT_OFFSET..ACTIVATION_POINT_OFFSET + 8]
+            .copy_from_slice(&1_700_000_000u64.to_le_bytes());
+        data[ACTIVATION_TYPE_OFFSET] = 1;
+
+        let info = MeteoraDAmmV2Info::load_checked(&data).unwrap();
+        assert_eq!(info.fee_scheduler.mode, FeeSchedulerMode::Linear);
+        assert_eq!(info.fee_scheduler.activation_type, ActivationType::Timestamp);
+        // Timestamp pools ignore the slot: two minutes in, two periods of 0.5% are gone
+        assert_eq!(info.fee_bps(1_700_000_000, 1_700_000_120), 900);
+
+        data[FEE_SCHEDULER_MODE_OFFSET] = 7;
+        assert!(MeteoraDAmmV2Info::load_checked(&data).is_err());
+    }
 }
@@ -1,4 +1,6 @@
-use crate::dex::meteora::constants::{dlmm_program_id, BIN_ARRAY};
+use crate::dex::meteora::constants::{
+    dlmm_program_id, BIN_ARRAY, DLMM_FEE_PRECISION, DLMM_MAX_FEE_RATE,
+};
 use anyhow::Result;
 use solana_program::pubkey::Pubkey;
 use std::mem::size_of;
@@ -85,6 +87,58 @@ pub struct LbPair {
     pub _reserved: [u8; 24],
 }
 
+/// Fee inputs of a DLMM pair as of its last swap
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DlmmFeeParameters {
+    pub base_factor: u16,
+    pub bin_step: u16,
+    pub variable_fee_control: u32,
+    pub volatility_accumulator: u32,
+}
+
+impl DlmmFeeParameters {
+    pub fn from_lb_pair(lb_pair: &LbPair) -> Self {
+        Self {
+            base_factor: lb_pair.parameters.base_factor,
+            bin_step: lb_pair.bin_step,
+            variable_fee_control: lb_pair.parameters.variable_fee_control,
+            volatility_accumulator: lb_pair.v_parameters.volatility_accumulator,
+        }
+    }
+
+    /// Static part of the fee: base_factor * bin_step * 10
+    pub fn base_fee_rate(&self) -> u64 {
+        self.base_factor as u64 * self.bin_step as u64 * 10
+    }
+
+    /// Volatility-driven part of the fee: (volatility_accumulator * bin_step)^2 * variable_fee_control
+    pub fn variable_fee_rate(&self) -> u64 {
+        if self.variable_fee_control == 0 {
+            return 0;
+        }
+
+        let square_vfa_bin =
+            (self.volatility_accumulator as u128 * self.bin_step as u128).pow(2);
+        let v_fee = square_vfa_bin * self.variable_fee_control as u128;
+
+        // Scale down to fee precision, rounding up
+        ((v_fee + 99_999_999_999) / 100_000_000_000).min(u64::MAX as u128) as u64
+    }
+
+    /// Total fee rate in units of `DLMM_FEE_PRECISION`, capped at `DLMM_MAX_FEE_RATE`
+    pub fn total_fee_rate(&self) -> u64 {
+        self.base_fee_rate()
+            .saturating_add(self.variable_fee_rate())
+            .min(DLMM_MAX_FEE_RATE)
+    }
+
+    /// Fee charged on `amount_in`, rounded up
+    pub fn fee_amount(&self, amount_in: u64) -> u64 {
+        let fee = amount_in as u128 * self.total_fee_rate() as u128;
+        ((fee + DLMM_FEE_PRECISION as u128 - 1) / DLMM_FEE_PRECISION as u128) as u64
+    }
+}
+
 #[derive(Debug)]
 pub struct DlmmInfo {
     pub token_x_mint: Pubkey,
@@ -93,13 +147,21 @@ pub struct DlmmInfo {
     pub token_y_vault: Pubkey,
     pub oracle: Pubkey,
     pub active_id: i32,
+    pub fee_parameters: DlmmFeeParameters,
     pub lb_pair: LbPair,
 }
 
 impl DlmmInfo {
+    /// Discriminator plus the full `LbPair` layout
+    pub const MIN_DATA_LEN: usize = 8 + size_of::<LbPair>();
+
     pub fn load_checked(data: &[u8]) -> Result<Self> {
-        if data.len() < 8 + size_of::<LbPair>() {
-            return Err(anyhow::anyhow!("Invalid data length for DlmmInfo"));
+        if data.len() < Self::MIN_DATA_LEN {
+            return Err(anyhow::anyhow!(
+                "Invalid data length for DlmmInfo: {} bytes, expected at least {}",
+                data.len(),
+                Self::MIN_DATA_LEN
+            ));
         }
 
         let raw_lb_pair = &data[8..8 + size_of::<LbPair>()];
@@ -116,6 +178,7 @@ impl DlmmInfo {
             token_y_vault: lb_pair.reserve_y,
             oracle: lb_pair.oracle,
             active_id: lb_pair.active_id,
+            fee_parameters: DlmmFeeParameters::from_lb_pair(&lb_pair),
             lb_pair,
         })
     }
@@ -184,3 +247,42 @@ impl LbPair {
         Ok(lb_pair)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee_parameters(volatility_accumulator: u32) -> DlmmFeeParameters {
+        DlmmFeeParameters {
+            base_factor: 10_000,
+            bin_step: 10,
+            variable_fee_control: 40_000,
+            volatility_accumulator,
+        }
+    }
+
+    #[test]
+    fn test_dlmm_fee_without_volatility() {
+        let params = fee_parameters(0);
+
+        assert_eq!(params.base_fee_rate(), 1_000_000); // 0.1%
+        assert_eq!(params.variable_fee_rate(), 0);
+        assert_eq!(params.total_fee_rate(), 1_000_000);
+    }
+
+    #[test]
+    fn test_dlmm_fee_with_volatility() {
+        let params = fee_parameters(10_000);
+
+        assert_eq!(params.variable_fee_rate(), 4_000);
+        assert_eq!(params.total_fee_rate(), 1_004_000);
+        assert_eq!(params.fee_amount(1_000_000_000), 1_004_000);
+    }
+
+    #[test]
+    fn test_dlmm_fee_is_capped() {
+        let params = fee_parameters(10_000_000);
+
+        assert_eq!(params.total_fee_rate(), DLMM_MAX_FEE_RATE);
+    }
+}
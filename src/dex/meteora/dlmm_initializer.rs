@@ -3,12 +3,16 @@
 /// Implementation for Meteora Dynamic Liquidity Market Maker pools.
 
 use crate::constants::sol_mint;
-use crate::dex::meteora::{meteora_dlmm_program_id, MeteoraDlmmInfo};
-use crate::dex::traits::{DexPool, PoolInitializer, PoolValidator};
+use crate::dex::meteora::constants::{dlmm_event_authority, dlmm_program_id, DLMM_FEE_PRECISION};
+use crate::dex::meteora::dlmm_info::{DlmmFeeParameters, DlmmInfo};
+use crate::dex::traits::{
+    directional_arrays, fee_rate_to_bps, fetch_pool_accounts, DexPool, OracleBasedPool, PoolInitializer, PoolValidator, SwapDirection,
+};
 use crate::error::{BotError, BotResult};
 use async_trait::async_trait;
 use solana_client::rpc_client::RpcClient;
 use solana_program::instruction::AccountMeta;
+use solana_sdk::account::Account;
 use solana_sdk::pubkey::Pubkey;
 use std::sync::Arc;
 use tracing::{error, info};
@@ -19,31 +23,60 @@ pub struct MeteoraDlmmPool {
     pub token_vault: Pubkey,
     pub sol_vault: Pubkey,
     pub oracle: Pubkey,
+    /// The active bin's array and its neighbours, ordered by index
     pub bin_arrays: Vec<Pubkey>,
+    pub fee_parameters: DlmmFeeParameters,
+    /// Whether SOL is the pair's token x, which decides the bin direction of a buy
+    pub sol_is_token_x: bool,
 }
 
-#[async_trait]
-impl DexPool for MeteoraDlmmPool {
-    async fn initialize(&mut self, _rpc_client: &RpcClient, _pool_address: &Pubkey) -> BotResult<()> {
-        Ok(())
+impl MeteoraDlmmPool {
+    /// Amount left to swap after the DLMM base and variable fees are taken
+    ///
+    /// The bin-walking quote builds on this once liquidity is loaded.
+    pub fn amount_after_fee(&self, amount_in: u64) -> u64 {
+        amount_in.saturating_sub(self.fee_parameters.fee_amount(amount_in))
     }
 
-    fn get_swap_accounts(&self, _wallet: &Pubkey) -> Vec<AccountMeta> {
+    /// Bin arrays a swap going `direction` crosses, from the active one
+    pub fn bin_arrays_for(&self, direction: SwapDirection) -> Vec<Pubkey> {
+        directional_arrays(&self.bin_arrays, direction.lowers_price_index(self.sol_is_token_x))
+    }
+
+    fn swap_accounts(&self, bin_arrays: &[Pubkey]) -> Vec<AccountMeta> {
         let mut accounts = vec![
-            AccountMeta::new_readonly(meteora_dlmm_program_id(), false),
+            AccountMeta::new_readonly(dlmm_program_id(), false),
+            AccountMeta::new(dlmm_event_authority(), false),
             AccountMeta::new(self.pair, false),
             AccountMeta::new(self.token_vault, false),
             AccountMeta::new(self.sol_vault, false),
-            AccountMeta::new_readonly(self.oracle, false),
+            AccountMeta::new(self.oracle, false), // Oracle is updated on every swap
         ];
         
-        for bin_array in &self.bin_arrays {
+        for bin_array in bin_arrays {
             accounts.push(AccountMeta::new(*bin_array, false));
         }
         
         accounts
     }
+}
+
+#[async_trait]
+impl DexPool for MeteoraDlmmPool {
+    async fn initialize(&mut self, _rpc_client: &RpcClient, _pool_address: &Pubkey) -> BotResult<()> {
+        Ok(())
+    }
+
+    fn get_swap_accounts(&self, _wallet: &Pubkey) -> Vec<AccountMeta> {
+        self.swap_accounts(&self.bin_arrays)
+    }
 
+    fn get_swap_accounts_for(&self, _wallet: &Pubkey, direction: SwapDirection) -> Vec<AccountMeta> {
+        self.swap_accounts(&self.bin_arrays_for(direction))
+    }
+
+    /// Bin liquidity lives in the bin arrays, which aren't read, so DLMM pools report
+    /// none and routes through them are left to the full on-chain-sized route
     fn get_liquidity(&self) -> (u64, u64) {
         (0, 0)
     }
@@ -59,6 +92,39 @@ impl DexPool for MeteoraDlmmPool {
     fn contains_mint(&self, mint: &Pubkey) -> bool {
         true // Simplified - would check actual mints
     }
+
+    fn fee_bps(&self) -> u16 {
+        // Base plus current volatility fee, as read from the pair
+        fee_rate_to_bps(self.fee_parameters.total_fee_rate(), DLMM_FEE_PRECISION)
+    }
+
+    fn is_concentrated(&self) -> bool {
+        true
+    }
+
+    /// The volatility accumulator behind the variable fee lives on the pair itself; its
+    /// oracle account only records price samples
+    fn observation_account(&self) -> Option<Pubkey> {
+        Some(self.pair)
+    }
+
+    fn update_observation(&mut self, account: Option<&Account>) -> BotResult<()> {
+        let account = account.ok_or_else(|| {
+            BotError::PoolValidationError(format!("DLMM pair {} no longer exists", self.pair))
+        })?;
+        let dlmm_info = DlmmInfo::load_checked(&account.data).map_err(|e| BotError::DeserializationError {
+            data_type: "DlmmInfo".to_string(),
+            source: e.into(),
+        })?;
+        self.fee_parameters = dlmm_info.fee_parameters;
+        Ok(())
+    }
+}
+
+impl OracleBasedPool for MeteoraDlmmPool {
+    fn oracle_account(&self) -> Pubkey {
+        self.oracle
+    }
 }
 
 pub struct MeteoraDlmmInitializer;
@@ -80,10 +146,11 @@ impl PoolInitializer for MeteoraDlmmInitializer {
         mint: &Pubkey,
     ) -> BotResult<Vec<Self::Pool>> {
         let pool_pubkeys = self.validate_addresses(addresses)?;
+        let accounts = fetch_pool_accounts(rpc_client.as_ref(), self.dex_name(), &pool_pubkeys)?;
         let mut pools = Vec::new();
 
-        for pool_address in pool_pubkeys {
-            match self.initialize_single_pool(&rpc_client, &pool_address, mint).await {
+        for (pool_address, account) in accounts {
+            match self.initialize_single_pool(&pool_address, &account, mint) {
                 Ok(pool) => {
                     info!("✓ Initialized Meteora DLMM pool: {}", pool_address);
                     pools.push(pool);
@@ -103,28 +170,162 @@ impl PoolInitializer for MeteoraDlmmInitializer {
 }
 
 impl MeteoraDlmmInitializer {
-    async fn initialize_single_pool(
+    fn initialize_single_pool(
         &self,
-        rpc_client: &RpcClient,
         pool_address: &Pubkey,
-        _expected_mint: &Pubkey,
+        account: &Account,
+        expected_mint: &Pubkey,
     ) -> BotResult<MeteoraDlmmPool> {
-        let account = rpc_client.get_account(pool_address).map_err(|e| {
-            BotError::AccountFetchError {
-                address: *pool_address,
-                reason: format!("Failed to fetch Meteora DLMM pool: {}", e),
+        PoolValidator::validate_owner(pool_address, &account.owner, &dlmm_program_id())?;
+
+        PoolValidator::validate_data_len(
+            pool_address,
+            "DlmmInfo",
+            &account.data,
+            DlmmInfo::MIN_DATA_LEN,
+        )?;
+
+        let dlmm_info = DlmmInfo::load_checked(&account.data).map_err(|e| {
+            BotError::DeserializationError {
+                data_type: "DlmmInfo".to_string(),
+                source: e.into(),
             }
         })?;
 
-        PoolValidator::validate_owner(pool_address, &account.owner, &meteora_dlmm_program_id())?;
+        let sol_mint_pubkey = sol_mint();
+        PoolValidator::validate_mint_pair(
+            pool_address,
+            &dlmm_info.token_x_mint,
+            &dlmm_info.token_y_mint,
+            expected_mint,
+            &sol_mint_pubkey,
+        )?;
+
+        let (token_vault, sol_vault) =
+            dlmm_info.get_token_and_sol_vaults(expected_mint, &sol_mint_pubkey);
+        let bin_arrays = dlmm_info.calculate_bin_arrays(pool_address).map_err(|e| {
+            BotError::PoolValidationError(format!(
+                "Failed to derive bin arrays for DLMM pair {}: {}",
+                pool_address, e
+            ))
+        })?;
 
-        // Simplified - real implementation would parse actual pool data
         Ok(MeteoraDlmmPool {
             pair: *pool_address,
+            token_vault,
+            sol_vault,
+            oracle: dlmm_info.oracle,
+            bin_arrays,
+            fee_parameters: dlmm_info.fee_parameters,
+            sol_is_token_x: dlmm_info.token_x_mint == sol_mint_pubkey,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dlmm_pool_oracle_is_writable() {
+        let oracle = Pubkey::new_unique();
+        let pool = MeteoraDlmmPool {
+            pair: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            sol_vault: Pubkey::new_unique(),
+            oracle,
+            bin_arrays: vec![],
+            fee_parameters: DlmmFeeParameters::default(),
+            sol_is_token_x: false,
+        };
+
+        assert_eq!(pool.oracle_account(), oracle);
+        let meta = pool
+            .get_swap_accounts(&Pubkey::new_unique())
+            .into_iter()
+            .find(|meta| meta.pubkey == oracle)
+            .unwrap();
+        assert!(meta.is_writable);
+    }
+
+    #[test]
+    fn test_dlmm_amount_after_fee() {
+        let pool = MeteoraDlmmPool {
+            pair: Pubkey::new_unique(),
             token_vault: Pubkey::new_unique(),
             sol_vault: Pubkey::new_unique(),
             oracle: Pubkey::new_unique(),
             bin_arrays: vec![],
-        })
+            fee_parameters: DlmmFeeParameters {
+                base_factor: 10_000,
+                bin_step: 10,
+                variable_fee_control: 40_000,
+                volatility_accumulator: 10_000,
+            },
+            sol_is_token_x: false,
+        };
+
+        assert_eq!(pool.amount_after_fee(1_000_000_000), 998_996_000);
+        // 0.1004% rounds up to 11 bps
+        assert_eq!(pool.fee_bps(), 11);
+    }
+
+    #[test]
+    fn test_bin_arrays_follow_swap_direction() {
+        let (below, active, above) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let pool = MeteoraDlmmPool {
+            pair: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            sol_vault: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            bin_arrays: vec![below, active, above],
+            fee_parameters: DlmmFeeParameters::default(),
+            sol_is_token_x: true,
+        };
+        let bin_arrays = |direction| {
+            let accounts = pool.get_swap_accounts_for(&Pubkey::new_unique(), direction);
+            accounts[6..].iter().map(|meta| meta.pubkey).collect::<Vec<_>>()
+        };
+
+        // Swapping x (SOL) for y walks the active bin down
+        assert_eq!(bin_arrays(SwapDirection::Buy), vec![active, below]);
+        assert_eq!(bin_arrays(SwapDirection::Sell), vec![active, above]);
+        assert_eq!(pool.get_swap_accounts(&Pubkey::new_unique()).len(), 9);
+    }
+
+    #[test]
+    fn test_pair_refresh_reprices_the_variable_fee() {
+        use crate::dex::meteora::dlmm_info::{LbPair, StaticParameters, VariableParameters};
+        use std::mem::offset_of;
+
+        let mut pool = MeteoraDlmmPool {
+            pair: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            sol_vault: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            bin_arrays: vec![],
+            fee_parameters: DlmmFeeParameters::default(),
+            sol_is_token_x: false,
+        };
+        assert_eq!(pool.observation_account(), Some(pool.pair));
+
+        let mut data = vec![0u8; DlmmInfo::MIN_DATA_LEN];
+        let parameters = 8 + offset_of!(LbPair, parameters);
+        let base_factor = parameters + offset_of!(StaticParameters, base_factor);
+        data[base_factor..base_factor + 2].copy_from_slice(&10_000u16.to_le_bytes());
+        let control = parameters + offset_of!(StaticParameters, variable_fee_control);
+        data[control..control + 4].copy_from_slice(&40_000u32.to_le_bytes());
+        let bin_step = 8 + offset_of!(LbPair, bin_step);
+        data[bin_step..bin_step + 2].copy_from_slice(&10u16.to_le_bytes());
+        let volatility =
+            8 + offset_of!(LbPair, v_parameters) + offset_of!(VariableParameters, volatility_accumulator);
+        // Bins crossed since startup raised the volatility accumulator
+        data[volatility..volatility + 4].copy_from_slice(&100_000u32.to_le_bytes());
+
+        pool.update_observation(Some(&Account { data, ..Account::default() })).unwrap();
+        assert_eq!(pool.fee_parameters.volatility_accumulator, 100_000);
+        // 0.1% base plus 0.04% variable
+        assert_eq!(pool.fee_bps(), 14);
+        assert!(pool.update_observation(None).is_err());
     }
 }
@@ -1,3 +1,4 @@
 pub mod constants;
 pub mod dammv2_info;
 pub mod dlmm_info;
+pub mod dlmm_initializer;
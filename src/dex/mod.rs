@@ -1,4 +1,6 @@
+pub mod lifinity;
 pub mod meteora;
+pub mod phoenix;
 pub mod pump;
 pub mod raydium;
 pub mod solfi;
@@ -0,0 +1,16 @@
+use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub fn phoenix_program_id() -> Pubkey {
+    Pubkey::from_str("PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY").unwrap()
+}
+
+/// PDA the program emits its fill events through, shared by every market
+pub fn phoenix_log_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"log"], &phoenix_program_id()).0
+}
+
+/// `trader`'s seat on `market`
+pub fn phoenix_seat(market: &Pubkey, trader: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"seat", market.as_ref(), trader.as_ref()], &phoenix_program_id()).0
+}
@@ -0,0 +1,302 @@
+/// Phoenix Pool Initializer
+///
+/// Implementation for Phoenix markets, a central limit order book. There are no reserves
+/// to price against, so quotes walk the resting orders read with the market.
+
+use crate::constants::sol_mint;
+use crate::dex::phoenix::{
+    phoenix_log_authority, phoenix_program_id, phoenix_seat, PhoenixBook, PhoenixMarket,
+};
+use crate::dex::traits::{fetch_pool_accounts, DexPool, PoolInitializer, PoolValidator};
+use crate::error::{BotError, BotResult};
+use async_trait::async_trait;
+use solana_client::rpc_client::RpcClient;
+use solana_program::instruction::AccountMeta;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Phoenix Pool structure
+#[derive(Debug, Clone)]
+pub struct PhoenixPool {
+    pub market: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub log_authority: Pubkey,
+    /// Resting orders from the last read of the market (at startup, then every
+    /// `update_state`)
+    pub book: PhoenixBook,
+}
+
+impl PhoenixPool {
+    fn sol_is_quote(&self) -> bool {
+        self.quote_mint == sol_mint()
+    }
+
+    /// `wallet`'s seat on the market
+    pub fn seat(&self, wallet: &Pubkey) -> Pubkey {
+        phoenix_seat(&self.market, wallet)
+    }
+}
+
+#[async_trait]
+impl DexPool for PhoenixPool {
+    async fn initialize(&mut self, _rpc_client: &RpcClient, _pool_address: &Pubkey) -> BotResult<()> {
+        Ok(())
+    }
+
+    fn get_swap_accounts(&self, _wallet: &Pubkey) -> Vec<AccountMeta> {
+        let (token_vault, sol_vault) = if self.sol_is_quote() {
+            (self.base_vault, self.quote_vault)
+        } else {
+            (self.quote_vault, self.base_vault)
+        };
+        vec![
+            AccountMeta::new_readonly(phoenix_program_id(), false),
+            AccountMeta::new_readonly(self.log_authority, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(token_vault, false),
+            AccountMeta::new(sol_vault, false),
+        ]
+    }
+
+    fn extend_wallet_swap_accounts(&self, wallet: &Pubkey, accounts: &mut Vec<AccountMeta>) {
+        accounts.push(AccountMeta::new_readonly(self.seat(wallet), false));
+    }
+
+    /// Resting size on each side of the book, standing in for reserves
+    fn get_liquidity(&self) -> (u64, u64) {
+        let (base_depth, quote_depth) = self.book.depth();
+        if self.sol_is_quote() {
+            (base_depth, quote_depth)
+        } else {
+            (quote_depth, base_depth)
+        }
+    }
+
+    fn dex_name(&self) -> &'static str {
+        "Phoenix"
+    }
+
+    fn pool_address(&self) -> Pubkey {
+        self.market
+    }
+
+    fn contains_mint(&self, mint: &Pubkey) -> bool {
+        &self.base_mint == mint || &self.quote_mint == mint
+    }
+
+    fn fee_bps(&self) -> u16 {
+        self.book.taker_fee_bps
+    }
+
+    fn is_concentrated(&self) -> bool {
+        true
+    }
+
+    fn state_accounts(&self) -> Vec<Pubkey> {
+        vec![self.market]
+    }
+
+    /// Re-read the ladder, since orders come and go between every quote
+    fn update_state(&mut self, accounts: &[Option<Account>]) -> BotResult<()> {
+        let account = accounts.first().and_then(Option::as_ref).ok_or_else(|| {
+            BotError::PoolValidationError(format!("Phoenix market {} no longer exists", self.market))
+        })?;
+        self.book = load_market(account)?.book;
+        Ok(())
+    }
+
+    /// Output of filling `amount_in` against the book: buying the base lifts the asks,
+    /// selling it hits the bids
+    fn quote(&self, amount_in: u64, sol_to_token: bool) -> u64 {
+        if sol_to_token == self.sol_is_quote() {
+            self.book.buy_base(amount_in)
+        } else {
+            self.book.sell_base(amount_in)
+        }
+    }
+}
+
+/// Phoenix Pool Initializer
+pub struct PhoenixInitializer;
+
+impl PhoenixInitializer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl PoolInitializer for PhoenixInitializer {
+    type Pool = PhoenixPool;
+
+    async fn initialize_pools(
+        &self,
+        addresses: &[String],
+        rpc_client: Arc<RpcClient>,
+        mint: &Pubkey,
+    ) -> BotResult<Vec<Self::Pool>> {
+        let pool_pubkeys = self.validate_addresses(addresses)?;
+        let accounts = fetch_pool_accounts(rpc_client.as_ref(), self.dex_name(), &pool_pubkeys)?;
+        let mut pools = Vec::with_capacity(accounts.len());
+
+        for (market_address, account) in accounts {
+            match Self::pool_from_account(&market_address, &account, mint) {
+                Ok(pool) => {
+                    info!("✓ Initialized Phoenix market: {}", market_address);
+                    pools.push(pool);
+                }
+                Err(e) => {
+                    error!("✗ Failed to initialize Phoenix market {}: {}", market_address, e);
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(pools)
+    }
+
+    fn dex_name(&self) -> &'static str {
+        "Phoenix"
+    }
+}
+
+impl PhoenixInitializer {
+    /// Validate and decode a fetched market account
+    fn pool_from_account(
+        market_address: &Pubkey,
+        account: &Account,
+        expected_mint: &Pubkey,
+    ) -> BotResult<PhoenixPool> {
+        PoolValidator::validate_owner(market_address, &account.owner, &phoenix_program_id())?;
+
+        let market = load_market(account)?;
+
+        PoolValidator::validate_mint_pair(
+            market_address,
+            &market.header.base_mint,
+            &market.header.quote_mint,
+            expected_mint,
+            &sol_mint(),
+        )?;
+
+        Ok(PhoenixPool {
+            market: *market_address,
+            base_mint: market.header.base_mint,
+            quote_mint: market.header.quote_mint,
+            base_vault: market.header.base_vault,
+            quote_vault: market.header.quote_vault,
+            log_authority: phoenix_log_authority(),
+            book: market.book,
+        })
+    }
+}
+
+fn load_market(account: &Account) -> BotResult<PhoenixMarket> {
+    PhoenixMarket::load_checked(&account.data).map_err(|e| BotError::DeserializationError {
+        data_type: "PhoenixMarket".to_string(),
+        source: Box::new(e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::phoenix::market::tests::market_data;
+
+    fn market_account(data: Vec<u8>) -> Account {
+        Account {
+            lamports: 1,
+            data,
+            owner: phoenix_program_id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_pool_from_market_fixture() {
+        let (market_address, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (base_vault, quote_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // mint/SOL: one lot of the mint costs 1_000 * 10 / 1_000 = 10 lamports at 1_000 ticks
+        let data = market_data(
+            &mint,
+            &sol_mint(),
+            &base_vault,
+            &quote_vault,
+            &[(1_000, 3)],
+            &[(1_100, 2), (1_200, 5)],
+        );
+        let pool = PhoenixInitializer::pool_from_account(
+            &market_address,
+            &market_account(data.clone()),
+            &mint,
+        )
+        .unwrap();
+
+        assert_eq!((pool.base_vault, pool.quote_vault), (base_vault, quote_vault));
+        assert_eq!(pool.log_authority, phoenix_log_authority());
+        assert_eq!(pool.fee_bps(), 5);
+        assert!(pool.is_concentrated());
+        assert!(pool.contains_mint(&mint) && pool.contains_mint(&sol_mint()));
+        assert_eq!(pool.get_liquidity(), (7_000, 30));
+
+        // SOL buys the mint off the asks: 22 lamports fill both lots at 11, net of the fee
+        assert_eq!(pool.quote(23, true), 2_000);
+        // Selling the mint hits the bid at 10 a lot, less 5 bps
+        assert_eq!(pool.quote(2_000, false), 19);
+
+        let wallet = Pubkey::new_unique();
+        let mut accounts = pool.get_swap_accounts(&wallet);
+        assert_eq!(accounts.len(), 5);
+        assert_eq!(accounts[0].pubkey, phoenix_program_id());
+        assert!(accounts[2].is_writable && accounts[2].pubkey == market_address);
+        assert_eq!((accounts[3].pubkey, accounts[4].pubkey), (base_vault, quote_vault));
+        pool.extend_wallet_swap_accounts(&wallet, &mut accounts);
+        assert_eq!(accounts[5].pubkey, phoenix_seat(&market_address, &wallet));
+
+        // Another mint's market, a foreign owner and a truncated book are all rejected
+        let other_mint = Pubkey::new_unique();
+        let other = market_data(&other_mint, &sol_mint(), &base_vault, &quote_vault, &[], &[]);
+        assert!(matches!(
+            PhoenixInitializer::pool_from_account(&market_address, &market_account(other), &mint),
+            Err(BotError::PoolValidationError(_))
+        ));
+        let mut foreign = market_account(data.clone());
+        foreign.owner = Pubkey::new_unique();
+        assert!(matches!(
+            PhoenixInitializer::pool_from_account(&market_address, &foreign, &mint),
+            Err(BotError::InvalidAccountOwner { .. })
+        ));
+        let truncated = market_account(data[..900].to_vec());
+        assert!(matches!(
+            PhoenixInitializer::pool_from_account(&market_address, &truncated, &mint),
+            Err(BotError::DeserializationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_update_state_rereads_the_book() {
+        let (market_address, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (base_vault, quote_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let book = |asks: &[(u64, u64)]| {
+            market_account(market_data(&mint, &sol_mint(), &base_vault, &quote_vault, &[(1_000, 3)], asks))
+        };
+        let mut pool =
+            PhoenixInitializer::pool_from_account(&market_address, &book(&[(1_100, 2)]), &mint).unwrap();
+        assert_eq!(pool.state_accounts(), vec![market_address]);
+        assert_eq!(pool.quote(23, true), 2_000);
+
+        // The asks were lifted and re-posted higher, so the same SOL buys less
+        pool.update_state(&[Some(book(&[(1_200, 1), (1_300, 4)]))]).unwrap();
+        assert_eq!(pool.quote(23, true), 1_000);
+
+        // A closed market keeps the last book
+        assert!(pool.update_state(&[None]).is_err());
+        assert_eq!(pool.quote(23, true), 1_000);
+    }
+}
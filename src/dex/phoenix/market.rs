@@ -0,0 +1,380 @@
+use crate::constants::BASIS_POINTS_DENOMINATOR;
+use anyhow::Result;
+use solana_program::pubkey::Pubkey;
+use std::collections::BTreeMap;
+
+// Offsets into the `MarketHeader` that starts every market account
+const BIDS_SIZE_OFFSET: usize = 16;
+const ASKS_SIZE_OFFSET: usize = 24;
+const BASE_MINT_OFFSET: usize = 48;
+const BASE_VAULT_OFFSET: usize = 80;
+const BASE_LOT_SIZE_OFFSET: usize = 112;
+const QUOTE_MINT_OFFSET: usize = 128;
+const QUOTE_VAULT_OFFSET: usize = 160;
+const QUOTE_LOT_SIZE_OFFSET: usize = 192;
+
+// Offsets into the FIFO market that follows the header and its padding
+const BASE_LOTS_PER_BASE_UNIT_OFFSET: usize = 832;
+const TICK_SIZE_IN_QUOTE_LOTS_OFFSET: usize = 840;
+const TAKER_FEE_BPS_OFFSET: usize = 856;
+const BIDS_OFFSET: usize = 880;
+
+/// Tree root and padding, then the node allocator's size, bump index and free list
+const TREE_HEADER_LEN: usize = 32;
+/// Left/right/parent/color registers, the (price in ticks, sequence number) key, then
+/// the resting order
+const ORDER_NODE_LEN: usize = 64;
+const NODE_RIGHT_OFFSET: usize = 4;
+const NODE_PRICE_OFFSET: usize = 16;
+const NODE_BASE_LOTS_OFFSET: usize = 40;
+
+fn pubkey_at(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new(&data[offset..offset + 32])
+}
+
+fn u64_at(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+#[derive(Debug)]
+pub struct PhoenixMarketHeader {
+    /// Orders each side of the book has room for
+    pub bids_size: u64,
+    pub asks_size: u64,
+    pub base_mint: Pubkey,
+    pub base_vault: Pubkey,
+    /// Base atoms per base lot
+    pub base_lot_size: u64,
+    pub quote_mint: Pubkey,
+    pub quote_vault: Pubkey,
+    /// Quote atoms per quote lot
+    pub quote_lot_size: u64,
+}
+
+impl PhoenixMarketHeader {
+    pub const LEN: usize = 576;
+
+    pub fn load_checked(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(anyhow::anyhow!(
+                "Invalid data length for PhoenixMarketHeader: {} bytes, expected at least {}",
+                data.len(),
+                Self::LEN
+            ));
+        }
+
+        Ok(Self {
+            bids_size: u64_at(data, BIDS_SIZE_OFFSET),
+            asks_size: u64_at(data, ASKS_SIZE_OFFSET),
+            base_mint: pubkey_at(data, BASE_MINT_OFFSET),
+            base_vault: pubkey_at(data, BASE_VAULT_OFFSET),
+            base_lot_size: u64_at(data, BASE_LOT_SIZE_OFFSET),
+            quote_mint: pubkey_at(data, QUOTE_MINT_OFFSET),
+            quote_vault: pubkey_at(data, QUOTE_VAULT_OFFSET),
+            quote_lot_size: u64_at(data, QUOTE_LOT_SIZE_OFFSET),
+        })
+    }
+}
+
+/// Resting size at one price
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LadderLevel {
+    pub price_in_ticks: u64,
+    pub size_in_base_lots: u64,
+}
+
+/// The top of a market's book and what's needed to turn lots and ticks into atoms
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PhoenixBook {
+    pub base_lot_size: u64,
+    pub quote_lot_size: u64,
+    pub base_lots_per_base_unit: u64,
+    pub tick_size_in_quote_lots_per_base_unit: u64,
+    pub taker_fee_bps: u16,
+    /// Best bid first
+    pub bids: Vec<LadderLevel>,
+    /// Best ask first
+    pub asks: Vec<LadderLevel>,
+}
+
+impl PhoenixBook {
+    /// Quote atoms one base lot trades for at `price_in_ticks`
+    fn quote_atoms_per_base_lot(&self, price_in_ticks: u64) -> u128 {
+        if self.base_lots_per_base_unit == 0 {
+            return 0;
+        }
+        price_in_ticks as u128
+            * self.tick_size_in_quote_lots_per_base_unit as u128
+            * self.quote_lot_size as u128
+            / self.base_lots_per_base_unit as u128
+    }
+
+    fn after_taker_fee(&self, amount: u128) -> u128 {
+        let fee_bps = (self.taker_fee_bps as u128).min(BASIS_POINTS_DENOMINATOR as u128);
+        amount * (BASIS_POINTS_DENOMINATOR as u128 - fee_bps) / BASIS_POINTS_DENOMINATOR as u128
+    }
+
+    /// Base atoms resting on the asks and quote atoms resting on the bids
+    pub fn depth(&self) -> (u64, u64) {
+        let base_lots: u128 = self.asks.iter().map(|level| level.size_in_base_lots as u128).sum();
+        let quote_atoms: u128 = self
+            .bids
+            .iter()
+            .map(|level| {
+                level.size_in_base_lots as u128 * self.quote_atoms_per_base_lot(level.price_in_ticks)
+            })
+            .sum();
+        (
+            (base_lots * self.base_lot_size as u128).min(u64::MAX as u128) as u64,
+            quote_atoms.min(u64::MAX as u128) as u64,
+        )
+    }
+
+    /// Base atoms bought by lifting the asks with `quote_in` quote atoms, the taker fee
+    /// taken from the input. Only whole lots fill.
+    pub fn buy_base(&self, quote_in: u64) -> u64 {
+        let mut remaining = self.after_taker_fee(quote_in as u128);
+        let mut base_lots = 0u128;
+        for level in &self.asks {
+            let lot_cost = self.quote_atoms_per_base_lot(level.price_in_ticks);
+            if lot_cost == 0 {
+                continue;
+            }
+            let lots = (remaining / lot_cost).min(level.size_in_base_lots as u128);
+            base_lots += lots;
+            remaining -= lots * lot_cost;
+            if lots < level.size_in_base_lots as u128 {
+                break;
+            }
+        }
+        (base_lots * self.base_lot_size as u128).min(u64::MAX as u128) as u64
+    }
+
+    /// Quote atoms received for hitting the bids with `base_in` base atoms, net of the
+    /// taker fee. Only whole lots fill.
+    pub fn sell_base(&self, base_in: u64) -> u64 {
+        if self.base_lot_size == 0 {
+            return 0;
+        }
+        let mut remaining_lots = base_in / self.base_lot_size;
+        let mut quote_out = 0u128;
+        for level in &self.bids {
+            if remaining_lots == 0 {
+                break;
+            }
+            let lots = remaining_lots.min(level.size_in_base_lots);
+            quote_out += lots as u128 * self.quote_atoms_per_base_lot(level.price_in_ticks);
+            remaining_lots -= lots;
+        }
+        self.after_taker_fee(quote_out).min(u64::MAX as u128) as u64
+    }
+}
+
+/// A whole market account: the header and the book behind it
+#[derive(Debug)]
+pub struct PhoenixMarket {
+    pub header: PhoenixMarketHeader,
+    pub book: PhoenixBook,
+}
+
+impl PhoenixMarket {
+    pub fn load_checked(data: &[u8]) -> Result<Self> {
+        let header = PhoenixMarketHeader::load_checked(data)?;
+        let bids_len = tree_len(header.bids_size)?;
+        let asks_offset = BIDS_OFFSET.saturating_add(bids_len);
+        let data_len = asks_offset.saturating_add(tree_len(header.asks_size)?);
+        if data.len() < data_len {
+            return Err(anyhow::anyhow!(
+                "Invalid data length for PhoenixMarket: {} bytes, its book needs {}",
+                data.len(),
+                data_len
+            ));
+        }
+
+        let mut bids = read_levels(data, BIDS_OFFSET, header.bids_size as usize);
+        bids.reverse();
+        let asks = read_levels(data, asks_offset, header.asks_size as usize);
+        let book = PhoenixBook {
+            base_lot_size: header.base_lot_size,
+            quote_lot_size: header.quote_lot_size,
+            base_lots_per_base_unit: u64_at(data, BASE_LOTS_PER_BASE_UNIT_OFFSET),
+            tick_size_in_quote_lots_per_base_unit: u64_at(data, TICK_SIZE_IN_QUOTE_LOTS_OFFSET),
+            taker_fee_bps: u64_at(data, TAKER_FEE_BPS_OFFSET).min(u16::MAX as u64) as u16,
+            bids,
+            asks,
+        };
+        Ok(Self { header, book })
+    }
+}
+
+fn tree_len(capacity: u64) -> Result<usize> {
+    usize::try_from(capacity)
+        .ok()
+        .and_then(|capacity| capacity.checked_mul(ORDER_NODE_LEN))
+        .and_then(|nodes| nodes.checked_add(TREE_HEADER_LEN))
+        .ok_or_else(|| anyhow::anyhow!("Invalid Phoenix order tree capacity: {}", capacity))
+}
+
+/// Resting size per price of the order tree at `offset`, lowest price first. Only nodes
+/// reachable from the root are read; freed nodes stay behind in the allocator.
+fn read_levels(data: &[u8], offset: usize, capacity: usize) -> Vec<LadderLevel> {
+    let nodes = offset + TREE_HEADER_LEN;
+    let mut sizes: BTreeMap<u64, u64> = BTreeMap::new();
+    let mut stack = vec![u32_at(data, offset)];
+    let mut visited = 0;
+    // Node indices are 1-based, 0 is the empty link
+    while let Some(index) = stack.pop() {
+        if index == 0 || index as usize > capacity {
+            continue;
+        }
+        // A corrupt tree can't loop forever
+        if visited == capacity {
+            break;
+        }
+        visited += 1;
+        let node = nodes + (index as usize - 1) * ORDER_NODE_LEN;
+        let size = sizes.entry(u64_at(data, node + NODE_PRICE_OFFSET)).or_default();
+        *size = size.saturating_add(u64_at(data, node + NODE_BASE_LOTS_OFFSET));
+        stack.push(u32_at(data, node));
+        stack.push(u32_at(data, node + NODE_RIGHT_OFFSET));
+    }
+    sizes
+        .into_iter()
+        .map(|(price_in_ticks, size_in_base_lots)| LadderLevel {
+            price_in_ticks,
+            size_in_base_lots,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Order tree of `capacity` nodes holding `orders` (price in ticks, base lots), each
+    /// order node's left link pointing at the next so they're all reachable
+    fn order_tree(capacity: usize, orders: &[(u64, u64)]) -> Vec<u8> {
+        let mut tree = vec![0u8; TREE_HEADER_LEN + capacity * ORDER_NODE_LEN];
+        if !orders.is_empty() {
+            tree[..4].copy_from_slice(&1u32.to_le_bytes());
+        }
+        tree[16..24].copy_from_slice(&(orders.len() as u64).to_le_bytes());
+        for (i, (price, lots)) in orders.iter().enumerate() {
+            let node = TREE_HEADER_LEN + i * ORDER_NODE_LEN;
+            if i + 1 < orders.len() {
+                tree[node..node + 4].copy_from_slice(&(i as u32 + 2).to_le_bytes());
+            }
+            tree[node + NODE_PRICE_OFFSET..node + 24].copy_from_slice(&price.to_le_bytes());
+            tree[node + 24..node + 32].copy_from_slice(&(i as u64).to_le_bytes());
+            tree[node + NODE_BASE_LOTS_OFFSET..node + 48].copy_from_slice(&lots.to_le_bytes());
+        }
+        tree
+    }
+
+    /// A base/quote market with lots of 1_000 base and 10 quote atoms, 1_000 base lots
+    /// per unit, one quote lot per tick and a 5 bps taker fee
+    pub(crate) fn market_data(
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+        base_vault: &Pubkey,
+        quote_vault: &Pubkey,
+        bids: &[(u64, u64)],
+        asks: &[(u64, u64)],
+    ) -> Vec<u8> {
+        let capacity = 4;
+        let mut data = vec![0u8; BIDS_OFFSET];
+        data[..8].copy_from_slice(&12_610_206_325_545_081_173u64.to_le_bytes()); // market discriminant
+        data[8..16].copy_from_slice(&1u64.to_le_bytes()); // active
+        data[BIDS_SIZE_OFFSET..BIDS_SIZE_OFFSET + 8].copy_from_slice(&(capacity as u64).to_le_bytes());
+        data[ASKS_SIZE_OFFSET..ASKS_SIZE_OFFSET + 8].copy_from_slice(&(capacity as u64).to_le_bytes());
+        data[40..44].copy_from_slice(&9u32.to_le_bytes()); // base decimals
+        data[BASE_MINT_OFFSET..BASE_MINT_OFFSET + 32].copy_from_slice(base_mint.as_ref());
+        data[BASE_VAULT_OFFSET..BASE_VAULT_OFFSET + 32].copy_from_slice(base_vault.as_ref());
+        data[BASE_LOT_SIZE_OFFSET..BASE_LOT_SIZE_OFFSET + 8].copy_from_slice(&1_000u64.to_le_bytes());
+        data[120..124].copy_from_slice(&6u32.to_le_bytes()); // quote decimals
+        data[QUOTE_MINT_OFFSET..QUOTE_MINT_OFFSET + 32].copy_from_slice(quote_mint.as_ref());
+        data[QUOTE_VAULT_OFFSET..QUOTE_VAULT_OFFSET + 32].copy_from_slice(quote_vault.as_ref());
+        data[QUOTE_LOT_SIZE_OFFSET..QUOTE_LOT_SIZE_OFFSET + 8].copy_from_slice(&10u64.to_le_bytes());
+        data[BASE_LOTS_PER_BASE_UNIT_OFFSET..BASE_LOTS_PER_BASE_UNIT_OFFSET + 8]
+            .copy_from_slice(&1_000u64.to_le_bytes());
+        data[TICK_SIZE_IN_QUOTE_LOTS_OFFSET..TICK_SIZE_IN_QUOTE_LOTS_OFFSET + 8]
+            .copy_from_slice(&1u64.to_le_bytes());
+        data[TAKER_FEE_BPS_OFFSET..TAKER_FEE_BPS_OFFSET + 8].copy_from_slice(&5u64.to_le_bytes());
+        data.extend(order_tree(capacity, bids));
+        data.extend(order_tree(capacity, asks));
+        data
+    }
+
+    #[test]
+    fn test_market_deserialization() {
+        let (base_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (base_vault, quote_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // Two bids share a price level
+        let data = market_data(
+            &base_mint,
+            &quote_mint,
+            &base_vault,
+            &quote_vault,
+            &[(990, 5), (1_000, 2), (990, 3)],
+            &[(1_020, 4), (1_010, 1)],
+        );
+
+        let market = PhoenixMarket::load_checked(&data).unwrap();
+        assert_eq!(market.header.base_mint, base_mint);
+        assert_eq!(market.header.quote_mint, quote_mint);
+        assert_eq!(market.header.base_vault, base_vault);
+        assert_eq!(market.header.quote_vault, quote_vault);
+        assert_eq!((market.book.base_lot_size, market.book.quote_lot_size), (1_000, 10));
+        assert_eq!(market.book.base_lots_per_base_unit, 1_000);
+        assert_eq!(market.book.taker_fee_bps, 5);
+        let level = |price_in_ticks, size_in_base_lots| LadderLevel {
+            price_in_ticks,
+            size_in_base_lots,
+        };
+        assert_eq!(market.book.bids, vec![level(1_000, 2), level(990, 8)]);
+        assert_eq!(market.book.asks, vec![level(1_010, 1), level(1_020, 4)]);
+
+        // The header alone reads, but not the book it promises
+        assert!(PhoenixMarketHeader::load_checked(&data[..PhoenixMarketHeader::LEN]).is_ok());
+        assert!(PhoenixMarket::load_checked(&data[..data.len() - 1]).is_err());
+        assert!(PhoenixMarketHeader::load_checked(&data[..100]).is_err());
+    }
+
+    #[test]
+    fn test_top_of_book_quote() {
+        let level = |price_in_ticks, size_in_base_lots| LadderLevel {
+            price_in_ticks,
+            size_in_base_lots,
+        };
+        // A lot of 1_000 base atoms at 1_000 ticks costs 1_000 * 10 / 1_000 = 10 quote atoms
+        let book = PhoenixBook {
+            base_lot_size: 1_000,
+            quote_lot_size: 10,
+            base_lots_per_base_unit: 1_000,
+            tick_size_in_quote_lots_per_base_unit: 1,
+            taker_fee_bps: 0,
+            bids: vec![level(1_000, 2), level(500, 10)],
+            asks: vec![level(2_000, 3), level(4_000, 10)],
+        };
+
+        // 3 lots at 20, then 2 at 40, with 10 atoms left over
+        assert_eq!(book.buy_base(150), 5_000);
+        // 2 lots at 10, then 1 at 5; the partial lot doesn't fill
+        assert_eq!(book.sell_base(3_999), 25);
+        // Past the book's depth only what rests fills
+        assert_eq!(book.sell_base(1_000_000), 20 + 50);
+
+        let with_fee = PhoenixBook {
+            taker_fee_bps: 100,
+            ..book.clone()
+        };
+        assert_eq!(with_fee.sell_base(2_000), 19);
+        assert_eq!(with_fee.buy_base(61), 3_000);
+        assert_eq!(PhoenixBook::default().buy_base(1_000), 0);
+        assert_eq!(book.depth(), (13_000, 70));
+    }
+}
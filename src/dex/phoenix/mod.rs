@@ -0,0 +1,7 @@
+pub mod constants;
+pub mod initializer;
+pub mod market;
+
+pub use constants::*;
+pub use initializer::{PhoenixInitializer, PhoenixPool};
+pub use market::{LadderLevel, PhoenixBook, PhoenixMarket, PhoenixMarketHeader};
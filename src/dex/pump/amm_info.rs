@@ -13,14 +13,20 @@ pub struct PumpAmmInfo {
 }
 
 impl PumpAmmInfo {
-    pub fn load_checked(data: &[u8]) -> Result<Self> {
-        let data = &data[8 + 1 + 2 + 32..];
+    /// Header (discriminator, bump, index, creator) plus the five Pubkeys read below
+    pub const MIN_DATA_LEN: usize = 8 + 1 + 2 + 32 + 5 * 32;
 
-        if data.len() < 4 * 32 + 8 {
-            // 4 Pubkeys (32 bytes each) + lp_supply (8 bytes)
-            return Err(anyhow::anyhow!("Invalid data length for PumpAmmInfo"));
+    pub fn load_checked(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::MIN_DATA_LEN {
+            return Err(anyhow::anyhow!(
+                "Invalid data length for PumpAmmInfo: {} bytes, expected at least {}",
+                data.len(),
+                Self::MIN_DATA_LEN
+            ));
         }
 
+        let data = &data[8 + 1 + 2 + 32..];
+
         let base_mint = Pubkey::from(<[u8; 32]>::try_from(&data[0..32]).unwrap());
         let quote_mint = Pubkey::from(<[u8; 32]>::try_from(&data[32..64]).unwrap());
         let pool_base_token_account = Pubkey::from(<[u8; 32]>::try_from(&data[96..128]).unwrap());
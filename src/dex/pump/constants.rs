@@ -11,3 +11,6 @@ pub fn pump_program_id() -> Pubkey {
 pub fn pump_fee_wallet() -> Pubkey {
     Pubkey::from_str(PUMP_FEE_WALLET).unwrap()
 }
+
+// LP, protocol and coin creator fees combined
+pub const PUMP_AMM_FEE_BPS: u16 = 30;
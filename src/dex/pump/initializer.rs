@@ -2,13 +2,16 @@
 /// 
 /// Implementation of the PoolInitializer trait for Pump.fun pools.
 
-use crate::constants::sol_mint;
-use crate::dex::pump::{pump_fee_wallet, pump_program_id, PumpAmmInfo};
-use crate::dex::traits::{DexPool, PoolInitializer, PoolValidator};
+use crate::constants::{sol_mint, PUMP_AUTHORITY_PUBKEY, PUMP_GLOBAL_CONFIG_PUBKEY};
+use crate::dex::pump::{pump_fee_wallet, pump_program_id, PumpAmmInfo, PUMP_AMM_FEE_BPS};
+use crate::dex::traits::{
+    fetch_pool_accounts, vault_reserves, DexPool, PoolInitializer, PoolValidator,
+};
 use crate::error::{BotError, BotResult};
 use async_trait::async_trait;
 use solana_client::rpc_client::RpcClient;
 use solana_program::instruction::AccountMeta;
+use solana_sdk::account::Account;
 use solana_sdk::pubkey::Pubkey;
 use spl_associated_token_account::get_associated_token_address;
 use std::sync::Arc;
@@ -25,6 +28,9 @@ pub struct PumpPool {
     pub coin_creator_vault_authority: Pubkey,
     pub base_mint: Pubkey,
     pub quote_mint: Pubkey,
+    /// Vault balances from the last `update_state`
+    pub token_reserve: u64,
+    pub sol_reserve: u64,
 }
 
 #[async_trait]
@@ -36,6 +42,9 @@ impl DexPool for PumpPool {
     fn get_swap_accounts(&self, _wallet: &Pubkey) -> Vec<AccountMeta> {
         vec![
             AccountMeta::new_readonly(pump_program_id(), false),
+            AccountMeta::new_readonly(*PUMP_GLOBAL_CONFIG_PUBKEY, false),
+            AccountMeta::new_readonly(*PUMP_AUTHORITY_PUBKEY, false),
+            AccountMeta::new_readonly(pump_fee_wallet(), false),
             AccountMeta::new_readonly(self.pool, false),
             AccountMeta::new(self.token_vault, false),
             AccountMeta::new(self.sol_vault, false),
@@ -46,7 +55,16 @@ impl DexPool for PumpPool {
     }
 
     fn get_liquidity(&self) -> (u64, u64) {
-        (0, 0)
+        (self.token_reserve, self.sol_reserve)
+    }
+
+    fn state_accounts(&self) -> Vec<Pubkey> {
+        vec![self.token_vault, self.sol_vault]
+    }
+
+    fn update_state(&mut self, accounts: &[Option<Account>]) -> BotResult<()> {
+        (self.token_reserve, self.sol_reserve) = vault_reserves(&self.pool, accounts)?;
+        Ok(())
     }
 
     fn dex_name(&self) -> &'static str {
@@ -60,6 +78,14 @@ impl DexPool for PumpPool {
     fn contains_mint(&self, mint: &Pubkey) -> bool {
         &self.base_mint == mint || &self.quote_mint == mint
     }
+
+    fn fee_bps(&self) -> u16 {
+        PUMP_AMM_FEE_BPS
+    }
+
+    fn shared_accounts(&self) -> Vec<Pubkey> {
+        vec![*PUMP_GLOBAL_CONFIG_PUBKEY]
+    }
 }
 
 /// Pump.fun Pool Initializer
@@ -82,10 +108,11 @@ impl PoolInitializer for PumpInitializer {
         mint: &Pubkey,
     ) -> BotResult<Vec<Self::Pool>> {
         let pool_pubkeys = self.validate_addresses(addresses)?;
-        let mut pools = Vec::with_capacity(pool_pubkeys.len());
+        let accounts = fetch_pool_accounts(rpc_client.as_ref(), self.dex_name(), &pool_pubkeys)?;
+        let mut pools = Vec::with_capacity(accounts.len());
 
-        for pool_address in pool_pubkeys {
-            match self.initialize_single_pool(&rpc_client, &pool_address, mint).await {
+        for (pool_address, account) in accounts {
+            match self.initialize_single_pool(&pool_address, &account, mint) {
                 Ok(pool) => {
                     info!("✓ Initialized Pump.fun pool: {}", pool_address);
                     pools.push(pool);
@@ -106,21 +133,21 @@ impl PoolInitializer for PumpInitializer {
 }
 
 impl PumpInitializer {
-    async fn initialize_single_pool(
+    fn initialize_single_pool(
         &self,
-        rpc_client: &RpcClient,
         pool_address: &Pubkey,
+        account: &Account,
         expected_mint: &Pubkey,
     ) -> BotResult<PumpPool> {
-        let account = rpc_client.get_account(pool_address).map_err(|e| {
-            BotError::AccountFetchError {
-                address: *pool_address,
-                reason: format!("Failed to fetch Pump pool: {}", e),
-            }
-        })?;
-
         PoolValidator::validate_owner(pool_address, &account.owner, &pump_program_id())?;
 
+        PoolValidator::validate_data_len(
+            pool_address,
+            "PumpAmmInfo",
+            &account.data,
+            PumpAmmInfo::MIN_DATA_LEN,
+        )?;
+
         let amm_info = PumpAmmInfo::load_checked(&account.data).map_err(|e| {
             BotError::DeserializationError {
                 data_type: "PumpAmmInfo".to_string(),
@@ -163,6 +190,8 @@ impl PumpInitializer {
             coin_creator_vault_authority: amm_info.coin_creator_vault_authority,
             base_mint: amm_info.base_mint,
             quote_mint: amm_info.quote_mint,
+            token_reserve: 0,
+            sol_reserve: 0,
         })
     }
 }
@@ -182,6 +211,8 @@ mod tests {
             coin_creator_vault_authority: Pubkey::new_unique(),
             base_mint: Pubkey::new_unique(),
             quote_mint: Pubkey::new_unique(),
+            token_reserve: 0,
+            sol_reserve: 0,
         };
 
         assert_eq!(pool.dex_name(), "Pump.fun");
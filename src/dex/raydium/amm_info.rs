@@ -15,9 +15,16 @@ pub struct RaydiumAmmInfo {
 }
 
 impl RaydiumAmmInfo {
+    /// Bytes needed to read every field below
+    pub const MIN_DATA_LEN: usize = PC_MINT_OFFSET + 32;
+
     pub fn load_checked(data: &[u8]) -> Result<Self> {
-        if data.len() < PC_MINT_OFFSET + 32 {
-            return Err(anyhow::anyhow!("Invalid data length for RaydiumAmmInfo"));
+        if data.len() < Self::MIN_DATA_LEN {
+            return Err(anyhow::anyhow!(
+                "Invalid data length for RaydiumAmmInfo: {} bytes, expected at least {}",
+                data.len(),
+                Self::MIN_DATA_LEN
+            ));
         }
         
         let coin_vault = Pubkey::new(&data[COIN_VAULT_OFFSET..COIN_VAULT_OFFSET + 32]);
@@ -1,3 +1,5 @@
+use crate::dex::raydium::constants::RAYDIUM_FEE_RATE_DENOMINATOR;
+use crate::dex::traits::fee_rate_to_bps;
 use anyhow::Result;
 use solana_program::pubkey::Pubkey;
 
@@ -8,6 +10,9 @@ pub const REWARD_NUM: usize = 3;
 
 pub const POOL_TICK_ARRAY_BITMAP_SEED: &str = "pool_tick_array_bitmap_extension";
 
+// AmmConfig: discriminator, bump, index, owner, protocol_fee_rate, then trade_fee_rate
+const CONFIG_TRADE_FEE_RATE_OFFSET: usize = 47;
+
 pub enum RewardState {
     Uninitialized,
     Initialized,
@@ -102,10 +107,15 @@ pub struct PoolState {
 }
 
 impl PoolState {
+    /// Discriminator through `tick_current`, the last field read below
+    pub const MIN_DATA_LEN: usize = 8 + 1 + 32 * 7 + 1 + 1 + 2 + 16 + 16 + 4;
+
     pub fn load_checked(data: &[u8]) -> Result<Self> {
-        if data.len() < 8 + 32 + 32 + 32 + 32 + 32 + 32 + 4 + 2 {
+        if data.len() < Self::MIN_DATA_LEN {
             return Err(anyhow::anyhow!(
-                "Invalid data length for RaydiumClmmPoolState"
+                "Invalid data length for RaydiumClmmPoolState: {} bytes, expected at least {}",
+                data.len(),
+                Self::MIN_DATA_LEN
             ));
         }
 
@@ -153,9 +163,14 @@ impl PoolState {
         let tick_spacing = u16::from_le_bytes(tick_spacing_bytes);
         offset += 2;
 
+        let mut liquidity_bytes = [0u8; 16];
+        liquidity_bytes.copy_from_slice(&data[offset..offset + 16]);
+        let liquidity = u128::from_le_bytes(liquidity_bytes);
         offset += 16;
 
-        // Skip sqrt_price_x64
+        let mut sqrt_price_bytes = [0u8; 16];
+        sqrt_price_bytes.copy_from_slice(&data[offset..offset + 16]);
+        let sqrt_price_x64 = u128::from_le_bytes(sqrt_price_bytes);
         offset += 16;
 
         let mut tick_current_bytes = [0u8; 4];
@@ -171,12 +186,27 @@ impl PoolState {
             token_vault_1,
             observation_key,
             tick_spacing,
+            liquidity,
+            sqrt_price_x64,
             tick_current,
             ..Default::default()
         })
     }
 }
 
+/// Trade fee in basis points from a Raydium CLMM `AmmConfig` account
+pub fn clmm_config_fee_bps(data: &[u8]) -> Result<u16> {
+    if data.len() < CONFIG_TRADE_FEE_RATE_OFFSET + 4 {
+        return Err(anyhow::anyhow!("Invalid data length for Raydium CLMM AmmConfig"));
+    }
+    let trade_fee_rate = u32::from_le_bytes(
+        data[CONFIG_TRADE_FEE_RATE_OFFSET..CONFIG_TRADE_FEE_RATE_OFFSET + 4]
+            .try_into()
+            .unwrap(),
+    );
+    Ok(fee_rate_to_bps(trade_fee_rate as u64, RAYDIUM_FEE_RATE_DENOMINATOR))
+}
+
 pub fn compute_tick_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
     let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
     let mut start = tick / ticks_in_array;
@@ -213,3 +243,28 @@ pub fn get_tick_array_pubkeys(
 
     Ok(result)
 }
+
+/// The pool's tick array bitmap extension, which the swap takes whether or not it exists
+pub fn tick_array_bitmap_extension(pool_pubkey: &Pubkey, raydium_clmm_program_id: &Pubkey) -> Pubkey {
+    let seeds = &[POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(), pool_pubkey.as_ref()];
+    Pubkey::find_program_address(seeds, raydium_clmm_program_id).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clmm_config_fee_bps() {
+        let mut data = vec![0u8; 117];
+        // 0.01% stable-pair tier
+        data[CONFIG_TRADE_FEE_RATE_OFFSET..CONFIG_TRADE_FEE_RATE_OFFSET + 4]
+            .copy_from_slice(&100u32.to_le_bytes());
+        assert_eq!(clmm_config_fee_bps(&data).unwrap(), 1);
+
+        data[CONFIG_TRADE_FEE_RATE_OFFSET..CONFIG_TRADE_FEE_RATE_OFFSET + 4]
+            .copy_from_slice(&2_500u32.to_le_bytes());
+        assert_eq!(clmm_config_fee_bps(&data).unwrap(), 25);
+        assert!(clmm_config_fee_bps(&data[..40]).is_err());
+    }
+}
@@ -1,15 +1,22 @@
 /// Raydium CLMM Pool Initializer
 
 use crate::constants::sol_mint;
-use crate::dex::raydium::{raydium_clmm_program_id, PoolState};
-use crate::dex::traits::{ConcentratedLiquidityPool, DexPool, PoolInitializer, PoolValidator};
+use crate::dex::raydium::{
+    clmm_config_fee_bps, get_tick_array_pubkeys, raydium_clmm_program_id, tick_array_bitmap_extension,
+    PoolState, RAYDIUM_CLMM_DEFAULT_FEE_BPS,
+};
+use crate::dex::traits::{
+    directional_arrays, fetch_pool_accounts, virtual_reserves, ConcentratedLiquidityPool, DexPool, PoolInitializer,
+    PoolValidator, SwapDirection,
+};
 use crate::error::{BotError, BotResult};
 use async_trait::async_trait;
 use solana_client::rpc_client::RpcClient;
 use solana_program::instruction::AccountMeta;
+use solana_sdk::account::Account;
 use solana_sdk::pubkey::Pubkey;
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 #[derive(Debug, Clone)]
 pub struct RaydiumClmmPool {
@@ -18,35 +25,107 @@ pub struct RaydiumClmmPool {
     pub observation_state: Pubkey,
     pub token_vault: Pubkey,
     pub sol_vault: Pubkey,
+    /// Ordered by start index, the array holding the current tick in the middle
     pub tick_arrays: Vec<Pubkey>,
     pub current_tick: i32,
+    /// Liquidity of the current tick range and its Q64.64 sqrt price, re-read each iteration
+    pub liquidity: u128,
+    pub sqrt_price_x64: u128,
+    /// Trade fee from the pool's AmmConfig, re-read from the pinned account each iteration
+    pub fee_bps: u16,
+    /// Whether SOL is the pool's token 0, which decides the tick direction of a buy
+    pub sol_is_token_0: bool,
 }
 
-#[async_trait]
-impl DexPool for RaydiumClmmPool {
-    async fn initialize(&mut self, _rpc_client: &RpcClient, _pool_address: &Pubkey) -> BotResult<()> {
-        Ok(())
+/// Trade fee of a CLMM AmmConfig account in basis points
+fn amm_config_fee_bps(account: &Account) -> BotResult<u16> {
+    clmm_config_fee_bps(&account.data).map_err(|e| BotError::DeserializationError {
+        data_type: "RaydiumClmmAmmConfig".to_string(),
+        source: e.into(),
+    })
+}
+
+fn load_pool_state(account: &Account) -> BotResult<PoolState> {
+    PoolState::load_checked(&account.data).map_err(|e| BotError::DeserializationError {
+        data_type: "RaydiumClmmPoolState".to_string(),
+        source: e.into(),
+    })
+}
+
+/// The tick arrays below, at and above the pool's current tick, in the ascending order
+/// `directional_arrays` expects
+fn tick_arrays_around(pool_address: &Pubkey, pool_state: &PoolState) -> BotResult<Vec<Pubkey>> {
+    Ok(get_tick_array_pubkeys(
+        pool_address,
+        pool_state.tick_current,
+        pool_state.tick_spacing,
+        &[-1, 0, 1],
+        &raydium_clmm_program_id(),
+    )?)
+}
+
+impl RaydiumClmmPool {
+    /// Tick arrays a swap going `direction` crosses, from the current one
+    pub fn tick_arrays_for(&self, direction: SwapDirection) -> Vec<Pubkey> {
+        directional_arrays(&self.tick_arrays, direction.lowers_price_index(self.sol_is_token_0))
     }
 
-    fn get_swap_accounts(&self, _wallet: &Pubkey) -> Vec<AccountMeta> {
+    fn swap_accounts(&self, tick_arrays: &[Pubkey]) -> Vec<AccountMeta> {
         let mut accounts = vec![
             AccountMeta::new_readonly(raydium_clmm_program_id(), false),
             AccountMeta::new(self.pool, false),
             AccountMeta::new_readonly(self.amm_config, false),
             AccountMeta::new(self.observation_state, false),
+            AccountMeta::new(tick_array_bitmap_extension(&self.pool, &raydium_clmm_program_id()), false),
             AccountMeta::new(self.token_vault, false),
             AccountMeta::new(self.sol_vault, false),
         ];
         
-        for tick_array in &self.tick_arrays {
+        for tick_array in tick_arrays {
             accounts.push(AccountMeta::new(*tick_array, false));
         }
         
         accounts
     }
+}
+
+#[async_trait]
+impl DexPool for RaydiumClmmPool {
+    async fn initialize(&mut self, _rpc_client: &RpcClient, _pool_address: &Pubkey) -> BotResult<()> {
+        Ok(())
+    }
 
+    fn get_swap_accounts(&self, _wallet: &Pubkey) -> Vec<AccountMeta> {
+        self.swap_accounts(&self.tick_arrays)
+    }
+
+    fn get_swap_accounts_for(&self, _wallet: &Pubkey, direction: SwapDirection) -> Vec<AccountMeta> {
+        self.swap_accounts(&self.tick_arrays_for(direction))
+    }
+
+    /// Virtual reserves of the current tick range, so quotes price its liquidity
     fn get_liquidity(&self) -> (u64, u64) {
-        (0, 0)
+        virtual_reserves(self.liquidity, self.sqrt_price_x64, self.sol_is_token_0)
+    }
+
+    /// The AmmConfig, pinned like every shared config, so its fee reaches the quotes, and
+    /// the pool for its current price and liquidity
+    fn state_accounts(&self) -> Vec<Pubkey> {
+        vec![self.amm_config, self.pool]
+    }
+
+    fn update_state(&mut self, accounts: &[Option<Account>]) -> BotResult<()> {
+        if let Some(Some(config_account)) = accounts.first() {
+            self.fee_bps = amm_config_fee_bps(config_account)?;
+        }
+        let pool_account = accounts.get(1).and_then(Option::as_ref).ok_or_else(|| {
+            BotError::PoolValidationError(format!("Raydium CLMM pool {} no longer exists", self.pool))
+        })?;
+        let pool_state = load_pool_state(pool_account)?;
+        self.liquidity = pool_state.liquidity;
+        self.sqrt_price_x64 = pool_state.sqrt_price_x64;
+        self.current_tick = pool_state.tick_current;
+        Ok(())
     }
 
     fn dex_name(&self) -> &'static str {
@@ -60,6 +139,18 @@ impl DexPool for RaydiumClmmPool {
     fn contains_mint(&self, _mint: &Pubkey) -> bool {
         true
     }
+
+    fn fee_bps(&self) -> u16 {
+        self.fee_bps
+    }
+
+    fn shared_accounts(&self) -> Vec<Pubkey> {
+        vec![self.amm_config]
+    }
+
+    fn is_concentrated(&self) -> bool {
+        true
+    }
 }
 
 impl ConcentratedLiquidityPool for RaydiumClmmPool {
@@ -91,10 +182,11 @@ impl PoolInitializer for RaydiumClmmInitializer {
         mint: &Pubkey,
     ) -> BotResult<Vec<Self::Pool>> {
         let pool_pubkeys = self.validate_addresses(addresses)?;
+        let accounts = fetch_pool_accounts(rpc_client.as_ref(), self.dex_name(), &pool_pubkeys)?;
         let mut pools = Vec::new();
 
-        for pool_address in pool_pubkeys {
-            match self.initialize_single_pool(&rpc_client, &pool_address, mint).await {
+        for (pool_address, account) in accounts {
+            match self.initialize_single_pool(&rpc_client, &pool_address, &account, mint).await {
                 Ok(pool) => {
                     info!("✓ Initialized Raydium CLMM pool: {}", pool_address);
                     pools.push(pool);
@@ -118,25 +210,151 @@ impl RaydiumClmmInitializer {
         &self,
         rpc_client: &RpcClient,
         pool_address: &Pubkey,
+        account: &Account,
         _expected_mint: &Pubkey,
     ) -> BotResult<RaydiumClmmPool> {
-        let account = rpc_client.get_account(pool_address).map_err(|e| {
-            BotError::AccountFetchError {
-                address: *pool_address,
-                reason: format!("Failed to fetch Raydium CLMM pool: {}", e),
+        PoolValidator::validate_owner(pool_address, &account.owner, &raydium_clmm_program_id())?;
+
+        PoolValidator::validate_data_len(
+            pool_address,
+            "RaydiumClmmPoolState",
+            &account.data,
+            PoolState::MIN_DATA_LEN,
+        )?;
+
+        let pool_state = load_pool_state(account)?;
+
+        // Fee tiers live in the AmmConfig account shared by pools of the same tier
+        let fee_bps = match rpc_client.get_account(&pool_state.amm_config) {
+            Ok(config_account) => amm_config_fee_bps(&config_account)?,
+            Err(e) => {
+                warn!(
+                    "Failed to fetch AmmConfig {} for Raydium CLMM pool {}, assuming {} bps fee: {}",
+                    pool_state.amm_config, pool_address, RAYDIUM_CLMM_DEFAULT_FEE_BPS, e
+                );
+                RAYDIUM_CLMM_DEFAULT_FEE_BPS
             }
-        })?;
+        };
 
-        PoolValidator::validate_owner(pool_address, &account.owner, &raydium_clmm_program_id())?;
+        let (token_vault, sol_vault) = PoolValidator::order_vaults(
+            &pool_state.token_mint_0,
+            &pool_state.token_mint_1,
+            pool_state.token_vault_0,
+            pool_state.token_vault_1,
+            &sol_mint(),
+        );
 
         Ok(RaydiumClmmPool {
             pool: *pool_address,
+            amm_config: pool_state.amm_config,
+            observation_state: pool_state.observation_key,
+            token_vault,
+            sol_vault,
+            tick_arrays: tick_arrays_around(pool_address, &pool_state)?,
+            current_tick: pool_state.tick_current,
+            liquidity: pool_state.liquidity,
+            sqrt_price_x64: pool_state.sqrt_price_x64,
+            fee_bps,
+            sol_is_token_0: pool_state.token_mint_0 == sol_mint(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amm_config_account(trade_fee_rate: u32) -> Account {
+        let mut data = vec![0u8; 117];
+        // trade_fee_rate follows the discriminator, bump, index, owner and protocol fee
+        data[47..51].copy_from_slice(&trade_fee_rate.to_le_bytes());
+        Account {
+            lamports: 1_000_000,
+            data,
+            owner: raydium_clmm_program_id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    /// Pool state with `liquidity` at `sqrt_price_x64`, the tick current at 0
+    fn pool_state_account(liquidity: u128, sqrt_price_x64: u128) -> Account {
+        let mut data = vec![0u8; PoolState::MIN_DATA_LEN];
+        // Past the discriminator, bump, seven keys, both decimals and the tick spacing
+        data[237..253].copy_from_slice(&liquidity.to_le_bytes());
+        data[253..269].copy_from_slice(&sqrt_price_x64.to_le_bytes());
+        Account {
+            lamports: 1_000_000,
+            data,
+            owner: raydium_clmm_program_id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn clmm_pool() -> RaydiumClmmPool {
+        RaydiumClmmPool {
+            pool: Pubkey::new_unique(),
             amm_config: Pubkey::new_unique(),
             observation_state: Pubkey::new_unique(),
             token_vault: Pubkey::new_unique(),
             sol_vault: Pubkey::new_unique(),
             tick_arrays: vec![],
             current_tick: 0,
-        })
+            liquidity: 0,
+            sqrt_price_x64: 0,
+            fee_bps: RAYDIUM_CLMM_DEFAULT_FEE_BPS,
+            sol_is_token_0: false,
+        }
+    }
+
+    #[test]
+    fn test_tick_arrays_straddle_the_current_tick() {
+        let pool_address = Pubkey::new_unique();
+        let mut data = pool_state_account(0, 0).data;
+        // Tick spacing 10 puts 600 ticks in an array; tick -1 is in the one starting at -600
+        data[235..237].copy_from_slice(&10u16.to_le_bytes());
+        data[269..273].copy_from_slice(&(-1i32).to_le_bytes());
+        let pool_state = PoolState::load_checked(&data).unwrap();
+
+        let tick_arrays = tick_arrays_around(&pool_address, &pool_state).unwrap();
+        let expected = get_tick_array_pubkeys(&pool_address, 0, 10, &[-2, -1, 0], &raydium_clmm_program_id())
+            .unwrap();
+        assert_eq!(tick_arrays, expected);
+    }
+
+    #[test]
+    fn test_fee_read_from_amm_config() {
+        let mut pool = clmm_pool();
+        assert_eq!(pool.state_accounts(), vec![pool.amm_config, pool.pool]);
+        let state = Some(pool_state_account(0, 0));
+
+        pool.update_state(&[Some(amm_config_account(100)), state.clone()]).unwrap();
+        assert_eq!(pool.fee_bps(), 1);
+        pool.update_state(&[Some(amm_config_account(2_500)), state.clone()]).unwrap();
+        assert_eq!(pool.fee_bps(), 25);
+
+        // An unreadable config keeps the last fee
+        pool.update_state(&[None, state]).unwrap();
+        assert_eq!(pool.fee_bps(), 25);
+    }
+
+    #[test]
+    fn test_quotes_price_the_current_range() {
+        let mut pool = clmm_pool();
+        // No liquidity read yet, nothing to quote
+        assert_eq!(pool.quote(1_000_000, true), 0);
+
+        // Token 1 (SOL) at 4 per token 0: sqrt price 2 in Q64.64
+        let liquidity = 1_000_000_000_000u128;
+        pool.update_state(&[None, Some(pool_state_account(liquidity, 2 << 64))]).unwrap();
+        assert_eq!(pool.get_liquidity(), (500_000_000_000, 2_000_000_000_000));
+
+        // A small buy pays about 4 SOL per token, less the fee
+        let tokens = pool.quote(4_000_000, true);
+        assert!(tokens < 1_000_000 && tokens > 990_000, "{}", tokens);
+        assert!(pool.quote(tokens, false) < 4_000_000);
+
+        assert!(pool.update_state(&[None, None]).is_err());
     }
 }
@@ -22,3 +22,11 @@ pub fn raydium_cp_authority() -> Pubkey {
 pub fn raydium_clmm_program_id() -> Pubkey {
     Pubkey::from_str("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK").unwrap()
 }
+
+// Swap fees in basis points; CP and CLMM pools read the actual rate from their AmmConfig
+pub const RAYDIUM_AMM_FEE_BPS: u16 = 25;
+pub const RAYDIUM_CP_DEFAULT_FEE_BPS: u16 = 25;
+pub const RAYDIUM_CLMM_DEFAULT_FEE_BPS: u16 = 25;
+
+// AmmConfig `trade_fee_rate` is expressed per million
+pub const RAYDIUM_FEE_RATE_DENOMINATOR: u64 = 1_000_000;
@@ -1,3 +1,5 @@
+use crate::dex::raydium::constants::RAYDIUM_FEE_RATE_DENOMINATOR;
+use crate::dex::traits::fee_rate_to_bps;
 use solana_program::pubkey::Pubkey;
 use anyhow::Result;
 
@@ -12,6 +14,9 @@ const TOKEN_0_PROGRAM_OFFSET: usize = 232; // token_0_program
 const TOKEN_1_PROGRAM_OFFSET: usize = 264; // token_1_program
 const OBSERVATION_KEY_OFFSET: usize = 296; // observation_key
 
+// AmmConfig: discriminator, bump, disable_create_pool, index, then trade_fee_rate
+const CONFIG_TRADE_FEE_RATE_OFFSET: usize = 12;
+
 #[derive(Debug)]
 pub struct RaydiumCpAmmInfo {
     pub token_0_mint: Pubkey,
@@ -23,9 +28,16 @@ pub struct RaydiumCpAmmInfo {
 }
 
 impl RaydiumCpAmmInfo {
+    /// Bytes needed to read every field below
+    pub const MIN_DATA_LEN: usize = OBSERVATION_KEY_OFFSET + 32;
+
     pub fn load_checked(data: &[u8]) -> Result<Self> {
-        if data.len() < OBSERVATION_KEY_OFFSET + 32 {
-            return Err(anyhow::anyhow!("Invalid data length for RaydiumCpAmmInfo"));
+        if data.len() < Self::MIN_DATA_LEN {
+            return Err(anyhow::anyhow!(
+                "Invalid data length for RaydiumCpAmmInfo: {} bytes, expected at least {}",
+                data.len(),
+                Self::MIN_DATA_LEN
+            ));
         }
         
         let token_0_vault = Pubkey::new(&data[TOKEN_0_VAULT_OFFSET..TOKEN_0_VAULT_OFFSET + 32]);
@@ -45,3 +57,35 @@ impl RaydiumCpAmmInfo {
         })
     }
 }
+
+/// Trade fee in basis points from a Raydium CP `AmmConfig` account
+pub fn cp_config_fee_bps(data: &[u8]) -> Result<u16> {
+    if data.len() < CONFIG_TRADE_FEE_RATE_OFFSET + 8 {
+        return Err(anyhow::anyhow!("Invalid data length for Raydium CP AmmConfig"));
+    }
+    let trade_fee_rate = u64::from_le_bytes(
+        data[CONFIG_TRADE_FEE_RATE_OFFSET..CONFIG_TRADE_FEE_RATE_OFFSET + 8]
+            .try_into()
+            .unwrap(),
+    );
+    Ok(fee_rate_to_bps(trade_fee_rate, RAYDIUM_FEE_RATE_DENOMINATOR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amm_config(trade_fee_rate: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 236];
+        data[CONFIG_TRADE_FEE_RATE_OFFSET..CONFIG_TRADE_FEE_RATE_OFFSET + 8]
+            .copy_from_slice(&trade_fee_rate.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_cp_config_fee_bps() {
+        assert_eq!(cp_config_fee_bps(&amm_config(2_500)).unwrap(), 25);
+        assert_eq!(cp_config_fee_bps(&amm_config(10_000)).unwrap(), 100);
+        assert!(cp_config_fee_bps(&[0u8; 12]).is_err());
+    }
+}
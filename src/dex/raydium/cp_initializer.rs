@@ -1,15 +1,21 @@
 /// Raydium CP Pool Initializer
 
 use crate::constants::sol_mint;
-use crate::dex::raydium::{raydium_cp_authority, raydium_cp_program_id, RaydiumCpAmmInfo};
-use crate::dex::traits::{DexPool, PoolInitializer, PoolValidator};
+use crate::dex::raydium::{
+    cp_config_fee_bps, raydium_cp_authority, raydium_cp_program_id, RaydiumCpAmmInfo,
+    RAYDIUM_CP_DEFAULT_FEE_BPS,
+};
+use crate::dex::traits::{
+    fetch_pool_accounts, vault_reserves, DexPool, PoolInitializer, PoolValidator,
+};
 use crate::error::{BotError, BotResult};
 use async_trait::async_trait;
 use solana_client::rpc_client::RpcClient;
 use solana_program::instruction::AccountMeta;
+use solana_sdk::account::Account;
 use solana_sdk::pubkey::Pubkey;
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 #[derive(Debug, Clone)]
 pub struct RaydiumCpPool {
@@ -18,6 +24,11 @@ pub struct RaydiumCpPool {
     pub sol_vault: Pubkey,
     pub amm_config: Pubkey,
     pub observation: Pubkey,
+    /// Trade fee from the pool's AmmConfig
+    pub fee_bps: u16,
+    /// Vault balances from the last `update_state`
+    pub token_reserve: u64,
+    pub sol_reserve: u64,
 }
 
 #[async_trait]
@@ -32,14 +43,32 @@ impl DexPool for RaydiumCpPool {
             AccountMeta::new_readonly(raydium_cp_authority(), false),
             AccountMeta::new(self.pool, false),
             AccountMeta::new_readonly(self.amm_config, false),
-            AccountMeta::new(self.observation, false),
             AccountMeta::new(self.token_vault, false),
             AccountMeta::new(self.sol_vault, false),
+            AccountMeta::new(self.observation, false),
         ]
     }
 
     fn get_liquidity(&self) -> (u64, u64) {
-        (0, 0)
+        (self.token_reserve, self.sol_reserve)
+    }
+
+    /// The vaults, then the AmmConfig, which is pinned and only re-read on the pin schedule
+    fn state_accounts(&self) -> Vec<Pubkey> {
+        vec![self.token_vault, self.sol_vault, self.amm_config]
+    }
+
+    fn update_state(&mut self, accounts: &[Option<Account>]) -> BotResult<()> {
+        (self.token_reserve, self.sol_reserve) = vault_reserves(&self.pool, accounts)?;
+        if let Some(Some(config_account)) = accounts.get(2) {
+            self.fee_bps = cp_config_fee_bps(&config_account.data).map_err(|e| {
+                BotError::DeserializationError {
+                    data_type: "RaydiumCpAmmConfig".to_string(),
+                    source: e.into(),
+                }
+            })?;
+        }
+        Ok(())
     }
 
     fn dex_name(&self) -> &'static str {
@@ -53,6 +82,14 @@ impl DexPool for RaydiumCpPool {
     fn contains_mint(&self, _mint: &Pubkey) -> bool {
         true
     }
+
+    fn fee_bps(&self) -> u16 {
+        self.fee_bps
+    }
+
+    fn shared_accounts(&self) -> Vec<Pubkey> {
+        vec![self.amm_config]
+    }
 }
 
 pub struct RaydiumCpInitializer;
@@ -74,10 +111,11 @@ impl PoolInitializer for RaydiumCpInitializer {
         mint: &Pubkey,
     ) -> BotResult<Vec<Self::Pool>> {
         let pool_pubkeys = self.validate_addresses(addresses)?;
+        let accounts = fetch_pool_accounts(rpc_client.as_ref(), self.dex_name(), &pool_pubkeys)?;
         let mut pools = Vec::new();
 
-        for pool_address in pool_pubkeys {
-            match self.initialize_single_pool(&rpc_client, &pool_address, mint).await {
+        for (pool_address, account) in accounts {
+            match self.initialize_single_pool(&rpc_client, &pool_address, &account, mint).await {
                 Ok(pool) => {
                     info!("✓ Initialized Raydium CP pool: {}", pool_address);
                     pools.push(pool);
@@ -101,23 +139,68 @@ impl RaydiumCpInitializer {
         &self,
         rpc_client: &RpcClient,
         pool_address: &Pubkey,
-        _expected_mint: &Pubkey,
+        account: &Account,
+        expected_mint: &Pubkey,
     ) -> BotResult<RaydiumCpPool> {
-        let account = rpc_client.get_account(pool_address).map_err(|e| {
-            BotError::AccountFetchError {
-                address: *pool_address,
-                reason: format!("Failed to fetch Raydium CP pool: {}", e),
+        PoolValidator::validate_owner(pool_address, &account.owner, &raydium_cp_program_id())?;
+
+        PoolValidator::validate_data_len(
+            pool_address,
+            "RaydiumCpAmmInfo",
+            &account.data,
+            RaydiumCpAmmInfo::MIN_DATA_LEN,
+        )?;
+
+        let amm_info = RaydiumCpAmmInfo::load_checked(&account.data).map_err(|e| {
+            BotError::DeserializationError {
+                data_type: "RaydiumCpAmmInfo".to_string(),
+                source: e.into(),
             }
         })?;
 
-        PoolValidator::validate_owner(pool_address, &account.owner, &raydium_cp_program_id())?;
+        let sol_mint_pubkey = sol_mint();
+        PoolValidator::validate_mint_pair(
+            pool_address,
+            &amm_info.token_0_mint,
+            &amm_info.token_1_mint,
+            expected_mint,
+            &sol_mint_pubkey,
+        )?;
+
+        let (token_vault, sol_vault) = PoolValidator::order_vaults(
+            &amm_info.token_0_mint,
+            &amm_info.token_1_mint,
+            amm_info.token_0_vault,
+            amm_info.token_1_vault,
+            &sol_mint_pubkey,
+        );
+
+        // Pools share fee tiers through their AmmConfig account
+        let fee_bps = match rpc_client.get_account(&amm_info.amm_config) {
+            Ok(config_account) => cp_config_fee_bps(&config_account.data).map_err(|e| {
+                BotError::DeserializationError {
+                    data_type: "RaydiumCpAmmConfig".to_string(),
+                    source: e.into(),
+                }
+            })?,
+            Err(e) => {
+                warn!(
+                    "Failed to fetch AmmConfig {} for Raydium CP pool {}, assuming {} bps fee: {}",
+                    amm_info.amm_config, pool_address, RAYDIUM_CP_DEFAULT_FEE_BPS, e
+                );
+                RAYDIUM_CP_DEFAULT_FEE_BPS
+            }
+        };
 
         Ok(RaydiumCpPool {
             pool: *pool_address,
-            token_vault: Pubkey::new_unique(),
-            sol_vault: Pubkey::new_unique(),
-            amm_config: Pubkey::new_unique(),
-            observation: Pubkey::new_unique(),
+            token_vault,
+            sol_vault,
+            amm_config: amm_info.amm_config,
+            observation: amm_info.observation_key,
+            fee_bps,
+            token_reserve: 0,
+            sol_reserve: 0,
         })
     }
 }
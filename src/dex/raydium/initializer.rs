@@ -4,12 +4,15 @@
 /// This demonstrates the pattern that will be replicated for all 10 DEX types.
 
 use crate::constants::sol_mint;
-use crate::dex::raydium::{raydium_authority, raydium_program_id, RaydiumAmmInfo};
-use crate::dex::traits::{DexPool, PoolInitializer, PoolValidator};
+use crate::dex::raydium::{raydium_authority, raydium_program_id, RaydiumAmmInfo, RAYDIUM_AMM_FEE_BPS};
+use crate::dex::traits::{
+    fetch_pool_accounts, vault_reserves, DexPool, PoolInitializer, PoolValidator,
+};
 use crate::error::{BotError, BotResult};
 use async_trait::async_trait;
 use solana_client::rpc_client::RpcClient;
 use solana_program::instruction::AccountMeta;
+use solana_sdk::account::Account;
 use solana_sdk::pubkey::Pubkey;
 use std::sync::Arc;
 use tracing::{error, info};
@@ -22,6 +25,9 @@ pub struct RaydiumCpmmPool {
     pub sol_vault: Pubkey,
     pub coin_mint: Pubkey,
     pub pc_mint: Pubkey,
+    /// Vault balances from the last `update_state`
+    pub token_reserve: u64,
+    pub sol_reserve: u64,
 }
 
 #[async_trait]
@@ -47,8 +53,16 @@ impl DexPool for RaydiumCpmmPool {
     }
 
     fn get_liquidity(&self) -> (u64, u64) {
-        // Would query actual liquidity in full implementation
-        (0, 0)
+        (self.token_reserve, self.sol_reserve)
+    }
+
+    fn state_accounts(&self) -> Vec<Pubkey> {
+        vec![self.token_vault, self.sol_vault]
+    }
+
+    fn update_state(&mut self, accounts: &[Option<Account>]) -> BotResult<()> {
+        (self.token_reserve, self.sol_reserve) = vault_reserves(&self.pool, accounts)?;
+        Ok(())
     }
 
     fn dex_name(&self) -> &'static str {
@@ -62,6 +76,11 @@ impl DexPool for RaydiumCpmmPool {
     fn contains_mint(&self, mint: &Pubkey) -> bool {
         &self.coin_mint == mint || &self.pc_mint == mint
     }
+
+    fn fee_bps(&self) -> u16 {
+        // AMM v4 pools all charge the same fixed fee
+        RAYDIUM_AMM_FEE_BPS
+    }
 }
 
 /// Raydium CPMM Pool Initializer
@@ -84,13 +103,11 @@ impl PoolInitializer for RaydiumCpmmInitializer {
         mint: &Pubkey,
     ) -> BotResult<Vec<Self::Pool>> {
         let pool_pubkeys = self.validate_addresses(addresses)?;
-        let mut pools = Vec::with_capacity(pool_pubkeys.len());
+        let accounts = fetch_pool_accounts(rpc_client.as_ref(), self.dex_name(), &pool_pubkeys)?;
+        let mut pools = Vec::with_capacity(accounts.len());
 
-        for pool_address in pool_pubkeys {
-            match self
-                .initialize_single_pool(&rpc_client, &pool_address, mint)
-                .await
-            {
+        for (pool_address, account) in accounts {
+            match Self::pool_from_account(&pool_address, &account, mint) {
                 Ok(pool) => {
                     info!("✓ Initialized Raydium CPMM pool: {}", pool_address);
                     pools.push(pool);
@@ -111,25 +128,22 @@ impl PoolInitializer for RaydiumCpmmInitializer {
 }
 
 impl RaydiumCpmmInitializer {
-    /// Initialize a single pool (extracted for clarity)
-    async fn initialize_single_pool(
-        &self,
-        rpc_client: &RpcClient,
+    /// Validate and decode a fetched pool account
+    fn pool_from_account(
         pool_address: &Pubkey,
+        account: &Account,
         expected_mint: &Pubkey,
     ) -> BotResult<RaydiumCpmmPool> {
-        // Fetch pool account
-        let account = rpc_client.get_account(pool_address).map_err(|e| {
-            BotError::AccountFetchError {
-                address: *pool_address,
-                reason: format!("Failed to fetch Raydium pool: {}", e),
-            }
-        })?;
-
         // Validate ownership
         PoolValidator::validate_owner(pool_address, &account.owner, &raydium_program_id())?;
 
         // Deserialize pool data
+        PoolValidator::validate_data_len(
+            pool_address,
+            "RaydiumAmmInfo",
+            &account.data,
+            RaydiumAmmInfo::MIN_DATA_LEN,
+        )?;
         let amm_info = RaydiumAmmInfo::load_checked(&account.data).map_err(|e| {
             BotError::DeserializationError {
                 data_type: "RaydiumAmmInfo".to_string(),
@@ -162,6 +176,8 @@ impl RaydiumCpmmInitializer {
             sol_vault,
             coin_mint: amm_info.coin_mint,
             pc_mint: amm_info.pc_mint,
+            token_reserve: 0,
+            sol_reserve: 0,
         })
     }
 }
@@ -181,6 +197,8 @@ mod tests {
             sol_vault: Pubkey::new_unique(),
             coin_mint: mint1,
             pc_mint: mint2,
+            token_reserve: 0,
+            sol_reserve: 0,
         };
 
         assert!(pool.contains_mint(&mint1));
@@ -196,8 +214,32 @@ mod tests {
             sol_vault: Pubkey::new_unique(),
             coin_mint: Pubkey::new_unique(),
             pc_mint: Pubkey::new_unique(),
+            token_reserve: 0,
+            sol_reserve: 0,
         };
 
         assert_eq!(pool.dex_name(), "Raydium CPMM");
+        assert_eq!(pool.fee_bps(), 25);
+    }
+
+    #[test]
+    fn test_undersized_pool_account_rejected() {
+        let pool_address = Pubkey::new_unique();
+        let account = Account {
+            lamports: 1,
+            data: vec![0u8; 100],
+            owner: raydium_program_id(),
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        let expected_mint = Pubkey::new_unique();
+        let err = RaydiumCpmmInitializer::pool_from_account(&pool_address, &account, &expected_mint)
+            .unwrap_err();
+
+        assert!(matches!(err, BotError::DeserializationError { .. }));
+        let message = err.to_string();
+        assert!(message.contains("100 bytes"));
+        assert!(message.contains(&format!("at least {}", RaydiumAmmInfo::MIN_DATA_LEN)));
     }
 }
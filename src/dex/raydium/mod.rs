@@ -6,6 +6,6 @@ pub mod initializer;
 
 pub use amm_info::RaydiumAmmInfo;
 pub use constants::*;
-pub use cp_amm_info::RaydiumCpAmmInfo;
-pub use clmm_info::{PoolState, get_tick_array_pubkeys};
+pub use cp_amm_info::{cp_config_fee_bps, RaydiumCpAmmInfo};
+pub use clmm_info::{clmm_config_fee_bps, PoolState, get_tick_array_pubkeys, tick_array_bitmap_extension};
 pub use initializer::{RaydiumCpmmPool, RaydiumCpmmInitializer};
@@ -4,3 +4,6 @@ use std::str::FromStr;
 pub fn solfi_program_id() -> Pubkey {
     Pubkey::from_str("SoLFiHG9TfgtdUXUjWAxi3LtvYuFyDLVhBWxdMZxyCe").unwrap()
 }
+
+// SolFi quotes from its own oracle price and charges no separate swap fee
+pub const SOLFI_FEE_BPS: u16 = 0;
@@ -9,7 +9,18 @@ pub struct SolfiInfo {
 }
 
 impl SolfiInfo {
+    /// Bytes needed to read every field below
+    pub const MIN_DATA_LEN: usize = 2800;
+
     pub fn load_checked(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::MIN_DATA_LEN {
+            return Err(anyhow::anyhow!(
+                "Invalid data length for SolfiInfo: {} bytes, expected at least {}",
+                data.len(),
+                Self::MIN_DATA_LEN
+            ));
+        }
+
         let base_mint = Pubkey::new(&data[2664..2696]);
         let quote_mint = Pubkey::new(&data[2696..2728]);
         let base_vault = Pubkey::new(&data[2736..2768]);
@@ -1,12 +1,13 @@
 /// Solfi Pool Initializer
 
-use crate::constants::sol_mint;
-use crate::dex::solfi::constants::solfi_program_id;
-use crate::dex::traits::{DexPool, PoolInitializer, PoolValidator};
-use crate::error::{BotError, BotResult};
+use crate::constants::{sol_mint, SYSVAR_INSTRUCTIONS_PUBKEY};
+use crate::dex::solfi::constants::{solfi_program_id, SOLFI_FEE_BPS};
+use crate::dex::traits::{fetch_pool_accounts, DexPool, PoolInitializer, PoolValidator};
+use crate::error::BotResult;
 use async_trait::async_trait;
 use solana_client::rpc_client::RpcClient;
 use solana_program::instruction::AccountMeta;
+use solana_sdk::account::Account;
 use solana_sdk::pubkey::Pubkey;
 use std::sync::Arc;
 use tracing::{error, info};
@@ -27,6 +28,7 @@ impl DexPool for SolfiPool {
     fn get_swap_accounts(&self, _wallet: &Pubkey) -> Vec<AccountMeta> {
         vec![
             AccountMeta::new_readonly(solfi_program_id(), false),
+            AccountMeta::new_readonly(*SYSVAR_INSTRUCTIONS_PUBKEY, false),
             AccountMeta::new(self.pool, false),
             AccountMeta::new(self.token_vault, false),
             AccountMeta::new(self.sol_vault, false),
@@ -48,6 +50,10 @@ impl DexPool for SolfiPool {
     fn contains_mint(&self, _mint: &Pubkey) -> bool {
         true
     }
+
+    fn fee_bps(&self) -> u16 {
+        SOLFI_FEE_BPS
+    }
 }
 
 pub struct SolfiInitializer;
@@ -69,10 +75,11 @@ impl PoolInitializer for SolfiInitializer {
         mint: &Pubkey,
     ) -> BotResult<Vec<Self::Pool>> {
         let pool_pubkeys = self.validate_addresses(addresses)?;
+        let accounts = fetch_pool_accounts(rpc_client.as_ref(), self.dex_name(), &pool_pubkeys)?;
         let mut pools = Vec::new();
 
-        for pool_address in pool_pubkeys {
-            match self.initialize_single_pool(&rpc_client, &pool_address, mint).await {
+        for (pool_address, account) in accounts {
+            match self.initialize_single_pool(&pool_address, &account, mint) {
                 Ok(pool) => {
                     info!("✓ Initialized Solfi pool: {}", pool_address);
                     pools.push(pool);
@@ -92,19 +99,12 @@ impl PoolInitializer for SolfiInitializer {
 }
 
 impl SolfiInitializer {
-    async fn initialize_single_pool(
+    fn initialize_single_pool(
         &self,
-        rpc_client: &RpcClient,
         pool_address: &Pubkey,
+        account: &Account,
         _expected_mint: &Pubkey,
     ) -> BotResult<SolfiPool> {
-        let account = rpc_client.get_account(pool_address).map_err(|e| {
-            BotError::AccountFetchError {
-                address: *pool_address,
-                reason: format!("Failed to fetch Solfi pool: {}", e),
-            }
-        })?;
-
         PoolValidator::validate_owner(pool_address, &account.owner, &solfi_program_id())?;
 
         Ok(SolfiPool {
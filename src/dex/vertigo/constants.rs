@@ -4,3 +4,6 @@ use std::str::FromStr;
 pub fn vertigo_program_id() -> Pubkey {
     Pubkey::from_str("vrTGoBuy5rYSxAfV3jaRJWHH6nN9WK4NRExGxsk1bCJ").unwrap()
 }
+
+// Vertigo pools set their own fee; this is assumed until it is read from the pool
+pub const VERTIGO_DEFAULT_FEE_BPS: u16 = 25;
@@ -1,12 +1,13 @@
 /// Vertigo Pool Initializer
 
 use crate::constants::sol_mint;
-use crate::dex::vertigo::constants::vertigo_program_id;
-use crate::dex::traits::{DexPool, PoolInitializer, PoolValidator};
-use crate::error::{BotError, BotResult};
+use crate::dex::vertigo::constants::{vertigo_program_id, VERTIGO_DEFAULT_FEE_BPS};
+use crate::dex::traits::{fetch_pool_accounts, DexPool, PoolInitializer, PoolValidator};
+use crate::error::BotResult;
 use async_trait::async_trait;
 use solana_client::rpc_client::RpcClient;
 use solana_program::instruction::AccountMeta;
+use solana_sdk::account::Account;
 use solana_sdk::pubkey::Pubkey;
 use std::sync::Arc;
 use tracing::{error, info};
@@ -50,6 +51,10 @@ impl DexPool for VertigoPool {
     fn contains_mint(&self, _mint: &Pubkey) -> bool {
         true
     }
+
+    fn fee_bps(&self) -> u16 {
+        VERTIGO_DEFAULT_FEE_BPS
+    }
 }
 
 pub struct VertigoInitializer;
@@ -71,10 +76,11 @@ impl PoolInitializer for VertigoInitializer {
         mint: &Pubkey,
     ) -> BotResult<Vec<Self::Pool>> {
         let pool_pubkeys = self.validate_addresses(addresses)?;
+        let accounts = fetch_pool_accounts(rpc_client.as_ref(), self.dex_name(), &pool_pubkeys)?;
         let mut pools = Vec::new();
 
-        for pool_address in pool_pubkeys {
-            match self.initialize_single_pool(&rpc_client, &pool_address, mint).await {
+        for (pool_address, account) in accounts {
+            match self.initialize_single_pool(&pool_address, &account, mint) {
                 Ok(pool) => {
                     info!("✓ Initialized Vertigo pool: {}", pool_address);
                     pools.push(pool);
@@ -94,19 +100,12 @@ impl PoolInitializer for VertigoInitializer {
 }
 
 impl VertigoInitializer {
-    async fn initialize_single_pool(
+    fn initialize_single_pool(
         &self,
-        rpc_client: &RpcClient,
         pool_address: &Pubkey,
+        account: &Account,
         _expected_mint: &Pubkey,
     ) -> BotResult<VertigoPool> {
-        let account = rpc_client.get_account(pool_address).map_err(|e| {
-            BotError::AccountFetchError {
-                address: *pool_address,
-                reason: format!("Failed to fetch Vertigo pool: {}", e),
-            }
-        })?;
-
         PoolValidator::validate_owner(pool_address, &account.owner, &vertigo_program_id())?;
 
         Ok(VertigoPool {
@@ -117,3 +116,35 @@ impl VertigoInitializer {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::MockRpcClient;
+
+    #[test]
+    fn test_missing_pool_account_skipped() {
+        let rpc = MockRpcClient::new();
+        let pools = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        for pool in [pools[0], pools[2]] {
+            rpc.add_account(
+                pool,
+                Account {
+                    lamports: 1,
+                    owner: vertigo_program_id(),
+                    ..Account::default()
+                },
+            );
+        }
+
+        let initializer = VertigoInitializer::new();
+        let accounts = fetch_pool_accounts(&rpc, initializer.dex_name(), &pools).unwrap();
+        let initialized: Vec<Pubkey> = accounts
+            .iter()
+            .map(|(pool, account)| initializer.initialize_single_pool(pool, account, &Pubkey::new_unique()))
+            .map(|pool| pool.unwrap().pool)
+            .collect();
+
+        assert_eq!(initialized, vec![pools[0], pools[2]]);
+    }
+}
@@ -8,3 +8,14 @@ pub const MIN_TICK_INDEX: i32 = -443636;
 pub fn whirlpool_program_id() -> Pubkey {
     Pubkey::from_str(WHIRLPOOL_PROGRAM_ID).unwrap()
 }
+
+// Most common fee tier (0.3%); the pool's own `fee_rate` is not read yet
+pub const WHIRLPOOL_DEFAULT_FEE_BPS: u16 = 30;
+
+// Whirlpool fee rates are in hundredths of a basis point
+pub const WHIRLPOOL_FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+// Adaptive fee: (volatility_accumulator * tick_group_size)^2 * control_factor, scaled down by
+// the control factor denominator and the accumulator's scale squared, capped at the hard limit
+pub const ADAPTIVE_FEE_CONTROL_FACTOR_DENOMINATOR: u128 = 100_000;
+pub const VOLATILITY_ACCUMULATOR_SCALE_FACTOR: u128 = 10_000;
+pub const FEE_RATE_HARD_LIMIT: u32 = 100_000;
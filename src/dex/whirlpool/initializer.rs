@@ -3,12 +3,18 @@
 /// Implementation of the PoolInitializer trait for Orca Whirlpool pools.
 
 use crate::constants::sol_mint;
-use crate::dex::traits::{ConcentratedLiquidityPool, DexPool, PoolInitializer, PoolValidator};
+use crate::dex::traits::{
+    directional_arrays, fee_rate_to_bps, fetch_pool_accounts, virtual_reserves, ConcentratedLiquidityPool, DexPool, PoolInitializer,
+    PoolValidator, SwapDirection,
+};
+use crate::dex::whirlpool::constants::{WHIRLPOOL_DEFAULT_FEE_BPS, WHIRLPOOL_FEE_RATE_DENOMINATOR};
+use crate::dex::whirlpool::state::{AdaptiveFee, Whirlpool};
 use crate::dex::whirlpool::{whirlpool_program_id, WhirlpoolInfo};
 use crate::error::{BotError, BotResult};
 use async_trait::async_trait;
 use solana_client::rpc_client::RpcClient;
 use solana_program::instruction::AccountMeta;
+use solana_sdk::account::Account;
 use solana_sdk::pubkey::Pubkey;
 use std::sync::Arc;
 use tracing::{error, info};
@@ -20,37 +26,83 @@ pub struct WhirlpoolPool {
     pub oracle: Pubkey,
     pub token_vault_a: Pubkey,
     pub token_vault_b: Pubkey,
+    /// Ordered by start index, the array holding the current tick in the middle
     pub tick_arrays: Vec<Pubkey>,
     pub current_tick: i32,
     pub token_mint_a: Pubkey,
     pub token_mint_b: Pubkey,
+    /// Liquidity of the current tick range and its Q64.64 sqrt price, read each iteration
+    pub liquidity: u128,
+    pub sqrt_price: u128,
+    /// Variable fee from the oracle's volatility accumulator, in hundredths of a basis
+    /// point; 0 for pools without adaptive fees, which have no oracle account
+    pub adaptive_fee_rate: u32,
 }
 
-#[async_trait]
-impl DexPool for WhirlpoolPool {
-    async fn initialize(&mut self, _rpc_client: &RpcClient, _pool_address: &Pubkey) -> BotResult<()> {
-        Ok(())
+impl WhirlpoolPool {
+    /// Tick arrays a swap going `direction` crosses, from the current one
+    pub fn tick_arrays_for(&self, direction: SwapDirection) -> Vec<Pubkey> {
+        directional_arrays(
+            &self.tick_arrays,
+            direction.lowers_price_index(self.token_mint_a == sol_mint()),
+        )
     }
 
-    fn get_swap_accounts(&self, _wallet: &Pubkey) -> Vec<AccountMeta> {
+    fn swap_accounts(&self, tick_arrays: &[Pubkey]) -> Vec<AccountMeta> {
         let mut accounts = vec![
             AccountMeta::new_readonly(whirlpool_program_id(), false),
             AccountMeta::new(self.pool, false),
+            AccountMeta::new(self.oracle, false),
             AccountMeta::new(self.token_vault_a, false),
             AccountMeta::new(self.token_vault_b, false),
-            AccountMeta::new_readonly(self.oracle, false),
         ];
 
         // Add tick arrays
-        for tick_array in &self.tick_arrays {
+        for tick_array in tick_arrays {
             accounts.push(AccountMeta::new(*tick_array, false));
         }
 
         accounts
     }
+}
+
+#[async_trait]
+impl DexPool for WhirlpoolPool {
+    async fn initialize(&mut self, _rpc_client: &RpcClient, _pool_address: &Pubkey) -> BotResult<()> {
+        Ok(())
+    }
 
+    fn get_swap_accounts(&self, _wallet: &Pubkey) -> Vec<AccountMeta> {
+        self.swap_accounts(&self.tick_arrays)
+    }
+
+    fn get_swap_accounts_for(&self, _wallet: &Pubkey, direction: SwapDirection) -> Vec<AccountMeta> {
+        self.swap_accounts(&self.tick_arrays_for(direction))
+    }
+
+    /// Virtual reserves of the current tick range, so quotes price its liquidity
     fn get_liquidity(&self) -> (u64, u64) {
-        (0, 0)
+        virtual_reserves(self.liquidity, self.sqrt_price, self.token_mint_a == sol_mint())
+    }
+
+    fn state_accounts(&self) -> Vec<Pubkey> {
+        vec![self.pool]
+    }
+
+    fn update_state(&mut self, accounts: &[Option<Account>]) -> BotResult<()> {
+        let account = accounts.first().and_then(Option::as_ref).ok_or_else(|| {
+            BotError::PoolValidationError(format!("Whirlpool {} no longer exists", self.pool))
+        })?;
+        let whirlpool = Whirlpool::try_deserialize(&account.data).map_err(|e| {
+            BotError::DeserializationError {
+                data_type: "Whirlpool".to_string(),
+                source: Box::new(e),
+            }
+        })?;
+        self.liquidity = whirlpool.liquidity;
+        self.sqrt_price = whirlpool.sqrt_price;
+        self.current_tick = whirlpool.tick_current_index;
+        Ok(())
     }
 
     fn dex_name(&self) -> &'static str {
@@ -64,6 +116,33 @@ impl DexPool for WhirlpoolPool {
     fn contains_mint(&self, mint: &Pubkey) -> bool {
         &self.token_mint_a == mint || &self.token_mint_b == mint
     }
+
+    fn fee_bps(&self) -> u16 {
+        WHIRLPOOL_DEFAULT_FEE_BPS
+            + fee_rate_to_bps(self.adaptive_fee_rate as u64, WHIRLPOOL_FEE_RATE_DENOMINATOR)
+    }
+
+    fn is_concentrated(&self) -> bool {
+        true
+    }
+
+    /// Adaptive-fee pools keep their volatility accumulator in the oracle account
+    fn observation_account(&self) -> Option<Pubkey> {
+        Some(self.oracle)
+    }
+
+    fn update_observation(&mut self, account: Option<&Account>) -> BotResult<()> {
+        self.adaptive_fee_rate = match account {
+            Some(account) => AdaptiveFee::try_deserialize(&account.data)
+                .map_err(|e| BotError::DeserializationError {
+                    data_type: "Whirlpool oracle".to_string(),
+                    source: Box::new(e),
+                })?
+                .fee_rate(),
+            None => 0,
+        };
+        Ok(())
+    }
 }
 
 impl ConcentratedLiquidityPool for WhirlpoolPool {
@@ -96,10 +175,11 @@ impl PoolInitializer for WhirlpoolInitializer {
         mint: &Pubkey,
     ) -> BotResult<Vec<Self::Pool>> {
         let pool_pubkeys = self.validate_addresses(addresses)?;
-        let mut pools = Vec::with_capacity(pool_pubkeys.len());
+        let accounts = fetch_pool_accounts(rpc_client.as_ref(), self.dex_name(), &pool_pubkeys)?;
+        let mut pools = Vec::with_capacity(accounts.len());
 
-        for pool_address in pool_pubkeys {
-            match self.initialize_single_pool(&rpc_client, &pool_address, mint).await {
+        for (pool_address, account) in accounts {
+            match self.initialize_single_pool(&pool_address, &account, mint) {
                 Ok(pool) => {
                     info!("✓ Initialized Whirlpool pool: {}", pool_address);
                     pools.push(pool);
@@ -120,19 +200,12 @@ impl PoolInitializer for WhirlpoolInitializer {
 }
 
 impl WhirlpoolInitializer {
-    async fn initialize_single_pool(
+    fn initialize_single_pool(
         &self,
-        rpc_client: &RpcClient,
         pool_address: &Pubkey,
+        account: &Account,
         expected_mint: &Pubkey,
     ) -> BotResult<WhirlpoolPool> {
-        let account = rpc_client.get_account(pool_address).map_err(|e| {
-            BotError::AccountFetchError {
-                address: *pool_address,
-                reason: format!("Failed to fetch Whirlpool pool: {}", e),
-            }
-        })?;
-
         PoolValidator::validate_owner(pool_address, &account.owner, &whirlpool_program_id())?;
 
         let pool_info = WhirlpoolInfo::load_checked(&account.data).map_err(|e| {
@@ -172,6 +245,9 @@ impl WhirlpoolInitializer {
             current_tick: pool_info.tick_current_index,
             token_mint_a: pool_info.token_mint_a,
             token_mint_b: pool_info.token_mint_b,
+            liquidity: 0,
+            sqrt_price: 0,
+            adaptive_fee_rate: 0,
         })
     }
 }
@@ -191,6 +267,9 @@ mod tests {
             current_tick: 0,
             token_mint_a: Pubkey::new_unique(),
             token_mint_b: Pubkey::new_unique(),
+            liquidity: 0,
+            sqrt_price: 0,
+            adaptive_fee_rate: 0,
         };
 
         assert_eq!(pool.dex_name(), "Orca Whirlpool");
@@ -210,10 +289,98 @@ mod tests {
             current_tick: 42,
             token_mint_a: Pubkey::new_unique(),
             token_mint_b: Pubkey::new_unique(),
+            liquidity: 0,
+            sqrt_price: 0,
+            adaptive_fee_rate: 0,
         };
 
         assert_eq!(pool.current_tick(), 42);
         assert_eq!(pool.get_tick_arrays().len(), 2);
         assert_eq!(pool.get_tick_arrays()[0], tick1);
     }
+
+    #[test]
+    fn test_tick_arrays_follow_swap_direction() {
+        let (below, current, above) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut pool = WhirlpoolPool {
+            pool: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            token_vault_a: Pubkey::new_unique(),
+            token_vault_b: Pubkey::new_unique(),
+            tick_arrays: vec![below, current, above],
+            current_tick: 0,
+            token_mint_a: Pubkey::new_unique(),
+            token_mint_b: sol_mint(),
+            liquidity: 0,
+            sqrt_price: 0,
+            adaptive_fee_rate: 0,
+        };
+        let tick_arrays = |pool: &WhirlpoolPool, direction| {
+            let accounts = pool.get_swap_accounts_for(&Pubkey::new_unique(), direction);
+            accounts[5..].iter().map(|meta| meta.pubkey).collect::<Vec<_>>()
+        };
+
+        // Buying puts SOL (b) in, which raises the tick
+        assert_eq!(tick_arrays(&pool, SwapDirection::Buy), vec![current, above]);
+        assert_eq!(tick_arrays(&pool, SwapDirection::Sell), vec![current, below]);
+
+        // With SOL as token a it's the other way round
+        pool.token_mint_b = pool.token_mint_a;
+        pool.token_mint_a = sol_mint();
+        assert_eq!(tick_arrays(&pool, SwapDirection::Buy), vec![current, below]);
+        assert_eq!(tick_arrays(&pool, SwapDirection::Sell), vec![current, above]);
+
+        // Without a direction every array is listed
+        assert_eq!(pool.get_swap_accounts(&Pubkey::new_unique()).len(), 8);
+    }
+
+    /// Oracle account data with the given adaptive-fee constants and accumulator
+    fn oracle_data(control_factor: u32, max_volatility: u32, tick_group_size: u16, volatility: u32) -> Vec<u8> {
+        let mut data = vec![0u8; AdaptiveFee::LEN + 128];
+        data[54..58].copy_from_slice(&control_factor.to_le_bytes());
+        data[58..62].copy_from_slice(&max_volatility.to_le_bytes());
+        data[62..64].copy_from_slice(&tick_group_size.to_le_bytes());
+        data[106..110].copy_from_slice(&volatility.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_adaptive_fee_follows_the_oracle() {
+        let mut pool = WhirlpoolPool {
+            pool: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            token_vault_a: Pubkey::new_unique(),
+            token_vault_b: Pubkey::new_unique(),
+            tick_arrays: vec![],
+            current_tick: 0,
+            token_mint_a: Pubkey::new_unique(),
+            token_mint_b: sol_mint(),
+            liquidity: 0,
+            sqrt_price: 0,
+            adaptive_fee_rate: 0,
+        };
+        assert_eq!(pool.observation_account(), Some(pool.oracle));
+        assert_eq!(pool.fee_bps(), WHIRLPOOL_DEFAULT_FEE_BPS);
+
+        // One tick group of volatility: (10_000 * 64)^2 * 4_000 / 1e13 = 163.84
+        let oracle = Account {
+            data: oracle_data(4_000, 350_000, 64, 10_000),
+            ..Account::default()
+        };
+        pool.update_observation(Some(&oracle)).unwrap();
+        assert_eq!(pool.adaptive_fee_rate, 164);
+        assert_eq!(pool.fee_bps(), WHIRLPOOL_DEFAULT_FEE_BPS + 2);
+
+        // The accumulator is capped at its maximum
+        let capped = Account {
+            data: oracle_data(4_000, 5_000, 64, 10_000),
+            ..Account::default()
+        };
+        pool.update_observation(Some(&capped)).unwrap();
+        assert_eq!(pool.adaptive_fee_rate, 41);
+
+        // A pool without adaptive fees has no oracle account
+        pool.update_observation(None).unwrap();
+        assert_eq!(pool.fee_bps(), WHIRLPOOL_DEFAULT_FEE_BPS);
+    }
 }
@@ -1,3 +1,6 @@
+use crate::dex::whirlpool::constants::{
+    ADAPTIVE_FEE_CONTROL_FACTOR_DENOMINATOR, FEE_RATE_HARD_LIMIT, VOLATILITY_ACCUMULATOR_SCALE_FACTOR,
+};
 use solana_program::pubkey::Pubkey;
 
 pub const NUM_REWARDS: usize = 3;
@@ -41,6 +44,55 @@ impl Whirlpool {
     pub const LEN: usize = 8 + 261 + 384;
 }
 
+/// Adaptive-fee state an adaptive-fee Whirlpool's oracle account keeps: the constants its
+/// variable fee is priced from and the volatility accumulated over recent swaps
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AdaptiveFee {
+    pub adaptive_fee_control_factor: u32,
+    pub max_volatility_accumulator: u32,
+    pub tick_group_size: u16,
+    pub volatility_accumulator: u32,
+}
+
+impl AdaptiveFee {
+    // Discriminator, whirlpool, trade_enable_timestamp, the constants and the variables
+    pub const LEN: usize = 8 + 32 + 8 + 34 + 44;
+    const CONSTANTS_OFFSET: usize = 8 + 32 + 8;
+    const VARIABLES_OFFSET: usize = Self::CONSTANTS_OFFSET + 34;
+
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "data too short for Whirlpool oracle"));
+        }
+        let u16_at = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let u32_at = |offset: usize| {
+            u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+        };
+
+        // filter_period, decay_period and reduction_factor only move the accumulator
+        let constants = Self::CONSTANTS_OFFSET;
+        // last_reference_update_timestamp, last_major_swap_timestamp, volatility_reference,
+        // tick_group_index_reference, then the accumulator
+        let variables = Self::VARIABLES_OFFSET;
+        Ok(AdaptiveFee {
+            adaptive_fee_control_factor: u32_at(constants + 6),
+            max_volatility_accumulator: u32_at(constants + 10),
+            tick_group_size: u16_at(constants + 14),
+            volatility_accumulator: u32_at(variables + 24),
+        })
+    }
+
+    /// Variable fee rate, in hundredths of a basis point, charged on top of the pool's
+    /// static fee rate
+    pub fn fee_rate(&self) -> u32 {
+        let volatility = self.volatility_accumulator.min(self.max_volatility_accumulator);
+        let crossed = volatility as u128 * self.tick_group_size as u128;
+        let numerator = self.adaptive_fee_control_factor as u128 * crossed * crossed;
+        let denominator = ADAPTIVE_FEE_CONTROL_FACTOR_DENOMINATOR * VOLATILITY_ACCUMULATOR_SCALE_FACTOR.pow(2);
+        numerator.div_ceil(denominator).min(FEE_RATE_HARD_LIMIT as u128) as u32
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct WhirlpoolRewardInfo {
     pub mint: Pubkey,
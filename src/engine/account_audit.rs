@@ -0,0 +1,242 @@
+/// Swap Account Writability Audit
+///
+/// Compares the writability every pool's `get_swap_accounts` assigns to each account
+/// with what the on-chain account implies: token vaults and the pool state are written
+/// by the swap, programs, mints and sysvars never are. A mislabeled account otherwise
+/// only shows up as failing swaps.
+
+use crate::constants::token_2022_program;
+use crate::error::BotResult;
+use crate::pools::MintPoolData;
+use crate::rpc::AccountFetcher;
+use solana_program::instruction::AccountMeta;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use std::fmt;
+
+// SPL token account layouts; Token 2022 appends an account type byte after the base
+// account when extensions are present
+const TOKEN_ACCOUNT_LEN: usize = 165;
+const MINT_LEN: usize = 82;
+const TOKEN_2022_MINT_TYPE: u8 = 1;
+const TOKEN_2022_ACCOUNT_TYPE: u8 = 2;
+
+/// What an account is, as far as its on-chain state tells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountKind {
+    Program,
+    Sysvar,
+    Mint,
+    TokenVault,
+    PoolState,
+    /// Exists but its role can't be told from the account alone (config, oracle, ...)
+    Other,
+    /// No account at the address, e.g. a PDA authority
+    Missing,
+}
+
+impl AccountKind {
+    /// `None` when the kind doesn't decide writability
+    pub fn expected_writable(self) -> Option<bool> {
+        match self {
+            AccountKind::Program | AccountKind::Sysvar | AccountKind::Mint => Some(false),
+            AccountKind::TokenVault | AccountKind::PoolState => Some(true),
+            AccountKind::Other | AccountKind::Missing => None,
+        }
+    }
+}
+
+impl fmt::Display for AccountKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AccountKind::Program => "program",
+            AccountKind::Sysvar => "sysvar",
+            AccountKind::Mint => "mint",
+            AccountKind::TokenVault => "token vault",
+            AccountKind::PoolState => "pool state",
+            AccountKind::Other => "other",
+            AccountKind::Missing => "missing",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Classify `address` from its fetched account; `pool` is the pool being swapped through
+pub fn classify_account(address: &Pubkey, account: Option<&Account>, pool: &Pubkey) -> AccountKind {
+    let Some(account) = account else {
+        return AccountKind::Missing;
+    };
+    if account.executable {
+        return AccountKind::Program;
+    }
+    if account.owner == solana_sdk::sysvar::ID {
+        return AccountKind::Sysvar;
+    }
+    if account.owner == spl_token::ID || account.owner == token_2022_program() {
+        return match account.data.len() {
+            TOKEN_ACCOUNT_LEN => AccountKind::TokenVault,
+            MINT_LEN => AccountKind::Mint,
+            len if len > TOKEN_ACCOUNT_LEN => match account.data[TOKEN_ACCOUNT_LEN] {
+                TOKEN_2022_ACCOUNT_TYPE => AccountKind::TokenVault,
+                TOKEN_2022_MINT_TYPE => AccountKind::Mint,
+                _ => AccountKind::Other,
+            },
+            _ => AccountKind::Other,
+        };
+    }
+    if address == pool {
+        return AccountKind::PoolState;
+    }
+    AccountKind::Other
+}
+
+/// One swap account with its labeled and expected writability
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditedAccount {
+    pub address: Pubkey,
+    pub is_writable: bool,
+    pub kind: AccountKind,
+}
+
+impl AuditedAccount {
+    pub fn is_mismatch(&self) -> bool {
+        self.kind
+            .expected_writable()
+            .map_or(false, |expected| expected != self.is_writable)
+    }
+}
+
+/// Audit of one pool's swap accounts, in instruction order
+#[derive(Debug, Clone)]
+pub struct PoolAudit {
+    pub dex: &'static str,
+    pub pool: Pubkey,
+    pub accounts: Vec<AuditedAccount>,
+}
+
+impl PoolAudit {
+    pub fn mismatches(&self) -> impl Iterator<Item = &AuditedAccount> {
+        self.accounts.iter().filter(|account| account.is_mismatch())
+    }
+}
+
+impl fmt::Display for PoolAudit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} pool {}:", self.dex, self.pool)?;
+        for account in &self.accounts {
+            let label = if account.is_writable { "writable" } else { "readonly" };
+            let verdict = match account.kind.expected_writable() {
+                _ if account.is_mismatch() => "MISMATCH",
+                Some(_) => "ok",
+                None => "?",
+            };
+            writeln!(
+                f,
+                "  {:<44} {:<8} {:<11} {}",
+                account.address.to_string(),
+                label,
+                account.kind.to_string(),
+                verdict
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Check `accounts` against their on-chain state
+pub fn audit_accounts(
+    fetcher: &dyn AccountFetcher,
+    dex: &'static str,
+    pool: Pubkey,
+    accounts: &[AccountMeta],
+) -> BotResult<PoolAudit> {
+    let addresses: Vec<Pubkey> = accounts.iter().map(|meta| meta.pubkey).collect();
+    let fetched = fetcher.fetch_multiple_accounts(&addresses)?;
+    Ok(PoolAudit {
+        dex,
+        pool,
+        accounts: accounts
+            .iter()
+            .zip(&fetched)
+            .map(|(meta, account)| AuditedAccount {
+                address: meta.pubkey,
+                is_writable: meta.is_writable,
+                kind: classify_account(&meta.pubkey, account.as_ref(), &pool),
+            })
+            .collect(),
+    })
+}
+
+/// Audit the swap accounts of every pool of a mint
+pub fn audit_mint_pools(
+    fetcher: &dyn AccountFetcher,
+    pool_data: &MintPoolData,
+) -> BotResult<Vec<PoolAudit>> {
+    pool_data
+        .dex_pools()
+        .into_iter()
+        .map(|pool| {
+            let accounts = pool.get_swap_accounts(&pool_data.wallet_account);
+            audit_accounts(fetcher, pool.dex_name(), pool.pool_address(), &accounts)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::raydium::{raydium_authority, raydium_program_id, RaydiumCpmmPool};
+    use crate::dex::traits::DexPool;
+    use crate::rpc::MockRpcClient;
+
+    fn account(owner: Pubkey, data_len: usize, executable: bool) -> Account {
+        Account {
+            lamports: 1_000_000,
+            data: vec![0; data_len],
+            owner,
+            executable,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_audit_flags_mislabeled_vault() {
+        let pool = RaydiumCpmmPool {
+            pool: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            sol_vault: Pubkey::new_unique(),
+            coin_mint: Pubkey::new_unique(),
+            pc_mint: Pubkey::new_unique(),
+            token_reserve: 0,
+            sol_reserve: 0,
+        };
+        let rpc = MockRpcClient::new();
+        rpc.add_account(raydium_program_id(), account(Pubkey::new_unique(), 0, true));
+        rpc.add_account(pool.pool, account(raydium_program_id(), 752, false));
+        rpc.add_account(pool.token_vault, account(spl_token::ID, TOKEN_ACCOUNT_LEN, false));
+        rpc.add_account(pool.sol_vault, account(spl_token::ID, TOKEN_ACCOUNT_LEN, false));
+
+        let mut accounts = pool.get_swap_accounts(&Pubkey::new_unique());
+        let audit = audit_accounts(&rpc, pool.dex_name(), pool.pool, &accounts).unwrap();
+        assert_eq!(audit.mismatches().count(), 0);
+        // The PDA authority has no account, so its writability is left unchecked
+        let authority = audit
+            .accounts
+            .iter()
+            .find(|account| account.address == raydium_authority())
+            .unwrap();
+        assert_eq!(authority.kind, AccountKind::Missing);
+
+        let vault = accounts
+            .iter_mut()
+            .find(|meta| meta.pubkey == pool.sol_vault)
+            .unwrap();
+        vault.is_writable = false;
+        let audit = audit_accounts(&rpc, pool.dex_name(), pool.pool, &accounts).unwrap();
+        let mismatches: Vec<&AuditedAccount> = audit.mismatches().collect();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].address, pool.sol_vault);
+        assert_eq!(mismatches[0].kind, AccountKind::TokenVault);
+        assert!(audit.to_string().contains("MISMATCH"));
+    }
+}
@@ -0,0 +1,201 @@
+/// Wallet Token Account Check
+///
+/// Every swap moves tokens through the wallet's associated token account for the mint.
+/// Startup creates any that are missing, but an external process can close one later
+/// and every swap of that mint then fails. `run_ata_check` re-checks them periodically
+/// and recreates a missing one with the idempotent create instruction.
+
+use crate::constants::{ATA_CREATION_COMPUTE_UNIT_LIMIT, ATA_CREATION_COMPUTE_UNIT_PRICE};
+use crate::error::{BotError, BotResult};
+use crate::metrics::METRICS;
+use crate::rpc::AccountFetcher;
+use crate::signer::WalletSigner;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// A wallet's token account for one traded mint
+#[derive(Clone)]
+pub struct WatchedAta {
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    /// Owns the account and pays to recreate it
+    pub wallet: WalletSigner,
+    pub address: Pubkey,
+}
+
+impl WatchedAta {
+    pub fn new(wallet: WalletSigner, mint: Pubkey, token_program: Pubkey) -> Self {
+        let address =
+            get_associated_token_address_with_program_id(&wallet.pubkey(), &mint, &token_program);
+        Self {
+            mint,
+            token_program,
+            wallet,
+            address,
+        }
+    }
+}
+
+/// Prioritized transaction creating the wallet's ATA for `mint`; a no-op if it exists
+pub fn create_ata_transaction(
+    wallet: &dyn Signer,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    blockhash: Hash,
+) -> Transaction {
+    let create_ata_ix =
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            &wallet.pubkey(),
+            &wallet.pubkey(),
+            mint,
+            token_program,
+        );
+    Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_price(ATA_CREATION_COMPUTE_UNIT_PRICE),
+            ComputeBudgetInstruction::set_compute_unit_limit(ATA_CREATION_COMPUTE_UNIT_LIMIT),
+            create_ata_ix,
+        ],
+        Some(&wallet.pubkey()),
+        &[wallet],
+        blockhash,
+    )
+}
+
+/// Sends the transaction recreating a missing ATA
+pub trait AtaCreator: Send + Sync {
+    fn create_ata(&self, ata: &WatchedAta) -> BotResult<Signature>;
+}
+
+impl AtaCreator for RpcClient {
+    fn create_ata(&self, ata: &WatchedAta) -> BotResult<Signature> {
+        let blockhash = self.get_latest_blockhash()?;
+        let tx = create_ata_transaction(ata.wallet.as_ref(), &ata.mint, &ata.token_program, blockhash);
+        self.send_and_confirm_transaction(&tx).map_err(|e| {
+            BotError::WalletError(format!("Failed to create token account for {}: {}", ata.mint, e))
+        })
+    }
+}
+
+/// Check every ATA once, recreating the missing ones. Returns how many are still
+/// missing afterwards (also exported as the `missing_atas` gauge).
+pub fn check_atas(fetcher: &dyn AccountFetcher, creator: &dyn AtaCreator, atas: &[WatchedAta]) -> usize {
+    let addresses: Vec<Pubkey> = atas.iter().map(|ata| ata.address).collect();
+    let accounts = match fetcher.fetch_multiple_accounts(&addresses) {
+        Ok(accounts) => accounts,
+        Err(e) => {
+            // Can't tell either way; keep the last reading
+            warn!("Token account check failed: {}", e);
+            return METRICS.missing_atas() as usize;
+        }
+    };
+
+    let mut missing = 0;
+    for (ata, account) in atas.iter().zip(&accounts) {
+        if account.is_some() {
+            continue;
+        }
+        warn!(
+            "Token account {} of wallet {} for mint {} is missing, recreating it",
+            ata.address,
+            ata.wallet.pubkey(),
+            ata.mint
+        );
+        match creator.create_ata(ata) {
+            Ok(signature) => info!("Recreated token account {}: {}", ata.address, signature),
+            Err(e) => {
+                error!("{}", e);
+                missing += 1;
+            }
+        }
+    }
+    METRICS.set_missing_atas(missing as u64);
+    missing
+}
+
+/// Re-check the ATAs every `interval` for as long as the bot runs
+pub async fn run_ata_check(rpc_client: Arc<RpcClient>, atas: Vec<WatchedAta>, interval: Duration) {
+    let atas = Arc::new(atas);
+    let mut ticker = tokio::time::interval(interval);
+    // The first tick fires at once, and startup has only just checked them
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        // The read and the confirmed recreation block, so they run off the async workers
+        let (rpc_client, atas) = (rpc_client.clone(), atas.clone());
+        let checked = tokio::task::spawn_blocking(move || {
+            check_atas(rpc_client.as_ref(), rpc_client.as_ref(), &atas)
+        })
+        .await;
+        if let Err(e) = checked {
+            error!("Token account check panicked: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::MockRpcClient;
+    use solana_sdk::account::Account;
+    use solana_sdk::signature::Keypair;
+    use std::sync::Mutex;
+
+    /// Records recreations and "lands" them in the mock RPC
+    struct RecordingCreator<'a> {
+        rpc: &'a MockRpcClient,
+        created: Mutex<Vec<Pubkey>>,
+    }
+
+    impl AtaCreator for RecordingCreator<'_> {
+        fn create_ata(&self, ata: &WatchedAta) -> BotResult<Signature> {
+            self.created.lock().unwrap().push(ata.address);
+            self.rpc.add_account(ata.address, token_account());
+            Ok(Signature::default())
+        }
+    }
+
+    fn token_account() -> Account {
+        Account {
+            lamports: 2_039_280,
+            data: vec![0; 165],
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_ata_closed_mid_run_is_recreated() {
+        let wallet: WalletSigner = Arc::new(Keypair::new());
+        let atas: Vec<WatchedAta> = (0..2)
+            .map(|_| WatchedAta::new(wallet.clone(), Pubkey::new_unique(), spl_token::ID))
+            .collect();
+        let rpc = MockRpcClient::new();
+        for ata in &atas {
+            rpc.add_account(ata.address, token_account());
+        }
+        let creator = RecordingCreator {
+            rpc: &rpc,
+            created: Mutex::new(Vec::new()),
+        };
+
+        assert_eq!(check_atas(&rpc, &creator, &atas), 0);
+        assert!(creator.created.lock().unwrap().is_empty());
+
+        // Closed by something outside the bot
+        rpc.remove_account(&atas[1].address);
+        assert_eq!(check_atas(&rpc, &creator, &atas), 0);
+        assert_eq!(*creator.created.lock().unwrap(), vec![atas[1].address]);
+        assert!(rpc.get_account(&atas[1].address).is_some());
+    }
+}
@@ -1,40 +1,81 @@
-use crate::config::Config;
+use crate::alert;
+use crate::ata_check::{create_ata_transaction, run_ata_check, WatchedAta};
+use crate::config::{BlockhashSource, Config, ExecutionMode, MintConfig, TransactionVersion};
 use crate::constants::{
-    ATA_CREATION_COMPUTE_UNIT_LIMIT, ATA_CREATION_COMPUTE_UNIT_PRICE,
-    DEFAULT_BLOCKHASH_REFRESH_INTERVAL_SECS, DEFAULT_LOOKUP_TABLE_PUBKEY,
+    ADAPTIVE_DELAY_LATENCY_FACTOR, DEFAULT_BLOCKHASH_REFRESH_INTERVAL_SECS,
+    DEFAULT_LOOKUP_TABLE_PUBKEY, DEFAULT_RPC_CACHE_TTL_SECS, IDLE_BACKOFF_AFTER_ITERATIONS,
+    LATENCY_EMA_ALPHA, MAX_MULTIPLE_ACCOUNTS_BATCH, MAX_TRANSACTION_ACCOUNTS, POOL_REFRESH_INTERVAL_SECS, SENT_CONFIRMATION_POLL_INTERVAL_MS, SOL_DECIMALS,
+    WALLET_BALANCE_CHECK_INTERVAL_SECS, WS_RECONNECT_DELAY_SECS,
+    set_base_mint,
 };
-use crate::database::Database;
+use crate::database::{landed_profit, Database, TradeLog};
 use crate::error::{BotError, BotResult};
+use crate::failover::WalletFailover;
+use crate::health::{start_health_server, ShutdownHandler};
+use crate::in_flight::{
+    CapitalLimiter, CapitalReservation, InFlightGuard, InFlightToken, TradeCooldown,
+};
 use crate::jito::{JitoClient, JITO_NYC};
-use crate::refresh::initialize_pool_data;
-use crate::transaction::build_and_send_transaction;
+use crate::latency::{
+    adaptive_delay, notify_account_updates, startup_delay, subscribe_slot_blockhash, AccountSubscriber,
+    BlockhashCache, FeedKind, IdleBackoff, LatencyEma, LatencyTracker,
+};
+use crate::metrics::{run_history_recorder, run_metrics_pusher, METRICS};
+use crate::mint_checks::MintRestrictions;
+use crate::observation_refresh::ObservationRefresher;
+use crate::opportunity::ArbitrageOpportunity;
+use crate::opportunity_feed::OpportunityFeed;
+use crate::pool_init::PoolInitLimiter;
+use crate::pools::{format_token_amount, MintPoolData};
+use crate::ranking::pick_top;
+use crate::refresh::{
+    check_account_freshness, initialize_pool_data, refresh_pools, restore_pools, update_pool_states, PoolRefresh,
+};
+use crate::rpc::{run_pinned_refresh, AccountFetcher, RpcPool};
+use crate::send_queue::SendQueue;
+use crate::signer::SignerSource;
+use crate::trade_events::TradeEventWriter;
+use crate::transaction::{
+    build_and_send_split_transactions, build_and_send_transaction, compute_unit_budget,
+    estimate_compute_unit_price, estimated_fees, executor_base_accounts, landed_fees,
+    poll_sent_confirmations, pool_candidates, run_sender_blockhash_refresher, select_pools,
+    split_pool_data, with_dex_compute_units, PoolCandidate, PoolSelection, SendResult,
+};
+use crate::watchdog::TradeWatchdog;
+use solana_client::nonblocking::rpc_client::RpcClient as AsyncRpcClient;
 use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
 use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::clock::Slot;
 use solana_sdk::hash::Hash;
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::Keypair;
 use solana_sdk::signer::Signer;
-use solana_sdk::{
-    address_lookup_table::state::AddressLookupTable, compute_budget::ComputeBudgetInstruction,
-};
-use spl_associated_token_account::{
-    get_associated_token_address, get_associated_token_address_with_program_id,
-};
+use solana_sdk::sysvar;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use spl_associated_token_account::get_associated_token_address;
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::Mutex;
-use tracing::{error, info, warn};
+use tokio::sync::{Mutex, Notify};
+use tracing::{debug, error, info, warn};
 
-pub async fn run_bot(config_path: &str) -> BotResult<()> {
-    let config = Config::load(config_path)?;
+pub async fn run_bot(config: Config, dry_run: bool) -> BotResult<()> {
+    if let Some(base_mint) = config.base_mint_override() {
+        warn!("Using custom base mint {} instead of wSOL", base_mint);
+        set_base_mint(base_mint);
+    }
     info!("Configuration loaded successfully");
+    if let Some(webhook_url) = &config.bot.alert_webhook_url {
+        alert::install(webhook_url);
+    }
 
     // Initialize Database (Optional)
     let db = if let Ok(db_url) = std::env::var("DATABASE_URL") {
         info!("Initializing database connection...");
         match Database::new(&db_url).await {
-            Ok(db) => Some(Arc::new(db)),
+            Ok(db) => Some(Arc::new(TradeLog::new(db, config.bot.db_retry_buffer_size()))),
             Err(e) => {
                 warn!("Failed to initialize database: {}. Running without persistence.", e);
                 None
@@ -45,12 +86,49 @@ pub async fn run_bot(config_path: &str) -> BotResult<()> {
         None
     };
 
-    let rpc_client = Arc::new(RpcClient::new(config.rpc.url.clone()));
+    let rpc_timeout = config.rpc.timeout();
+    let send_timeout = config.rpc.send_timeout();
+    let rpc_client = Arc::new(RpcClient::new_with_timeout(config.rpc.url.clone(), rpc_timeout));
+
+    let wallet_signer = SignerSource::from_wallet_config(&config.wallet).load()?;
+    info!("Wallet loaded: {}", wallet_signer.pubkey());
+    let backup_signer = match &config.backup_wallet {
+        Some(backup_wallet) => {
+            let signer = SignerSource::from_wallet_config(backup_wallet).load()?;
+            if signer.pubkey() == wallet_signer.pubkey() {
+                return Err(BotError::ConfigError(
+                    "backup_wallet must be a different wallet than wallet".to_string(),
+                ));
+            }
+            info!("Backup wallet loaded: {}", signer.pubkey());
+            Some(signer)
+        }
+        None => None,
+    };
+    let wallets = Arc::new(WalletFailover::new(
+        wallet_signer.clone(),
+        backup_signer.clone(),
+        config.bot.wallet_failover_after(),
+        config.bot.wallet_min_balance_lamports,
+    ));
 
     // Initialize Jito Client
-    let wallet_kp_for_jito = load_keypair(&config.wallet.private_key)?;
-    let jito_client = match JitoClient::new(JITO_NYC, Arc::new(wallet_kp_for_jito)).await {
-        Ok(client) => {
+    let pinned_tip_accounts = config.jito_tip_accounts();
+    let tip_accounts_pinned = pinned_tip_accounts.is_some();
+    let jito_client = match JitoClient::new(
+        JITO_NYC,
+        wallet_signer.clone(),
+        pinned_tip_accounts,
+    )
+    .await
+    {
+        Ok(mut client) => {
+            if !tip_accounts_pinned {
+                match client.refresh_tip_accounts().await {
+                    Ok(count) => info!("Fetched {} Jito tip accounts from block engine", count),
+                    Err(e) => warn!("Failed to fetch Jito tip accounts: {}. Using built-in list.", e),
+                }
+            }
             info!("Jito Client initialized successfully (Elite MEV enabled)");
             Some(Arc::new(client))
         }
@@ -65,22 +143,38 @@ pub async fn run_bot(config_path: &str) -> BotResult<()> {
             spam_config
                 .sending_rpc_urls
                 .iter()
-                .map(|url| Arc::new(RpcClient::new(url.clone())))
+                .map(|url| Arc::new(RpcClient::new_with_timeout(url.clone(), send_timeout)))
                 .collect::<Vec<_>>()
         } else {
-            vec![rpc_client.clone()]
+            vec![Arc::new(RpcClient::new_with_timeout(config.rpc.url.clone(), send_timeout))]
         }
     } else {
-        vec![rpc_client.clone()]
+        vec![Arc::new(RpcClient::new_with_timeout(config.rpc.url.clone(), send_timeout))]
     };
 
-    let wallet_kp = load_keypair(&config.wallet.private_key)?;
-    info!("Wallet loaded: {}", wallet_kp.pubkey());
-
-    let initial_blockhash = rpc_client
-        .get_latest_blockhash()
+    let (initial_blockhash, last_valid_block_height) = rpc_client
+        .get_latest_blockhash_with_commitment(rpc_client.commitment())
         .map_err(|e| BotError::rpc_retryable(config.rpc.url.clone(), format!("Failed to get initial blockhash: {}", e)))?;
-    let cached_blockhash = Arc::new(Mutex::new(initial_blockhash));
+    let cached_blockhash = Arc::new(BlockhashCache::with_expiry(initial_blockhash, last_valid_block_height));
+
+    tokio::spawn(run_history_recorder());
+    if let Some(push_config) = config.metrics_push.clone() {
+        info!(
+            "Pushing metrics to {} every {:?}",
+            push_config.pushgateway_url,
+            push_config.interval()
+        );
+        tokio::spawn(run_metrics_pusher(METRICS.clone(), push_config));
+    }
+    let opportunity_feed = match &config.opportunity_feed {
+        Some(feed_config) => Some(
+            OpportunityFeed::bind(&feed_config.bind_address, feed_config.buffer_size()).await?,
+        ),
+        None => None,
+    };
+
+    // Per-endpoint latencies of the pool reads, slot feed and sends, served on /status
+    let latency_tracker = Arc::new(LatencyTracker::new());
 
     let refresh_interval = Duration::from_secs(DEFAULT_BLOCKHASH_REFRESH_INTERVAL_SECS);
     let blockhash_client = rpc_client.clone();
@@ -90,6 +184,60 @@ pub async fn run_bot(config_path: &str) -> BotResult<()> {
         blockhash_refresher(blockhash_client, blockhash_cache, refresh_interval, rpc_url_for_task).await;
     });
 
+    if config.bot.use_websocket_blockhash() {
+        let ws_url = config.rpc.ws_url();
+        // Every slot fetches a blockhash, so through the async client: the blocking one
+        // would hold up a runtime worker for each round trip
+        let slot_client = AsyncRpcClient::new_with_timeout(config.rpc.url.clone(), rpc_timeout);
+        let slot_cache = cached_blockhash.clone();
+        let slot_latency = latency_tracker.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) =
+                    subscribe_slot_blockhash(&ws_url, &slot_cache, &slot_client, &slot_latency).await
+                {
+                    warn!("Slot subscription failed: {}. Falling back to polling.", e);
+                }
+                tokio::time::sleep(Duration::from_secs(WS_RECONNECT_DELAY_SECS)).await;
+            }
+        });
+    }
+
+    // Mints listed more than once share a guard, so their sends never overlap
+    let in_flight = InFlightGuard::new();
+    let capital_limiter = config.bot.max_total_input_lamports.map(CapitalLimiter::new);
+
+    let rpc_pool = Arc::new(RpcPool::new(
+        vec![config.rpc.url.clone()],
+        DEFAULT_RPC_CACHE_TTL_SECS,
+        rpc_timeout,
+    ));
+
+    if let Some(port) = config.bot.health_port {
+        let (rpc_pool, wallets, latency) = (rpc_pool.clone(), wallets.clone(), latency_tracker.clone());
+        tokio::spawn(async move {
+            let shutdown_handler = Arc::new(ShutdownHandler::new());
+            if let Err(e) =
+                start_health_server(port, shutdown_handler, rpc_pool, Some(wallets), Some(latency)).await
+            {
+                error!("Health server on port {} stopped: {}", port, e);
+            }
+        });
+    }
+
+    // Shared across mint loops: any landed trade proves the bot is healthy
+    let watchdog = config.watchdog.as_ref().map(|watchdog_config| {
+        Arc::new(TradeWatchdog::new(
+            Duration::from_secs(watchdog_config.max_idle_minutes * 60),
+            watchdog_config
+                .resume_cooldown_minutes
+                .map(|minutes| Duration::from_secs(minutes * 60)),
+        ))
+    });
+
+    // Mints left out of trading, e.g. when the RPC never returned their mint account
+    let mut skipped_mints = HashSet::new();
+    let mut watched_atas = Vec::new();
     for mint_config in &config.routing.mint_config_list {
         // Get the mint account info to check owner
         let mint_pubkey = Pubkey::from_str(&mint_config.mint)
@@ -98,67 +246,70 @@ pub async fn run_bot(config_path: &str) -> BotResult<()> {
                 source: e,
             })?;
 
-        let mint_account = rpc_client
-            .get_account(&mint_pubkey)
-            .map_err(|e| BotError::AccountFetchError {
-                address: mint_pubkey,
-                reason: format!("Failed to fetch mint account: {}", e),
-            })?;
-        
-        let mint_owner = mint_account.owner;
-        let wallet_token_account = get_associated_token_address_with_program_id(
-            &wallet_kp.pubkey(),
-            &mint_pubkey,
-            &mint_owner,
-        );
+        let mint_account = match fetch_mint_account(rpc_pool.as_ref(), &mint_pubkey).await {
+            Ok(account) => account,
+            Err(e) => {
+                error!("Giving up on mint {}: {}", mint_config.mint, e);
+                skipped_mints.insert(mint_config.mint.clone());
+                continue;
+            }
+        };
 
-        println!("   Token mint: {}", mint_config.mint);
-        println!("   Wallet token ATA: {}", wallet_token_account);
-        // Check if the PWEASE token account exists and create it if it doesn't
-        println!("\n   Checking if token account exists...");
-        loop {
-            match rpc_client.get_account(&wallet_token_account) {
-                Ok(_) => {
-                    println!("   token account exists!");
-                    break;
+        match MintRestrictions::parse(&mint_account.data) {
+            Ok(restrictions) => {
+                let issues = restrictions.issues();
+                for issue in &issues {
+                    warn!("Mint {} has {}", mint_config.mint, issue);
                 }
-                Err(_) => {
-                    println!("   token account does not exist. Creating it...");
-
-                    // Create the instruction to create the associated token account
-                    let create_ata_ix =
-                            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
-                                &wallet_kp.pubkey(), // Funding account
-                                &wallet_kp.pubkey(), // Wallet account
-                                &mint_pubkey,        // Token mint
-                                &spl_token::ID,      // Token program
-                            );
+                if !issues.is_empty() && config.bot.strict_token_checks() {
+                    warn!("Skipping mint {} (strict_token_checks enabled)", mint_config.mint);
+                    skipped_mints.insert(mint_config.mint.clone());
+                    continue;
+                }
+            }
+            Err(e) => warn!("Could not check restrictions for mint {}: {}", mint_config.mint, e),
+        }
 
-                    // Get a recent blockhash
-                    let blockhash = rpc_client.get_latest_blockhash()?;
+        let mint_owner = mint_account.owner;
+        // The backup wallet gets its token accounts too, so failover can trade at once
+        for signer in std::iter::once(&wallet_signer).chain(backup_signer.as_ref()) {
+            let watched_ata = WatchedAta::new(signer.clone(), mint_pubkey, mint_owner);
+            let wallet_token_account = watched_ata.address;
+            watched_atas.push(watched_ata);
 
-                    let compute_unit_price_ix =
-                        ComputeBudgetInstruction::set_compute_unit_price(ATA_CREATION_COMPUTE_UNIT_PRICE);
-                    let compute_unit_limit_ix =
-                        ComputeBudgetInstruction::set_compute_unit_limit(ATA_CREATION_COMPUTE_UNIT_LIMIT);
+            println!("   Token mint: {}", mint_config.mint);
+            println!("   Wallet token ATA: {}", wallet_token_account);
+            // Check if the PWEASE token account exists and create it if it doesn't
+            println!("\n   Checking if token account exists...");
+            loop {
+                match rpc_client.get_account(&wallet_token_account) {
+                    Ok(_) => {
+                        println!("   token account exists!");
+                        break;
+                    }
+                    Err(_) if dry_run => {
+                        warn!("   token account does not exist (dry run, not creating it)");
+                        break;
+                    }
+                    Err(_) => {
+                        println!("   token account does not exist. Creating it...");
 
-                    // Create the transaction
-                    let create_ata_tx = solana_sdk::transaction::Transaction::new_signed_with_payer(
-                        &[compute_unit_price_ix, compute_unit_limit_ix, create_ata_ix],
-                        Some(&wallet_kp.pubkey()),
-                        &[&wallet_kp],
-                        blockhash,
-                    );
+                        // Get a recent blockhash
+                        let blockhash = rpc_client.get_latest_blockhash()?;
 
-                    // Send the transaction
-                    match rpc_client.send_and_confirm_transaction(&create_ata_tx) {
-                        Ok(sig) => {
-                            println!("   token account created successfully! Signature: {}", sig);
-                        }
-                        Err(e) => {
-                            let err = BotError::WalletError(format!("Failed to create token account for {}: {}", mint_config.mint, e));
-                            error!("{}", err);
-                            return Err(err);
+                        let create_ata_tx =
+                            create_ata_transaction(signer.as_ref(), &mint_pubkey, &mint_owner, blockhash);
+
+                        // Send the transaction
+                        match rpc_client.send_and_confirm_transaction(&create_ata_tx) {
+                            Ok(sig) => {
+                                println!("   token account created successfully! Signature: {}", sig);
+                            }
+                            Err(e) => {
+                                let err = BotError::WalletError(format!("Failed to create token account for {}: {}", mint_config.mint, e));
+                                error!("{}", err);
+                                return Err(err);
+                            }
                         }
                     }
                 }
@@ -166,175 +317,1602 @@ pub async fn run_bot(config_path: &str) -> BotResult<()> {
         }
     }
 
-    for mint_config in &config.routing.mint_config_list {
-        info!("Spawning strategy task for mint: {}", mint_config.mint);
+    // An ATA closed after startup would fail every swap of its mint
+    if let (false, Some(interval)) = (dry_run, config.bot.ata_check_interval()) {
+        tokio::spawn(run_ata_check(rpc_client.clone(), watched_atas, interval));
+    }
 
-        let pool_data = initialize_pool_data(
-            &mint_config.mint,
-            &wallet_kp.pubkey().to_string(),
-            mint_config.raydium_pool_list.as_ref(),
-            mint_config.raydium_cp_pool_list.as_ref(),
-            mint_config.pump_pool_list.as_ref(),
-            mint_config.meteora_dlmm_pool_list.as_ref(),
-            mint_config.whirlpool_pool_list.as_ref(),
-            mint_config.raydium_clmm_pool_list.as_ref(),
-            mint_config.meteora_damm_pool_list.as_ref(),
-            mint_config.solfi_pool_list.as_ref(),
-            mint_config.meteora_damm_v2_pool_list.as_ref(),
-            mint_config.vertigo_pool_list.as_ref(),
-            rpc_client.clone(),
-        )
-        .await?;
+    // Strategy loops only detect; these workers build and send what they queue
+    let send_queue: SendQueue<SendJob> =
+        SendQueue::new(config.bot.send_queue_capacity(), config.bot.send_queue_overflow());
+    if !dry_run {
+        let trade_events = match &config.bot.trade_log_path {
+            Some(path) => Some(Arc::new(TradeEventWriter::open(
+                std::path::Path::new(path),
+                config.bot.trade_log_rotate(),
+            )?)),
+            None => None,
+        };
+        let send_worker = Arc::new(SendWorker {
+            wallets: wallets.clone(),
+            config: config.clone(),
+            rpc_clients: sending_rpc_clients.clone(),
+            jito_client: jito_client.clone(),
+            db: db.clone(),
+            watchdog: watchdog.clone(),
+            trade_events,
+            cached_blockhash: cached_blockhash.clone(),
+            async_rpc_client: Arc::new(AsyncRpcClient::new_with_timeout(
+                config.rpc.url.clone(),
+                rpc_timeout,
+            )),
+            latency_tracker: latency_tracker.clone(),
+        });
+        send_queue.spawn_workers(config.bot.send_workers(), move |job| {
+            let send_worker = send_worker.clone();
+            async move { send_worker.send(job).await }
+        });
+
+        if wallets.min_balance_lamports().is_some() {
+            tokio::spawn(wallet_balance_sampler(
+                Arc::new(AsyncRpcClient::new_with_timeout(config.rpc.url.clone(), rpc_timeout)),
+                wallets.clone(),
+                Duration::from_secs(WALLET_BALANCE_CHECK_INTERVAL_SECS),
+            ));
+        }
+
+        // Sends signed with each endpoint's own blockhash read it from a cache kept fresh here
+        if config.spam.as_ref().map(|spam| spam.blockhash_source()) == Some(BlockhashSource::Sender) {
+            let senders = sending_rpc_clients
+                .iter()
+                .map(|client| Arc::new(AsyncRpcClient::new_with_timeout(client.url(), send_timeout)))
+                .collect();
+            tokio::spawn(run_sender_blockhash_refresher(senders, refresh_interval));
+        }
+
+        // Nothing waits on spam sends, so their confirmations are polled in the background
+        if config.spam.as_ref().map_or(false, |spam| spam.enabled) {
+            let poll_client = rpc_client.clone();
+            let commitment = config.bot.confirm_commitment();
+            tokio::spawn(async move {
+                let mut interval =
+                    tokio::time::interval(Duration::from_millis(SENT_CONFIRMATION_POLL_INTERVAL_MS));
+                loop {
+                    interval.tick().await;
+                    poll_sent_confirmations(poll_client.as_ref(), commitment);
+                }
+            });
+        }
+    }
+
+    // Phase-shift the strategy loops so they don't all hit RPC on the same blockhash
+    let startup_jitter = config.bot.startup_jitter();
+    let mut startup_rng = StdRng::from_entropy();
+
+    // Initialize every mint's pools concurrently, a bounded number of DEX batches at a time
+    let trading_mints: Vec<&MintConfig> = config
+        .routing
+        .mint_config_list
+        .iter()
+        .filter(|mint_config| !skipped_mints.contains(&mint_config.mint))
+        .collect();
+    let total_pools = trading_mints
+        .iter()
+        .map(|mint_config| mint_config.pool_addresses().len())
+        .sum();
+    let init_limiter = PoolInitLimiter::logging(config.bot.init_concurrency(), total_pools);
+    let wallet_pubkey = wallet_signer.pubkey();
+    let initialized_mints = futures::future::join_all(trading_mints.iter().map(|mint_config| {
+        let mint_rpc_client = mint_rpc_client(mint_config, &rpc_client, rpc_timeout);
+        let config = &config;
+        let init_limiter = &init_limiter;
+        let wallet_pubkey = &wallet_pubkey;
+        async move {
+            let pool_data = initialize_mint_pools(
+                config,
+                mint_config,
+                wallet_pubkey,
+                mint_rpc_client.clone(),
+                Some(init_limiter),
+            )
+            .await;
+            (mint_rpc_client, pool_data)
+        }
+    }))
+    .await;
 
-        let mint_pool_data = Arc::new(Mutex::new(pool_data));
+    // Every mint's lookup tables in a few batched reads, shared tables once
+    let fetched_lookup_tables = fetch_lookup_tables(
+        rpc_pool.as_ref(),
+        &trading_mints,
+        config.bot.transaction_version(),
+    )
+    .await;
+
+    let min_pools = config.bot.min_pools_per_mint();
+    // Mint endpoints whose pinned accounts need their own refresh task
+    let mut pinned_mint_pools: Vec<Arc<RpcPool>> = Vec::new();
+    // Whether a quoted route's fee estimate has to include the Jito tip
+    let jito_enabled = jito_client.is_some();
+    for (mint_config, (mint_rpc_client, pool_data)) in trading_mints.into_iter().zip(initialized_mints) {
+        let mut pool_data = match pool_data {
+            Ok(pool_data) => pool_data,
+            Err(e) => {
+                error!("Giving up on mint {}: {}", mint_config.mint, e);
+                continue;
+            }
+        };
+        // A mint short of pools still gets a loop, paused until re-initialization restores them
+        let enough_pools = has_enough_pools(&mint_config.mint, pool_data.dex_pools().len(), min_pools);
+        info!("Spawning strategy task for mint: {}", mint_config.mint);
 
-        let config_clone = config.clone();
         let mint_config_clone = mint_config.clone();
-        let sending_rpc_clients_clone = sending_rpc_clients.clone();
         let cached_blockhash_clone = cached_blockhash.clone();
-        let wallet_bytes = wallet_kp.to_bytes();
-        let wallet_kp_clone = Keypair::from_bytes(&wallet_bytes)
-            .map_err(|e| BotError::WalletError(format!("Failed to clone keypair: {}", e)))?;
-        let jito_client_clone = jito_client.clone();
-        let db_clone = db.clone();
-        
-        let mut lookup_table_accounts = mint_config_clone.lookup_table_accounts.unwrap_or_default();
-        lookup_table_accounts.push(DEFAULT_LOOKUP_TABLE_PUBKEY.to_string());
-
-        let mut lookup_table_accounts_list = vec![];
-
-        for lookup_table_account in lookup_table_accounts {
-            match Pubkey::from_str(&lookup_table_account) {
-                Ok(pubkey) => {
-                    match rpc_client.get_account(&pubkey) {
-                        Ok(account) => {
-                            match AddressLookupTable::deserialize(&account.data) {
-                                Ok(lookup_table) => {
-                                    let lookup_table_account = AddressLookupTableAccount {
-                                        key: pubkey,
-                                        addresses: lookup_table.addresses.into_owned(),
-                                    };
-                                    lookup_table_accounts_list.push(lookup_table_account);
-                                    info!("   Successfully loaded lookup table: {}", pubkey);
-                                }
-                                Err(e) => {
-                                    error!("   Failed to deserialize lookup table {}: {}", pubkey, e);
-                                    continue;
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            error!("   Failed to fetch lookup table account {}: {}", pubkey, e);
-                            continue;
-                        }
-                    }
+        let watchdog_clone = watchdog.clone();
+        let in_flight_clone = in_flight.clone();
+        let capital_limiter_clone = capital_limiter.clone();
+        let send_queue_clone = send_queue.clone();
+        let opportunity_feed_clone = opportunity_feed.clone();
+        let max_account_staleness = config.bot.max_account_staleness_slots;
+        let observation_cadences = config.observation_refresh();
+        let ranking = config.bot.ranking();
+        let max_split_pools = config.bot.max_split_pools();
+        let refresh_client = mint_rpc_client.clone();
+        // The pool the loop reads pool state through, and the one holding the mint's pins
+        let state_pool = mint_rpc_pool(mint_config, &rpc_pool, rpc_timeout);
+        let state_endpoint = state_pool
+            .endpoint_health()
+            .first()
+            .map_or_else(String::new, |endpoint| endpoint.url.clone());
+        let latency_tracker_clone = latency_tracker.clone();
+        let loop_config = config.clone();
+
+        let lookup_table_accounts_list = mint_lookup_tables(
+            &config,
+            mint_config,
+            &pool_data,
+            &wallet_signer.pubkey(),
+            &fetched_lookup_tables,
+        );
+
+        // Keep only the pools that fit in a single transaction
+        let (_, selection) = plan_pool_selection(
+            &config,
+            &pool_data,
+            &wallet_signer.pubkey(),
+            &lookup_table_accounts_list,
+        );
+
+        // Pools that don't fit in one transaction are sent in further transactions
+        // instead of being dropped when max_pools_per_tx is set
+        let split = config.bot.max_pools_per_tx().is_some();
+
+        // Global and fee configs read by every trade stay cached past the TTL
+        let mut shared_accounts = pool_data.shared_accounts();
+        shared_accounts.extend(config.bot.pinned_accounts());
+        shared_accounts.extend(config.jito_tip_accounts().unwrap_or_default());
+        if let Err(e) = state_pool.pin_accounts(&shared_accounts).await {
+            warn!("Failed to pin shared accounts for mint {}: {}", mint_config.mint, e);
+        }
+        if !Arc::ptr_eq(&state_pool, &rpc_pool) && state_pool.pinned_count() > 0 {
+            pinned_mint_pools.push(state_pool.clone());
+        }
+
+        if dry_run {
+            selection.log_report(&mint_config.mint);
+            if let Some(max_pools_per_tx) = config.bot.max_pools_per_tx() {
+                match split_pool_data(
+                    &config,
+                    &pool_data,
+                    &wallet_signer.pubkey(),
+                    &lookup_table_accounts_list,
+                    max_pools_per_tx,
+                ) {
+                    Ok(groups) => info!(
+                        "  Split into {} transactions of at most {} pools",
+                        groups.len(),
+                        max_pools_per_tx
+                    ),
+                    Err(e) => warn!("  Failed to split pools into transactions: {}", e),
                 }
-                Err(e) => {
-                    error!("   Invalid lookup table pubkey string {}: {}", lookup_table_account, e);
-                    continue;
+            }
+            continue;
+        }
+        // Pools re-initialization may bring back: the configured ones the route has room for
+        let route_pools: HashSet<Pubkey> = mint_config
+            .pool_addresses()
+            .into_iter()
+            .filter_map(|address| Pubkey::from_str(address).ok())
+            .filter(|pool| split || !selection.skipped.contains(pool))
+            .collect();
+        if !split && !selection.skipped.is_empty() {
+            pool_data.retain_pools(&selection.selected.iter().copied().collect::<HashSet<_>>());
+        }
+
+        // Prime the cache with the state the loop reads so its first iteration is served
+        // from the warm-up rather than a second round of reads
+        if config.bot.warmup_on_startup() {
+            let warmup_accounts: Vec<Pubkey> = pool_data
+                .dex_pools()
+                .iter()
+                .flat_map(|pool| pool.state_accounts())
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            match state_pool.warm_up(&warmup_accounts).await {
+                Ok(report) => {
+                    for missing in &report.missing {
+                        warn!("Mint {}: account {} not found during warm-up", mint_config.mint, missing);
+                    }
                 }
+                Err(e) => warn!("Cache warm-up failed for mint {}: {}", mint_config.mint, e),
             }
         }
 
+        // Only a mint that backs off while idle has a sleep for pool updates to cut short
+        let pool_updates = (config.bot.use_websocket_pool_updates() && mint_config.max_idle_delay().is_some())
+            .then(|| {
+                let updates = Arc::new(Notify::new());
+                tokio::spawn(notify_account_updates(
+                    AccountSubscriber::new(config.rpc.ws_url(), latency_tracker.clone()),
+                    pool_update_accounts(&pool_data),
+                    updates.clone(),
+                ));
+                updates
+            });
+
+        let mint_context = Arc::new(MintContext {
+            mint: mint_config.mint.clone(),
+            pool_data: Mutex::new(pool_data),
+            lookup_tables: lookup_table_accounts_list,
+            cooldown: TradeCooldown::new(config.bot.trade_cooldown()),
+            execution_mode: mint_config.execution_mode(),
+            jito_min_profit_lamports: mint_config.jito_min_profit_lamports(),
+        });
+        let initial_delay = startup_delay(startup_jitter, &mut startup_rng);
+
         tokio::spawn(async move {
+            if !initial_delay.is_zero() {
+                debug!(
+                    "Delaying strategy loop for mint {} by {:?}",
+                    mint_config_clone.mint, initial_delay
+                );
+                tokio::time::sleep(initial_delay).await;
+            }
             let process_delay = Duration::from_millis(mint_config_clone.process_delay);
+            let min_profit = mint_config_clone.min_profit_lamports();
+            let use_adaptive_delay = mint_config_clone.adaptive_delay.unwrap_or(false);
+            let min_delay = Duration::from_millis(mint_config_clone.min_process_delay());
+            let max_delay = Duration::from_millis(mint_config_clone.max_process_delay());
+            let mut latency_ema = LatencyEma::new(LATENCY_EMA_ALPHA);
+            let mut idle_backoff = mint_config_clone
+                .max_idle_delay()
+                .map(|max_delay| IdleBackoff::new(IDLE_BACKOFF_AFTER_ITERATIONS, max_delay));
+            // Set when a watched pool account changed during the last sleep
+            let mut pool_moved = false;
+            let pool_refresh_interval = Duration::from_secs(POOL_REFRESH_INTERVAL_SECS);
+            let mut last_pool_refresh = std::time::Instant::now();
+            // Set while fewer than min_pools are left; re-checked every refresh
+            let mut paused_for_pools = !enough_pools;
+            let mut observations = ObservationRefresher::new(
+                &mint_context.pool_data.lock().await.dex_pools(),
+                &observation_cadences,
+                std::time::Instant::now(),
+            );
             info!("Strategy loop started for mint: {}", mint_config_clone.mint);
 
             loop {
-                let latest_blockhash = {
-                    let guard = cached_blockhash_clone.lock().await;
-                    *guard
-                };
-
-                // Scope to hold lock only during transaction building
-                let signatures = {
-                    let guard = mint_pool_data.lock().await;
-                    
-                    // Pass jito_client option (converting Arc<T> to &T)
-                    build_and_send_transaction(
-                        &wallet_kp_clone,
-                        &config_clone,
-                        &*guard,
-                        &sending_rpc_clients_clone,
-                        latest_blockhash,
-                        &lookup_table_accounts_list,
-                        jito_client_clone.as_deref(), 
-                    )
-                    .await
-                };
+                if std::mem::take(&mut pool_moved) {
+                    if let Some(backoff) = idle_backoff.as_mut() {
+                        backoff.reset();
+                    }
+                }
+                if let Some(watchdog) = &watchdog_clone {
+                    if !watchdog.trading_allowed() {
+                        tokio::time::sleep(process_delay).await;
+                        continue;
+                    }
+                }
 
-                match signatures {
-                    Ok(signatures) => {
-                        for signature in signatures {
-                            // Log successful attempt to DB if available
-                            if !signature.to_string().is_empty() && signature != solana_sdk::signature::Signature::default() {
-                                if let Some(db) = &db_clone {
-                                    let _ = db.log_trade(
-                                        &mint_config_clone.mint, 
-                                        0, // Profit placeholder
-                                        &signature.to_string(), 
-                                        &["All pools".to_string()], // Placeholder
-                                        0, 
-                                        0
-                                    ).await;
+                // Pools missing from the route are re-initialized and pools closed on-chain
+                // dropped; the loop only stops once every pool it had was closed
+                if last_pool_refresh.elapsed() >= pool_refresh_interval {
+                    last_pool_refresh = std::time::Instant::now();
+                    let (pool_count, wallet) = {
+                        let pool_data = mint_context.pool_data.lock().await;
+                        (pool_data.dex_pools().len(), pool_data.wallet_account)
+                    };
+                    if pool_count < route_pools.len() {
+                        match initialize_mint_pools(
+                            &loop_config,
+                            &mint_config_clone,
+                            &wallet,
+                            refresh_client.clone(),
+                            None,
+                        )
+                        .await
+                        {
+                            Ok(reinitialized) => {
+                                let mut pool_data = mint_context.pool_data.lock().await;
+                                let restored = restore_pools(&mut pool_data, reinitialized, &route_pools);
+                                if restored > 0 {
+                                    info!("Mint {}: re-initialized {} pool(s)", mint_config_clone.mint, restored);
                                 }
                             }
+                            Err(e) => alert::log_error(
+                                &format!("Mint {}: pool re-initialization failed", mint_config_clone.mint),
+                                &e,
+                            ),
                         }
                     }
-                    Err(e) => {
-                        error!(
-                            "Error sending transaction for mint {}: {}",
+                    let mut pool_data = mint_context.pool_data.lock().await;
+                    match refresh_pools(refresh_client.as_ref(), &mut pool_data) {
+                        Ok(refresh) => match pool_status(&mint_config_clone.mint, &refresh, min_pools) {
+                            PoolStatus::Closed => {
+                                error!(
+                                    "Mint {}: every pool was closed on-chain, stopping its strategy loop",
+                                    mint_config_clone.mint
+                                );
+                                break;
+                            }
+                            PoolStatus::Paused => paused_for_pools = true,
+                            PoolStatus::Trading => {
+                                if paused_for_pools {
+                                    info!(
+                                        "Mint {}: {} pools available, resuming its strategy loop",
+                                        mint_config_clone.mint, refresh.remaining
+                                    );
+                                }
+                                paused_for_pools = false;
+                            }
+                        },
+                        Err(e) => {
+                            alert::log_error(&format!("Mint {}: pool refresh failed", mint_config_clone.mint), &e)
+                        }
+                    }
+                    observations.track(&pool_data.dex_pools(), &observation_cadences, std::time::Instant::now());
+                }
+                if paused_for_pools {
+                    tokio::time::sleep(process_delay).await;
+                    continue;
+                }
+
+                // Dynamic fees move with every swap, far sooner than the pool refresh runs
+                let due_observations = observations.due(std::time::Instant::now());
+                if !due_observations.is_empty() {
+                    let client = refresh_client.clone();
+                    let addresses = due_observations.clone();
+                    match tokio::task::spawn_blocking(move || client.fetch_multiple_accounts(&addresses)).await {
+                        Ok(Ok(fetched)) => {
+                            let mut pool_data = mint_context.pool_data.lock().await;
+                            observations.apply(
+                                pool_data.dex_pools_mut(),
+                                &due_observations,
+                                &fetched,
+                                std::time::Instant::now(),
+                            );
+                        }
+                        Ok(Err(e)) => alert::log_error(
+                            &format!("Mint {}: observation account refresh failed", mint_config_clone.mint),
+                            &e,
+                        ),
+                        Err(e) => warn!(
+                            "Mint {}: observation account refresh panicked: {}",
                             mint_config_clone.mint, e
+                        ),
+                    }
+                }
+
+                let in_flight_token = match in_flight_clone.try_begin(&mint_config_clone.mint) {
+                    Some(token) => token,
+                    None => {
+                        debug!("Mint {}: previous send still in flight, skipping iteration", mint_config_clone.mint);
+                        tokio::time::sleep(process_delay).await;
+                        continue;
+                    }
+                };
+
+                let iteration_start = std::time::Instant::now();
+                let latest_blockhash = cached_blockhash_clone.get();
+                METRICS.set_blockhash_age(cached_blockhash_clone.age());
+
+                // Quotes price this iteration's reserves, not the ones seen at startup
+                let read_start = std::time::Instant::now();
+                let pool_states =
+                    update_pool_states(state_pool.as_ref(), &mut *mint_context.pool_data.lock().await).await;
+                // Only reads that reached the endpoint; pins are served locally
+                if let Ok(Some(_)) = pool_states {
+                    latency_tracker_clone
+                        .record_endpoint(FeedKind::Rpc, &state_endpoint, read_start.elapsed())
+                        .await;
+                }
+                let state_slot = match pool_states {
+                    Ok(slot) => slot,
+                    Err(e) => {
+                        alert::log_error(
+                            &format!("Mint {}: failed to read pool state, skipping", mint_config_clone.mint),
+                            &e,
                         );
+                        tokio::time::sleep(process_delay).await;
+                        continue;
+                    }
+                };
+                // Don't quote from pool state an RPC node served from a lagging slot
+                if let (Some(max_staleness), Some(latest_slot), Some(state_slot)) =
+                    (max_account_staleness, cached_blockhash_clone.slot(), state_slot)
+                {
+                    if let Err(e) = check_account_freshness(state_slot, latest_slot, max_staleness) {
+                        warn!("Mint {}: {}, skipping", mint_config_clone.mint, e);
+                        tokio::time::sleep(process_delay).await;
+                        continue;
                     }
                 }
 
-                tokio::time::sleep(process_delay).await;
+                // The full route is sized on-chain; quoted pairs compete with it when they
+                // show an edge
+                let opportunity = {
+                    let pool_data = mint_context.pool_data.lock().await;
+                    let mut candidates = vec![ArbitrageOpportunity::for_pools(&pool_data)];
+                    candidates.extend(ArbitrageOpportunity::quote_pairs(
+                        &pool_data,
+                        mint_config_clone.max_input_lamports,
+                    ));
+                    if max_split_pools > 1 {
+                        candidates.extend(ArbitrageOpportunity::quote_splits(
+                            &pool_data,
+                            mint_config_clone.max_input_lamports,
+                            max_split_pools,
+                            MAX_TRANSACTION_ACCOUNTS,
+                        ));
+                    }
+                    // Every quoted edge goes to the feed, not just the one picked to send
+                    if let Some(feed) = &opportunity_feed_clone {
+                        for candidate in &candidates[1..] {
+                            feed.publish(candidate);
+                        }
+                    }
+                    // A quoted route pays its fees out of the quoted edge, so it is dropped
+                    // unless what is left clears the mint's minimum profit; the route sized
+                    // on-chain is held to it by the executor
+                    let net_profit = |opportunity: &ArbitrageOpportunity| {
+                        (opportunity.input_amount > 0).then(|| {
+                            let via_jito = send_jito_client(
+                                mint_context.execution_mode,
+                                jito_enabled.then_some(()),
+                                opportunity.expected_profit,
+                                mint_context.jito_min_profit_lamports,
+                            )
+                            .is_some();
+                            let fees = estimated_fees(&loop_config, &pool_data, via_jito);
+                            opportunity.net_profit(
+                                fees.priority_fee_lamports,
+                                fees.tip_lamports,
+                                fees.base_fee_lamports,
+                                opportunity.transfer_fees,
+                            )
+                        })
+                    };
+                    candidates.retain(|candidate| {
+                        candidate.input_amount == 0 || candidate.meets_threshold(min_profit)
+                    });
+                    candidates.retain(|candidate| match net_profit(candidate) {
+                        Some(net) if !candidate.should_send(net, min_profit) => {
+                            debug!(
+                                "Mint {}: {} lamports quoted through {:?} nets {} after fees, skipping",
+                                mint_config_clone.mint, candidate.expected_profit, candidate.route, net
+                            );
+                            false
+                        }
+                        _ => true,
+                    });
+                    pick_top(ranking.as_ref(), candidates)
+                        .map(|opportunity| (net_profit(&opportunity), opportunity))
+                };
+                // The on-chain sized route always comes back, but only a quoted edge
+                // counts as finding something
+                let found_opportunity = opportunity
+                    .as_ref()
+                    .map_or(false, |(_, opportunity)| opportunity.expected_profit > 0);
+                let Some((net_profit, opportunity)) = opportunity else {
+                    pool_moved = sleep_until_pool_update(
+                        idle_delay(idle_backoff.as_mut(), false, process_delay),
+                        pool_updates.as_deref(),
+                    )
+                    .await;
+                    continue;
+                };
+                // While backing off only a quoted edge is sent; the route sized on-chain
+                // waits until the mint finds one or a watched pool moves
+                if !found_opportunity && idle_backoff.as_ref().map_or(false, IdleBackoff::is_idle) {
+                    pool_moved = sleep_until_pool_update(
+                        idle_delay(idle_backoff.as_mut(), false, process_delay),
+                        pool_updates.as_deref(),
+                    )
+                    .await;
+                    continue;
+                }
+                if mint_context
+                    .cooldown
+                    .is_duplicate(&opportunity, std::time::Instant::now())
+                {
+                    debug!(
+                        "Mint {}: same trade as the last send within trade_cooldown_ms, skipping",
+                        mint_config_clone.mint
+                    );
+                    METRICS.inc_duplicate_opportunity_suppressed();
+                    tokio::time::sleep(process_delay).await;
+                    continue;
+                }
+                // Routes sized on-chain can spend up to the mint's input cap
+                let capital = match &capital_limiter_clone {
+                    Some(limiter) => {
+                        let amount = match opportunity.input_amount {
+                            0 => mint_config_clone.max_input_lamports.unwrap_or(0),
+                            amount => amount,
+                        };
+                        match limiter.try_reserve(amount) {
+                            Some(reservation) => Some(reservation),
+                            None => {
+                                warn!(
+                                    "Mint {}: sending {} lamports would exceed max_total_input_lamports ({} of {} in flight), skipping",
+                                    mint_config_clone.mint,
+                                    amount,
+                                    limiter.reserved(),
+                                    limiter.cap()
+                                );
+                                METRICS.inc_capital_cap_blocked();
+                                tokio::time::sleep(process_delay).await;
+                                continue;
+                            }
+                        }
+                    }
+                    None => None,
+                };
+                // The token travels with the job, so the mint stays claimed until it is sent
+                // (or dropped from a full queue)
+                send_queue_clone
+                    .push(SendJob {
+                        opportunity,
+                        net_profit,
+                        blockhash: latest_blockhash,
+                        mint: mint_context.clone(),
+                        _in_flight: in_flight_token,
+                        _capital: capital,
+                    })
+                    .await;
+
+                let delay = if use_adaptive_delay {
+                    let ema = latency_ema.update(iteration_start.elapsed());
+                    adaptive_delay(ema, ADAPTIVE_DELAY_LATENCY_FACTOR, min_delay, max_delay)
+                } else {
+                    process_delay
+                };
+                pool_moved = sleep_until_pool_update(
+                    idle_delay(idle_backoff.as_mut(), found_opportunity, delay),
+                    pool_updates.as_deref(),
+                )
+                .await;
             }
         });
     }
 
+    if dry_run {
+        info!("Dry run complete, no transactions were sent");
+        return Ok(());
+    }
+
+    // Accounts pinned by the mints, on the shared endpoint or their own, are re-read on
+    // their own schedule
+    if let Some(interval) = config.bot.pinned_refresh_interval() {
+        if rpc_pool.pinned_count() > 0 {
+            tokio::spawn(run_pinned_refresh(rpc_pool.clone(), interval));
+        }
+        for mint_rpc_pool in pinned_mint_pools {
+            tokio::spawn(run_pinned_refresh(mint_rpc_pool, interval));
+        }
+    }
+
     loop {
         tokio::time::sleep(Duration::from_secs(1)).await;
     }
 }
 
+/// Initialize every configured pool for one mint
+pub async fn initialize_mint_pools(
+    config: &Config,
+    mint_config: &MintConfig,
+    wallet: &Pubkey,
+    rpc_client: Arc<RpcClient>,
+    limiter: Option<&PoolInitLimiter>,
+) -> BotResult<MintPoolData> {
+    Ok(initialize_pool_data(
+        &mint_config.mint,
+        &wallet.to_string(),
+        mint_config.raydium_pool_list.as_ref(),
+        mint_config.raydium_cp_pool_list.as_ref(),
+        mint_config.pump_pool_list.as_ref(),
+        mint_config.meteora_dlmm_pool_list.as_ref(),
+        mint_config.whirlpool_pool_list.as_ref(),
+        mint_config.raydium_clmm_pool_list.as_ref(),
+        mint_config.meteora_damm_pool_list.as_ref(),
+        mint_config.solfi_pool_list.as_ref(),
+        mint_config.meteora_damm_v2_pool_list.as_ref(),
+        mint_config.vertigo_pool_list.as_ref(),
+        mint_config.lifinity_pool_list.as_ref(),
+        mint_config.phoenix_pool_list.as_ref(),
+        config.bot.disabled_dexes(),
+        rpc_client,
+        limiter,
+    )
+    .await?)
+}
+
+/// Whether `pool_count` initialized pools are enough for the mint to arbitrage, logging
+/// why not
+pub fn has_enough_pools(mint: &str, pool_count: usize, min_pools: usize) -> bool {
+    if pool_count >= min_pools {
+        return true;
+    }
+    warn!(
+        "Mint {}: only {} pool(s) initialized, at least {} needed to arbitrage; not trading it",
+        mint, pool_count, min_pools
+    );
+    false
+}
+
+/// What a strategy loop does after a pool refresh
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PoolStatus {
+    /// Every pool the route still had was closed on-chain
+    Closed,
+    /// Fewer than min_pools left; waits for re-initialization to restore some
+    Paused,
+    Trading,
+}
+
+fn pool_status(mint: &str, refresh: &PoolRefresh, min_pools: usize) -> PoolStatus {
+    if refresh.remaining == 0 && !refresh.removed.is_empty() {
+        PoolStatus::Closed
+    } else if has_enough_pools(mint, refresh.remaining, min_pools) {
+        PoolStatus::Trading
+    } else {
+        PoolStatus::Paused
+    }
+}
+
+/// The mint's own RPC client when it sets `rpc_url`, otherwise the shared one
+pub fn mint_rpc_client(
+    mint_config: &MintConfig,
+    shared: &Arc<RpcClient>,
+    timeout: Duration,
+) -> Arc<RpcClient> {
+    match &mint_config.rpc_url {
+        Some(url) => Arc::new(RpcClient::new_with_timeout(url.clone(), timeout)),
+        None => shared.clone(),
+    }
+}
+
+/// The mint's own RPC pool when it sets `rpc_url`, otherwise the shared one
+pub fn mint_rpc_pool(mint_config: &MintConfig, shared: &Arc<RpcPool>, timeout: Duration) -> Arc<RpcPool> {
+    match &mint_config.rpc_url {
+        Some(url) => Arc::new(RpcPool::new(vec![url.clone()], DEFAULT_RPC_CACHE_TTL_SECS, timeout)),
+        None => shared.clone(),
+    }
+}
+
+/// Fetch a mint's account during startup, retrying transient RPC failures
+pub async fn fetch_mint_account(rpc_pool: &RpcPool, mint: &Pubkey) -> BotResult<Account> {
+    rpc_pool.get_account_with_retry(mint).await
+}
+
+/// The lookup tables a mint's transactions may use: its configured ones, plus the default
+/// one for v0 (legacy transactions cannot reference lookup tables)
+fn mint_lookup_table_addresses(
+    mint_config: &MintConfig,
+    transaction_version: TransactionVersion,
+) -> Vec<Pubkey> {
+    let mut addresses: Vec<Pubkey> = mint_config
+        .lookup_table_accounts
+        .iter()
+        .flatten()
+        .filter_map(|address| match Pubkey::from_str(address) {
+            Ok(pubkey) => Some(pubkey),
+            Err(e) => {
+                error!("   Invalid lookup table pubkey string {}: {}", address, e);
+                None
+            }
+        })
+        .collect();
+    if transaction_version == TransactionVersion::V0 {
+        addresses.push(*DEFAULT_LOOKUP_TABLE_PUBKEY);
+    }
+    addresses
+}
+
+/// Fetch the lookup tables of every mint in `getMultipleAccounts` batches, a table shared
+/// by several mints only once. Tables that are missing or unusable, or whose batch still
+/// fails once the retry budget is spent, are skipped.
+pub async fn fetch_lookup_tables(
+    rpc_pool: &RpcPool,
+    mint_configs: &[&MintConfig],
+    transaction_version: TransactionVersion,
+) -> HashMap<Pubkey, AddressLookupTableAccount> {
+    let mut seen = HashSet::new();
+    let addresses: Vec<Pubkey> = mint_configs
+        .iter()
+        .flat_map(|mint_config| mint_lookup_table_addresses(mint_config, transaction_version))
+        .filter(|address| seen.insert(*address))
+        .collect();
+
+    let mut lookup_tables = HashMap::with_capacity(addresses.len());
+    for batch in addresses.chunks(MAX_MULTIPLE_ACCOUNTS_BATCH) {
+        let fetched = rpc_pool.get_multiple_accounts_with_slot(batch).await;
+        let (current_slot, accounts) = match fetched {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                error!("   Failed to fetch {} lookup tables: {}", batch.len(), e);
+                continue;
+            }
+        };
+        for (pubkey, account) in batch.iter().zip(accounts) {
+            let Some(account) = account else {
+                error!("   Lookup table account {} not found", pubkey);
+                continue;
+            };
+            match usable_lookup_table(pubkey, &account, current_slot) {
+                Ok(lookup_table) => {
+                    info!("   Successfully loaded lookup table: {}", pubkey);
+                    lookup_tables.insert(*pubkey, lookup_table);
+                }
+                Err(e) => warn!("   Skipping lookup table {}: {}", pubkey, e),
+            }
+        }
+    }
+    lookup_tables
+}
+
+/// Up to `max_tables` of the mint's fetched lookup tables: each pick is the table resolving
+/// the most of `accounts` the earlier picks don't, the configured order breaking ties
+pub fn select_lookup_tables(
+    mint_config: &MintConfig,
+    transaction_version: TransactionVersion,
+    fetched: &HashMap<Pubkey, AddressLookupTableAccount>,
+    accounts: &[Pubkey],
+    max_tables: usize,
+) -> Vec<AddressLookupTableAccount> {
+    let mut seen = HashSet::new();
+    let mut remaining: Vec<&AddressLookupTableAccount> =
+        mint_lookup_table_addresses(mint_config, transaction_version)
+            .into_iter()
+            .filter(|address| seen.insert(*address))
+            .filter_map(|address| fetched.get(&address))
+            .collect();
+    let mut uncovered: HashSet<Pubkey> = accounts.iter().copied().collect();
+    let mut selected = Vec::new();
+    while selected.len() < max_tables && !remaining.is_empty() {
+        let coverage = |table: &AddressLookupTableAccount| {
+            table.addresses.iter().filter(|address| uncovered.contains(address)).count()
+        };
+        let best = remaining
+            .iter()
+            .enumerate()
+            .max_by_key(|(index, table)| (coverage(table), std::cmp::Reverse(*index)))
+            .map(|(index, _)| index)
+            .unwrap_or_default();
+        let table = remaining.remove(best);
+        for address in &table.addresses {
+            uncovered.remove(address);
+        }
+        selected.push(table.clone());
+    }
+    for table in remaining {
+        info!(
+            "   Mint {}: leaving out lookup table {}, over the limit of {}",
+            mint_config.mint, table.key, max_tables
+        );
+    }
+    selected
+}
+
+/// The mint's lookup tables out of `fetched`, at most `bot.max_lookup_tables_per_mint`,
+/// ranked by how many of the mint's pool and executor accounts they resolve
+pub fn mint_lookup_tables(
+    config: &Config,
+    mint_config: &MintConfig,
+    pool_data: &MintPoolData,
+    wallet: &Pubkey,
+    fetched: &HashMap<Pubkey, AddressLookupTableAccount>,
+) -> Vec<AddressLookupTableAccount> {
+    let mut accounts = executor_base_accounts(config, wallet, pool_data);
+    accounts.extend(
+        pool_candidates(pool_data, wallet)
+            .into_iter()
+            .flat_map(|candidate| candidate.accounts),
+    );
+    select_lookup_tables(
+        mint_config,
+        config.bot.transaction_version(),
+        fetched,
+        &accounts,
+        config.bot.max_lookup_tables_per_mint(),
+    )
+}
+
+/// Decode a fetched lookup table, rejecting one not owned by the lookup table program or
+/// already deactivated: it stops resolving once closed, failing every transaction using it
+pub fn usable_lookup_table(
+    pubkey: &Pubkey,
+    account: &Account,
+    current_slot: Slot,
+) -> BotResult<AddressLookupTableAccount> {
+    if account.owner != solana_sdk::address_lookup_table::program::ID {
+        return Err(BotError::InvalidAccountOwner {
+            address: *pubkey,
+            expected_owner: solana_sdk::address_lookup_table::program::ID,
+            actual_owner: account.owner,
+        });
+    }
+    let lookup_table =
+        AddressLookupTable::deserialize(&account.data).map_err(|e| BotError::DeserializationError {
+            data_type: "AddressLookupTable".to_string(),
+            source: Box::new(e),
+        })?;
+    let deactivation_slot = lookup_table.meta.deactivation_slot;
+    if deactivation_slot != Slot::MAX {
+        return Err(BotError::ConfigError(format!(
+            "lookup table was deactivated at slot {} (current slot {}) and will be closed",
+            deactivation_slot, current_slot
+        )));
+    }
+    Ok(AddressLookupTableAccount {
+        key: *pubkey,
+        addresses: lookup_table.addresses.into_owned(),
+    })
+}
+
+/// The mint's pool candidates and the subset that fits in one transaction (the dry-run plan)
+pub fn plan_pool_selection(
+    config: &Config,
+    pool_data: &MintPoolData,
+    wallet: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> (Vec<PoolCandidate>, PoolSelection) {
+    let candidates = with_dex_compute_units(&config.bot, pool_candidates(pool_data, wallet));
+    let selection = select_pools(
+        &candidates,
+        &executor_base_accounts(config, wallet, pool_data),
+        lookup_tables,
+        compute_unit_budget(config, &pool_data.mint),
+    );
+    (candidates, selection)
+}
+
+/// Per-mint state shared by its strategy loop and whichever worker sends its jobs
+struct MintContext {
+    mint: String,
+    pool_data: Mutex<MintPoolData>,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+    /// Last trade sent for the mint, written by the worker that sent it
+    cooldown: TradeCooldown,
+    /// `execution_mode` and the "auto" threshold from the mint's config
+    execution_mode: Option<ExecutionMode>,
+    jito_min_profit_lamports: u64,
+}
+
+/// An opportunity queued for the sender workers
+struct SendJob {
+    opportunity: ArbitrageOpportunity,
+    /// The quoted route's profit after estimated fees; None when sized on-chain
+    net_profit: Option<i64>,
+    blockhash: Hash,
+    mint: Arc<MintContext>,
+    _in_flight: InFlightToken,
+    /// Counts the send's input against `max_total_input_lamports` until it resolves
+    _capital: Option<CapitalReservation>,
+}
+
+/// Everything a sender worker needs beyond the job itself
+struct SendWorker {
+    wallets: Arc<WalletFailover>,
+    config: Config,
+    rpc_clients: Vec<Arc<RpcClient>>,
+    jito_client: Option<Arc<JitoClient>>,
+    db: Option<Arc<TradeLog>>,
+    watchdog: Option<Arc<TradeWatchdog>>,
+    trade_events: Option<Arc<TradeEventWriter>>,
+    /// Shared blockhash cache for the expiry guard
+    cached_blockhash: Arc<BlockhashCache>,
+    /// The read RPC, for block heights and priority fees looked up while sending
+    /// without blocking a worker thread
+    async_rpc_client: Arc<AsyncRpcClient>,
+    /// Records how long each send took against the endpoints it went to
+    latency_tracker: Arc<LatencyTracker>,
+}
+
+impl SendWorker {
+    async fn send(&self, job: SendJob) {
+        let mint = &job.mint.mint;
+        let wallet = self.wallets.active();
+        let blockhash = self.blockhash(job.blockhash).await;
+        let jito_client = send_jito_client(
+            job.mint.execution_mode,
+            self.jito_client.as_deref(),
+            job.opportunity.expected_profit,
+            job.mint.jito_min_profit_lamports,
+        );
+
+        // The route's pools are copied out under the lock, which is released before
+        // sending: confirming can take `confirm_timeout`, and the mint's strategy loop
+        // needs the lock for every iteration's pool state
+        let (mut route_data, swap_fees) = {
+            let guard = job.mint.pool_data.lock().await;
+            (guard.clone(), job.opportunity.swap_fees(&guard))
+        };
+        let send_start = std::time::Instant::now();
+        let send_results = {
+            // A ranked pair route only swaps through its own pools, and after a failover
+            // the backup wallet's token accounts are used
+            let route_pools: HashSet<Pubkey> = job.opportunity.pools.iter().copied().collect();
+            let restrict = route_pools.len() < route_data.dex_pools().len();
+            if restrict {
+                route_data.retain_pools(&route_pools);
+            }
+            // A quoted route buys in its leading pools (several for a split buy) and sells
+            // in the rest, so their swaps only need the tick/bin arrays of that direction
+            if job.opportunity.input_amount > 0 {
+                route_data.set_route(&job.opportunity.pools, job.opportunity.buy_inputs.len());
+            }
+            if route_data.wallet_account != wallet.pubkey() {
+                route_data.set_wallet(&wallet.pubkey());
+            }
+            let pool_data = &route_data;
+            // The full route is split across transactions when its pools don't fit in one
+            let groups = match self.config.bot.max_pools_per_tx() {
+                Some(max_pools_per_tx) if !restrict => split_pool_data(
+                    &self.config,
+                    pool_data,
+                    &wallet.pubkey(),
+                    &job.mint.lookup_tables,
+                    max_pools_per_tx,
+                ),
+                _ => Ok(Vec::new()),
+            };
+            let compute_unit_price = estimate_compute_unit_price(
+                &self.config,
+                self.async_rpc_client.as_ref(),
+                pool_data,
+                &wallet.pubkey(),
+            )
+            .await;
+            match groups {
+                Ok(groups) if groups.len() > 1 => {
+                    build_and_send_split_transactions(
+                        &wallet,
+                        &self.config,
+                        &groups,
+                        &self.rpc_clients,
+                        blockhash,
+                        &job.mint.lookup_tables,
+                        jito_client,
+                        compute_unit_price,
+                    )
+                    .await
+                }
+                Ok(_) => {
+                    build_and_send_transaction(
+                        &wallet,
+                        &self.config,
+                        pool_data,
+                        &self.rpc_clients,
+                        blockhash,
+                        &job.mint.lookup_tables,
+                        jito_client,
+                        compute_unit_price,
+                        job.opportunity.input_amount,
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
+            }
+        };
+
+        if let Ok(results) = &send_results {
+            let send_time = send_start.elapsed();
+            let endpoints: HashSet<&str> = results.iter().map(|result| result.endpoint.as_str()).collect();
+            for endpoint in endpoints {
+                self.latency_tracker
+                    .record_endpoint(FeedKind::Rpc, endpoint, send_time)
+                    .await;
+            }
+        }
+
+        let landed = matches!(&send_results, Ok(results) if results.iter().any(|r| r.landed));
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.record_opportunity();
+            if landed {
+                watchdog.record_success();
+            }
+        }
+        self.track_wallet_health(&send_results, landed, jito_client.is_some());
+
+        match send_results {
+            Ok(mut send_results) => {
+                // The route's swap fees are paid once, by one transaction: the one that
+                // landed, else the first sent
+                let swap_signature = send_results
+                    .iter()
+                    .find(|result| result.landed)
+                    .or(send_results.first())
+                    .map(|result| result.signature);
+                for result in &mut send_results {
+                    if Some(result.signature) == swap_signature {
+                        result.fees.protocol_fee_lamports = swap_fees;
+                    }
+                }
+                METRICS.add_fees(landed_fees(&send_results));
+                METRICS.add_profit(landed_profit(job.net_profit, &send_results));
+                if !send_results.is_empty() {
+                    job.mint
+                        .cooldown
+                        .record_sent(&job.opportunity, std::time::Instant::now());
+                }
+                for result in &send_results {
+                    info!(
+                        "Mint {}: sent {} via {} (landed: {}, expected profit {} SOL)",
+                        mint,
+                        result.signature,
+                        result.endpoint,
+                        result.landed,
+                        format_token_amount(job.opportunity.expected_profit, SOL_DECIMALS)
+                    );
+                }
+                if let Some(trade_events) = &self.trade_events {
+                    trade_events.log_send_results(mint, job.net_profit, &send_results, &job.opportunity.route);
+                }
+                // Log successful attempts to DB if available; failures are buffered, never fatal
+                if let Some(db) = &self.db {
+                    db.log_opportunity(&job.opportunity, job.net_profit, &send_results)
+                        .await;
+                }
+            }
+            Err(e) => match e.downcast_ref::<BotError>() {
+                Some(error) => alert::log_error(&format!("Error sending transaction for mint {}", mint), error),
+                None => error!("Error sending transaction for mint {}: {}", mint, e),
+            },
+        }
+    }
+
+    /// Count the send against the active wallet and fail over to the backup wallet when
+    /// the primary keeps failing or runs low on SOL
+    fn track_wallet_health(&self, send_results: &anyhow::Result<Vec<SendResult>>, landed: bool, via_jito: bool) {
+        // Landing is only tracked when sends are confirmed, so spam and Jito sends only
+        // count as failures when no endpoint accepted them at all
+        let confirms = !via_jito
+            && (self.config.bot.resend_dropped()
+                || !self.config.spam.as_ref().map_or(false, |spam| spam.enabled));
+        let failed = match send_results {
+            Ok(results) if results.is_empty() => true,
+            Ok(_) => confirms && !landed,
+            Err(_) => true,
+        };
+        if landed {
+            self.wallets.record_landed();
+        } else if failed {
+            self.wallets.record_failure();
+        }
+    }
+
+    /// Blockhash to sign the job with. The job's was cached when the opportunity was
+    /// found; with `blockhash_expiry_margin_blocks` the cache's is used instead, refreshed
+    /// first if it is about to expire.
+    async fn blockhash(&self, job_blockhash: Hash) -> Hash {
+        let Some(margin) = self.config.bot.blockhash_expiry_margin_blocks else {
+            return job_blockhash;
+        };
+        match self
+            .cached_blockhash
+            .unexpired(self.async_rpc_client.as_ref(), margin)
+            .await
+        {
+            Ok(blockhash) => blockhash,
+            Err(e) => {
+                warn!("Blockhash expiry check failed, sending with the cached blockhash: {}", e);
+                job_blockhash
+            }
+        }
+    }
+}
+
+/// Sleep after an iteration, stretched while the mint finds nothing profitable
+fn idle_delay(idle_backoff: Option<&mut IdleBackoff>, found_opportunity: bool, delay: Duration) -> Duration {
+    let Some(backoff) = idle_backoff else {
+        return delay;
+    };
+    let backed_off = backoff.next_delay(found_opportunity, delay);
+    if backed_off > delay {
+        METRICS.add_idle_backoff(backed_off - delay);
+    }
+    backed_off
+}
+
+/// Sleep for `delay`, cut short by an update to one of the mint's watched pool accounts.
+/// Returns whether one arrived.
+async fn sleep_until_pool_update(delay: Duration, pool_updates: Option<&Notify>) -> bool {
+    let Some(pool_updates) = pool_updates else {
+        tokio::time::sleep(delay).await;
+        return false;
+    };
+    tokio::select! {
+        _ = tokio::time::sleep(delay) => false,
+        _ = pool_updates.notified() => true,
+    }
+}
+
+/// Accounts whose updates mean one of the mint's pools moved: each pool's state read
+/// less the clock and the configs pools share, or the pool itself when nothing is left
+fn pool_update_accounts(mint_pool_data: &MintPoolData) -> Vec<Pubkey> {
+    let mut accounts = Vec::new();
+    for pool in mint_pool_data.dex_pools() {
+        let shared = pool.shared_accounts();
+        let mut watched: Vec<Pubkey> = pool
+            .state_accounts()
+            .into_iter()
+            .filter(|account| *account != sysvar::clock::id() && !shared.contains(account))
+            .collect();
+        if watched.is_empty() {
+            watched.push(pool.pool_address());
+        }
+        for account in watched {
+            if !accounts.contains(&account) {
+                accounts.push(account);
+            }
+        }
+    }
+    accounts
+}
+
+/// The Jito client a send goes through, None to send through RPC only. Without a
+/// per-mint `execution_mode` every send uses Jito when the client is available.
+fn send_jito_client<T>(
+    execution_mode: Option<ExecutionMode>,
+    jito_client: Option<T>,
+    expected_profit: u64,
+    jito_min_profit_lamports: u64,
+) -> Option<T> {
+    match execution_mode {
+        Some(mode) if !mode.uses_jito(expected_profit, jito_min_profit_lamports) => None,
+        _ => jito_client,
+    }
+}
+
+/// Check the active wallet's balance every `interval`, failing over to the backup wallet
+/// once it drops below `wallet_min_balance_lamports`
+async fn wallet_balance_sampler(rpc_client: Arc<AsyncRpcClient>, wallets: Arc<WalletFailover>, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        let wallet = wallets.active().pubkey();
+        match rpc_client.get_balance(&wallet).await {
+            Ok(lamports) => {
+                wallets.record_balance(lamports);
+            }
+            Err(e) => warn!("Failed to check balance of wallet {}: {}", wallet, e),
+        }
+    }
+}
+
 async fn blockhash_refresher(
     rpc_client: Arc<RpcClient>,
-    cached_blockhash: Arc<Mutex<Hash>>,
+    cached_blockhash: Arc<BlockhashCache>,
     refresh_interval: Duration,
     rpc_url: String,
 ) {
     loop {
-        match rpc_client.get_latest_blockhash() {
-            Ok(blockhash) => {
-                let mut guard = cached_blockhash.lock().await;
-                *guard = blockhash;
+        // The slot subscription, when running, keeps the cache fresher than this poll
+        let age = cached_blockhash.age();
+        if age < refresh_interval {
+            tokio::time::sleep(refresh_interval - age).await;
+            continue;
+        }
+
+        match rpc_client.get_latest_blockhash_with_commitment(rpc_client.commitment()) {
+            // The slot backs max_account_staleness_slots when there is no slot subscription
+            Ok((blockhash, last_valid_block_height)) => {
+                cached_blockhash.update_with_expiry(blockhash, rpc_client.get_slot().ok(), last_valid_block_height)
             }
             Err(e) => {
-                let error = BotError::rpc_retryable(rpc_url.clone(), format!("Failed to refresh blockhash: {}", e));
-                error!("{} (severity: {})", error, error.severity().as_str());
+                let error = BotError::rpc_retryable(rpc_url.clone(), e.to_string());
+                alert::log_error("Failed to refresh blockhash", &error);
             }
         }
         tokio::time::sleep(refresh_interval).await;
     }
 }
 
-fn load_keypair(private_key: &str) -> BotResult<Keypair> {
-    // Try base58 decoding first
-    if let Ok(bytes) = bs58::decode(private_key).into_vec() {
-        if let Ok(keypair) = Keypair::from_bytes(&bytes) {
-            return Ok(keypair);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAX_RPC_RETRIES;
+    use solana_sdk::address_lookup_table::state::LookupTableMeta;
+    use std::borrow::Cow;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Times out `failures` times, then serves the mint account
+    struct FlakyFetcher {
+        failures: AtomicU32,
+        attempts: AtomicU32,
+        mint_account: Account,
+    }
+
+    impl AccountFetcher for FlakyFetcher {
+        fn fetch_account(&self, pubkey: &Pubkey) -> BotResult<Account> {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            let remaining = self.failures.load(Ordering::SeqCst);
+            if remaining > 0 {
+                self.failures.store(remaining - 1, Ordering::SeqCst);
+                return Err(BotError::AccountFetchError {
+                    address: *pubkey,
+                    reason: "RPC error: request timed out".to_string(),
+                });
+            }
+            Ok(self.mint_account.clone())
+        }
+
+        fn fetch_multiple_accounts(&self, pubkeys: &[Pubkey]) -> BotResult<Vec<Option<Account>>> {
+            pubkeys.iter().map(|pubkey| self.fetch_account(pubkey).map(Some)).collect()
+        }
+
+        fn fetch_multiple_accounts_with_slot(
+            &self,
+            pubkeys: &[Pubkey],
+        ) -> BotResult<(Slot, Vec<Option<Account>>)> {
+            Ok((0, self.fetch_multiple_accounts(pubkeys)?))
+        }
+
+        fn endpoint(&self) -> String {
+            "flaky".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mint_fetch_retries_transient_failures() {
+        let mint_account = Account {
+            lamports: 1_461_600,
+            data: vec![0; 82],
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let fetcher = Arc::new(FlakyFetcher {
+            failures: AtomicU32::new(2),
+            attempts: AtomicU32::new(0),
+            mint_account: mint_account.clone(),
+        });
+        let rpc_pool = RpcPool::with_fetchers(
+            vec![fetcher.clone() as Arc<dyn AccountFetcher>],
+            DEFAULT_RPC_CACHE_TTL_SECS,
+        );
+
+        let fetched = fetch_mint_account(&rpc_pool, &Pubkey::new_unique()).await.unwrap();
+        assert_eq!(fetched, mint_account);
+        assert_eq!(fetcher.attempts.load(Ordering::SeqCst), 3);
+
+        // An RPC that never recovers exhausts the budget and only this mint is given up
+        let fetcher = Arc::new(FlakyFetcher {
+            failures: AtomicU32::new(u32::MAX),
+            attempts: AtomicU32::new(0),
+            mint_account,
+        });
+        let rpc_pool = RpcPool::with_fetchers(
+            vec![fetcher.clone() as Arc<dyn AccountFetcher>],
+            DEFAULT_RPC_CACHE_TTL_SECS,
+        );
+        assert!(fetch_mint_account(&rpc_pool, &Pubkey::new_unique()).await.is_err());
+        assert_eq!(fetcher.attempts.load(Ordering::SeqCst), MAX_RPC_RETRIES);
+    }
+
+    fn lookup_table_account(deactivation_slot: Slot, addresses: Vec<Pubkey>) -> Account {
+        let table = AddressLookupTable {
+            meta: LookupTableMeta {
+                deactivation_slot,
+                ..LookupTableMeta::default()
+            },
+            addresses: Cow::Owned(addresses),
+        };
+        Account {
+            lamports: 1_000_000,
+            data: table.serialize_for_tests().unwrap(),
+            owner: solana_sdk::address_lookup_table::program::ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_deactivated_lookup_table_is_skipped() {
+        let current_slot = 250_000_000;
+        let active = (Pubkey::new_unique(), lookup_table_account(Slot::MAX, vec![Pubkey::new_unique()]));
+        let deactivated = (
+            Pubkey::new_unique(),
+            lookup_table_account(current_slot - 100, vec![Pubkey::new_unique()]),
+        );
+
+        let usable: Vec<AddressLookupTableAccount> = [&active, &deactivated]
+            .into_iter()
+            .filter_map(|(pubkey, account)| usable_lookup_table(pubkey, account, current_slot).ok())
+            .collect();
+        assert_eq!(usable.len(), 1);
+        assert_eq!(usable[0].key, active.0);
+        assert_eq!(usable[0].addresses.len(), 1);
+
+        let err = usable_lookup_table(&deactivated.0, &deactivated.1, current_slot).unwrap_err();
+        assert!(err.to_string().contains("deactivated"));
+    }
+
+    /// Serves lookup tables and records every address requested
+    struct RecordingFetcher {
+        tables: HashMap<Pubkey, Account>,
+        requested: std::sync::Mutex<Vec<Vec<Pubkey>>>,
+    }
+
+    impl AccountFetcher for RecordingFetcher {
+        fn fetch_account(&self, pubkey: &Pubkey) -> BotResult<Account> {
+            self.tables.get(pubkey).cloned().ok_or_else(|| BotError::AccountFetchError {
+                address: *pubkey,
+                reason: "not a lookup table".to_string(),
+            })
+        }
+
+        fn fetch_multiple_accounts(&self, pubkeys: &[Pubkey]) -> BotResult<Vec<Option<Account>>> {
+            self.requested.lock().unwrap().push(pubkeys.to_vec());
+            Ok(pubkeys.iter().map(|pubkey| self.tables.get(pubkey).cloned()).collect())
+        }
+
+        fn fetch_multiple_accounts_with_slot(
+            &self,
+            pubkeys: &[Pubkey],
+        ) -> BotResult<(Slot, Vec<Option<Account>>)> {
+            Ok((0, self.fetch_multiple_accounts(pubkeys)?))
+        }
+
+        fn endpoint(&self) -> String {
+            "recording".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shared_lookup_tables_are_fetched_once_and_capped_per_mint() {
+        let accounts: Vec<Pubkey> = (0..6).map(|_| Pubkey::new_unique()).collect();
+        // The shared table resolves four of the mint's accounts, the others one or none
+        let shared = (Pubkey::new_unique(), accounts[..4].to_vec());
+        let small = (Pubkey::new_unique(), vec![accounts[4]]);
+        let unrelated = (Pubkey::new_unique(), vec![Pubkey::new_unique()]);
+        let fetcher = Arc::new(RecordingFetcher {
+            tables: [&shared, &small, &unrelated]
+                .into_iter()
+                .map(|(key, addresses)| (*key, lookup_table_account(Slot::MAX, addresses.clone())))
+                .collect(),
+            requested: std::sync::Mutex::new(Vec::new()),
+        });
+        let rpc_pool = RpcPool::with_fetchers(
+            vec![fetcher.clone() as Arc<dyn AccountFetcher>],
+            DEFAULT_RPC_CACHE_TTL_SECS,
+        );
+        let mint_config = |tables: &[Pubkey]| -> MintConfig {
+            MintConfig {
+                lookup_table_accounts: Some(tables.iter().map(Pubkey::to_string).collect()),
+                ..toml::from_str("mint = \"EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v\"\nprocess_delay = 400")
+                    .unwrap()
+            }
+        };
+        let first = mint_config(&[unrelated.0, small.0, shared.0]);
+        let second = mint_config(&[shared.0]);
+
+        // Legacy leaves the default table out of the reads
+        let fetched =
+            fetch_lookup_tables(&rpc_pool, &[&first, &second], TransactionVersion::Legacy).await;
+        assert_eq!(fetched.len(), 3);
+        let requested = fetcher.requested.lock().unwrap().clone();
+        assert_eq!(requested.len(), 1, "one batched read");
+        assert_eq!(requested[0].iter().filter(|key| **key == shared.0).count(), 1);
+        assert_eq!(requested[0].len(), 3);
+
+        // Two of three allowed: the best-covering tables win over the configured order
+        let selected =
+            select_lookup_tables(&first, TransactionVersion::Legacy, &fetched, &accounts, 2);
+        let keys: Vec<Pubkey> = selected.iter().map(|table| table.key).collect();
+        assert_eq!(keys, vec![shared.0, small.0]);
+
+        let selected =
+            select_lookup_tables(&first, TransactionVersion::Legacy, &fetched, &accounts, 8);
+        assert_eq!(selected.len(), 3);
+        let selected =
+            select_lookup_tables(&second, TransactionVersion::Legacy, &fetched, &accounts, 8);
+        assert_eq!(selected.len(), 1);
+    }
+
+    #[test]
+    fn test_lookup_table_with_wrong_owner_is_rejected() {
+        let pubkey = Pubkey::new_unique();
+        let mut account = lookup_table_account(Slot::MAX, vec![Pubkey::new_unique()]);
+        account.owner = Pubkey::new_unique();
+
+        assert!(matches!(
+            usable_lookup_table(&pubkey, &account, 0),
+            Err(BotError::InvalidAccountOwner { .. })
+        ));
+    }
+
+    #[test]
+    fn test_mint_rpc_override_uses_its_own_client() {
+        let shared = Arc::new(RpcClient::new("https://api.mainnet-beta.solana.com".to_string()));
+        let mut mint_config: MintConfig = toml::from_str(
+            r#"
+            mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+            process_delay = 400
+            "#,
+        )
+        .unwrap();
+        let timeout = Duration::from_secs(2);
+
+        let client = mint_rpc_client(&mint_config, &shared, timeout);
+        assert!(Arc::ptr_eq(&client, &shared));
+
+        mint_config.rpc_url = Some("https://premium.example.com".to_string());
+        let client = mint_rpc_client(&mint_config, &shared, timeout);
+        assert!(!Arc::ptr_eq(&client, &shared));
+        assert_eq!(client.url(), "https://premium.example.com");
+
+        let shared_pool = Arc::new(RpcPool::new(
+            vec!["https://api.mainnet-beta.solana.com".to_string()],
+            DEFAULT_RPC_CACHE_TTL_SECS,
+            timeout,
+        ));
+        let pool = mint_rpc_pool(&mint_config, &shared_pool, timeout);
+        assert!(!Arc::ptr_eq(&pool, &shared_pool));
+        assert_eq!(pool.endpoint_health()[0].url, "https://premium.example.com");
+
+        mint_config.rpc_url = None;
+        assert!(Arc::ptr_eq(&mint_rpc_pool(&mint_config, &shared_pool, timeout), &shared_pool));
+    }
+
+    #[test]
+    fn test_execution_mode_picks_the_sender() {
+        let jito = Some("jito");
+        let threshold = 1_000_000;
+
+        // Unset keeps sending through Jito whenever it's available
+        assert_eq!(send_jito_client(None, jito, 0, threshold), jito);
+        assert_eq!(send_jito_client(None, None::<&str>, 0, threshold), None);
+
+        assert_eq!(send_jito_client(Some(ExecutionMode::Jito), jito, 0, threshold), jito);
+        assert_eq!(send_jito_client(Some(ExecutionMode::Spam), jito, 5_000_000, threshold), None);
+
+        // "auto" only pays the tip once the profit clears the threshold
+        let auto = Some(ExecutionMode::Auto);
+        assert_eq!(send_jito_client(auto, jito, 999_999, threshold), None);
+        assert_eq!(send_jito_client(auto, jito, 1_000_000, threshold), jito);
+        assert_eq!(send_jito_client(auto, None::<&str>, 5_000_000, threshold), None);
+    }
+
+    #[test]
+    fn test_mint_with_one_valid_pool_is_not_spawned() {
+        let mint = Pubkey::new_unique().to_string();
+        let mut pool_data =
+            MintPoolData::new(&mint, &Pubkey::new_unique().to_string(), spl_token::ID).unwrap();
+        let pools = [Pubkey::new_unique(), Pubkey::new_unique()];
+        for pool in &pools {
+            pool_data
+                .add_raydium_pool(
+                    &pool.to_string(),
+                    &Pubkey::new_unique().to_string(),
+                    &Pubkey::new_unique().to_string(),
+                )
+                .unwrap();
         }
+        assert!(has_enough_pools(&mint, pool_data.dex_pools().len(), 2));
+
+        // pools[1] has no account on-chain, leaving a single pool to trade against
+        let rpc = crate::rpc::MockRpcClient::new();
+        rpc.add_account(
+            pools[0],
+            Account {
+                lamports: 1,
+                data: vec![1; 64],
+                ..Account::default()
+            },
+        );
+        let refresh = refresh_pools(&rpc, &mut pool_data).unwrap();
+        assert_eq!(refresh.remaining, 1);
+        assert!(!has_enough_pools(&mint, refresh.remaining, 2));
+        assert!(has_enough_pools(&mint, refresh.remaining, 1));
     }
 
-    // Try loading from file
-    if let Ok(keypair) = solana_sdk::signature::read_keypair_file(private_key) {
-        return Ok(keypair);
+    #[test]
+    fn test_paused_mint_resumes_once_its_pools_are_reinitialized() {
+        let mint = Pubkey::new_unique().to_string();
+        let mut configured =
+            MintPoolData::new(&mint, &Pubkey::new_unique().to_string(), spl_token::ID).unwrap();
+        let pools = [Pubkey::new_unique(), Pubkey::new_unique()];
+        for pool in &pools {
+            configured
+                .add_raydium_pool(
+                    &pool.to_string(),
+                    &Pubkey::new_unique().to_string(),
+                    &Pubkey::new_unique().to_string(),
+                )
+                .unwrap();
+        }
+        let route_pools: HashSet<Pubkey> = pools.iter().copied().collect();
+        let rpc = crate::rpc::MockRpcClient::new();
+        for pool in &pools {
+            rpc.add_account(*pool, Account { lamports: 1, data: vec![1; 64], ..Account::default() });
+        }
+
+        // pools[1] failed to initialize, so the loop is spawned paused
+        let mut pool_data = configured.clone();
+        pool_data.retain_pools(&HashSet::from([pools[0]]));
+        let refresh = refresh_pools(&rpc, &mut pool_data).unwrap();
+        assert_eq!(pool_status(&mint, &refresh, 2), PoolStatus::Paused);
+
+        // A re-initialization that lost pools[0] in turn is not adopted
+        let mut only_second = configured.clone();
+        only_second.retain_pools(&HashSet::from([pools[1]]));
+        assert_eq!(restore_pools(&mut pool_data, only_second, &route_pools), 0);
+
+        // Once pools[1] initializes the loop resumes trading both
+        assert_eq!(restore_pools(&mut pool_data, configured, &route_pools), 1);
+        let refresh = refresh_pools(&rpc, &mut pool_data).unwrap();
+        assert_eq!(refresh.remaining, 2);
+        assert_eq!(pool_status(&mint, &refresh, 2), PoolStatus::Trading);
+
+        // With both closed on-chain the loop stops
+        let closed = crate::rpc::MockRpcClient::new();
+        let refresh = refresh_pools(&closed, &mut pool_data).unwrap();
+        assert_eq!(pool_status(&mint, &refresh, 2), PoolStatus::Closed);
     }
 
-    Err(BotError::WalletError(format!(
-        "Failed to load keypair from '{}'. Expected base58-encoded private key or path to keypair file.",
-        private_key
-    )))
+    #[test]
+    fn test_pool_updates_watch_vaults_not_shared_configs() {
+        use crate::dex::raydium::cp_initializer::RaydiumCpPool;
+
+        let mut pool_data =
+            MintPoolData::new(&Pubkey::new_unique().to_string(), &Pubkey::new_unique().to_string(), spl_token::ID)
+                .unwrap();
+        let amm_config = Pubkey::new_unique();
+        let cp_pool = || RaydiumCpPool {
+            pool: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            sol_vault: Pubkey::new_unique(),
+            amm_config,
+            observation: Pubkey::new_unique(),
+            fee_bps: 25,
+            token_reserve: 0,
+            sol_reserve: 0,
+        };
+        pool_data.raydium_cp_pools.extend([cp_pool(), cp_pool()]);
+
+        let watched = pool_update_accounts(&pool_data);
+        let vaults: Vec<Pubkey> = pool_data
+            .raydium_cp_pools
+            .iter()
+            .flat_map(|pool| [pool.token_vault, pool.sol_vault])
+            .collect();
+        assert_eq!(watched, vaults);
+    }
+
+    #[tokio::test]
+    async fn test_pool_update_cuts_the_idle_sleep_short() {
+        let updates = Notify::new();
+        let long = Duration::from_secs(60);
+
+        // An update that arrived while the loop was busy still wakes the next sleep
+        updates.notify_one();
+        let start = std::time::Instant::now();
+        assert!(sleep_until_pool_update(long, Some(&updates)).await);
+        assert!(start.elapsed() < long);
+
+        assert!(!sleep_until_pool_update(Duration::from_millis(10), Some(&updates)).await);
+        assert!(!sleep_until_pool_update(Duration::from_millis(10), None).await);
+    }
 }
@@ -0,0 +1,230 @@
+/// Config Route Diff
+///
+/// Runs the dry-run pool selection for two configs and compares them per mint,
+/// so the effect of a pool list edit is visible before it is deployed.
+
+use crate::bot::{fetch_lookup_tables, initialize_mint_pools, mint_lookup_tables, plan_pool_selection};
+use crate::config::{Config, MintConfig};
+use crate::constants::DEFAULT_RPC_CACHE_TTL_SECS;
+use crate::error::BotResult;
+use crate::rpc::{AccountFetcher, RpcPool};
+use crate::transaction::PoolSelection;
+use solana_client::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+
+/// Dry-run outcome for one mint of one config
+#[derive(Debug, Clone)]
+pub struct MintRoutePlan {
+    pub mint: String,
+    /// Pool addresses listed in the config
+    pub pools: BTreeSet<String>,
+    pub selection: PoolSelection,
+}
+
+impl MintRoutePlan {
+    pub fn new(mint_config: &MintConfig, selection: PoolSelection) -> Self {
+        Self {
+            mint: mint_config.mint.clone(),
+            pools: mint_config
+                .pool_addresses()
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            selection,
+        }
+    }
+}
+
+/// Initialize and dry-run every mint of `config`
+pub async fn plan_routes(
+    config: &Config,
+    wallet: &Pubkey,
+    rpc_client: Arc<RpcClient>,
+) -> BotResult<Vec<MintRoutePlan>> {
+    let mint_configs: Vec<&MintConfig> = config.routing.mint_config_list.iter().collect();
+    let rpc_pool = RpcPool::with_fetchers(
+        vec![rpc_client.clone() as Arc<dyn AccountFetcher>],
+        DEFAULT_RPC_CACHE_TTL_SECS,
+    );
+    let fetched_lookup_tables =
+        fetch_lookup_tables(&rpc_pool, &mint_configs, config.bot.transaction_version()).await;
+    let mut plans = Vec::new();
+    for mint_config in mint_configs {
+        let pool_data =
+            initialize_mint_pools(config, mint_config, wallet, rpc_client.clone(), None).await?;
+        let lookup_tables =
+            mint_lookup_tables(config, mint_config, &pool_data, wallet, &fetched_lookup_tables);
+        let (_, selection) = plan_pool_selection(config, &pool_data, wallet, &lookup_tables);
+        plans.push(MintRoutePlan::new(mint_config, selection));
+    }
+    Ok(plans)
+}
+
+/// How one mint's route changes between the old and new config
+#[derive(Debug, Clone)]
+pub struct MintRouteDiff {
+    pub mint: String,
+    /// `None` when the mint is missing from that config
+    pub old: Option<PoolSelection>,
+    pub new: Option<PoolSelection>,
+    pub added_pools: Vec<String>,
+    pub removed_pools: Vec<String>,
+}
+
+impl MintRouteDiff {
+    pub fn profit_change(&self) -> i64 {
+        let profit = |selection: &Option<PoolSelection>| {
+            selection
+                .as_ref()
+                .map_or(0, |selection| selection.expected_profit as i64)
+        };
+        profit(&self.new) - profit(&self.old)
+    }
+
+    pub fn account_change(&self) -> i64 {
+        let accounts = |selection: &Option<PoolSelection>| {
+            selection
+                .as_ref()
+                .map_or(0, |selection| selection.account_count as i64)
+        };
+        accounts(&self.new) - accounts(&self.old)
+    }
+}
+
+impl fmt::Display for MintRouteDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe = |selection: &Option<PoolSelection>| match selection {
+            Some(selection) => format!(
+                "{} pools, {} accounts, est. profit {}",
+                selection.selected.len(),
+                selection.account_count,
+                selection.expected_profit
+            ),
+            None => "not configured".to_string(),
+        };
+
+        writeln!(f, "Mint {}:", self.mint)?;
+        writeln!(f, "  old: {}", describe(&self.old))?;
+        writeln!(f, "  new: {}", describe(&self.new))?;
+        writeln!(
+            f,
+            "  change: est. profit {:+}, accounts {:+}",
+            self.profit_change(),
+            self.account_change()
+        )?;
+        for pool in &self.added_pools {
+            writeln!(f, "    + {}", pool)?;
+        }
+        for pool in &self.removed_pools {
+            writeln!(f, "    - {}", pool)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare two configs' plans mint by mint, in old-config order followed by new-only mints
+pub fn diff_route_plans(old: &[MintRoutePlan], new: &[MintRoutePlan]) -> Vec<MintRouteDiff> {
+    let mut mints: Vec<&str> = old.iter().map(|plan| plan.mint.as_str()).collect();
+    for plan in new {
+        if !mints.contains(&plan.mint.as_str()) {
+            mints.push(&plan.mint);
+        }
+    }
+
+    let empty = BTreeSet::new();
+    mints
+        .into_iter()
+        .map(|mint| {
+            let old_plan = old.iter().find(|plan| plan.mint == mint);
+            let new_plan = new.iter().find(|plan| plan.mint == mint);
+            let old_pools = old_plan.map_or(&empty, |plan| &plan.pools);
+            let new_pools = new_plan.map_or(&empty, |plan| &plan.pools);
+            MintRouteDiff {
+                mint: mint.to_string(),
+                old: old_plan.map(|plan| plan.selection.clone()),
+                new: new_plan.map(|plan| plan.selection.clone()),
+                added_pools: new_pools.difference(old_pools).cloned().collect(),
+                removed_pools: old_pools.difference(new_pools).cloned().collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::{select_pools, PoolCandidate};
+    use std::str::FromStr;
+
+    const MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const PUMP_POOL: &str = "Gf7sXMoP8iRw4iiXmJ1nq4vxcRycbGXy5RL8a8LnTd3v";
+    const RAYDIUM_POOL: &str = "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2";
+
+    fn config(pools: &str) -> Config {
+        toml::from_str(&format!(
+            r#"
+            [bot]
+            compute_unit_limit = 600000
+
+            [[routing.mint_config_list]]
+            mint = "{}"
+            process_delay = 400
+            {}
+
+            [rpc]
+            url = "https://api.mainnet-beta.solana.com"
+
+            [wallet]
+            private_key = "test"
+            "#,
+            MINT, pools
+        ))
+        .unwrap()
+    }
+
+    /// Dry-run selection over stand-in candidates with three accounts per pool
+    fn plan(config: &Config) -> MintRoutePlan {
+        let mint_config = &config.routing.mint_config_list[0];
+        let candidates: Vec<PoolCandidate> = mint_config
+            .pool_addresses()
+            .into_iter()
+            .map(|pool| PoolCandidate {
+                pool: Pubkey::from_str(pool).unwrap(),
+                dex: "Pump.fun",
+                accounts: (0..3).map(|_| Pubkey::new_unique()).collect(),
+                compute_units: 1,
+                expected_profit: 1,
+            })
+            .collect();
+        let selection = select_pools(&candidates, &[], &[], config.bot.compute_unit_limit);
+        MintRoutePlan::new(mint_config, selection)
+    }
+
+    #[test]
+    fn test_diff_reports_added_pool() {
+        let old = config(&format!("pump_pool_list = [\"{}\"]", PUMP_POOL));
+        let new = config(&format!(
+            "pump_pool_list = [\"{}\"]\nraydium_pool_list = [\"{}\"]",
+            PUMP_POOL, RAYDIUM_POOL
+        ));
+
+        let diffs = diff_route_plans(&[plan(&old)], &[plan(&new)]);
+
+        assert_eq!(diffs.len(), 1);
+        let diff = &diffs[0];
+        assert_eq!(diff.mint, MINT);
+        assert_eq!(diff.added_pools, vec![RAYDIUM_POOL.to_string()]);
+        assert!(diff.removed_pools.is_empty());
+        assert_eq!(diff.profit_change(), 1);
+        assert_eq!(diff.account_change(), 3);
+        assert!(diff.to_string().contains(&format!("+ {}", RAYDIUM_POOL)));
+
+        // Reversed, the same pool shows as removed
+        let reversed = diff_route_plans(&[plan(&new)], &[plan(&old)]);
+        assert_eq!(reversed[0].removed_pools, vec![RAYDIUM_POOL.to_string()]);
+        assert_eq!(reversed[0].profit_change(), -1);
+    }
+}
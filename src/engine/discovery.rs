@@ -0,0 +1,244 @@
+/// Pool Discovery
+///
+/// Finds a mint's pools against the base mint with `getProgramAccounts` memcmp
+/// filters and prints them as config-ready pool lists.
+
+use crate::dex::pump::constants::pump_program_id;
+use crate::dex::raydium::constants::raydium_program_id;
+use crate::error::BotResult;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+
+// Raydium AMM v4 `AmmInfo`: fixed-size account with coin/pc mints at these offsets
+const RAYDIUM_AMM_DATA_LEN: u64 = 752;
+const RAYDIUM_COIN_MINT_OFFSET: usize = 400;
+const RAYDIUM_PC_MINT_OFFSET: usize = 432;
+
+// Pump AMM `Pool`: discriminator, bump, index and creator precede the base/quote mints
+const PUMP_BASE_MINT_OFFSET: usize = 8 + 1 + 2 + 32;
+const PUMP_QUOTE_MINT_OFFSET: usize = PUMP_BASE_MINT_OFFSET + 32;
+
+/// DEXes whose pools can be discovered on-chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DiscoverableDex {
+    RaydiumCpmm,
+    Pump,
+}
+
+impl DiscoverableDex {
+    pub fn dex_name(&self) -> &'static str {
+        match self {
+            DiscoverableDex::RaydiumCpmm => "Raydium CPMM",
+            DiscoverableDex::Pump => "Pump.fun",
+        }
+    }
+
+    /// `MintConfig` field the discovered pools belong in
+    pub fn config_key(&self) -> &'static str {
+        match self {
+            DiscoverableDex::RaydiumCpmm => "raydium_pool_list",
+            DiscoverableDex::Pump => "pump_pool_list",
+        }
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        match self {
+            DiscoverableDex::RaydiumCpmm => raydium_program_id(),
+            DiscoverableDex::Pump => pump_program_id(),
+        }
+    }
+
+    /// Offsets of the pool's two mints (coin/base, then pc/quote)
+    fn mint_offsets(&self) -> (usize, usize) {
+        match self {
+            DiscoverableDex::RaydiumCpmm => (RAYDIUM_COIN_MINT_OFFSET, RAYDIUM_PC_MINT_OFFSET),
+            DiscoverableDex::Pump => (PUMP_BASE_MINT_OFFSET, PUMP_QUOTE_MINT_OFFSET),
+        }
+    }
+
+    fn data_len(&self) -> Option<u64> {
+        match self {
+            DiscoverableDex::RaydiumCpmm => Some(RAYDIUM_AMM_DATA_LEN),
+            DiscoverableDex::Pump => None,
+        }
+    }
+
+    /// One filter set per slot order: `mint` first with `base_mint` second, and reversed
+    pub fn filters(&self, mint: &Pubkey, base_mint: &Pubkey) -> Vec<Vec<RpcFilterType>> {
+        let (first, second) = self.mint_offsets();
+        [(mint, base_mint), (base_mint, mint)]
+            .into_iter()
+            .map(|(first_mint, second_mint)| {
+                let mut filters: Vec<RpcFilterType> = self
+                    .data_len()
+                    .into_iter()
+                    .map(RpcFilterType::DataSize)
+                    .collect();
+                filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    first,
+                    first_mint.as_ref(),
+                )));
+                filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    second,
+                    second_mint.as_ref(),
+                )));
+                filters
+            })
+            .collect()
+    }
+
+    /// The pool's two mints, or `None` if the data is too short to hold them
+    fn pool_mints(&self, data: &[u8]) -> Option<(Pubkey, Pubkey)> {
+        let (first, second) = self.mint_offsets();
+        let read = |offset: usize| {
+            data.get(offset..offset + 32)
+                .map(|bytes| Pubkey::new_from_array(bytes.try_into().unwrap()))
+        };
+        Some((read(first)?, read(second)?))
+    }
+}
+
+/// Pools among `accounts` owned by the DEX program that pair `mint` with `base_mint`,
+/// sorted and deduplicated
+pub fn matching_pools(
+    dex: DiscoverableDex,
+    mint: &Pubkey,
+    base_mint: &Pubkey,
+    accounts: &[(Pubkey, Account)],
+) -> Vec<Pubkey> {
+    let program_id = dex.program_id();
+    let mut pools: Vec<Pubkey> = accounts
+        .iter()
+        .filter(|(_, account)| account.owner == program_id)
+        .filter(|(_, account)| {
+            matches!(
+                dex.pool_mints(&account.data),
+                Some((first, second))
+                    if (first == *mint && second == *base_mint)
+                        || (first == *base_mint && second == *mint)
+            )
+        })
+        .map(|(address, _)| *address)
+        .collect();
+    pools.sort();
+    pools.dedup();
+    pools
+}
+
+/// Query the DEX program for pools pairing `mint` with `base_mint`
+///
+/// `getProgramAccounts` scans the whole program, so public RPCs often reject or
+/// time out on it.
+pub fn discover_pools(
+    rpc_client: &RpcClient,
+    dex: DiscoverableDex,
+    mint: &Pubkey,
+    base_mint: &Pubkey,
+) -> BotResult<Vec<Pubkey>> {
+    let mut accounts = Vec::new();
+    for filters in dex.filters(mint, base_mint) {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        accounts.extend(rpc_client.get_program_accounts_with_config(&dex.program_id(), config)?);
+    }
+    Ok(matching_pools(dex, mint, base_mint, &accounts))
+}
+
+/// Render pools as a `[[routing.mint_config_list]]` entry line
+pub fn format_pool_list(dex: DiscoverableDex, pools: &[Pubkey]) -> String {
+    let addresses: Vec<String> = pools.iter().map(|pool| format!("\"{}\"", pool)).collect();
+    format!("{} = [{}]", dex.config_key(), addresses.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::SOL_MINT_PUBKEY;
+
+    fn pool_account(dex: DiscoverableDex, first: &Pubkey, second: &Pubkey) -> Account {
+        let (first_offset, second_offset) = dex.mint_offsets();
+        let mut data = vec![0u8; dex.data_len().unwrap_or(300) as usize];
+        data[first_offset..first_offset + 32].copy_from_slice(first.as_ref());
+        data[second_offset..second_offset + 32].copy_from_slice(second.as_ref());
+        Account {
+            lamports: 1,
+            data,
+            owner: dex.program_id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn filters_match(filters: &[RpcFilterType], data: &[u8]) -> bool {
+        filters.iter().all(|filter| match filter {
+            RpcFilterType::DataSize(len) => data.len() as u64 == *len,
+            RpcFilterType::Memcmp(memcmp) => memcmp.bytes_match(data),
+            _ => false,
+        })
+    }
+
+    #[test]
+    fn test_filters_cover_both_mint_orders() {
+        let mint = Pubkey::new_unique();
+        let sol = *SOL_MINT_PUBKEY;
+
+        for dex in [DiscoverableDex::RaydiumCpmm, DiscoverableDex::Pump] {
+            let filters = dex.filters(&mint, &sol);
+            assert_eq!(filters.len(), 2);
+
+            let mint_first = pool_account(dex, &mint, &sol);
+            let sol_first = pool_account(dex, &sol, &mint);
+            let unrelated = pool_account(dex, &Pubkey::new_unique(), &sol);
+
+            assert!(filters_match(&filters[0], &mint_first.data));
+            assert!(filters_match(&filters[1], &sol_first.data));
+            assert!(!filters.iter().any(|set| filters_match(set, &unrelated.data)));
+        }
+        assert!(matches!(
+            DiscoverableDex::RaydiumCpmm.filters(&mint, &sol)[0][0],
+            RpcFilterType::DataSize(RAYDIUM_AMM_DATA_LEN)
+        ));
+    }
+
+    #[test]
+    fn test_matching_pools_validates_mints_and_owner() {
+        let dex = DiscoverableDex::Pump;
+        let mint = Pubkey::new_unique();
+        let sol = *SOL_MINT_PUBKEY;
+        let good = Pubkey::new_unique();
+        let reversed = Pubkey::new_unique();
+
+        let mut foreign = pool_account(dex, &mint, &sol);
+        foreign.owner = Pubkey::new_unique();
+        let mut truncated = pool_account(dex, &mint, &sol);
+        truncated.data.truncate(PUMP_QUOTE_MINT_OFFSET);
+
+        let accounts = vec![
+            (good, pool_account(dex, &mint, &sol)),
+            (good, pool_account(dex, &mint, &sol)),
+            (reversed, pool_account(dex, &sol, &mint)),
+            (Pubkey::new_unique(), pool_account(dex, &mint, &Pubkey::new_unique())),
+            (Pubkey::new_unique(), foreign),
+            (Pubkey::new_unique(), truncated),
+        ];
+
+        let mut expected = vec![good, reversed];
+        expected.sort();
+        let pools = matching_pools(dex, &mint, &sol, &accounts);
+        assert_eq!(pools, expected);
+        assert_eq!(
+            format_pool_list(dex, &pools),
+            format!("pump_pool_list = [\"{}\", \"{}\"]", expected[0], expected[1])
+        );
+    }
+}
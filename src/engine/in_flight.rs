@@ -0,0 +1,251 @@
+/// Per-Mint In-Flight Guard
+///
+/// Ensures at most one send per mint is outstanding, so a slow send cannot
+/// overlap the next iteration (or another loop for the same mint) and reuse
+/// the same blockhash/opportunity. `CapitalLimiter` bounds the input of all
+/// outstanding sends together, across mints, and `TradeCooldown` keeps a mint
+/// from resending the same trade every iteration while the price stays put.
+
+use crate::constants::DUPLICATE_OPPORTUNITY_SIZE_TOLERANCE_BPS;
+use crate::opportunity::ArbitrageOpportunity;
+use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Shared registry of in-flight flags keyed by mint
+#[derive(Clone, Default)]
+pub struct InFlightGuard {
+    flags: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+}
+
+/// Held while a mint's send is in flight; releases the mint on drop
+pub struct InFlightToken {
+    flag: Arc<AtomicBool>,
+}
+
+impl InFlightGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Claim `mint` for one iteration, or `None` if its previous iteration is still in flight
+    pub fn try_begin(&self, mint: &str) -> Option<InFlightToken> {
+        let flag = self
+            .flags
+            .lock()
+            .unwrap()
+            .entry(mint.to_string())
+            .or_default()
+            .clone();
+
+        flag.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| InFlightToken { flag })
+    }
+}
+
+impl Drop for InFlightToken {
+    fn drop(&mut self) {
+        self.flag.store(false, Ordering::Release);
+    }
+}
+
+/// Portfolio-wide cap on the input lamports of every send in flight
+#[derive(Clone)]
+pub struct CapitalLimiter {
+    cap: u64,
+    reserved: Arc<AtomicU64>,
+}
+
+/// Held while a send's input counts against the cap; releases it on drop
+pub struct CapitalReservation {
+    amount: u64,
+    reserved: Arc<AtomicU64>,
+}
+
+impl CapitalLimiter {
+    pub fn new(cap: u64) -> Self {
+        Self {
+            cap,
+            reserved: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Reserve `amount` lamports, or `None` if that would take the total past the cap.
+    /// A zero amount is an unsized input, which the cap can't bound, so it is refused.
+    pub fn try_reserve(&self, amount: u64) -> Option<CapitalReservation> {
+        if amount == 0 {
+            return None;
+        }
+        self.reserved
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |reserved| {
+                reserved.checked_add(amount).filter(|total| *total <= self.cap)
+            })
+            .ok()
+            .map(|_| CapitalReservation {
+                amount,
+                reserved: self.reserved.clone(),
+            })
+    }
+
+    /// Input lamports currently reserved by in-flight sends
+    pub fn reserved(&self) -> u64 {
+        self.reserved.load(Ordering::Acquire)
+    }
+
+    pub fn cap(&self) -> u64 {
+        self.cap
+    }
+}
+
+impl Drop for CapitalReservation {
+    fn drop(&mut self) {
+        self.reserved.fetch_sub(self.amount, Ordering::AcqRel);
+    }
+}
+
+/// Remembers a mint's last sent trade so identical opportunities found right after
+/// it are suppressed for `cooldown`
+pub struct TradeCooldown {
+    cooldown: Duration,
+    last_sent: Mutex<Option<SentTrade>>,
+}
+
+struct SentTrade {
+    pools: Vec<Pubkey>,
+    input_amount: u64,
+    at: Instant,
+}
+
+impl TradeCooldown {
+    /// A zero `cooldown` never suppresses anything
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            last_sent: Mutex::new(None),
+        }
+    }
+
+    /// Record `opportunity` as sent at `now`, starting the cooldown
+    pub fn record_sent(&self, opportunity: &ArbitrageOpportunity, now: Instant) {
+        if self.cooldown.is_zero() {
+            return;
+        }
+        *self.last_sent.lock().unwrap() = Some(SentTrade {
+            pools: opportunity.pools.clone(),
+            input_amount: opportunity.input_amount,
+            at: now,
+        });
+    }
+
+    /// Whether `opportunity` goes through the same pools as the last sent trade, with
+    /// an input within `DUPLICATE_OPPORTUNITY_SIZE_TOLERANCE_BPS` of it, inside the cooldown
+    pub fn is_duplicate(&self, opportunity: &ArbitrageOpportunity, now: Instant) -> bool {
+        let last_sent = self.last_sent.lock().unwrap();
+        let Some(last) = last_sent.as_ref() else {
+            return false;
+        };
+        if now.saturating_duration_since(last.at) >= self.cooldown || last.pools != opportunity.pools {
+            return false;
+        }
+        let difference = last.input_amount.abs_diff(opportunity.input_amount) as u128;
+        let tolerance = last.input_amount.max(opportunity.input_amount) as u128
+            * DUPLICATE_OPPORTUNITY_SIZE_TOLERANCE_BPS as u128
+            / 10_000;
+        difference <= tolerance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_overlapping_iteration_is_skipped() {
+        let guard = InFlightGuard::new();
+
+        // A slow send holds the mint
+        let token = guard.try_begin("mint").unwrap();
+        let send = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            drop(token);
+        });
+
+        // A quick timer fires while the send is still outstanding
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(guard.try_begin("mint").is_none());
+        // Other mints are unaffected
+        assert!(guard.try_begin("other-mint").is_some());
+
+        send.await.unwrap();
+        assert!(guard.try_begin("mint").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_capital_cap_blocks_second_mint_until_first_resolves() {
+        let limiter = CapitalLimiter::new(1_500_000_000);
+
+        // The first mint's 1 SOL send is outstanding
+        let reservation = limiter.try_reserve(1_000_000_000).unwrap();
+        let send = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            drop(reservation);
+        });
+
+        // The second mint's 1 SOL send would put 2 SOL at risk
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(limiter.try_reserve(1_000_000_000).is_none());
+        assert_eq!(limiter.reserved(), 1_000_000_000);
+
+        send.await.unwrap();
+        assert_eq!(limiter.reserved(), 0);
+        let second = limiter.try_reserve(1_000_000_000).unwrap();
+        assert_eq!(limiter.reserved(), 1_000_000_000);
+        drop(second);
+        assert_eq!(limiter.reserved(), 0);
+
+        // Even with the cap untouched, an unsized input is not let through
+        assert!(limiter.try_reserve(0).is_none());
+    }
+
+    fn opportunity(pools: &[Pubkey], input_amount: u64) -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            pools: pools.to_vec(),
+            ..ArbitrageOpportunity::test_quote(input_amount, 10_000)
+        }
+    }
+
+    #[test]
+    fn test_identical_back_to_back_opportunities_send_once() {
+        let cooldown = TradeCooldown::new(Duration::from_millis(1_000));
+        let pools = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let start = Instant::now();
+
+        // Two loop iterations 400ms apart find the same trade
+        let mut sends = 0;
+        for at in [start, start + Duration::from_millis(400)] {
+            let opp = opportunity(&pools, 1_000_000_000);
+            if !cooldown.is_duplicate(&opp, at) {
+                sends += 1;
+                cooldown.record_sent(&opp, at);
+            }
+        }
+        assert_eq!(sends, 1);
+
+        let during = start + Duration::from_millis(500);
+        // A slightly different size is still the same trade; other pools or sizes are not
+        assert!(cooldown.is_duplicate(&opportunity(&pools, 1_005_000_000), during));
+        assert!(!cooldown.is_duplicate(&opportunity(&pools, 1_500_000_000), during));
+        assert!(!cooldown.is_duplicate(&opportunity(&[pools[0], Pubkey::new_unique()], 1_000_000_000), during));
+        // Once the cooldown has passed the trade may be sent again
+        let after = start + Duration::from_millis(1_000);
+        assert!(!cooldown.is_duplicate(&opportunity(&pools, 1_000_000_000), after));
+
+        let disabled = TradeCooldown::new(Duration::ZERO);
+        disabled.record_sent(&opportunity(&pools, 1_000_000_000), start);
+        assert!(!disabled.is_duplicate(&opportunity(&pools, 1_000_000_000), start));
+    }
+}
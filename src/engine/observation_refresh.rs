@@ -0,0 +1,218 @@
+/// Observation/Oracle Account Refresh
+///
+/// DLMM and adaptive-fee Whirlpool pools price a variable fee from volatility their
+/// program accumulates on every swap, and a quote built from a stale accumulator charges
+/// the wrong fee. The full pool refresh only runs every `POOL_REFRESH_INTERVAL_SECS`, so
+/// `ObservationRefresher` re-reads just the accounts holding it, each on its DEX's cadence
+/// from `[observation_refresh]`, and hands them to the pool's `update_observation`.
+
+use crate::config::ObservationRefreshConfig;
+use crate::dex::traits::DexPool;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// One observation account and when it's next due
+#[derive(Debug, Clone)]
+struct ObservedAccount {
+    address: Pubkey,
+    cadence: Duration,
+    next_due: Instant,
+}
+
+/// Observation/oracle accounts of one mint's pools
+#[derive(Debug, Default)]
+pub struct ObservationRefresher {
+    accounts: Vec<ObservedAccount>,
+}
+
+impl ObservationRefresher {
+    /// Track the observation account of every pool that has one, all due at `now`
+    pub fn new(pools: &[&dyn DexPool], cadences: &ObservationRefreshConfig, now: Instant) -> Self {
+        let mut refresher = Self::default();
+        refresher.track(pools, cadences, now);
+        refresher
+    }
+
+    /// Follow a change to the mint's pools: accounts already tracked keep their schedule,
+    /// those of new pools are due at `now` and those of removed pools are dropped
+    pub fn track(&mut self, pools: &[&dyn DexPool], cadences: &ObservationRefreshConfig, now: Instant) {
+        let previous = std::mem::take(&mut self.accounts);
+        self.accounts = pools
+            .iter()
+            .filter_map(|pool| {
+                let address = pool.observation_account()?;
+                let cadence = cadences.cadence(pool.dex_name())?;
+                let next_due = previous
+                    .iter()
+                    .find(|observed| observed.address == address)
+                    .map_or(now, |observed| observed.next_due);
+                Some(ObservedAccount {
+                    address,
+                    cadence,
+                    next_due,
+                })
+            })
+            .collect();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Accounts due for a re-read at `now`, to be read in one request
+    pub fn due(&self, now: Instant) -> Vec<Pubkey> {
+        self.accounts
+            .iter()
+            .filter(|observed| observed.next_due <= now)
+            .map(|observed| observed.address)
+            .collect()
+    }
+
+    /// Hand each of the `due` accounts read at `now` to the pools it belongs to and
+    /// schedule its next read. Returns how many pools took a new observation; one that
+    /// can't be parsed keeps the pool's previous state. On a failed read nothing is
+    /// applied, so the accounts stay due and are retried on the next iteration.
+    pub fn apply(
+        &mut self,
+        pools: Vec<&mut dyn DexPool>,
+        due: &[Pubkey],
+        fetched: &[Option<Account>],
+        now: Instant,
+    ) -> usize {
+        for observed in self.accounts.iter_mut().filter(|observed| due.contains(&observed.address)) {
+            observed.next_due = now + observed.cadence;
+        }
+        let mut updated = 0;
+        for pool in pools {
+            let Some(address) = pool.observation_account() else {
+                continue;
+            };
+            let Some(index) = due.iter().position(|due| *due == address) else {
+                continue;
+            };
+            match pool.update_observation(fetched.get(index).and_then(Option::as_ref)) {
+                Ok(()) => updated += 1,
+                Err(e) => warn!(
+                    "Failed to update {} pool {} from its observation account: {}",
+                    pool.dex_name(),
+                    pool.pool_address(),
+                    e
+                ),
+            }
+        }
+        updated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::whirlpool::initializer::WhirlpoolPool;
+    use crate::dex::whirlpool::state::AdaptiveFee;
+    use crate::error::BotResult;
+    use crate::rpc::{AccountFetcher, MockRpcClient};
+    use solana_sdk::clock::Slot;
+    use std::sync::Mutex;
+
+    /// Records every address it's asked for
+    struct RecordingFetcher {
+        rpc: MockRpcClient,
+        requested: Mutex<Vec<Pubkey>>,
+    }
+
+    impl AccountFetcher for RecordingFetcher {
+        fn fetch_account(&self, pubkey: &Pubkey) -> BotResult<Account> {
+            self.requested.lock().unwrap().push(*pubkey);
+            self.rpc.fetch_account(pubkey)
+        }
+
+        fn fetch_multiple_accounts(&self, pubkeys: &[Pubkey]) -> BotResult<Vec<Option<Account>>> {
+            self.requested.lock().unwrap().extend_from_slice(pubkeys);
+            self.rpc.fetch_multiple_accounts(pubkeys)
+        }
+
+        fn fetch_multiple_accounts_with_slot(
+            &self,
+            pubkeys: &[Pubkey],
+        ) -> BotResult<(Slot, Vec<Option<Account>>)> {
+            self.requested.lock().unwrap().extend_from_slice(pubkeys);
+            self.rpc.fetch_multiple_accounts_with_slot(pubkeys)
+        }
+
+        fn endpoint(&self) -> String {
+            self.rpc.endpoint()
+        }
+    }
+
+    /// Whirlpool oracle with a 4_000 control factor and 64-tick groups
+    fn oracle(volatility_accumulator: u32) -> Account {
+        let mut data = vec![0u8; AdaptiveFee::LEN];
+        data[54..58].copy_from_slice(&4_000u32.to_le_bytes());
+        data[58..62].copy_from_slice(&u32::MAX.to_le_bytes());
+        data[62..64].copy_from_slice(&64u16.to_le_bytes());
+        data[106..110].copy_from_slice(&volatility_accumulator.to_le_bytes());
+        Account { data, ..Account::default() }
+    }
+
+    /// Read what's due at `now` and hand it to `pool`, as the strategy loop does
+    fn refresh(
+        refresher: &mut ObservationRefresher,
+        fetcher: &RecordingFetcher,
+        pool: &mut WhirlpoolPool,
+        now: Instant,
+    ) -> usize {
+        let due = refresher.due(now);
+        if due.is_empty() {
+            return 0;
+        }
+        let fetched = fetcher.fetch_multiple_accounts(&due).unwrap();
+        refresher.apply(vec![pool as &mut dyn DexPool], &due, &fetched, now)
+    }
+
+    #[test]
+    fn test_observation_refetched_on_schedule_without_pool() {
+        let mut pool = WhirlpoolPool {
+            pool: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            token_vault_a: Pubkey::new_unique(),
+            token_vault_b: Pubkey::new_unique(),
+            tick_arrays: vec![Pubkey::new_unique()],
+            current_tick: 0,
+            token_mint_a: Pubkey::new_unique(),
+            token_mint_b: Pubkey::new_unique(),
+            liquidity: 0,
+            sqrt_price: 0,
+            adaptive_fee_rate: 0,
+        };
+        let fetcher = RecordingFetcher {
+            rpc: MockRpcClient::new(),
+            requested: Mutex::new(Vec::new()),
+        };
+        fetcher.rpc.add_account(pool.pool, Account::default());
+        fetcher.rpc.add_account(pool.oracle, oracle(10_000));
+        let cadences = ObservationRefreshConfig {
+            whirlpool_ms: Some(1_000),
+            ..ObservationRefreshConfig::default()
+        };
+
+        let start = Instant::now();
+        let mut refresher = ObservationRefresher::new(&[&pool as &dyn DexPool], &cadences, start);
+        assert_eq!(refresh(&mut refresher, &fetcher, &mut pool, start), 1);
+        assert_eq!(pool.adaptive_fee_rate, 164);
+
+        // Not due again until the cadence has passed
+        assert_eq!(refresh(&mut refresher, &fetcher, &mut pool, start + Duration::from_millis(500)), 0);
+        fetcher.rpc.add_account(pool.oracle, oracle(20_000));
+        assert_eq!(refresh(&mut refresher, &fetcher, &mut pool, start + Duration::from_millis(1_000)), 1);
+        // The quote prices the new fee: (20_000 * 64)^2 * 4_000 / 1e13 = 655.36
+        assert_eq!(pool.adaptive_fee_rate, 656);
+
+        // Re-tracking the same pools keeps the schedule
+        refresher.track(&[&pool as &dyn DexPool], &cadences, start + Duration::from_millis(1_500));
+        assert!(refresher.due(start + Duration::from_millis(1_500)).is_empty());
+
+        assert_eq!(*fetcher.requested.lock().unwrap(), vec![pool.oracle, pool.oracle]);
+    }
+}
@@ -0,0 +1,558 @@
+/// Arbitrage Opportunities
+///
+/// Centralizes the data the strategy loop uses to decide whether to send:
+/// route, sizing and expected profit, as produced by the quoting step, and the
+/// net profit once transaction costs are paid.
+
+use crate::account_metas::AccountMetasBuilder;
+use crate::constants::{BASIS_POINTS_DENOMINATOR, OPTIMAL_INPUT_TOLERANCE_LAMPORTS, SOL_DECIMALS};
+use crate::dex::traits::{
+    golden_section_max_input, optimal_constant_product_input, DexPool, SwapDirection,
+};
+use crate::metrics::METRICS;
+use crate::pools::{format_token_amount, MintPoolData};
+use crate::trade_split::{split_pools, split_trade, SplitPool};
+use solana_program::pubkey::Pubkey;
+use tracing::{debug, info};
+
+/// A quoted round trip through one or more pools
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ArbitrageOpportunity {
+    #[serde(serialize_with = "serialize_pubkey")]
+    pub mint: Pubkey,
+    /// DEX names in swap order
+    pub route: Vec<String>,
+    pub input_amount: u64,
+    /// SOL each leading pool of `pools` buys the mint with: one pool for a pair, several
+    /// for a split buy, none for routes sized on-chain. The remaining pools sell.
+    pub buy_inputs: Vec<u64>,
+    pub expected_output: u64,
+    pub expected_profit: u64,
+    /// Part of `expected_output` withheld by the mint's transfer fee
+    pub transfer_fees: u64,
+    #[serde(serialize_with = "serialize_pubkeys")]
+    pub pools: Vec<Pubkey>,
+    /// Distinct accounts the route's swaps reference (fewer means less that can go wrong)
+    pub account_count: usize,
+}
+
+// Addresses serialize as base58 strings rather than byte arrays
+fn serialize_pubkey<S: serde::Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(pubkey)
+}
+
+fn serialize_pubkeys<S: serde::Serializer>(pubkeys: &[Pubkey], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(pubkeys.iter().map(Pubkey::to_string))
+}
+
+impl ArbitrageOpportunity {
+    /// Build an opportunity from a quote, netting out the mint's transfer fee.
+    /// Counts towards `opportunities_found` when it is profitable.
+    pub fn from_quote(
+        mint_pool_data: &MintPoolData,
+        route: Vec<String>,
+        pools: Vec<Pubkey>,
+        input_amount: u64,
+        expected_output: u64,
+    ) -> Self {
+        let opportunity = Self {
+            mint: mint_pool_data.mint,
+            route,
+            input_amount,
+            buy_inputs: vec![input_amount],
+            expected_output,
+            expected_profit: mint_pool_data.expected_profit(input_amount, expected_output),
+            transfer_fees: mint_pool_data.transfer_fees(expected_output),
+            account_count: route_account_count(mint_pool_data, &pools),
+            pools,
+        };
+        if opportunity.expected_profit > 0 {
+            METRICS.inc_opportunity_found();
+        }
+        opportunity
+    }
+
+    /// Two-pool Raydium CPMM to Meteora DLMM quote for tests, buying with `input_amount`
+    #[cfg(test)]
+    pub fn test_quote(input_amount: u64, expected_profit: u64) -> Self {
+        Self {
+            mint: Pubkey::default(),
+            route: vec!["Raydium CPMM".to_string(), "Meteora DLMM".to_string()],
+            input_amount,
+            buy_inputs: vec![input_amount],
+            expected_output: input_amount + expected_profit,
+            expected_profit,
+            transfer_fees: 0,
+            pools: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            account_count: 14,
+        }
+    }
+
+    /// Route across every pool of the mint, left to the executor program to size and
+    /// quote on-chain when the transaction runs
+    pub fn for_pools(mint_pool_data: &MintPoolData) -> Self {
+        let pools = mint_pool_data.dex_pools();
+        let addresses: Vec<Pubkey> = pools.iter().map(|pool| pool.pool_address()).collect();
+        Self {
+            mint: mint_pool_data.mint,
+            route: pools.iter().map(|pool| pool.dex_name().to_string()).collect(),
+            input_amount: 0,
+            buy_inputs: Vec::new(),
+            expected_output: 0,
+            expected_profit: 0,
+            transfer_fees: 0,
+            account_count: route_account_count(mint_pool_data, &addresses),
+            pools: addresses,
+        }
+    }
+
+    /// Quote a buy-then-sell round trip through every ordered pair of the mint's pools,
+    /// each sized with `optimal_input_amount`. Only pairs whose quoted output exceeds the
+    /// input are returned.
+    pub fn quote_pairs(mint_pool_data: &MintPoolData, max_input_lamports: Option<u64>) -> Vec<Self> {
+        let pools = mint_pool_data.dex_pools();
+        let mut opportunities = Vec::new();
+        for (buy_index, buy) in pools.iter().enumerate() {
+            for (sell_index, sell) in pools.iter().enumerate() {
+                if buy_index == sell_index {
+                    continue;
+                }
+                let input_amount =
+                    optimal_input_amount(*buy, *sell, max_input_lamports, &mint_pool_data.mint);
+                if input_amount == 0 {
+                    continue;
+                }
+                let token_amount = buy.quote(input_amount, true);
+                let expected_output = sell.quote(token_amount, false);
+                if expected_output <= input_amount {
+                    continue;
+                }
+                debug!(
+                    "Mint {}: {} SOL buys {} tokens on {}, selling them on {} returns {} SOL",
+                    mint_pool_data.mint,
+                    format_token_amount(input_amount, SOL_DECIMALS),
+                    mint_pool_data.format_token_amount(token_amount),
+                    buy.dex_name(),
+                    sell.dex_name(),
+                    format_token_amount(expected_output, SOL_DECIMALS)
+                );
+                opportunities.push(Self::from_quote(
+                    mint_pool_data,
+                    vec![buy.dex_name().to_string(), sell.dex_name().to_string()],
+                    vec![buy.pool_address(), sell.pool_address()],
+                    input_amount,
+                    expected_output,
+                ));
+            }
+        }
+        opportunities
+    }
+
+    /// Quote buys spread over several of the mint's constant-product pools with
+    /// `split_trade`, each selling the tokens in one other pool. The total input is searched
+    /// for the most profitable size, bounded by the buy pools' SOL reserves and
+    /// `max_input_lamports`, and the buy uses at most `max_pools` pools and `max_accounts`
+    /// swap accounts together with the sell pool. Only splits over two or more pools that
+    /// show an edge are returned; single-pool buys are `quote_pairs`' routes.
+    pub fn quote_splits(
+        mint_pool_data: &MintPoolData,
+        max_input_lamports: Option<u64>,
+        max_pools: usize,
+        max_accounts: usize,
+    ) -> Vec<Self> {
+        let mut opportunities = Vec::new();
+        let candidates = split_pools(mint_pool_data, true);
+        if max_pools < 2 || candidates.len() < 2 {
+            return opportunities;
+        }
+        let pools = mint_pool_data.dex_pools();
+        let dex_name = |address: &Pubkey| {
+            pools
+                .iter()
+                .find(|pool| pool.pool_address() == *address)
+                .map_or("", |pool| pool.dex_name())
+                .to_string()
+        };
+        for &sell in &pools {
+            let buy_pools: Vec<SplitPool> = candidates
+                .iter()
+                .filter(|pool| pool.pool != sell.pool_address())
+                .copied()
+                .collect();
+            if buy_pools.len() < 2 {
+                continue;
+            }
+            let sell_accounts = mint_pool_data
+                .swap_accounts(sell, &mint_pool_data.wallet_account, Some(SwapDirection::Sell))
+                .len();
+            let buy_accounts = max_accounts.saturating_sub(sell_accounts);
+            let buy = |total_input: u64| split_trade(&buy_pools, total_input, max_pools, buy_accounts);
+            let profit = |total_input: u64| {
+                sell.quote(buy(total_input).output(), false) as i128 - total_input as i128
+            };
+            let liquidity = buy_pools.iter().map(|pool| pool.reserve_in).sum::<u64>();
+            let bound = max_input_lamports.map_or(liquidity, |max_input| max_input.min(liquidity));
+            let input_amount = golden_section_max_input(profit, bound, OPTIMAL_INPUT_TOLERANCE_LAMPORTS);
+            let split = buy(input_amount);
+            if split.legs.len() < 2 || profit(input_amount) <= 0 {
+                continue;
+            }
+
+            let mut route_pools = split.pools();
+            route_pools.push(sell.pool_address());
+            let expected_output = sell.quote(split.output(), false);
+            debug!(
+                "Mint {}: {} SOL split over {} pools buys {} tokens, selling them on {} returns {} SOL",
+                mint_pool_data.mint,
+                format_token_amount(input_amount, SOL_DECIMALS),
+                split.legs.len(),
+                mint_pool_data.format_token_amount(split.output()),
+                sell.dex_name(),
+                format_token_amount(expected_output, SOL_DECIMALS)
+            );
+            opportunities.push(Self {
+                buy_inputs: split.legs.iter().map(|leg| leg.input).collect(),
+                ..Self::from_quote(
+                    mint_pool_data,
+                    route_pools.iter().map(dex_name).collect(),
+                    route_pools,
+                    input_amount,
+                    expected_output,
+                )
+            });
+        }
+        opportunities
+    }
+
+    /// Expected profit per lamport of input; zero for routes sized on-chain
+    pub fn roi(&self) -> f64 {
+        if self.input_amount == 0 {
+            return 0.0;
+        }
+        self.expected_profit as f64 / self.input_amount as f64
+    }
+
+    /// Whether the expected profit clears `minimum_profit` (and is positive)
+    pub fn meets_threshold(&self, minimum_profit: u64) -> bool {
+        self.expected_profit > 0 && self.expected_profit >= minimum_profit
+    }
+
+    /// Profit left after every fee the trade pays, negative when the fees exceed the
+    /// gross edge
+    pub fn net_profit(
+        &self,
+        priority_fee: u64,
+        jito_tip: u64,
+        base_fee: u64,
+        transfer_fees: u64,
+    ) -> i64 {
+        let net = self.expected_output as i128
+            - self.input_amount as i128
+            - priority_fee as i128
+            - jito_tip as i128
+            - base_fee as i128
+            - transfer_fees as i128;
+        net.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    /// SOL the DEX fees take out of a quoted route: each buy pool's fee on its input and
+    /// the sell pool's fee grossed up from the SOL it pays out. Zero for routes sized
+    /// on-chain, whose amounts aren't known.
+    pub fn swap_fees(&self, mint_pool_data: &MintPoolData) -> u64 {
+        if self.input_amount == 0 {
+            return 0;
+        }
+        let pools = mint_pool_data.dex_pools();
+        let fee_bps = |address: &Pubkey| {
+            pools
+                .iter()
+                .find(|pool| pool.pool_address() == *address)
+                .map_or(0, |pool| (pool.fee_bps() as u64).min(BASIS_POINTS_DENOMINATOR))
+        };
+        let buy_fee: u64 = self
+            .pools
+            .iter()
+            .zip(&self.buy_inputs)
+            .map(|(buy, input)| {
+                (*input as u128 * fee_bps(buy) as u128 / BASIS_POINTS_DENOMINATOR as u128) as u64
+            })
+            .sum();
+        let sell_fee = match self.pools.split_first() {
+            Some((_, [.., sell])) => {
+                let bps = fee_bps(sell);
+                (self.expected_output as u128 * bps as u128)
+                    .checked_div((BASIS_POINTS_DENOMINATOR - bps) as u128)
+                    .unwrap_or(0) as u64
+            }
+            _ => 0,
+        };
+        buy_fee.saturating_add(sell_fee)
+    }
+
+    /// Whether a trade with `net_profit` is worth sending: it must be positive and
+    /// clear `minimum_profit`
+    pub fn should_send(&self, net_profit: i64, minimum_profit: u64) -> bool {
+        net_profit > 0 && net_profit as u64 >= minimum_profit
+    }
+}
+
+/// Distinct swap accounts referenced by the listed pools of the mint: programs and
+/// configs the pools share are one message key however many pools pass them
+fn route_account_count(mint_pool_data: &MintPoolData, pools: &[Pubkey]) -> usize {
+    let mut accounts = AccountMetasBuilder::new();
+    for pool in mint_pool_data
+        .dex_pools()
+        .into_iter()
+        .filter(|pool| pools.contains(&pool.pool_address()))
+    {
+        accounts.extend(
+            mint_pool_data
+                .swap_accounts(pool, &mint_pool_data.wallet_account, None)
+                .iter()
+                .cloned(),
+        );
+    }
+    accounts.unique().len()
+}
+
+/// Size a route that buys the mint in `buy` and sells it in `sell`: the profit-maximizing
+/// constant-product input for both pools' reserves and fees, capped at `max_input_lamports`.
+/// Routes through a concentrated-liquidity pool are sized with `optimal_quoted_input`.
+pub fn optimal_input_amount<B: DexPool + ?Sized, S: DexPool + ?Sized>(
+    buy: &B,
+    sell: &S,
+    max_input_lamports: Option<u64>,
+    mint: &Pubkey,
+) -> u64 {
+    if buy.is_concentrated() || sell.is_concentrated() {
+        return optimal_quoted_input(buy, sell, max_input_lamports);
+    }
+    let (buy_token, buy_sol) = buy.get_liquidity();
+    let (sell_token, sell_sol) = sell.get_liquidity();
+    let optimal = optimal_constant_product_input(
+        buy_sol,
+        buy_token,
+        buy.fee_bps(),
+        sell_token,
+        sell_sol,
+        sell.fee_bps(),
+    );
+    cap_input_amount(optimal, max_input_lamports, mint)
+}
+
+/// Profit-maximizing input found by searching over both pools' `quote`s, bounded by the
+/// buy pool's SOL liquidity and `max_input_lamports`; 0 when no size is profitable or
+/// either pool has no liquidity to quote (DLMM)
+pub fn optimal_quoted_input<B: DexPool + ?Sized, S: DexPool + ?Sized>(
+    buy: &B,
+    sell: &S,
+    max_input_lamports: Option<u64>,
+) -> u64 {
+    let (_, buy_sol) = buy.get_liquidity();
+    if buy_sol == 0 || sell.get_liquidity() == (0, 0) {
+        return 0;
+    }
+    let bound = max_input_lamports.map_or(buy_sol, |max_input| max_input.min(buy_sol));
+    let profit =
+        |amount_in: u64| sell.quote(buy.quote(amount_in, true), false) as i128 - amount_in as i128;
+    let input = golden_section_max_input(profit, bound, OPTIMAL_INPUT_TOLERANCE_LAMPORTS);
+    if profit(input) > 0 {
+        input
+    } else {
+        0
+    }
+}
+
+/// Clamp the sizing step's optimal input to the mint's `max_input_lamports`
+pub fn cap_input_amount(optimal_input: u64, max_input_lamports: Option<u64>, mint: &Pubkey) -> u64 {
+    match max_input_lamports {
+        Some(max_input) if optimal_input > max_input => {
+            info!(
+                "Mint {}: optimal input {} clamped to max_input_lamports {}",
+                mint, optimal_input, max_input
+            );
+            max_input
+        }
+        _ => optimal_input,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::raydium::clmm_initializer::RaydiumClmmPool;
+    use crate::dex::raydium::cp_initializer::RaydiumCpPool;
+
+    fn mint_pool_data(transfer_fee_bps: u16) -> MintPoolData {
+        let mut data = MintPoolData::new(
+            &Pubkey::new_unique().to_string(),
+            &Pubkey::new_unique().to_string(),
+            spl_token::ID,
+        )
+        .unwrap();
+        data.transfer_fee_bps = transfer_fee_bps;
+        data
+    }
+
+    #[test]
+    fn test_opportunity_threshold() {
+        let data = mint_pool_data(0);
+        let opportunity = ArbitrageOpportunity::from_quote(
+            &data,
+            vec!["Raydium CPMM".to_string(), "Meteora DLMM".to_string()],
+            vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            1_000_000,
+            1_020_000,
+        );
+
+        assert_eq!(opportunity.mint, data.mint);
+        assert_eq!(opportunity.expected_profit, 20_000);
+        assert!(opportunity.meets_threshold(10_000));
+        assert!(!opportunity.meets_threshold(25_000));
+    }
+
+    #[test]
+    fn test_unprofitable_opportunity_never_meets_threshold() {
+        let opportunity = ArbitrageOpportunity::from_quote(
+            &mint_pool_data(100),
+            vec!["Pump.fun".to_string()],
+            vec![Pubkey::new_unique()],
+            1_000_000,
+            1_010_000,
+        );
+
+        assert_eq!(opportunity.expected_profit, 0);
+        assert!(!opportunity.meets_threshold(0));
+    }
+
+    #[test]
+    fn test_net_profit_deducts_fees() {
+        let opportunity = ArbitrageOpportunity::from_quote(
+            &mint_pool_data(0),
+            vec!["Raydium CPMM".to_string()],
+            vec![Pubkey::new_unique()],
+            1_000_000,
+            1_200_000,
+        );
+
+        let net = opportunity.net_profit(20_000, 100_000, 5_000, opportunity.transfer_fees);
+        assert_eq!(net, 75_000);
+        assert!(opportunity.should_send(net, 50_000));
+        assert!(!opportunity.should_send(net, 80_000));
+    }
+
+    #[test]
+    fn test_positive_gross_negative_net_is_not_sent() {
+        // Gross edge of 20_000 lamports, but the Jito tip alone is 100_000
+        let opportunity = ArbitrageOpportunity::from_quote(
+            &mint_pool_data(0),
+            vec!["Raydium CPMM".to_string(), "Meteora DLMM".to_string()],
+            vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            1_000_000,
+            1_020_000,
+        );
+        assert!(opportunity.meets_threshold(0));
+
+        let net = opportunity.net_profit(1_400, 100_000, 5_000, opportunity.transfer_fees);
+        assert_eq!(net, -86_400);
+        assert!(!opportunity.should_send(net, 0));
+    }
+
+    #[test]
+    fn test_transfer_fees_reduce_net_profit() {
+        let opportunity = ArbitrageOpportunity::from_quote(
+            &mint_pool_data(100),
+            vec!["Pump.fun".to_string()],
+            vec![Pubkey::new_unique()],
+            1_000_000,
+            1_050_000,
+        );
+        // 1_050_000 * 0.99 * 0.99 = 1_029_105
+        assert_eq!(opportunity.transfer_fees, 20_895);
+
+        let net = opportunity.net_profit(0, 0, 5_000, opportunity.transfer_fees);
+        assert_eq!(net, 24_105);
+        assert_eq!(net, opportunity.expected_profit as i64 - 5_000);
+        assert!(!opportunity.should_send(opportunity.net_profit(0, 30_000, 5_000, 20_895), 0));
+    }
+
+    fn cp_pool(token_reserve: u64, sol_reserve: u64) -> RaydiumCpPool {
+        RaydiumCpPool {
+            pool: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            sol_vault: Pubkey::new_unique(),
+            amm_config: Pubkey::new_unique(),
+            observation: Pubkey::new_unique(),
+            fee_bps: 25,
+            token_reserve,
+            sol_reserve,
+        }
+    }
+
+    #[test]
+    fn test_split_buy_beats_the_best_pair() {
+        const SOL: u64 = 1_000_000_000;
+        let mut data = mint_pool_data(0);
+        // Two pools sell the token cheap; the third buys it back 10% higher
+        data.raydium_cp_pools = vec![
+            cp_pool(10_000 * SOL, 100 * SOL),
+            cp_pool(10_000 * SOL, 100 * SOL),
+            cp_pool(10_000 * SOL, 110 * SOL),
+        ];
+        let sell = data.raydium_cp_pools[2].pool;
+
+        let best_pair = ArbitrageOpportunity::quote_pairs(&data, None)
+            .into_iter()
+            .filter(|pair| pair.pools[1] == sell)
+            .max_by_key(|pair| pair.expected_profit)
+            .unwrap();
+        let splits = ArbitrageOpportunity::quote_splits(&data, None, 2, 64);
+        assert_eq!(splits.len(), 1);
+        let split = &splits[0];
+        assert_eq!(split.buy_inputs.len(), 2);
+        assert_eq!(split.pools[2], sell);
+        assert_eq!(split.buy_inputs.iter().sum::<u64>(), split.input_amount);
+        assert!(split.expected_profit > best_pair.expected_profit);
+        // Every buy pays its own pool's fee on its own input
+        assert!(split.swap_fees(&data) > split.input_amount * 25 / 10_000);
+
+        // One pool per buy is a pair, not a split
+        assert!(ArbitrageOpportunity::quote_splits(&data, None, 1, 64).is_empty());
+    }
+
+    #[test]
+    fn test_concentrated_pool_routes_are_sized() {
+        const SOL: u64 = 1_000_000_000;
+        let mut data = mint_pool_data(0);
+        // 0.01 SOL per token in the current range, the same depth as a 100 SOL CP pool
+        data.raydium_clmm_pools = vec![RaydiumClmmPool {
+            pool: Pubkey::new_unique(),
+            amm_config: Pubkey::new_unique(),
+            observation_state: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            sol_vault: Pubkey::new_unique(),
+            tick_arrays: vec![],
+            current_tick: 0,
+            liquidity: 1_000 * SOL as u128,
+            sqrt_price_x64: (1u128 << 64) / 10,
+            fee_bps: 25,
+            sol_is_token_0: false,
+        }];
+        data.raydium_cp_pools = vec![cp_pool(10_000 * SOL, 110 * SOL)];
+
+        let pairs = ArbitrageOpportunity::quote_pairs(&data, None);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].pools[0], data.raydium_clmm_pools[0].pool);
+        assert!(pairs[0].input_amount > 0);
+        assert!(pairs[0].expected_profit > 0);
+    }
+
+    #[test]
+    fn test_input_amount_capped() {
+        let mint = Pubkey::new_unique();
+
+        assert_eq!(cap_input_amount(5_000_000_000, Some(2_000_000_000), &mint), 2_000_000_000);
+        assert_eq!(cap_input_amount(1_000_000_000, Some(2_000_000_000), &mint), 1_000_000_000);
+        assert_eq!(cap_input_amount(5_000_000_000, None, &mint), 5_000_000_000);
+    }
+}
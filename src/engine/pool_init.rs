@@ -0,0 +1,147 @@
+/// Bounded Startup Pool Initialization
+///
+/// With hundreds of configured pools, initializing every mint at once fires all of
+/// their RPC reads together and trips RPC rate limits, while initializing them one
+/// by one is slow and silent. `PoolInitLimiter` lets the mints initialize
+/// concurrently but runs at most a fixed number of DEX batches at a time, and
+/// reports how many pools are done after each batch. The initializers read with the
+/// blocking RPC client, so each batch runs on the blocking pool (`run_blocking_batch`)
+/// for batches to actually overlap.
+
+use crate::error::{BotError, BotResult};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::Semaphore;
+use tracing::info;
+
+/// Called with the pools done so far and the total
+pub type InitProgressFn = Box<dyn Fn(usize, usize) + Send + Sync>;
+
+/// Shared by every mint's initialization during startup
+pub struct PoolInitLimiter {
+    permits: Semaphore,
+    total: usize,
+    done: AtomicUsize,
+    on_progress: InitProgressFn,
+}
+
+impl PoolInitLimiter {
+    pub fn new(max_concurrent: usize, total: usize, on_progress: InitProgressFn) -> Self {
+        Self {
+            permits: Semaphore::new(max_concurrent.max(1)),
+            total,
+            done: AtomicUsize::new(0),
+            on_progress,
+        }
+    }
+
+    /// Limiter that logs "initialized N/M pools"
+    pub fn logging(max_concurrent: usize, total: usize) -> Self {
+        Self::new(
+            max_concurrent,
+            total,
+            Box::new(|done, total| info!("initialized {}/{} pools", done, total)),
+        )
+    }
+
+    /// Run one batch of `pools` pools once a slot is free, then count them as done
+    pub async fn run<F: Future>(&self, pools: usize, batch: F) -> F::Output {
+        let output = {
+            // Never closed, so acquiring can't fail
+            let _permit = self.permits.acquire().await.expect("semaphore closed");
+            batch.await
+        };
+        self.advance(pools);
+        output
+    }
+
+    /// Count pools that are done without an RPC batch, e.g. those of a disabled DEX
+    pub fn advance(&self, pools: usize) {
+        if pools == 0 {
+            return;
+        }
+        let done = self.done.fetch_add(pools, Ordering::AcqRel) + pools;
+        (self.on_progress)(done, self.total);
+    }
+
+    pub fn done(&self) -> usize {
+        self.done.load(Ordering::Acquire)
+    }
+}
+
+/// Run `batch` on the blocking pool, off the async workers, so batches started together
+/// overlap instead of holding up a worker one after another
+pub async fn run_blocking_batch<T, F>(batch: F) -> BotResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    tokio::task::spawn_blocking(batch)
+        .await
+        .map_err(|e| BotError::Other(anyhow::anyhow!("pool initialization batch panicked: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_progress_reported_within_concurrency_bound() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let recorded = reports.clone();
+        let limiter = PoolInitLimiter::new(
+            3,
+            35,
+            Box::new(move |done, total| recorded.lock().unwrap().push((done, total))),
+        );
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+
+        let batches = (0..10).map(|_| {
+            limiter.run(3, async {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+            })
+        });
+        futures::future::join_all(batches).await;
+        // A disabled DEX's pools count without a batch
+        limiter.advance(5);
+
+        let max_running = max_running.load(Ordering::SeqCst);
+        assert!(max_running <= 3, "{} batches ran at once", max_running);
+        assert!(max_running > 1);
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.len(), 11);
+        assert!(reports.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(reports.last(), Some(&(35, 35)));
+        assert_eq!(limiter.done(), 35);
+    }
+
+    #[tokio::test]
+    async fn test_blocking_batches_overlap_within_the_bound() {
+        let limiter = PoolInitLimiter::new(2, 6, Box::new(|_, _| {}));
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        let batches = (0..6).map(|_| {
+            let (running, max_running) = (running.clone(), max_running.clone());
+            // Blocks its thread the way an RPC read through the blocking client does
+            limiter.run(1, run_blocking_batch(move || {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(20));
+                running.fetch_sub(1, Ordering::SeqCst);
+            }))
+        });
+        let results = futures::future::join_all(batches).await;
+
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+        assert_eq!(limiter.done(), 6);
+    }
+}
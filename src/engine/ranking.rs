@@ -0,0 +1,127 @@
+/// Opportunity Ranking
+///
+/// Orders the opportunities found in one iteration so the send path can take the
+/// best one. What counts as best is configurable with `bot.ranking`.
+
+use crate::error::BotError;
+use crate::opportunity::ArbitrageOpportunity;
+use std::cmp::Ordering;
+
+/// Strategy for choosing between competing opportunities
+pub trait RankingStrategy: Send + Sync {
+    /// Indices into `opps`, best first
+    fn rank(&self, opps: &[ArbitrageOpportunity]) -> Vec<usize>;
+}
+
+/// Highest expected profit in lamports first
+pub struct MaxProfit;
+
+/// Highest expected profit per lamport of input first
+pub struct MaxRoi;
+
+/// Fewest referenced accounts first, then highest expected profit
+pub struct LowestAccountCount;
+
+impl RankingStrategy for MaxProfit {
+    fn rank(&self, opps: &[ArbitrageOpportunity]) -> Vec<usize> {
+        sorted_indices(opps, |a, b| b.expected_profit.cmp(&a.expected_profit))
+    }
+}
+
+impl RankingStrategy for MaxRoi {
+    fn rank(&self, opps: &[ArbitrageOpportunity]) -> Vec<usize> {
+        sorted_indices(opps, |a, b| b.roi().total_cmp(&a.roi()))
+    }
+}
+
+impl RankingStrategy for LowestAccountCount {
+    fn rank(&self, opps: &[ArbitrageOpportunity]) -> Vec<usize> {
+        sorted_indices(opps, |a, b| {
+            a.account_count
+                .cmp(&b.account_count)
+                .then(b.expected_profit.cmp(&a.expected_profit))
+        })
+    }
+}
+
+/// Stable sort, so ties keep the order the opportunities were found in
+fn sorted_indices<F>(opps: &[ArbitrageOpportunity], compare: F) -> Vec<usize>
+where
+    F: Fn(&ArbitrageOpportunity, &ArbitrageOpportunity) -> Ordering,
+{
+    let mut indices: Vec<usize> = (0..opps.len()).collect();
+    indices.sort_by(|&a, &b| compare(&opps[a], &opps[b]));
+    indices
+}
+
+/// Built-in strategy for a `ranking` config value
+pub fn ranking_strategy(name: &str) -> Result<Box<dyn RankingStrategy>, BotError> {
+    match name {
+        "max_profit" => Ok(Box::new(MaxProfit)),
+        "max_roi" => Ok(Box::new(MaxRoi)),
+        "lowest_account_count" => Ok(Box::new(LowestAccountCount)),
+        other => Err(BotError::ConfigError(format!(
+            "ranking must be \"max_profit\", \"max_roi\" or \"lowest_account_count\", got: {}",
+            other
+        ))),
+    }
+}
+
+/// The top-ranked opportunity, if there is any
+pub fn pick_top(
+    strategy: &dyn RankingStrategy,
+    mut opps: Vec<ArbitrageOpportunity>,
+) -> Option<ArbitrageOpportunity> {
+    let best = strategy.rank(&opps).first().copied()?;
+    Some(opps.swap_remove(best))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opportunity(input_amount: u64, expected_profit: u64, account_count: usize) -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            account_count,
+            ..ArbitrageOpportunity::test_quote(input_amount, expected_profit)
+        }
+    }
+
+    /// Big but capital-hungry, small but efficient, and a lean route with middling profit
+    fn opportunities() -> Vec<ArbitrageOpportunity> {
+        vec![
+            opportunity(10_000_000_000, 50_000, 30),
+            opportunity(100_000_000, 20_000, 24),
+            opportunity(1_000_000_000, 30_000, 12),
+        ]
+    }
+
+    #[test]
+    fn test_max_profit_ranking() {
+        assert_eq!(MaxProfit.rank(&opportunities()), vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn test_max_roi_ranking() {
+        assert_eq!(MaxRoi.rank(&opportunities()), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn test_lowest_account_count_ranking() {
+        let mut opps = opportunities();
+        opps.push(opportunity(1_000_000_000, 40_000, 12));
+
+        // Equal account counts fall back to profit
+        assert_eq!(LowestAccountCount.rank(&opps), vec![3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_ranking_from_config_name() {
+        let strategy = ranking_strategy("max_roi").unwrap();
+        let top = pick_top(strategy.as_ref(), opportunities()).unwrap();
+        assert_eq!(top.expected_profit, 20_000);
+
+        assert!(pick_top(&MaxProfit, Vec::new()).is_none());
+        assert!(ranking_strategy("random").is_err());
+    }
+}
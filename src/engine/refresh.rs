@@ -1,8 +1,10 @@
 use crate::dex::{
+    lifinity::initializer::LifinityInitializer,
     meteora::{
         damm_initializer::MeteoraDammInitializer, damm_v2_initializer::MeteoraDammV2Initializer,
         dlmm_initializer::MeteoraDlmmInitializer,
     },
+    phoenix::initializer::PhoenixInitializer,
     pump::initializer::PumpInitializer,
     raydium::{
         clmm_initializer::RaydiumClmmInitializer, cp_initializer::RaydiumCpInitializer,
@@ -13,12 +15,21 @@ use crate::dex::{
     vertigo::initializer::VertigoInitializer,
     whirlpool::initializer::WhirlpoolInitializer,
 };
+use crate::constants::DEFAULT_RPC_CACHE_TTL_SECS;
+use crate::error::{BotError, BotResult};
+use crate::metrics::METRICS;
+use crate::mint_checks::{mint_decimals, MintRestrictions};
+use crate::pool_init::{run_blocking_batch, PoolInitLimiter};
 use crate::pools::MintPoolData;
+use crate::rpc::{AccountFetcher, RpcPool};
 use solana_client::rpc_client::RpcClient;
 use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use solana_sdk::clock::Slot;
+use std::collections::HashSet;
 use std::str::FromStr;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
 pub async fn initialize_pool_data(
     mint: &str,
@@ -33,12 +44,21 @@ pub async fn initialize_pool_data(
     solfi_pools: Option<&Vec<String>>,
     meteora_damm_v2_pools: Option<&Vec<String>>,
     vertigo_pools: Option<&Vec<String>>,
+    lifinity_pools: Option<&Vec<String>>,
+    phoenix_pools: Option<&Vec<String>>,
+    disabled_dexes: &[String],
     rpc_client: Arc<RpcClient>,
+    limiter: Option<&PoolInitLimiter>,
 ) -> anyhow::Result<MintPoolData> {
     info!("Initializing pool data for mint: {}", mint);
 
     let mint_pubkey = Pubkey::from_str(mint)?;
-    let mint_account = rpc_client.get_account(&mint_pubkey)?;
+    let mint_account = RpcPool::with_fetchers(
+        vec![rpc_client.clone() as Arc<dyn AccountFetcher>],
+        DEFAULT_RPC_CACHE_TTL_SECS,
+    )
+    .get_account_with_retry(&mint_pubkey)
+    .await?;
 
     let token_2022_program_id =
         Pubkey::from_str("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb").unwrap();
@@ -52,27 +72,56 @@ pub async fn initialize_pool_data(
 
     info!("Detected token program: {}", token_program);
     let mut pool_data = MintPoolData::new(mint, wallet_account, token_program)?;
+    match mint_decimals(&mint_account.data) {
+        Ok(decimals) => pool_data.decimals = decimals,
+        Err(e) => warn!("Failed to read decimals of mint {}: {}", mint, e),
+    }
+    if token_program == token_2022_program_id {
+        match MintRestrictions::parse(&mint_account.data) {
+            Ok(restrictions) => {
+                pool_data.transfer_fee_bps = restrictions.transfer_fee_bps.unwrap_or(0);
+                if pool_data.transfer_fee_bps > 0 {
+                    info!(
+                        "Mint {} charges a {} bps transfer fee; expected profit is reduced accordingly",
+                        mint, pool_data.transfer_fee_bps
+                    );
+                }
+            }
+            Err(e) => tracing::warn!("Failed to parse Token-2022 extensions for {}: {}", mint, e),
+        }
+    }
 
     // Helper macro to initialize pools
     macro_rules! init_pools {
         ($pool_list:expr, $initializer_type:ty, $target_vec:expr, $name:expr) => {
-            if let Some(pools) = $pool_list {
+            let initializer = <$initializer_type>::new();
+            if let Some(pools) = enabled_pool_list($pool_list, initializer.dex_name(), disabled_dexes) {
                 if !pools.is_empty() {
                     info!("Initializing {} {} pools...", pools.len(), $name);
-                    let initializer = <$initializer_type>::new();
-                    match initializer
-                        .initialize_pools(pools, rpc_client.clone(), &mint_pubkey)
-                        .await
-                    {
+                    let (addresses, rpc_client) = (pools.clone(), rpc_client.clone());
+                    let batch = run_blocking_batch(move || {
+                        futures::executor::block_on(initializer.initialize_pools(&addresses, rpc_client, &mint_pubkey))
+                    });
+                    let initialized = match limiter {
+                        Some(limiter) => limiter.run(pools.len(), batch).await,
+                        None => batch.await,
+                    }
+                    .and_then(|initialized| initialized);
+                    match initialized {
                         Ok(initialized) => {
                             info!("Successfully initialized {} {} pools", initialized.len(), $name);
+                            METRICS.add_pools_initialized_for_dex($name, initialized.len() as u64);
                             $target_vec.extend(initialized);
                         }
                         Err(e) => {
                             tracing::error!("Failed to initialize {} pools: {}", $name, e);
+                            METRICS.inc_pool_failure_for_dex($name);
                         }
                     }
                 }
+            } else if let (Some(limiter), Some(pools)) = (limiter, $pool_list) {
+                // Still part of the progress total
+                limiter.advance(pools.len());
             }
         };
     }
@@ -157,6 +206,299 @@ pub async fn initialize_pool_data(
         "Vertigo"
     );
 
+    // 11. Lifinity
+    init_pools!(
+        lifinity_pools,
+        LifinityInitializer,
+        pool_data.lifinity_pools,
+        "Lifinity"
+    );
+
+    // 12. Phoenix
+    init_pools!(
+        phoenix_pools,
+        PhoenixInitializer,
+        pool_data.phoenix_pools,
+        "Phoenix"
+    );
+
+    // Fix account ordering regardless of initialization completion order
+    pool_data.sort_pools();
+    pool_data.cache_swap_accounts();
+
     info!("Pool initialization complete for mint: {}", mint);
     Ok(pool_data)
 }
+
+/// Reject pool state the RPC node served at `account_slot` when that is more than
+/// `max_staleness` slots behind `latest_slot`. The slot is the context of the
+/// iteration's own state read, so the check costs no request.
+pub fn check_account_freshness(account_slot: Slot, latest_slot: Slot, max_staleness: u64) -> BotResult<()> {
+    if latest_slot.saturating_sub(account_slot) > max_staleness {
+        METRICS.inc_stale_account_rejection();
+        return Err(BotError::StaleAccountData {
+            account_slot,
+            latest_slot,
+            max_staleness,
+        });
+    }
+    Ok(())
+}
+
+/// Re-read every pool's `state_accounts` and hand them to its `update_state`, so quotes
+/// price the reserves of this iteration rather than those seen at startup. Returns the
+/// oldest slot the reads were served at, None when every state account is pinned. A pool
+/// whose state can't be parsed keeps its previous state.
+pub async fn update_pool_states(rpc_pool: &RpcPool, pool_data: &mut MintPoolData) -> BotResult<Option<Slot>> {
+    let per_pool: Vec<Vec<Pubkey>> = pool_data
+        .dex_pools()
+        .iter()
+        .map(|pool| pool.state_accounts())
+        .collect();
+    let keys: Vec<Pubkey> = per_pool.iter().flatten().copied().collect();
+    // Pinned configs are served from the pins, so only vaults and markets are read
+    let (oldest_slot, accounts) = rpc_pool.get_fresh_accounts(&keys).await?;
+
+    let mint = pool_data.mint;
+    let mut pool_accounts = accounts.as_slice();
+    for (pool, keys) in pool_data.dex_pools_mut().into_iter().zip(&per_pool) {
+        let (state, rest) = pool_accounts.split_at(keys.len());
+        pool_accounts = rest;
+        if let Err(e) = pool.update_state(state) {
+            warn!("Mint {}: failed to update {} pool {}: {}", mint, pool.dex_name(), pool.pool_address(), e);
+        }
+    }
+    Ok(oldest_slot)
+}
+
+/// Result of re-checking a mint's pools on-chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolRefresh {
+    /// Pools dropped because their account was closed
+    pub removed: Vec<Pubkey>,
+    /// Pools left in the mint's route
+    pub remaining: usize,
+}
+
+/// Re-fetch every pool account and drop the pools closed since startup, so the
+/// route never keeps trading against stale data
+pub fn refresh_pools(
+    fetcher: &dyn AccountFetcher,
+    pool_data: &mut MintPoolData,
+) -> BotResult<PoolRefresh> {
+    let addresses: Vec<Pubkey> = pool_data
+        .dex_pools()
+        .iter()
+        .map(|pool| pool.pool_address())
+        .collect();
+    let accounts = fetcher.fetch_multiple_accounts(&addresses)?;
+
+    let removed: Vec<Pubkey> = addresses
+        .iter()
+        .zip(&accounts)
+        .filter(|(_, account)| account.as_ref().map_or(true, is_closed))
+        .map(|(address, _)| *address)
+        .collect();
+    if !removed.is_empty() {
+        for pool in &removed {
+            warn!("Mint {}: pool {} was closed on-chain, removing it", pool_data.mint, pool);
+            METRICS.inc_pool_removed();
+        }
+        let keep: HashSet<Pubkey> = addresses
+            .into_iter()
+            .filter(|address| !removed.contains(address))
+            .collect();
+        pool_data.retain_pools(&keep);
+    }
+
+    Ok(PoolRefresh {
+        removed,
+        remaining: pool_data.dex_pools().len(),
+    })
+}
+
+/// Adopt `reinitialized` when it holds every pool `pool_data` has and more of
+/// `route_pools`, e.g. once a pool that failed to initialize or was closed is back.
+/// Pools outside `route_pools` are dropped from it first. Returns how many pools came back.
+pub fn restore_pools(
+    pool_data: &mut MintPoolData,
+    mut reinitialized: MintPoolData,
+    route_pools: &HashSet<Pubkey>,
+) -> usize {
+    reinitialized.retain_pools(route_pools);
+    let available: HashSet<Pubkey> = reinitialized
+        .dex_pools()
+        .iter()
+        .map(|pool| pool.pool_address())
+        .collect();
+    let current = pool_data.dex_pools();
+    if available.len() <= current.len() || !current.iter().all(|pool| available.contains(&pool.pool_address())) {
+        return 0;
+    }
+    let restored = available.len() - current.len();
+    reinitialized.route_directions = std::mem::take(&mut pool_data.route_directions);
+    *pool_data = reinitialized;
+    restored
+}
+
+/// Closed accounts are drained of lamports and their data zeroed
+fn is_closed(account: &Account) -> bool {
+    account.lamports == 0 || account.data.iter().all(|byte| *byte == 0)
+}
+
+/// `pools`, unless `dex_name` is in `disabled_dexes`
+fn enabled_pool_list<'a>(
+    pools: Option<&'a Vec<String>>,
+    dex_name: &str,
+    disabled_dexes: &[String],
+) -> Option<&'a Vec<String>> {
+    let pools = pools?;
+    if disabled_dexes.iter().any(|disabled| disabled == dex_name) {
+        if !pools.is_empty() {
+            info!("Skipping {} {} pools: DEX is disabled", pools.len(), dex_name);
+        }
+        return None;
+    }
+    Some(pools)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::raydium::cp_initializer::RaydiumCpPool;
+    use crate::dex::traits::DexPool;
+    use crate::opportunity::ArbitrageOpportunity;
+    use crate::rpc::MockRpcClient;
+
+    fn pool_account(data: Vec<u8>) -> Account {
+        Account {
+            lamports: 2_039_280,
+            data,
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_refresh_removes_closed_pools() {
+        let mut pool_data = MintPoolData::new(
+            &Pubkey::new_unique().to_string(),
+            &Pubkey::new_unique().to_string(),
+            spl_token::ID,
+        )
+        .unwrap();
+        let pools: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        for pool in &pools {
+            pool_data
+                .add_raydium_pool(
+                    &pool.to_string(),
+                    &Pubkey::new_unique().to_string(),
+                    &Pubkey::new_unique().to_string(),
+                )
+                .unwrap();
+        }
+
+        let rpc = MockRpcClient::new();
+        rpc.add_account(pools[0], pool_account(vec![1; 64]));
+        // pools[1] is gone entirely, pools[2] was closed and zeroed
+        rpc.add_account(pools[2], pool_account(vec![0; 64]));
+
+        let refresh = refresh_pools(&rpc, &mut pool_data).unwrap();
+        assert_eq!(refresh.removed, vec![pools[1], pools[2]]);
+        assert_eq!(refresh.remaining, 1);
+
+        // The surviving pool still routes
+        assert_eq!(ArbitrageOpportunity::for_pools(&pool_data).pools, vec![pools[0]]);
+        let refresh = refresh_pools(&rpc, &mut pool_data).unwrap();
+        assert!(refresh.removed.is_empty());
+        assert_eq!(refresh.remaining, 1);
+
+        rpc.clear();
+        assert_eq!(refresh_pools(&rpc, &mut pool_data).unwrap().remaining, 0);
+    }
+
+    #[test]
+    fn test_stale_slot_context_rejected() {
+        assert!(check_account_freshness(1_000, 1_003, 4).is_ok());
+        // A node ahead of the cached latest slot is never stale
+        assert!(check_account_freshness(1_000, 990, 4).is_ok());
+
+        let rejections = METRICS.stale_account_rejections.load(std::sync::atomic::Ordering::Relaxed);
+        assert!(matches!(
+            check_account_freshness(990, 1_000, 4),
+            Err(BotError::StaleAccountData {
+                account_slot: 990,
+                latest_slot: 1_000,
+                max_staleness: 4
+            })
+        ));
+        assert!(METRICS.stale_account_rejections.load(std::sync::atomic::Ordering::Relaxed) > rejections);
+    }
+
+    #[test]
+    fn test_disabled_dex_pools_are_skipped() {
+        let pump_pools = vec![Pubkey::new_unique().to_string()];
+        let raydium_pools = vec![Pubkey::new_unique().to_string()];
+        let disabled = vec!["Pump.fun".to_string()];
+
+        let pump = PumpInitializer::new();
+        assert!(enabled_pool_list(Some(&pump_pools), pump.dex_name(), &disabled).is_none());
+
+        let raydium = RaydiumCpmmInitializer::new();
+        assert_eq!(
+            enabled_pool_list(Some(&raydium_pools), raydium.dex_name(), &disabled),
+            Some(&raydium_pools)
+        );
+        assert_eq!(
+            enabled_pool_list(Some(&pump_pools), pump.dex_name(), &[]),
+            Some(&pump_pools)
+        );
+    }
+
+    fn token_account(amount: u64) -> Account {
+        let mut data = vec![0; 165];
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        pool_account(data)
+    }
+
+    #[tokio::test]
+    async fn test_pool_states_are_read_before_quoting() {
+        let mut pool_data = MintPoolData::new(
+            &Pubkey::new_unique().to_string(),
+            &Pubkey::new_unique().to_string(),
+            spl_token::ID,
+        )
+        .unwrap();
+        let rpc = Arc::new(MockRpcClient::new());
+        for (token_reserve, sol_reserve) in [(1_000_000, 500), (2_000_000, 900)] {
+            let (token_vault, sol_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+            rpc.add_account(token_vault, token_account(token_reserve));
+            rpc.add_account(sol_vault, token_account(sol_reserve));
+            pool_data.raydium_cp_pools.push(RaydiumCpPool {
+                pool: Pubkey::new_unique(),
+                token_vault,
+                sol_vault,
+                amm_config: Pubkey::new_unique(),
+                observation: Pubkey::new_unique(),
+                fee_bps: 25,
+                token_reserve: 0,
+                sol_reserve: 0,
+            });
+        }
+        rpc.set_slot(4_321);
+        let rpc_pool = RpcPool::with_fetchers(vec![rpc.clone() as Arc<dyn AccountFetcher>], DEFAULT_RPC_CACHE_TTL_SECS);
+
+        assert_eq!(update_pool_states(&rpc_pool, &mut pool_data).await.unwrap(), Some(4_321));
+        assert_eq!(pool_data.raydium_cp_pools[0].get_liquidity(), (1_000_000, 500));
+        assert_eq!(pool_data.raydium_cp_pools[1].get_liquidity(), (2_000_000, 900));
+
+        // The vaults move between iterations; a vault that disappears keeps the last reserves
+        let second = pool_data.raydium_cp_pools[1].clone();
+        rpc.add_account(pool_data.raydium_cp_pools[0].sol_vault, token_account(450));
+        rpc.remove_account(&second.token_vault);
+        update_pool_states(&rpc_pool, &mut pool_data).await.unwrap();
+        assert_eq!(pool_data.raydium_cp_pools[0].get_liquidity(), (1_000_000, 450));
+        assert_eq!(pool_data.raydium_cp_pools[1].get_liquidity(), (2_000_000, 900));
+    }
+}
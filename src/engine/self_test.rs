@@ -0,0 +1,464 @@
+/// DEX Self-Test
+///
+/// Loads one known pool per DEX layout parser and checks it still deserializes
+/// and pairs the expected mint with SOL, so a DEX program upgrade that moves
+/// fields shows up before the bot trades on garbage. Meteora DAMM (v1) has no
+/// layout parser in this tree and is not covered.
+use crate::constants::sol_mint;
+use crate::dex::lifinity::{lifinity_program_id, LifinityAmmInfo};
+use crate::dex::meteora::constants::{damm_v2_program_id, dlmm_program_id};
+use crate::dex::meteora::dammv2_info::MeteoraDAmmV2Info;
+use crate::dex::meteora::dlmm_info::DlmmInfo;
+use crate::dex::phoenix::{phoenix_program_id, PhoenixMarketHeader};
+use crate::dex::pump::{pump_program_id, PumpAmmInfo};
+use crate::dex::raydium::{
+    raydium_clmm_program_id, raydium_cp_program_id, raydium_program_id, PoolState, RaydiumAmmInfo,
+    RaydiumCpAmmInfo,
+};
+use crate::dex::solfi::constants::solfi_program_id;
+use crate::dex::solfi::info::SolfiInfo;
+use crate::dex::traits::PoolValidator;
+use crate::dex::vertigo::{vertigo_program_id, VertigoInfo};
+use crate::dex::whirlpool::constants::whirlpool_program_id;
+use crate::dex::whirlpool::state::Whirlpool;
+use crate::error::{BotError, BotResult};
+use crate::rpc::AccountFetcher;
+use serde::Deserialize;
+use solana_program::pubkey::Pubkey;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+// Mainnet USDC/SOL pools used when no fixtures file is given
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const BUILTIN_FIXTURES: &[(SelfTestDex, &str)] = &[
+    (
+        SelfTestDex::Raydium,
+        "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2",
+    ),
+    (
+        SelfTestDex::RaydiumClmm,
+        "3ucNos4NbumPLZNWztqGHNFFgkHeRMBQAVemeeomsUxv",
+    ),
+    (
+        SelfTestDex::Pump,
+        "Gf7sXMoP8iRw4iiXmJ1nq4vxcRycbGXy5RL8a8LnTd3v",
+    ),
+    (
+        SelfTestDex::MeteoraDlmm,
+        "5rCf1DM8LjKTw4YqhnoLcngyZYeNnQqztScTogYHAS6",
+    ),
+    (
+        SelfTestDex::Whirlpool,
+        "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE",
+    ),
+];
+
+/// DEXes with a pool layout parser, named after their `MintConfig` pool lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTestDex {
+    Raydium,
+    RaydiumCp,
+    RaydiumClmm,
+    Pump,
+    MeteoraDlmm,
+    MeteoraDammV2,
+    Solfi,
+    Vertigo,
+    Whirlpool,
+    Lifinity,
+    Phoenix,
+}
+
+impl SelfTestDex {
+    pub const ALL: [SelfTestDex; 11] = [
+        SelfTestDex::Raydium,
+        SelfTestDex::RaydiumCp,
+        SelfTestDex::RaydiumClmm,
+        SelfTestDex::Pump,
+        SelfTestDex::MeteoraDlmm,
+        SelfTestDex::MeteoraDammV2,
+        SelfTestDex::Solfi,
+        SelfTestDex::Vertigo,
+        SelfTestDex::Whirlpool,
+        SelfTestDex::Lifinity,
+        SelfTestDex::Phoenix,
+    ];
+
+    pub fn dex_name(&self) -> &'static str {
+        match self {
+            SelfTestDex::Raydium => "Raydium",
+            SelfTestDex::RaydiumCp => "Raydium CP",
+            SelfTestDex::RaydiumClmm => "Raydium CLMM",
+            SelfTestDex::Pump => "Pump.fun",
+            SelfTestDex::MeteoraDlmm => "Meteora DLMM",
+            SelfTestDex::MeteoraDammV2 => "Meteora DAMM V2",
+            SelfTestDex::Solfi => "Solfi",
+            SelfTestDex::Vertigo => "Vertigo",
+            SelfTestDex::Whirlpool => "Whirlpool",
+            SelfTestDex::Lifinity => "Lifinity",
+            SelfTestDex::Phoenix => "Phoenix",
+        }
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        match self {
+            SelfTestDex::Raydium => raydium_program_id(),
+            SelfTestDex::RaydiumCp => raydium_cp_program_id(),
+            SelfTestDex::RaydiumClmm => raydium_clmm_program_id(),
+            SelfTestDex::Pump => pump_program_id(),
+            SelfTestDex::MeteoraDlmm => dlmm_program_id(),
+            SelfTestDex::MeteoraDammV2 => damm_v2_program_id(),
+            SelfTestDex::Solfi => solfi_program_id(),
+            SelfTestDex::Vertigo => vertigo_program_id(),
+            SelfTestDex::Whirlpool => whirlpool_program_id(),
+            SelfTestDex::Lifinity => lifinity_program_id(),
+            SelfTestDex::Phoenix => phoenix_program_id(),
+        }
+    }
+
+    /// Deserialize pool data with the DEX's layout parser and return its two mints
+    fn pool_mints(&self, pool: &Pubkey, data: &[u8]) -> anyhow::Result<(Pubkey, Pubkey)> {
+        Ok(match self {
+            SelfTestDex::Raydium => {
+                let info = RaydiumAmmInfo::load_checked(data)?;
+                (info.coin_mint, info.pc_mint)
+            }
+            SelfTestDex::RaydiumCp => {
+                let info = RaydiumCpAmmInfo::load_checked(data)?;
+                (info.token_0_mint, info.token_1_mint)
+            }
+            SelfTestDex::RaydiumClmm => {
+                let info = PoolState::load_checked(data)?;
+                (info.token_mint_0, info.token_mint_1)
+            }
+            SelfTestDex::Pump => {
+                let info = PumpAmmInfo::load_checked(data)?;
+                (info.base_mint, info.quote_mint)
+            }
+            SelfTestDex::MeteoraDlmm => {
+                let info = DlmmInfo::load_checked(data)?;
+                (info.token_x_mint, info.token_y_mint)
+            }
+            SelfTestDex::MeteoraDammV2 => {
+                let info = MeteoraDAmmV2Info::load_checked(data)?;
+                (info.base_mint, info.quote_mint)
+            }
+            SelfTestDex::Solfi => {
+                let info = SolfiInfo::load_checked(data)?;
+                (info.base_mint, info.quote_mint)
+            }
+            SelfTestDex::Vertigo => {
+                let info = VertigoInfo::load_checked(data, pool)?;
+                (info.mint_a, info.mint_b)
+            }
+            SelfTestDex::Whirlpool => {
+                let info = Whirlpool::try_deserialize(data)?;
+                (info.token_mint_a, info.token_mint_b)
+            }
+            SelfTestDex::Lifinity => {
+                let info = LifinityAmmInfo::load_checked(data)?;
+                (info.token_a_mint, info.token_b_mint)
+            }
+            SelfTestDex::Phoenix => {
+                let info = PhoenixMarketHeader::load_checked(data)?;
+                (info.base_mint, info.quote_mint)
+            }
+        })
+    }
+}
+
+/// A known pool expected to pair `mint` with SOL
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfTestFixture {
+    pub dex: SelfTestDex,
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[derive(Deserialize)]
+struct FixturesFile {
+    fixture: Vec<RawFixture>,
+}
+
+#[derive(Deserialize)]
+struct RawFixture {
+    dex: SelfTestDex,
+    pool: String,
+    mint: String,
+}
+
+impl SelfTestFixture {
+    /// Built-in mainnet pools; DEXes without one are skipped unless a fixtures file adds it
+    pub fn builtin() -> Vec<Self> {
+        let mint = Pubkey::from_str(USDC_MINT).unwrap();
+        BUILTIN_FIXTURES
+            .iter()
+            .map(|(dex, pool)| SelfTestFixture {
+                dex: *dex,
+                pool: Pubkey::from_str(pool).unwrap(),
+                mint,
+            })
+            .collect()
+    }
+
+    /// Parse a TOML fixtures file of `[[fixture]]` entries with `dex`, `pool` and `mint`
+    pub fn parse(contents: &str) -> BotResult<Vec<Self>> {
+        let file: FixturesFile = toml::from_str(contents)?;
+        file.fixture
+            .into_iter()
+            .map(|raw| {
+                let parse = |value: &str| {
+                    Pubkey::from_str(value).map_err(|e| BotError::InvalidPublicKey {
+                        key: value.to_string(),
+                        source: e,
+                    })
+                };
+                Ok(SelfTestFixture {
+                    dex: raw.dex,
+                    pool: parse(&raw.pool)?,
+                    mint: parse(&raw.mint)?,
+                })
+            })
+            .collect()
+    }
+
+    pub fn load(path: &Path) -> BotResult<Vec<Self>> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// Outcome of one DEX's self-test
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelfTestOutcome {
+    Pass,
+    Fail(String),
+    /// No fixture pool for this DEX
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfTestResult {
+    pub dex: SelfTestDex,
+    pub pool: Option<Pubkey>,
+    pub outcome: SelfTestOutcome,
+}
+
+impl fmt::Display for SelfTestResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pool = self
+            .pool
+            .map(|pool| pool.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        match &self.outcome {
+            SelfTestOutcome::Pass => write!(f, "{:<16} PASS  {}", self.dex.dex_name(), pool),
+            SelfTestOutcome::Fail(reason) => {
+                write!(f, "{:<16} FAIL  {} ({})", self.dex.dex_name(), pool, reason)
+            }
+            SelfTestOutcome::Skipped => {
+                write!(f, "{:<16} SKIP  no fixture pool", self.dex.dex_name())
+            }
+        }
+    }
+}
+
+/// Fetch, owner-check, deserialize and mint-check one fixture pool
+pub fn check_fixture(fetcher: &dyn AccountFetcher, fixture: &SelfTestFixture) -> BotResult<()> {
+    let account = fetcher.fetch_account(&fixture.pool)?;
+    PoolValidator::validate_owner(&fixture.pool, &account.owner, &fixture.dex.program_id())?;
+
+    let (mint_a, mint_b) = fixture
+        .dex
+        .pool_mints(&fixture.pool, &account.data)
+        .map_err(|e| BotError::DeserializationError {
+            data_type: fixture.dex.dex_name().to_string(),
+            source: e.into(),
+        })?;
+    PoolValidator::validate_mint_pair(&fixture.pool, &mint_a, &mint_b, &fixture.mint, &sol_mint())
+}
+
+/// Run every fixture, reporting each DEX in `SelfTestDex::ALL` order (skipped if it has none)
+pub fn run_self_test(
+    fetcher: &dyn AccountFetcher,
+    fixtures: &[SelfTestFixture],
+) -> Vec<SelfTestResult> {
+    SelfTestDex::ALL
+        .iter()
+        .flat_map(|dex| {
+            let dex_fixtures: Vec<&SelfTestFixture> = fixtures
+                .iter()
+                .filter(|fixture| fixture.dex == *dex)
+                .collect();
+            if dex_fixtures.is_empty() {
+                return vec![SelfTestResult {
+                    dex: *dex,
+                    pool: None,
+                    outcome: SelfTestOutcome::Skipped,
+                }];
+            }
+            dex_fixtures
+                .into_iter()
+                .map(|fixture| SelfTestResult {
+                    dex: *dex,
+                    pool: Some(fixture.pool),
+                    outcome: match check_fixture(fetcher, fixture) {
+                        Ok(()) => SelfTestOutcome::Pass,
+                        Err(e) => SelfTestOutcome::Fail(e.to_string()),
+                    },
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::meteora::dlmm_info::LbPair;
+    use crate::rpc::MockRpcClient;
+    use solana_sdk::account::Account;
+    use std::mem::{offset_of, size_of};
+
+    /// Minimal account data for the DEX's parser with both mints at their offsets
+    fn pool_data(dex: SelfTestDex, mint_a: &Pubkey, mint_b: &Pubkey) -> Vec<u8> {
+        let (len, offset_a, offset_b) = match dex {
+            SelfTestDex::Raydium => (RaydiumAmmInfo::MIN_DATA_LEN, 400, 432),
+            SelfTestDex::RaydiumCp => (RaydiumCpAmmInfo::MIN_DATA_LEN, 168, 200),
+            SelfTestDex::RaydiumClmm => (PoolState::MIN_DATA_LEN, 73, 105),
+            SelfTestDex::Pump => (PumpAmmInfo::MIN_DATA_LEN, 43, 75),
+            SelfTestDex::MeteoraDlmm => (
+                DlmmInfo::MIN_DATA_LEN,
+                8 + offset_of!(LbPair, token_x_mint),
+                8 + offset_of!(LbPair, token_y_mint),
+            ),
+            SelfTestDex::MeteoraDammV2 => (MeteoraDAmmV2Info::MIN_DATA_LEN, 168, 200),
+            SelfTestDex::Solfi => (SolfiInfo::MIN_DATA_LEN, 2664, 2696),
+            SelfTestDex::Vertigo => (3 * size_of::<Pubkey>(), 0, 32),
+            SelfTestDex::Whirlpool => (Whirlpool::LEN, 101, 181),
+            SelfTestDex::Lifinity => (LifinityAmmInfo::MIN_DATA_LEN, 254, 286),
+            SelfTestDex::Phoenix => (PhoenixMarketHeader::LEN, 48, 128),
+        };
+        let mut data = vec![0u8; len];
+        data[offset_a..offset_a + 32].copy_from_slice(mint_a.as_ref());
+        data[offset_b..offset_b + 32].copy_from_slice(mint_b.as_ref());
+        data
+    }
+
+    fn pool_account(dex: SelfTestDex, data: Vec<u8>) -> Account {
+        Account {
+            lamports: 1,
+            data,
+            owner: dex.program_id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_self_test_passes_for_every_dex() {
+        let rpc = MockRpcClient::new();
+        let mint = Pubkey::new_unique();
+        let fixtures: Vec<SelfTestFixture> = SelfTestDex::ALL
+            .iter()
+            .map(|dex| {
+                let pool = Pubkey::new_unique();
+                rpc.add_account(
+                    pool,
+                    pool_account(*dex, pool_data(*dex, &mint, &sol_mint())),
+                );
+                SelfTestFixture {
+                    dex: *dex,
+                    pool,
+                    mint,
+                }
+            })
+            .collect();
+
+        let results = run_self_test(&rpc, &fixtures);
+
+        assert_eq!(results.len(), SelfTestDex::ALL.len());
+        for result in &results {
+            assert_eq!(result.outcome, SelfTestOutcome::Pass, "{}", result);
+        }
+    }
+
+    #[test]
+    fn test_self_test_reports_failures_and_skips() {
+        let rpc = MockRpcClient::new();
+        let mint = Pubkey::new_unique();
+        let sol = sol_mint();
+
+        let mut foreign =
+            pool_account(SelfTestDex::Pump, pool_data(SelfTestDex::Pump, &mint, &sol));
+        foreign.owner = Pubkey::new_unique();
+        let truncated = pool_account(SelfTestDex::Whirlpool, vec![0u8; 100]);
+        let wrong_pair = pool_account(
+            SelfTestDex::Raydium,
+            pool_data(SelfTestDex::Raydium, &mint, &Pubkey::new_unique()),
+        );
+
+        let mut fixtures = Vec::new();
+        for (dex, account) in [
+            (SelfTestDex::Pump, Some(foreign)),
+            (SelfTestDex::Whirlpool, Some(truncated)),
+            (SelfTestDex::Raydium, Some(wrong_pair)),
+            (SelfTestDex::Solfi, None),
+        ] {
+            let pool = Pubkey::new_unique();
+            if let Some(account) = account {
+                rpc.add_account(pool, account);
+            }
+            fixtures.push(SelfTestFixture { dex, pool, mint });
+        }
+
+        let results = run_self_test(&rpc, &fixtures);
+        let outcome = |dex: SelfTestDex| {
+            results
+                .iter()
+                .find(|result| result.dex == dex)
+                .unwrap()
+                .outcome
+                .clone()
+        };
+
+        for dex in [
+            SelfTestDex::Pump,
+            SelfTestDex::Whirlpool,
+            SelfTestDex::Raydium,
+            SelfTestDex::Solfi,
+        ] {
+            assert!(
+                matches!(outcome(dex), SelfTestOutcome::Fail(_)),
+                "{:?}",
+                dex
+            );
+        }
+        assert_eq!(outcome(SelfTestDex::Vertigo), SelfTestOutcome::Skipped);
+    }
+
+    #[test]
+    fn test_fixtures_file_and_builtin_fixtures() {
+        let pool = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let fixtures = SelfTestFixture::parse(&format!(
+            "[[fixture]]\ndex = \"meteora_damm_v2\"\npool = \"{}\"\nmint = \"{}\"\n",
+            pool, mint
+        ))
+        .unwrap();
+        assert_eq!(
+            fixtures,
+            vec![SelfTestFixture {
+                dex: SelfTestDex::MeteoraDammV2,
+                pool,
+                mint
+            }]
+        );
+        assert!(SelfTestFixture::parse(
+            "[[fixture]]\ndex = \"meteora_damm\"\npool = \"x\"\nmint = \"y\"\n"
+        )
+        .is_err());
+
+        assert_eq!(SelfTestFixture::builtin().len(), BUILTIN_FIXTURES.len());
+    }
+}
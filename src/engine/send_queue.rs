@@ -0,0 +1,163 @@
+/// Opportunity Send Queue
+///
+/// Bounded channel between the strategy loops and a pool of sender workers, so
+/// send latency no longer throttles detection. When the workers lag and the
+/// queue fills, the overflow policy either drops the oldest queued item or
+/// blocks the producer.
+
+use crate::config::OverflowPolicy;
+use crate::metrics::METRICS;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// Multi-producer queue drained by any number of workers
+pub struct SendQueue<T> {
+    sender: mpsc::Sender<T>,
+    // Shared by the workers; producers also take it to evict under `DropOldest`
+    receiver: Arc<Mutex<mpsc::Receiver<T>>>,
+    policy: OverflowPolicy,
+    dropped: Arc<AtomicU64>,
+}
+
+impl<T> Clone for SendQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            receiver: self.receiver.clone(),
+            policy: self.policy,
+            dropped: self.dropped.clone(),
+        }
+    }
+}
+
+impl<T: Send + 'static> SendQueue<T> {
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        let (sender, receiver) = mpsc::channel(capacity);
+        Self {
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            policy,
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Queue `item`, evicting the oldest queued item or waiting for space when full
+    pub async fn push(&self, item: T) {
+        match self.policy {
+            OverflowPolicy::Block => {
+                // Only fails once every worker is gone, when there is nobody to send anyway
+                let _ = self.sender.send(item).await;
+            }
+            OverflowPolicy::DropOldest => {
+                let mut item = item;
+                loop {
+                    match self.sender.try_send(item) {
+                        Ok(()) | Err(TrySendError::Closed(_)) => return,
+                        Err(TrySendError::Full(rejected)) => item = rejected,
+                    }
+                    let mut receiver = self.receiver.lock().await;
+                    // A worker may have freed a slot while we waited for the lock
+                    match self.sender.try_send(item) {
+                        Ok(()) | Err(TrySendError::Closed(_)) => return,
+                        Err(TrySendError::Full(rejected)) => item = rejected,
+                    }
+                    if receiver.try_recv().is_ok() {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        METRICS.inc_opportunity_dropped();
+                        debug!("Send queue full, dropped the oldest opportunity");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Items evicted by this queue under `DropOldest`
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Spawn `count` workers that each take the next queued item and run `handler` on it
+    pub fn spawn_workers<F, Fut>(&self, count: usize, handler: F) -> Vec<JoinHandle<()>>
+    where
+        F: Fn(T) -> Fut + Clone + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        (0..count)
+            .map(|_| {
+                let receiver = self.receiver.clone();
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    loop {
+                        let item = receiver.lock().await.recv().await;
+                        match item {
+                            Some(item) => handler(item).await,
+                            None => return,
+                        }
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_items_flow_through_workers() {
+        let queue = SendQueue::new(8, OverflowPolicy::Block);
+        let (done_sender, mut done) = mpsc::unbounded_channel();
+        queue.spawn_workers(3, move |item: u64| {
+            let done_sender = done_sender.clone();
+            async move {
+                done_sender.send(item).unwrap();
+            }
+        });
+
+        for item in 0..20 {
+            queue.push(item).await;
+        }
+
+        let mut received = Vec::new();
+        for _ in 0..20 {
+            let item = tokio::time::timeout(Duration::from_secs(5), done.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            received.push(item);
+        }
+        received.sort();
+        assert_eq!(received, (0..20).collect::<Vec<_>>());
+        assert_eq!(queue.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_overflow_drops_oldest_and_counts() {
+        let queue = SendQueue::new(2, OverflowPolicy::DropOldest);
+        let metrics_before = METRICS.opportunities_dropped.load(Ordering::Relaxed);
+
+        // No workers yet, so everything past the capacity overflows
+        for item in 0..5u64 {
+            queue.push(item).await;
+        }
+        assert_eq!(queue.dropped(), 3);
+        assert!(METRICS.opportunities_dropped.load(Ordering::Relaxed) >= metrics_before + 3);
+
+        let (done_sender, mut done) = mpsc::unbounded_channel();
+        queue.spawn_workers(1, move |item: u64| {
+            let done_sender = done_sender.clone();
+            async move {
+                done_sender.send(item).unwrap();
+            }
+        });
+        assert_eq!(done.recv().await, Some(3));
+        assert_eq!(done.recv().await, Some(4));
+    }
+}
@@ -0,0 +1,267 @@
+/// Swap Account Simulation Check
+///
+/// `get_swap_accounts` is hand-written per DEX, and a wrong account list only shows up
+/// as failing swaps on mainnet. `validate_swap_accounts` simulates a swap through each
+/// pool alone and tells an account list the DEX program rejects apart from a
+/// simulation that only failed for lack of funds or profit.
+
+use crate::config::Config;
+use crate::error::BotResult;
+use crate::pools::MintPoolData;
+use crate::transaction::single_pool_transaction;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::signer::null_signer::NullSigner;
+use solana_sdk::transaction::{TransactionError, VersionedTransaction};
+use std::fmt;
+use std::str::FromStr;
+
+// Anchor reports account constraint (2000-2999) and account (3000-3999) errors in
+// these ranges, e.g. a discriminator mismatch or a missing `mut`
+const ANCHOR_ACCOUNT_ERRORS: std::ops::RangeInclusive<u32> = 2_000..=3_999;
+
+/// What simulating a pool's swap says about its account list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwapAccountVerdict {
+    /// The swap simulated cleanly
+    Accepted,
+    /// The DEX swap ran; the executor only rejected the one-pool round trip's result
+    Unprofitable,
+    /// The accounts got past the DEX program's checks but the wallet couldn't pay
+    InsufficientFunds,
+    /// The DEX program rejected the account list
+    LayoutWrong(String),
+    /// Failed for a reason that doesn't tell about the accounts
+    Failed(String),
+}
+
+impl SwapAccountVerdict {
+    pub fn is_pass(&self) -> bool {
+        matches!(
+            self,
+            SwapAccountVerdict::Accepted
+                | SwapAccountVerdict::Unprofitable
+                | SwapAccountVerdict::InsufficientFunds
+        )
+    }
+}
+
+impl fmt::Display for SwapAccountVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwapAccountVerdict::Accepted => write!(f, "ok"),
+            SwapAccountVerdict::Unprofitable => write!(f, "ok (executor rejected the unprofitable route)"),
+            SwapAccountVerdict::InsufficientFunds => write!(f, "ok (insufficient funds)"),
+            SwapAccountVerdict::LayoutWrong(reason) => write!(f, "ACCOUNT LAYOUT WRONG: {}", reason),
+            SwapAccountVerdict::Failed(reason) => write!(f, "FAILED: {}", reason),
+        }
+    }
+}
+
+/// Verdict for a simulation that ended with `err` and `logs`
+pub fn simulation_verdict(
+    err: Option<&TransactionError>,
+    logs: &[String],
+    executor_program_id: &Pubkey,
+) -> SwapAccountVerdict {
+    let Some(err) = err else {
+        return SwapAccountVerdict::Accepted;
+    };
+    let insufficient_funds = logs.iter().any(|log| {
+        let log = log.to_lowercase();
+        log.contains("insufficient funds") || log.contains("insufficient lamports")
+    });
+    match err {
+        // A fee payer that doesn't exist has no SOL either
+        TransactionError::AccountNotFound
+        | TransactionError::InsufficientFundsForFee
+        | TransactionError::InsufficientFundsForRent { .. } => SwapAccountVerdict::InsufficientFunds,
+        TransactionError::ProgramAccountNotFound
+        | TransactionError::InvalidAccountIndex
+        | TransactionError::InvalidProgramForExecution
+        | TransactionError::AccountLoadedTwice => SwapAccountVerdict::LayoutWrong(err.to_string()),
+        TransactionError::InstructionError(_, InstructionError::InsufficientFunds) => {
+            SwapAccountVerdict::InsufficientFunds
+        }
+        TransactionError::InstructionError(_, _) if insufficient_funds => SwapAccountVerdict::InsufficientFunds,
+        TransactionError::InstructionError(_, instruction_err) if is_account_error(instruction_err) => {
+            SwapAccountVerdict::LayoutWrong(err.to_string())
+        }
+        TransactionError::InstructionError(_, InstructionError::Custom(_))
+            if failed_program(logs) == Some(*executor_program_id) =>
+        {
+            SwapAccountVerdict::Unprofitable
+        }
+        _ => SwapAccountVerdict::Failed(err.to_string()),
+    }
+}
+
+fn is_account_error(err: &InstructionError) -> bool {
+    match err {
+        InstructionError::NotEnoughAccountKeys
+        | InstructionError::MissingAccount
+        | InstructionError::InvalidAccountData
+        | InstructionError::InvalidAccountOwner
+        | InstructionError::IncorrectProgramId
+        | InstructionError::InvalidSeeds
+        | InstructionError::UninitializedAccount
+        | InstructionError::AccountNotExecutable
+        | InstructionError::MissingRequiredSignature
+        | InstructionError::PrivilegeEscalation
+        | InstructionError::ReadonlyDataModified
+        | InstructionError::ReadonlyLamportChange
+        | InstructionError::ExternalAccountDataModified => true,
+        InstructionError::Custom(code) => ANCHOR_ACCOUNT_ERRORS.contains(code),
+        _ => false,
+    }
+}
+
+/// Innermost program that failed: a failing CPI is logged before every caller's failure
+fn failed_program(logs: &[String]) -> Option<Pubkey> {
+    logs.iter().find_map(|log| {
+        let program = log.strip_prefix("Program ")?.split_once(" failed")?.0;
+        Pubkey::from_str(program).ok()
+    })
+}
+
+/// Simulates a transaction without checking its signatures or blockhash
+pub trait SwapSimulator {
+    fn simulate_unsigned(&self, tx: &VersionedTransaction) -> BotResult<(Option<TransactionError>, Vec<String>)>;
+}
+
+impl SwapSimulator for RpcClient {
+    fn simulate_unsigned(&self, tx: &VersionedTransaction) -> BotResult<(Option<TransactionError>, Vec<String>)> {
+        let simulation = self
+            .simulate_transaction_with_config(
+                tx,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    commitment: Some(CommitmentConfig::processed()),
+                    ..Default::default()
+                },
+            )?
+            .value;
+        Ok((simulation.err, simulation.logs.unwrap_or_default()))
+    }
+}
+
+/// Simulation check of one pool's swap accounts
+#[derive(Debug, Clone)]
+pub struct SwapAccountCheck {
+    pub dex: &'static str,
+    pub pool: Pubkey,
+    pub verdict: SwapAccountVerdict,
+}
+
+impl fmt::Display for SwapAccountCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<16} {:<44} {}", self.dex, self.pool.to_string(), self.verdict)
+    }
+}
+
+/// Simulate a swap through each of the mint's pools alone, as `wallet`. Nothing is
+/// signed, so the wallet's key isn't needed.
+pub fn validate_swap_accounts(
+    simulator: &dyn SwapSimulator,
+    config: &Config,
+    wallet: &Pubkey,
+    pool_data: &MintPoolData,
+) -> BotResult<Vec<SwapAccountCheck>> {
+    let signer = NullSigner::new(wallet);
+    let executor_program_id = config.executor_program_id();
+    pool_data
+        .dex_pools()
+        .into_iter()
+        .map(|pool| {
+            let verdict = match single_pool_transaction(
+                &signer,
+                config,
+                pool_data,
+                &pool.pool_address(),
+                Hash::default(),
+            ) {
+                Ok(tx) => {
+                    let (err, logs) = simulator.simulate_unsigned(&tx)?;
+                    simulation_verdict(err.as_ref(), &logs, &executor_program_id)
+                }
+                Err(e) => SwapAccountVerdict::Failed(format!("could not build the swap: {}", e)),
+            };
+            Ok(SwapAccountCheck {
+                dex: pool.dex_name(),
+                pool: pool.pool_address(),
+                verdict,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instruction_error(err: InstructionError) -> TransactionError {
+        TransactionError::InstructionError(2, err)
+    }
+
+    #[test]
+    fn test_simulation_outcomes_map_to_verdicts() {
+        let executor = Pubkey::new_unique();
+        let dex = Pubkey::new_unique();
+        let verdict = |err: Option<TransactionError>, logs: &[String]| {
+            simulation_verdict(err.as_ref(), logs, &executor)
+        };
+
+        assert_eq!(verdict(None, &[]), SwapAccountVerdict::Accepted);
+
+        // Broken account lists
+        let layout = [
+            instruction_error(InstructionError::NotEnoughAccountKeys),
+            instruction_error(InstructionError::PrivilegeEscalation),
+            // Anchor AccountDiscriminatorMismatch
+            instruction_error(InstructionError::Custom(3_002)),
+            TransactionError::ProgramAccountNotFound,
+        ];
+        for err in layout {
+            let result = verdict(Some(err), &[]);
+            assert!(matches!(result, SwapAccountVerdict::LayoutWrong(_)), "{:?}", result);
+            assert!(!result.is_pass());
+        }
+
+        // Accepted accounts, wallet can't pay
+        let token_logs = vec![
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]".to_string(),
+            "Program log: Error: insufficient funds".to_string(),
+        ];
+        let result = verdict(Some(instruction_error(InstructionError::Custom(1))), &token_logs);
+        assert_eq!(result, SwapAccountVerdict::InsufficientFunds);
+        assert!(result.is_pass());
+        assert_eq!(
+            verdict(Some(TransactionError::InsufficientFundsForFee), &[]),
+            SwapAccountVerdict::InsufficientFunds
+        );
+
+        // The DEX swap went through and the executor refused the losing round trip
+        let executor_logs = vec![
+            format!("Program {} invoke [2]", dex),
+            format!("Program {} success", dex),
+            format!("Program {} failed: custom program error: 0x1770", executor),
+        ];
+        let result = verdict(Some(instruction_error(InstructionError::Custom(6_000))), &executor_logs);
+        assert_eq!(result, SwapAccountVerdict::Unprofitable);
+        assert!(result.is_pass());
+
+        // The same error from the DEX itself says nothing about the accounts
+        let dex_logs = vec![
+            format!("Program {} failed: custom program error: 0x1770", dex),
+            format!("Program {} failed: custom program error: 0x1770", executor),
+        ];
+        let result = verdict(Some(instruction_error(InstructionError::Custom(6_000))), &dex_logs);
+        assert!(matches!(result, SwapAccountVerdict::Failed(_)));
+        assert!(!result.is_pass());
+    }
+}
@@ -0,0 +1,280 @@
+/// Split Trades
+///
+/// A constant-product pool prices a large input ever worse as the trade eats into its
+/// reserves. With several pools of the same mint/SOL pair, `split_trade` spreads the input
+/// so that every pool used ends at the same marginal price (water-filling). A pool's
+/// marginal output `g*R_in*R_out / (R_in + g*x)^2` set to a common level `λ` gives
+/// `x = sqrt(R_in*R_out / g) / sqrt(λ) - R_in / g`, for the pools whose spot price is
+/// above `λ`. Concentrated pools don't follow the formula and are left out.
+
+use crate::constants::BASIS_POINTS_DENOMINATOR;
+use crate::dex::traits::constant_product_quote;
+use crate::pools::MintPoolData;
+use solana_program::pubkey::Pubkey;
+
+/// A pool's reserves in the direction of the trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitPool {
+    pub pool: Pubkey,
+    pub reserve_in: u64,
+    pub reserve_out: u64,
+    pub fee_bps: u16,
+    /// Swap accounts the pool adds to the transaction
+    pub account_count: usize,
+}
+
+impl SplitPool {
+    fn fee_multiplier(&self) -> f64 {
+        let fee_bps = (self.fee_bps as u64).min(BASIS_POINTS_DENOMINATOR);
+        1.0 - fee_bps as f64 / BASIS_POINTS_DENOMINATOR as f64
+    }
+
+    /// Output per unit of input of an infinitesimal trade, after the fee
+    pub fn spot_price(&self) -> f64 {
+        if self.reserve_in == 0 {
+            return 0.0;
+        }
+        self.fee_multiplier() * self.reserve_out as f64 / self.reserve_in as f64
+    }
+
+    pub fn quote(&self, amount_in: u64) -> u64 {
+        constant_product_quote(amount_in, self.reserve_in, self.reserve_out, self.fee_bps)
+    }
+}
+
+/// One pool's share of a split trade
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitLeg {
+    pub pool: Pubkey,
+    pub input: u64,
+    pub output: u64,
+}
+
+/// A trade spread over pools of one pair
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SplitRoute {
+    pub legs: Vec<SplitLeg>,
+    /// Swap accounts of the pools the route uses
+    pub account_count: usize,
+}
+
+impl SplitRoute {
+    pub fn input(&self) -> u64 {
+        self.legs.iter().map(|leg| leg.input).sum()
+    }
+
+    pub fn output(&self) -> u64 {
+        self.legs.iter().map(|leg| leg.output).sum()
+    }
+
+    /// Pools in the route, best spot price first
+    pub fn pools(&self) -> Vec<Pubkey> {
+        self.legs.iter().map(|leg| leg.pool).collect()
+    }
+}
+
+/// Input for each of `pools`, in order, that brings the pools used to the same marginal
+/// price; the inputs add up to `total_input`
+pub fn water_fill(pools: &[SplitPool], total_input: u64) -> Vec<u64> {
+    let mut allocations = vec![0; pools.len()];
+    // A pool joins once the others' marginal price drops to its spot price
+    let mut order: Vec<usize> = (0..pools.len()).filter(|&i| pools[i].spot_price() > 0.0).collect();
+    order.sort_by(|&a, &b| pools[b].spot_price().total_cmp(&pools[a].spot_price()));
+    if total_input == 0 || order.is_empty() {
+        return allocations;
+    }
+
+    // Over the active pools, sum(x) = total_input solves to 1/sqrt(λ) = (total_input + c) / s
+    let (mut s, mut c) = (0.0, 0.0);
+    let mut active = 0;
+    let mut inverse_sqrt_level = 0.0;
+    for (rank, &index) in order.iter().enumerate() {
+        let pool = &pools[index];
+        let g = pool.fee_multiplier();
+        s += (pool.reserve_in as f64 * pool.reserve_out as f64 / g).sqrt();
+        c += pool.reserve_in as f64 / g;
+        active = rank + 1;
+        inverse_sqrt_level = (total_input as f64 + c) / s;
+        let level = 1.0 / (inverse_sqrt_level * inverse_sqrt_level);
+        match order.get(rank + 1) {
+            Some(&next) if level < pools[next].spot_price() => continue,
+            _ => break,
+        }
+    }
+
+    let mut remaining = total_input;
+    for &index in &order[..active] {
+        let pool = &pools[index];
+        let g = pool.fee_multiplier();
+        let ideal = (pool.reserve_in as f64 * pool.reserve_out as f64 / g).sqrt() * inverse_sqrt_level
+            - pool.reserve_in as f64 / g;
+        let input = (ideal.max(0.0).floor() as u64).min(remaining);
+        allocations[index] = input;
+        remaining -= input;
+    }
+    // Rounding leftovers go to the deepest-priced pool
+    allocations[order[0]] += remaining;
+    allocations
+}
+
+/// Spread `total_input` over `pools`, using at most `max_pools` of them with no more than
+/// `max_accounts` swap accounts between them. The pools with the best spot price are kept,
+/// and pools the allocation leaves empty aren't part of the route.
+pub fn split_trade(
+    pools: &[SplitPool],
+    total_input: u64,
+    max_pools: usize,
+    max_accounts: usize,
+) -> SplitRoute {
+    let mut candidates = pools.to_vec();
+    candidates.sort_by(|a, b| b.spot_price().total_cmp(&a.spot_price()));
+    let mut selected = Vec::new();
+    let mut accounts = 0;
+    for pool in candidates {
+        if selected.len() == max_pools {
+            break;
+        }
+        if accounts + pool.account_count > max_accounts {
+            continue;
+        }
+        accounts += pool.account_count;
+        selected.push(pool);
+    }
+
+    let allocations = water_fill(&selected, total_input);
+    let used: Vec<(SplitPool, u64)> = selected
+        .into_iter()
+        .zip(allocations)
+        .filter(|(_, input)| *input > 0)
+        .collect();
+    SplitRoute {
+        account_count: used.iter().map(|(pool, _)| pool.account_count).sum(),
+        legs: used
+            .into_iter()
+            .map(|(pool, input)| SplitLeg {
+                pool: pool.pool,
+                input,
+                output: pool.quote(input),
+            })
+            .collect(),
+    }
+}
+
+/// The mint's constant-product pools as split candidates, buying the mint with SOL when
+/// `sol_to_token`, selling it otherwise
+pub fn split_pools(mint_pool_data: &MintPoolData, sol_to_token: bool) -> Vec<SplitPool> {
+    mint_pool_data
+        .dex_pools()
+        .into_iter()
+        .filter(|pool| !pool.is_concentrated())
+        .map(|pool| {
+            let (token_reserve, sol_reserve) = pool.get_liquidity();
+            let (reserve_in, reserve_out) = if sol_to_token {
+                (sol_reserve, token_reserve)
+            } else {
+                (token_reserve, sol_reserve)
+            };
+            SplitPool {
+                pool: pool.pool_address(),
+                reserve_in,
+                reserve_out,
+                fee_bps: pool.fee_bps(),
+                account_count: mint_pool_data
+                    .swap_accounts(pool, &mint_pool_data.wallet_account, None)
+                    .len(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL: u64 = 1_000_000_000;
+
+    fn pool(sol_reserve: u64, token_reserve: u64, fee_bps: u16) -> SplitPool {
+        SplitPool {
+            pool: Pubkey::new_unique(),
+            reserve_in: sol_reserve,
+            reserve_out: token_reserve,
+            fee_bps,
+            account_count: 8,
+        }
+    }
+
+    fn marginal_price(pool: &SplitPool, input: u64) -> f64 {
+        let g = pool.fee_multiplier();
+        let reserve_in = pool.reserve_in as f64 + g * input as f64;
+        g * pool.reserve_in as f64 * pool.reserve_out as f64 / (reserve_in * reserve_in)
+    }
+
+    #[test]
+    fn test_split_reduces_slippage_against_the_best_single_pool() {
+        // Same price, different depths
+        let pools = [
+            pool(100 * SOL, 10_000 * SOL, 25),
+            pool(40 * SOL, 4_000 * SOL, 25),
+            pool(10 * SOL, 1_000 * SOL, 30),
+        ];
+        let total_input = 20 * SOL;
+        let route = split_trade(&pools, total_input, pools.len(), 64);
+        assert_eq!(route.input(), total_input);
+        assert_eq!(route.legs.len(), 3);
+        assert_eq!(route.account_count, 24);
+
+        // Every leg ends at the same marginal price
+        let levels: Vec<f64> = route
+            .legs
+            .iter()
+            .map(|leg| {
+                let pool = pools.iter().find(|pool| pool.pool == leg.pool).unwrap();
+                marginal_price(pool, leg.input)
+            })
+            .collect();
+        for level in &levels {
+            assert!((level / levels[0] - 1.0).abs() < 1e-6, "{:?}", levels);
+        }
+
+        // Same input, more out: the split loses less to price impact
+        let best_single = pools.iter().map(|pool| pool.quote(total_input)).max().unwrap();
+        assert!(route.output() > best_single, "{} <= {}", route.output(), best_single);
+        let spot_value = (total_input as f64 * pools[0].spot_price()) as u64;
+        let split_slippage = spot_value - route.output();
+        let single_slippage = spot_value - best_single;
+        assert!(split_slippage * 10 < single_slippage * 9);
+    }
+
+    #[test]
+    fn test_small_trade_stays_in_the_best_priced_pool() {
+        // The second pool quotes 1% worse, more than a small trade moves the first
+        let best = pool(100 * SOL, 10_000 * SOL, 25);
+        let worse = pool(100 * SOL, 9_900 * SOL, 25);
+        let allocations = water_fill(&[worse, best], SOL / 10);
+        assert_eq!(allocations, vec![0, SOL / 10]);
+
+        // A trade large enough to push the best pool's price past the other's uses both
+        let allocations = water_fill(&[worse, best], 10 * SOL);
+        assert!(allocations[0] > 0 && allocations[1] > allocations[0]);
+        assert_eq!(allocations.iter().sum::<u64>(), 10 * SOL);
+    }
+
+    #[test]
+    fn test_split_respects_pool_and_account_limits() {
+        let deep = pool(100 * SOL, 10_000 * SOL, 25);
+        let wide = SplitPool {
+            account_count: 40,
+            ..pool(50 * SOL, 5_000 * SOL, 25)
+        };
+        let shallow = pool(10 * SOL, 1_000 * SOL, 25);
+
+        // The wide pool's accounts don't fit next to the deep pool's
+        let route = split_trade(&[deep, wide, shallow], 10 * SOL, 3, 20);
+        assert_eq!(route.pools(), vec![deep.pool, shallow.pool]);
+        assert!(route.account_count <= 20);
+
+        let route = split_trade(&[deep, wide, shallow], 10 * SOL, 1, 64);
+        assert_eq!(route.pools(), vec![deep.pool]);
+        assert_eq!(route.output(), deep.quote(10 * SOL));
+    }
+}
@@ -0,0 +1,156 @@
+/// Swap Instruction Accounts
+///
+/// Pools of a route pass many of the same accounts: each Raydium pool lists the program
+/// and authority, DEX configs and event authorities are shared, and the wallet's token
+/// accounts show up next to the pools'. `AccountMetasBuilder` collects the executor's
+/// accounts in order and merges every pubkey into one entry with its strongest flags,
+/// writable if any occurrence writes and signer if any signs. The executor reads each
+/// pool's accounts by position, so the instruction keeps every position, each repeat
+/// carrying the merged flags; the merged list is what the message stores as keys.
+
+use solana_program::instruction::AccountMeta;
+use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+pub struct AccountMetasBuilder {
+    /// Accounts in the order the executor reads them
+    metas: Vec<AccountMeta>,
+    /// One entry per pubkey, in order of first appearance
+    unique: Vec<AccountMeta>,
+    positions: HashMap<Pubkey, usize>,
+}
+
+impl AccountMetasBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, meta: AccountMeta) {
+        match self.positions.get(&meta.pubkey) {
+            Some(&position) => {
+                let merged = &mut self.unique[position];
+                merged.is_writable |= meta.is_writable;
+                merged.is_signer |= meta.is_signer;
+            }
+            None => {
+                self.positions.insert(meta.pubkey, self.unique.len());
+                self.unique.push(meta.clone());
+            }
+        }
+        self.metas.push(meta);
+    }
+
+    /// Accounts passed, repeats included
+    pub fn len(&self) -> usize {
+        self.metas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.metas.is_empty()
+    }
+
+    /// The deduplicated accounts with their merged flags
+    pub fn unique(&self) -> &[AccountMeta] {
+        &self.unique
+    }
+
+    /// Positions that repeat an earlier account
+    pub fn duplicates(&self) -> usize {
+        self.metas.len() - self.unique.len()
+    }
+
+    /// The executor's accounts in order, every repeat flagged like its merged entry
+    pub fn build(self) -> Vec<AccountMeta> {
+        let Self {
+            metas,
+            unique,
+            positions,
+        } = self;
+        metas
+            .into_iter()
+            .map(|meta| unique[positions[&meta.pubkey]].clone())
+            .collect()
+    }
+}
+
+impl Extend<AccountMeta> for AccountMetasBuilder {
+    fn extend<I: IntoIterator<Item = AccountMeta>>(&mut self, metas: I) {
+        for meta in metas {
+            self.push(meta);
+        }
+    }
+}
+
+impl FromIterator<AccountMeta> for AccountMetasBuilder {
+    fn from_iter<I: IntoIterator<Item = AccountMeta>>(metas: I) -> Self {
+        let mut builder = Self::new();
+        builder.extend(metas);
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_pool_accounts_merge() {
+        let [wallet, program, authority, shared_vault, first_pool, second_pool] =
+            [(); 6].map(|_| Pubkey::new_unique());
+
+        let mut builder = AccountMetasBuilder::new();
+        builder.push(AccountMeta::new_readonly(wallet, true));
+        // Two pools of one DEX, the second writing a vault the first only reads
+        builder.extend([
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new_readonly(authority, false),
+            AccountMeta::new(first_pool, false),
+            AccountMeta::new_readonly(shared_vault, false),
+        ]);
+        builder.extend([
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new_readonly(authority, false),
+            AccountMeta::new(second_pool, false),
+            AccountMeta::new(shared_vault, false),
+            AccountMeta::new(wallet, false),
+        ]);
+
+        assert_eq!(builder.len(), 10);
+        assert_eq!(builder.duplicates(), 4);
+        // First appearance order, strongest flags
+        assert_eq!(
+            builder.unique(),
+            &[
+                AccountMeta::new(wallet, true),
+                AccountMeta::new_readonly(program, false),
+                AccountMeta::new_readonly(authority, false),
+                AccountMeta::new(first_pool, false),
+                AccountMeta::new(shared_vault, false),
+                AccountMeta::new(second_pool, false),
+            ]
+        );
+
+        // Every position survives for the executor, repeats flagged alike
+        let accounts = builder.build();
+        let keys: Vec<Pubkey> = accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            keys,
+            vec![
+                wallet,
+                program,
+                authority,
+                first_pool,
+                shared_vault,
+                program,
+                authority,
+                second_pool,
+                shared_vault,
+                wallet,
+            ]
+        );
+        assert!(accounts[4].is_writable && accounts[8].is_writable);
+        assert!(accounts[0].is_writable && accounts[0].is_signer && accounts[9].is_signer);
+        assert!(!accounts[1].is_writable && !accounts[5].is_writable);
+    }
+}
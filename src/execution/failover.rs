@@ -0,0 +1,234 @@
+/// Wallet Failover
+///
+/// Keeps an optional backup wallet on warm standby and switches trading to it when
+/// the primary keeps failing to land transactions or runs low on SOL. Failover is
+/// one-way: switching back to the primary needs a restart. Once the wallet traded with
+/// keeps failing and there is none left to switch to, that is alerted as critical.
+
+use crate::alert;
+use crate::metrics::METRICS;
+use crate::signer::WalletSigner;
+use serde::{Deserialize, Serialize};
+use solana_sdk::signer::Signer;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use tracing::{error, warn};
+
+/// Which configured wallet the bot is trading with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActiveWallet {
+    Primary,
+    Backup,
+}
+
+/// Active wallet as reported on `/status`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletStatus {
+    pub active: ActiveWallet,
+    pub pubkey: String,
+    pub backup_configured: bool,
+    /// Whether the active wallet is failing with no wallet left to fail over to
+    pub exhausted: bool,
+}
+
+/// Primary and backup signers, and the failures counted against the primary
+pub struct WalletFailover {
+    primary: WalletSigner,
+    backup: Option<WalletSigner>,
+    failover_after: u32,
+    min_balance_lamports: Option<u64>,
+    on_backup: AtomicBool,
+    exhausted: AtomicBool,
+    consecutive_failures: AtomicU32,
+}
+
+impl WalletFailover {
+    pub fn new(
+        primary: WalletSigner,
+        backup: Option<WalletSigner>,
+        failover_after: u32,
+        min_balance_lamports: Option<u64>,
+    ) -> Self {
+        Self {
+            primary,
+            backup,
+            failover_after,
+            min_balance_lamports,
+            on_backup: AtomicBool::new(false),
+            exhausted: AtomicBool::new(false),
+            consecutive_failures: AtomicU32::new(0),
+        }
+    }
+
+    /// Signer to build and sign the next transaction with
+    pub fn active(&self) -> WalletSigner {
+        match (&self.backup, self.active_wallet()) {
+            (Some(backup), ActiveWallet::Backup) => backup.clone(),
+            _ => self.primary.clone(),
+        }
+    }
+
+    pub fn active_wallet(&self) -> ActiveWallet {
+        if self.on_backup.load(Ordering::Acquire) {
+            ActiveWallet::Backup
+        } else {
+            ActiveWallet::Primary
+        }
+    }
+
+    pub fn status(&self) -> WalletStatus {
+        WalletStatus {
+            active: self.active_wallet(),
+            pubkey: self.active().pubkey().to_string(),
+            backup_configured: self.backup.is_some(),
+            exhausted: self.exhausted.load(Ordering::Acquire),
+        }
+    }
+
+    /// Minimum balance the active wallet must keep, if any
+    pub fn min_balance_lamports(&self) -> Option<u64> {
+        self.min_balance_lamports
+    }
+
+    pub fn record_landed(&self) {
+        self.consecutive_failures.store(0, Ordering::Release);
+        self.exhausted.store(false, Ordering::Release);
+    }
+
+    /// Count a send that failed or did not land; fails over once the primary has failed
+    /// `failover_after` times in a row. Returns whether this call switched wallets.
+    pub fn record_failure(&self) -> bool {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::AcqRel) + 1;
+        if failures < self.failover_after {
+            return false;
+        }
+        self.fail_over(&format!("{} consecutive sends failed to land", failures))
+    }
+
+    /// Fail over when the active wallet's balance is below the configured minimum.
+    /// Returns whether this call switched wallets.
+    pub fn record_balance(&self, lamports: u64) -> bool {
+        match self.min_balance_lamports {
+            Some(min) if lamports < min => self.fail_over(&format!(
+                "balance {} lamports is below the {} lamport minimum",
+                lamports, min
+            )),
+            _ => false,
+        }
+    }
+
+    fn fail_over(&self, reason: &str) -> bool {
+        let Some(backup) = &self.backup else {
+            self.exhaust(format!(
+                "primary wallet {} {}; no backup_wallet configured",
+                self.primary.pubkey(),
+                reason
+            ));
+            return false;
+        };
+        if self
+            .on_backup
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            self.exhaust(format!(
+                "backup wallet {} {}; no wallet left to fail over to",
+                backup.pubkey(),
+                reason
+            ));
+            return false;
+        }
+        self.consecutive_failures.store(0, Ordering::Release);
+        let message = format!(
+            "primary wallet {} {}; failing over to backup wallet {}",
+            self.primary.pubkey(),
+            reason,
+            backup.pubkey()
+        );
+        error!("CRITICAL: {}", message);
+        alert::alert_critical(message);
+        METRICS.inc_wallet_failover();
+        true
+    }
+
+    /// Alert that the active wallet keeps failing with nothing to switch to, once until
+    /// it lands a trade again
+    fn exhaust(&self, message: String) {
+        if self.exhausted.swap(true, Ordering::AcqRel) {
+            warn!("{}", message);
+            return;
+        }
+        error!("CRITICAL: {}", message);
+        alert::alert_critical(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Keypair;
+    use std::sync::Arc;
+
+    fn failover(backup: Option<WalletSigner>) -> (WalletFailover, WalletSigner) {
+        let primary: WalletSigner = Arc::new(Keypair::new());
+        (
+            WalletFailover::new(primary.clone(), backup, 3, Some(10_000_000)),
+            primary,
+        )
+    }
+
+    #[test]
+    fn test_fails_over_after_repeated_primary_failures() {
+        let backup: WalletSigner = Arc::new(Keypair::new());
+        let (wallets, primary) = failover(Some(backup.clone()));
+
+        assert!(!wallets.record_failure());
+        assert!(!wallets.record_failure());
+        // A landed trade resets the streak
+        wallets.record_landed();
+        assert!(!wallets.record_failure());
+        assert!(!wallets.record_failure());
+        assert_eq!(wallets.active().pubkey(), primary.pubkey());
+
+        assert!(wallets.record_failure());
+        assert_eq!(wallets.active_wallet(), ActiveWallet::Backup);
+        assert_eq!(wallets.active().pubkey(), backup.pubkey());
+        assert_eq!(wallets.status().pubkey, backup.pubkey().to_string());
+
+        // Failures on the backup never switch back, and past the threshold leave the
+        // bot with no wallet to fail over to
+        assert!(!wallets.record_failure());
+        assert!(!wallets.record_failure());
+        assert!(!wallets.status().exhausted);
+        for _ in 0..3 {
+            assert!(!wallets.record_failure());
+        }
+        assert_eq!(wallets.active().pubkey(), backup.pubkey());
+        assert!(wallets.status().exhausted);
+
+        wallets.record_landed();
+        assert!(!wallets.status().exhausted);
+    }
+
+    #[test]
+    fn test_fails_over_on_low_balance() {
+        let backup: WalletSigner = Arc::new(Keypair::new());
+        let (wallets, _) = failover(Some(backup.clone()));
+
+        assert!(!wallets.record_balance(50_000_000));
+        assert!(wallets.record_balance(5_000_000));
+        assert_eq!(wallets.active().pubkey(), backup.pubkey());
+    }
+
+    #[test]
+    fn test_without_backup_stays_on_primary() {
+        let (wallets, primary) = failover(None);
+        for _ in 0..5 {
+            assert!(!wallets.record_failure());
+        }
+        assert!(!wallets.record_balance(0));
+        assert_eq!(wallets.active().pubkey(), primary.pubkey());
+        assert!(!wallets.status().backup_configured);
+        assert!(wallets.status().exhausted);
+    }
+}
@@ -4,19 +4,25 @@
 /// This enables "atomic" transaction execution and reverts on failure,
 /// protecting the bot from failed arbitrage attempts.
 
+use crate::constants::{JITO_RATE_LIMIT_INITIAL_BACKOFF_MS, JITO_RATE_LIMIT_MAX_BACKOFF_MS};
 use crate::error::{BotError, BotResult};
-use jito_protos::searcher::searcher_service_client::SearcherServiceClient;
-use jito_protos::searcher::SendBundleRequest;
+use crate::metrics::METRICS;
+use jito_protos::auth::auth_service_client::AuthServiceClient;
+use jito_protos::auth::{GenerateAuthChallengeRequest, GenerateAuthTokensRequest, Role};
 use jito_protos::bundle::Bundle;
+use jito_protos::packet::{Meta, Packet};
+use jito_protos::searcher::searcher_service_client::SearcherServiceClient;
+use jito_protos::searcher::{GetTipAccountsRequest, SendBundleRequest};
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::signature::Signer;
+use crate::signer::WalletSigner;
 use solana_sdk::transaction::VersionedTransaction;
-use std::sync::Arc;
-use tokio::sync::Mutex;
-use tonic::transport::Channel;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
 use tonic::codegen::InterceptedService;
 use tonic::service::Interceptor;
-use tonic::Status;
+use tonic::{Code, Status};
 use tracing::{info, error, warn};
 
 /// Jito Block Engine URLs
@@ -25,80 +31,373 @@ pub const JITO_AMSTERDAM: &str = "https://amsterdam.mainnet.block-engine.jito.wt
 pub const JITO_FRANKFURT: &str = "https://frankfurt.mainnet.block-engine.jito.wtf";
 pub const JITO_TOKYO: &str = "https://tokyo.mainnet.block-engine.jito.wtf";
 
-/// Auth interceptor for Jito
+/// Regions tried in order when the current block engine is unreachable
+pub const JITO_REGIONS: &[&str] = &[JITO_NYC, JITO_AMSTERDAM, JITO_FRANKFURT, JITO_TOKYO];
+
+/// Built-in Jito tip accounts, used when neither config nor the block engine provide a list
+pub const DEFAULT_TIP_ACCOUNTS: &[&str] = &[
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PuybsLxrr0whg",
+    "Cw8CFyM9FkoMi7K7JuOm59taPPqy4Q5mR5sM2Qyj9y3",
+    "ADaUMid9yfUytqMBgopDjb6u78Q986BMuhvAuqKgF4b",
+    "DfXygSm4jCyNCyb3VxG6ai7hDFj7tZkfy3qImCkPB38",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnIzKZ6j",
+    "ADuUkR4ykGytmZ5x4zo9uTRu2436LfJpFP3fvCwJoqF",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+];
+
+/// Parse the built-in tip accounts, skipping any entry that is not a valid pubkey
+pub fn default_tip_accounts() -> Vec<Pubkey> {
+    DEFAULT_TIP_ACCOUNTS
+        .iter()
+        .filter_map(|account| match account.parse() {
+            Ok(pubkey) => Some(pubkey),
+            Err(e) => {
+                warn!("Skipping invalid built-in Jito tip account {}: {}", account, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Why a bundle submission failed
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum JitoError {
+    #[error("authentication expired: {0}")]
+    AuthExpired(String),
+    #[error("rate limited: {0}")]
+    RateLimited(String),
+    #[error("connection failed: {0}")]
+    Connection(String),
+    #[error("bundle rejected: {0}")]
+    Rejected(String),
+}
+
+/// How the strategy loop responds to a failed bundle submission
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JitoRetryAction {
+    /// Re-authenticate and resend the bundle once
+    Reauthenticate,
+    /// Stop using the block engine until the backoff expires
+    Backoff,
+    /// Switch to the next block engine region
+    Failover,
+    /// The bundle itself is bad; resending it anywhere would fail the same way
+    GiveUp,
+}
+
+impl JitoError {
+    /// Classify a block engine gRPC status
+    pub fn from_status(status: &Status) -> Self {
+        let message = status.message().to_string();
+        match status.code() {
+            Code::Unauthenticated | Code::PermissionDenied => JitoError::AuthExpired(message),
+            Code::ResourceExhausted => JitoError::RateLimited(message),
+            Code::Unavailable | Code::DeadlineExceeded | Code::Cancelled | Code::Aborted => {
+                JitoError::Connection(message)
+            }
+            _ => JitoError::Rejected(message),
+        }
+    }
+
+    pub fn action(&self) -> JitoRetryAction {
+        match self {
+            JitoError::AuthExpired(_) => JitoRetryAction::Reauthenticate,
+            JitoError::RateLimited(_) => JitoRetryAction::Backoff,
+            JitoError::Connection(_) => JitoRetryAction::Failover,
+            JitoError::Rejected(_) => JitoRetryAction::GiveUp,
+        }
+    }
+
+    /// Label for the `jito_bundles_failed` metric
+    pub fn reason(&self) -> &'static str {
+        match self {
+            JitoError::AuthExpired(_) => "auth_expired",
+            JitoError::RateLimited(_) => "rate_limited",
+            JitoError::Connection(_) => "connection",
+            JitoError::Rejected(_) => "rejected",
+        }
+    }
+}
+
+impl From<Status> for BotError {
+    fn from(status: Status) -> Self {
+        BotError::JitoError(JitoError::from_status(&status))
+    }
+}
+
+/// Block engine region and rate-limit backoff, updated as submissions fail
+struct JitoState {
+    block_engine_url: String,
+    /// Channel to `block_engine_url`, replaced on region failover
+    channel: Channel,
+    backoff: Duration,
+    backoff_until: Option<Instant>,
+}
+
+/// Adds the block engine access token to every searcher request
 #[derive(Clone)]
 struct AuthInterceptor {
-    keypair: Arc<Keypair>,
+    access_token: Arc<RwLock<Option<String>>>,
 }
 
 impl Interceptor for AuthInterceptor {
     fn call(&mut self, mut request: tonic::Request<()>) -> Result<tonic::Request<()>, Status> {
-        let signature = self.keypair.sign_message(b"jito-auth-challenge"); // Simplified challenge signing
-        // In reality, Jito uses a challenge-response auth flow.
-        // For this implementation, we'll placeholder the auth logic as specialized Jito auth 
-        // usually requires establishing the stream first.
-        
+        if let Some(token) = self.access_token.read().unwrap().as_ref() {
+            let value = format!("Bearer {}", token)
+                .parse()
+                .map_err(|_| Status::unauthenticated("access token is not a valid header value"))?;
+            request.metadata_mut().insert("authorization", value);
+        }
         Ok(request)
     }
 }
 
+type SearcherClient = SearcherServiceClient<InterceptedService<Channel, AuthInterceptor>>;
+
 /// Jito Client Wrapper
 pub struct JitoClient {
-    client: Option<SearcherServiceClient<Channel>>, // Generic client for now
-    keypair: Arc<Keypair>,
-    block_engine_url: String,
+    keypair: WalletSigner,
+    /// Set by the challenge-response flow; `None` until the first authentication and
+    /// after a region failover
+    access_token: Arc<RwLock<Option<String>>>,
+    state: Mutex<JitoState>,
     tip_accounts: Vec<Pubkey>,
 }
 
+/// Channel to a block engine, connected on first use
+fn connect(block_engine_url: &str) -> BotResult<Channel> {
+    let mut endpoint = Endpoint::from_shared(block_engine_url.to_string())
+        .map_err(|e| BotError::ConfigError(format!("Invalid Jito URL: {}", e)))?;
+    if block_engine_url.starts_with("https") {
+        endpoint = endpoint
+            .tls_config(ClientTlsConfig::new())
+            .map_err(|e| BotError::ConfigError(format!("Invalid Jito TLS config: {}", e)))?;
+    }
+    Ok(endpoint.connect_lazy())
+}
+
+/// A transaction as the block engine expects it in a bundle
+fn bundle_packet(transaction: &VersionedTransaction) -> BotResult<Packet> {
+    let data = bincode::serialize(transaction).map_err(|e| {
+        BotError::TransactionBuildError(format!("Failed to serialize bundle transaction: {}", e))
+    })?;
+    Ok(Packet {
+        meta: Some(Meta {
+            size: data.len() as u64,
+            addr: String::new(),
+            port: 0,
+            flags: None,
+            sender_stake: 0,
+        }),
+        data,
+    })
+}
+
 impl JitoClient {
+    /// Create a client; `tip_accounts` pins the tip account list, falling back to the built-in list
     pub async fn new(
         block_engine_url: &str,
-        keypair: Arc<Keypair>,
+        keypair: WalletSigner,
+        tip_accounts: Option<Vec<Pubkey>>,
     ) -> BotResult<Self> {
         info!("Connecting to Jito Block Engine: {}", block_engine_url);
+        let channel = connect(block_engine_url)?;
 
-        // In a real implementation, we would perform the challenge-response auth here.
-        // Connecting to gRPC endpoint:
-        let endpoint = Request::from_shared(block_engine_url.to_string())
-            .map_err(|e| BotError::ConfigError(format!("Invalid Jito URL: {}", e)))?;
-        
-        // Simulating client creation
-        // let client = SearcherServiceClient::connect(endpoint).await ...
+        let tip_accounts = match tip_accounts {
+            Some(accounts) if !accounts.is_empty() => {
+                info!("Using {} configured Jito tip accounts", accounts.len());
+                accounts
+            }
+            _ => default_tip_accounts(),
+        };
 
         Ok(Self {
-            client: None,
             keypair,
-            block_engine_url: block_engine_url.to_string(),
-            tip_accounts: vec![
-                // Popular Jito Tip Accounts
-                "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5".parse().unwrap(),
-                "HFqU5x63VTqvQss8hp11i4wVV8bD44PuybsLxrr0whg".parse().unwrap(),
-                "Cw8CFyM9FkoMi7K7JuOm59taPPqy4Q5mR5sM2Qyj9y3".parse().unwrap(),
-                "ADaUMid9yfUytqMBgopDjb6u78Q986BMuhvAuqKgF4b".parse().unwrap(),
-                "DfXygSm4jCyNCyb3VxG6ai7hDFj7tZkfy3qImCkPB38".parse().unwrap(),
-                "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnIzKZ6j".parse().unwrap(),
-                "ADuUkR4ykGytmZ5x4zo9uTRu2436LfJpFP3fvCwJoqF".parse().unwrap(),
-                "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL".parse().unwrap(),
-            ],
+            access_token: Arc::new(RwLock::new(None)),
+            state: Mutex::new(JitoState {
+                block_engine_url: block_engine_url.to_string(),
+                channel,
+                backoff: Duration::from_millis(JITO_RATE_LIMIT_INITIAL_BACKOFF_MS),
+                backoff_until: None,
+            }),
+            tip_accounts,
         })
     }
 
-    /// Submit a bundle of transactions
+    /// Replace the tip account list with the block engine's current canonical accounts.
+    /// Returns the number of accounts fetched; the current list is kept on any failure.
+    pub async fn refresh_tip_accounts(&mut self) -> BotResult<usize> {
+        self.ensure_authenticated().await?;
+        let response = self
+            .searcher()
+            .get_tip_accounts(GetTipAccountsRequest {})
+            .await
+            .map_err(|e| BotError::rpc_retryable(self.block_engine_url(), e.to_string()))?;
+
+        let accounts = response
+            .into_inner()
+            .accounts
+            .iter()
+            .map(|account| {
+                account.parse().map_err(|e| BotError::InvalidPublicKey {
+                    key: account.clone(),
+                    source: e,
+                })
+            })
+            .collect::<BotResult<Vec<Pubkey>>>()?;
+
+        if accounts.is_empty() {
+            return Err(BotError::ConfigError(
+                "Jito block engine returned no tip accounts".to_string(),
+            ));
+        }
+
+        let count = accounts.len();
+        self.tip_accounts = accounts;
+        Ok(count)
+    }
+
+    /// Tip accounts bundles may pay into
+    pub fn tip_accounts(&self) -> &[Pubkey] {
+        &self.tip_accounts
+    }
+
+    /// Submit a bundle of transactions, returning the block engine's bundle ID
     pub async fn send_bundle(&self, transactions: Vec<VersionedTransaction>) -> BotResult<String> {
         if transactions.is_empty() {
-            return Err(BotError::TransactionError("Empty bundle".to_string()));
+            return Err(BotError::TransactionBuildError("Empty bundle".to_string()));
         }
+        let packets = transactions
+            .iter()
+            .map(bundle_packet)
+            .collect::<BotResult<Vec<_>>>()?;
+        self.ensure_authenticated().await?;
 
         info!("Sending bundle with {} transactions to Jito", transactions.len());
+        let response = self
+            .searcher()
+            .send_bundle(SendBundleRequest {
+                bundle: Some(Bundle {
+                    header: None,
+                    packets,
+                }),
+            })
+            .await?;
 
-        // Protobuf conversion would happen here
-        // let packets: Vec<Packet> = transactions.iter().map(...).collect();
-        // let bundle = Bundle { packets, header: None };
-        
-        // self.client.send_bundle(SendBundleRequest { bundle: Some(bundle) }).await ...
+        METRICS.inc_jito_bundle_sent();
+        self.state.lock().unwrap().reset_backoff();
+        Ok(response.into_inner().uuid)
+    }
 
-        // Placeholder for successful submission
-        Ok("bundle_signature_placeholder".to_string())
+    /// Searcher client on the current region's channel, carrying the access token
+    fn searcher(&self) -> SearcherClient {
+        SearcherServiceClient::with_interceptor(
+            self.state.lock().unwrap().channel.clone(),
+            AuthInterceptor {
+                access_token: self.access_token.clone(),
+            },
+        )
+    }
+
+    async fn ensure_authenticated(&self) -> BotResult<()> {
+        if self.access_token.read().unwrap().is_some() {
+            return Ok(());
+        }
+        self.reauthenticate().await
+    }
+
+    /// Count a failed submission and apply its strategy: arm the rate-limit backoff or
+    /// fail over to the next region. Returns what the caller should do next.
+    pub fn handle_failure(&self, error: &JitoError) -> JitoRetryAction {
+        self.handle_failure_at(error, Instant::now())
+    }
+
+    fn handle_failure_at(&self, error: &JitoError, now: Instant) -> JitoRetryAction {
+        METRICS.inc_jito_bundle_failed(error.reason());
+        let action = error.action();
+        let mut state = self.state.lock().unwrap();
+        match action {
+            JitoRetryAction::Backoff => {
+                warn!(
+                    "Jito rate limited, skipping the block engine for {}ms",
+                    state.backoff.as_millis()
+                );
+                state.backoff_until = Some(now + state.backoff);
+                state.backoff = (state.backoff * 2)
+                    .min(Duration::from_millis(JITO_RATE_LIMIT_MAX_BACKOFF_MS));
+            }
+            JitoRetryAction::Failover => {
+                let next = next_region(&state.block_engine_url);
+                warn!(
+                    "Jito block engine {} unreachable, failing over to {}",
+                    state.block_engine_url, next
+                );
+                match connect(next) {
+                    Ok(channel) => {
+                        state.block_engine_url = next.to_string();
+                        state.channel = channel;
+                        // Tokens are issued per block engine
+                        *self.access_token.write().unwrap() = None;
+                    }
+                    Err(e) => error!("Failed to connect to Jito block engine {}: {}", next, e),
+                }
+            }
+            JitoRetryAction::Reauthenticate | JitoRetryAction::GiveUp => {}
+        }
+        action
+    }
+
+    /// Run the block engine's challenge-response flow and store a fresh access token
+    pub async fn reauthenticate(&self) -> BotResult<()> {
+        info!("Authenticating with Jito block engine {}", self.block_engine_url());
+        let channel = self.state.lock().unwrap().channel.clone();
+        let mut auth = AuthServiceClient::new(channel);
+        let pubkey = self.keypair.pubkey();
+
+        let challenge = auth
+            .generate_auth_challenge(GenerateAuthChallengeRequest {
+                role: Role::Searcher as i32,
+                pubkey: pubkey.to_bytes().to_vec(),
+            })
+            .await?
+            .into_inner()
+            .challenge;
+        // The block engine signs off on "<pubkey>-<challenge>"
+        let challenge = format!("{}-{}", pubkey, challenge);
+        let signed_challenge = self.keypair.sign_message(challenge.as_bytes());
+
+        let tokens = auth
+            .generate_auth_tokens(GenerateAuthTokensRequest {
+                challenge,
+                client_pubkey: pubkey.to_bytes().to_vec(),
+                signed_challenge: signed_challenge.as_ref().to_vec(),
+            })
+            .await?
+            .into_inner();
+        let access_token = tokens.access_token.ok_or_else(|| {
+            JitoError::AuthExpired("block engine returned no access token".to_string())
+        })?;
+        *self.access_token.write().unwrap() = Some(access_token.value);
+        Ok(())
+    }
+
+    /// Whether submissions should skip the block engine after a rate limit
+    pub fn is_backing_off(&self) -> bool {
+        self.is_backing_off_at(Instant::now())
+    }
+
+    fn is_backing_off_at(&self, now: Instant) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .backoff_until
+            .map_or(false, |until| now < until)
+    }
+
+    /// Block engine URL this client submits bundles to
+    pub fn block_engine_url(&self) -> String {
+        self.state.lock().unwrap().block_engine_url.clone()
     }
 
     /// Get a random tip account to include in the bundle
@@ -109,5 +408,98 @@ impl JitoClient {
     }
 }
 
-use tonic::transport::Endpoint as Request;
+impl JitoState {
+    fn reset_backoff(&mut self) {
+        self.backoff = Duration::from_millis(JITO_RATE_LIMIT_INITIAL_BACKOFF_MS);
+        self.backoff_until = None;
+    }
+}
+
+/// Region after `current`, wrapping around; unknown URLs fail over to the first region
+fn next_region(current: &str) -> &'static str {
+    let next = JITO_REGIONS
+        .iter()
+        .position(|region| *region == current)
+        .map_or(0, |index| (index + 1) % JITO_REGIONS.len());
+    JITO_REGIONS[next]
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Keypair;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_configured_tip_accounts_override_defaults() {
+        let configured = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let client = JitoClient::new(JITO_NYC, Arc::new(Keypair::new()), Some(configured.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(client.tip_accounts(), configured.as_slice());
+        for _ in 0..50 {
+            assert!(configured.contains(&client.get_random_tip_account()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_empty_tip_accounts_fall_back_to_defaults() {
+        let client = JitoClient::new(JITO_NYC, Arc::new(Keypair::new()), Some(vec![]))
+            .await
+            .unwrap();
+
+        assert!(!client.tip_accounts().is_empty());
+        assert_eq!(client.tip_accounts(), default_tip_accounts().as_slice());
+    }
+
+    #[test]
+    fn test_status_maps_to_retry_action() {
+        let cases = [
+            (Status::unauthenticated("token expired"), JitoRetryAction::Reauthenticate),
+            (Status::permission_denied("not allowed"), JitoRetryAction::Reauthenticate),
+            (Status::resource_exhausted("slow down"), JitoRetryAction::Backoff),
+            (Status::unavailable("connection reset"), JitoRetryAction::Failover),
+            (Status::deadline_exceeded("timed out"), JitoRetryAction::Failover),
+            (Status::invalid_argument("bundle simulation failed"), JitoRetryAction::GiveUp),
+        ];
+
+        for (status, action) in cases {
+            assert_eq!(JitoError::from_status(&status).action(), action, "{:?}", status);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failures_apply_backoff_and_failover() {
+        let client = JitoClient::new(JITO_NYC, Arc::new(Keypair::new()), None)
+            .await
+            .unwrap();
+        let now = Instant::now();
+
+        let backoff = Duration::from_millis(JITO_RATE_LIMIT_INITIAL_BACKOFF_MS);
+
+        let action = client.handle_failure_at(&JitoError::RateLimited("slow down".to_string()), now);
+        assert_eq!(action, JitoRetryAction::Backoff);
+        assert!(client.is_backing_off_at(now));
+        assert!(!client.is_backing_off_at(now + backoff));
+
+        let action = client.handle_failure_at(&JitoError::Connection("reset".to_string()), now);
+        assert_eq!(action, JitoRetryAction::Failover);
+        assert_eq!(client.block_engine_url(), JITO_AMSTERDAM);
+
+        let action = client.handle_failure_at(&JitoError::Rejected("bad bundle".to_string()), now);
+        assert_eq!(action, JitoRetryAction::GiveUp);
+        assert_eq!(client.block_engine_url(), JITO_AMSTERDAM);
+        assert_eq!(next_region(JITO_TOKYO), JITO_NYC);
+    }
+
+    #[test]
+    fn test_bundle_packet_carries_serialized_transaction() {
+        let transaction = VersionedTransaction::default();
+        let packet = bundle_packet(&transaction).unwrap();
+
+        assert_eq!(packet.meta.unwrap().size, packet.data.len() as u64);
+        let decoded: VersionedTransaction = bincode::deserialize(&packet.data).unwrap();
+        assert_eq!(decoded, transaction);
+    }
+}
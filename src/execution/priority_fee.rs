@@ -0,0 +1,180 @@
+/// Account-Specific Priority Fees
+///
+/// Priority fees are paid for write locks, so what it takes to land depends on the
+/// accounts a transaction writes. With `spam.priority_fee_percentile` set, the
+/// compute-unit price comes from the recent fees paid to write the route's own
+/// accounts (the pool state and vaults) instead of `spam.compute_unit_price`, falling
+/// back to the account-agnostic fees when those accounts have none.
+
+use crate::error::BotResult;
+use async_trait::async_trait;
+use solana_client::nonblocking::rpc_client::RpcClient as AsyncRpcClient;
+use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Recent prioritization fees, in micro-lamports per compute unit
+#[async_trait]
+pub trait PriorityFeeSource: Sync {
+    /// Fees of recent slots paid by transactions writing all of `accounts`; an empty
+    /// slice asks for fees regardless of account
+    async fn recent_fees(&self, accounts: &[Pubkey]) -> BotResult<Vec<u64>>;
+}
+
+#[async_trait]
+impl PriorityFeeSource for AsyncRpcClient {
+    async fn recent_fees(&self, accounts: &[Pubkey]) -> BotResult<Vec<u64>> {
+        Ok(self
+            .get_recent_prioritization_fees(accounts)
+            .await?
+            .into_iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect())
+    }
+}
+
+/// Nearest-rank `percentile` (1-100) of the non-zero `fees`, or None if there are none
+pub fn fee_percentile(fees: &[u64], percentile: u8) -> Option<u64> {
+    let mut paid: Vec<u64> = fees.iter().copied().filter(|fee| *fee > 0).collect();
+    if paid.is_empty() {
+        return None;
+    }
+    paid.sort_unstable();
+    let rank = (paid.len() * percentile.clamp(1, 100) as usize).div_ceil(100);
+    Some(paid[rank - 1])
+}
+
+/// `percentile` of the fees paid across every account's recent slots
+pub fn aggregate_account_fees(per_account: &[Vec<u64>], percentile: u8) -> Option<u64> {
+    let fees: Vec<u64> = per_account.iter().flatten().copied().collect();
+    fee_percentile(&fees, percentile)
+}
+
+/// Caches each account's recent fees for `ttl`, since a route's accounts are read on
+/// every send
+pub struct PriorityFeeEstimator {
+    ttl: Duration,
+    fees: Mutex<HashMap<Pubkey, (Vec<u64>, Instant)>>,
+}
+
+impl PriorityFeeEstimator {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            fees: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Compute-unit price at `percentile` of the fees paid to write `writable_accounts`,
+    /// or of all recent fees when none were paid for them. None if neither is known.
+    /// Accounts missing from the cache are read concurrently.
+    pub async fn estimate(
+        &self,
+        source: &dyn PriorityFeeSource,
+        writable_accounts: &[Pubkey],
+        percentile: u8,
+    ) -> Option<u64> {
+        let per_account: Vec<Vec<u64>> = futures::future::join_all(
+            writable_accounts
+                .iter()
+                .map(|account| self.account_fees(source, Some(account))),
+        )
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+        if let Some(price) = aggregate_account_fees(&per_account, percentile) {
+            debug!(
+                "Priority fee p{} over {} writable accounts: {} micro-lamports/CU",
+                percentile,
+                writable_accounts.len(),
+                price
+            );
+            return Some(price);
+        }
+
+        let global = self.account_fees(source, None).await?;
+        let price = fee_percentile(&global, percentile);
+        debug!("No fees paid for the route's accounts, global p{}: {:?}", percentile, price);
+        price
+    }
+
+    /// Recent fees for `account`, or account-agnostic fees for None
+    async fn account_fees(&self, source: &dyn PriorityFeeSource, account: Option<&Pubkey>) -> Option<Vec<u64>> {
+        // The default key stands in for "every account"; no real account has it
+        let key = account.copied().unwrap_or_default();
+        if let Some(fees) = self.cached(&key) {
+            return Some(fees);
+        }
+        let accounts: Vec<Pubkey> = account.into_iter().copied().collect();
+        match source.recent_fees(&accounts).await {
+            Ok(fees) => {
+                self.fees.lock().unwrap().insert(key, (fees.clone(), Instant::now()));
+                Some(fees)
+            }
+            Err(e) => {
+                warn!("Failed to read recent prioritization fees: {}", e);
+                None
+            }
+        }
+    }
+
+    fn cached(&self, key: &Pubkey) -> Option<Vec<u64>> {
+        let fees = self.fees.lock().unwrap();
+        let (fees, fetched_at) = fees.get(key)?;
+        (fetched_at.elapsed() < self.ttl).then(|| fees.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serves fixed fees per account and counts the requests
+    struct FixedFees {
+        per_account: HashMap<Pubkey, Vec<u64>>,
+        global: Vec<u64>,
+        requests: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl PriorityFeeSource for FixedFees {
+        async fn recent_fees(&self, accounts: &[Pubkey]) -> BotResult<Vec<u64>> {
+            *self.requests.lock().unwrap() += 1;
+            Ok(match accounts.first() {
+                Some(account) => self.per_account.get(account).cloned().unwrap_or_default(),
+                None => self.global.clone(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_percentile_across_account_fees() {
+        // Zeros are slots nobody paid to write the account in
+        let per_account = vec![vec![0, 100, 400], vec![200, 0, 300], vec![0, 0, 0], vec![]];
+        // Paid fees sorted: 100, 200, 300, 400
+        assert_eq!(aggregate_account_fees(&per_account, 50), Some(200));
+        assert_eq!(aggregate_account_fees(&per_account, 75), Some(300));
+        assert_eq!(aggregate_account_fees(&per_account, 100), Some(400));
+        assert_eq!(aggregate_account_fees(&per_account, 1), Some(100));
+        assert_eq!(aggregate_account_fees(&[vec![0, 0], vec![]], 75), None);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_falls_back_to_global_fees() {
+        let (hot, cold) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let source = FixedFees {
+            per_account: HashMap::from([(hot, vec![5_000, 10_000]), (cold, vec![0, 0])]),
+            global: vec![1_000, 2_000, 3_000],
+            requests: Mutex::new(0),
+        };
+        let estimator = PriorityFeeEstimator::new(Duration::from_secs(60));
+
+        assert_eq!(estimator.estimate(&source, &[hot, cold], 50).await, Some(5_000));
+        assert_eq!(estimator.estimate(&source, &[cold], 50).await, Some(2_000));
+        // hot, cold and the global query; the second estimate reused cold's fees
+        assert_eq!(*source.requests.lock().unwrap(), 3);
+    }
+}
@@ -0,0 +1,147 @@
+/// Profit Reconciliation
+///
+/// Reads a confirmed trade's profit, from the executor's return data when it reports
+/// one and otherwise from the effect on the wallet's SOL (native plus wSOL) balance,
+/// so the realized profit can be stored next to the simulated estimate.
+
+use crate::constants::sol_mint;
+use crate::error::BotResult;
+use crate::metrics::METRICS;
+use crate::transaction::{ExecutorResult, SendResult};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{
+    UiTransactionEncoding, UiTransactionStatusMeta, UiTransactionTokenBalance,
+};
+use tracing::{info, warn};
+
+/// SOL the wallet holds, natively and in wSOL token accounts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalletBalance {
+    pub lamports: u64,
+    pub wsol_lamports: u64,
+}
+
+impl WalletBalance {
+    pub fn total(&self) -> i128 {
+        self.lamports as i128 + self.wsol_lamports as i128
+    }
+}
+
+/// Realized profit of a trade: the wallet's SOL change net of every fee and tip it paid
+pub fn realized_profit(pre: WalletBalance, post: WalletBalance) -> i64 {
+    (post.total() - pre.total()).clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+/// The wallet's balance before and after the transaction. The wallet pays the fee, so
+/// its native balance is the first account's.
+pub fn wallet_balances(
+    meta: &UiTransactionStatusMeta,
+    wallet: &Pubkey,
+) -> Option<(WalletBalance, WalletBalance)> {
+    let wsol = |balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>| -> u64 {
+        let owner = wallet.to_string();
+        let mint = sol_mint().to_string();
+        match balances {
+            OptionSerializer::Some(balances) => balances
+                .iter()
+                .filter(|balance| {
+                    balance.mint == mint
+                        && matches!(&balance.owner, OptionSerializer::Some(o) if *o == owner)
+                })
+                .filter_map(|balance| balance.ui_token_amount.amount.parse::<u64>().ok())
+                .sum(),
+            _ => 0,
+        }
+    };
+
+    let pre = WalletBalance {
+        lamports: *meta.pre_balances.first()?,
+        wsol_lamports: wsol(&meta.pre_token_balances),
+    };
+    let post = WalletBalance {
+        lamports: *meta.post_balances.first()?,
+        wsol_lamports: wsol(&meta.post_token_balances),
+    };
+    Some((pre, post))
+}
+
+/// Fetch the confirmed transaction and take its realized profit from the executor's
+/// return data, falling back to the wallet's balance change
+pub fn fetch_realized_profit(
+    client: &RpcClient,
+    signature: &Signature,
+    wallet: &Pubkey,
+    executor_program_id: &Pubkey,
+) -> BotResult<Option<i64>> {
+    let transaction = client.get_transaction_with_config(
+        signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        },
+    )?;
+    let Some(meta) = transaction.transaction.meta.as_ref() else {
+        return Ok(None);
+    };
+    if let OptionSerializer::Some(return_data) = &meta.return_data {
+        if let Some(executor_result) = ExecutorResult::from_return_data(return_data, executor_program_id)
+        {
+            return Ok(Some(executor_result.profit_lamports.min(i64::MAX as u64) as i64));
+        }
+    }
+    Ok(wallet_balances(meta, wallet).map(|(pre, post)| realized_profit(pre, post)))
+}
+
+/// Record the realized profit of a landed result and its error against the estimate
+pub fn reconcile_send_result(
+    client: &RpcClient,
+    result: &mut SendResult,
+    wallet: &Pubkey,
+    executor_program_id: &Pubkey,
+) {
+    match fetch_realized_profit(client, &result.signature, wallet, executor_program_id) {
+        Ok(Some(realized)) => {
+            let estimated = result.simulated_profit.unwrap_or(0) as i64;
+            info!(
+                "Transaction {} realized {} lamports (estimated {})",
+                result.signature, realized, estimated
+            );
+            METRICS.observe_profit_estimation_error(realized - estimated);
+            result.realized_profit = Some(realized);
+        }
+        Ok(None) => warn!("Transaction {} has no balance metadata to reconcile", result.signature),
+        Err(e) => warn!("Failed to reconcile profit of {}: {}", result.signature, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_realized_profit_from_balances() {
+        // 12_000 lamports of wSOL gained, 5_000 lamport fee paid natively
+        let pre = WalletBalance {
+            lamports: 1_000_000_000,
+            wsol_lamports: 500_000_000,
+        };
+        let post = WalletBalance {
+            lamports: 999_995_000,
+            wsol_lamports: 500_012_000,
+        };
+        assert_eq!(realized_profit(pre, post), 7_000);
+
+        // A failed route still costs the fee
+        let failed = WalletBalance {
+            lamports: 999_995_000,
+            wsol_lamports: 500_000_000,
+        };
+        assert_eq!(realized_profit(pre, failed), -5_000);
+    }
+}
@@ -0,0 +1,301 @@
+/// Wallet Signers
+///
+/// Loads the wallet signer from a key, keypair file, environment variable or a
+/// remote signing service, so the private key does not have to live in the config.
+
+use crate::config::WalletConfig;
+use crate::constants::REMOTE_SIGNER_TIMEOUT_MS;
+use crate::error::{BotError, BotResult};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature};
+use solana_sdk::signer::{Signer, SignerError};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Signer shared by the strategy loops and the Jito client
+pub type WalletSigner = Arc<dyn Signer + Send + Sync>;
+
+/// `private_key` prefix naming the environment variable that holds the key
+const ENV_KEY_PREFIX: &str = "env:";
+
+/// Where the wallet signer comes from
+#[derive(Clone, PartialEq)]
+pub enum SignerSource {
+    /// Base58-encoded 64-byte keypair
+    Base58(String),
+    /// Path to a JSON keypair file
+    File(PathBuf),
+    /// Environment variable holding a base58-encoded keypair
+    Env(String),
+    /// HTTP signing service that holds the key
+    RemoteSigner { url: String },
+}
+
+// Never print key material
+impl fmt::Debug for SignerSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignerSource::Base58(_) => write!(f, "Base58(<redacted>)"),
+            SignerSource::File(path) => write!(f, "File({:?})", path),
+            SignerSource::Env(var) => write!(f, "Env({})", var),
+            SignerSource::RemoteSigner { url } => write!(f, "RemoteSigner {{ url: {} }}", url),
+        }
+    }
+}
+
+impl SignerSource {
+    /// Pick the source for a wallet config: a remote signer if configured, otherwise
+    /// `private_key` as `env:VAR` to read the key from `VAR` when the signer is loaded
+    /// (so it never sits in the config), a base58 keypair, or a keypair file path
+    pub fn from_wallet_config(wallet: &WalletConfig) -> Self {
+        if let Some(url) = &wallet.remote_signer_url {
+            return SignerSource::RemoteSigner { url: url.clone() };
+        }
+        if let Some(var) = wallet.private_key.strip_prefix(ENV_KEY_PREFIX) {
+            return SignerSource::Env(var.to_string());
+        }
+
+        match bs58::decode(&wallet.private_key).into_vec() {
+            Ok(bytes) if bytes.len() == 64 => SignerSource::Base58(wallet.private_key.clone()),
+            _ => SignerSource::File(PathBuf::from(&wallet.private_key)),
+        }
+    }
+
+    pub fn load(&self) -> BotResult<WalletSigner> {
+        match self {
+            SignerSource::Base58(key) => Ok(Arc::new(keypair_from_base58(key)?)),
+            SignerSource::File(path) => read_keypair_file(path)
+                .map(|keypair| Arc::new(keypair) as WalletSigner)
+                .map_err(|e| {
+                    BotError::WalletError(format!(
+                        "Failed to load keypair file '{}': {}. Expected base58-encoded private key or path to keypair file.",
+                        path.display(),
+                        e
+                    ))
+                }),
+            SignerSource::Env(var) => {
+                let key = std::env::var(var).map_err(|_| {
+                    BotError::WalletError(format!("Environment variable '{}' is not set", var))
+                })?;
+                Ok(Arc::new(keypair_from_base58(&key)?))
+            }
+            SignerSource::RemoteSigner { url } => Ok(Arc::new(RemoteSigner::new(Box::new(
+                HttpSigningTransport::new(url, Duration::from_millis(REMOTE_SIGNER_TIMEOUT_MS))?,
+            ))?)),
+        }
+    }
+}
+
+fn keypair_from_base58(key: &str) -> BotResult<Keypair> {
+    let bytes = bs58::decode(key)
+        .into_vec()
+        .map_err(|e| BotError::WalletError(format!("Invalid base58 private key: {}", e)))?;
+    Keypair::from_bytes(&bytes)
+        .map_err(|e| BotError::WalletError(format!("Invalid keypair bytes: {}", e)))
+}
+
+/// Transport to a service that holds the wallet key and signs messages on request
+pub trait SigningTransport: Send + Sync {
+    fn pubkey(&self) -> Result<Pubkey, String>;
+
+    fn sign(&self, pubkey: &Pubkey, message: &[u8]) -> Result<Signature, String>;
+}
+
+/// Signer that delegates to a `SigningTransport`, verifying every returned signature
+pub struct RemoteSigner {
+    transport: Box<dyn SigningTransport>,
+    pubkey: Pubkey,
+}
+
+impl RemoteSigner {
+    /// Connect to the signer and fetch the pubkey it signs for
+    pub fn new(transport: Box<dyn SigningTransport>) -> BotResult<Self> {
+        let pubkey = transport
+            .pubkey()
+            .map_err(|e| BotError::WalletError(format!("Remote signer pubkey request failed: {}", e)))?;
+        Ok(Self { transport, pubkey })
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let signature = self
+            .transport
+            .sign(&self.pubkey, message)
+            .map_err(SignerError::Custom)?;
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(SignerError::Custom(
+                "remote signer returned a signature that does not verify".to_string(),
+            ));
+        }
+        Ok(signature)
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+/// JSON-over-HTTP signing endpoint:
+/// `GET {url}/pubkey` returns `{"pubkey": <base58>}` and
+/// `POST {url}/sign` with `{"pubkey", "message": <base64>}` returns `{"signature": <base58>}`
+pub struct HttpSigningTransport {
+    url: String,
+    /// Shared by every request, so its connections to the signer are reused
+    client: reqwest::blocking::Client,
+}
+
+impl HttpSigningTransport {
+    pub fn new(url: &str, timeout: Duration) -> BotResult<Self> {
+        // Built off the async runtime like the requests, as it starts the client's own runtime
+        let client = std::thread::scope(|scope| {
+            scope
+                .spawn(|| reqwest::blocking::Client::builder().timeout(timeout).build())
+                .join()
+                .map_err(|_| BotError::WalletError("remote signer client setup panicked".to_string()))
+        })?
+        .map_err(|e| BotError::WalletError(format!("Failed to build remote signer client: {}", e)))?;
+        Ok(Self {
+            url: url.trim_end_matches('/').to_string(),
+            client,
+        })
+    }
+
+    fn request(&self, path: &str, body: Option<serde_json::Value>) -> Result<serde_json::Value, String> {
+        let url = format!("{}/{}", self.url, path);
+        // `Signer` is synchronous; the blocking client must not run on an async runtime thread
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let request = match &body {
+                        Some(body) => self.client.post(&url).json(body),
+                        None => self.client.get(&url),
+                    };
+                    request
+                        .send()
+                        .and_then(|response| response.error_for_status())
+                        .and_then(|response| response.json::<serde_json::Value>())
+                        .map_err(|e| format!("{}: {}", url, e))
+                })
+                .join()
+                .map_err(|_| "remote signer request panicked".to_string())?
+        })
+    }
+}
+
+impl SigningTransport for HttpSigningTransport {
+    fn pubkey(&self) -> Result<Pubkey, String> {
+        let response = self.request("pubkey", None)?;
+        let pubkey = response["pubkey"]
+            .as_str()
+            .ok_or_else(|| "response has no pubkey".to_string())?;
+        Pubkey::from_str(pubkey).map_err(|e| e.to_string())
+    }
+
+    fn sign(&self, pubkey: &Pubkey, message: &[u8]) -> Result<Signature, String> {
+        let body = serde_json::json!({
+            "pubkey": pubkey.to_string(),
+            "message": BASE64.encode(message),
+        });
+        let response = self.request("sign", Some(body))?;
+        let signature = response["signature"]
+            .as_str()
+            .ok_or_else(|| "response has no signature".to_string())?;
+        Signature::from_str(signature).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::message::{v0, VersionedMessage};
+    use solana_sdk::system_instruction;
+    use solana_sdk::transaction::VersionedTransaction;
+
+    /// Signs with a local keypair, or with a different key when `tamper` is set
+    struct MockTransport {
+        keypair: Keypair,
+        tamper: bool,
+    }
+
+    impl SigningTransport for MockTransport {
+        fn pubkey(&self) -> Result<Pubkey, String> {
+            Ok(self.keypair.pubkey())
+        }
+
+        fn sign(&self, _pubkey: &Pubkey, message: &[u8]) -> Result<Signature, String> {
+            if self.tamper {
+                return Ok(Keypair::new().sign_message(message));
+            }
+            Ok(self.keypair.sign_message(message))
+        }
+    }
+
+    fn remote_signer(tamper: bool) -> (RemoteSigner, Pubkey) {
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+        let signer = RemoteSigner::new(Box::new(MockTransport { keypair, tamper })).unwrap();
+        (signer, pubkey)
+    }
+
+    #[test]
+    fn test_remote_signer_signs_transaction() {
+        let (signer, pubkey) = remote_signer(false);
+        assert_eq!(signer.pubkey(), pubkey);
+
+        let ix = system_instruction::transfer(&pubkey, &Pubkey::new_unique(), 1);
+        let message = v0::Message::try_compile(&pubkey, &[ix], &[], Hash::new_unique()).unwrap();
+        let signer: &dyn Signer = &signer;
+        let tx = VersionedTransaction::try_new(VersionedMessage::V0(message), &[signer]).unwrap();
+
+        assert!(tx.verify_with_results().into_iter().all(|valid| valid));
+    }
+
+    #[test]
+    fn test_remote_signer_rejects_bad_signature() {
+        let (signer, _) = remote_signer(true);
+        assert!(signer.try_sign_message(b"message").is_err());
+    }
+
+    #[test]
+    fn test_signer_source_from_wallet_config() {
+        let keypair = Keypair::new();
+        let mut wallet = WalletConfig {
+            private_key: keypair.to_base58_string(),
+            remote_signer_url: None,
+        };
+        let source = SignerSource::from_wallet_config(&wallet);
+        assert_eq!(source, SignerSource::Base58(keypair.to_base58_string()));
+        assert_eq!(source.load().unwrap().pubkey(), keypair.pubkey());
+        assert!(!format!("{:?}", source).contains(&keypair.to_base58_string()));
+
+        wallet.private_key = "env:WALLET_KEY".to_string();
+        assert_eq!(
+            SignerSource::from_wallet_config(&wallet),
+            SignerSource::Env("WALLET_KEY".to_string())
+        );
+
+        wallet.private_key = "/etc/solana/id.json".to_string();
+        assert_eq!(
+            SignerSource::from_wallet_config(&wallet),
+            SignerSource::File(PathBuf::from("/etc/solana/id.json"))
+        );
+
+        wallet.remote_signer_url = Some("https://signer.example.com".to_string());
+        assert_eq!(
+            SignerSource::from_wallet_config(&wallet),
+            SignerSource::RemoteSigner {
+                url: "https://signer.example.com".to_string()
+            }
+        );
+    }
+}
@@ -1,58 +1,318 @@
-use crate::config::Config;
+use crate::account_metas::AccountMetasBuilder;
+use crate::config::{
+    BlockhashSource, BotConfig, Config, ConfirmationMode, SendStrategy, StaticAccount, TransactionVersion,
+};
 use crate::constants::{
-    COMPUTE_UNIT_RANDOMIZATION_RANGE, DEFAULT_COMPUTE_UNIT_PRICE, DEFAULT_MAX_RETRIES,
-    EXECUTOR_PROGRAM_PUBKEY, FEE_COLLECTOR_PUBKEY, KAMINO_LENDING_PROGRAM_PUBKEY,
-    MINIMUM_PROFIT_DEFAULT, NO_FAILURE_MODE_DEFAULT, PUMP_AUTHORITY_PUBKEY,
-    PUMP_GLOBAL_CONFIG_PUBKEY, SYSVAR_INSTRUCTIONS_PUBKEY,
+    ACCOUNT_IN_USE_BACKOFF_MS, BASE_FEE_LAMPORTS_PER_SIGNATURE, BASIS_POINTS_DENOMINATOR, COMPUTE_UNIT_RANDOMIZATION_RANGE, COMPUTE_UNIT_SIMULATION_MARGIN_BPS, DEFAULT_ACCOUNT_IN_USE_RETRIES, CONCENTRATED_POOL_COMPUTE_UNITS, CONFIRM_POLL_INTERVAL_MS,
+    DEFAULT_BLOCKHASH_REFRESH_INTERVAL_SECS, DEFAULT_COMPUTE_UNIT_PRICE,
+    DEFAULT_MAX_RETRIES, ESTIMATED_TRANSACTION_OVERHEAD_BYTES, JITO_TIP_LAMPORTS,
+    KAMINO_LENDING_PROGRAM_PUBKEY, MAX_COMPUTE_UNIT_LIMIT, MAX_TRANSACTION_ACCOUNTS, MICRO_LAMPORTS_PER_LAMPORT,
+    MAX_DROPPED_RESENDS, MAX_JITO_BUNDLE_TRANSACTIONS, MAX_LOOKUP_TABLES_PER_TRANSACTION, MAX_TRANSACTION_SIZE_BYTES, MINIMUM_PROFIT_DEFAULT,
+    NO_FAILURE_MODE_DEFAULT, PRIORITY_FEE_CACHE_TTL_MS, PUMP_GLOBAL_CONFIG_PUBKEY,
+    MAX_SIGNATURE_STATUSES_PER_REQUEST, RECENT_SIGNATURES_CAPACITY, RECENT_SIGNATURE_WINDOW_SECS,
+    RESEND_CONFIRM_WINDOW_MS, RESEND_POLL_INTERVAL_MS, STANDARD_POOL_COMPUTE_UNITS,
+    SYSVAR_INSTRUCTIONS_PUBKEY,
 };
-use crate::dex::raydium::{raydium_authority, raydium_cp_authority};
-use crate::dex::solfi::constants::solfi_program_id;
-use crate::dex::vertigo::constants::vertigo_program_id;
 use crate::error::BotError;
+use crate::metrics::METRICS;
 use crate::pools::MintPoolData;
+use crate::priority_fee::{PriorityFeeEstimator, PriorityFeeSource};
+use crate::reconcile::reconcile_send_result;
+use crate::signer::WalletSigner;
+use crate::tx_dump;
+use base64::Engine;
+use solana_client::nonblocking::rpc_client::RpcClient as AsyncRpcClient;
 use solana_client::rpc_client::RpcClient;
 use solana_program::instruction::Instruction;
 use solana_sdk::address_lookup_table::AddressLookupTableAccount;
-use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_sdk::compute_budget::ComputeBudgetInstruction;
 use solana_sdk::hash::Hash;
-use solana_sdk::message::v0::Message;
-use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::message::{v0, Message as LegacyMessage, VersionedMessage};
+use solana_sdk::signature::Signature;
 use solana_sdk::signer::Signer;
-use solana_sdk::transaction::VersionedTransaction;
-use std::sync::Arc;
-use tracing::{debug, error, info};
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::{TransactionError, VersionedTransaction};
+use solana_transaction_status::{TransactionStatus, UiReturnDataEncoding, UiTransactionReturnData};
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
 
 use crate::constants::sol_mint;
-use crate::dex::meteora::constants::{
-    damm_program_id, damm_v2_event_authority, damm_v2_pool_authority, damm_v2_program_id,
-    dlmm_event_authority, dlmm_program_id, vault_program_id,
-};
-use crate::dex::pump::constants::{pump_fee_wallet, pump_program_id};
-use crate::dex::raydium::constants::{
-    raydium_clmm_program_id, raydium_cp_program_id, raydium_program_id,
-};
-use crate::dex::whirlpool::constants::whirlpool_program_id;
 use solana_program::instruction::AccountMeta;
 use solana_program::pubkey::Pubkey;
 use solana_program::{system_instruction, system_program};
 use spl_associated_token_account::ID as associated_token_program_id;
 use spl_token::ID as token_program_id;
-use std::str::FromStr;
 
-use crate::jito::JitoClient;
+use crate::jito::{JitoClient, JitoRetryAction};
 
-pub async fn build_and_send_transaction(
-    wallet_kp: &Keypair,
+/// Outcome of sending the swap transaction through one endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct SendResult {
+    pub signature: Signature,
+    pub endpoint: String,
+    pub simulated_profit: Option<u64>,
+    pub landed: bool,
+    /// Wallet balance change once reconciled (see `reconcile_profit`)
+    pub realized_profit: Option<i64>,
+    /// What the transaction pays if it lands
+    pub fees: FeeBreakdown,
+}
+
+/// Fees a trade pays, in lamports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FeeBreakdown {
+    /// Signature fees
+    pub base_fee_lamports: u64,
+    /// Compute-unit price times the requested compute-unit limit
+    pub priority_fee_lamports: u64,
+    /// Jito tip transferred by the transaction
+    pub tip_lamports: u64,
+    /// DEX swap fees of a quoted route, in SOL (see `ArbitrageOpportunity::swap_fees`)
+    pub protocol_fee_lamports: u64,
+}
+
+impl FeeBreakdown {
+    /// Base and priority fees of the signed `tx`, which transfers `tip_lamports` as a tip
+    pub fn of_transaction(tx: &VersionedTransaction, tip_lamports: u64) -> Self {
+        let (compute_unit_limit, compute_unit_price) = tx_dump::compute_budget(&tx.message);
+        Self {
+            base_fee_lamports: tx.signatures.len() as u64 * BASE_FEE_LAMPORTS_PER_SIGNATURE,
+            priority_fee_lamports: priority_fee_lamports(
+                compute_unit_limit.unwrap_or(0),
+                compute_unit_price.unwrap_or(0),
+            ),
+            tip_lamports,
+            protocol_fee_lamports: 0,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.base_fee_lamports
+            .saturating_add(self.priority_fee_lamports)
+            .saturating_add(self.tip_lamports)
+            .saturating_add(self.protocol_fee_lamports)
+    }
+}
+
+impl SendResult {
+    /// Whether this result carries a real transaction signature
+    pub fn has_signature(&self) -> bool {
+        self.signature != Signature::default()
+    }
+
+    /// Profit the executor reported in simulation less the base fee, priority fee and
+    /// tip, None when the send was not simulated
+    pub fn simulated_net_profit(&self) -> Option<i64> {
+        let paid = self.fees.base_fee_lamports as i128
+            + self.fees.priority_fee_lamports as i128
+            + self.fees.tip_lamports as i128;
+        self.simulated_profit
+            .map(|profit| (profit as i128 - paid).clamp(i64::MIN as i128, i64::MAX as i128) as i64)
+    }
+}
+
+/// Fees of the landed transactions among `results`, each signature counted once
+pub fn landed_fees(results: &[SendResult]) -> u64 {
+    let mut seen = HashSet::new();
+    results
+        .iter()
+        .filter(|result| result.landed && seen.insert(result.signature))
+        .map(|result| result.fees.total())
+        .sum()
+}
+
+/// Compile the message in the requested format.
+/// Legacy messages cannot reference lookup tables, so that combination is rejected.
+pub fn compile_message(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    blockhash: Hash,
+    version: TransactionVersion,
+) -> anyhow::Result<VersionedMessage> {
+    match version {
+        TransactionVersion::V0 => {
+            check_lookup_table_count(address_lookup_table_accounts.len())?;
+            let message =
+                v0::Message::try_compile(payer, instructions, address_lookup_table_accounts, blockhash)?;
+            let addresses = message.account_keys.len()
+                + message
+                    .address_table_lookups
+                    .iter()
+                    .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+                    .sum::<usize>();
+            if addresses > MAX_TRANSACTION_ACCOUNTS {
+                return Err(BotError::LookupTableAddressLimitExceeded {
+                    addresses,
+                    max: MAX_TRANSACTION_ACCOUNTS,
+                }
+                .into());
+            }
+            Ok(VersionedMessage::V0(message))
+        }
+        TransactionVersion::Legacy => {
+            if !address_lookup_table_accounts.is_empty() {
+                return Err(BotError::TransactionBuildError(
+                    "legacy transactions cannot use address lookup tables".to_string(),
+                )
+                .into());
+            }
+            Ok(VersionedMessage::Legacy(LegacyMessage::new_with_blockhash(
+                instructions,
+                Some(payer),
+                &blockhash,
+            )))
+        }
+    }
+}
+
+/// Reject more lookup tables than a transaction can use, before anything is built
+pub fn check_lookup_table_count(tables: usize) -> Result<(), BotError> {
+    if tables > MAX_LOOKUP_TABLES_PER_TRANSACTION {
+        return Err(BotError::LookupTableLimitExceeded {
+            tables,
+            max: MAX_LOOKUP_TABLES_PER_TRANSACTION,
+        });
+    }
+    Ok(())
+}
+
+/// Profit report the executor program leaves with `set_return_data`: the profit in
+/// lamports as a little-endian u64, then the number of swaps in the route as a u8
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutorResult {
+    pub profit_lamports: u64,
+    pub route_len: u8,
+}
+
+const EXECUTOR_RESULT_LEN: usize = 9;
+
+impl ExecutorResult {
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() != EXECUTOR_RESULT_LEN {
+            return None;
+        }
+        Some(Self {
+            profit_lamports: u64::from_le_bytes(data[..8].try_into().ok()?),
+            route_len: data[8],
+        })
+    }
+
+    /// Decode a simulation's or confirmed transaction's return data, if the executor
+    /// program set it
+    pub fn from_return_data(
+        return_data: &UiTransactionReturnData,
+        executor_program_id: &Pubkey,
+    ) -> Option<Self> {
+        if return_data.program_id != executor_program_id.to_string() {
+            return None;
+        }
+        let (data, UiReturnDataEncoding::Base64) = &return_data.data;
+        let bytes = base64::engine::general_purpose::STANDARD.decode(data).ok()?;
+        Self::decode(&bytes)
+    }
+}
+
+/// Lamports paid for requesting `compute_unit_limit` units at `compute_unit_price`
+/// micro-lamports per unit, rounded up like the runtime does
+pub fn priority_fee_lamports(compute_unit_limit: u32, compute_unit_price: u64) -> u64 {
+    let micro_lamports = compute_unit_limit as u128 * compute_unit_price as u128;
+    let lamports = micro_lamports.div_ceil(MICRO_LAMPORTS_PER_LAMPORT as u128);
+    lamports.min(u64::MAX as u128) as u64
+}
+
+/// `spam.compute_unit_price`, or the default without a spam config
+fn configured_compute_unit_price(config: &Config) -> u64 {
+    config
+        .spam
+        .as_ref()
+        .map_or(DEFAULT_COMPUTE_UNIT_PRICE, |s| s.compute_unit_price)
+}
+
+/// Fees the route's transaction pays if it lands, estimated before it is built: one
+/// signature, the route's compute unit limit at the configured price and the Jito tip
+/// when `via_jito`
+pub fn estimated_fees(config: &Config, mint_pool_data: &MintPoolData, via_jito: bool) -> FeeBreakdown {
+    let (compute_unit_limit, _) = route_compute_unit_limit(config, mint_pool_data);
+    let compute_unit_price = config.bot.clamp_compute_unit_price(configured_compute_unit_price(config));
+    FeeBreakdown {
+        base_fee_lamports: BASE_FEE_LAMPORTS_PER_SIGNATURE,
+        priority_fee_lamports: priority_fee_lamports(compute_unit_limit, compute_unit_price),
+        tip_lamports: if via_jito { JITO_TIP_LAMPORTS } else { 0 },
+        protocol_fee_lamports: 0,
+    }
+}
+
+/// Create the wSOL account if needed, move `lamports` of native SOL into it and sync
+/// its token balance
+pub fn wrap_sol_instructions(
+    wallet: &Pubkey,
+    wsol_account: &Pubkey,
+    lamports: u64,
+) -> anyhow::Result<Vec<Instruction>> {
+    Ok(vec![
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            wallet,
+            wallet,
+            &spl_token::native_mint::ID,
+            &token_program_id,
+        ),
+        system_instruction::transfer(wallet, wsol_account, lamports),
+        spl_token::instruction::sync_native(&token_program_id, wsol_account)?,
+    ])
+}
+
+/// Close the wSOL account, returning its balance and rent to the wallet as native SOL
+pub fn unwrap_sol_instruction(wallet: &Pubkey, wsol_account: &Pubkey) -> anyhow::Result<Instruction> {
+    Ok(spl_token::instruction::close_account(
+        &token_program_id,
+        wsol_account,
+        wallet,
+        wallet,
+        &[],
+    )?)
+}
+
+/// The wSOL wrap and unwrap instructions every transaction carries when auto-wrapping
+pub fn wrap_overhead_instructions(
     config: &Config,
+    wallet: &Pubkey,
     mint_pool_data: &MintPoolData,
-    rpc_clients: &[Arc<RpcClient>],
-    blockhash: Hash,
-    address_lookup_table_accounts: &[AddressLookupTableAccount],
-    jito_client: Option<&JitoClient>,
-) -> anyhow::Result<Vec<Signature>> {
+) -> anyhow::Result<Vec<Instruction>> {
+    let Some(lamports) = config.bot.wrap_sol_lamports() else {
+        return Ok(Vec::new());
+    };
+    let mut instructions =
+        wrap_sol_instructions(wallet, &mint_pool_data.wallet_wsol_account, lamports)?;
+    instructions.push(unwrap_sol_instruction(wallet, &mint_pool_data.wallet_wsol_account)?);
+    Ok(instructions)
+}
+
+/// Lamports to wrap for a route quoted at `input_amount`: the input itself, or
+/// `wrap_sol_lamports` for a route sized on-chain (0). None when auto-wrapping is off.
+pub fn wrap_amount(config: &Config, input_amount: u64) -> Option<u64> {
+    config
+        .bot
+        .wrap_sol_lamports()
+        .map(|configured| if input_amount > 0 { input_amount } else { configured })
+}
+
+/// Compute budget, optional wSOL wrap of `wrap_lamports`, swap, optional Jito tip and the
+/// closing unwrap, in that order
+fn build_instructions(
+    wallet: &dyn Signer,
+    config: &Config,
+    mint_pool_data: &MintPoolData,
+    tip_account: Option<Pubkey>,
+    compute_unit_limit: u32,
+    estimated_compute_unit_price: Option<u64>,
+    wrap_lamports: Option<u64>,
+) -> anyhow::Result<Vec<Instruction>> {
     let enable_flashloan = config.flashloan.as_ref().map_or(false, |k| k.enabled);
-    let compute_unit_limit = config.bot.compute_unit_limit;
     let mut instructions = vec![];
     // Add a random number here to make each transaction unique
     let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(
@@ -60,69 +320,230 @@ pub async fn build_and_send_transaction(
     );
     instructions.push(compute_budget_ix);
 
-    let compute_unit_price = config
-        .spam
-        .as_ref()
-        .map_or(DEFAULT_COMPUTE_UNIT_PRICE, |s| s.compute_unit_price);
+    let configured_price =
+        estimated_compute_unit_price.unwrap_or_else(|| configured_compute_unit_price(config));
+    let compute_unit_price = config.bot.clamp_compute_unit_price(configured_price);
+    if compute_unit_price > configured_price {
+        debug!(
+            "Raising compute unit price from {} to the {} floor",
+            configured_price, compute_unit_price
+        );
+        METRICS.inc_compute_unit_price_floor_applied();
+    }
     let compute_budget_price_ix =
         ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price);
     instructions.push(compute_budget_price_ix);
 
+    // Fund the wSOL account from native SOL when auto-wrapping
+    let unwrap_ix = match wrap_lamports {
+        Some(lamports) => {
+            debug!("Wrapping {} lamports into {}", lamports, mint_pool_data.wallet_wsol_account);
+            instructions.extend(wrap_sol_instructions(
+                &wallet.pubkey(),
+                &mint_pool_data.wallet_wsol_account,
+                lamports,
+            )?);
+            Some(unwrap_sol_instruction(
+                &wallet.pubkey(),
+                &mint_pool_data.wallet_wsol_account,
+            )?)
+        }
+        None => None,
+    };
+
+    let minimum_profit = config
+        .mint_config(&mint_pool_data.mint)
+        .map_or(MINIMUM_PROFIT_DEFAULT, |mint_config| mint_config.min_profit_lamports());
     let swap_ix = create_swap_instruction(
-        wallet_kp,
+        wallet,
         mint_pool_data,
         compute_unit_limit as u64,
+        minimum_profit,
         enable_flashloan,
+        config.executor_program_id(),
+        config.fee_collector(),
+        &config.executor_static_accounts(),
     )?;
 
     instructions.push(swap_ix);
 
     // Jito Logic: Append Tip if client is present
-    if let Some(jito_client) = jito_client {
+    if let Some(tip_account) = tip_account {
         debug!("Adding Jito tip instruction");
-        let tip_account = jito_client.get_random_tip_account();
-        let tip_amount = 100_000; // 0.0001 SOL tip (configurable in future)
         let tip_ix = system_instruction::transfer(
-            &wallet_kp.pubkey(),
+            &wallet.pubkey(),
             &tip_account,
-            tip_amount,
+            JITO_TIP_LAMPORTS,
         );
         instructions.push(tip_ix);
     }
 
-    let message = Message::try_compile(
-        &wallet_kp.pubkey(),
-        &instructions,
-        address_lookup_table_accounts,
-        blockhash,
-    )?;
+    // Closing the wSOL account must come last so the proceeds and rent return as SOL
+    if let Some(unwrap_ix) = unwrap_ix {
+        instructions.push(unwrap_ix);
+    }
 
-    let tx = VersionedTransaction::try_new(
-        solana_sdk::message::VersionedMessage::V0(message),
-        &[wallet_kp],
-    )?;
+    Ok(instructions)
+}
+
+/// Compute-unit price from the fees recently paid to write the route's accounts, when
+/// `spam.priority_fee_percentile` is set. Split routes pass every group's pools, so all
+/// their transactions pay the same price.
+pub async fn estimate_compute_unit_price(
+    config: &Config,
+    fee_source: &dyn PriorityFeeSource,
+    mint_pool_data: &MintPoolData,
+    wallet: &Pubkey,
+) -> Option<u64> {
+    let percentile = config.spam.as_ref()?.priority_fee_percentile?;
+    PRIORITY_FEES
+        .estimate(fee_source, &writable_pool_accounts(mint_pool_data, wallet), percentile)
+        .await
+}
+
+/// Build, sign and send the route's transaction. `estimated_compute_unit_price` comes
+/// from `estimate_compute_unit_price`; None uses `spam.compute_unit_price`. A route quoted
+/// at `input_amount` wraps that much SOL when auto-wrapping; 0 is a route sized on-chain.
+pub async fn build_and_send_transaction(
+    wallet: &WalletSigner,
+    config: &Config,
+    mint_pool_data: &MintPoolData,
+    rpc_clients: &[Arc<RpcClient>],
+    blockhash: Hash,
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    jito_client: Option<&JitoClient>,
+    estimated_compute_unit_price: Option<u64>,
+    input_amount: u64,
+) -> anyhow::Result<Vec<SendResult>> {
+    // Only a bundle pays the tip; a send over RPC goes out without the tip transfer
+    let tip_account = jito_client
+        .filter(|jito_client| !jito_client.is_backing_off())
+        .map(|jito_client| jito_client.get_random_tip_account());
+    let wrap_lamports = wrap_amount(config, input_amount);
+
+    let transaction_version = config.bot.transaction_version();
+    let sign = |instructions: &[Instruction], blockhash: Hash| {
+        sign_transaction(
+            wallet.as_ref(),
+            instructions,
+            address_lookup_table_accounts,
+            blockhash,
+            transaction_version,
+        )
+    };
+
+    let (mut compute_unit_limit, estimated) = route_compute_unit_limit(config, mint_pool_data);
+    let mut simulated_profit = None;
+    if config.bot.simulate_before_send() {
+        if let Some(client) = rpc_clients.first() {
+            // Simulate at the cap so the units the route really needs are measured
+            let simulation_instructions = build_instructions(
+                wallet.as_ref(),
+                config,
+                mint_pool_data,
+                tip_account,
+                MAX_COMPUTE_UNIT_LIMIT,
+                estimated_compute_unit_price,
+                wrap_lamports,
+            )?;
+            let (units_consumed, executor_result) = simulate_and_validate(
+                client,
+                &sign(&simulation_instructions, blockhash)?,
+                &pool_candidates(mint_pool_data, &wallet.pubkey()),
+                &config.executor_program_id(),
+            )?;
+            if let Some(executor_result) = executor_result {
+                debug!(
+                    "Executor reports {} lamports profit over {} swaps",
+                    executor_result.profit_lamports, executor_result.route_len
+                );
+                simulated_profit = Some(executor_result.profit_lamports);
+            }
+            if let Some(units_consumed) = units_consumed {
+                compute_unit_limit = if estimated {
+                    refined_compute_unit_limit(units_consumed, compute_unit_limit)?
+                } else {
+                    required_compute_unit_limit(
+                        units_consumed,
+                        compute_unit_limit,
+                        config.bot.auto_raise_compute_unit_limit(),
+                    )?
+                };
+            }
+        }
+    }
+
+    // The transaction paying `tip_account`, its fees and a builder re-signing it
+    let prepare = |tip_account: Option<Pubkey>| {
+        let instructions = build_instructions(
+            wallet.as_ref(),
+            config,
+            mint_pool_data,
+            tip_account,
+            compute_unit_limit,
+            estimated_compute_unit_price,
+            wrap_lamports,
+        )?;
+        let tx = sign(&instructions, blockhash)?;
+        tx_dump::dump_if_enabled(&mint_pool_data.mint, &tx, address_lookup_table_accounts);
+        let fees = FeeBreakdown::of_transaction(&tx, tip_account.map_or(0, |_| JITO_TIP_LAMPORTS));
+        // Owned, so endpoints racing under the "fastest" strategy can rebuild on their own threads
+        let build_tx: TransactionBuilder = {
+            let wallet = wallet.clone();
+            let lookup_tables = address_lookup_table_accounts.to_vec();
+            Arc::new(move |blockhash| {
+                sign_transaction(
+                    wallet.as_ref(),
+                    &instructions,
+                    &lookup_tables,
+                    blockhash,
+                    transaction_version,
+                )
+            })
+        };
+        anyhow::Ok((tx, fees, build_tx))
+    };
 
     // Submit via Jito if enabled
     if let Some(jito_client) = jito_client {
-        match jito_client.send_bundle(vec![tx.clone()]).await {
-            Ok(sig_str) => {
-                info!("Bundle sent via Jito! Signature: {}", sig_str);
-                // Jito returns a signature string, usually we trust it.
-                // We can return it as a Signature object.
-                if let Ok(sig) = Signature::from_str(&sig_str) {
-                    return Ok(vec![sig]);
-                } else {
-                    // Fallback if signature parsing fails (Jito might return ID)
-                    info!("Jito returned ID: {}. Returning zero signature for logs.", sig_str);
-                    return Ok(vec![Signature::default()]); 
+        if jito_client.is_backing_off() {
+            debug!("Jito is backing off after a rate limit, sending via RPC");
+        } else {
+            let (tx, fees, _) = prepare(tip_account)?;
+            match send_bundle_with_recovery(jito_client, std::slice::from_ref(&tx)).await {
+                Ok(bundle_id) => {
+                    // Jito returns a bundle ID; the transaction signature is already known locally
+                    let signature = tx.signatures.first().copied().unwrap_or_default();
+                    info!("Bundle {} sent via Jito! Signature: {}", bundle_id, signature);
+                    return Ok(vec![SendResult {
+                        signature,
+                        endpoint: jito_client.block_engine_url(),
+                        simulated_profit,
+                        landed: false,
+                        realized_profit: None,
+                        fees,
+                    }]);
+                }
+                Err(BotError::JitoError(e)) if e.action() == JitoRetryAction::GiveUp => {
+                    // The same transaction would fail through RPC too
+                    warn!("Jito rejected the bundle, not resending: {}", e);
+                    return Ok(Vec::new());
+                }
+                Err(e) => {
+                    error!("Jito bundle submission failed: {}. Falling back to RPC.", e);
+                    // Fallback to standard RPC below
                 }
-            }
-            Err(e) => {
-                error!("Jito bundle submission failed: {}. Falling back to RPC.", e);
-                // Fallback to standard RPC below
             }
         }
     }
+    let (tx, fees, build_tx) = prepare(None)?;
+
+    // A rebuilt route can come out identical; the same signature is only sent once
+    let signature = tx.signatures.first().copied().unwrap_or_default();
+    if !RECENT_SIGNATURES.remember(signature, Instant::now()) {
+        info!("Transaction {} was already sent, not sending it again", signature);
+        return Ok(Vec::new());
+    }
 
     let max_retries = config
         .spam
@@ -130,238 +551,2924 @@ pub async fn build_and_send_transaction(
         .and_then(|s| s.max_retries)
         .unwrap_or(DEFAULT_MAX_RETRIES);
 
-    let mut signatures = Vec::new();
+    let account_in_use_retries = config
+        .spam
+        .as_ref()
+        .map_or(DEFAULT_ACCOUNT_IN_USE_RETRIES, |spam| spam.account_in_use_retries());
+
+    let strategy = config
+        .spam
+        .as_ref()
+        .map_or(SendStrategy::Broadcast, |spam| spam.strategy());
+    let sender_blockhashes: Vec<Hash> = match config.spam.as_ref().map(|spam| spam.blockhash_source()) {
+        Some(BlockhashSource::Sender) => rpc_clients
+            .iter()
+            .map(|client| SENDER_BLOCKHASHES.blockhash(&client.url()).unwrap_or(blockhash))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let mut results: Vec<SendResult> = dispatch_transaction(
+        strategy,
+        rpc_clients,
+        &build_tx,
+        tx,
+        blockhash,
+        &sender_blockhashes,
+        max_retries,
+        account_in_use_retries,
+    )
+    .await
+    .into_iter()
+    .map(|(i, signature)| SendResult {
+        signature,
+        endpoint: rpc_clients[i].url(),
+        simulated_profit,
+        landed: false,
+        realized_profit: None,
+        fees,
+    })
+    .collect();
+    // Endpoints with their own blockhash, and retries after an expired one, re-sign
+    for result in &results {
+        RECENT_SIGNATURES.remember(result.signature, Instant::now());
+    }
 
-    for (i, client) in rpc_clients.iter().enumerate() {
-        debug!("Sending transaction through RPC client {}", i);
+    let spam_enabled = config.spam.as_ref().map_or(false, |spam| spam.enabled);
 
-        let signature = match send_transaction_with_retries(client, &tx, max_retries).await {
-            Ok(sig) => sig,
-            Err(e) => {
-                error!("Failed to send transaction through RPC client {}: {}", i, e);
-                continue;
+    if config.bot.resend_dropped() {
+        if let Some(result) = results.first_mut() {
+            if let Some(client) = rpc_clients.iter().find(|client| client.url() == result.endpoint) {
+                let (resend_client, build_tx, signature) = (client.clone(), build_tx.clone(), result.signature);
+                let outcome = on_blocking_pool(async move {
+                    let rebuild = |blockhash: Hash| build_tx(blockhash);
+                    resend_until_confirmed(
+                        resend_client.as_ref(),
+                        &rebuild,
+                        signature,
+                        MAX_DROPPED_RESENDS,
+                        Duration::from_millis(RESEND_CONFIRM_WINDOW_MS),
+                        Duration::from_millis(RESEND_POLL_INTERVAL_MS),
+                        max_retries,
+                    )
+                    .await
+                })
+                .await
+                .and_then(|outcome| outcome);
+                match outcome {
+                    Ok(ResendOutcome::Confirmed(signature)) => {
+                        METRICS.inc_tx_confirmed();
+                        RECENT_SIGNATURES.mark_resolved(&result.signature);
+                        RECENT_SIGNATURES.mark_resolved(&signature);
+                        result.signature = signature;
+                        result.landed = true;
+                        if config.bot.reconcile_profit() {
+                            reconcile_on_blocking_pool(client, result, wallet.pubkey(), config.executor_program_id())
+                                .await;
+                        }
+                    }
+                    Ok(ResendOutcome::OpportunityGone) => {
+                        info!("Opportunity no longer profitable, cancelled resends")
+                    }
+                    Ok(ResendOutcome::Exhausted) => {
+                        warn!("Transaction not confirmed after {} resends", MAX_DROPPED_RESENDS)
+                    }
+                    Err(e) => warn!("Failed to track transaction for resend: {}", e),
+                }
             }
-        };
-
-        info!(
-            "Transaction sent successfully through RPC client {}: {}",
-            i, signature
+        }
+    } else if let Some(result) = results.first_mut() {
+        let wait = confirmation_wait(
+            config.bot.confirmation_mode(),
+            spam_enabled,
+            config.bot.confirm_commitment(),
         );
-        signatures.push(signature);
+        METRICS.inc_send_confirmation_wait(confirmation_wait_label(wait));
+        if let Some(client) = rpc_clients.iter().find(|client| client.url() == result.endpoint) {
+            let timeout = config.bot.confirm_timeout();
+            let (poll_client, signature) = (client.clone(), result.signature);
+            let confirmation = on_blocking_pool(async move {
+                await_confirmation(poll_client.as_ref(), &signature, wait, timeout).await
+            })
+            .await?;
+            if let Some(confirmation) = confirmation {
+                match confirmation {
+                    Ok(slot) => {
+                        info!("Transaction {} landed in slot {}", result.signature, slot);
+                        result.landed = true;
+                        METRICS.inc_tx_confirmed();
+                        if config.bot.reconcile_profit() {
+                            reconcile_on_blocking_pool(client, result, wallet.pubkey(), config.executor_program_id())
+                                .await;
+                        }
+                    }
+                    Err(e) => {
+                        warn!("{}", e);
+                        METRICS.inc_tx_failed();
+                    }
+                }
+                // Counted either way, so the background poll leaves it alone
+                RECENT_SIGNATURES.mark_resolved(&result.signature);
+            }
+        }
     }
 
-    Ok(signatures)
+    Ok(results)
 }
 
-async fn send_transaction_with_retries(
-    client: &RpcClient,
-    tx: &VersionedTransaction,
-    max_retries: u64,
-) -> anyhow::Result<Signature> {
-    Ok(client.send_transaction_with_config(
-        tx,
-        solana_client::rpc_config::RpcSendTransactionConfig {
-            skip_preflight: true,
-            max_retries: Some(max_retries as usize),
-            preflight_commitment: Some(CommitmentLevel::Confirmed),
-            ..Default::default()
-        },
-    )?)
+/// Sign one transaction per pool group. Only the last transaction of each Jito bundle
+/// pays the tip.
+pub fn build_split_transactions(
+    wallet: &dyn Signer,
+    config: &Config,
+    groups: &[MintPoolData],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    blockhash: Hash,
+    tip_account: Option<Pubkey>,
+    estimated_compute_unit_price: Option<u64>,
+) -> anyhow::Result<Vec<VersionedTransaction>> {
+    groups
+        .iter()
+        .enumerate()
+        .map(|(index, group)| {
+            let ends_bundle =
+                (index + 1) % MAX_JITO_BUNDLE_TRANSACTIONS == 0 || index + 1 == groups.len();
+            let instructions = build_instructions(
+                wallet,
+                config,
+                group,
+                tip_account.filter(|_| ends_bundle),
+                route_compute_unit_limit(config, group).0,
+                estimated_compute_unit_price,
+                config.bot.wrap_sol_lamports(),
+            )?;
+            sign_transaction(
+                wallet,
+                &instructions,
+                address_lookup_table_accounts,
+                blockhash,
+                config.bot.transaction_version(),
+            )
+        })
+        .collect()
 }
 
-/// Helper function to derive the vault token account PDA address for a given mint
-pub fn derive_vault_token_account(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
-    Pubkey::find_program_address(&[b"vault_token_account", mint.as_ref()], program_id)
+/// Send one transaction per pool group (see `bot.max_pools_per_tx`)
+///
+/// Through Jito the groups go out together, up to five per bundle, so each bundle lands
+/// all-or-nothing. Over RPC, or when a bundle fails, every group is sent and confirmed on
+/// its own like a single-transaction route.
+pub async fn build_and_send_split_transactions(
+    wallet: &WalletSigner,
+    config: &Config,
+    groups: &[MintPoolData],
+    rpc_clients: &[Arc<RpcClient>],
+    blockhash: Hash,
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    jito_client: Option<&JitoClient>,
+    estimated_compute_unit_price: Option<u64>,
+) -> anyhow::Result<Vec<SendResult>> {
+    let mut results = Vec::new();
+    let mut rpc_groups: Vec<&MintPoolData> = Vec::new();
+
+    match jito_client {
+        Some(jito_client) if !jito_client.is_backing_off() => {
+            let transactions = build_split_transactions(
+                wallet.as_ref(),
+                config,
+                groups,
+                address_lookup_table_accounts,
+                blockhash,
+                Some(jito_client.get_random_tip_account()),
+                estimated_compute_unit_price,
+            )?;
+            for (bundle, bundle_groups) in transactions
+                .chunks(MAX_JITO_BUNDLE_TRANSACTIONS)
+                .zip(groups.chunks(MAX_JITO_BUNDLE_TRANSACTIONS))
+            {
+                match send_bundle_with_recovery(jito_client, bundle).await {
+                    Ok(bundle_id) => {
+                        info!(
+                            "Bundle {} of {} split transactions sent via Jito",
+                            bundle_id,
+                            bundle.len()
+                        );
+                        // The bundle's last transaction carries the tip
+                        results.extend(bundle.iter().enumerate().map(|(index, tx)| SendResult {
+                            signature: tx.signatures.first().copied().unwrap_or_default(),
+                            endpoint: jito_client.block_engine_url(),
+                            simulated_profit: None,
+                            landed: false,
+                            realized_profit: None,
+                            fees: FeeBreakdown::of_transaction(
+                                tx,
+                                if index + 1 == bundle.len() { JITO_TIP_LAMPORTS } else { 0 },
+                            ),
+                        }));
+                    }
+                    Err(BotError::JitoError(e)) if e.action() == JitoRetryAction::GiveUp => {
+                        warn!("Jito rejected the split bundle, not resending: {}", e);
+                    }
+                    Err(e) => {
+                        error!("Jito split bundle submission failed: {}. Falling back to RPC.", e);
+                        rpc_groups.extend(bundle_groups);
+                    }
+                }
+            }
+        }
+        Some(_) => {
+            debug!("Jito is backing off after a rate limit, sending split transactions via RPC");
+            rpc_groups.extend(groups);
+        }
+        None => rpc_groups.extend(groups),
+    }
+
+    let sends = rpc_groups.into_iter().map(|group| {
+        build_and_send_transaction(
+            wallet,
+            config,
+            group,
+            rpc_clients,
+            blockhash,
+            address_lookup_table_accounts,
+            None,
+            estimated_compute_unit_price,
+            // Each group is sized on-chain
+            0,
+        )
+    });
+    for outcome in futures::future::join_all(sends).await {
+        match outcome {
+            Ok(group_results) => results.extend(group_results),
+            Err(e) => error!("Split transaction failed: {}", e),
+        }
+    }
+
+    Ok(results)
 }
 
-// See https://docs.solanamevbot.com/home/onchain-bot/onchain-program for more information
-fn create_swap_instruction(
-    wallet_kp: &Keypair,
-    mint_pool_data: &MintPoolData,
-    compute_unit_limit: u64,
-    use_flashloan: bool,
-) -> anyhow::Result<Instruction> {
-    debug!("Creating swap instruction for all DEX types");
+/// Submit `transactions` as one bundle, letting the client apply the failure's
+/// strategy (backoff, region failover) and re-authenticating and resending once if
+/// the auth token expired
+async fn send_bundle_with_recovery(
+    jito_client: &JitoClient,
+    transactions: &[VersionedTransaction],
+) -> Result<String, BotError> {
+    let error = match jito_client.send_bundle(transactions.to_vec()).await {
+        Err(BotError::JitoError(error)) => error,
+        other => return other,
+    };
+    if jito_client.handle_failure(&error) != JitoRetryAction::Reauthenticate {
+        return Err(error.into());
+    }
 
-    let executor_program_id = *EXECUTOR_PROGRAM_PUBKEY;
-    let fee_collector = *FEE_COLLECTOR_PUBKEY;
+    jito_client.reauthenticate().await?;
+    match jito_client.send_bundle(transactions.to_vec()).await {
+        Err(BotError::JitoError(error)) => {
+            jito_client.handle_failure(&error);
+            Err(error.into())
+        }
+        other => other,
+    }
+}
 
-    let pump_global_config = *PUMP_GLOBAL_CONFIG_PUBKEY;
-    let pump_authority = *PUMP_AUTHORITY_PUBKEY;
-    let sysvar_instructions = *SYSVAR_INSTRUCTIONS_PUBKEY;
+/// Simulate `tx` and fail if it would not execute, returning the compute units it consumed
+/// and the executor's profit report. Writability errors are mapped to the pool whose swap
+/// accounts are misflagged.
+fn simulate_and_validate(
+    client: &RpcClient,
+    tx: &VersionedTransaction,
+    candidates: &[PoolCandidate],
+    executor_program_id: &Pubkey,
+) -> anyhow::Result<(Option<u64>, Option<ExecutorResult>)> {
+    let simulation = client.simulate_transaction(tx)?.value;
+    let err = match simulation.err {
+        Some(err) => err,
+        None => {
+            let executor_result = simulation.return_data.as_ref().and_then(|return_data| {
+                ExecutorResult::from_return_data(return_data, executor_program_id)
+            });
+            return Ok((simulation.units_consumed, executor_result));
+        }
+    };
 
-    let wallet = wallet_kp.pubkey();
-    let sol_mint_pubkey = sol_mint();
-    let wallet_sol_account = mint_pool_data.wallet_wsol_account;
+    let logs = simulation.logs.unwrap_or_default();
+    if let Some(validation_error) = diagnose_account_flag_error(&err, &logs, candidates) {
+        return Err(validation_error.into());
+    }
 
-    let mut accounts = vec![
-        AccountMeta::new_readonly(wallet, true), // 0. Wallet (signer)
-        AccountMeta::new_readonly(sol_mint_pubkey, false), // 1. SOL mint
-        AccountMeta::new(fee_collector, false),  // 2. Fee collector
-        AccountMeta::new(wallet_sol_account, false), // 3. Wallet SOL account
-        AccountMeta::new_readonly(token_program_id, false), // 4. Token program
-        AccountMeta::new_readonly(system_program::ID, false), // 5. System program
-        AccountMeta::new_readonly(associated_token_program_id, false), // 6. Associated Token program
-    ];
+    Err(BotError::TransactionBuildError(format!("Simulation failed: {}", err)).into())
+}
 
-    let base_mint = sol_mint_pubkey;
+/// Transaction swapping through `pool` alone, with no lookup tables, for checking in
+/// simulation that the DEX program accepts the pool's swap accounts
+pub fn single_pool_transaction(
+    wallet: &dyn Signer,
+    config: &Config,
+    mint_pool_data: &MintPoolData,
+    pool: &Pubkey,
+    blockhash: Hash,
+) -> anyhow::Result<VersionedTransaction> {
+    let mut single_pool = mint_pool_data.clone();
+    single_pool.retain_pools(&HashSet::from([*pool]));
+    let instructions = build_instructions(
+        wallet,
+        config,
+        &single_pool,
+        None,
+        MAX_COMPUTE_UNIT_LIMIT,
+        None,
+        config.bot.wrap_sol_lamports(),
+    )?;
+    sign_transaction(wallet, &instructions, &[], blockhash, config.bot.transaction_version())
+}
 
-    if use_flashloan {
-        accounts.push(AccountMeta::new_readonly(
-            *KAMINO_LENDING_PROGRAM_PUBKEY,
-            false,
-        ));
-        let token_pda = derive_vault_token_account(&executor_program_id, &base_mint);
-        accounts.push(AccountMeta::new(token_pda.0, false));
+/// Compute unit limit to request for a route that simulated at `units_consumed`:
+/// `configured` when it suffices, otherwise the consumed units if `auto_raise` allows it
+/// and they fit under Solana's cap, else `ComputeBudgetExceeded`
+pub fn required_compute_unit_limit(
+    units_consumed: u64,
+    configured: u32,
+    auto_raise: bool,
+) -> Result<u32, BotError> {
+    if units_consumed <= configured as u64 {
+        return Ok(configured);
     }
 
-    accounts.push(AccountMeta::new_readonly(mint_pool_data.mint, false));
-    accounts.push(AccountMeta::new_readonly(
-        mint_pool_data.token_program,
-        false,
-    )); // Token program (SPL Token or Token 2022)
-    let wallet_x_account =
-        spl_associated_token_account::get_associated_token_address_with_program_id(
-            &wallet,
-            &mint_pool_data.mint,
-            &mint_pool_data.token_program,
+    if auto_raise && units_consumed <= MAX_COMPUTE_UNIT_LIMIT as u64 {
+        warn!(
+            "Route needs {} compute units, raising compute_unit_limit {} for this transaction; \
+             consider raising compute_unit_limit to at least {}",
+            units_consumed, configured, units_consumed
         );
-    accounts.push(AccountMeta::new(wallet_x_account, false));
+        return Ok(units_consumed as u32);
+    }
 
-    for pool in &mint_pool_data.raydium_pools {
-        accounts.push(AccountMeta::new_readonly(raydium_program_id(), false));
-        accounts.push(AccountMeta::new_readonly(raydium_authority(), false)); // Raydium authority
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new(pool.token_vault, false));
-        accounts.push(AccountMeta::new(pool.sol_vault, false));
-    }
-
-    for pool in &mint_pool_data.raydium_cp_pools {
-        accounts.push(AccountMeta::new_readonly(raydium_cp_program_id(), false));
-        accounts.push(AccountMeta::new_readonly(raydium_cp_authority(), false)); // Raydium CP authority
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new_readonly(pool.amm_config, false));
-        accounts.push(AccountMeta::new(pool.token_vault, false));
-        accounts.push(AccountMeta::new(pool.sol_vault, false));
-        accounts.push(AccountMeta::new(pool.observation, false));
-    }
-
-    for pool in &mint_pool_data.pump_pools {
-        accounts.push(AccountMeta::new_readonly(pump_program_id(), false));
-        accounts.push(AccountMeta::new_readonly(pump_global_config, false));
-        accounts.push(AccountMeta::new_readonly(pump_authority, false));
-        accounts.push(AccountMeta::new_readonly(pump_fee_wallet(), false));
-        accounts.push(AccountMeta::new_readonly(pool.pool, false));
-        accounts.push(AccountMeta::new(pool.token_vault, false));
-        accounts.push(AccountMeta::new(pool.sol_vault, false));
-        accounts.push(AccountMeta::new(pool.fee_token_wallet, false));
-        accounts.push(AccountMeta::new(pool.coin_creator_vault_ata, false));
-        accounts.push(AccountMeta::new_readonly(
-            pool.coin_creator_vault_authority,
-            false,
-        ));
+    if units_consumed > MAX_COMPUTE_UNIT_LIMIT as u64 {
+        warn!(
+            "Route needs {} compute units, more than the {} a transaction may use; \
+             remove pools from this mint",
+            units_consumed, MAX_COMPUTE_UNIT_LIMIT
+        );
+    } else {
+        warn!(
+            "Route needs {} compute units but compute_unit_limit is {}; raise compute_unit_limit \
+             or enable auto_raise_compute_unit_limit",
+            units_consumed, configured
+        );
     }
+    Err(BotError::ComputeBudgetExceeded {
+        needed: units_consumed,
+        configured,
+    })
+}
 
-    for pair in &mint_pool_data.dlmm_pairs {
-        accounts.push(AccountMeta::new_readonly(dlmm_program_id(), false));
-        accounts.push(AccountMeta::new(dlmm_event_authority(), false)); // DLMM event authority
-        if let Some(memo_program) = pair.memo_program {
-            accounts.push(AccountMeta::new_readonly(memo_program, false)); // Token 2022 memo program
-        }
-        accounts.push(AccountMeta::new(pair.pair, false));
-        accounts.push(AccountMeta::new(pair.token_vault, false));
-        accounts.push(AccountMeta::new(pair.sol_vault, false));
-        accounts.push(AccountMeta::new(pair.oracle, false));
-        for bin_array in &pair.bin_arrays {
-            accounts.push(AccountMeta::new(*bin_array, false));
-        }
+/// Map an "account not writable"/"privilege escalation" simulation failure to a
+/// `PoolValidationError` naming the pool and DEX that owns the offending account.
+pub fn diagnose_account_flag_error(
+    err: &TransactionError,
+    logs: &[String],
+    candidates: &[PoolCandidate],
+) -> Option<BotError> {
+    let flag_error = matches!(
+        err,
+        TransactionError::InstructionError(
+            _,
+            InstructionError::PrivilegeEscalation
+                | InstructionError::ReadonlyDataModified
+                | InstructionError::ReadonlyLamportChange
+                | InstructionError::ExternalAccountDataModified
+        )
+    );
+    let flag_logs: Vec<&String> = logs
+        .iter()
+        .filter(|line| {
+            let line = line.to_lowercase();
+            line.contains("privilege escalated") || line.contains("not writable")
+        })
+        .collect();
+    if !flag_error && flag_logs.is_empty() {
+        return None;
     }
 
-    for pool in &mint_pool_data.whirlpool_pools {
-        accounts.push(AccountMeta::new_readonly(whirlpool_program_id(), false));
-        if let Some(memo_program) = pool.memo_program {
-            accounts.push(AccountMeta::new_readonly(memo_program, false)); // Token 2022 memo program
-        }
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new(pool.oracle, false));
-        accounts.push(AccountMeta::new(pool.x_vault, false));
-        accounts.push(AccountMeta::new(pool.y_vault, false));
-        for tick_array in &pool.tick_arrays {
-            accounts.push(AccountMeta::new(*tick_array, false));
+    // Log lines name the account, e.g. "<pubkey>'s writable privilege escalated"
+    let offender = flag_logs.iter().find_map(|line| {
+        line.split(|c: char| !c.is_ascii_alphanumeric())
+            .filter_map(|word| word.parse::<Pubkey>().ok())
+            .find_map(|account| {
+                candidates
+                    .iter()
+                    .find(|candidate| candidate.accounts.contains(&account))
+                    .map(|candidate| (candidate, account))
+            })
+    });
+
+    Some(BotError::PoolValidationError(match offender {
+        Some((candidate, account)) => format!(
+            "{} pool {} passes account {} with the wrong writable/signer flag ({})",
+            candidate.dex, candidate.pool, account, err
+        ),
+        None => format!(
+            "a swap account has the wrong writable/signer flag, pool unknown ({})",
+            err
+        ),
+    }))
+}
+
+fn sign_transaction(
+    wallet: &dyn Signer,
+    instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    blockhash: Hash,
+    transaction_version: TransactionVersion,
+) -> anyhow::Result<VersionedTransaction> {
+    let message = compile_message(
+        &wallet.pubkey(),
+        instructions,
+        address_lookup_table_accounts,
+        blockhash,
+        transaction_version,
+    )?;
+    let signers: [&dyn Signer; 1] = [wallet];
+    Ok(VersionedTransaction::try_new(message, &signers)?)
+}
+
+/// Re-signs the transaction for a new blockhash
+type TransactionBuilder = Arc<dyn Fn(Hash) -> anyhow::Result<VersionedTransaction> + Send + Sync>;
+
+lazy_static! {
+    // A couple of failed refreshes can pass before an endpoint falls back to the read RPC
+    static ref SENDER_BLOCKHASHES: SenderBlockhashCache =
+        SenderBlockhashCache::new(Duration::from_secs(3 * DEFAULT_BLOCKHASH_REFRESH_INTERVAL_SECS));
+    static ref PRIORITY_FEES: PriorityFeeEstimator =
+        PriorityFeeEstimator::new(Duration::from_millis(PRIORITY_FEE_CACHE_TTL_MS));
+    static ref RECENT_SIGNATURES: RecentSignatures = RecentSignatures::new(
+        RECENT_SIGNATURES_CAPACITY,
+        Duration::from_secs(RECENT_SIGNATURE_WINDOW_SECS),
+    );
+}
+
+/// One remembered send
+#[derive(Debug, Clone, Copy)]
+struct SentSignature {
+    signature: Signature,
+    sent_at: Instant,
+    /// Landed or failed, so no longer polled
+    resolved: bool,
+}
+
+/// Signatures sent within `window`, at most `capacity` of them with the oldest evicted
+/// first, so the same signed transaction isn't sent twice and spam sends, which nothing
+/// waits on, still get their confirmations polled
+pub struct RecentSignatures {
+    capacity: usize,
+    window: Duration,
+    sent: Mutex<VecDeque<SentSignature>>,
+}
+
+impl RecentSignatures {
+    pub fn new(capacity: usize, window: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            window,
+            sent: Mutex::new(VecDeque::new()),
         }
     }
 
-    for pool in &mint_pool_data.raydium_clmm_pools {
-        accounts.push(AccountMeta::new_readonly(raydium_clmm_program_id(), false));
-        if let Some(memo_program) = pool.memo_program {
-            accounts.push(AccountMeta::new_readonly(memo_program, false)); // Token 2022 memo program
+    /// Remember `signature` as sent at `now`. False if it was already sent within the
+    /// window, in which case it shouldn't be sent again.
+    pub fn remember(&self, signature: Signature, now: Instant) -> bool {
+        let mut sent = self.sent.lock().unwrap();
+        self.expire(&mut sent, now);
+        if sent.iter().any(|entry| entry.signature == signature) {
+            return false;
         }
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new_readonly(pool.amm_config, false));
-        accounts.push(AccountMeta::new(pool.observation_state, false));
-        accounts.push(AccountMeta::new(pool.bitmap_extension, false));
-        accounts.push(AccountMeta::new(pool.x_vault, false));
-        accounts.push(AccountMeta::new(pool.y_vault, false));
-        for tick_array in &pool.tick_arrays {
-            accounts.push(AccountMeta::new(*tick_array, false));
+        if sent.len() >= self.capacity {
+            sent.pop_front();
         }
+        sent.push_back(SentSignature {
+            signature,
+            sent_at: now,
+            resolved: false,
+        });
+        METRICS.set_recent_signatures(sent.len());
+        true
     }
 
-    for pool in &mint_pool_data.meteora_damm_pools {
-        accounts.push(AccountMeta::new_readonly(damm_program_id(), false));
-        accounts.push(AccountMeta::new_readonly(vault_program_id(), false));
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new(pool.token_x_vault, false));
-        accounts.push(AccountMeta::new(pool.token_sol_vault, false));
-        accounts.push(AccountMeta::new(pool.token_x_token_vault, false));
-        accounts.push(AccountMeta::new(pool.token_sol_token_vault, false));
-        accounts.push(AccountMeta::new(pool.token_x_lp_mint, false));
-        accounts.push(AccountMeta::new(pool.token_sol_lp_mint, false));
-        accounts.push(AccountMeta::new(pool.token_x_pool_lp, false));
-        accounts.push(AccountMeta::new(pool.token_sol_pool_lp, false));
-        accounts.push(AccountMeta::new(pool.admin_token_fee_x, false));
-        accounts.push(AccountMeta::new(pool.admin_token_fee_sol, false));
+    pub fn contains(&self, signature: &Signature) -> bool {
+        self.sent.lock().unwrap().iter().any(|entry| entry.signature == *signature)
     }
 
-    for pool in &mint_pool_data.meteora_damm_v2_pools {
-        accounts.push(AccountMeta::new_readonly(damm_v2_program_id(), false));
-        accounts.push(AccountMeta::new_readonly(damm_v2_event_authority(), false));
-        accounts.push(AccountMeta::new_readonly(damm_v2_pool_authority(), false));
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new(pool.token_x_vault, false));
-        accounts.push(AccountMeta::new(pool.token_sol_vault, false));
+    pub fn len(&self) -> usize {
+        self.sent.lock().unwrap().len()
     }
 
-    for pool in &mint_pool_data.solfi_pools {
-        accounts.push(AccountMeta::new_readonly(solfi_program_id(), false));
-        accounts.push(AccountMeta::new_readonly(sysvar_instructions, false));
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new(pool.token_x_vault, false));
-        accounts.push(AccountMeta::new(pool.token_sol_vault, false));
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
-    for pool in &mint_pool_data.vertigo_pools {
-        accounts.push(AccountMeta::new_readonly(vertigo_program_id(), false));
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new_readonly(pool.pool_owner, false));
-        accounts.push(AccountMeta::new(pool.token_x_vault, false));
-        accounts.push(AccountMeta::new(pool.token_sol_vault, false));
+    /// Signatures still in the window at `now` whose outcome isn't known yet
+    pub fn unconfirmed(&self, now: Instant) -> Vec<Signature> {
+        let mut sent = self.sent.lock().unwrap();
+        self.expire(&mut sent, now);
+        sent.iter()
+            .filter(|entry| !entry.resolved)
+            .map(|entry| entry.signature)
+            .collect()
     }
 
-    let mut data = vec![26u8];
-
-    let minimum_profit: u64 = MINIMUM_PROFIT_DEFAULT;
-    let no_failure_mode = NO_FAILURE_MODE_DEFAULT;
+    /// Stop polling `signature` once it landed or failed; it stays remembered so it
+    /// still isn't resent
+    pub fn mark_resolved(&self, signature: &Signature) {
+        if let Some(entry) = self
+            .sent
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|entry| entry.signature == *signature)
+        {
+            entry.resolved = true;
+        }
+    }
 
-    data.extend_from_slice(&minimum_profit.to_le_bytes());
-    data.extend_from_slice(&compute_unit_limit.to_le_bytes());
-    data.extend_from_slice(if no_failure_mode { &[1] } else { &[0] });
-    data.extend_from_slice(&0u16.to_le_bytes()); // Keep this 0.
-    data.extend_from_slice(if use_flashloan { &[1] } else { &[0] });
+    fn expire(&self, sent: &mut VecDeque<SentSignature>, now: Instant) {
+        while sent
+            .front()
+            .is_some_and(|entry| now.saturating_duration_since(entry.sent_at) >= self.window)
+        {
+            sent.pop_front();
+        }
+        METRICS.set_recent_signatures(sent.len());
+    }
+}
 
-    Ok(Instruction {
-        program_id: executor_program_id,
-        accounts,
-        data,
-    })
+/// Check the statuses of every unresolved recent signature in batches, resolving those
+/// that failed or reached `commitment`. Returns how many landed.
+pub fn poll_recent_confirmations<S: SignatureStatusSource + ?Sized>(
+    recent: &RecentSignatures,
+    rpc: &S,
+    commitment: CommitmentConfig,
+    now: Instant,
+) -> usize {
+    let mut confirmed = 0;
+    for batch in recent.unconfirmed(now).chunks(MAX_SIGNATURE_STATUSES_PER_REQUEST) {
+        let statuses = match rpc.signature_statuses(batch) {
+            Ok(statuses) => statuses,
+            Err(e) => {
+                debug!("Signature status request for {} sent transactions failed: {}", batch.len(), e);
+                continue;
+            }
+        };
+        for (signature, status) in batch.iter().zip(statuses) {
+            let Some(status) = status else {
+                continue;
+            };
+            if let Some(err) = &status.err {
+                debug!("Sent transaction {} failed in slot {}: {}", signature, status.slot, err);
+                recent.mark_resolved(signature);
+                METRICS.inc_tx_failed();
+            } else if status.satisfies_commitment(commitment) {
+                debug!("Sent transaction {} landed in slot {}", signature, status.slot);
+                recent.mark_resolved(signature);
+                METRICS.inc_tx_confirmed();
+                confirmed += 1;
+            }
+        }
+    }
+    confirmed
+}
+
+/// `poll_recent_confirmations` over the signatures this process has sent
+pub fn poll_sent_confirmations<S: SignatureStatusSource + ?Sized>(rpc: &S, commitment: CommitmentConfig) -> usize {
+    poll_recent_confirmations(&RECENT_SIGNATURES, rpc, commitment, Instant::now())
+}
+
+/// Latest blockhash of each sending endpoint (`spam.blockhash_source = "sender"`), kept
+/// fresh by `run_sender_blockhash_refresher` so a send never waits on an RPC round trip
+pub struct SenderBlockhashCache {
+    ttl: Duration,
+    hashes: Mutex<HashMap<String, (Hash, Instant)>>,
+}
+
+impl SenderBlockhashCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            hashes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// `endpoint`'s last refreshed blockhash, None if it has none younger than the TTL
+    pub fn blockhash(&self, endpoint: &str) -> Option<Hash> {
+        let hashes = self.hashes.lock().unwrap();
+        let (hash, fetched_at) = hashes.get(endpoint)?;
+        (fetched_at.elapsed() < self.ttl).then_some(*hash)
+    }
+
+    pub fn store(&self, endpoint: &str, hash: Hash) {
+        self.hashes
+            .lock()
+            .unwrap()
+            .insert(endpoint.to_string(), (hash, Instant::now()));
+    }
+}
+
+/// Refresh every sending endpoint's blockhash each `interval`, concurrently; an endpoint
+/// that fails keeps its previous blockhash until it ages out of the cache
+pub async fn run_sender_blockhash_refresher(senders: Vec<Arc<AsyncRpcClient>>, interval: Duration) {
+    loop {
+        let fetches = senders
+            .iter()
+            .map(|sender| async move { (sender.url(), sender.get_latest_blockhash().await) });
+        for (endpoint, fetched) in futures::future::join_all(fetches).await {
+            match fetched {
+                Ok(hash) => SENDER_BLOCKHASHES.store(&endpoint, hash),
+                Err(e) => warn!("Failed to get the latest blockhash from {}: {}", endpoint, e),
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// The transaction to send through a sender whose blockhash is `sender_blockhash`,
+/// re-signed unless it already uses it
+fn transaction_for_sender(
+    build_tx: &TransactionBuilder,
+    tx: &VersionedTransaction,
+    blockhash: Hash,
+    sender_blockhash: Hash,
+) -> (VersionedTransaction, Hash) {
+    if sender_blockhash == blockhash {
+        return (tx.clone(), blockhash);
+    }
+    match build_tx(sender_blockhash) {
+        Ok(sender_tx) => (sender_tx, sender_blockhash),
+        Err(e) => {
+            warn!("Failed to sign for the sender's blockhash, using the read RPC's: {}", e);
+            (tx.clone(), blockhash)
+        }
+    }
+}
+
+/// Send `tx` to `senders` as `strategy` says, returning the index of every sender that
+/// accepted it with the signature it returned. A sender with an entry in
+/// `sender_blockhashes` gets the transaction signed with that blockhash instead.
+async fn dispatch_transaction<S: TransactionSender + Send + Sync + 'static>(
+    strategy: SendStrategy,
+    senders: &[Arc<S>],
+    build_tx: &TransactionBuilder,
+    tx: VersionedTransaction,
+    blockhash: Hash,
+    sender_blockhashes: &[Hash],
+    max_retries: u64,
+    account_in_use_retries: u32,
+) -> Vec<(usize, Signature)> {
+    let sender_blockhash = |i: usize| sender_blockhashes.get(i).copied().unwrap_or(blockhash);
+    let mut accepted = Vec::new();
+    match strategy {
+        SendStrategy::Broadcast | SendStrategy::FirstSuccess => {
+            for (i, sender) in senders.iter().enumerate() {
+                let (tx, blockhash) = transaction_for_sender(build_tx, &tx, blockhash, sender_blockhash(i));
+                let sent = send_through(
+                    i,
+                    sender.as_ref(),
+                    build_tx,
+                    tx,
+                    blockhash,
+                    max_retries,
+                    account_in_use_retries,
+                )
+                .await;
+                if let Some(signature) = sent {
+                    accepted.push((i, signature));
+                    if strategy == SendStrategy::FirstSuccess {
+                        break;
+                    }
+                }
+            }
+        }
+        SendStrategy::Fastest => {
+            let mut sends = tokio::task::JoinSet::new();
+            for (i, sender) in senders.iter().enumerate() {
+                let (tx, blockhash) = transaction_for_sender(build_tx, &tx, blockhash, sender_blockhash(i));
+                let (sender, build_tx) = (sender.clone(), build_tx.clone());
+                // RPC sends block, so each endpoint races on its own thread
+                sends.spawn_blocking(move || {
+                    let send = send_through(
+                        i,
+                        sender.as_ref(),
+                        &build_tx,
+                        tx,
+                        blockhash,
+                        max_retries,
+                        account_in_use_retries,
+                    );
+                    (i, futures::executor::block_on(send))
+                });
+            }
+            while let Some(joined) = sends.join_next().await {
+                if let Ok((i, Some(signature))) = joined {
+                    // Dropping the set cancels the sends that haven't started; one
+                    // already on the wire can't be recalled and is ignored
+                    accepted.push((i, signature));
+                    break;
+                }
+            }
+        }
+    }
+    accepted
+}
+
+async fn send_through<S: TransactionSender + ?Sized>(
+    i: usize,
+    sender: &S,
+    build_tx: &TransactionBuilder,
+    tx: VersionedTransaction,
+    blockhash: Hash,
+    max_retries: u64,
+    account_in_use_retries: u32,
+) -> Option<Signature> {
+    debug!("Sending transaction through RPC client {}", i);
+    let rebuild = |blockhash: Hash| build_tx(blockhash);
+    let sent =
+        send_transaction_with_retries(sender, &rebuild, tx, blockhash, max_retries, account_in_use_retries)
+            .await;
+    match sent {
+        Ok(signature) => {
+            METRICS.inc_tx_sent();
+            info!("Transaction sent successfully through RPC client {}: {}", i, signature);
+            Some(signature)
+        }
+        Err(e) => {
+            error!("Failed to send transaction through RPC client {}: {}", i, e);
+            None
+        }
+    }
+}
+
+/// Endpoint that swap transactions are submitted to
+pub trait TransactionSender {
+    fn send(&self, tx: &VersionedTransaction, max_retries: u64) -> Result<Signature, BotError>;
+
+    fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool, BotError>;
+
+    fn latest_blockhash(&self) -> Result<Hash, BotError>;
+
+    fn is_confirmed(&self, signature: &Signature) -> Result<bool, BotError>;
+
+    /// Simulate `tx`, returning the error it would fail with
+    fn simulate(&self, tx: &VersionedTransaction) -> Result<Option<TransactionError>, BotError>;
+}
+
+impl TransactionSender for RpcClient {
+    fn send(&self, tx: &VersionedTransaction, max_retries: u64) -> Result<Signature, BotError> {
+        Ok(self.send_transaction_with_config(
+            tx,
+            solana_client::rpc_config::RpcSendTransactionConfig {
+                skip_preflight: true,
+                max_retries: Some(max_retries as usize),
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                ..Default::default()
+            },
+        )?)
+    }
+
+    fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool, BotError> {
+        Ok(RpcClient::is_blockhash_valid(
+            self,
+            blockhash,
+            CommitmentConfig::processed(),
+        )?)
+    }
+
+    fn latest_blockhash(&self) -> Result<Hash, BotError> {
+        Ok(self.get_latest_blockhash()?)
+    }
+
+    fn is_confirmed(&self, signature: &Signature) -> Result<bool, BotError> {
+        Ok(self
+            .confirm_transaction_with_commitment(signature, CommitmentConfig::confirmed())?
+            .value)
+    }
+
+    fn simulate(&self, tx: &VersionedTransaction) -> Result<Option<TransactionError>, BotError> {
+        Ok(self.simulate_transaction(tx)?.value.err)
+    }
+}
+
+/// Send errors meaning a copy of the transaction, or one writing the same accounts,
+/// is already in flight rather than that the send failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateSendError {
+    /// This exact transaction was already processed
+    AlreadyProcessed,
+    /// An account it writes is locked by a transaction being processed
+    AccountInUse,
+}
+
+impl DuplicateSendError {
+    /// Recognizes both the `TransactionError` names and their messages, as RPC nodes
+    /// report them either way
+    pub fn classify(error: &BotError) -> Option<Self> {
+        let message = error.to_string();
+        if message.contains("AlreadyProcessed") || message.contains("already been processed") {
+            Some(DuplicateSendError::AlreadyProcessed)
+        } else if message.contains("AccountInUse") || message.contains("Account in use") {
+            Some(DuplicateSendError::AccountInUse)
+        } else {
+            None
+        }
+    }
+}
+
+/// Send `tx`, re-sending up to `max_retries` more times on retryable errors.
+///
+/// This is separate from the read-path retries (`MAX_RPC_RETRIES`); before each retry
+/// the transaction is rebuilt with a fresh blockhash if the current one has expired.
+/// "AlreadyProcessed" counts as sent, leaving the signature to confirmation, and
+/// "AccountInUse" is retried up to `account_in_use_retries` times after a short
+/// backoff without using up `max_retries`.
+async fn send_transaction_with_retries<S: TransactionSender + ?Sized>(
+    sender: &S,
+    build_tx: &impl Fn(Hash) -> anyhow::Result<VersionedTransaction>,
+    mut tx: VersionedTransaction,
+    mut blockhash: Hash,
+    max_retries: u64,
+    account_in_use_retries: u32,
+) -> anyhow::Result<Signature> {
+    let mut attempt = 0;
+    let mut account_in_use = 0;
+    loop {
+        attempt += 1;
+        let error = match sender.send(&tx, max_retries) {
+            Ok(signature) => return Ok(signature),
+            Err(e) => e,
+        };
+
+        match DuplicateSendError::classify(&error) {
+            Some(DuplicateSendError::AlreadyProcessed) => {
+                METRICS.inc_send_already_processed();
+                let signature = tx.signatures[0];
+                info!("Transaction {} was already processed, confirming it instead of resending", signature);
+                return Ok(signature);
+            }
+            Some(DuplicateSendError::AccountInUse) => {
+                METRICS.inc_send_account_in_use();
+                if account_in_use >= account_in_use_retries {
+                    return Err(error.into());
+                }
+                account_in_use += 1;
+                attempt -= 1;
+                debug!(
+                    "Account in use, retrying in {}ms ({}/{})",
+                    ACCOUNT_IN_USE_BACKOFF_MS, account_in_use, account_in_use_retries
+                );
+                tokio::time::sleep(Duration::from_millis(ACCOUNT_IN_USE_BACKOFF_MS)).await;
+                continue;
+            }
+            None => {}
+        }
+
+        warn!("Send attempt {}/{} failed: {}", attempt, max_retries + 1, error);
+        if !error.is_retryable() || attempt > max_retries {
+            return Err(error.into());
+        }
+
+        if !sender.is_blockhash_valid(&blockhash).unwrap_or(false) {
+            blockhash = sender.latest_blockhash()?;
+            debug!("Blockhash expired, rebuilding transaction with {}", blockhash);
+            tx = build_tx(blockhash)?;
+        }
+    }
+}
+
+/// Source of signature statuses for confirmation polling
+pub trait SignatureStatusSource {
+    fn signature_status(&self, signature: &Signature) -> Result<Option<TransactionStatus>, BotError>;
+
+    /// Statuses of `signatures`, in the same order
+    fn signature_statuses(&self, signatures: &[Signature]) -> Result<Vec<Option<TransactionStatus>>, BotError> {
+        signatures.iter().map(|signature| self.signature_status(signature)).collect()
+    }
+}
+
+impl SignatureStatusSource for RpcClient {
+    fn signature_status(&self, signature: &Signature) -> Result<Option<TransactionStatus>, BotError> {
+        Ok(self
+            .get_signature_statuses(&[*signature])?
+            .value
+            .into_iter()
+            .next()
+            .flatten())
+    }
+
+    fn signature_statuses(&self, signatures: &[Signature]) -> Result<Vec<Option<TransactionStatus>>, BotError> {
+        Ok(self.get_signature_statuses(signatures)?.value)
+    }
+}
+
+/// Poll `get_signature_statuses` until `signature` reaches `commitment`, returning the
+/// slot it landed in. Fails if the transaction executed with an error or is still not
+/// at `commitment` when `timeout` elapses.
+pub async fn confirm_signature<S: SignatureStatusSource + ?Sized>(
+    rpc: &S,
+    signature: &Signature,
+    commitment: CommitmentConfig,
+    timeout: Duration,
+) -> Result<u64, BotError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match rpc.signature_status(signature) {
+            Ok(Some(status)) => {
+                if let Some(err) = status.err {
+                    return Err(BotError::TransactionSendError(format!(
+                        "Transaction {} failed in slot {}: {}",
+                        signature, status.slot, err
+                    )));
+                }
+                if status.satisfies_commitment(commitment) {
+                    return Ok(status.slot);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => debug!("Signature status request for {} failed: {}", signature, e),
+        }
+        if Instant::now() >= deadline {
+            return Err(BotError::TransactionSendError(format!(
+                "Transaction {} not {:?} within {}ms",
+                signature,
+                commitment.commitment,
+                timeout.as_millis()
+            )));
+        }
+        tokio::time::sleep(Duration::from_millis(CONFIRM_POLL_INTERVAL_MS)).await;
+    }
+}
+
+/// Commitment the send path waits for after an RPC send, or None to move on at once.
+/// Without a `confirmation_mode`, spam sends don't wait and others wait for
+/// `confirm_commitment`.
+pub fn confirmation_wait(
+    mode: Option<ConfirmationMode>,
+    spam_enabled: bool,
+    confirm_commitment: CommitmentConfig,
+) -> Option<CommitmentConfig> {
+    match mode {
+        Some(mode) => mode.commitment(),
+        None if spam_enabled => None,
+        None => Some(confirm_commitment),
+    }
+}
+
+/// Metric label of a confirmation wait
+fn confirmation_wait_label(wait: Option<CommitmentConfig>) -> &'static str {
+    match wait {
+        None => "none",
+        Some(commitment) if commitment.is_finalized() => "finalized",
+        Some(commitment) if commitment.is_confirmed() => "confirmed",
+        Some(_) => "processed",
+    }
+}
+
+/// Confirm `signature` at `wait`, or return None without polling when there's nothing to
+/// wait for (the background poll resolves the signature later)
+pub async fn await_confirmation<S: SignatureStatusSource + ?Sized>(
+    rpc: &S,
+    signature: &Signature,
+    wait: Option<CommitmentConfig>,
+    timeout: Duration,
+) -> Option<Result<u64, BotError>> {
+    let commitment = wait?;
+    Some(confirm_signature(rpc, signature, commitment, timeout).await)
+}
+
+/// Run `future` on the blocking pool. The send path's confirmation polling goes through
+/// the blocking RPC client, and would otherwise hold up an async worker, and every
+/// strategy loop scheduled on it, for each round trip until the transaction confirms.
+async fn on_blocking_pool<T, F>(future: F) -> anyhow::Result<T>
+where
+    T: Send + 'static,
+    F: std::future::Future<Output = T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || futures::executor::block_on(future))
+        .await
+        .map_err(|e| anyhow::anyhow!("confirmation task panicked: {}", e))
+}
+
+/// `reconcile_send_result` on the blocking pool, as it reads the landed transaction
+/// through the blocking RPC client
+async fn reconcile_on_blocking_pool(
+    client: &Arc<RpcClient>,
+    result: &mut SendResult,
+    wallet: Pubkey,
+    executor_program_id: Pubkey,
+) {
+    let (client, mut reconciled) = (client.clone(), result.clone());
+    match tokio::task::spawn_blocking(move || {
+        reconcile_send_result(&client, &mut reconciled, &wallet, &executor_program_id);
+        reconciled
+    })
+    .await
+    {
+        Ok(reconciled) => *result = reconciled,
+        Err(e) => warn!("Failed to reconcile profit of {}: {}", result.signature, e),
+    }
+}
+
+/// How tracking a sent transaction ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResendOutcome {
+    /// One of the sent signatures (original or a resend) confirmed
+    Confirmed(Signature),
+    /// Re-simulation failed, so the opportunity is gone and resending was cancelled
+    OpportunityGone,
+    /// Nothing confirmed after `max_resends` resends
+    Exhausted,
+}
+
+/// Wait for `signature` to confirm; if it is dropped, rebuild with a fresh blockhash
+/// and resend up to `max_resends` times while the transaction still simulates cleanly.
+async fn resend_until_confirmed<S: TransactionSender + ?Sized>(
+    sender: &S,
+    build_tx: &impl Fn(Hash) -> anyhow::Result<VersionedTransaction>,
+    signature: Signature,
+    max_resends: u32,
+    confirm_window: Duration,
+    poll_interval: Duration,
+    max_retries: u64,
+) -> anyhow::Result<ResendOutcome> {
+    // A dropped-looking signature may still land late, so keep checking all of them
+    let mut signatures = vec![signature];
+    let mut resends = 0;
+    loop {
+        if let Some(confirmed) =
+            wait_for_confirmation(sender, &signatures, confirm_window, poll_interval).await
+        {
+            return Ok(ResendOutcome::Confirmed(confirmed));
+        }
+        if resends >= max_resends {
+            return Ok(ResendOutcome::Exhausted);
+        }
+
+        let blockhash = sender.latest_blockhash()?;
+        let tx = build_tx(blockhash)?;
+        if let Some(err) = sender.simulate(&tx)? {
+            debug!("Resend simulation failed: {}", err);
+            return Ok(ResendOutcome::OpportunityGone);
+        }
+
+        resends += 1;
+        match sender.send(&tx, max_retries) {
+            Ok(resent) => {
+                METRICS.inc_tx_sent();
+                RECENT_SIGNATURES.remember(resent, Instant::now());
+                debug!(
+                    "Resend {}/{}: {} not confirmed, sent {}",
+                    resends, max_resends, signature, resent
+                );
+                signatures.push(resent);
+            }
+            Err(e) => warn!("Resend {}/{} failed: {}", resends, max_resends, e),
+        }
+    }
+}
+
+/// Poll until any of `signatures` confirms or `window` elapses
+async fn wait_for_confirmation<S: TransactionSender + ?Sized>(
+    sender: &S,
+    signatures: &[Signature],
+    window: Duration,
+    poll_interval: Duration,
+) -> Option<Signature> {
+    let deadline = Instant::now() + window;
+    loop {
+        if let Some(confirmed) = signatures
+            .iter()
+            .find(|signature| sender.is_confirmed(signature).unwrap_or(false))
+        {
+            return Some(*confirmed);
+        }
+        if Instant::now() >= deadline {
+            return None;
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// A pool considered for inclusion in the swap transaction
+#[derive(Debug, Clone)]
+pub struct PoolCandidate {
+    pub pool: Pubkey,
+    pub dex: &'static str,
+    pub accounts: Vec<Pubkey>,
+    pub compute_units: u32,
+    pub expected_profit: u64,
+}
+
+/// Subset of pools chosen to fit within a single transaction
+#[derive(Debug, Clone, Default)]
+pub struct PoolSelection {
+    pub selected: Vec<Pubkey>,
+    pub skipped: Vec<Pubkey>,
+    pub account_count: usize,
+    pub estimated_size: usize,
+    pub compute_units: u32,
+    pub expected_profit: u64,
+}
+
+impl PoolSelection {
+    /// Log the chosen subset (used by dry-run mode)
+    pub fn log_report(&self, mint: &str) {
+        info!("Pool selection for mint {}:", mint);
+        info!("  Selected pools: {}", self.selected.len());
+        for pool in &self.selected {
+            info!("    + {}", pool);
+        }
+        for pool in &self.skipped {
+            info!("    - {} (does not fit)", pool);
+        }
+        info!(
+            "  Accounts: {}/{}, est. size: {}/{} bytes, est. compute units: {}",
+            self.account_count,
+            MAX_TRANSACTION_ACCOUNTS,
+            self.estimated_size,
+            MAX_TRANSACTION_SIZE_BYTES,
+            self.compute_units
+        );
+    }
+}
+
+/// Rough compute units consumed by one swap leg on the given DEX
+pub fn estimate_pool_compute_units(dex: &str) -> u32 {
+    match dex {
+        "Raydium CLMM" | "Orca Whirlpool" | "Meteora DLMM" | "Phoenix" => {
+            CONCENTRATED_POOL_COMPUTE_UNITS
+        }
+        _ => STANDARD_POOL_COMPUTE_UNITS,
+    }
+}
+
+/// Compute units of one swap leg on `dex`: `bot.dex_compute_units` when it lists the DEX,
+/// the built-in estimate otherwise
+pub fn pool_compute_units(bot: &BotConfig, dex: &str) -> u32 {
+    bot.dex_compute_units(dex)
+        .unwrap_or_else(|| estimate_pool_compute_units(dex))
+}
+
+/// The route's swap legs at their per-DEX cost plus `bot.compute_unit_overhead`, up to
+/// Solana's cap
+pub fn estimated_compute_unit_limit(bot: &BotConfig, mint_pool_data: &MintPoolData) -> u32 {
+    let legs: u64 = mint_pool_data
+        .dex_pools()
+        .iter()
+        .map(|pool| pool_compute_units(bot, pool.dex_name()) as u64)
+        .sum();
+    (legs + bot.compute_unit_overhead() as u64).min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+}
+
+/// Compute unit limit to request for the route, and whether it is an estimate simulation
+/// may refine: the mint's `compute_unit_limit` when set, the per-DEX estimate with
+/// `bot.estimate_compute_unit_limit`, else `bot.compute_unit_limit`
+pub fn route_compute_unit_limit(config: &Config, mint_pool_data: &MintPoolData) -> (u32, bool) {
+    if let Some(limit) = config
+        .mint_config(&mint_pool_data.mint)
+        .and_then(|mint_config| mint_config.compute_unit_limit)
+    {
+        return (limit, false);
+    }
+    if config.bot.estimate_compute_unit_limit() {
+        return (estimated_compute_unit_limit(&config.bot, mint_pool_data), true);
+    }
+    (config.bot.compute_unit_limit, false)
+}
+
+/// Compute units pool selection may fill for `mint`: the mint's `compute_unit_limit`, the
+/// cap less the overhead when limits are estimated per route, else `bot.compute_unit_limit`
+pub fn compute_unit_budget(config: &Config, mint: &Pubkey) -> u32 {
+    if let Some(limit) = config
+        .mint_config(mint)
+        .and_then(|mint_config| mint_config.compute_unit_limit)
+    {
+        return limit;
+    }
+    if config.bot.estimate_compute_unit_limit() {
+        return MAX_COMPUTE_UNIT_LIMIT.saturating_sub(config.bot.compute_unit_overhead());
+    }
+    config.bot.compute_unit_limit
+}
+
+/// `candidates` costed with the configured `bot.dex_compute_units`
+pub fn with_dex_compute_units(bot: &BotConfig, mut candidates: Vec<PoolCandidate>) -> Vec<PoolCandidate> {
+    for candidate in &mut candidates {
+        candidate.compute_units = pool_compute_units(bot, candidate.dex);
+    }
+    candidates
+}
+
+/// Limit for an estimated route that simulated at `units_consumed`: the measured units plus
+/// a `COMPUTE_UNIT_SIMULATION_MARGIN_BPS` margin, up to Solana's cap; a route past the cap
+/// is `ComputeBudgetExceeded`
+pub fn refined_compute_unit_limit(units_consumed: u64, estimated: u32) -> Result<u32, BotError> {
+    if units_consumed > MAX_COMPUTE_UNIT_LIMIT as u64 {
+        warn!(
+            "Route needs {} compute units, more than the {} a transaction may use; \
+             remove pools from this mint",
+            units_consumed, MAX_COMPUTE_UNIT_LIMIT
+        );
+        return Err(BotError::ComputeBudgetExceeded {
+            needed: units_consumed,
+            configured: estimated,
+        });
+    }
+    let margin = units_consumed * COMPUTE_UNIT_SIMULATION_MARGIN_BPS / BASIS_POINTS_DENOMINATOR;
+    Ok((units_consumed + margin).min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32)
+}
+
+/// Accounts every swap instruction carries regardless of which pools are included
+pub fn base_swap_accounts(
+    wallet: &Pubkey,
+    mint_pool_data: &MintPoolData,
+    fee_collector: &Pubkey,
+) -> Vec<Pubkey> {
+    vec![
+        *wallet,
+        sol_mint(),
+        *fee_collector,
+        mint_pool_data.wallet_wsol_account,
+        token_program_id,
+        system_program::ID,
+        associated_token_program_id,
+        mint_pool_data.mint,
+        mint_pool_data.token_program,
+        spl_associated_token_account::get_associated_token_address_with_program_id(
+            wallet,
+            &mint_pool_data.mint,
+            &mint_pool_data.token_program,
+        ),
+    ]
+}
+
+/// Fixed accounts of `layout` in order, as the executor expects them before the pools
+pub fn static_account_metas(
+    layout: &[StaticAccount],
+    executor_program_id: &Pubkey,
+    fee_collector: &Pubkey,
+) -> Vec<AccountMeta> {
+    layout
+        .iter()
+        .map(|account| match account {
+            StaticAccount::PumpGlobalConfig => {
+                AccountMeta::new_readonly(*PUMP_GLOBAL_CONFIG_PUBKEY, false)
+            }
+            StaticAccount::SysvarInstructions => {
+                AccountMeta::new_readonly(*SYSVAR_INSTRUCTIONS_PUBKEY, false)
+            }
+            StaticAccount::FeeCollector => AccountMeta::new(*fee_collector, false),
+            StaticAccount::ExecutorProgram => AccountMeta::new_readonly(*executor_program_id, false),
+        })
+        .collect()
+}
+
+/// `base_swap_accounts` plus the static accounts configured for the executor
+pub fn executor_base_accounts(
+    config: &Config,
+    wallet: &Pubkey,
+    mint_pool_data: &MintPoolData,
+) -> Vec<Pubkey> {
+    let mut accounts = base_swap_accounts(wallet, mint_pool_data, &config.fee_collector());
+    accounts.extend(
+        static_account_metas(
+            &config.executor_static_accounts(),
+            &config.executor_program_id(),
+            &config.fee_collector(),
+        )
+        .into_iter()
+        .map(|meta| meta.pubkey),
+    );
+    accounts
+}
+
+/// Accounts the pools' swaps write (pool states, vaults, ...), without repeats
+pub fn writable_pool_accounts(mint_pool_data: &MintPoolData, wallet: &Pubkey) -> Vec<Pubkey> {
+    let mut seen = HashSet::new();
+    let mut writable = Vec::new();
+    for pool in mint_pool_data.dex_pools() {
+        let accounts = mint_pool_data.swap_accounts(pool, wallet, None);
+        writable.extend(
+            accounts
+                .iter()
+                .filter(|meta| meta.is_writable && seen.insert(meta.pubkey))
+                .map(|meta| meta.pubkey),
+        );
+    }
+    writable
+}
+
+/// Build selection candidates for every pool of a mint
+///
+/// Quoting is not wired in yet, so each pool carries the same expected profit and
+/// the selector maximizes the number of pools that fit. Quoted profits should go
+/// through `MintPoolData::expected_profit` so Token-2022 transfer fees are netted out.
+pub fn pool_candidates(mint_pool_data: &MintPoolData, wallet: &Pubkey) -> Vec<PoolCandidate> {
+    mint_pool_data
+        .dex_pools()
+        .into_iter()
+        .map(|pool| PoolCandidate {
+            pool: pool.pool_address(),
+            dex: pool.dex_name(),
+            // With the wallet's accounts (a Phoenix seat), as `create_swap_instruction` passes them
+            accounts: mint_pool_data
+                .swap_accounts(pool, wallet, None)
+                .iter()
+                .map(|meta| meta.pubkey)
+                .collect(),
+            compute_units: estimate_pool_compute_units(pool.dex_name()),
+            expected_profit: 1,
+        })
+        .collect()
+}
+
+/// Greedily choose the pools that maximize expected profit within transaction limits
+///
+/// Accounts covered by a lookup table cost one byte instead of a full 32-byte key,
+/// so candidates are ranked by profit per marginal byte and added until nothing fits.
+pub fn select_pools(
+    candidates: &[PoolCandidate],
+    base_accounts: &[Pubkey],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    compute_unit_limit: u32,
+) -> PoolSelection {
+    let selection = fit_pools(
+        candidates,
+        base_accounts,
+        &[],
+        address_lookup_table_accounts,
+        compute_unit_limit,
+    );
+    if !selection.skipped.is_empty() {
+        warn!(
+            "{} pools do not fit in a single transaction and were skipped",
+            selection.skipped.len()
+        );
+    }
+    selection
+}
+
+/// Split the candidates into groups of at most `max_pools_per_tx` pools that each fit in
+/// one transaction next to `extra_instructions` (the wSOL wrap and unwrap, which every
+/// transaction repeats). A pool too large for a transaction on its own is left out, and
+/// so is a group left with a single pool, which has nothing to trade against.
+pub fn partition_pools(
+    candidates: &[PoolCandidate],
+    max_pools_per_tx: usize,
+    base_accounts: &[Pubkey],
+    extra_instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    compute_unit_limit: u32,
+) -> Vec<PoolSelection> {
+    let mut groups = Vec::new();
+    let mut remaining = candidates.to_vec();
+    while !remaining.is_empty() {
+        let window = remaining.len().min(max_pools_per_tx.max(1));
+        let mut group = fit_pools(
+            &remaining[..window],
+            base_accounts,
+            extra_instructions,
+            address_lookup_table_accounts,
+            compute_unit_limit,
+        );
+        if group.selected.is_empty() {
+            let pool = remaining.remove(0).pool;
+            warn!("Pool {} does not fit in a transaction on its own and was skipped", pool);
+            continue;
+        }
+        // Pools of the window that didn't fit move on to the next group
+        group.skipped.clear();
+        remaining.retain(|candidate| !group.selected.contains(&candidate.pool));
+        if group.selected.len() < 2 {
+            warn!(
+                "Pool {} is alone in its transaction and was skipped",
+                group.selected[0]
+            );
+            continue;
+        }
+        groups.push(group);
+    }
+    groups
+}
+
+/// A copy of the mint's pool data per group of at most `max_pools_per_tx` pools
+pub fn split_pool_data(
+    config: &Config,
+    mint_pool_data: &MintPoolData,
+    wallet: &Pubkey,
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    max_pools_per_tx: usize,
+) -> anyhow::Result<Vec<MintPoolData>> {
+    let groups = partition_pools(
+        &with_dex_compute_units(&config.bot, pool_candidates(mint_pool_data, wallet)),
+        max_pools_per_tx,
+        &executor_base_accounts(config, wallet, mint_pool_data),
+        &wrap_overhead_instructions(config, wallet, mint_pool_data)?,
+        address_lookup_table_accounts,
+        compute_unit_budget(config, &mint_pool_data.mint),
+    );
+    Ok(groups
+        .iter()
+        .map(|group| {
+            let mut data = mint_pool_data.clone();
+            data.retain_pools(&group.selected.iter().copied().collect());
+            data
+        })
+        .collect())
+}
+
+/// Greedy selection behind `select_pools`, also fitting `extra_instructions`
+fn fit_pools(
+    candidates: &[PoolCandidate],
+    base_accounts: &[Pubkey],
+    extra_instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    compute_unit_limit: u32,
+) -> PoolSelection {
+    let lookup_addresses: HashSet<Pubkey> = address_lookup_table_accounts
+        .iter()
+        .flat_map(|table| table.addresses.iter().copied())
+        .collect();
+
+    let key_bytes = |key: &Pubkey| if lookup_addresses.contains(key) { 1 } else { 32 };
+
+    let mut included: HashSet<Pubkey> = base_accounts.iter().copied().collect();
+    for instruction in extra_instructions {
+        included.insert(instruction.program_id);
+        included.extend(instruction.accounts.iter().map(|meta| meta.pubkey));
+    }
+    // Program index, account count, account indices, data length and data per instruction
+    let extra_instruction_bytes: usize = extra_instructions
+        .iter()
+        .map(|instruction| 3 + instruction.accounts.len() + instruction.data.len())
+        .sum();
+    let mut estimated_size = ESTIMATED_TRANSACTION_OVERHEAD_BYTES
+        + included.iter().map(key_bytes).sum::<usize>()
+        + base_accounts.len()
+        + extra_instruction_bytes;
+    let mut selection = PoolSelection::default();
+    let mut remaining: Vec<&PoolCandidate> = candidates.iter().collect();
+
+    loop {
+        let mut best: Option<(usize, f64, usize)> = None; // (index, score, marginal bytes)
+
+        for (index, candidate) in remaining.iter().enumerate() {
+            let new_keys: HashSet<&Pubkey> = candidate
+                .accounts
+                .iter()
+                .filter(|key| !included.contains(*key))
+                .collect();
+            // New keys plus one instruction index byte per account meta
+            let marginal_bytes =
+                new_keys.iter().map(|key| key_bytes(key)).sum::<usize>() + candidate.accounts.len();
+
+            let fits = included.len() + new_keys.len() <= MAX_TRANSACTION_ACCOUNTS
+                && estimated_size + marginal_bytes <= MAX_TRANSACTION_SIZE_BYTES
+                && selection.compute_units + candidate.compute_units <= compute_unit_limit;
+            if !fits {
+                continue;
+            }
+
+            let score = candidate.expected_profit as f64 / marginal_bytes.max(1) as f64;
+            let better = match best {
+                None => true,
+                Some((_, best_score, best_bytes)) => {
+                    score > best_score || (score == best_score && marginal_bytes < best_bytes)
+                }
+            };
+            if better {
+                best = Some((index, score, marginal_bytes));
+            }
+        }
+
+        let Some((index, _, marginal_bytes)) = best else {
+            break;
+        };
+
+        let candidate = remaining.remove(index);
+        included.extend(candidate.accounts.iter().copied());
+        estimated_size += marginal_bytes;
+        selection.compute_units += candidate.compute_units;
+        selection.expected_profit += candidate.expected_profit;
+        selection.selected.push(candidate.pool);
+    }
+
+    selection.skipped = remaining.iter().map(|candidate| candidate.pool).collect();
+    selection.account_count = included.len();
+    selection.estimated_size = estimated_size;
+    selection
+}
+
+/// Helper function to derive the vault token account PDA address for a given mint
+pub fn derive_vault_token_account(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault_token_account", mint.as_ref()], program_id)
+}
+
+// See https://docs.solanamevbot.com/home/onchain-bot/onchain-program for more information
+fn create_swap_instruction(
+    wallet: &dyn Signer,
+    mint_pool_data: &MintPoolData,
+    compute_unit_limit: u64,
+    minimum_profit: u64,
+    use_flashloan: bool,
+    executor_program_id: Pubkey,
+    fee_collector: Pubkey,
+    static_accounts: &[StaticAccount],
+) -> anyhow::Result<Instruction> {
+    debug!("Creating swap instruction for all DEX types");
+
+    let wallet = wallet.pubkey();
+    let sol_mint_pubkey = sol_mint();
+    let wallet_sol_account = mint_pool_data.wallet_wsol_account;
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(wallet, true), // 0. Wallet (signer)
+        AccountMeta::new_readonly(sol_mint_pubkey, false), // 1. SOL mint
+        AccountMeta::new(fee_collector, false),  // 2. Fee collector
+        AccountMeta::new(wallet_sol_account, false), // 3. Wallet SOL account
+        AccountMeta::new_readonly(token_program_id, false), // 4. Token program
+        AccountMeta::new_readonly(system_program::ID, false), // 5. System program
+        AccountMeta::new_readonly(associated_token_program_id, false), // 6. Associated Token program
+    ];
+
+    let base_mint = sol_mint_pubkey;
+
+    if use_flashloan {
+        accounts.push(AccountMeta::new_readonly(
+            *KAMINO_LENDING_PROGRAM_PUBKEY,
+            false,
+        ));
+        let token_pda = derive_vault_token_account(&executor_program_id, &base_mint);
+        accounts.push(AccountMeta::new(token_pda.0, false));
+    }
+
+    accounts.push(AccountMeta::new_readonly(mint_pool_data.mint, false));
+    accounts.push(AccountMeta::new_readonly(
+        mint_pool_data.token_program,
+        false,
+    )); // Token program (SPL Token or Token 2022)
+    let wallet_x_account =
+        spl_associated_token_account::get_associated_token_address_with_program_id(
+            &wallet,
+            &mint_pool_data.mint,
+            &mint_pool_data.token_program,
+        );
+    accounts.push(AccountMeta::new(wallet_x_account, false));
+    accounts.extend(static_account_metas(
+        static_accounts,
+        &executor_program_id,
+        &fee_collector,
+    ));
+
+    // Copied from the swap account cache in `dex_pools` order, the executor's layout;
+    // a quoted route only lists the tick and bin arrays its swaps cross
+    for pool in mint_pool_data.dex_pools() {
+        let direction = mint_pool_data.swap_direction(&pool.pool_address());
+        accounts.extend_from_slice(&mint_pool_data.swap_accounts(pool, &wallet, direction));
+    }
+
+    let accounts: AccountMetasBuilder = accounts.into_iter().collect();
+    debug!(
+        "Swap instruction passes {} accounts, {} unique",
+        accounts.len(),
+        accounts.unique().len()
+    );
+
+    let mut data = vec![26u8];
+
+    let no_failure_mode = NO_FAILURE_MODE_DEFAULT;
+
+    data.extend_from_slice(&minimum_profit.to_le_bytes());
+    data.extend_from_slice(&compute_unit_limit.to_le_bytes());
+    data.extend_from_slice(if no_failure_mode { &[1] } else { &[0] });
+    data.extend_from_slice(&0u16.to_le_bytes()); // Keep this 0.
+    data.extend_from_slice(if use_flashloan { &[1] } else { &[0] });
+
+    Ok(Instruction {
+        program_id: executor_program_id,
+        accounts: accounts.build(),
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::phoenix::phoenix_seat;
+    use crate::dex::raydium::raydium_program_id;
+    use solana_sdk::signature::Keypair;
+    use solana_transaction_status::TransactionConfirmationStatus;
+
+    fn candidate(account_count: usize, expected_profit: u64) -> PoolCandidate {
+        PoolCandidate {
+            pool: Pubkey::new_unique(),
+            dex: "Test",
+            accounts: (0..account_count).map(|_| Pubkey::new_unique()).collect(),
+            compute_units: STANDARD_POOL_COMPUTE_UNITS,
+            expected_profit,
+        }
+    }
+
+    #[test]
+    fn test_executor_result_from_return_data() {
+        // 1_234_567 lamports profit over a three-swap route
+        let mut bytes = 1_234_567u64.to_le_bytes().to_vec();
+        bytes.push(3);
+        let executor = Pubkey::new_unique();
+        let return_data = UiTransactionReturnData {
+            program_id: executor.to_string(),
+            data: (
+                base64::engine::general_purpose::STANDARD.encode(&bytes),
+                UiReturnDataEncoding::Base64,
+            ),
+        };
+
+        assert_eq!(
+            ExecutorResult::from_return_data(&return_data, &executor),
+            Some(ExecutorResult {
+                profit_lamports: 1_234_567,
+                route_len: 3,
+            })
+        );
+        // Return data set by another program (e.g. a DEX) is ignored
+        assert_eq!(ExecutorResult::from_return_data(&return_data, &Pubkey::new_unique()), None);
+        assert_eq!(ExecutorResult::decode(&bytes[..8]), None);
+    }
+
+    #[test]
+    fn test_phoenix_candidate_counts_the_wallet_seat() {
+        let wallet = Pubkey::new_unique();
+        let mut mint_pool_data = MintPoolData::new(
+            &Pubkey::new_unique().to_string(),
+            &wallet.to_string(),
+            token_program_id,
+        )
+        .unwrap();
+        let market = Pubkey::new_unique();
+        mint_pool_data.phoenix_pools.push(crate::dex::phoenix::initializer::PhoenixPool {
+            market,
+            base_mint: mint_pool_data.mint,
+            quote_mint: sol_mint(),
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            log_authority: Pubkey::new_unique(),
+            book: Default::default(),
+        });
+
+        let candidates = pool_candidates(&mint_pool_data, &wallet);
+        assert_eq!(candidates[0].accounts.len(), 6);
+        assert_eq!(candidates[0].accounts[5], phoenix_seat(&market, &wallet));
+    }
+
+    #[test]
+    fn test_swap_instruction_uses_configured_executor() {
+        let wallet = Keypair::new();
+        let mint_pool_data = MintPoolData::new(
+            &Pubkey::new_unique().to_string(),
+            &wallet.pubkey().to_string(),
+            token_program_id,
+        )
+        .unwrap();
+        let program_id = Pubkey::new_unique();
+        let fee_collector = Pubkey::new_unique();
+
+        let ix = create_swap_instruction(
+            &wallet,
+            &mint_pool_data,
+            200_000,
+            50_000,
+            false,
+            program_id,
+            fee_collector,
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(ix.program_id, program_id);
+        // The mint's minimum profit follows the instruction tag
+        assert_eq!(ix.data[1..9], 50_000u64.to_le_bytes());
+        assert_eq!(ix.accounts[2].pubkey, fee_collector);
+        assert!(base_swap_accounts(&wallet.pubkey(), &mint_pool_data, &fee_collector)
+            .contains(&fee_collector));
+    }
+
+    #[test]
+    fn test_static_accounts_lead_pool_accounts() {
+        let wallet = Keypair::new();
+        let mut mint_pool_data = MintPoolData::new(
+            &Pubkey::new_unique().to_string(),
+            &wallet.pubkey().to_string(),
+            token_program_id,
+        )
+        .unwrap();
+        mint_pool_data.raydium_pools.push(crate::dex::raydium::RaydiumCpmmPool {
+            pool: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            sol_vault: Pubkey::new_unique(),
+            coin_mint: Pubkey::new_unique(),
+            pc_mint: Pubkey::new_unique(),
+            token_reserve: 0,
+            sol_reserve: 0,
+        });
+        let program_id = Pubkey::new_unique();
+        let fee_collector = Pubkey::new_unique();
+        let layout = [
+            StaticAccount::PumpGlobalConfig,
+            StaticAccount::SysvarInstructions,
+            StaticAccount::FeeCollector,
+            StaticAccount::ExecutorProgram,
+        ];
+
+        let ix = create_swap_instruction(
+            &wallet,
+            &mint_pool_data,
+            200_000,
+            MINIMUM_PROFIT_DEFAULT,
+            false,
+            program_id,
+            fee_collector,
+            &layout,
+        )
+        .unwrap();
+
+        // 7 base accounts, then the mint, its token program and the wallet's token account
+        let leading: Vec<Pubkey> = ix.accounts[10..14].iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            leading,
+            vec![
+                *PUMP_GLOBAL_CONFIG_PUBKEY,
+                *SYSVAR_INSTRUCTIONS_PUBKEY,
+                fee_collector,
+                program_id
+            ]
+        );
+        assert!(ix.accounts[12].is_writable);
+        assert_eq!(ix.accounts[14].pubkey, raydium_program_id());
+        assert_eq!(ix.accounts[16].pubkey, mint_pool_data.raydium_pools[0].pool);
+    }
+
+    #[test]
+    fn test_swap_instruction_passes_the_cached_route_accounts() {
+        use crate::dex::traits::{DexPool, SwapDirection};
+        use crate::dex::whirlpool::initializer::WhirlpoolPool;
+
+        let wallet = Keypair::new();
+        let mut mint_pool_data = MintPoolData::new(
+            &Pubkey::new_unique().to_string(),
+            &wallet.pubkey().to_string(),
+            token_program_id,
+        )
+        .unwrap();
+        mint_pool_data.whirlpool_pools.push(WhirlpoolPool {
+            pool: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            token_vault_a: Pubkey::new_unique(),
+            token_vault_b: Pubkey::new_unique(),
+            tick_arrays: (0..3).map(|_| Pubkey::new_unique()).collect(),
+            current_tick: 0,
+            token_mint_a: mint_pool_data.mint,
+            token_mint_b: sol_mint(),
+            liquidity: 0,
+            sqrt_price: 0,
+            adaptive_fee_rate: 0,
+        });
+        mint_pool_data.raydium_pools.push(crate::dex::raydium::RaydiumCpmmPool {
+            pool: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            sol_vault: Pubkey::new_unique(),
+            coin_mint: Pubkey::new_unique(),
+            pc_mint: Pubkey::new_unique(),
+            token_reserve: 0,
+            sol_reserve: 0,
+        });
+        let whirlpool = mint_pool_data.whirlpool_pools[0].clone();
+        let raydium = mint_pool_data.raydium_pools[0].clone();
+        mint_pool_data.set_route(&[whirlpool.pool, raydium.pool], 1);
+        mint_pool_data.cache_swap_accounts();
+
+        let ix = create_swap_instruction(
+            &wallet,
+            &mint_pool_data,
+            200_000,
+            MINIMUM_PROFIT_DEFAULT,
+            false,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            &[],
+        )
+        .unwrap();
+
+        // Raydium before Whirlpool, and only the two tick arrays the buy crosses
+        let expected: Vec<Pubkey> = raydium
+            .get_swap_accounts_for(&wallet.pubkey(), SwapDirection::Sell)
+            .into_iter()
+            .chain(whirlpool.get_swap_accounts_for(&wallet.pubkey(), SwapDirection::Buy))
+            .map(|meta| meta.pubkey)
+            .collect();
+        let pool_accounts: Vec<Pubkey> = ix.accounts[10..].iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(pool_accounts, expected);
+        assert_eq!(pool_accounts.len(), 5 + 7);
+    }
+
+    #[test]
+    fn test_priority_fee_lamports_rounds_up() {
+        assert_eq!(priority_fee_lamports(200_000, 1_000), 200);
+        assert_eq!(priority_fee_lamports(1, 1), 1);
+        assert_eq!(priority_fee_lamports(1_400_000, 0), 0);
+    }
+
+    #[test]
+    fn test_compute_budget_auto_raise() {
+        assert_eq!(required_compute_unit_limit(500_000, 600_000, false).unwrap(), 600_000);
+        assert_eq!(required_compute_unit_limit(750_000, 600_000, true).unwrap(), 750_000);
+
+        assert!(matches!(
+            required_compute_unit_limit(750_000, 600_000, false),
+            Err(BotError::ComputeBudgetExceeded {
+                needed: 750_000,
+                configured: 600_000
+            })
+        ));
+        // Never raised past Solana's cap
+        assert!(matches!(
+            required_compute_unit_limit(1_500_000, 600_000, true),
+            Err(BotError::ComputeBudgetExceeded { needed: 1_500_000, .. })
+        ));
+    }
+
+    #[test]
+    fn test_estimated_compute_unit_limit_follows_the_route_dexes() {
+        use crate::constants::DEFAULT_COMPUTE_UNIT_OVERHEAD;
+        use crate::dex::pump::initializer::PumpPool;
+        use crate::dex::raydium::clmm_initializer::RaydiumClmmPool;
+
+        let mint = Pubkey::new_unique();
+        let mut config: Config = toml::from_str(&format!(
+            r#"
+            [bot]
+            compute_unit_limit = 600000
+            estimate_compute_unit_limit = true
+
+            [routing]
+            [[routing.mint_config_list]]
+            mint = "{}"
+            process_delay = 100
+
+            [rpc]
+            url = "https://api.mainnet-beta.solana.com"
+
+            [wallet]
+            private_key = "test"
+            "#,
+            mint
+        ))
+        .unwrap();
+        let wallet = Pubkey::new_unique().to_string();
+        let mut pump_route = MintPoolData::new(&mint.to_string(), &wallet, token_program_id).unwrap();
+        let mut clmm_route = pump_route.clone();
+        for _ in 0..2 {
+            pump_route.pump_pools.push(PumpPool {
+                pool: Pubkey::new_unique(),
+                token_vault: Pubkey::new_unique(),
+                sol_vault: Pubkey::new_unique(),
+                fee_token_wallet: Pubkey::new_unique(),
+                coin_creator_vault_ata: Pubkey::new_unique(),
+                coin_creator_vault_authority: Pubkey::new_unique(),
+                base_mint: mint,
+                quote_mint: sol_mint(),
+                token_reserve: 0,
+                sol_reserve: 0,
+            });
+        }
+        for _ in 0..3 {
+            clmm_route.raydium_clmm_pools.push(RaydiumClmmPool {
+                pool: Pubkey::new_unique(),
+                amm_config: Pubkey::new_unique(),
+                observation_state: Pubkey::new_unique(),
+                token_vault: Pubkey::new_unique(),
+                sol_vault: Pubkey::new_unique(),
+                tick_arrays: (0..3).map(|_| Pubkey::new_unique()).collect(),
+                current_tick: 0,
+                liquidity: 0,
+                sqrt_price_x64: 0,
+                fee_bps: 25,
+                sol_is_token_0: false,
+            });
+        }
+
+        let (pump_limit, estimated) = route_compute_unit_limit(&config, &pump_route);
+        assert!(estimated);
+        assert_eq!(pump_limit, 2 * STANDARD_POOL_COMPUTE_UNITS + DEFAULT_COMPUTE_UNIT_OVERHEAD);
+        let (clmm_limit, _) = route_compute_unit_limit(&config, &clmm_route);
+        assert_eq!(clmm_limit, 3 * CONCENTRATED_POOL_COMPUTE_UNITS + DEFAULT_COMPUTE_UNIT_OVERHEAD);
+        assert!(pump_limit < clmm_limit);
+        assert!(clmm_limit < config.bot.compute_unit_limit);
+
+        // Configured per-DEX costs replace the built-in ones
+        config.bot.dex_compute_units = Some(HashMap::from([("Pump.fun".to_string(), 30_000)]));
+        assert_eq!(
+            route_compute_unit_limit(&config, &pump_route),
+            (2 * 30_000 + DEFAULT_COMPUTE_UNIT_OVERHEAD, true)
+        );
+
+        // A mint's own limit wins and isn't refined
+        config.routing.mint_config_list[0].compute_unit_limit = Some(250_000);
+        assert_eq!(route_compute_unit_limit(&config, &clmm_route), (250_000, false));
+        assert_eq!(compute_unit_budget(&config, &mint), 250_000);
+
+        config.routing.mint_config_list[0].compute_unit_limit = None;
+        config.bot.estimate_compute_unit_limit = Some(false);
+        assert_eq!(route_compute_unit_limit(&config, &pump_route), (600_000, false));
+
+        // A quoted route is costed at that limit before it is sent
+        let fees = estimated_fees(&config, &pump_route, true);
+        assert_eq!(fees.priority_fee_lamports, priority_fee_lamports(600_000, DEFAULT_COMPUTE_UNIT_PRICE));
+        assert_eq!(fees.base_fee_lamports, BASE_FEE_LAMPORTS_PER_SIGNATURE);
+        assert_eq!(fees.tip_lamports, JITO_TIP_LAMPORTS);
+        assert_eq!(estimated_fees(&config, &pump_route, false).tip_lamports, 0);
+    }
+
+    #[test]
+    fn test_refined_compute_unit_limit_adds_a_margin() {
+        assert_eq!(refined_compute_unit_limit(200_000, 130_000).unwrap(), 220_000);
+        // Never past Solana's cap
+        assert_eq!(
+            refined_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT as u64 - 1, 130_000).unwrap(),
+            MAX_COMPUTE_UNIT_LIMIT
+        );
+        assert!(matches!(
+            refined_compute_unit_limit(1_500_000, 130_000),
+            Err(BotError::ComputeBudgetExceeded { needed: 1_500_000, .. })
+        ));
+    }
+
+    #[test]
+    fn test_wrap_and_unwrap_bracket_swap() {
+        let wallet = Keypair::new();
+        let mint_pool_data = MintPoolData::new(
+            &Pubkey::new_unique().to_string(),
+            &wallet.pubkey().to_string(),
+            token_program_id,
+        )
+        .unwrap();
+        let config: Config = toml::from_str(
+            r#"
+            [bot]
+            compute_unit_limit = 600000
+            auto_wrap_sol = true
+            wrap_sol_lamports = 1000000
+
+            [routing]
+            mint_config_list = []
+
+            [rpc]
+            url = "https://api.mainnet-beta.solana.com"
+
+            [wallet]
+            private_key = "test"
+            "#,
+        )
+        .unwrap();
+        let tip_account = Pubkey::new_unique();
+
+        let instructions =
+            build_instructions(
+                &wallet,
+                &config,
+                &mint_pool_data,
+                Some(tip_account),
+                config.bot.compute_unit_limit,
+                None,
+                wrap_amount(&config, 0),
+            )
+            .unwrap();
+        let programs: Vec<Pubkey> = instructions.iter().map(|ix| ix.program_id).collect();
+        let wsol_account = mint_pool_data.wallet_wsol_account;
+
+        assert_eq!(
+            programs[2..],
+            [
+                associated_token_program_id,
+                system_program::ID,
+                token_program_id,
+                config.executor_program_id(),
+                system_program::ID,
+                token_program_id,
+            ]
+        );
+        assert_eq!(
+            instructions[3],
+            system_instruction::transfer(&wallet.pubkey(), &wsol_account, 1_000_000)
+        );
+        assert_eq!(
+            instructions[4],
+            spl_token::instruction::sync_native(&token_program_id, &wsol_account).unwrap()
+        );
+        assert_eq!(instructions[6].accounts[1].pubkey, tip_account);
+        // Close goes last and returns rent to the wallet
+        let close_ix = instructions.last().unwrap();
+        assert_eq!(close_ix.accounts[0].pubkey, wsol_account);
+        assert_eq!(close_ix.accounts[1].pubkey, wallet.pubkey());
+
+        // A quoted route wraps exactly its input
+        assert_eq!(wrap_amount(&config, 250_000_000), Some(250_000_000));
+        let mut manual = config.clone();
+        manual.bot.auto_wrap_sol = Some(false);
+        assert_eq!(wrap_amount(&manual, 250_000_000), None);
+    }
+
+    fn transfer_ix(payer: &Pubkey) -> Instruction {
+        system_instruction::transfer(payer, &Pubkey::new_unique(), 1)
+    }
+
+    struct FlakySender {
+        failures_remaining: std::sync::atomic::AtomicU32,
+        attempts: std::sync::atomic::AtomicU32,
+    }
+
+    impl TransactionSender for FlakySender {
+        fn send(&self, tx: &VersionedTransaction, _max_retries: u64) -> Result<Signature, BotError> {
+            use std::sync::atomic::Ordering;
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            if self.failures_remaining.load(Ordering::SeqCst) > 0 {
+                self.failures_remaining.fetch_sub(1, Ordering::SeqCst);
+                return Err(BotError::TransactionSendError("node is behind".to_string()));
+            }
+            Ok(tx.signatures[0])
+        }
+
+        fn is_blockhash_valid(&self, _blockhash: &Hash) -> Result<bool, BotError> {
+            Ok(true)
+        }
+
+        fn latest_blockhash(&self) -> Result<Hash, BotError> {
+            Ok(Hash::new_unique())
+        }
+
+        fn is_confirmed(&self, _signature: &Signature) -> Result<bool, BotError> {
+            Ok(false)
+        }
+
+        fn simulate(&self, _tx: &VersionedTransaction) -> Result<Option<TransactionError>, BotError> {
+            Ok(None)
+        }
+    }
+
+    /// Returns the queued statuses one poll at a time, then keeps returning the last
+    struct MockStatuses {
+        statuses: std::sync::Mutex<Vec<Option<TransactionStatus>>>,
+    }
+
+    impl SignatureStatusSource for MockStatuses {
+        fn signature_status(&self, _signature: &Signature) -> Result<Option<TransactionStatus>, BotError> {
+            let mut statuses = self.statuses.lock().unwrap();
+            if statuses.len() > 1 {
+                return Ok(statuses.remove(0));
+            }
+            Ok(statuses.first().cloned().flatten())
+        }
+    }
+
+    fn status(
+        slot: u64,
+        confirmation_status: TransactionConfirmationStatus,
+        err: Option<TransactionError>,
+    ) -> Option<TransactionStatus> {
+        Some(TransactionStatus {
+            slot,
+            confirmations: Some(1),
+            status: err.clone().map_or(Ok(()), Err),
+            err,
+            confirmation_status: Some(confirmation_status),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_confirm_signature_waits_for_commitment() {
+        let rpc = MockStatuses {
+            statuses: std::sync::Mutex::new(vec![
+                None,
+                status(100, TransactionConfirmationStatus::Processed, None),
+                status(100, TransactionConfirmationStatus::Confirmed, None),
+            ]),
+        };
+
+        let slot = confirm_signature(
+            &rpc,
+            &Signature::new_unique(),
+            CommitmentConfig::confirmed(),
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+        assert_eq!(slot, 100);
+    }
+
+    #[tokio::test]
+    async fn test_confirm_signature_times_out_or_fails() {
+        let signature = Signature::new_unique();
+        let processed_only = MockStatuses {
+            statuses: std::sync::Mutex::new(vec![status(
+                100,
+                TransactionConfirmationStatus::Processed,
+                None,
+            )]),
+        };
+        let result = confirm_signature(
+            &processed_only,
+            &signature,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(CONFIRM_POLL_INTERVAL_MS),
+        )
+        .await;
+        assert!(result.unwrap_err().to_string().contains("not Confirmed"));
+
+        let failed = MockStatuses {
+            statuses: std::sync::Mutex::new(vec![status(
+                101,
+                TransactionConfirmationStatus::Confirmed,
+                Some(TransactionError::InstructionError(2, InstructionError::Custom(6001))),
+            )]),
+        };
+        let result =
+            confirm_signature(&failed, &signature, CommitmentConfig::confirmed(), Duration::ZERO).await;
+        assert!(result.unwrap_err().to_string().contains("failed in slot 101"));
+    }
+
+    /// Counts the status polls made through it
+    struct CountingStatuses {
+        inner: MockStatuses,
+        polls: std::sync::Mutex<usize>,
+    }
+
+    impl SignatureStatusSource for CountingStatuses {
+        fn signature_status(&self, signature: &Signature) -> Result<Option<TransactionStatus>, BotError> {
+            *self.polls.lock().unwrap() += 1;
+            self.inner.signature_status(signature)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_path_awaits_per_confirmation_mode() {
+        let rpc = || CountingStatuses {
+            inner: MockStatuses {
+                statuses: std::sync::Mutex::new(vec![
+                    None,
+                    status(100, TransactionConfirmationStatus::Processed, None),
+                    status(100, TransactionConfirmationStatus::Confirmed, None),
+                    status(100, TransactionConfirmationStatus::Finalized, None),
+                ]),
+            },
+            polls: std::sync::Mutex::new(0),
+        };
+        let signature = Signature::new_unique();
+        let confirmed = CommitmentConfig::confirmed();
+        let timeout = Duration::from_secs(5);
+
+        // "none", and spam without a mode, never poll
+        for (mode, spam_enabled) in [(Some(ConfirmationMode::None), false), (None, true)] {
+            let wait = confirmation_wait(mode, spam_enabled, confirmed);
+            assert_eq!(wait, None);
+            let rpc = rpc();
+            assert!(await_confirmation(&rpc, &signature, wait, timeout).await.is_none());
+            assert_eq!(*rpc.polls.lock().unwrap(), 0);
+        }
+
+        // Each later mode polls until its commitment shows up
+        let cases = [
+            (Some(ConfirmationMode::Signature), 2),
+            (None, 3),
+            (Some(ConfirmationMode::Finalized), 4),
+        ];
+        for (mode, polls) in cases {
+            let wait = confirmation_wait(mode, false, confirmed);
+            let rpc = rpc();
+            let slot = await_confirmation(&rpc, &signature, wait, timeout).await.unwrap().unwrap();
+            assert_eq!(slot, 100);
+            assert_eq!(*rpc.polls.lock().unwrap(), polls, "{:?}", mode);
+        }
+        // An explicit mode applies with spam enabled too
+        assert_eq!(
+            confirmation_wait(Some(ConfirmationMode::Signature), true, confirmed),
+            Some(CommitmentConfig::processed())
+        );
+    }
+
+    #[test]
+    fn test_recent_signatures_remember_and_evict_oldest() {
+        let recent = RecentSignatures::new(2, Duration::from_secs(60));
+        let start = Instant::now();
+        let (first, second, third) = (Signature::new_unique(), Signature::new_unique(), Signature::new_unique());
+
+        assert!(recent.remember(first, start));
+        // The same transaction again within the window is a duplicate
+        assert!(!recent.remember(first, start + Duration::from_secs(1)));
+        assert!(recent.remember(second, start + Duration::from_secs(2)));
+        assert!(recent.remember(third, start + Duration::from_secs(3)));
+
+        // Over capacity, so the oldest was let go
+        assert_eq!(recent.len(), 2);
+        assert!(!recent.contains(&first));
+        assert!(recent.contains(&second) && recent.contains(&third));
+
+        // Nothing is remembered past the window
+        assert_eq!(recent.unconfirmed(start + Duration::from_secs(62)), vec![third]);
+        assert!(recent.remember(second, start + Duration::from_secs(62)));
+    }
+
+    #[test]
+    fn test_poll_resolves_landed_signatures() {
+        let recent = RecentSignatures::new(16, Duration::from_secs(60));
+        let now = Instant::now();
+        let (landed, pending) = (Signature::new_unique(), Signature::new_unique());
+        recent.remember(landed, now);
+        recent.remember(pending, now);
+        let rpc = MockStatuses {
+            statuses: std::sync::Mutex::new(vec![
+                status(100, TransactionConfirmationStatus::Confirmed, None),
+                None,
+            ]),
+        };
+
+        assert_eq!(poll_recent_confirmations(&recent, &rpc, CommitmentConfig::confirmed(), now), 1);
+        assert_eq!(recent.unconfirmed(now), vec![pending]);
+        // Still remembered, so it won't be sent again
+        assert!(!recent.remember(landed, now));
+        assert_eq!(poll_recent_confirmations(&recent, &rpc, CommitmentConfig::confirmed(), now), 0);
+    }
+
+    /// Drops every send except the `confirm_send`-th (1-based); simulation fails unless `profitable`
+    struct DroppingSender {
+        sent: std::sync::Mutex<Vec<Signature>>,
+        confirm_send: usize,
+        profitable: bool,
+    }
+
+    impl TransactionSender for DroppingSender {
+        fn send(&self, tx: &VersionedTransaction, _max_retries: u64) -> Result<Signature, BotError> {
+            self.sent.lock().unwrap().push(tx.signatures[0]);
+            Ok(tx.signatures[0])
+        }
+
+        fn is_blockhash_valid(&self, _blockhash: &Hash) -> Result<bool, BotError> {
+            Ok(true)
+        }
+
+        fn latest_blockhash(&self) -> Result<Hash, BotError> {
+            Ok(Hash::new_unique())
+        }
+
+        fn is_confirmed(&self, signature: &Signature) -> Result<bool, BotError> {
+            let sent = self.sent.lock().unwrap();
+            Ok(sent.get(self.confirm_send - 1) == Some(signature))
+        }
+
+        fn simulate(&self, _tx: &VersionedTransaction) -> Result<Option<TransactionError>, BotError> {
+            Ok((!self.profitable)
+                .then(|| TransactionError::InstructionError(2, InstructionError::Custom(6001))))
+        }
+    }
+
+    fn transfer_tx_builder(
+        payer: &Keypair,
+    ) -> impl Fn(Hash) -> anyhow::Result<VersionedTransaction> + '_ {
+        move |blockhash: Hash| {
+            let message = compile_message(
+                &payer.pubkey(),
+                &[transfer_ix(&payer.pubkey())],
+                &[],
+                blockhash,
+                TransactionVersion::V0,
+            )?;
+            Ok(VersionedTransaction::try_new(message, &[payer])?)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dropped_send_is_resent_and_confirmed() {
+        let payer = Keypair::new();
+        let build_tx = transfer_tx_builder(&payer);
+        let sender = DroppingSender {
+            sent: Default::default(),
+            confirm_send: 2,
+            profitable: true,
+        };
+
+        let first = sender.send(&build_tx(Hash::new_unique()).unwrap(), 0).unwrap();
+        let window = Duration::from_millis(20);
+        let poll = Duration::from_millis(5);
+        let outcome = resend_until_confirmed(&sender, &build_tx, first, 3, window, poll, 0)
+            .await
+            .unwrap();
+
+        let sent = sender.sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_ne!(sent[0], sent[1]);
+        assert_eq!(outcome, ResendOutcome::Confirmed(sent[1]));
+        // The resend is remembered, so poll_sent_confirmations tracks it too
+        assert!(RECENT_SIGNATURES.contains(&sent[1]));
+    }
+
+    #[tokio::test]
+    async fn test_resend_cancelled_when_unprofitable() {
+        let payer = Keypair::new();
+        let build_tx = transfer_tx_builder(&payer);
+        let sender = DroppingSender {
+            sent: Default::default(),
+            confirm_send: 2,
+            profitable: false,
+        };
+
+        let first = sender.send(&build_tx(Hash::new_unique()).unwrap(), 0).unwrap();
+        let window = Duration::from_millis(20);
+        let poll = Duration::from_millis(5);
+        let outcome = resend_until_confirmed(&sender, &build_tx, first, 3, window, poll, 0)
+            .await
+            .unwrap();
+
+        assert_eq!(outcome, ResendOutcome::OpportunityGone);
+        assert_eq!(sender.sent.lock().unwrap().len(), 1);
+    }
+
+    /// Accepts or rejects every transaction after `latency`
+    struct TimedSender {
+        latency: Duration,
+        accepts: bool,
+        sends: std::sync::atomic::AtomicU32,
+    }
+
+    impl TimedSender {
+        fn new(latency_ms: u64, accepts: bool) -> Arc<Self> {
+            Arc::new(Self {
+                latency: Duration::from_millis(latency_ms),
+                accepts,
+                sends: 0.into(),
+            })
+        }
+
+        fn sends(&self) -> u32 {
+            self.sends.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    impl TransactionSender for TimedSender {
+        fn send(&self, tx: &VersionedTransaction, _max_retries: u64) -> Result<Signature, BotError> {
+            self.sends.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            std::thread::sleep(self.latency);
+            if self.accepts {
+                Ok(tx.signatures[0])
+            } else {
+                Err(BotError::TransactionSendError("rejected".to_string()))
+            }
+        }
+
+        fn is_blockhash_valid(&self, _blockhash: &Hash) -> Result<bool, BotError> {
+            Ok(true)
+        }
+
+        fn latest_blockhash(&self) -> Result<Hash, BotError> {
+            Ok(Hash::new_unique())
+        }
+
+        fn is_confirmed(&self, _signature: &Signature) -> Result<bool, BotError> {
+            Ok(false)
+        }
+
+        fn simulate(&self, _tx: &VersionedTransaction) -> Result<Option<TransactionError>, BotError> {
+            Ok(None)
+        }
+    }
+
+    async fn dispatch_to(strategy: SendStrategy, senders: &[Arc<TimedSender>]) -> Vec<usize> {
+        let payer: WalletSigner = Arc::new(Keypair::new());
+        let build_tx: TransactionBuilder = Arc::new(move |blockhash| {
+            let instructions = [transfer_ix(&payer.pubkey())];
+            sign_transaction(payer.as_ref(), &instructions, &[], blockhash, TransactionVersion::V0)
+        });
+        let blockhash = Hash::new_unique();
+        let tx = build_tx(blockhash).unwrap();
+        dispatch_transaction(strategy, senders, &build_tx, tx, blockhash, &[], 0, 0)
+            .await
+            .into_iter()
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Reports a fixed blockhash and records the blockhash of every transaction it sends
+    struct PinnedBlockhashSender {
+        blockhash: Hash,
+        sent: std::sync::Mutex<Vec<Hash>>,
+    }
+
+    impl PinnedBlockhashSender {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                blockhash: Hash::new_unique(),
+                sent: std::sync::Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    impl TransactionSender for PinnedBlockhashSender {
+        fn send(&self, tx: &VersionedTransaction, _max_retries: u64) -> Result<Signature, BotError> {
+            self.sent.lock().unwrap().push(*tx.message.recent_blockhash());
+            Ok(tx.signatures[0])
+        }
+
+        fn is_blockhash_valid(&self, _blockhash: &Hash) -> Result<bool, BotError> {
+            Ok(true)
+        }
+
+        fn latest_blockhash(&self) -> Result<Hash, BotError> {
+            Ok(self.blockhash)
+        }
+
+        fn is_confirmed(&self, _signature: &Signature) -> Result<bool, BotError> {
+            Ok(false)
+        }
+
+        fn simulate(&self, _tx: &VersionedTransaction) -> Result<Option<TransactionError>, BotError> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_each_sender_signs_with_its_own_blockhash() {
+        let payer: WalletSigner = Arc::new(Keypair::new());
+        let build_tx: TransactionBuilder = Arc::new(move |blockhash| {
+            let instructions = [transfer_ix(&payer.pubkey())];
+            sign_transaction(payer.as_ref(), &instructions, &[], blockhash, TransactionVersion::V0)
+        });
+        let read_blockhash = Hash::new_unique();
+        let tx = build_tx(read_blockhash).unwrap();
+        let senders = [PinnedBlockhashSender::new(), PinnedBlockhashSender::new()];
+        // What the refresher stored for each endpoint
+        let cache = SenderBlockhashCache::new(Duration::from_secs(60));
+        for (i, sender) in senders.iter().enumerate() {
+            cache.store(&format!("sender-{}", i), sender.blockhash);
+        }
+
+        for _ in 0..2 {
+            let sender_blockhashes: Vec<Hash> = (0..senders.len())
+                .map(|i| cache.blockhash(&format!("sender-{}", i)).unwrap())
+                .collect();
+            let accepted = dispatch_transaction(
+                SendStrategy::Broadcast,
+                &senders,
+                &build_tx,
+                tx.clone(),
+                read_blockhash,
+                &sender_blockhashes,
+                0,
+                0,
+            )
+            .await;
+            assert_eq!(accepted.len(), 2);
+        }
+
+        for sender in &senders {
+            assert_eq!(*sender.sent.lock().unwrap(), vec![sender.blockhash; 2]);
+        }
+
+        // Endpoints not refreshed yet, or not within the TTL, use the read RPC's blockhash
+        assert_eq!(cache.blockhash("sender-2"), None);
+        let expired = SenderBlockhashCache::new(Duration::ZERO);
+        expired.store("sender-0", senders[0].blockhash);
+        assert_eq!(expired.blockhash("sender-0"), None);
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_sends_to_every_endpoint() {
+        let senders = [TimedSender::new(50, true), TimedSender::new(0, false), TimedSender::new(10, true)];
+
+        assert_eq!(dispatch_to(SendStrategy::Broadcast, &senders).await, vec![0, 2]);
+        assert!(senders.iter().all(|sender| sender.sends() == 1));
+    }
+
+    #[tokio::test]
+    async fn test_first_success_stops_at_first_accepting_endpoint() {
+        let senders = [TimedSender::new(0, false), TimedSender::new(50, true), TimedSender::new(10, true)];
+
+        assert_eq!(dispatch_to(SendStrategy::FirstSuccess, &senders).await, vec![1]);
+        assert_eq!(senders[2].sends(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_fastest_returns_first_accepted_signature() {
+        let senders = [TimedSender::new(500, true), TimedSender::new(20, true), TimedSender::new(0, false)];
+
+        let start = Instant::now();
+        assert_eq!(dispatch_to(SendStrategy::Fastest, &senders).await, vec![1]);
+        // The slow endpoint is not waited for
+        assert!(start.elapsed() < Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn test_send_retries_until_success() {
+        let payer = Keypair::new();
+        let build_tx = |blockhash: Hash| -> anyhow::Result<VersionedTransaction> {
+            let message = compile_message(
+                &payer.pubkey(),
+                &[transfer_ix(&payer.pubkey())],
+                &[],
+                blockhash,
+                TransactionVersion::V0,
+            )?;
+            Ok(VersionedTransaction::try_new(message, &[&payer])?)
+        };
+        let sender = FlakySender {
+            failures_remaining: 2.into(),
+            attempts: 0.into(),
+        };
+
+        let blockhash = Hash::new_unique();
+        let tx = build_tx(blockhash).unwrap();
+        let result = send_transaction_with_retries(&sender, &build_tx, tx, blockhash, 5, 0).await;
+
+        assert!(result.is_ok());
+        assert_eq!(sender.attempts.into_inner(), 3);
+    }
+
+    /// Fails each send with the next scripted error, then accepts
+    struct ScriptedSender {
+        errors: std::sync::Mutex<std::collections::VecDeque<&'static str>>,
+        sends: std::sync::atomic::AtomicU32,
+    }
+
+    impl ScriptedSender {
+        fn new(errors: &[&'static str]) -> Self {
+            Self {
+                errors: std::sync::Mutex::new(errors.iter().copied().collect()),
+                sends: 0.into(),
+            }
+        }
+    }
+
+    impl TransactionSender for ScriptedSender {
+        fn send(&self, tx: &VersionedTransaction, _max_retries: u64) -> Result<Signature, BotError> {
+            self.sends.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            match self.errors.lock().unwrap().pop_front() {
+                Some(error) => Err(BotError::TransactionSendError(error.to_string())),
+                None => Ok(tx.signatures[0]),
+            }
+        }
+
+        fn is_blockhash_valid(&self, _blockhash: &Hash) -> Result<bool, BotError> {
+            Ok(true)
+        }
+
+        fn latest_blockhash(&self) -> Result<Hash, BotError> {
+            Ok(Hash::new_unique())
+        }
+
+        fn is_confirmed(&self, _signature: &Signature) -> Result<bool, BotError> {
+            Ok(false)
+        }
+
+        fn simulate(&self, _tx: &VersionedTransaction) -> Result<Option<TransactionError>, BotError> {
+            Ok(None)
+        }
+    }
+
+    const ALREADY_PROCESSED: &str =
+        "Transaction simulation failed: This transaction has already been processed";
+    const ACCOUNT_IN_USE: &str = "Transaction simulation failed: Account in use";
+
+    async fn send_scripted(
+        sender: &ScriptedSender,
+        max_retries: u64,
+        account_in_use_retries: u32,
+    ) -> (anyhow::Result<Signature>, Signature) {
+        let payer = Keypair::new();
+        let build_tx = |blockhash: Hash| -> anyhow::Result<VersionedTransaction> {
+            let instructions = [transfer_ix(&payer.pubkey())];
+            sign_transaction(&payer, &instructions, &[], blockhash, TransactionVersion::V0)
+        };
+        let blockhash = Hash::new_unique();
+        let tx = build_tx(blockhash).unwrap();
+        let signature = tx.signatures[0];
+        let result =
+            send_transaction_with_retries(sender, &build_tx, tx, blockhash, max_retries, account_in_use_retries)
+                .await;
+        (result, signature)
+    }
+
+    #[test]
+    fn test_classify_duplicate_send_errors() {
+        let classify = |message: &str| {
+            DuplicateSendError::classify(&BotError::TransactionSendError(message.to_string()))
+        };
+        assert_eq!(classify(ALREADY_PROCESSED), Some(DuplicateSendError::AlreadyProcessed));
+        assert_eq!(classify("AlreadyProcessed"), Some(DuplicateSendError::AlreadyProcessed));
+        assert_eq!(classify(ACCOUNT_IN_USE), Some(DuplicateSendError::AccountInUse));
+        assert_eq!(classify("AccountInUse"), Some(DuplicateSendError::AccountInUse));
+        assert_eq!(classify("node is behind"), None);
+    }
+
+    #[tokio::test]
+    async fn test_already_processed_is_confirmed_not_resent() {
+        let sender = ScriptedSender::new(&[ALREADY_PROCESSED]);
+        let before = METRICS.sends_already_processed.load(std::sync::atomic::Ordering::Relaxed);
+
+        let (result, signature) = send_scripted(&sender, 5, 0).await;
+
+        assert_eq!(result.unwrap(), signature);
+        assert_eq!(sender.sends.into_inner(), 1);
+        assert!(METRICS.sends_already_processed.load(std::sync::atomic::Ordering::Relaxed) > before);
+    }
+
+    #[tokio::test]
+    async fn test_account_in_use_retried_without_using_max_retries() {
+        let sender = ScriptedSender::new(&[ACCOUNT_IN_USE, ACCOUNT_IN_USE]);
+        let before = METRICS.sends_account_in_use.load(std::sync::atomic::Ordering::Relaxed);
+
+        // No ordinary retries allowed, yet both in-use rejections are resent
+        let (result, signature) = send_scripted(&sender, 0, 3).await;
+
+        assert_eq!(result.unwrap(), signature);
+        assert_eq!(sender.sends.into_inner(), 3);
+        assert!(METRICS.sends_account_in_use.load(std::sync::atomic::Ordering::Relaxed) >= before + 2);
+
+        let sender = ScriptedSender::new(&[ACCOUNT_IN_USE, ACCOUNT_IN_USE]);
+        let (result, _) = send_scripted(&sender, 5, 1).await;
+        assert!(result.is_err());
+        assert_eq!(sender.sends.into_inner(), 2);
+    }
+
+    #[test]
+    fn test_diagnose_account_not_writable_names_dex() {
+        let mut dlmm = candidate(8, 10);
+        dlmm.dex = "Meteora DLMM";
+        let raydium = candidate(8, 10);
+        let oracle = dlmm.accounts[3];
+
+        let err = TransactionError::InstructionError(2, InstructionError::PrivilegeEscalation);
+        let logs = vec![
+            "Program MEViEnscUm6tsQRoGd9h6nLQaQspKj7DB2M5FwM3Xvz invoke [1]".to_string(),
+            format!("{}'s writable privilege escalated", oracle),
+        ];
+
+        let mapped = diagnose_account_flag_error(&err, &logs, &[raydium, dlmm.clone()]).unwrap();
+        match mapped {
+            BotError::PoolValidationError(message) => {
+                assert!(message.contains("Meteora DLMM"));
+                assert!(message.contains(&dlmm.pool.to_string()));
+                assert!(message.contains(&oracle.to_string()));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_diagnose_ignores_unrelated_failures() {
+        let err = TransactionError::InstructionError(2, InstructionError::Custom(6001));
+        let logs = vec!["Program log: not profitable".to_string()];
+
+        assert!(diagnose_account_flag_error(&err, &logs, &[candidate(8, 10)]).is_none());
+    }
+
+    #[test]
+    fn test_compile_message_v0() {
+        let payer = Pubkey::new_unique();
+        let message = compile_message(
+            &payer,
+            &[transfer_ix(&payer)],
+            &[],
+            Hash::default(),
+            TransactionVersion::V0,
+        )
+        .unwrap();
+
+        assert!(matches!(message, VersionedMessage::V0(_)));
+    }
+
+    #[test]
+    fn test_compile_message_legacy() {
+        let payer = Pubkey::new_unique();
+        let message = compile_message(
+            &payer,
+            &[transfer_ix(&payer)],
+            &[],
+            Hash::default(),
+            TransactionVersion::Legacy,
+        )
+        .unwrap();
+
+        assert!(matches!(message, VersionedMessage::Legacy(_)));
+    }
+
+    #[test]
+    fn test_compile_message_legacy_rejects_lookup_tables() {
+        let payer = Pubkey::new_unique();
+        let lookup_table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![Pubkey::new_unique()],
+        };
+
+        assert!(compile_message(
+            &payer,
+            &[transfer_ix(&payer)],
+            &[lookup_table],
+            Hash::default(),
+            TransactionVersion::Legacy,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_compile_message_rejects_too_many_lookup_tables() {
+        let payer = Pubkey::new_unique();
+        let tables: Vec<AddressLookupTableAccount> = (0..MAX_LOOKUP_TABLES_PER_TRANSACTION + 1)
+            .map(|_| AddressLookupTableAccount {
+                key: Pubkey::new_unique(),
+                addresses: vec![Pubkey::new_unique()],
+            })
+            .collect();
+
+        let err = compile_message(
+            &payer,
+            &[transfer_ix(&payer)],
+            &tables,
+            Hash::default(),
+            TransactionVersion::V0,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BotError>(),
+            Some(BotError::LookupTableLimitExceeded { tables, max })
+                if *tables == MAX_LOOKUP_TABLES_PER_TRANSACTION + 1
+                    && *max == MAX_LOOKUP_TABLES_PER_TRANSACTION
+        ));
+        assert!(compile_message(
+            &payer,
+            &[transfer_ix(&payer)],
+            &tables[..MAX_LOOKUP_TABLES_PER_TRANSACTION],
+            Hash::default(),
+            TransactionVersion::V0,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_compile_message_rejects_too_many_referenced_addresses() {
+        let payer = Pubkey::new_unique();
+        let addresses: Vec<Pubkey> = (0..MAX_TRANSACTION_ACCOUNTS).map(|_| Pubkey::new_unique()).collect();
+        let instruction = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: addresses.iter().map(|key| AccountMeta::new(*key, false)).collect(),
+            data: vec![],
+        };
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses,
+        };
+
+        // Every address resolves through the table, but the payer and program push the
+        // total over the account limit
+        let err = compile_message(&payer, &[instruction], &[table], Hash::default(), TransactionVersion::V0)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BotError>(),
+            Some(BotError::LookupTableAddressLimitExceeded { addresses, max })
+                if *addresses == MAX_TRANSACTION_ACCOUNTS + 2 && *max == MAX_TRANSACTION_ACCOUNTS
+        ));
+    }
+
+    #[test]
+    fn test_select_pools_prefers_profit_per_account() {
+        let dense = candidate(5, 100);
+        let sparse = candidate(30, 100);
+        let selection = select_pools(&[sparse.clone(), dense.clone()], &[], &[], 1_400_000);
+
+        // The cheaper pool is taken first and the larger one no longer fits
+        assert_eq!(selection.selected, vec![dense.pool]);
+        assert_eq!(selection.skipped, vec![sparse.pool]);
+        assert!(selection.account_count <= MAX_TRANSACTION_ACCOUNTS);
+        assert!(selection.estimated_size <= MAX_TRANSACTION_SIZE_BYTES);
+    }
+
+    #[test]
+    fn test_select_pools_respects_account_limit() {
+        let candidates: Vec<_> = (0..10).map(|_| candidate(12, 10)).collect();
+        let selection = select_pools(&candidates, &[], &[], 1_400_000);
+
+        assert!(selection.account_count <= MAX_TRANSACTION_ACCOUNTS);
+        assert!(!selection.skipped.is_empty());
+        assert_eq!(selection.selected.len() + selection.skipped.len(), 10);
+    }
+
+    #[test]
+    fn test_select_pools_lookup_tables_extend_capacity() {
+        let candidates: Vec<_> = (0..6).map(|_| candidate(8, 10)).collect();
+        let without_tables = select_pools(&candidates, &[], &[], 1_400_000);
+
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: candidates.iter().flat_map(|c| c.accounts.clone()).collect(),
+        };
+        let with_tables = select_pools(&candidates, &[], &[table], 1_400_000);
+
+        assert!(with_tables.selected.len() > without_tables.selected.len());
+    }
+
+    #[test]
+    fn test_select_pools_respects_compute_limit() {
+        let candidates: Vec<_> = (0..4).map(|_| candidate(2, 10)).collect();
+        let selection = select_pools(&candidates, &[], &[], STANDARD_POOL_COMPUTE_UNITS * 2);
+
+        assert_eq!(selection.selected.len(), 2);
+        assert_eq!(selection.compute_units, STANDARD_POOL_COMPUTE_UNITS * 2);
+    }
+
+    #[test]
+    fn test_pools_split_into_capped_transactions() {
+        let wallet = Keypair::new();
+        let mut mint_pool_data = MintPoolData::new(
+            &Pubkey::new_unique().to_string(),
+            &wallet.pubkey().to_string(),
+            token_program_id,
+        )
+        .unwrap();
+        for _ in 0..5 {
+            mint_pool_data.raydium_pools.push(crate::dex::raydium::RaydiumCpmmPool {
+                pool: Pubkey::new_unique(),
+                token_vault: Pubkey::new_unique(),
+                sol_vault: Pubkey::new_unique(),
+                coin_mint: mint_pool_data.mint,
+                pc_mint: sol_mint(),
+                token_reserve: 0,
+                sol_reserve: 0,
+            });
+        }
+        let config: Config = toml::from_str(
+            r#"
+            [bot]
+            compute_unit_limit = 600000
+            auto_wrap_sol = true
+            wrap_sol_lamports = 1000000
+            max_pools_per_tx = 2
+
+            [routing]
+            mint_config_list = []
+
+            [rpc]
+            url = "https://api.mainnet-beta.solana.com"
+
+            [wallet]
+            private_key = "test"
+            "#,
+        )
+        .unwrap();
+
+        let groups =
+            split_pool_data(&config, &mint_pool_data, &wallet.pubkey(), &[], 2).unwrap();
+        // Two full groups; the fifth pool would be alone in a third and is left out
+        assert_eq!(groups.len(), 2);
+        assert!(groups.iter().all(|group| group.dex_pools().len() == 2));
+        let split: HashSet<Pubkey> = groups
+            .iter()
+            .flat_map(|group| group.dex_pools().into_iter().map(|pool| pool.pool_address()))
+            .collect();
+        assert_eq!(split.len(), 4);
+
+        let tip_account = Pubkey::new_unique();
+        let transactions = build_split_transactions(
+            &wallet,
+            &config,
+            &groups,
+            &[],
+            Hash::default(),
+            Some(tip_account),
+            Some(25_000),
+        )
+        .unwrap();
+        assert_eq!(transactions.len(), 2);
+        // Every group pays the route's estimated price
+        let price = ComputeBudgetInstruction::set_compute_unit_price(25_000).data;
+        assert!(transactions.iter().all(|tx| tx.message.instructions()[1].data == price));
+        // One tip for the bundle, on its last transaction
+        let tipped: Vec<bool> = transactions
+            .iter()
+            .map(|tx| tx.message.static_account_keys().contains(&tip_account))
+            .collect();
+        assert_eq!(tipped, vec![false, true]);
+    }
+
+    #[test]
+    fn test_partition_spills_pools_that_do_not_fit() {
+        // Only two of these fit per transaction even though the cap allows four
+        let candidates: Vec<_> = (0..5).map(|_| candidate(12, 10)).collect();
+        let groups = partition_pools(&candidates, 4, &[], &[], &[], 1_400_000);
+
+        // Two groups of two; the last pool would trade alone
+        assert_eq!(groups.len(), 2);
+        assert!(groups.iter().all(|group| group.selected.len() == 2));
+        assert!(groups.iter().all(|group| group.account_count <= MAX_TRANSACTION_ACCOUNTS));
+        assert!(groups.iter().all(|group| group.skipped.is_empty()));
+    }
 }
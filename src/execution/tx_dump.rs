@@ -0,0 +1,350 @@
+/// Transaction Dumps
+///
+/// `run --dump-tx <dir>` writes every transaction the bot is about to send to
+/// `<dir>/<mint>-<unix ms>.json`, one file per mint per iteration. Besides the signed wire
+/// transaction in base64 (`solana decode-transaction <base64> base64` decodes it), the dump
+/// lists the resolved accounts with their signer/writable flags and the lookup table each
+/// came from, every instruction's program, accounts and data, the compute budget and the
+/// lookup tables read. Nothing is written without the flag.
+
+use crate::error::BotResult;
+use base64::Engine;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::compute_budget;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::transaction::{TransactionVersion, VersionedTransaction};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tracing::{debug, info, warn};
+
+/// `ComputeBudgetInstruction` discriminators
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// An account of the transaction, in the order instructions index them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DumpedAccount {
+    pub pubkey: String,
+    pub signer: bool,
+    pub writable: bool,
+    /// The lookup table the account was loaded from, None for static keys
+    pub lookup_table: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DumpedInstruction {
+    pub program_id: String,
+    pub accounts: Vec<String>,
+    /// Base64 instruction data
+    pub data: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DumpedLookupTable {
+    pub address: String,
+    pub writable_indexes: Vec<u8>,
+    pub readonly_indexes: Vec<u8>,
+}
+
+/// Everything needed to inspect or replay one transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionDump {
+    pub mint: String,
+    pub signature: String,
+    pub version: String,
+    pub recent_blockhash: String,
+    /// Signed wire transaction, base64
+    pub transaction: String,
+    pub compute_unit_limit: Option<u32>,
+    pub compute_unit_price: Option<u64>,
+    pub accounts: Vec<DumpedAccount>,
+    pub instructions: Vec<DumpedInstruction>,
+    pub lookup_tables: Vec<DumpedLookupTable>,
+}
+
+/// Signed wire bytes: the compact-u16 signature count, the signatures, then the message
+pub fn wire_bytes(tx: &VersionedTransaction) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut count = tx.signatures.len() as u16;
+    loop {
+        let byte = (count & 0x7f) as u8;
+        count >>= 7;
+        if count == 0 {
+            bytes.push(byte);
+            break;
+        }
+        bytes.push(byte | 0x80);
+    }
+    for signature in &tx.signatures {
+        bytes.extend_from_slice(signature.as_ref());
+    }
+    bytes.extend(tx.message.serialize());
+    bytes
+}
+
+/// Compute-unit limit and price (micro-lamports) set by the message's compute budget
+/// instructions, None for each one it doesn't set
+pub fn compute_budget(message: &VersionedMessage) -> (Option<u32>, Option<u64>) {
+    let static_keys = message.static_account_keys();
+    let mut compute_unit_limit = None;
+    let mut compute_unit_price = None;
+    for instruction in message.instructions() {
+        if static_keys.get(instruction.program_id_index as usize) != Some(&compute_budget::id()) {
+            continue;
+        }
+        match instruction.data.split_first() {
+            Some((&SET_COMPUTE_UNIT_LIMIT, rest)) => {
+                compute_unit_limit = rest.try_into().ok().map(u32::from_le_bytes);
+            }
+            Some((&SET_COMPUTE_UNIT_PRICE, rest)) => {
+                compute_unit_price = rest.try_into().ok().map(u64::from_le_bytes);
+            }
+            _ => {}
+        }
+    }
+    (compute_unit_limit, compute_unit_price)
+}
+
+/// Describe `tx`, sent for `mint`, resolving its lookups against `lookup_tables`
+pub fn dump_transaction(
+    mint: &Pubkey,
+    tx: &VersionedTransaction,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> TransactionDump {
+    let message = &tx.message;
+    let header = message.header();
+    let static_keys = message.static_account_keys();
+    let signers = header.num_required_signatures as usize;
+    let writable_signers = signers.saturating_sub(header.num_readonly_signed_accounts as usize);
+    let writable_unsigned =
+        static_keys.len().saturating_sub(header.num_readonly_unsigned_accounts as usize);
+
+    let mut accounts: Vec<DumpedAccount> = static_keys
+        .iter()
+        .enumerate()
+        .map(|(index, pubkey)| DumpedAccount {
+            pubkey: pubkey.to_string(),
+            signer: index < signers,
+            writable: if index < signers {
+                index < writable_signers
+            } else {
+                index < writable_unsigned
+            },
+            lookup_table: None,
+        })
+        .collect();
+
+    // The runtime appends every lookup's writable addresses, then every lookup's readonly ones
+    let lookups = message.address_table_lookups().unwrap_or_default();
+    for writable in [true, false] {
+        for lookup in lookups {
+            let table = lookup_tables.iter().find(|table| table.key == lookup.account_key);
+            let indexes = if writable {
+                &lookup.writable_indexes
+            } else {
+                &lookup.readonly_indexes
+            };
+            for &index in indexes {
+                let pubkey = table
+                    .and_then(|table| table.addresses.get(index as usize))
+                    .map(|address| address.to_string())
+                    .unwrap_or_else(|| format!("{}[{}]", lookup.account_key, index));
+                accounts.push(DumpedAccount {
+                    pubkey,
+                    signer: false,
+                    writable,
+                    lookup_table: Some(lookup.account_key.to_string()),
+                });
+            }
+        }
+    }
+
+    let account_key = |index: u8| {
+        accounts
+            .get(index as usize)
+            .map(|account| account.pubkey.clone())
+            .unwrap_or_default()
+    };
+    let (compute_unit_limit, compute_unit_price) = compute_budget(message);
+    let instructions = message
+        .instructions()
+        .iter()
+        .map(|instruction| {
+            let program_id = static_keys
+                .get(instruction.program_id_index as usize)
+                .copied()
+                .unwrap_or_default();
+            DumpedInstruction {
+                program_id: program_id.to_string(),
+                accounts: instruction.accounts.iter().map(|&index| account_key(index)).collect(),
+                data: base64::engine::general_purpose::STANDARD.encode(&instruction.data),
+            }
+        })
+        .collect();
+
+    TransactionDump {
+        mint: mint.to_string(),
+        signature: tx.signatures.first().map(|s| s.to_string()).unwrap_or_default(),
+        version: match tx.version() {
+            TransactionVersion::Legacy(_) => "legacy".to_string(),
+            TransactionVersion::Number(n) => format!("v{}", n),
+        },
+        recent_blockhash: message.recent_blockhash().to_string(),
+        transaction: base64::engine::general_purpose::STANDARD.encode(wire_bytes(tx)),
+        compute_unit_limit,
+        compute_unit_price,
+        accounts,
+        instructions,
+        lookup_tables: lookups
+            .iter()
+            .map(|lookup| DumpedLookupTable {
+                address: lookup.account_key.to_string(),
+                writable_indexes: lookup.writable_indexes.clone(),
+                readonly_indexes: lookup.readonly_indexes.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// Write `tx`'s dump to `dir`, returning the file's path
+pub fn write_dump(
+    dir: &Path,
+    mint: &Pubkey,
+    tx: &VersionedTransaction,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> BotResult<PathBuf> {
+    let dump = dump_transaction(mint, tx, lookup_tables);
+    let path = dir.join(format!("{}-{}.json", mint, chrono::Utc::now().timestamp_millis()));
+    let json = serde_json::to_string_pretty(&dump).map_err(std::io::Error::from)?;
+    std::fs::write(&path, json)?;
+    Ok(path)
+}
+
+lazy_static! {
+    static ref DUMP_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+}
+
+/// Dump every transaction about to be sent into `dir` from now on, creating it if needed.
+/// Call once at startup.
+pub fn install(dir: &Path) -> BotResult<()> {
+    std::fs::create_dir_all(dir)?;
+    info!("Dumping assembled transactions to {}", dir.display());
+    *DUMP_DIR.write().unwrap() = Some(dir.to_path_buf());
+    Ok(())
+}
+
+/// Dump `tx` if a dump directory is installed; a failed write is only logged
+pub fn dump_if_enabled(
+    mint: &Pubkey,
+    tx: &VersionedTransaction,
+    lookup_tables: &[AddressLookupTableAccount],
+) {
+    let Some(dir) = DUMP_DIR.read().unwrap().clone() else {
+        return;
+    };
+    match write_dump(&dir, mint, tx, lookup_tables) {
+        Ok(path) => debug!("Dumped transaction for {} to {}", mint, path.display()),
+        Err(e) => warn!("Failed to dump transaction for {}: {}", mint, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TransactionVersion as MessageVersion;
+    use crate::transaction::compile_message;
+    use solana_program::instruction::{AccountMeta, Instruction};
+    use solana_program::system_instruction;
+    use solana_sdk::compute_budget::ComputeBudgetInstruction;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::signer::Signer;
+
+    #[test]
+    fn test_dump_lists_instructions_and_resolved_accounts() {
+        let wallet = Keypair::new();
+        let (program, pool, vault, recipient) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![pool, vault],
+        };
+        let instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(300_000),
+            ComputeBudgetInstruction::set_compute_unit_price(5_000),
+            Instruction::new_with_bytes(
+                program,
+                &[7, 1, 2],
+                vec![
+                    AccountMeta::new(wallet.pubkey(), true),
+                    AccountMeta::new(pool, false),
+                    AccountMeta::new_readonly(vault, false),
+                ],
+            ),
+            system_instruction::transfer(&wallet.pubkey(), &recipient, 1_000),
+        ];
+        let message = compile_message(
+            &wallet.pubkey(),
+            &instructions,
+            std::slice::from_ref(&table),
+            Hash::new_unique(),
+            MessageVersion::V0,
+        )
+        .unwrap();
+        let signers: [&dyn Signer; 1] = [&wallet];
+        let tx = VersionedTransaction::try_new(message, &signers).unwrap();
+
+        let mint = Pubkey::new_unique();
+        let dump = dump_transaction(&mint, &tx, std::slice::from_ref(&table));
+        assert_eq!(dump.version, "v0");
+        assert_eq!(dump.signature, tx.signatures[0].to_string());
+        assert_eq!(dump.compute_unit_limit, Some(300_000));
+        assert_eq!(dump.compute_unit_price, Some(5_000));
+
+        assert_eq!(dump.instructions.len(), 4);
+        let swap = &dump.instructions[2];
+        assert_eq!(swap.program_id, program.to_string());
+        assert_eq!(
+            swap.accounts,
+            vec![wallet.pubkey().to_string(), pool.to_string(), vault.to_string()]
+        );
+        assert_eq!(swap.data, base64::engine::general_purpose::STANDARD.encode([7, 1, 2]));
+
+        // The pool and vault are loaded through the table, with their requested access
+        let account = |pubkey: &Pubkey| {
+            dump.accounts.iter().find(|account| account.pubkey == pubkey.to_string()).unwrap()
+        };
+        assert!(account(&wallet.pubkey()).signer && account(&wallet.pubkey()).writable);
+        assert!(account(&recipient).writable && account(&recipient).lookup_table.is_none());
+        assert!(!account(&program).writable);
+        assert_eq!(account(&pool).lookup_table, Some(table.key.to_string()));
+        assert!(account(&pool).writable);
+        assert!(!account(&vault).writable);
+        assert_eq!(dump.lookup_tables.len(), 1);
+        assert_eq!(dump.lookup_tables[0].writable_indexes, vec![0]);
+        assert_eq!(dump.lookup_tables[0].readonly_indexes, vec![1]);
+
+        // The wire bytes are the signature count, the signature, then the message
+        let bytes = base64::engine::general_purpose::STANDARD.decode(&dump.transaction).unwrap();
+        assert_eq!(bytes[0], 1);
+        assert_eq!(&bytes[1..65], tx.signatures[0].as_ref());
+        assert_eq!(&bytes[65..], tx.message.serialize().as_slice());
+
+        // The file on disk reads back as the same dump
+        let dir = std::env::temp_dir().join(format!("tx-dump-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = write_dump(&dir, &mint, &tx, std::slice::from_ref(&table)).unwrap();
+        assert!(path.file_name().unwrap().to_str().unwrap().starts_with(&mint.to_string()));
+        let written: TransactionDump =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written, dump);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -5,26 +5,54 @@ pub mod common {
 }
 pub mod configuration {
     pub mod config;
+    pub mod lint;
     pub mod secrets;
 }
 pub mod engine {
+    pub mod account_audit;
+    pub mod ata_check;
     pub mod bot;
+    pub mod config_diff;
+    pub mod discovery;
+    pub mod in_flight;
+    pub mod observation_refresh;
+    pub mod opportunity;
+    pub mod pool_init;
+    pub mod ranking;
     pub mod refresh;
+    pub mod self_test;
+    pub mod send_queue;
+    pub mod swap_account_check;
+    pub mod trade_split;
 }
 pub mod state {
+    pub mod mint_checks;
     pub mod pools;
+    pub mod swap_accounts;
 }
 pub mod storage {
     pub mod database;
+    pub mod export;
+    pub mod trade_events;
 }
 pub mod execution {
+    pub mod account_metas;
     pub mod transaction;
     pub mod jito;
+    pub mod signer;
+    pub mod reconcile;
+    pub mod failover;
+    pub mod priority_fee;
+    pub mod tx_dump;
 }
 pub mod monitoring {
+    pub mod alert;
+    pub mod feed_compare;
     pub mod metrics;
     pub mod health;
     pub mod latency;
+    pub mod opportunity_feed;
+    pub mod watchdog;
 }
 
 // Flat modules (unchanged)
@@ -35,10 +63,14 @@ pub mod rpc;
 
 // Re-exports for easier access / compatibility
 pub use common::{constants, error};
-pub use configuration::{config, secrets};
-pub use engine::{bot, refresh};
-pub use state::pools;
-pub use storage::database;
-pub use execution::{transaction, jito};
-pub use monitoring::{metrics, health, latency};
+pub use configuration::{config, lint, secrets};
+pub use engine::{
+    account_audit, ata_check, bot, config_diff, discovery, in_flight, observation_refresh,
+    opportunity, pool_init, ranking, refresh, self_test, send_queue, swap_account_check,
+    trade_split,
+};
+pub use state::{mint_checks, pools, swap_accounts};
+pub use storage::{database, export, trade_events};
+pub use execution::{account_metas, transaction, jito, signer, reconcile, failover, priority_fee, tx_dump};
+pub use monitoring::{alert, feed_compare, metrics, health, latency, opportunity_feed, watchdog};
 
@@ -1,16 +1,36 @@
 use clap::Parser;
 use solana_onchain_arbitrage_bot::{
+    account_audit::audit_mint_pools,
     cli::{Cli, Commands},
+    alert,
     engine::bot,
     config::Config,
+    config_diff::{diff_route_plans, plan_routes},
+    constants::{
+        sol_mint, COMPARE_FEEDS_POLL_INTERVAL_MS, DISCOVERY_RPC_TIMEOUT_SECS, METRICS_HISTORY_FILE,
+    },
+    database::Database,
+    discovery::{discover_pools, format_pool_list},
+    export::export_trades,
+    feed_compare::compare_feeds,
+    health::StatusResponse,
+    lint::lint_config,
+    metrics::TimestampedSnapshot,
+    self_test::{run_self_test, SelfTestFixture, SelfTestOutcome},
+    signer::SignerSource,
+    swap_account_check::validate_swap_accounts,
+    tx_dump,
 };
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 use std::str::FromStr;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
 
     // Set up logging
     let log_level = match cli.log_level.to_lowercase().as_str() {
@@ -31,20 +51,26 @@ async fn main() -> anyhow::Result<()> {
     Cli::print_banner();
 
     // Default to run if no subcommand
-    let command = cli.command.unwrap_or(Commands::Run { dry_run: false });
+    let command = cli.command.take().unwrap_or(Commands::Run { dry_run: false, dump_tx: None });
 
     match command {
-        Commands::Run { dry_run } => {
+        Commands::Run { dry_run, dump_tx } => {
             if dry_run {
                 info!("Starting bot in DRY RUN mode (no transactions will be sent)");
-                // We would pass this flag to run_bot if supported
+            }
+            if let Some(dir) = &dump_tx {
+                tx_dump::install(dir)?;
             }
             
-            let config_path = cli.config.to_str().ok_or_else(|| anyhow::anyhow!("Invalid config path"))?;
-            info!("Initializing bot with config: {}", config_path);
-            
-            // Run the bot engine
-            bot::run_bot(config_path).await?;
+            info!("Initializing bot with config: {}", cli.config_source());
+            let config = cli.load_config()?;
+
+            // Run the bot engine; a critical error it exits on (e.g. an unusable
+            // wallet) still pages
+            if let Err(e) = bot::run_bot(config, dry_run).await {
+                alert::report(&e).await;
+                return Err(e.into());
+            }
         }
         Commands::Validate { config } => {
             let config_path = config.to_str().unwrap_or("config.toml");
@@ -57,13 +83,55 @@ async fn main() -> anyhow::Result<()> {
                 }
             }
         }
+        Commands::Lint { config, strict } => {
+            let config_path = config.to_str().unwrap_or("config.toml");
+            let config = match Config::load(config_path) {
+                Ok(config) => config,
+                Err(e) => {
+                    tracing::error!("Configuration file is INVALID ❌");
+                    tracing::error!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let warnings = lint_config(&config);
+            for warning in &warnings {
+                tracing::warn!("[{}] {}", warning.category, warning.message);
+            }
+            if warnings.is_empty() {
+                info!("No lint warnings ✅");
+            } else {
+                info!("{} lint warning(s)", warnings.len());
+                if strict {
+                    std::process::exit(1);
+                }
+            }
+        }
         Commands::ListDexs => {
             Cli::print_dex_list();
         }
         Commands::Stats => {
             Cli::print_stats_header();
-            // In a real app we might connect to the DB or metrics to show stats
-            info!("Connect to Grafana at http://localhost:3000 for full stats");
+            // Prefer live history from a running bot, then the exported snapshot file
+            let status_url = format!("http://127.0.0.1:{}/status", cli.health_port);
+            let live = match reqwest::get(&status_url).await {
+                Ok(response) => response.json::<StatusResponse>().await.ok(),
+                Err(_) => None,
+            };
+            let history = match live {
+                Some(status) => {
+                    Cli::print_endpoint_latency(&status.latency);
+                    Some(status.history)
+                }
+                None => std::fs::read(METRICS_HISTORY_FILE)
+                    .ok()
+                    .and_then(|json| serde_json::from_slice::<Vec<TimestampedSnapshot>>(&json).ok()),
+            };
+
+            match history {
+                Some(history) => Cli::print_history(&history),
+                None => info!("No running bot or exported history found; connect to Grafana at http://localhost:3000 for full stats"),
+            }
         }
         Commands::TestRpc { url } => {
             info!("Testing RPC connection to: {}", url);
@@ -81,6 +149,170 @@ async fn main() -> anyhow::Result<()> {
             info!("Generating example config to: {:?}", output);
             // Placeholder
         }
+        Commands::Export { output, from_ts, to_ts, format } => {
+            let db_url = std::env::var("DATABASE_URL")
+                .map_err(|_| anyhow::anyhow!("DATABASE_URL must be set to export trades"))?;
+            let db = Database::new(&db_url).await?;
+
+            let trades = db
+                .get_trades_between(from_ts.unwrap_or(0), to_ts.unwrap_or(i64::MAX))
+                .await?;
+            if trades.is_empty() {
+                info!("No trades found in the requested range");
+            }
+
+            let file = std::fs::File::create(&output)?;
+            let rows = export_trades(&trades, format, std::io::BufWriter::new(file))?;
+            info!("Exported {} trades to {:?}", rows, output);
+        }
+        Commands::DiscoverPools { mint, dex } => {
+            let config = cli.load_config()?;
+            let mint = Pubkey::from_str(&mint)?;
+            let base_mint = config.base_mint_override().unwrap_or_else(sol_mint);
+
+            tracing::warn!(
+                "getProgramAccounts is heavy; public RPCs often reject it and a paid RPC may be required"
+            );
+            let client = solana_client::rpc_client::RpcClient::new_with_timeout(
+                config.rpc.url.clone(),
+                Duration::from_secs(DISCOVERY_RPC_TIMEOUT_SECS),
+            );
+            let pools = discover_pools(&client, dex, &mint, &base_mint)?;
+            if pools.is_empty() {
+                info!("No {} pools found pairing {} with {}", dex.dex_name(), mint, base_mint);
+            } else {
+                info!("Found {} {} pools for {}", pools.len(), dex.dex_name(), mint);
+                println!("{}", format_pool_list(dex, &pools));
+            }
+        }
+        Commands::SelfTest { rpc_url, fixtures } => {
+            let rpc_url = match rpc_url {
+                Some(url) => url,
+                None => cli.load_config()?.rpc.url,
+            };
+            let fixtures = match fixtures {
+                Some(path) => SelfTestFixture::load(&path)?,
+                None => SelfTestFixture::builtin(),
+            };
+
+            info!("Running DEX self-test against {}", rpc_url);
+            let client = solana_client::rpc_client::RpcClient::new(rpc_url);
+            let results = run_self_test(&client, &fixtures);
+            for result in &results {
+                println!("{}", result);
+            }
+
+            let failed = results
+                .iter()
+                .filter(|result| matches!(result.outcome, SelfTestOutcome::Fail(_)))
+                .count();
+            if failed > 0 {
+                tracing::error!("{} DEX self-test(s) FAILED ❌", failed);
+                std::process::exit(1);
+            }
+            info!("DEX self-test passed ✅");
+        }
+        Commands::CompareFeeds { pool, window_secs } => {
+            let config = cli.load_config()?;
+            let pool = Pubkey::from_str(&pool)?;
+
+            let comparison = compare_feeds(
+                config.rpc.url.clone(),
+                config.rpc.ws_url(),
+                pool,
+                Duration::from_secs(window_secs),
+                Duration::from_millis(COMPARE_FEEDS_POLL_INTERVAL_MS),
+            )
+            .await?;
+            println!("{}", comparison);
+        }
+        Commands::Diff { old, new } => {
+            let load = |path: &std::path::PathBuf| {
+                Config::load(path.to_str().ok_or_else(|| anyhow::anyhow!("Invalid config path"))?)
+                    .map_err(anyhow::Error::from)
+            };
+            let old_config = load(&old)?;
+            let new_config = load(&new)?;
+
+            // Both configs are simulated against the same wallet and RPC so only the
+            // routing changes show up in the diff
+            let wallet = SignerSource::from_wallet_config(&new_config.wallet).load()?.pubkey();
+            let rpc_client = std::sync::Arc::new(
+                solana_client::rpc_client::RpcClient::new_with_timeout(
+                    new_config.rpc.url.clone(),
+                    new_config.rpc.timeout(),
+                ),
+            );
+
+            info!("Dry-running {:?} and {:?}", old, new);
+            let old_plans = plan_routes(&old_config, &wallet, rpc_client.clone()).await?;
+            let new_plans = plan_routes(&new_config, &wallet, rpc_client).await?;
+            for diff in diff_route_plans(&old_plans, &new_plans) {
+                print!("{}", diff);
+            }
+        }
+        Commands::AuditAccounts { mint } => {
+            let config = cli.load_config()?;
+            let mint_config = config
+                .routing
+                .mint_config_list
+                .iter()
+                .find(|mint_config| mint_config.mint == mint)
+                .ok_or_else(|| anyhow::anyhow!("Mint {} is not in {}", mint, cli.config_source()))?;
+
+            let wallet = SignerSource::from_wallet_config(&config.wallet).load()?.pubkey();
+            let rpc_client = std::sync::Arc::new(
+                solana_client::rpc_client::RpcClient::new_with_timeout(
+                    mint_config.rpc_url.clone().unwrap_or_else(|| config.rpc.url.clone()),
+                    config.rpc.timeout(),
+                ),
+            );
+            let pool_data =
+                bot::initialize_mint_pools(&config, mint_config, &wallet, rpc_client.clone(), None)
+                    .await?;
+
+            let audits = audit_mint_pools(rpc_client.as_ref(), &pool_data)?;
+            for audit in &audits {
+                print!("{}", audit);
+            }
+            let mismatches: usize = audits.iter().map(|audit| audit.mismatches().count()).sum();
+            if mismatches > 0 {
+                tracing::error!("{} swap account(s) have the wrong writability ❌", mismatches);
+                std::process::exit(1);
+            }
+            info!("Swap account writability matches on-chain state ✅");
+        }
+        Commands::ValidateSwapAccounts { mint } => {
+            let config = cli.load_config()?;
+            let mint_config = config
+                .routing
+                .mint_config_list
+                .iter()
+                .find(|mint_config| mint_config.mint == mint)
+                .ok_or_else(|| anyhow::anyhow!("Mint {} is not in {}", mint, cli.config_source()))?;
+
+            let wallet = SignerSource::from_wallet_config(&config.wallet).load()?.pubkey();
+            let rpc_client = std::sync::Arc::new(
+                solana_client::rpc_client::RpcClient::new_with_timeout(
+                    mint_config.rpc_url.clone().unwrap_or_else(|| config.rpc.url.clone()),
+                    config.rpc.timeout(),
+                ),
+            );
+            let pool_data =
+                bot::initialize_mint_pools(&config, mint_config, &wallet, rpc_client.clone(), None)
+                    .await?;
+
+            let checks = validate_swap_accounts(rpc_client.as_ref(), &config, &wallet, &pool_data)?;
+            for check in &checks {
+                println!("{}", check);
+            }
+            let failed = checks.iter().filter(|check| !check.verdict.is_pass()).count();
+            if failed > 0 {
+                tracing::error!("{} pool(s) failed the swap simulation ❌", failed);
+                std::process::exit(1);
+            }
+            info!("Every pool's swap accounts were accepted in simulation ✅");
+        }
     }
 
     Ok(())
@@ -0,0 +1,244 @@
+/// Critical Error Alerts
+///
+/// With `bot.alert_webhook_url` set, critical events (a `BotError` of
+/// `ErrorSeverity::Critical` such as every RPC endpoint being down, a wallet failover
+/// or having no wallet left to fail over to, the watchdog halting trading) are
+/// POSTed to the webhook as JSON `{severity, message, timestamp}` so an operator gets
+/// paged. At most one alert goes out per `ALERT_MIN_INTERVAL_SECS`; the rest are only
+/// logged.
+
+use crate::constants::{ALERT_MIN_INTERVAL_SECS, ALERT_WEBHOOK_TIMEOUT_MS};
+use crate::error::{BotError, ErrorSeverity};
+use lazy_static::lazy_static;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+/// Body POSTed to the webhook
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Alert {
+    pub severity: &'static str,
+    pub message: String,
+    pub timestamp: i64,
+}
+
+/// Rate-limited poster of critical alerts
+pub struct AlertSender {
+    webhook_url: String,
+    min_interval: Duration,
+    last_sent: Mutex<Option<Instant>>,
+    client: reqwest::Client,
+}
+
+impl AlertSender {
+    pub fn new(webhook_url: String, min_interval: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(ALERT_WEBHOOK_TIMEOUT_MS))
+            .build()
+            .unwrap_or_default();
+        Self {
+            webhook_url,
+            min_interval,
+            last_sent: Mutex::new(None),
+            client,
+        }
+    }
+
+    /// Take the alert slot at `now` unless one was taken less than `min_interval` ago
+    fn claim(&self, now: Instant) -> bool {
+        let mut last_sent = self.last_sent.lock().unwrap();
+        if matches!(*last_sent, Some(sent_at) if now.duration_since(sent_at) < self.min_interval) {
+            return false;
+        }
+        *last_sent = Some(now);
+        true
+    }
+
+    /// POST `message` if `severity` is critical and no alert went out within the rate
+    /// limit. Returns whether the webhook accepted it.
+    pub async fn alert(&self, severity: ErrorSeverity, message: &str) -> bool {
+        if severity != ErrorSeverity::Critical {
+            return false;
+        }
+        if !self.claim(Instant::now()) {
+            debug!("Alert rate-limited: {}", message);
+            return false;
+        }
+        let alert = Alert {
+            severity: severity.as_str(),
+            message: message.to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+        match self
+            .client
+            .post(&self.webhook_url)
+            .json(&alert)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+        {
+            Ok(_) => true,
+            Err(e) => {
+                warn!("Failed to post alert to {}: {}", self.webhook_url, e);
+                false
+            }
+        }
+    }
+}
+
+lazy_static! {
+    static ref ALERTS: RwLock<Option<Arc<AlertSender>>> = RwLock::new(None);
+}
+
+/// Send critical alerts to `webhook_url` from now on. Call once at startup.
+pub fn install(webhook_url: &str) {
+    info!("Alerting critical errors to {}", webhook_url);
+    let sender = AlertSender::new(
+        webhook_url.to_string(),
+        Duration::from_secs(ALERT_MIN_INTERVAL_SECS),
+    );
+    *ALERTS.write().unwrap() = Some(Arc::new(sender));
+}
+
+fn installed() -> Option<Arc<AlertSender>> {
+    ALERTS.read().unwrap().clone()
+}
+
+/// Log `error` at the level of its severity, prefixed with what failed, alerting the
+/// webhook in the background when it is critical. Errors the bot recovers from go
+/// through here, so a critical one pages whichever task hit it.
+pub fn log_error(context: &str, error: &BotError) {
+    let severity = error.severity();
+    match severity {
+        ErrorSeverity::Critical | ErrorSeverity::Error => {
+            error!("{}: {} (severity: {})", context, error, severity.as_str())
+        }
+        ErrorSeverity::Warning => warn!("{}: {} (severity: {})", context, error, severity.as_str()),
+        ErrorSeverity::Info => info!("{}: {} (severity: {})", context, error, severity.as_str()),
+    }
+    dispatch(severity, format!("{}: {}", context, error));
+}
+
+/// Alert the webhook in the background about a critical event that isn't a
+/// `BotError`; the caller logs it
+pub fn alert_critical(message: String) {
+    dispatch(ErrorSeverity::Critical, message);
+}
+
+/// Alert the webhook about `error` if it is critical, waiting for the POST (for errors
+/// the bot exits on)
+pub async fn report(error: &BotError) {
+    if let Some(sender) = installed() {
+        sender.alert(error.severity(), &error.to_string()).await;
+    }
+}
+
+/// Posting never blocks the caller; outside the runtime the event is only logged
+fn dispatch(severity: ErrorSeverity, message: String) {
+    if severity != ErrorSeverity::Critical {
+        return;
+    }
+    let (Some(sender), Ok(runtime)) = (installed(), tokio::runtime::Handle::try_current()) else {
+        return;
+    };
+    runtime.spawn(async move {
+        sender.alert(severity, &message).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Body of one HTTP request read off `stream`
+    fn read_body(stream: &mut std::net::TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut chunk = [0u8; 1024];
+        loop {
+            let n = stream.read(&mut chunk).unwrap();
+            request.extend_from_slice(&chunk[..n]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end]
+                    .lines()
+                    .find_map(|line| {
+                        let line = line.to_lowercase();
+                        line.strip_prefix("content-length:")?.trim().parse::<usize>().ok()
+                    })
+                    .unwrap_or(0);
+                if request.len() >= header_end + 4 + content_length {
+                    return text[header_end + 4..].to_string();
+                }
+            }
+            if n == 0 {
+                return String::new();
+            }
+        }
+    }
+
+    /// Mock webhook recording every body it's sent
+    fn mock_webhook() -> (String, tokio::sync::mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (posts, received) = tokio::sync::mpsc::unbounded_channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let body = read_body(&mut stream);
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .unwrap();
+                if posts.send(body).is_err() {
+                    break;
+                }
+            }
+        });
+        (url, received)
+    }
+
+    #[tokio::test]
+    async fn test_only_critical_errors_post_to_webhook() {
+        let (url, mut received) = mock_webhook();
+
+        let sender = AlertSender::new(url, Duration::from_secs(60));
+        let warning = BotError::rpc_retryable("http://rpc".to_string(), "timeout".to_string());
+        assert!(!sender.alert(warning.severity(), &warning.to_string()).await);
+
+        let critical = BotError::WalletError("keypair file unreadable".to_string());
+        assert!(sender.alert(critical.severity(), &critical.to_string()).await);
+        let body = tokio::time::timeout(Duration::from_secs(5), received.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let alert: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(alert["severity"], "CRITICAL");
+        assert!(alert["message"].as_str().unwrap().contains("keypair file unreadable"));
+        assert!(alert["timestamp"].as_i64().unwrap() > 0);
+
+        // A second critical error within the minute is only logged
+        assert!(!sender.alert(critical.severity(), &critical.to_string()).await);
+        // The warning never reached the webhook either
+        assert!(received.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_logged_outage_pages_with_its_context() {
+        let (url, mut received) = mock_webhook();
+        install(&url);
+
+        let warning = BotError::rpc_retryable("http://rpc".to_string(), "timeout".to_string());
+        log_error("Mint A: failed to read pool state", &warning);
+        log_error("Mint A: failed to read pool state", &BotError::AllEndpointsDown { endpoints: 2 });
+
+        let body = tokio::time::timeout(Duration::from_secs(5), received.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let alert: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let message = alert["message"].as_str().unwrap();
+        assert!(message.starts_with("Mint A: failed to read pool state: All 2 RPC endpoint(s) are down"));
+        assert!(received.try_recv().is_err());
+    }
+}
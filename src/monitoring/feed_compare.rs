@@ -0,0 +1,201 @@
+/// WebSocket vs RPC Feed Comparison
+///
+/// Watches one account over a WebSocket subscription and RPC polling at the same
+/// time and measures how much earlier each new account state arrives over the
+/// WebSocket, to show whether enabling WS feeds is worth it.
+
+use crate::error::BotResult;
+use crate::latency::{
+    AccountEncoding, AccountSubscriber, FeedKind, LatencyTracker, SubscriptionConfig,
+};
+use base64::Engine;
+use solana_client::nonblocking::rpc_client::RpcClient as AsyncRpcClient;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// First arrival of each distinct account state, per feed
+#[derive(Debug, Default)]
+pub struct FeedArrivals {
+    ws: HashMap<String, Instant>,
+    rpc: HashMap<String, Instant>,
+}
+
+impl FeedArrivals {
+    pub fn record_ws(&mut self, data: String, at: Instant) {
+        self.ws.entry(data).or_insert(at);
+    }
+
+    pub fn record_rpc(&mut self, data: String, at: Instant) {
+        self.rpc.entry(data).or_insert(at);
+    }
+}
+
+/// How the two feeds compared over the sample window
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedComparison {
+    /// Account states seen by both feeds
+    pub matched: usize,
+    pub ws_only: usize,
+    pub rpc_only: usize,
+    /// Matched states that arrived over the WebSocket first
+    pub ws_first: usize,
+    /// Median of RPC arrival minus WS arrival; positive when the WebSocket leads
+    pub median_lead_ms: Option<f64>,
+}
+
+impl fmt::Display for FeedComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Account states seen by both feeds: {}", self.matched)?;
+        writeln!(f, "  WebSocket first: {}/{}", self.ws_first, self.matched)?;
+        // The state at startup is only ever polled, never pushed
+        writeln!(f, "  Only via WebSocket: {}, only via RPC: {}", self.ws_only, self.rpc_only)?;
+        match self.median_lead_ms {
+            Some(lead) if lead >= 0.0 => {
+                write!(f, "Median WebSocket lead over polling: {:.1}ms", lead)
+            }
+            Some(lead) => write!(f, "Median WebSocket lag behind polling: {:.1}ms", -lead),
+            None => write!(f, "No account updates seen by both feeds"),
+        }
+    }
+}
+
+/// Match arrivals by account state and aggregate the WebSocket's lead
+pub fn compare_arrivals(arrivals: &FeedArrivals) -> FeedComparison {
+    let mut leads_ms: Vec<f64> = arrivals
+        .ws
+        .iter()
+        .filter_map(|(data, ws_at)| {
+            let rpc_at = arrivals.rpc.get(data)?;
+            let lead = if rpc_at >= ws_at {
+                rpc_at.duration_since(*ws_at).as_secs_f64()
+            } else {
+                -ws_at.duration_since(*rpc_at).as_secs_f64()
+            };
+            Some(lead * 1_000.0)
+        })
+        .collect();
+    leads_ms.sort_by(f64::total_cmp);
+
+    let matched = leads_ms.len();
+    let median_lead_ms = match matched {
+        0 => None,
+        n if n % 2 == 1 => Some(leads_ms[n / 2]),
+        n => Some((leads_ms[n / 2 - 1] + leads_ms[n / 2]) / 2.0),
+    };
+    FeedComparison {
+        matched,
+        ws_only: arrivals.ws.len() - matched,
+        rpc_only: arrivals.rpc.len() - matched,
+        ws_first: leads_ms.iter().filter(|lead| **lead > 0.0).count(),
+        median_lead_ms,
+    }
+}
+
+/// Subscribe to `pool` over `ws_url` while polling it over `rpc_url` for `window`
+pub async fn compare_feeds(
+    rpc_url: String,
+    ws_url: String,
+    pool: Pubkey,
+    window: Duration,
+    poll_interval: Duration,
+) -> BotResult<FeedComparison> {
+    let tracker = Arc::new(LatencyTracker::new());
+    let arrivals = Mutex::new(FeedArrivals::default());
+
+    // Both feeds read at the same commitment, so only the transport differs
+    let subscriber = AccountSubscriber::new(ws_url, tracker.clone());
+    let subscription = SubscriptionConfig {
+        encoding: AccountEncoding::Base64,
+        commitment: CommitmentLevel::Confirmed,
+    };
+    let ws_feed = tokio::time::timeout(
+        window,
+        subscriber.subscribe_account_updates(&pool, subscription, |update| {
+            let data = base64::engine::general_purpose::STANDARD.encode(&update.account.data);
+            arrivals.lock().unwrap().record_ws(data, Instant::now());
+        }),
+    );
+
+    let client = AsyncRpcClient::new(rpc_url);
+    let rpc_feed = tokio::time::timeout(window, async {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            let start = Instant::now();
+            match client
+                .get_account_with_commitment(&pool, CommitmentConfig::confirmed())
+                .await
+            {
+                Ok(response) => {
+                    tracker
+                        .record_endpoint(FeedKind::Rpc, &client.url(), start.elapsed())
+                        .await;
+                    if let Some(account) = response.value {
+                        let data = base64::engine::general_purpose::STANDARD.encode(&account.data);
+                        arrivals.lock().unwrap().record_rpc(data, Instant::now());
+                    }
+                }
+                Err(e) => warn!("Polling {} failed: {}", pool, e),
+            }
+        }
+    });
+
+    info!("Comparing WebSocket and RPC feeds for {} over {:?}", pool, window);
+    let (ws_result, _) = tokio::join!(ws_feed, rpc_feed);
+    match ws_result {
+        Ok(Err(e)) => return Err(e),
+        Ok(Ok(())) => warn!("WebSocket closed before the sample window ended"),
+        Err(_) => {}
+    }
+
+    tracker.print_stats().await;
+    let comparison = compare_arrivals(&arrivals.lock().unwrap());
+    Ok(comparison)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arrivals(ws: &[(&str, u64)], rpc: &[(&str, u64)]) -> FeedArrivals {
+        let start = Instant::now();
+        let mut arrivals = FeedArrivals::default();
+        for (data, ms) in ws {
+            arrivals.record_ws(data.to_string(), start + Duration::from_millis(*ms));
+        }
+        for (data, ms) in rpc {
+            arrivals.record_rpc(data.to_string(), start + Duration::from_millis(*ms));
+        }
+        arrivals
+    }
+
+    #[test]
+    fn test_compare_arrivals_median_lead() {
+        let arrivals = arrivals(
+            &[("b", 100), ("c", 200), ("d", 300), ("e", 400), ("f", 500)],
+            // "a" is the state at startup; "f" was overwritten between polls
+            &[("a", 0), ("b", 250), ("c", 280), ("c", 380), ("d", 360), ("e", 390)],
+        );
+
+        let comparison = compare_arrivals(&arrivals);
+        assert_eq!(comparison.matched, 4);
+        assert_eq!(comparison.ws_only, 1);
+        assert_eq!(comparison.rpc_only, 1);
+        // Leads of 150, 80, 60 and -10ms
+        assert_eq!(comparison.ws_first, 3);
+        assert!((comparison.median_lead_ms.unwrap() - 70.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_compare_arrivals_without_overlap() {
+        let comparison = compare_arrivals(&arrivals(&[("b", 100)], &[("a", 0)]));
+        assert_eq!(comparison.matched, 0);
+        assert_eq!(comparison.median_lead_ms, None);
+        assert!(comparison.to_string().contains("No account updates"));
+    }
+}
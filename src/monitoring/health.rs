@@ -2,7 +2,10 @@
 /// 
 /// Provides HTTP health check endpoint and graceful shutdown handling.
 
-use crate::metrics::METRICS;
+use crate::failover::{WalletFailover, WalletStatus};
+use crate::latency::{EndpointLatency, LatencyTracker};
+use crate::metrics::{MetricsRates, TimestampedSnapshot, METRICS, METRICS_HISTORY};
+use crate::rpc::{EndpointHealth, RpcPool};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::signal;
@@ -25,6 +28,24 @@ pub struct HealthMetrics {
     pub cache_hit_rate: f64,
     pub transactions_sent: u64,
     pub opportunities_found: u64,
+    /// Wallet token accounts the periodic check could not recreate
+    pub missing_atas: u64,
+}
+
+/// Detailed status including per-endpoint RPC health
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StatusResponse {
+    pub status: String,
+    pub uptime_seconds: u64,
+    pub endpoints: Vec<EndpointHealth>,
+    pub rates: MetricsRates,
+    pub history: Vec<TimestampedSnapshot>,
+    /// Wallet currently trading, when the bot runs with one
+    #[serde(default)]
+    pub wallet: Option<WalletStatus>,
+    /// Latency percentiles per RPC and WebSocket endpoint
+    #[serde(default)]
+    pub latency: Vec<EndpointLatency>,
 }
 
 /// Shutdown signal handler
@@ -79,7 +100,25 @@ impl ShutdownHandler {
 pub async fn start_health_server(
     port: u16,
     shutdown_handler: Arc<ShutdownHandler>,
+    rpc_pool: Arc<RpcPool>,
+    wallets: Option<Arc<WalletFailover>>,
+    latency: Option<Arc<LatencyTracker>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    info!("Starting health check server on port {}", port);
+    warp::serve(routes(shutdown_handler, rpc_pool, wallets, latency))
+        .run(([0, 0, 0, 0], port))
+        .await;
+
+    Ok(())
+}
+
+/// Build the /health, /ready, /status and /metrics routes
+fn routes(
+    shutdown_handler: Arc<ShutdownHandler>,
+    rpc_pool: Arc<RpcPool>,
+    wallets: Option<Arc<WalletFailover>>,
+    latency: Option<Arc<LatencyTracker>>,
+) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
     let health_route = warp::path("health")
         .and(warp::get())
         .and(with_shutdown(shutdown_handler.clone()))
@@ -95,6 +134,7 @@ pub async fn start_health_server(
                     cache_hit_rate: snapshot.cache_hit_rate(),
                     transactions_sent: snapshot.transactions_sent,
                     opportunities_found: snapshot.opportunities_found,
+                    missing_atas: METRICS.missing_atas(),
                 },
             };
 
@@ -105,42 +145,40 @@ pub async fn start_health_server(
         .and(warp::get())
         .map(|| warp::reply::json(&serde_json::json!({"ready": true})));
 
+    let status_route = warp::path("status")
+        .and(warp::get())
+        .and(with_shutdown(shutdown_handler))
+        .then(move |handler: Arc<ShutdownHandler>| {
+            let status = StatusResponse {
+                status: "healthy".to_string(),
+                uptime_seconds: handler.uptime_seconds(),
+                endpoints: rpc_pool.endpoint_health(),
+                rates: METRICS.rates(),
+                history: METRICS_HISTORY.lock().unwrap().snapshots(),
+                wallet: wallets.as_ref().map(|wallets| wallets.status()),
+                latency: Vec::new(),
+            };
+            let latency = latency.clone();
+            async move {
+                let latency = match latency {
+                    Some(tracker) => tracker.endpoint_latencies().await,
+                    None => Vec::new(),
+                };
+                warp::reply::json(&StatusResponse { latency, ..status })
+            }
+        });
+
     let metrics_route = warp::path("metrics")
         .and(warp::get())
         .map(|| {
-            let snapshot = METRICS.snapshot();
-            let metrics_text = format!(
-                "# HELP rpc_requests_total Total RPC requests\n\
-                 # TYPE rpc_requests_total counter\n\
-                 rpc_requests_total {}\n\
-                 # HELP rpc_failures_total Total RPC failures\n\
-                 # TYPE rpc_failures_total counter\n\
-                 rpc_failures_total {}\n\
-                 # HELP cache_hit_rate Cache hit rate percentage\n\
-                 # TYPE cache_hit_rate gauge\n\
-                 cache_hit_rate {}\n\
-                 # HELP transactions_sent Total transactions sent\n\
-                 # TYPE transactions_sent counter\n\
-                 transactions_sent {}\n\
-                 # HELP opportunities_found Total opportunities found\n\
-                 # TYPE opportunities_found counter\n\
-                 opportunities_found {}\n",
-                snapshot.rpc_requests_total,
-                snapshot.rpc_failures_total,
-                snapshot.cache_hit_rate(),
-                snapshot.transactions_sent,
-                snapshot.opportunities_found,
-            );
-            
-            warp::reply::with_header(metrics_text, "Content-Type", "text/plain")
+            warp::reply::with_header(
+                METRICS.gather_text(),
+                "Content-Type",
+                prometheus::TEXT_FORMAT,
+            )
         });
 
-    let routes = health_route.or(ready_route).or(metrics_route);
-
-    info!("Starting health check server on port {}", port);
-    warp::serve(routes).run(([0, 0, 0, 0], port)).await;
-
-    Ok(())
+    health_route.or(ready_route).or(status_route).or(metrics_route)
 }
 
 fn with_shutdown(
@@ -152,6 +190,7 @@ fn with_shutdown(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::latency::FeedKind;
 
     #[test]
     fn test_shutdown_handler() {
@@ -171,4 +210,72 @@ mod tests {
         
         assert!(handler.uptime_seconds() >= 0);
     }
+
+    async fn test_routes() -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+        let rpc_pool = RpcPool::new(
+            vec!["http://127.0.0.1:8899".to_string()],
+            1,
+            std::time::Duration::from_secs(1),
+        );
+        let latency = LatencyTracker::new();
+        latency
+            .record_endpoint(FeedKind::Ws, "ws://127.0.0.1:8900", std::time::Duration::from_millis(12))
+            .await;
+        routes(Arc::new(ShutdownHandler::new()), Arc::new(rpc_pool), None, Some(Arc::new(latency)))
+    }
+
+    #[tokio::test]
+    async fn test_status_endpoint_lists_rpc_endpoints() {
+        let response = warp::test::request()
+            .method("GET")
+            .path("/status")
+            .reply(&test_routes().await)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["endpoints"][0]["url"], "http://127.0.0.1:8899");
+        assert_eq!(body["endpoints"][0]["state"], "closed");
+        assert!(body["rates"]["transactions_sent_per_second"].is_number());
+        assert!(body["wallet"].is_null());
+        assert_eq!(body["latency"][0]["endpoint"], "ws://127.0.0.1:8900");
+        assert_eq!(body["latency"][0]["feed"], "ws");
+        assert_eq!(body["latency"][0]["p95_ms"], 12.0);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_serves_prometheus_text() {
+        METRICS.inc_tx_sent();
+
+        let response = warp::test::request()
+            .method("GET")
+            .path("/metrics")
+            .reply(&test_routes().await)
+            .await;
+        assert_eq!(response.status(), 200);
+
+        let body = String::from_utf8(response.body().to_vec()).unwrap();
+        let mut samples = std::collections::HashMap::new();
+        for line in body.lines().filter(|l| !l.starts_with('#') && !l.is_empty()) {
+            let (name, value) = line.rsplit_once(' ').expect("Malformed sample line");
+            let value: f64 = value.parse().expect("Sample value is not a number");
+            samples.insert(name.to_string(), value);
+        }
+
+        for name in [
+            "rpc_requests_total",
+            "rpc_failures_total",
+            "cache_hit_rate",
+            "transactions_sent",
+            "transactions_confirmed",
+            "total_profit_lamports",
+            "opportunities_found",
+            "opportunities_executed",
+            "transactions_sent_per_second",
+        ] {
+            assert!(samples.contains_key(name), "missing metric {}", name);
+            assert!(body.contains(&format!("# TYPE {} ", name)));
+        }
+        assert!(samples["transactions_sent"] >= 1.0);
+    }
 }
@@ -2,73 +2,204 @@
 /// 
 /// Provides WebSocket feeds, request batching, and latency tracking for arbitrage.
 
+use crate::constants::{LATENCY_WINDOW_SAMPLES, WS_RECONNECT_DELAY_SECS};
 use crate::error::{BotError, BotResult};
 use crate::metrics::METRICS;
+use async_trait::async_trait;
+use base64::Engine;
+use rand::Rng;
 use solana_client::nonblocking::rpc_client::RpcClient as AsyncRpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::hash::Hash;
 use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
-use futures_util::StreamExt;
+use futures_util::{future, SinkExt, Stream, StreamExt};
 use tracing::{debug, warn, info};
 
+/// Fixed-size window of the latest samples. A sorted copy is maintained on insert, so
+/// percentiles are an index lookup rather than a clone and sort per query.
+#[derive(Debug)]
+struct LatencyWindow {
+    capacity: usize,
+    /// Ring buffer in arrival order; `next` is the oldest sample once full
+    samples: Vec<Duration>,
+    next: usize,
+    sorted: Vec<Duration>,
+    sum: Duration,
+}
+
+impl LatencyWindow {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            samples: Vec::with_capacity(capacity),
+            next: 0,
+            sorted: Vec::with_capacity(capacity),
+            sum: Duration::ZERO,
+        }
+    }
+
+    fn push(&mut self, sample: Duration) {
+        if self.samples.len() < self.capacity {
+            self.samples.push(sample);
+        } else {
+            let evicted = std::mem::replace(&mut self.samples[self.next], sample);
+            self.next = (self.next + 1) % self.capacity;
+            if let Ok(index) = self.sorted.binary_search(&evicted) {
+                self.sorted.remove(index);
+            }
+            self.sum -= evicted;
+        }
+
+        let index = self.sorted.partition_point(|existing| *existing <= sample);
+        self.sorted.insert(index, sample);
+        self.sum += sample;
+    }
+
+    fn average(&self) -> Duration {
+        if self.sorted.is_empty() {
+            return Duration::from_millis(0);
+        }
+        self.sum / self.sorted.len() as u32
+    }
+
+    fn percentile(&self, percentile: f64) -> Duration {
+        if self.sorted.is_empty() {
+            return Duration::from_millis(0);
+        }
+        let index = (self.sorted.len() as f64 * percentile) as usize;
+        self.sorted[index.min(self.sorted.len() - 1)]
+    }
+}
+
+/// Transport a latency sample was measured on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedKind {
+    Rpc,
+    Ws,
+}
+
+impl FeedKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedKind::Rpc => "RPC",
+            FeedKind::Ws => "WS",
+        }
+    }
+}
+
+/// Latency of one endpoint over its latest samples
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EndpointLatency {
+    pub endpoint: String,
+    pub feed: FeedKind,
+    pub samples: usize,
+    pub avg_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_micros() as f64 / 1_000.0
+}
+
 /// Latency tracker for monitoring performance
 pub struct LatencyTracker {
-    rpc_latencies: Arc<RwLock<Vec<Duration>>>,
-    ws_latencies: Arc<RwLock<Vec<Duration>>>,
+    rpc_latencies: Arc<RwLock<LatencyWindow>>,
+    ws_latencies: Arc<RwLock<LatencyWindow>>,
+    /// A window per feed and endpoint URL, kept across reconnects to the same URL, so a
+    /// slow RPC can be told apart from a slow WebSocket
+    endpoint_latencies: Arc<RwLock<HashMap<FeedKind, HashMap<String, LatencyWindow>>>>,
 }
 
 impl LatencyTracker {
     pub fn new() -> Self {
         Self {
-            rpc_latencies: Arc::new(RwLock::new(Vec::with_capacity(1000))),
-            ws_latencies: Arc::new(RwLock::new(Vec::with_capacity(1000))),
+            rpc_latencies: Arc::new(RwLock::new(LatencyWindow::new(LATENCY_WINDOW_SAMPLES))),
+            ws_latencies: Arc::new(RwLock::new(LatencyWindow::new(LATENCY_WINDOW_SAMPLES))),
+            endpoint_latencies: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Record a latency measured against `endpoint`, in its own window and the feed's
+    /// aggregate
+    pub async fn record_endpoint(&self, feed: FeedKind, endpoint: &str, duration: Duration) {
+        match feed {
+            FeedKind::Rpc => self.record_rpc(duration).await,
+            FeedKind::Ws => self.record_ws(duration).await,
+        }
+        let mut windows = self.endpoint_latencies.write().await;
+        let windows = windows.entry(feed).or_default();
+        match windows.get_mut(endpoint) {
+            Some(window) => window.push(duration),
+            None => {
+                let mut window = LatencyWindow::new(LATENCY_WINDOW_SAMPLES);
+                window.push(duration);
+                windows.insert(endpoint.to_string(), window);
+            }
         }
     }
 
+    /// Percentiles of every endpoint recorded through `record_endpoint`, RPC first, then
+    /// by URL
+    pub async fn endpoint_latencies(&self) -> Vec<EndpointLatency> {
+        let windows = self.endpoint_latencies.read().await;
+        let mut latencies: Vec<EndpointLatency> = windows
+            .iter()
+            .flat_map(|(feed, endpoints)| {
+                endpoints.iter().map(|(endpoint, window)| EndpointLatency {
+                    endpoint: endpoint.clone(),
+                    feed: *feed,
+                    samples: window.sorted.len(),
+                    avg_ms: millis(window.average()),
+                    p50_ms: millis(window.percentile(0.50)),
+                    p95_ms: millis(window.percentile(0.95)),
+                    p99_ms: millis(window.percentile(0.99)),
+                })
+            })
+            .collect();
+        latencies.sort_by(|a, b| (a.feed, &a.endpoint).cmp(&(b.feed, &b.endpoint)));
+        latencies
+    }
+
     /// Record RPC latency
     pub async fn record_rpc(&self, duration: Duration) {
-        let mut latencies = self.rpc_latencies.write().await;
-        latencies.push(duration);
-        
-        // Keep only last 1000 measurements
-        if latencies.len() > 1000 {
-            latencies.drain(0..100);
-        }
+        METRICS.observe_rpc_latency(duration);
+        self.rpc_latencies.write().await.push(duration);
     }
 
     /// Record WebSocket latency
     pub async fn record_ws(&self, duration: Duration) {
-        let mut latencies = self.ws_latencies.write().await;
-        latencies.push(duration);
-        
-        if latencies.len() > 1000 {
-            latencies.drain(0..100);
-        }
+        METRICS.observe_ws_latency(duration);
+        self.ws_latencies.write().await.push(duration);
     }
 
     /// Get average RPC latency
     pub async fn avg_rpc_latency(&self) -> Duration {
-        let latencies = self.rpc_latencies.read().await;
-        if latencies.is_empty() {
-            return Duration::from_millis(0);
-        }
-        
-        let sum: Duration = latencies.iter().sum();
-        sum / latencies.len() as u32
+        self.rpc_latencies.read().await.average()
     }
 
     /// Get p95 RPC latency
     pub async fn p95_rpc_latency(&self) -> Duration {
-        let mut latencies = self.rpc_latencies.read().await.clone();
-        if latencies.is_empty() {
-            return Duration::from_millis(0);
-        }
-        
-        latencies.sort();
-        let index = (latencies.len() as f64 * 0.95) as usize;
-        latencies[index.min(latencies.len() - 1)]
+        self.rpc_latencies.read().await.percentile(0.95)
+    }
+
+    /// Get average WebSocket latency
+    pub async fn avg_ws_latency(&self) -> Duration {
+        self.ws_latencies.read().await.average()
+    }
+
+    /// Get p95 WebSocket latency
+    pub async fn p95_ws_latency(&self) -> Duration {
+        self.ws_latencies.read().await.percentile(0.95)
     }
 
     /// Print latency statistics
@@ -79,10 +210,174 @@ impl LatencyTracker {
         info!("Latency Stats:");
         info!("  RPC Avg: {}ms", avg_rpc.as_millis());
         info!("  RPC P95: {}ms", p95_rpc.as_millis());
+        info!("  WS Avg: {}ms", self.avg_ws_latency().await.as_millis());
+        info!("  WS P95: {}ms", self.p95_ws_latency().await.as_millis());
+        for latency in self.endpoint_latencies().await {
+            info!(
+                "  {} {}: p50 {:.1}ms, p95 {:.1}ms, p99 {:.1}ms ({} samples)",
+                latency.feed.as_str(),
+                latency.endpoint,
+                latency.p50_ms,
+                latency.p95_ms,
+                latency.p99_ms,
+                latency.samples
+            );
+        }
+    }
+}
+
+/// Exponential moving average of iteration latency
+#[derive(Debug, Clone)]
+pub struct LatencyEma {
+    alpha: f64,
+    value: Option<Duration>,
+}
+
+impl LatencyEma {
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha: alpha.clamp(0.0, 1.0),
+            value: None,
+        }
+    }
+
+    /// Fold a new sample into the average (the first sample seeds it)
+    pub fn update(&mut self, sample: Duration) -> Duration {
+        let next = match self.value {
+            None => sample,
+            Some(current) => {
+                let nanos = self.alpha * sample.as_nanos() as f64
+                    + (1.0 - self.alpha) * current.as_nanos() as f64;
+                Duration::from_nanos(nanos.round() as u64)
+            }
+        };
+        self.value = Some(next);
+        next
+    }
+
+    pub fn value(&self) -> Option<Duration> {
+        self.value
+    }
+}
+
+/// Random delay in `[0, max_jitter]` that phase-shifts a strategy loop's first iteration
+pub fn startup_delay<R: Rng + ?Sized>(max_jitter: Duration, rng: &mut R) -> Duration {
+    if max_jitter.is_zero() {
+        return Duration::ZERO;
+    }
+    Duration::from_millis(rng.gen_range(0..=max_jitter.as_millis() as u64))
+}
+
+/// Sleep scaled to observed latency, clamped to `[min, max]`
+pub fn adaptive_delay(ema: Duration, factor: f64, min: Duration, max: Duration) -> Duration {
+    ema.mul_f64(factor.max(0.0)).clamp(min, max.max(min))
+}
+
+/// Stretches a strategy loop's sleep while its mint keeps producing nothing to trade
+#[derive(Debug)]
+pub struct IdleBackoff {
+    idle_after: u32,
+    max_delay: Duration,
+    idle_iterations: u32,
+}
+
+impl IdleBackoff {
+    pub fn new(idle_after: u32, max_delay: Duration) -> Self {
+        Self {
+            idle_after,
+            max_delay,
+            idle_iterations: 0,
+        }
+    }
+
+    /// Sleep after an iteration: `base` until `idle_after` idle iterations in a row, then
+    /// doubling with every further idle iteration up to `max_delay`. A profitable
+    /// opportunity drops straight back to `base`.
+    pub fn next_delay(&mut self, found_opportunity: bool, base: Duration) -> Duration {
+        if found_opportunity {
+            self.idle_iterations = 0;
+            return base;
+        }
+        self.idle_iterations = self.idle_iterations.saturating_add(1);
+        let Some(excess) = self.idle_iterations.checked_sub(self.idle_after) else {
+            return base;
+        };
+        let stretched = base.saturating_mul(2u32.saturating_pow(excess.min(31)));
+        stretched.min(self.max_delay.max(base))
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.idle_iterations > self.idle_after
+    }
+
+    /// Drop back to `base` without waiting for an opportunity, e.g. when a WebSocket
+    /// feed reports that a pool moved
+    pub fn reset(&mut self) {
+        self.idle_iterations = 0;
+    }
+}
+
+/// How account data is delivered by `accountSubscribe`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountEncoding {
+    /// Raw account bytes, decoded straight into the account
+    Base64,
+    /// Program-parsed JSON where the RPC knows the layout; slower, for debugging
+    JsonParsed,
+}
+
+impl AccountEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountEncoding::Base64 => "base64",
+            AccountEncoding::JsonParsed => "jsonParsed",
+        }
+    }
+}
+
+/// Encoding and commitment of one account subscription
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriptionConfig {
+    pub encoding: AccountEncoding,
+    pub commitment: CommitmentLevel,
+}
+
+impl Default for SubscriptionConfig {
+    /// Vault feeds are on the hot path: raw bytes at the earliest commitment
+    fn default() -> Self {
+        Self {
+            encoding: AccountEncoding::Base64,
+            commitment: CommitmentLevel::Processed,
+        }
+    }
+}
+
+impl SubscriptionConfig {
+    /// Parsed, confirmed updates for inspecting an account by hand
+    pub fn debug() -> Self {
+        Self {
+            encoding: AccountEncoding::JsonParsed,
+            commitment: CommitmentLevel::Confirmed,
+        }
+    }
+
+    /// `accountSubscribe` request for `pubkey`
+    pub fn subscribe_message(&self, pubkey: &Pubkey) -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "accountSubscribe",
+            "params": [
+                pubkey.to_string(),
+                {
+                    "encoding": self.encoding.as_str(),
+                    "commitment": self.commitment
+                }
+            ]
+        })
     }
 }
 
-/// WebSocket account subscriber for real-time updates
 pub struct AccountSubscriber {
     ws_url: String,
     latency_tracker: Arc<LatencyTracker>,
@@ -98,31 +393,45 @@ impl AccountSubscriber {
 
     /// Subscribe to account updates via WebSocket
     pub async fn subscribe_account(&self, pubkey: &Pubkey) -> BotResult<()> {
+        self.subscribe_account_updates(pubkey, SubscriptionConfig::default(), |_| {})
+            .await
+    }
+
+    /// Subscribe to account updates, calling `on_update` with each new account state
+    /// until the connection closes
+    pub async fn subscribe_account_updates<F>(
+        &self,
+        pubkey: &Pubkey,
+        subscription: SubscriptionConfig,
+        mut on_update: F,
+    ) -> BotResult<()>
+    where
+        F: FnMut(AccountNotification),
+    {
+        let ws_error = |message: String| BotError::RpcError {
+            endpoint: self.ws_url.clone(),
+            message,
+            retryable: true,
+        };
+
         let (ws_stream, _) = connect_async(&self.ws_url)
             .await
-            .map_err(|e| BotError::RpcError {
-                endpoint: self.ws_url.clone(),
-                message: format!("WebSocket connect failed: {}", e),
-                retryable: true,
-            })?;
+            .map_err(|e| ws_error(format!("WebSocket connect failed: {}", e)))?;
 
-        let (_, mut read) = ws_stream.split();
+        let (mut write, mut read) = ws_stream.split();
 
-        // Subscribe message
-        let subscribe_msg = serde_json::json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "accountSubscribe",
-            "params": [
-                pubkey.to_string(),
-                {
-                    "encoding": "jsonParsed",
-                    "commitment": "confirmed"
-                }
-            ]
-        });
+        let subscribe_msg = subscription.subscribe_message(pubkey);
+        write
+            .send(Message::Text(subscribe_msg.to_string()))
+            .await
+            .map_err(|e| ws_error(format!("accountSubscribe failed: {}", e)))?;
 
-        debug!("WebSocket subscribed to account: {}", pubkey);
+        debug!(
+            "WebSocket subscribed to account: {} ({}, {:?})",
+            pubkey,
+            subscription.encoding.as_str(),
+            subscription.commitment
+        );
 
         // Listen for updates
         while let Some(msg) = read.next().await {
@@ -132,9 +441,14 @@ impl AccountSubscriber {
                 Ok(Message::Text(text)) => {
                     // Process account update
                     debug!("Account update received: {} bytes", text.len());
+                    if let Some(notification) = parse_account_notification(&text) {
+                        on_update(notification);
+                    }
                     
                     let latency = start.elapsed();
-                    self.latency_tracker.record_ws(latency).await;
+                    self.latency_tracker
+                        .record_endpoint(FeedKind::Ws, &self.ws_url, latency)
+                        .await;
                     
                     if latency.as_millis() > 50 {
                         warn!("High WebSocket latency: {}ms", latency.as_millis());
@@ -156,6 +470,283 @@ impl AccountSubscriber {
     }
 }
 
+/// Wake `updates` on every update of any of `accounts`, each over its own subscription,
+/// re-subscribing `WS_RECONNECT_DELAY_SECS` after one drops. Runs until aborted.
+pub async fn notify_account_updates(subscriber: AccountSubscriber, accounts: Vec<Pubkey>, updates: Arc<Notify>) {
+    future::join_all(accounts.iter().map(|account| {
+        let (subscriber, updates) = (&subscriber, &updates);
+        async move {
+            loop {
+                if let Err(e) = subscriber
+                    .subscribe_account_updates(account, SubscriptionConfig::default(), |_| updates.notify_one())
+                    .await
+                {
+                    warn!("Subscription to pool account {} failed: {}", account, e);
+                }
+                tokio::time::sleep(Duration::from_secs(WS_RECONNECT_DELAY_SECS)).await;
+            }
+        }
+    }))
+    .await;
+}
+
+/// Account state carried by an `accountNotification` message
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountNotification {
+    pub slot: u64,
+    /// The account, with its raw data when the update carried bytes
+    pub account: Account,
+    /// Program-parsed data of a `jsonParsed` update (the account's data is then empty)
+    pub parsed: Option<serde_json::Value>,
+}
+
+/// Parse an `accountNotification` in either encoding. `jsonParsed` falls back to
+/// base64 bytes for programs the RPC cannot parse.
+pub fn parse_account_notification(text: &str) -> Option<AccountNotification> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    if value["method"] != "accountNotification" {
+        return None;
+    }
+    let result = &value["params"]["result"];
+    let account = &result["value"];
+    let (data, parsed) = match &account["data"] {
+        serde_json::Value::Array(encoded) if encoded.get(1)?.as_str()? == "base64" => {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(encoded.first()?.as_str()?)
+                .ok()?;
+            (bytes, None)
+        }
+        parsed @ serde_json::Value::Object(_) => (Vec::new(), Some(parsed.clone())),
+        _ => return None,
+    };
+    Some(AccountNotification {
+        slot: result["context"]["slot"].as_u64()?,
+        account: Account {
+            lamports: account["lamports"].as_u64()?,
+            data,
+            owner: Pubkey::from_str(account["owner"].as_str()?).ok()?,
+            executable: account["executable"].as_bool()?,
+            rent_epoch: account["rentEpoch"].as_u64().unwrap_or(u64::MAX),
+        },
+        parsed,
+    })
+}
+
+/// Latest blockhash shared by the refresher tasks and strategy loops
+pub struct BlockhashCache {
+    inner: std::sync::RwLock<CachedBlockhash>,
+}
+
+struct CachedBlockhash {
+    hash: Hash,
+    slot: Option<u64>,
+    /// Last block height `hash` is accepted at, when it was fetched with it
+    last_valid_block_height: Option<u64>,
+    updated_at: Instant,
+}
+
+/// Block heights for the expiry guard, read on the send path
+#[async_trait]
+pub trait BlockHeightSource: Sync {
+    async fn block_height(&self) -> BotResult<u64>;
+    /// Latest blockhash with its lastValidBlockHeight
+    async fn latest_blockhash_with_expiry(&self) -> BotResult<(Hash, u64)>;
+}
+
+#[async_trait]
+impl BlockHeightSource for AsyncRpcClient {
+    async fn block_height(&self) -> BotResult<u64> {
+        Ok(self.get_block_height().await?)
+    }
+
+    async fn latest_blockhash_with_expiry(&self) -> BotResult<(Hash, u64)> {
+        Ok(self.get_latest_blockhash_with_commitment(self.commitment()).await?)
+    }
+}
+
+/// Whether a blockhash valid through `last_valid_block_height` expires within `margin`
+/// blocks of `block_height`
+pub fn is_near_expiry(last_valid_block_height: u64, block_height: u64, margin: u64) -> bool {
+    last_valid_block_height.saturating_sub(block_height) <= margin
+}
+
+impl BlockhashCache {
+    pub fn new(hash: Hash) -> Self {
+        Self {
+            inner: std::sync::RwLock::new(CachedBlockhash {
+                hash,
+                slot: None,
+                last_valid_block_height: None,
+                updated_at: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn with_expiry(hash: Hash, last_valid_block_height: u64) -> Self {
+        let cache = Self::new(hash);
+        cache.inner.write().unwrap().last_valid_block_height = Some(last_valid_block_height);
+        cache
+    }
+
+    pub fn get(&self) -> Hash {
+        self.inner.read().unwrap().hash
+    }
+
+    /// Store a freshly fetched blockhash, with the slot that triggered the fetch if known.
+    /// The lastValidBlockHeight last supplied with a blockhash is kept: a newer blockhash
+    /// stays valid at least that long, so the expiry guard only errs toward refreshing.
+    pub fn update(&self, hash: Hash, slot: Option<u64>) {
+        self.store(hash, slot, None);
+    }
+
+    /// Same as `update`, keeping the blockhash's lastValidBlockHeight for the expiry guard
+    pub fn update_with_expiry(&self, hash: Hash, slot: Option<u64>, last_valid_block_height: u64) {
+        self.store(hash, slot, Some(last_valid_block_height));
+    }
+
+    fn store(&self, hash: Hash, slot: Option<u64>, last_valid_block_height: Option<u64>) {
+        let mut inner = self.inner.write().unwrap();
+        inner.hash = hash;
+        inner.slot = slot.or(inner.slot);
+        inner.last_valid_block_height = last_valid_block_height.or(inner.last_valid_block_height);
+        inner.updated_at = Instant::now();
+    }
+
+    pub fn last_valid_block_height(&self) -> Option<u64> {
+        self.inner.read().unwrap().last_valid_block_height
+    }
+
+    /// The cached blockhash, first replaced by a fresh one if it expires within `margin`
+    /// blocks of the current block height. A blockhash cached without its
+    /// lastValidBlockHeight is returned as is.
+    pub async fn unexpired(&self, source: &dyn BlockHeightSource, margin: u64) -> BotResult<Hash> {
+        let (hash, last_valid_block_height) = {
+            let inner = self.inner.read().unwrap();
+            (inner.hash, inner.last_valid_block_height)
+        };
+        let Some(last_valid_block_height) = last_valid_block_height else {
+            return Ok(hash);
+        };
+        let block_height = source.block_height().await?;
+        if !is_near_expiry(last_valid_block_height, block_height, margin) {
+            return Ok(hash);
+        }
+
+        let (fresh, fresh_last_valid) = source.latest_blockhash_with_expiry().await?;
+        debug!(
+            "Blockhash {} valid through block {} is near expiry at block {}, refreshed to {}",
+            hash, last_valid_block_height, block_height, fresh
+        );
+        METRICS.inc_near_expiry_refresh();
+        self.update_with_expiry(fresh, None, fresh_last_valid);
+        Ok(fresh)
+    }
+
+    /// Last slot seen by the slot subscription
+    pub fn slot(&self) -> Option<u64> {
+        self.inner.read().unwrap().slot
+    }
+
+    /// Time since the blockhash was last refreshed
+    pub fn age(&self) -> Duration {
+        self.inner.read().unwrap().updated_at.elapsed()
+    }
+}
+
+/// Slot number carried by a `slotNotification` message
+pub fn parse_slot_notification(text: &str) -> Option<u64> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    if value["method"] != "slotNotification" {
+        return None;
+    }
+    value["params"]["result"]["slot"].as_u64()
+}
+
+/// Refresh `cache` from `source` for every new slot in `notifications` until the stream
+/// ends, keeping each blockhash's lastValidBlockHeight for the expiry guard.
+/// Returns how many times the blockhash was updated. With `latency`, the time each new
+/// slot took to reach the cache is recorded against the endpoint.
+pub async fn apply_slot_notifications<S>(
+    mut notifications: S,
+    cache: &BlockhashCache,
+    source: &dyn BlockHeightSource,
+    latency: Option<(&LatencyTracker, &str)>,
+) -> usize
+where
+    S: Stream<Item = String> + Unpin,
+{
+    let mut updates = 0;
+    while let Some(text) = notifications.next().await {
+        let start = Instant::now();
+        let slot = match parse_slot_notification(&text) {
+            Some(slot) => slot,
+            None => continue,
+        };
+        // Notifications can arrive out of order across forks
+        if cache.slot().map_or(false, |last| slot <= last) {
+            continue;
+        }
+
+        match source.latest_blockhash_with_expiry().await {
+            Ok((hash, last_valid_block_height)) => {
+                cache.update_with_expiry(hash, Some(slot), last_valid_block_height);
+                updates += 1;
+                if let Some((tracker, endpoint)) = latency {
+                    tracker.record_endpoint(FeedKind::Ws, endpoint, start.elapsed()).await;
+                }
+            }
+            Err(e) => warn!("Failed to fetch blockhash for slot {}: {}", slot, e),
+        }
+    }
+    updates
+}
+
+/// Subscribe to slot updates and refresh `cache` on each new slot.
+/// Returns when the connection closes; callers fall back to polling meanwhile.
+pub async fn subscribe_slot_blockhash(
+    ws_url: &str,
+    cache: &BlockhashCache,
+    source: &dyn BlockHeightSource,
+    latency_tracker: &LatencyTracker,
+) -> BotResult<()> {
+    let ws_error = |message: String| BotError::RpcError {
+        endpoint: ws_url.to_string(),
+        message,
+        retryable: true,
+    };
+
+    let (ws_stream, _) = connect_async(ws_url)
+        .await
+        .map_err(|e| ws_error(format!("WebSocket connect failed: {}", e)))?;
+    let (mut write, read) = ws_stream.split();
+
+    let subscribe_msg = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "slotSubscribe",
+    });
+    write
+        .send(Message::Text(subscribe_msg.to_string()))
+        .await
+        .map_err(|e| ws_error(format!("slotSubscribe failed: {}", e)))?;
+    info!("Subscribed to slot updates at {}", ws_url);
+
+    let notifications = read
+        .take_while(|msg| future::ready(matches!(msg, Ok(msg) if !msg.is_close())))
+        .filter_map(|msg| {
+            future::ready(match msg {
+                Ok(Message::Text(text)) => Some(text),
+                _ => None,
+            })
+        });
+    futures_util::pin_mut!(notifications);
+
+    let updates =
+        apply_slot_notifications(notifications, cache, source, Some((latency_tracker, ws_url))).await;
+    warn!("Slot subscription closed after {} blockhash updates", updates);
+    Ok(())
+}
+
 /// Batch RPC request processor for reduced latency
 pub struct BatchProcessor {
     client: Arc<AsyncRpcClient>,
@@ -197,7 +788,9 @@ impl BatchProcessor {
         }
 
         let latency = start.elapsed();
-        self.latency_tracker.record_rpc(latency).await;
+        self.latency_tracker
+            .record_endpoint(FeedKind::Rpc, &self.client.url(), latency)
+            .await;
         
         debug!(
             "Fetched {} accounts in {}ms (parallel)",
@@ -212,6 +805,71 @@ impl BatchProcessor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_subscribe_message_uses_subscription_config() {
+        let pubkey = Pubkey::new_unique();
+
+        let params = &SubscriptionConfig::default().subscribe_message(&pubkey)["params"];
+        assert_eq!(params[0], pubkey.to_string());
+        assert_eq!(params[1]["encoding"], "base64");
+        assert_eq!(params[1]["commitment"], "processed");
+
+        let params = &SubscriptionConfig::debug().subscribe_message(&pubkey)["params"];
+        assert_eq!(params[1]["encoding"], "jsonParsed");
+        assert_eq!(params[1]["commitment"], "confirmed");
+    }
+
+    #[test]
+    fn test_parse_base64_notification_into_account() {
+        let owner = Pubkey::new_unique();
+        let text = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "accountNotification",
+            "params": {
+                "result": {
+                    "context": { "slot": 42 },
+                    "value": {
+                        "lamports": 2_039_280u64,
+                        "data": [base64::engine::general_purpose::STANDARD.encode([1u8, 2, 3]), "base64"],
+                        "owner": owner.to_string(),
+                        "executable": false,
+                        "rentEpoch": 0
+                    }
+                },
+                "subscription": 7
+            }
+        })
+        .to_string();
+
+        let notification = parse_account_notification(&text).unwrap();
+        assert_eq!(notification.slot, 42);
+        assert_eq!(notification.account.data, vec![1, 2, 3]);
+        assert_eq!(notification.account.owner, owner);
+        assert_eq!(notification.account.lamports, 2_039_280);
+        assert!(notification.parsed.is_none());
+    }
+
+    #[test]
+    fn test_startup_delays_are_staggered() {
+        let max_jitter = Duration::from_millis(500);
+        let delays: Vec<Duration> = {
+            let mut rng = StdRng::seed_from_u64(7);
+            (0..8).map(|_| startup_delay(max_jitter, &mut rng)).collect()
+        };
+
+        assert!(delays.iter().all(|delay| *delay <= max_jitter));
+        let distinct: std::collections::HashSet<_> = delays.iter().collect();
+        assert!(distinct.len() > 1);
+
+        // Same seed, same schedule
+        let mut rng = StdRng::seed_from_u64(7);
+        let replay: Vec<Duration> = (0..8).map(|_| startup_delay(max_jitter, &mut rng)).collect();
+        assert_eq!(delays, replay);
+
+        assert_eq!(startup_delay(Duration::ZERO, &mut rng), Duration::ZERO);
+    }
 
     #[tokio::test]
     async fn test_latency_tracker() {
@@ -224,4 +882,204 @@ mod tests {
         let avg = tracker.avg_rpc_latency().await;
         assert_eq!(avg, Duration::from_millis(20));
     }
+
+    #[tokio::test]
+    async fn test_latency_tracked_per_endpoint() {
+        let tracker = LatencyTracker::new();
+        let (fast, slow) = ("https://fast-rpc.example.com", "https://slow-rpc.example.com");
+        for ms in 1..=100 {
+            tracker.record_endpoint(FeedKind::Rpc, fast, Duration::from_millis(ms)).await;
+            tracker
+                .record_endpoint(FeedKind::Rpc, slow, Duration::from_millis(1_000 + ms))
+                .await;
+        }
+        tracker
+            .record_endpoint(FeedKind::Ws, fast, Duration::from_millis(5))
+            .await;
+
+        let latencies = tracker.endpoint_latencies().await;
+        assert_eq!(latencies.len(), 3);
+        let (fast_rpc, slow_rpc, fast_ws) = (&latencies[0], &latencies[1], &latencies[2]);
+        assert_eq!((fast_rpc.endpoint.as_str(), fast_rpc.feed), (fast, FeedKind::Rpc));
+        assert_eq!((slow_rpc.endpoint.as_str(), slow_rpc.feed), (slow, FeedKind::Rpc));
+        assert_eq!((fast_ws.endpoint.as_str(), fast_ws.feed), (fast, FeedKind::Ws));
+
+        // Each endpoint's percentiles only see its own samples
+        assert_eq!(fast_rpc.samples, 100);
+        assert_eq!(fast_rpc.p50_ms, 51.0);
+        assert_eq!(fast_rpc.p95_ms, 96.0);
+        assert_eq!(slow_rpc.p50_ms, 1_051.0);
+        assert_eq!(slow_rpc.p95_ms, 1_096.0);
+        assert_eq!((fast_ws.samples, fast_ws.p99_ms), (1, 5.0));
+
+        // The aggregates mix both RPC endpoints and leave the WebSocket sample out
+        assert_eq!(tracker.avg_rpc_latency().await, Duration::from_micros(550_500));
+        assert_eq!(tracker.avg_ws_latency().await, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_latency_window_evicts_oldest() {
+        let mut window = LatencyWindow::new(100);
+        // Slow samples first, then a full window of fast ones pushes them all out
+        for ms in 0..100 {
+            window.push(Duration::from_millis(1_000 + ms));
+        }
+        for ms in (1..=100).rev() {
+            window.push(Duration::from_millis(ms));
+        }
+
+        assert_eq!(window.sorted.len(), 100);
+        assert_eq!(window.average(), Duration::from_micros(50_500));
+        assert_eq!(window.percentile(0.95), Duration::from_millis(96));
+        assert_eq!(window.percentile(1.0), Duration::from_millis(100));
+
+        // Matches a clone-and-sort of the same samples
+        let mut expected = window.samples.clone();
+        expected.sort();
+        assert_eq!(window.sorted, expected);
+    }
+
+    fn slot_notification(slot: u64) -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "slotNotification",
+            "params": {
+                "result": { "parent": slot - 1, "root": slot - 32, "slot": slot },
+                "subscription": 0
+            }
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_slot_notifications_refresh_blockhash() {
+        let cache = BlockhashCache::new(Hash::default());
+        let source = FixedHeight {
+            block_height: 1_000,
+            fetches: std::sync::Mutex::new(Vec::new()),
+        };
+
+        let notifications = futures_util::stream::iter(vec![
+            slot_notification(100),
+            r#"{"jsonrpc":"2.0","result":0,"id":1}"#.to_string(),
+            slot_notification(99),
+            slot_notification(101),
+        ]);
+        let tracker = LatencyTracker::new();
+        let updates =
+            apply_slot_notifications(notifications, &cache, &source, Some((&tracker, "wss://slots.example"))).await;
+
+        let fetched = source.fetches.into_inner().unwrap();
+        assert_eq!(updates, 2);
+        // One sample per slot that reached the cache
+        let latencies = tracker.endpoint_latencies().await;
+        assert_eq!(latencies.len(), 1);
+        assert_eq!((latencies[0].feed, latencies[0].samples), (FeedKind::Ws, 2));
+        assert_eq!(fetched.len(), 2);
+        assert_eq!(cache.get(), fetched[1]);
+        assert_eq!(cache.slot(), Some(101));
+        assert_eq!(cache.last_valid_block_height(), Some(1_150));
+        assert!(cache.age() < Duration::from_secs(1));
+    }
+
+    /// Serves a fixed block height and a new blockhash per fetch
+    struct FixedHeight {
+        block_height: u64,
+        fetches: std::sync::Mutex<Vec<Hash>>,
+    }
+
+    #[async_trait]
+    impl BlockHeightSource for FixedHeight {
+        async fn block_height(&self) -> BotResult<u64> {
+            Ok(self.block_height)
+        }
+
+        async fn latest_blockhash_with_expiry(&self) -> BotResult<(Hash, u64)> {
+            let hash = Hash::new_unique();
+            self.fetches.lock().unwrap().push(hash);
+            Ok((hash, self.block_height + 150))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_near_expiry_blockhash_forces_refresh() {
+        let source = FixedHeight {
+            block_height: 1_000,
+            fetches: std::sync::Mutex::new(Vec::new()),
+        };
+        let cached = Hash::new_unique();
+
+        // 30 blocks left is outside a 20-block margin
+        let cache = BlockhashCache::with_expiry(cached, 1_030);
+        assert_eq!(cache.unexpired(&source, 20).await.unwrap(), cached);
+        assert!(source.fetches.lock().unwrap().is_empty());
+
+        // 15 blocks left is within it
+        let cache = BlockhashCache::with_expiry(cached, 1_015);
+        let refreshes = METRICS.near_expiry_refreshes.load(std::sync::atomic::Ordering::Relaxed);
+        let fresh = cache.unexpired(&source, 20).await.unwrap();
+        assert_eq!(*source.fetches.lock().unwrap(), vec![fresh]);
+        assert_ne!(fresh, cached);
+        assert_eq!(cache.get(), fresh);
+        assert_eq!(cache.last_valid_block_height(), Some(1_150));
+        // A slot-triggered update without an expiry keeps the refresher's
+        cache.update(Hash::new_unique(), Some(5));
+        assert_eq!(cache.last_valid_block_height(), Some(1_150));
+        assert!(METRICS.near_expiry_refreshes.load(std::sync::atomic::Ordering::Relaxed) > refreshes);
+
+        // Without a known expiry there is nothing to compare
+        let cache = BlockhashCache::new(cached);
+        assert_eq!(cache.unexpired(&source, 20).await.unwrap(), cached);
+        assert_eq!(source.fetches.lock().unwrap().len(), 1);
+
+        assert!(is_near_expiry(1_000, 1_010, 20));
+    }
+
+    #[test]
+    fn test_latency_ema_update() {
+        let mut ema = LatencyEma::new(0.5);
+
+        assert_eq!(ema.update(Duration::from_millis(100)), Duration::from_millis(100));
+        assert_eq!(ema.update(Duration::from_millis(200)), Duration::from_millis(150));
+        assert_eq!(ema.update(Duration::from_millis(50)), Duration::from_millis(100));
+        assert_eq!(ema.value(), Some(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn test_adaptive_delay_clamp() {
+        let min = Duration::from_millis(50);
+        let max = Duration::from_millis(500);
+
+        assert_eq!(adaptive_delay(Duration::from_millis(10), 2.0, min, max), min);
+        assert_eq!(
+            adaptive_delay(Duration::from_millis(100), 2.0, min, max),
+            Duration::from_millis(200)
+        );
+        assert_eq!(adaptive_delay(Duration::from_secs(1), 2.0, min, max), max);
+    }
+
+    #[test]
+    fn test_idle_backoff_grows_then_resets() {
+        let base = Duration::from_millis(100);
+        let mut backoff = IdleBackoff::new(2, Duration::from_millis(1_000));
+
+        let delays: Vec<Duration> = (0..7).map(|_| backoff.next_delay(false, base)).collect();
+        assert_eq!(
+            delays,
+            [100, 100, 100, 200, 400, 800, 1_000].map(Duration::from_millis)
+        );
+        assert!(backoff.is_idle());
+
+        assert_eq!(backoff.next_delay(true, base), base);
+        assert!(!backoff.is_idle());
+        assert_eq!(backoff.next_delay(false, base), base);
+
+        // A pool update ends the idle stretch too
+        for _ in 0..5 {
+            backoff.next_delay(false, base);
+        }
+        assert!(backoff.is_idle());
+        backoff.reset();
+        assert_eq!(backoff.next_delay(false, base), base);
+    }
 }
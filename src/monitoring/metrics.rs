@@ -3,13 +3,28 @@
 /// Provides prometheus-compatible metrics for monitoring bot performance.
 
 use lazy_static::lazy_static;
+use prometheus::proto::MetricFamily;
+use prometheus::{
+    Encoder, Gauge, Histogram, HistogramOpts, IntCounter, IntCounterVec, Opts, Registry,
+    TextEncoder,
+};
+use crate::config::MetricsPushConfig;
+use crate::constants::{
+    METRICS_HISTORY_CAPACITY, METRICS_HISTORY_FILE, METRICS_HISTORY_INTERVAL_SECS,
+    METRICS_RATE_WINDOW_SECS,
+};
+use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 lazy_static! {
     /// Global metrics registry
     pub static ref METRICS: Arc<BotMetrics> = Arc::new(BotMetrics::new());
+
+    /// Recent periodic snapshots for the `stats` command
+    pub static ref METRICS_HISTORY: Mutex<MetricsHistory> =
+        Mutex::new(MetricsHistory::new(METRICS_HISTORY_CAPACITY));
 }
 
 /// Bot performance metrics
@@ -23,20 +38,300 @@ pub struct BotMetrics {
     // Pool metrics
     pub pools_initialized_total: AtomicU64,
     pub pool_initialization_failures: AtomicU64,
+    pub pools_removed: AtomicU64,
+    pub stale_account_rejections: AtomicU64,
+    pub compute_unit_price_floor_applied: AtomicU64,
+    pub capital_cap_blocked: AtomicU64,
+    pub wallet_failovers: AtomicU64,
+    pub missing_atas: AtomicU64,
+    pub recent_signatures: AtomicU64,
     
     // Transaction metrics
     pub transactions_sent: AtomicU64,
     pub transactions_confirmed: AtomicU64,
     pub transactions_failed: AtomicU64,
+    pub sends_already_processed: AtomicU64,
+    pub sends_account_in_use: AtomicU64,
+    pub near_expiry_refreshes: AtomicU64,
     
     // Arbitrage metrics
     pub opportunities_found: AtomicU64,
     pub opportunities_executed: AtomicU64,
+    pub opportunities_dropped: AtomicU64,
+    pub duplicate_opportunities_suppressed: AtomicU64,
     pub total_profit_lamports: AtomicU64,
+    pub total_fees_lamports: AtomicU64,
+    pub idle_backoff_ms: AtomicU64,
+    pub opportunity_feed_dropped: AtomicU64,
+
+    // Watchdog metrics
+    pub watchdog_trips: AtomicU64,
+
+    // Persistence metrics
+    pub db_write_failures: AtomicU64,
+
+    // Jito metrics
+    pub jito_bundles_sent: AtomicU64,
+    pub jito_bundles_failed: AtomicU64,
+
+    // Live throughput over a sliding window
+    opportunity_rate: RateWindow,
+    tx_sent_rate: RateWindow,
+    tx_confirmed_rate: RateWindow,
+
+    // Prometheus mirror exported on /metrics
+    prometheus: PrometheusMetrics,
+}
+
+/// Prometheus registry mirroring every `BotMetrics` counter, plus per-DEX and latency metrics
+struct PrometheusMetrics {
+    registry: Registry,
+    rpc_requests_total: IntCounter,
+    rpc_failures_total: IntCounter,
+    rpc_cache_hits: IntCounter,
+    rpc_cache_misses: IntCounter,
+    pools_initialized_total: IntCounter,
+    pool_initialization_failures: IntCounter,
+    pools_removed: IntCounter,
+    stale_account_rejections: IntCounter,
+    compute_unit_price_floor_applied: IntCounter,
+    capital_cap_blocked: IntCounter,
+    wallet_failovers: IntCounter,
+    missing_atas: Gauge,
+    recent_signatures: Gauge,
+    transactions_sent: IntCounter,
+    transactions_confirmed: IntCounter,
+    transactions_failed: IntCounter,
+    sends_already_processed: IntCounter,
+    sends_account_in_use: IntCounter,
+    near_expiry_refreshes: IntCounter,
+    opportunities_found: IntCounter,
+    opportunities_executed: IntCounter,
+    opportunities_dropped: IntCounter,
+    duplicate_opportunities_suppressed: IntCounter,
+    total_profit_lamports: IntCounter,
+    total_fees_lamports: IntCounter,
+    idle_backoff_ms_total: IntCounter,
+    opportunity_feed_dropped: IntCounter,
+    cache_hit_rate: Gauge,
+    tx_success_rate: Gauge,
+    watchdog_trips_total: IntCounter,
+    trading_paused: Gauge,
+    db_write_failures: IntCounter,
+    jito_bundles_sent: IntCounter,
+    jito_bundles_failed: IntCounterVec,
+    sends_by_confirmation_wait: IntCounterVec,
+    blockhash_age_seconds: Gauge,
+    opportunities_per_second: Gauge,
+    transactions_sent_per_second: Gauge,
+    transactions_confirmed_per_second: Gauge,
+    pools_initialized_by_dex: IntCounterVec,
+    pool_failures_by_dex: IntCounterVec,
+    rpc_latency_seconds: Histogram,
+    ws_latency_seconds: Histogram,
+    profit_estimation_error: Histogram,
+}
+
+impl PrometheusMetrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let counter = |name: &str, help: &str| {
+            let counter = IntCounter::new(name, help).expect("Invalid counter definition");
+            registry
+                .register(Box::new(counter.clone()))
+                .expect("Duplicate counter registration");
+            counter
+        };
+        let gauge = |name: &str, help: &str| {
+            let gauge = Gauge::new(name, help).expect("Invalid gauge definition");
+            registry
+                .register(Box::new(gauge.clone()))
+                .expect("Duplicate gauge registration");
+            gauge
+        };
+        let dex_counter = |name: &str, help: &str| {
+            let counter = IntCounterVec::new(Opts::new(name, help), &["dex"])
+                .expect("Invalid counter definition");
+            registry
+                .register(Box::new(counter.clone()))
+                .expect("Duplicate counter registration");
+            counter
+        };
+        let reason_counter = |name: &str, help: &str| {
+            let counter = IntCounterVec::new(Opts::new(name, help), &["reason"])
+                .expect("Invalid counter definition");
+            registry
+                .register(Box::new(counter.clone()))
+                .expect("Duplicate counter registration");
+            counter
+        };
+        let histogram = |name: &str, help: &str| {
+            let histogram = Histogram::with_opts(
+                HistogramOpts::new(name, help)
+                    .buckets(vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5]),
+            )
+            .expect("Invalid histogram definition");
+            registry
+                .register(Box::new(histogram.clone()))
+                .expect("Duplicate histogram registration");
+            histogram
+        };
+
+        // Signed lamport buckets; the sum over the count shows systematic over/under-estimation
+        let profit_estimation_error = Histogram::with_opts(
+            HistogramOpts::new(
+                "profit_estimation_error",
+                "Realized minus estimated profit of reconciled trades, in lamports",
+            )
+            .buckets(vec![
+                -10_000_000.0,
+                -1_000_000.0,
+                -100_000.0,
+                -10_000.0,
+                0.0,
+                10_000.0,
+                100_000.0,
+                1_000_000.0,
+                10_000_000.0,
+            ]),
+        )
+        .expect("Invalid histogram definition");
+        registry
+            .register(Box::new(profit_estimation_error.clone()))
+            .expect("Duplicate histogram registration");
+
+        let sends_by_confirmation_wait = IntCounterVec::new(
+            Opts::new(
+                "sends_by_confirmation_wait",
+                "RPC sends per commitment the send path waited for (\"none\" when it didn't)",
+            ),
+            &["wait"],
+        )
+        .expect("Invalid counter definition");
+        registry
+            .register(Box::new(sends_by_confirmation_wait.clone()))
+            .expect("Duplicate counter registration");
+
+        Self {
+            rpc_requests_total: counter("rpc_requests_total", "Total RPC requests"),
+            rpc_failures_total: counter("rpc_failures_total", "Total RPC failures"),
+            rpc_cache_hits: counter("rpc_cache_hits", "RPC cache hits"),
+            rpc_cache_misses: counter("rpc_cache_misses", "RPC cache misses"),
+            pools_initialized_total: counter("pools_initialized_total", "Pools initialized"),
+            pool_initialization_failures: counter(
+                "pool_initialization_failures",
+                "Pool initialization failures",
+            ),
+            pools_removed: counter("pools_removed", "Pools dropped after closing on-chain"),
+            stale_account_rejections: counter(
+                "stale_account_rejections",
+                "Sends skipped because pool accounts were read too many slots behind the latest slot",
+            ),
+            compute_unit_price_floor_applied: counter(
+                "compute_unit_price_floor_applied",
+                "Transactions whose compute unit price was raised to the configured floor",
+            ),
+            capital_cap_blocked: counter(
+                "capital_cap_blocked",
+                "Sends skipped because max_total_input_lamports was reached",
+            ),
+            wallet_failovers: counter("wallet_failovers", "Switches from the primary to the backup wallet"),
+            missing_atas: gauge(
+                "missing_atas",
+                "Wallet token accounts found missing that could not be recreated",
+            ),
+            recent_signatures: gauge(
+                "recent_signatures",
+                "Signatures sent within the dedup window that are remembered",
+            ),
+            transactions_sent: counter("transactions_sent", "Total transactions sent"),
+            transactions_confirmed: counter("transactions_confirmed", "Transactions confirmed"),
+            transactions_failed: counter("transactions_failed", "Transactions failed"),
+            sends_already_processed: counter(
+                "sends_already_processed",
+                "Sends rejected as already processed, treated as sent and left to confirm",
+            ),
+            sends_account_in_use: counter(
+                "sends_account_in_use",
+                "Sends rejected because an account was locked by an in-flight transaction",
+            ),
+            near_expiry_refreshes: counter(
+                "near_expiry_refreshes",
+                "Blockhashes refetched before a send because they were about to expire",
+            ),
+            opportunities_found: counter("opportunities_found", "Total opportunities found"),
+            opportunities_executed: counter("opportunities_executed", "Opportunities executed"),
+            opportunities_dropped: counter(
+                "opportunities_dropped",
+                "Opportunities dropped because the send queue was full",
+            ),
+            duplicate_opportunities_suppressed: counter(
+                "duplicate_opportunities_suppressed",
+                "Opportunities skipped for repeating a mint's last trade within trade_cooldown_ms",
+            ),
+            total_profit_lamports: counter("total_profit_lamports", "Total profit in lamports"),
+            total_fees_lamports: counter(
+                "total_fees_lamports",
+                "Base, priority, tip and swap fees paid by landed trades, in lamports",
+            ),
+            idle_backoff_ms_total: counter(
+                "idle_backoff_ms_total",
+                "Milliseconds strategy loops slept beyond their delay while idle",
+            ),
+            opportunity_feed_dropped: counter(
+                "opportunity_feed_dropped",
+                "Opportunity feed events dropped because a client fell too far behind",
+            ),
+            cache_hit_rate: gauge("cache_hit_rate", "Cache hit rate percentage"),
+            tx_success_rate: gauge("tx_success_rate", "Transaction success rate percentage"),
+            watchdog_trips_total: counter(
+                "watchdog_trips_total",
+                "Times trading was halted for failing without a successful trade",
+            ),
+            trading_paused: gauge("trading_paused", "1 while the watchdog has halted trading"),
+            db_write_failures: counter("db_write_failures", "Failed trade writes to the database"),
+            jito_bundles_sent: counter("jito_bundles_sent", "Bundles accepted by the Jito block engine"),
+            jito_bundles_failed: reason_counter(
+                "jito_bundles_failed",
+                "Failed Jito bundle submissions per failure reason",
+            ),
+            sends_by_confirmation_wait,
+            blockhash_age_seconds: gauge(
+                "blockhash_age_seconds",
+                "Age of the cached blockhash when a transaction was built",
+            ),
+            opportunities_per_second: gauge(
+                "opportunities_per_second",
+                "Opportunities found per second over the rate window",
+            ),
+            transactions_sent_per_second: gauge(
+                "transactions_sent_per_second",
+                "Transactions sent per second over the rate window",
+            ),
+            transactions_confirmed_per_second: gauge(
+                "transactions_confirmed_per_second",
+                "Transactions confirmed per second over the rate window",
+            ),
+            pools_initialized_by_dex: dex_counter(
+                "dex_pools_initialized_total",
+                "Pools initialized per DEX",
+            ),
+            pool_failures_by_dex: dex_counter(
+                "dex_pool_initialization_failures_total",
+                "Pool initialization failures per DEX",
+            ),
+            rpc_latency_seconds: histogram("rpc_latency_seconds", "RPC request latency"),
+            ws_latency_seconds: histogram("ws_latency_seconds", "WebSocket message latency"),
+            profit_estimation_error,
+            registry,
+        }
+    }
 }
 
 impl BotMetrics {
     pub fn new() -> Self {
+        let rate_window = Duration::from_secs(METRICS_RATE_WINDOW_SECS);
         Self {
             rpc_requests_total: AtomicU64::new(0),
             rpc_failures_total: AtomicU64::new(0),
@@ -44,65 +339,318 @@ impl BotMetrics {
             rpc_cache_misses: AtomicU64::new(0),
             pools_initialized_total: AtomicU64::new(0),
             pool_initialization_failures: AtomicU64::new(0),
+            pools_removed: AtomicU64::new(0),
+            stale_account_rejections: AtomicU64::new(0),
+            compute_unit_price_floor_applied: AtomicU64::new(0),
+            capital_cap_blocked: AtomicU64::new(0),
+            wallet_failovers: AtomicU64::new(0),
+            missing_atas: AtomicU64::new(0),
+            recent_signatures: AtomicU64::new(0),
             transactions_sent: AtomicU64::new(0),
             transactions_confirmed: AtomicU64::new(0),
             transactions_failed: AtomicU64::new(0),
+            sends_already_processed: AtomicU64::new(0),
+            sends_account_in_use: AtomicU64::new(0),
+            near_expiry_refreshes: AtomicU64::new(0),
             opportunities_found: AtomicU64::new(0),
             opportunities_executed: AtomicU64::new(0),
+            opportunities_dropped: AtomicU64::new(0),
+            duplicate_opportunities_suppressed: AtomicU64::new(0),
             total_profit_lamports: AtomicU64::new(0),
+            total_fees_lamports: AtomicU64::new(0),
+            idle_backoff_ms: AtomicU64::new(0),
+            opportunity_feed_dropped: AtomicU64::new(0),
+            watchdog_trips: AtomicU64::new(0),
+            db_write_failures: AtomicU64::new(0),
+            jito_bundles_sent: AtomicU64::new(0),
+            jito_bundles_failed: AtomicU64::new(0),
+            opportunity_rate: RateWindow::new(rate_window),
+            tx_sent_rate: RateWindow::new(rate_window),
+            tx_confirmed_rate: RateWindow::new(rate_window),
+            prometheus: PrometheusMetrics::new(),
         }
     }
 
     // RPC metrics
     pub fn inc_rpc_request(&self) {
         self.rpc_requests_total.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.rpc_requests_total.inc();
     }
 
     pub fn inc_rpc_failure(&self) {
         self.rpc_failures_total.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.rpc_failures_total.inc();
     }
 
     pub fn inc_cache_hit(&self) {
         self.rpc_cache_hits.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.rpc_cache_hits.inc();
     }
 
     pub fn inc_cache_miss(&self) {
         self.rpc_cache_misses.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.rpc_cache_misses.inc();
     }
 
     // Pool metrics
     pub fn inc_pool_initialized(&self) {
         self.pools_initialized_total.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.pools_initialized_total.inc();
     }
 
     pub fn inc_pool_failure(&self) {
         self.pool_initialization_failures.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.pool_initialization_failures.inc();
+    }
+
+    pub fn inc_pool_removed(&self) {
+        self.pools_removed.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.pools_removed.inc();
+    }
+
+    pub fn inc_stale_account_rejection(&self) {
+        self.stale_account_rejections.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.stale_account_rejections.inc();
+    }
+
+    pub fn inc_compute_unit_price_floor_applied(&self) {
+        self.compute_unit_price_floor_applied.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.compute_unit_price_floor_applied.inc();
+    }
+
+    pub fn inc_capital_cap_blocked(&self) {
+        self.capital_cap_blocked.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.capital_cap_blocked.inc();
+    }
+
+    pub fn set_missing_atas(&self, count: u64) {
+        self.missing_atas.store(count, Ordering::Relaxed);
+        self.prometheus.missing_atas.set(count as f64);
+    }
+
+    pub fn missing_atas(&self) -> u64 {
+        self.missing_atas.load(Ordering::Relaxed)
+    }
+
+    pub fn set_recent_signatures(&self, count: usize) {
+        self.recent_signatures.store(count as u64, Ordering::Relaxed);
+        self.prometheus.recent_signatures.set(count as f64);
+    }
+
+    pub fn inc_wallet_failover(&self) {
+        self.wallet_failovers.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.wallet_failovers.inc();
     }
 
     // Transaction metrics
     pub fn inc_tx_sent(&self) {
         self.transactions_sent.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.transactions_sent.inc();
+        self.tx_sent_rate.record(1);
     }
 
     pub fn inc_tx_confirmed(&self) {
         self.transactions_confirmed.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.transactions_confirmed.inc();
+        self.tx_confirmed_rate.record(1);
     }
 
     pub fn inc_tx_failed(&self) {
         self.transactions_failed.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.transactions_failed.inc();
+    }
+
+    /// Count an RPC send under what `bot.confirmation_mode` made the send path wait for
+    pub fn inc_send_confirmation_wait(&self, wait: &str) {
+        self.prometheus
+            .sends_by_confirmation_wait
+            .with_label_values(&[wait])
+            .inc();
+    }
+
+    pub fn inc_send_already_processed(&self) {
+        self.sends_already_processed.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.sends_already_processed.inc();
+    }
+
+    pub fn inc_send_account_in_use(&self) {
+        self.sends_account_in_use.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.sends_account_in_use.inc();
+    }
+
+    pub fn inc_near_expiry_refresh(&self) {
+        self.near_expiry_refreshes.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.near_expiry_refreshes.inc();
     }
 
     // Arbitrage metrics
     pub fn inc_opportunity_found(&self) {
         self.opportunities_found.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.opportunities_found.inc();
+        self.opportunity_rate.record(1);
     }
 
     pub fn inc_opportunity_executed(&self) {
         self.opportunities_executed.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.opportunities_executed.inc();
+    }
+
+    pub fn inc_opportunity_dropped(&self) {
+        self.opportunities_dropped.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.opportunities_dropped.inc();
+    }
+
+    pub fn inc_duplicate_opportunity_suppressed(&self) {
+        self.duplicate_opportunities_suppressed.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.duplicate_opportunities_suppressed.inc();
     }
 
     pub fn add_profit(&self, lamports: u64) {
         self.total_profit_lamports.fetch_add(lamports, Ordering::Relaxed);
+        self.prometheus.total_profit_lamports.inc_by(lamports);
+    }
+
+    pub fn add_fees(&self, lamports: u64) {
+        self.total_fees_lamports.fetch_add(lamports, Ordering::Relaxed);
+        self.prometheus.total_fees_lamports.inc_by(lamports);
+    }
+
+    pub fn add_idle_backoff(&self, extra_sleep: Duration) {
+        let ms = extra_sleep.as_millis() as u64;
+        self.idle_backoff_ms.fetch_add(ms, Ordering::Relaxed);
+        self.prometheus.idle_backoff_ms_total.inc_by(ms);
+    }
+
+    pub fn add_opportunity_feed_dropped(&self, events: u64) {
+        self.opportunity_feed_dropped.fetch_add(events, Ordering::Relaxed);
+        self.prometheus.opportunity_feed_dropped.inc_by(events);
+    }
+
+    // Watchdog metrics
+    pub fn inc_watchdog_trip(&self) {
+        self.watchdog_trips.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.watchdog_trips_total.inc();
+    }
+
+    pub fn set_trading_paused(&self, paused: bool) {
+        self.prometheus
+            .trading_paused
+            .set(if paused { 1.0 } else { 0.0 });
+    }
+
+    // Persistence metrics
+    pub fn inc_db_write_failure(&self) {
+        self.db_write_failures.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.db_write_failures.inc();
+    }
+
+    // Jito metrics
+    pub fn inc_jito_bundle_sent(&self) {
+        self.jito_bundles_sent.fetch_add(1, Ordering::Relaxed);
+        self.prometheus.jito_bundles_sent.inc();
+    }
+
+    pub fn inc_jito_bundle_failed(&self, reason: &str) {
+        self.jito_bundles_failed.fetch_add(1, Ordering::Relaxed);
+        self.prometheus
+            .jito_bundles_failed
+            .with_label_values(&[reason])
+            .inc();
+    }
+
+    pub fn set_blockhash_age(&self, age: Duration) {
+        self.prometheus.blockhash_age_seconds.set(age.as_secs_f64());
+    }
+
+    // Per-DEX metrics
+    pub fn add_pools_initialized_for_dex(&self, dex: &str, count: u64) {
+        self.pools_initialized_total.fetch_add(count, Ordering::Relaxed);
+        self.prometheus.pools_initialized_total.inc_by(count);
+        self.prometheus
+            .pools_initialized_by_dex
+            .with_label_values(&[dex])
+            .inc_by(count);
+    }
+
+    pub fn inc_pool_failure_for_dex(&self, dex: &str) {
+        self.inc_pool_failure();
+        self.prometheus
+            .pool_failures_by_dex
+            .with_label_values(&[dex])
+            .inc();
+    }
+
+    // Latency metrics
+    pub fn observe_rpc_latency(&self, duration: Duration) {
+        self.prometheus
+            .rpc_latency_seconds
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn observe_ws_latency(&self, duration: Duration) {
+        self.prometheus
+            .ws_latency_seconds
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn observe_profit_estimation_error(&self, error_lamports: i64) {
+        self.prometheus
+            .profit_estimation_error
+            .observe(error_lamports as f64);
+    }
+
+    /// Render all registered metrics in the Prometheus text exposition format
+    /// Current per-second throughput over the rate window
+    pub fn rates(&self) -> MetricsRates {
+        MetricsRates {
+            opportunities_per_second: self.opportunity_rate.rate(),
+            transactions_sent_per_second: self.tx_sent_rate.rate(),
+            transactions_confirmed_per_second: self.tx_confirmed_rate.rate(),
+        }
+    }
+
+    pub fn gather_text(&self) -> String {
+        let mut buffer = Vec::new();
+        let encoder = TextEncoder::new();
+        if let Err(e) = encoder.encode(&self.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+
+    /// Refresh the derived gauges and collect every metric in the registry
+    pub fn gather(&self) -> Vec<MetricFamily> {
+        let snapshot = self.snapshot();
+        self.prometheus.cache_hit_rate.set(snapshot.cache_hit_rate());
+        self.prometheus.tx_success_rate.set(snapshot.tx_success_rate());
+
+        let rates = self.rates();
+        self.prometheus
+            .opportunities_per_second
+            .set(rates.opportunities_per_second);
+        self.prometheus
+            .transactions_sent_per_second
+            .set(rates.transactions_sent_per_second);
+        self.prometheus
+            .transactions_confirmed_per_second
+            .set(rates.transactions_confirmed_per_second);
+
+        self.prometheus.registry.gather()
+    }
+
+    /// Replace this bot's metric group on the pushgateway (blocking)
+    pub fn push(&self, config: &MetricsPushConfig) -> prometheus::Result<()> {
+        let mut grouping = HashMap::new();
+        if let Some(instance) = &config.instance {
+            grouping.insert("instance".to_string(), instance.clone());
+        }
+        prometheus::push_metrics(
+            config.job(),
+            grouping,
+            &config.pushgateway_url,
+            self.gather(),
+            None,
+        )
     }
 
     /// Get metrics snapshot
@@ -142,7 +690,7 @@ impl BotMetrics {
 }
 
 /// Immutable metrics snapshot
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct MetricsSnapshot {
     pub rpc_requests_total: u64,
     pub rpc_failures_total: u64,
@@ -181,6 +729,138 @@ impl MetricsSnapshot {
     }
 }
 
+/// Live per-second throughput
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MetricsRates {
+    pub opportunities_per_second: f64,
+    pub transactions_sent_per_second: f64,
+    pub transactions_confirmed_per_second: f64,
+}
+
+/// Timestamped event counts over a sliding window
+struct RateWindow {
+    window: Duration,
+    events: Mutex<VecDeque<(Instant, u64)>>,
+}
+
+impl RateWindow {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            events: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn record(&self, count: u64) {
+        self.record_at(Instant::now(), count);
+    }
+
+    fn record_at(&self, now: Instant, count: u64) {
+        let mut events = self.events.lock().unwrap();
+        Self::evict(&mut events, now, self.window);
+        events.push_back((now, count));
+    }
+
+    fn rate(&self) -> f64 {
+        self.rate_at(Instant::now())
+    }
+
+    /// Events per second within the window ending at `now`
+    fn rate_at(&self, now: Instant) -> f64 {
+        let mut events = self.events.lock().unwrap();
+        Self::evict(&mut events, now, self.window);
+        let total: u64 = events.iter().map(|(_, count)| count).sum();
+        total as f64 / self.window.as_secs_f64()
+    }
+
+    fn evict(events: &mut VecDeque<(Instant, u64)>, now: Instant, window: Duration) {
+        while let Some((at, _)) = events.front() {
+            if now.duration_since(*at) < window {
+                break;
+            }
+            events.pop_front();
+        }
+    }
+}
+
+/// Snapshot taken at a unix timestamp
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TimestampedSnapshot {
+    pub timestamp: i64,
+    pub snapshot: MetricsSnapshot,
+}
+
+/// Bounded ring buffer of periodic snapshots; the oldest is evicted when full
+#[derive(Debug)]
+pub struct MetricsHistory {
+    capacity: usize,
+    snapshots: VecDeque<TimestampedSnapshot>,
+}
+
+impl MetricsHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            snapshots: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn record(&mut self, timestamp: i64, snapshot: MetricsSnapshot) {
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(TimestampedSnapshot { timestamp, snapshot });
+    }
+
+    /// Snapshots, oldest first
+    pub fn snapshots(&self) -> Vec<TimestampedSnapshot> {
+        self.snapshots.iter().cloned().collect()
+    }
+}
+
+/// Record a snapshot into `METRICS_HISTORY` every interval and export it to disk
+pub async fn run_history_recorder() {
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(METRICS_HISTORY_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        let history = {
+            let mut history = METRICS_HISTORY.lock().unwrap();
+            history.record(chrono::Utc::now().timestamp(), METRICS.snapshot());
+            history.snapshots()
+        };
+
+        match serde_json::to_vec(&history) {
+            Ok(json) => {
+                if let Err(e) = tokio::fs::write(METRICS_HISTORY_FILE, json).await {
+                    tracing::warn!("Failed to export metrics history: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to serialize metrics history: {}", e),
+        }
+    }
+}
+
+/// Push `metrics` to the configured pushgateway every interval, for deployments that
+/// can't expose `/metrics` to a scraper
+pub async fn run_metrics_pusher(metrics: Arc<BotMetrics>, config: MetricsPushConfig) {
+    let mut interval = tokio::time::interval(config.interval());
+    loop {
+        interval.tick().await;
+        let metrics = metrics.clone();
+        let push_config = config.clone();
+        match tokio::task::spawn_blocking(move || metrics.push(&push_config)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::warn!(
+                "Failed to push metrics to {}: {}",
+                config.pushgateway_url,
+                e
+            ),
+            Err(e) => tracing::warn!("Metrics push task failed: {}", e),
+        }
+    }
+}
+
 /// Performance timer helper
 pub struct PerfTimer {
     start: Instant,
@@ -214,6 +894,81 @@ impl Drop for PerfTimer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    /// Request line and body of one HTTP request
+    fn read_request(stream: &mut TcpStream) -> (String, Vec<u8>) {
+        let mut request = Vec::new();
+        let mut chunk = [0u8; 4096];
+        let header_end = loop {
+            let n = stream.read(&mut chunk).unwrap();
+            request.extend_from_slice(&chunk[..n]);
+            if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+        };
+        let headers = String::from_utf8_lossy(&request[..header_end]).to_string();
+        let content_length = headers
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                if name.eq_ignore_ascii_case("content-length") {
+                    value.trim().parse().ok()
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(0);
+        while request.len() < header_end + content_length {
+            let n = stream.read(&mut chunk).unwrap();
+            request.extend_from_slice(&chunk[..n]);
+        }
+        let request_line = headers.lines().next().unwrap_or_default().to_string();
+        (request_line, request[header_end..].to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_metrics_pushed_at_configured_interval() {
+        // Mock pushgateway accepting every push
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (pushes, mut received) = tokio::sync::mpsc::unbounded_channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let request = read_request(&mut stream);
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .unwrap();
+                if pushes.send((Instant::now(), request)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let metrics = Arc::new(BotMetrics::new());
+        metrics.inc_opportunity_found();
+        let pusher = tokio::spawn(run_metrics_pusher(
+            metrics,
+            MetricsPushConfig {
+                pushgateway_url: url,
+                interval_secs: Some(1),
+                job: Some("arb".to_string()),
+                instance: Some("bot-1".to_string()),
+            },
+        ));
+
+        let timeout = Duration::from_secs(5);
+        let (first_at, (request_line, body)) =
+            tokio::time::timeout(timeout, received.recv()).await.unwrap().unwrap();
+        assert!(request_line.starts_with("PUT /metrics/job/arb/instance/bot-1 "));
+        assert!(body.windows(19).any(|w| w == b"opportunities_found"));
+
+        let (second_at, _) = tokio::time::timeout(timeout, received.recv()).await.unwrap().unwrap();
+        assert!(second_at.duration_since(first_at) >= Duration::from_millis(900));
+        pusher.abort();
+    }
 
     #[test]
     fn test_metrics_increment() {
@@ -240,6 +995,56 @@ mod tests {
         assert_eq!(snapshot.cache_hit_rate(), 75.0);
     }
 
+    #[test]
+    fn test_gather_text_exports_all_counters() {
+        let metrics = BotMetrics::new();
+        metrics.inc_rpc_request();
+        metrics.add_pools_initialized_for_dex("Raydium CPMM", 2);
+        metrics.observe_rpc_latency(Duration::from_millis(20));
+
+        let text = metrics.gather_text();
+        assert!(text.contains("# TYPE rpc_requests_total counter"));
+        assert!(text.contains("rpc_requests_total 1"));
+        assert!(text.contains("pools_initialized_total 2"));
+        assert!(text.contains("dex_pools_initialized_total{dex=\"Raydium CPMM\"} 2"));
+        assert!(text.contains("rpc_latency_seconds_count 1"));
+    }
+
+    #[test]
+    fn test_rate_window_per_second() {
+        let window = RateWindow::new(Duration::from_secs(10));
+        let start = Instant::now();
+
+        // 50 events over 5 seconds, all inside the 10s window
+        for i in 0..50 {
+            window.record_at(start + Duration::from_millis(i * 100), 1);
+        }
+        assert_eq!(window.rate_at(start + Duration::from_secs(5)), 5.0);
+
+        // Events from the first two seconds have left the window, 29 remain
+        assert_eq!(window.rate_at(start + Duration::from_secs(12)), 2.9);
+        assert_eq!(window.rate_at(start + Duration::from_secs(20)), 0.0);
+    }
+
+    #[test]
+    fn test_history_evicts_oldest() {
+        let metrics = BotMetrics::new();
+        let mut history = MetricsHistory::new(3);
+
+        for timestamp in 0..5 {
+            metrics.inc_tx_sent();
+            history.record(timestamp, metrics.snapshot());
+        }
+
+        let snapshots = history.snapshots();
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(
+            snapshots.iter().map(|s| s.timestamp).collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+        assert_eq!(snapshots[0].snapshot.transactions_sent, 3);
+    }
+
     #[test]
     fn test_tx_success_rate() {
         let metrics = BotMetrics::new();
@@ -0,0 +1,134 @@
+/// Opportunity Feed
+///
+/// Streams every opportunity the strategy loops detect, sent or not, as one JSON line
+/// per event to each TCP client connected to `opportunity_feed.bind_address`, for
+/// analysis outside the bot. Publishing never waits on a client: a consumer that
+/// falls more than the buffer behind loses the oldest events, counted in
+/// `opportunity_feed_dropped`.
+
+use crate::error::BotResult;
+use crate::metrics::METRICS;
+use crate::opportunity::ArbitrageOpportunity;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+
+/// Fan-out of detected opportunities to connected clients
+pub struct OpportunityFeed {
+    sender: broadcast::Sender<Arc<str>>,
+    local_addr: SocketAddr,
+}
+
+impl OpportunityFeed {
+    /// Listen on `bind_address`, keeping up to `buffer_size` events per client
+    pub async fn bind(bind_address: &str, buffer_size: usize) -> BotResult<Arc<Self>> {
+        let listener = TcpListener::bind(bind_address).await?;
+        let local_addr = listener.local_addr()?;
+        let (sender, _) = broadcast::channel(buffer_size.max(1));
+        tokio::spawn(accept_clients(listener, sender.clone()));
+        info!("Opportunity feed listening on {}", local_addr);
+        Ok(Arc::new(Self { sender, local_addr }))
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Queue `opportunity` for every client; a no-op while none is connected
+    pub fn publish(&self, opportunity: &ArbitrageOpportunity) {
+        if self.client_count() == 0 {
+            return;
+        }
+        match serde_json::to_string(opportunity) {
+            // Only fails if every client disconnected since the check
+            Ok(line) => {
+                let _ = self.sender.send(line.into());
+            }
+            Err(e) => warn!("Failed to serialize opportunity for the feed: {}", e),
+        }
+    }
+}
+
+async fn accept_clients(listener: TcpListener, sender: broadcast::Sender<Arc<str>>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                info!("Opportunity feed client connected: {}", peer);
+                tokio::spawn(stream_to_client(stream, sender.subscribe()));
+            }
+            Err(e) => warn!("Opportunity feed failed to accept a client: {}", e),
+        }
+    }
+}
+
+async fn stream_to_client(mut stream: TcpStream, mut events: broadcast::Receiver<Arc<str>>) {
+    loop {
+        let line = match events.recv().await {
+            Ok(line) => line,
+            Err(RecvError::Lagged(dropped)) => {
+                METRICS.add_opportunity_feed_dropped(dropped);
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+        let mut event = line.as_bytes().to_vec();
+        event.push(b'\n');
+        if stream.write_all(&event).await.is_err() {
+            // Disconnected; dropping the receiver unsubscribes it
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    #[tokio::test]
+    async fn test_client_receives_published_opportunities() {
+        let feed = OpportunityFeed::bind("127.0.0.1:0", 16).await.unwrap();
+        // Nobody is listening yet, so this one goes nowhere
+        feed.publish(&ArbitrageOpportunity::test_quote(1, 1));
+
+        let stream = TcpStream::connect(feed.local_addr()).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while feed.client_count() == 0 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let sent = [
+            ArbitrageOpportunity::test_quote(1_000_000, 5_000),
+            ArbitrageOpportunity::test_quote(2_000_000, 8_000),
+        ];
+        for opportunity in &sent {
+            feed.publish(opportunity);
+        }
+
+        let mut lines = BufReader::new(stream).lines();
+        for opportunity in &sent {
+            let line = tokio::time::timeout(Duration::from_secs(5), lines.next_line())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            let event: serde_json::Value = serde_json::from_str(&line).unwrap();
+            assert_eq!(event["mint"], opportunity.mint.to_string());
+            assert_eq!(event["pools"][1], opportunity.pools[1].to_string());
+            assert_eq!(event["input_amount"], opportunity.input_amount);
+            assert_eq!(event["expected_profit"], opportunity.expected_profit);
+            assert_eq!(event["route"][0], "Raydium CPMM");
+        }
+    }
+}
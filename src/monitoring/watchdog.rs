@@ -0,0 +1,183 @@
+/// Trade Watchdog (dead-man's switch)
+///
+/// Halts sending when opportunities keep being found but no trade has landed
+/// for too long, so a broken config or pool cannot burn fees silently.
+
+use crate::alert;
+use crate::metrics::METRICS;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{error, info};
+
+/// Trading state reported by the watchdog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogState {
+    /// Trading is allowed
+    Active,
+    /// No opportunities since the last successful trade; idling is normal
+    Idle,
+    /// Opportunities were found but all failed for longer than the limit
+    Halted,
+}
+
+struct WatchdogInner {
+    last_success: Instant,
+    opportunities_since_success: u64,
+    halted_at: Option<Instant>,
+}
+
+/// Tracks time since the last landed trade and halts trading when all attempts fail
+pub struct TradeWatchdog {
+    max_idle: Duration,
+    resume_cooldown: Option<Duration>,
+    inner: Mutex<WatchdogInner>,
+}
+
+impl TradeWatchdog {
+    /// `resume_cooldown` of `None` keeps trading halted until `resume` is called
+    pub fn new(max_idle: Duration, resume_cooldown: Option<Duration>) -> Self {
+        Self::new_at(max_idle, resume_cooldown, Instant::now())
+    }
+
+    fn new_at(max_idle: Duration, resume_cooldown: Option<Duration>, now: Instant) -> Self {
+        Self {
+            max_idle,
+            resume_cooldown,
+            inner: Mutex::new(WatchdogInner {
+                last_success: now,
+                opportunities_since_success: 0,
+                halted_at: None,
+            }),
+        }
+    }
+
+    /// Record that an opportunity was found and a trade attempted
+    pub fn record_opportunity(&self) {
+        self.inner.lock().unwrap().opportunities_since_success += 1;
+    }
+
+    /// Record a confirmed/landed trade
+    pub fn record_success(&self) {
+        self.record_success_at(Instant::now());
+    }
+
+    fn record_success_at(&self, now: Instant) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.last_success = now;
+        inner.opportunities_since_success = 0;
+    }
+
+    /// Manually resume trading after a halt
+    pub fn resume(&self) {
+        self.resume_at(Instant::now());
+    }
+
+    fn resume_at(&self, now: Instant) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.halted_at.take().is_some() {
+            info!("Trade watchdog resumed, trading re-enabled");
+            METRICS.set_trading_paused(false);
+        }
+        inner.last_success = now;
+        inner.opportunities_since_success = 0;
+    }
+
+    /// Current state, halting or auto-resuming as needed
+    pub fn check(&self) -> WatchdogState {
+        self.check_at(Instant::now())
+    }
+
+    fn check_at(&self, now: Instant) -> WatchdogState {
+        let mut inner = self.inner.lock().unwrap();
+
+        if let Some(halted_at) = inner.halted_at {
+            match self.resume_cooldown {
+                Some(cooldown) if now.duration_since(halted_at) >= cooldown => {
+                    drop(inner);
+                    self.resume_at(now);
+                    return WatchdogState::Active;
+                }
+                _ => return WatchdogState::Halted,
+            }
+        }
+
+        if inner.opportunities_since_success == 0 {
+            return WatchdogState::Idle;
+        }
+
+        let idle_for = now.duration_since(inner.last_success);
+        if idle_for > self.max_idle {
+            let message = format!(
+                "{} trade attempts without a landed trade in {}s, halting trading",
+                inner.opportunities_since_success,
+                idle_for.as_secs()
+            );
+            error!("CRITICAL: {}", message);
+            alert::alert_critical(message);
+            inner.halted_at = Some(now);
+            METRICS.inc_watchdog_trip();
+            METRICS.set_trading_paused(true);
+            return WatchdogState::Halted;
+        }
+
+        WatchdogState::Active
+    }
+
+    /// Whether the bot may send transactions right now
+    pub fn trading_allowed(&self) -> bool {
+        self.check() != WatchdogState::Halted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_no_opportunities_never_halts() {
+        let start = Instant::now();
+        let watchdog = TradeWatchdog::new_at(5 * MINUTE, None, start);
+
+        assert_eq!(watchdog.check_at(start + 60 * MINUTE), WatchdogState::Idle);
+    }
+
+    #[test]
+    fn test_failing_opportunities_halt_until_manual_resume() {
+        let start = Instant::now();
+        let watchdog = TradeWatchdog::new_at(5 * MINUTE, None, start);
+
+        watchdog.record_opportunity();
+        assert_eq!(watchdog.check_at(start + 4 * MINUTE), WatchdogState::Active);
+        assert_eq!(watchdog.check_at(start + 6 * MINUTE), WatchdogState::Halted);
+        assert_eq!(watchdog.check_at(start + 60 * MINUTE), WatchdogState::Halted);
+
+        watchdog.resume_at(start + 61 * MINUTE);
+        assert_eq!(watchdog.check_at(start + 62 * MINUTE), WatchdogState::Idle);
+    }
+
+    #[test]
+    fn test_success_resets_timer() {
+        let start = Instant::now();
+        let watchdog = TradeWatchdog::new_at(5 * MINUTE, None, start);
+
+        watchdog.record_opportunity();
+        watchdog.record_success_at(start + 4 * MINUTE);
+        watchdog.record_opportunity();
+
+        assert_eq!(watchdog.check_at(start + 8 * MINUTE), WatchdogState::Active);
+        assert_eq!(watchdog.check_at(start + 10 * MINUTE), WatchdogState::Halted);
+    }
+
+    #[test]
+    fn test_auto_resume_after_cooldown() {
+        let start = Instant::now();
+        let watchdog = TradeWatchdog::new_at(5 * MINUTE, Some(10 * MINUTE), start);
+
+        watchdog.record_opportunity();
+        assert_eq!(watchdog.check_at(start + 6 * MINUTE), WatchdogState::Halted);
+        assert_eq!(watchdog.check_at(start + 15 * MINUTE), WatchdogState::Halted);
+        assert_eq!(watchdog.check_at(start + 16 * MINUTE), WatchdogState::Active);
+    }
+}
@@ -1,3 +1,3 @@
 pub mod object_pool;
 
-pub use object_pool::ObjectPool;
+pub use object_pool::{ObjectPool, PooledObject};
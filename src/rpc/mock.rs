@@ -3,8 +3,10 @@
 /// Provides a mock implementation of RpcClient for unit and integration testing
 /// without requiring actual Solana RPC endpoints.
 
-use solana_client::rpc_client::RpcClient;
+use super::pool::AccountFetcher;
+use crate::error::{BotError, BotResult};
 use solana_sdk::account::Account;
+use solana_sdk::clock::Slot;
 use solana_sdk::hash::Hash;
 use solana_sdk::pubkey::Pubkey;
 use std::collections::HashMap;
@@ -14,6 +16,7 @@ use std::sync::{Arc, RwLock};
 pub struct MockRpcClient {
     accounts: Arc<RwLock<HashMap<Pubkey, Account>>>,
     latest_blockhash: Arc<RwLock<Hash>>,
+    slot: Arc<RwLock<Slot>>,
 }
 
 impl MockRpcClient {
@@ -22,6 +25,7 @@ impl MockRpcClient {
         Self {
             accounts: Arc::new(RwLock::new(HashMap::new())),
             latest_blockhash: Arc::new(RwLock::new(Hash::default())),
+            slot: Arc::new(RwLock::new(0)),
         }
     }
 
@@ -31,12 +35,23 @@ impl MockRpcClient {
         accounts.insert(pubkey, account);
     }
 
+    /// Remove a mock account, as if it was closed
+    pub fn remove_account(&self, pubkey: &Pubkey) {
+        let mut accounts = self.accounts.write().unwrap();
+        accounts.remove(pubkey);
+    }
+
     /// Set the latest blockhash
     pub fn set_latest_blockhash(&self, hash: Hash) {
         let mut latest = self.latest_blockhash.write().unwrap();
         *latest = hash;
     }
 
+    /// Set the slot reported as the context of account reads
+    pub fn set_slot(&self, slot: Slot) {
+        *self.slot.write().unwrap() = slot;
+    }
+
     /// Get account (test helper)
     pub fn get_account(&self, pubkey: &Pubkey) -> Option<Account> {
         let accounts = self.accounts.read().unwrap();
@@ -55,6 +70,30 @@ impl MockRpcClient {
     }
 }
 
+impl AccountFetcher for MockRpcClient {
+    fn fetch_account(&self, pubkey: &Pubkey) -> BotResult<Account> {
+        self.get_account(pubkey).ok_or_else(|| BotError::AccountFetchError {
+            address: *pubkey,
+            reason: "account not found in mock".to_string(),
+        })
+    }
+
+    fn fetch_multiple_accounts(&self, pubkeys: &[Pubkey]) -> BotResult<Vec<Option<Account>>> {
+        Ok(pubkeys.iter().map(|pubkey| self.get_account(pubkey)).collect())
+    }
+
+    fn fetch_multiple_accounts_with_slot(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> BotResult<(Slot, Vec<Option<Account>>)> {
+        Ok((*self.slot.read().unwrap(), self.fetch_multiple_accounts(pubkeys)?))
+    }
+
+    fn endpoint(&self) -> String {
+        "mock".to_string()
+    }
+}
+
 impl Default for MockRpcClient {
     fn default() -> Self {
         Self::new()
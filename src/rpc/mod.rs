@@ -3,7 +3,9 @@ pub mod pool;
 #[cfg(test)]
 pub mod mock;
 
-pub use pool::RpcPool;
+pub use pool::{
+    run_pinned_refresh, AccountFetcher, CircuitState, EndpointHealth, RpcPool, WarmupReport,
+};
 
 #[cfg(test)]
 pub use mock::MockRpcClient;
@@ -3,16 +3,80 @@
 /// Provides connection pooling, request caching, and circuit breaker pattern
 /// for resilient RPC communication.
 
-use crate::constants::{MAX_RPC_RETRIES, RETRY_INITIAL_BACKOFF_MS, RETRY_MAX_BACKOFF_MS};
+use crate::constants::{
+    MAX_MULTIPLE_ACCOUNTS_BATCH, MAX_RPC_RETRIES, RETRY_INITIAL_BACKOFF_MS, RETRY_MAX_BACKOFF_MS,
+};
 use crate::error::{BotError, BotResult};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::account::Account;
+use solana_sdk::clock::Slot;
 use solana_sdk::pubkey::Pubkey;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
+
+/// Source of account data for the pool (an RPC endpoint, or a mock in tests)
+pub trait AccountFetcher: Send + Sync {
+    fn fetch_account(&self, pubkey: &Pubkey) -> BotResult<Account>;
+
+    /// Fetch a batch of accounts; missing accounts are `None`
+    fn fetch_multiple_accounts(&self, pubkeys: &[Pubkey]) -> BotResult<Vec<Option<Account>>>;
+
+    /// `fetch_multiple_accounts` plus the slot the RPC node read them at
+    fn fetch_multiple_accounts_with_slot(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> BotResult<(Slot, Vec<Option<Account>>)>;
+
+    /// Endpoint identifier reported in health output
+    fn endpoint(&self) -> String;
+}
+
+impl AccountFetcher for RpcClient {
+    fn fetch_account(&self, pubkey: &Pubkey) -> BotResult<Account> {
+        self.get_account(pubkey).map_err(|e| BotError::AccountFetchError {
+            address: *pubkey,
+            reason: format!("RPC error: {}", e),
+        })
+    }
+
+    fn fetch_multiple_accounts(&self, pubkeys: &[Pubkey]) -> BotResult<Vec<Option<Account>>> {
+        self.get_multiple_accounts(pubkeys).map_err(|e| BotError::RpcError {
+            endpoint: self.url(),
+            message: format!("getMultipleAccounts failed: {}", e),
+            retryable: true,
+        })
+    }
+
+    fn fetch_multiple_accounts_with_slot(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> BotResult<(Slot, Vec<Option<Account>>)> {
+        let response = self
+            .get_multiple_accounts_with_commitment(pubkeys, self.commitment())
+            .map_err(|e| BotError::RpcError {
+                endpoint: self.url(),
+                message: format!("getMultipleAccounts failed: {}", e),
+                retryable: true,
+            })?;
+        Ok((response.context.slot, response.value))
+    }
+
+    fn endpoint(&self) -> String {
+        self.url()
+    }
+}
+
+/// Result of priming the cache before trading
+#[derive(Debug)]
+pub struct WarmupReport {
+    pub cached: usize,
+    pub missing: Vec<Pubkey>,
+    pub elapsed: Duration,
+}
 
 /// Cached RPC response with TTL
 #[derive(Clone)]
@@ -21,18 +85,43 @@ struct CachedResponse {
     expires_at: Instant,
 }
 
-/// RPC connection pool with caching and circuit breaker
+/// RPC connection pool with caching and per-endpoint circuit breakers
 pub struct RpcPool {
-    clients: Vec<Arc<RpcClient>>,
+    endpoints: Vec<Endpoint>,
     cache: Arc<RwLock<HashMap<Pubkey, CachedResponse>>>,
+    /// Shared accounts every transaction needs; they skip the TTL and are only re-read
+    /// by `refresh_pinned`
+    pinned: Arc<RwLock<HashMap<Pubkey, Account>>>,
+    /// Accounts read by `warm_up` with the slot they were read at; the first
+    /// `get_fresh_accounts` of each takes it instead of reading it again
+    primed: Arc<RwLock<HashMap<Pubkey, (Slot, CachedResponse)>>>,
     cache_ttl: Duration,
     current_client_index: Arc<RwLock<usize>>,
-    circuit_breaker: Arc<RwLock<CircuitBreaker>>,
+    request_timeout: Option<Duration>,
+}
+
+/// One RPC endpoint with its own circuit breaker and counters
+struct Endpoint {
+    client: Arc<dyn AccountFetcher>,
+    circuit_breaker: RwLock<CircuitBreaker>,
+    success_count: AtomicU64,
+    failure_count: AtomicU64,
+}
+
+/// Health of one endpoint, as reported on /status
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EndpointHealth {
+    pub url: String,
+    pub state: CircuitState,
+    pub success_count: u64,
+    pub failure_count: u64,
+    pub last_failure_age_secs: Option<u64>,
 }
 
 /// Circuit breaker states
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum CircuitState {
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CircuitState {
     Closed,  // Normal operation
     Open,    // Failing, reject requests
     HalfOpen, // Testing if service recovered
@@ -95,22 +184,65 @@ impl CircuitBreaker {
 }
 
 impl RpcPool {
-    /// Create a new RPC pool
-    pub fn new(urls: Vec<String>, cache_ttl_secs: u64) -> Self {
+    /// Create a new RPC pool whose clients give up on a request after `request_timeout`
+    pub fn new(urls: Vec<String>, cache_ttl_secs: u64, request_timeout: Duration) -> Self {
         let clients = urls
             .into_iter()
-            .map(|url| Arc::new(RpcClient::new(url)))
+            .map(|url| {
+                Arc::new(RpcClient::new_with_timeout(url, request_timeout)) as Arc<dyn AccountFetcher>
+            })
+            .collect();
+
+        let mut pool = Self::with_fetchers(clients, cache_ttl_secs);
+        pool.request_timeout = Some(request_timeout);
+        pool
+    }
+
+    /// Create a pool over arbitrary account fetchers
+    pub fn with_fetchers(clients: Vec<Arc<dyn AccountFetcher>>, cache_ttl_secs: u64) -> Self {
+        let endpoints = clients
+            .into_iter()
+            .map(|client| Endpoint {
+                client,
+                circuit_breaker: RwLock::new(CircuitBreaker::new(5, 30)),
+                success_count: AtomicU64::new(0),
+                failure_count: AtomicU64::new(0),
+            })
             .collect();
 
         Self {
-            clients,
+            endpoints,
             cache: Arc::new(RwLock::new(HashMap::new())),
+            pinned: Arc::new(RwLock::new(HashMap::new())),
+            primed: Arc::new(RwLock::new(HashMap::new())),
             cache_ttl: Duration::from_secs(cache_ttl_secs),
             current_client_index: Arc::new(RwLock::new(0)),
-            circuit_breaker: Arc::new(RwLock::new(CircuitBreaker::new(5, 30))),
+            request_timeout: None,
         }
     }
 
+    /// Per-request timeout of the pool's RPC clients (`None` for custom fetchers)
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout
+    }
+
+    /// Per-endpoint request counts and circuit state
+    pub fn endpoint_health(&self) -> Vec<EndpointHealth> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| {
+                let cb = endpoint.circuit_breaker.read().unwrap();
+                EndpointHealth {
+                    url: endpoint.client.endpoint(),
+                    state: cb.state,
+                    success_count: endpoint.success_count.load(Ordering::Relaxed),
+                    failure_count: endpoint.failure_count.load(Ordering::Relaxed),
+                    last_failure_age_secs: cb.last_failure_time.map(|t| t.elapsed().as_secs()),
+                }
+            })
+            .collect()
+    }
+
     /// Get account with caching and retry logic
     pub async fn get_account_with_retry(&self, pubkey: &Pubkey) -> BotResult<Account> {
         // Check cache first
@@ -119,31 +251,225 @@ impl RpcPool {
             return Ok(cached);
         }
 
-        // Check circuit breaker
-        {
-            let mut cb = self.circuit_breaker.write().unwrap();
-            if !cb.can_attempt() {
-                return Err(BotError::RpcError {
-                    endpoint: "pool".to_string(),
-                    message: "Circuit breaker is OPEN".to_string(),
-                    retryable: true,
-                });
+        let account = self
+            .with_retry(|client| client.fetch_account(pubkey))
+            .await?;
+        self.add_to_cache(pubkey, &account);
+        Ok(account)
+    }
+
+    /// Get many accounts with caching and retry logic, batching uncached keys.
+    /// Missing accounts are returned as `None` and are not cached.
+    pub async fn get_multiple_accounts_with_retry(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> BotResult<Vec<Option<Account>>> {
+        let mut results: Vec<Option<Account>> =
+            pubkeys.iter().map(|pubkey| self.get_from_cache(pubkey)).collect();
+
+        let uncached: Vec<(usize, Pubkey)> = pubkeys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| results[*i].is_none())
+            .map(|(i, pubkey)| (i, *pubkey))
+            .collect();
+
+        for batch in uncached.chunks(MAX_MULTIPLE_ACCOUNTS_BATCH) {
+            let keys: Vec<Pubkey> = batch.iter().map(|(_, pubkey)| *pubkey).collect();
+            let accounts = self
+                .with_retry(|client| client.fetch_multiple_accounts(&keys))
+                .await?;
+
+            for ((index, pubkey), account) in batch.iter().zip(accounts) {
+                if let Some(account) = &account {
+                    self.add_to_cache(pubkey, account);
+                }
+                results[*index] = account;
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Read `pubkeys` with the slot the endpoint read them at, bypassing the cache (a
+    /// single batch, so at most `MAX_MULTIPLE_ACCOUNTS_BATCH` keys)
+    pub async fn get_multiple_accounts_with_slot(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> BotResult<(Slot, Vec<Option<Account>>)> {
+        self.with_retry(|client| client.fetch_multiple_accounts_with_slot(pubkeys))
+            .await
+    }
+
+    /// Read `pubkeys` for the strategy loop: pinned accounts come from the pins, accounts
+    /// primed by `warm_up` within the TTL from that read (once), the rest from the
+    /// endpoints in batches, bypassing the TTL cache. Returns the oldest slot the
+    /// accounts were read at, None when every account was pinned.
+    pub async fn get_fresh_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> BotResult<(Option<Slot>, Vec<Option<Account>>)> {
+        let mut oldest_slot: Option<Slot> = None;
+        let mut results: Vec<Option<Account>> = {
+            let pinned = self.pinned.read().unwrap();
+            let mut primed = self.primed.write().unwrap();
+            let now = Instant::now();
+            pubkeys
+                .iter()
+                .map(|pubkey| {
+                    if let Some(account) = pinned.get(pubkey) {
+                        return Some(account.clone());
+                    }
+                    let (slot, cached) = primed.remove(pubkey).filter(|(_, cached)| cached.expires_at > now)?;
+                    oldest_slot = Some(oldest_slot.map_or(slot, |oldest| oldest.min(slot)));
+                    Some(cached.data)
+                })
+                .collect()
+        };
+        let unpinned: Vec<(usize, Pubkey)> = pubkeys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| results[*i].is_none())
+            .map(|(i, pubkey)| (i, *pubkey))
+            .collect();
+
+        for batch in unpinned.chunks(MAX_MULTIPLE_ACCOUNTS_BATCH) {
+            let keys: Vec<Pubkey> = batch.iter().map(|(_, pubkey)| *pubkey).collect();
+            let (slot, accounts) = self.get_multiple_accounts_with_slot(&keys).await?;
+            oldest_slot = Some(oldest_slot.map_or(slot, |oldest| oldest.min(slot)));
+            for ((index, _), account) in batch.iter().zip(accounts) {
+                results[*index] = account;
+            }
+        }
+        Ok((oldest_slot, results))
+    }
+
+    /// Prime the cache with `pubkeys`, reporting how many were cached and which are
+    /// unreachable. The strategy loop's first `get_fresh_accounts` is served from these
+    /// reads rather than repeating them.
+    pub async fn warm_up(&self, pubkeys: &[Pubkey]) -> BotResult<WarmupReport> {
+        let start = Instant::now();
+        let mut missing = Vec::new();
+        let mut cached = 0;
+        for batch in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS_BATCH) {
+            let (slot, accounts) = self.get_multiple_accounts_with_slot(batch).await?;
+            let mut primed = self.primed.write().unwrap();
+            for (pubkey, account) in batch.iter().zip(accounts) {
+                match account {
+                    Some(account) => {
+                        self.add_to_cache(pubkey, &account);
+                        let expires_at = Instant::now() + self.cache_ttl;
+                        primed.insert(*pubkey, (slot, CachedResponse { data: account, expires_at }));
+                        cached += 1;
+                    }
+                    None => missing.push(*pubkey),
+                }
+            }
+        }
+
+        let report = WarmupReport {
+            cached,
+            missing,
+            elapsed: start.elapsed(),
+        };
+        info!(
+            "Cache warm-up cached {} accounts in {:?} ({} missing)",
+            report.cached,
+            report.elapsed,
+            report.missing.len()
+        );
+        Ok(report)
+    }
+
+    /// Fetch `pubkeys` and keep them until the next `refresh_pinned`, whatever the cache
+    /// TTL. Returns how many were pinned; accounts that don't exist are skipped.
+    pub async fn pin_accounts(&self, pubkeys: &[Pubkey]) -> BotResult<usize> {
+        let unpinned: Vec<Pubkey> = {
+            let pinned = self.pinned.read().unwrap();
+            pubkeys.iter().filter(|pubkey| !pinned.contains_key(pubkey)).copied().collect()
+        };
+        let fetched = self.fetch_uncached(&unpinned).await?;
+        let mut pinned = self.pinned.write().unwrap();
+        let mut count = 0;
+        for (pubkey, account) in unpinned.iter().zip(fetched) {
+            match account {
+                Some(account) => {
+                    pinned.insert(*pubkey, account);
+                    count += 1;
+                }
+                None => warn!("Account {} to pin not found", pubkey),
+            }
+        }
+        debug!("Pinned {} shared accounts ({} in total)", count, pinned.len());
+        Ok(count)
+    }
+
+    /// Re-read every pinned account, dropping any that no longer exist
+    pub async fn refresh_pinned(&self) -> BotResult<()> {
+        let pubkeys: Vec<Pubkey> = self.pinned.read().unwrap().keys().copied().collect();
+        let fetched = self.fetch_uncached(&pubkeys).await?;
+        let mut pinned = self.pinned.write().unwrap();
+        for (pubkey, account) in pubkeys.iter().zip(fetched) {
+            match account {
+                Some(account) => {
+                    pinned.insert(*pubkey, account);
+                }
+                None => {
+                    warn!("Pinned account {} no longer exists, unpinning it", pubkey);
+                    pinned.remove(pubkey);
+                }
             }
         }
+        Ok(())
+    }
+
+    /// Number of pinned accounts
+    pub fn pinned_count(&self) -> usize {
+        self.pinned.read().unwrap().len()
+    }
 
+    /// `pubkeys` read from the endpoints in batches, bypassing the cache
+    async fn fetch_uncached(&self, pubkeys: &[Pubkey]) -> BotResult<Vec<Option<Account>>> {
+        let mut accounts = Vec::with_capacity(pubkeys.len());
+        for batch in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS_BATCH) {
+            accounts.extend(
+                self.with_retry(|client| client.fetch_multiple_accounts(batch))
+                    .await?,
+            );
+        }
+        Ok(accounts)
+    }
+
+    /// Run `request` against the pool's endpoints with circuit breakers and exponential backoff
+    async fn with_retry<T>(
+        &self,
+        request: impl Fn(&dyn AccountFetcher) -> BotResult<T>,
+    ) -> BotResult<T> {
         // Attempt with exponential backoff
         let mut backoff_ms = RETRY_INITIAL_BACKOFF_MS;
         let mut last_error = None;
 
         for attempt in 0..MAX_RPC_RETRIES {
-            match self.attempt_get_account(pubkey).await {
-                Ok(account) => {
-                    // Success - record in circuit breaker and cache
-                    self.circuit_breaker.write().unwrap().record_success();
-                    self.add_to_cache(pubkey, &account);
-                    return Ok(account);
+            // Skip endpoints whose circuit breaker is open
+            let endpoint = match self.get_next_endpoint() {
+                Some(endpoint) => endpoint,
+                None => {
+                    return Err(BotError::AllEndpointsDown {
+                        endpoints: self.endpoints.len(),
+                    });
+                }
+            };
+
+            match request(endpoint.client.as_ref()) {
+                Ok(value) => {
+                    // Success - record in circuit breaker
+                    endpoint.success_count.fetch_add(1, Ordering::Relaxed);
+                    endpoint.circuit_breaker.write().unwrap().record_success();
+                    return Ok(value);
                 }
                 Err(e) => {
+                    endpoint.failure_count.fetch_add(1, Ordering::Relaxed);
+                    endpoint.circuit_breaker.write().unwrap().record_failure();
                     last_error = Some(e);
                     
                     if attempt < MAX_RPC_RETRIES - 1 {
@@ -156,8 +482,6 @@ impl RpcPool {
         }
 
         // All attempts failed
-        self.circuit_breaker.write().unwrap().record_failure();
-
         Err(last_error.unwrap_or_else(|| {
             BotError::RpcError {
                 endpoint: "pool".to_string(),
@@ -167,20 +491,11 @@ impl RpcPool {
         }))
     }
 
-    /// Attempt to get account from current client
-    async fn attempt_get_account(&self, pubkey: &Pubkey) -> BotResult<Account> {
-        let client = self.get_next_client();
-        
-        client.get_account(pubkey).map_err(|e| {
-            BotError::AccountFetchError {
-                address: *pubkey,
-                reason: format!("RPC error: {}", e),
-            }
-        })
-    }
-
-    /// Get from cache if not expired
+    /// Get from the pinned accounts, or from cache if not expired
     fn get_from_cache(&self, pubkey: &Pubkey) -> Option<Account> {
+        if let Some(account) = self.pinned.read().unwrap().get(pubkey) {
+            return Some(account.clone());
+        }
         let cache = self.cache.read().unwrap();
         
         cache.get(pubkey).and_then(|cached| {
@@ -205,15 +520,20 @@ impl RpcPool {
         );
     }
 
-    /// Get next client (round-robin)
-    fn get_next_client(&self) -> Arc<RpcClient> {
+    /// Get next endpoint whose circuit breaker allows a request (round-robin)
+    fn get_next_endpoint(&self) -> Option<&Endpoint> {
         let mut index = self.current_client_index.write().unwrap();
-        let client = self.clients[*index % self.clients.len()].clone();
-        *index = (*index + 1) % self.clients.len();
-        client
+        for _ in 0..self.endpoints.len() {
+            let endpoint = &self.endpoints[*index % self.endpoints.len()];
+            *index = (*index + 1) % self.endpoints.len();
+            if endpoint.circuit_breaker.write().unwrap().can_attempt() {
+                return Some(endpoint);
+            }
+        }
+        None
     }
 
-    /// Clear cache (useful for testing or manual refresh)
+    /// Clear cache (useful for testing or manual refresh); pinned accounts are kept
     pub fn clear_cache(&self) {
         let mut cache = self.cache.write().unwrap();
         cache.clear();
@@ -233,9 +553,23 @@ impl RpcPool {
     }
 }
 
+/// Re-read `pool`'s pinned accounts every `interval` for as long as the bot runs
+pub async fn run_pinned_refresh(pool: Arc<RpcPool>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    // The first tick fires at once, and the accounts were only just pinned
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        if let Err(e) = pool.refresh_pinned().await {
+            warn!("Failed to refresh pinned accounts: {}", e);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rpc::MockRpcClient;
 
     #[test]
     fn test_circuit_breaker_state_transitions() {
@@ -259,6 +593,177 @@ mod tests {
         assert_eq!(cb.failure_count, 0);
     }
 
+    #[test]
+    fn test_configured_timeout_passed_to_clients() {
+        let pool = RpcPool::new(
+            vec!["http://127.0.0.1:8899".to_string()],
+            60,
+            Duration::from_millis(750),
+        );
+        assert_eq!(pool.request_timeout(), Some(Duration::from_millis(750)));
+
+        let mock = Arc::new(MockRpcClient::new());
+        let pool = RpcPool::with_fetchers(vec![mock as Arc<dyn AccountFetcher>], 60);
+        assert_eq!(pool.request_timeout(), None);
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_populates_cache() {
+        let mock = Arc::new(MockRpcClient::new());
+        let present = Pubkey::new_unique();
+        let absent = Pubkey::new_unique();
+        mock.add_account(present, Account { lamports: 1, ..Account::default() });
+        mock.set_slot(500);
+
+        let pool = RpcPool::with_fetchers(vec![mock.clone() as Arc<dyn AccountFetcher>], 60);
+        assert_eq!(pool.get_cache_stats(), (0, 0));
+
+        let report = pool.warm_up(&[present, absent]).await.unwrap();
+        assert_eq!(report.cached, 1);
+        assert_eq!(report.missing, vec![absent]);
+        assert_eq!(pool.get_cache_stats(), (1, 0));
+        assert!(pool.get_from_cache(&present).is_some());
+
+        // The first iteration's read is served from the warm-up, at its slot
+        mock.add_account(present, Account { lamports: 2, ..Account::default() });
+        mock.set_slot(501);
+        let (slot, accounts) = pool.get_fresh_accounts(&[present]).await.unwrap();
+        assert_eq!(slot, Some(500));
+        assert_eq!(accounts[0].as_ref().map(|account| account.lamports), Some(1));
+
+        // Later ones read the endpoint again
+        let (slot, accounts) = pool.get_fresh_accounts(&[present]).await.unwrap();
+        assert_eq!(slot, Some(501));
+        assert_eq!(accounts[0].as_ref().map(|account| account.lamports), Some(2));
+    }
+
+    struct FailingFetcher;
+
+    impl AccountFetcher for FailingFetcher {
+        fn fetch_account(&self, pubkey: &Pubkey) -> BotResult<Account> {
+            Err(BotError::AccountFetchError {
+                address: *pubkey,
+                reason: "unreachable".to_string(),
+            })
+        }
+
+        fn fetch_multiple_accounts(&self, _pubkeys: &[Pubkey]) -> BotResult<Vec<Option<Account>>> {
+            Err(BotError::rpc_retryable(self.endpoint(), "unreachable".to_string()))
+        }
+
+        fn fetch_multiple_accounts_with_slot(
+            &self,
+            _pubkeys: &[Pubkey],
+        ) -> BotResult<(Slot, Vec<Option<Account>>)> {
+            Err(BotError::rpc_retryable(self.endpoint(), "unreachable".to_string()))
+        }
+
+        fn endpoint(&self) -> String {
+            "https://down.example".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_health_reports_open_circuit() {
+        let healthy = Arc::new(MockRpcClient::new());
+        let pubkey = Pubkey::new_unique();
+        healthy.add_account(pubkey, Account::default());
+
+        let pool = RpcPool::with_fetchers(
+            vec![Arc::new(FailingFetcher) as Arc<dyn AccountFetcher>, healthy],
+            0,
+        );
+
+        // Alternating endpoints: the failing one is hit first and trips after 5 failures
+        for _ in 0..5 {
+            pool.get_account_with_retry(&pubkey).await.unwrap();
+        }
+
+        let health = pool.endpoint_health();
+        assert_eq!(health[0].url, "https://down.example");
+        assert_eq!(health[0].state, CircuitState::Open);
+        assert_eq!(health[0].failure_count, 5);
+        assert!(health[0].last_failure_age_secs.is_some());
+        assert_eq!(health[1].state, CircuitState::Closed);
+        assert_eq!(health[1].success_count, 5);
+        assert_eq!(health[1].last_failure_age_secs, None);
+    }
+
+    #[tokio::test]
+    async fn test_every_circuit_open_is_an_outage() {
+        let pool = RpcPool::with_fetchers(vec![Arc::new(FailingFetcher) as Arc<dyn AccountFetcher>], 0);
+        let pubkey = Pubkey::new_unique();
+
+        // The retries trip the only endpoint's circuit breaker
+        assert!(matches!(
+            pool.get_account_with_retry(&pubkey).await,
+            Err(BotError::AccountFetchError { .. })
+        ));
+        let outage = pool.get_account_with_retry(&pubkey).await.unwrap_err();
+        assert!(matches!(outage, BotError::AllEndpointsDown { endpoints: 1 }));
+        assert_eq!(outage.severity(), crate::error::ErrorSeverity::Critical);
+    }
+
+    #[tokio::test]
+    async fn test_pinned_accounts_outlive_the_cache_ttl() {
+        let mock = Arc::new(MockRpcClient::new());
+        let shared = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        let missing = Pubkey::new_unique();
+        let config_account = Account {
+            lamports: 1,
+            ..Account::default()
+        };
+        mock.add_account(shared, config_account.clone());
+        mock.add_account(vault, Account::default());
+
+        // A zero TTL expires every cached account at once
+        let pool = RpcPool::with_fetchers(vec![mock.clone() as Arc<dyn AccountFetcher>], 0);
+        assert_eq!(pool.pin_accounts(&[shared, missing]).await.unwrap(), 1);
+        pool.get_multiple_accounts_with_retry(&[vault]).await.unwrap();
+        mock.clear();
+
+        assert_eq!(pool.get_from_cache(&shared), Some(config_account));
+        assert!(pool.get_from_cache(&vault).is_none());
+        pool.clear_cache();
+        assert_eq!(pool.pinned_count(), 1);
+        let accounts = pool.get_multiple_accounts_with_retry(&[shared]).await.unwrap();
+        assert_eq!(accounts[0].as_ref().map(|account| account.lamports), Some(1));
+
+        // The scheduled refresh picks up changes, and unpins closed accounts
+        mock.add_account(shared, Account { lamports: 2, ..Account::default() });
+        pool.refresh_pinned().await.unwrap();
+        assert_eq!(pool.get_from_cache(&shared).map(|account| account.lamports), Some(2));
+        mock.clear();
+        pool.refresh_pinned().await.unwrap();
+        assert_eq!(pool.pinned_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_fresh_reads_serve_pins_and_skip_the_cache() {
+        let mock = Arc::new(MockRpcClient::new());
+        let (shared, vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        mock.add_account(shared, Account { lamports: 1, ..Account::default() });
+        mock.add_account(vault, Account { lamports: 10, ..Account::default() });
+        mock.set_slot(500);
+
+        let pool = RpcPool::with_fetchers(vec![mock.clone() as Arc<dyn AccountFetcher>], 60);
+        pool.pin_accounts(&[shared]).await.unwrap();
+        pool.get_multiple_accounts_with_retry(&[vault]).await.unwrap();
+        // The vault moved since it was cached, the pinned config didn't get re-read
+        mock.add_account(shared, Account { lamports: 2, ..Account::default() });
+        mock.add_account(vault, Account { lamports: 11, ..Account::default() });
+
+        let (slot, accounts) = pool.get_fresh_accounts(&[vault, shared]).await.unwrap();
+        assert_eq!(slot, Some(500));
+        let lamports: Vec<u64> = accounts.iter().flatten().map(|account| account.lamports).collect();
+        assert_eq!(lamports, vec![11, 1]);
+
+        // Nothing left to read when every account is pinned
+        let (slot, accounts) = pool.get_fresh_accounts(&[shared]).await.unwrap();
+        assert_eq!((slot, accounts.len()), (None, 1));
+    }
+
     #[test]
     fn test_circuit_breaker_timeout() {
         let mut cb = CircuitBreaker::new(1, 0); // 0 second timeout for testing
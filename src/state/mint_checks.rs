@@ -0,0 +1,248 @@
+/// Mint Transferability Checks
+///
+/// Reads a mint's freeze authority and Token-2022 extensions to catch tokens
+/// that make arbitrage impossible before any pools are set up.
+
+use crate::error::{BotError, BotResult};
+use solana_program::pubkey::Pubkey;
+
+// SPL mint layout
+const MINT_LEN: usize = 82;
+const DECIMALS_OFFSET: usize = 44;
+const FREEZE_AUTHORITY_OPTION_OFFSET: usize = 46;
+const FREEZE_AUTHORITY_OFFSET: usize = 50;
+
+// Token-2022 places the account type byte after the (padded) token account length,
+// followed by type-length-value extension entries
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+const EXTENSIONS_OFFSET: usize = ACCOUNT_TYPE_OFFSET + 1;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+// Token-2022 extension type discriminators
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXTENSION_DEFAULT_ACCOUNT_STATE: u16 = 6;
+const EXTENSION_NON_TRANSFERABLE: u16 = 9;
+const EXTENSION_TRANSFER_HOOK: u16 = 14;
+
+// Offsets inside the extension values
+const OLDER_TRANSFER_FEE_BPS_OFFSET: usize = 88;
+const NEWER_TRANSFER_FEE_BPS_OFFSET: usize = 106;
+const TRANSFER_HOOK_PROGRAM_OFFSET: usize = 32;
+const ACCOUNT_STATE_FROZEN: u8 = 2;
+
+/// Properties of a mint that can block or tax arbitrage transfers
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MintRestrictions {
+    pub freeze_authority: Option<Pubkey>,
+    pub transfer_fee_bps: Option<u16>,
+    pub transfer_hook_program: Option<Pubkey>,
+    pub non_transferable: bool,
+    pub default_frozen: bool,
+}
+
+impl MintRestrictions {
+    /// Parse an SPL Token or Token-2022 mint account
+    pub fn parse(data: &[u8]) -> BotResult<Self> {
+        if data.len() < MINT_LEN {
+            return Err(invalid_mint(format!(
+                "mint data is {} bytes, expected at least {}",
+                data.len(),
+                MINT_LEN
+            )));
+        }
+
+        let mut restrictions = MintRestrictions::default();
+
+        if read_u32(data, FREEZE_AUTHORITY_OPTION_OFFSET) == 1 {
+            restrictions.freeze_authority = Some(read_pubkey(data, FREEZE_AUTHORITY_OFFSET));
+        }
+
+        // Plain SPL mints (and Token-2022 mints without extensions) end here
+        if data.len() <= ACCOUNT_TYPE_OFFSET {
+            return Ok(restrictions);
+        }
+        if data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_MINT {
+            return Err(invalid_mint(format!(
+                "unexpected Token-2022 account type {}",
+                data[ACCOUNT_TYPE_OFFSET]
+            )));
+        }
+
+        let mut offset = EXTENSIONS_OFFSET;
+        while offset + 4 <= data.len() {
+            let extension_type = read_u16(data, offset);
+            let length = read_u16(data, offset + 2) as usize;
+            let value_start = offset + 4;
+            let value_end = value_start + length;
+            if extension_type == 0 && length == 0 {
+                // Uninitialized padding
+                break;
+            }
+            if value_end > data.len() {
+                return Err(invalid_mint(format!(
+                    "extension {} overruns mint data",
+                    extension_type
+                )));
+            }
+            let value = &data[value_start..value_end];
+
+            match extension_type {
+                EXTENSION_TRANSFER_FEE_CONFIG if value.len() >= NEWER_TRANSFER_FEE_BPS_OFFSET + 2 => {
+                    let fee_bps = read_u16(value, OLDER_TRANSFER_FEE_BPS_OFFSET)
+                        .max(read_u16(value, NEWER_TRANSFER_FEE_BPS_OFFSET));
+                    restrictions.transfer_fee_bps = Some(fee_bps);
+                }
+                EXTENSION_TRANSFER_HOOK if value.len() >= TRANSFER_HOOK_PROGRAM_OFFSET + 32 => {
+                    let program = read_pubkey(value, TRANSFER_HOOK_PROGRAM_OFFSET);
+                    if program != Pubkey::default() {
+                        restrictions.transfer_hook_program = Some(program);
+                    }
+                }
+                EXTENSION_NON_TRANSFERABLE => restrictions.non_transferable = true,
+                EXTENSION_DEFAULT_ACCOUNT_STATE if !value.is_empty() => {
+                    restrictions.default_frozen = value[0] == ACCOUNT_STATE_FROZEN;
+                }
+                _ => {}
+            }
+
+            offset = value_end;
+        }
+
+        Ok(restrictions)
+    }
+
+    /// Human-readable reasons this mint may not be safely arbitraged
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if let Some(authority) = self.freeze_authority {
+            issues.push(format!("active freeze authority {}", authority));
+        }
+        if let Some(fee_bps) = self.transfer_fee_bps.filter(|bps| *bps > 0) {
+            issues.push(format!("transfer fee of {} bps", fee_bps));
+        }
+        if let Some(program) = self.transfer_hook_program {
+            issues.push(format!("transfer hook program {}", program));
+        }
+        if self.non_transferable {
+            issues.push("non-transferable extension".to_string());
+        }
+        if self.default_frozen {
+            issues.push("new token accounts are frozen by default".to_string());
+        }
+        issues
+    }
+}
+
+/// Decimals of an SPL Token or Token-2022 mint account
+pub fn mint_decimals(data: &[u8]) -> BotResult<u8> {
+    if data.len() < MINT_LEN {
+        return Err(invalid_mint(format!(
+            "mint data is {} bytes, expected at least {}",
+            data.len(),
+            MINT_LEN
+        )));
+    }
+    Ok(data[DECIMALS_OFFSET])
+}
+
+fn invalid_mint(reason: String) -> BotError {
+    BotError::DeserializationError {
+        data_type: "Mint".to_string(),
+        source: reason.into(),
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_mint(freeze_authority: Option<Pubkey>) -> Vec<u8> {
+        let mut data = vec![0u8; MINT_LEN];
+        data[45] = 1; // is_initialized
+        if let Some(authority) = freeze_authority {
+            data[FREEZE_AUTHORITY_OPTION_OFFSET..FREEZE_AUTHORITY_OFFSET]
+                .copy_from_slice(&1u32.to_le_bytes());
+            data[FREEZE_AUTHORITY_OFFSET..MINT_LEN].copy_from_slice(authority.as_ref());
+        }
+        data
+    }
+
+    fn with_extensions(mut data: Vec<u8>, extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        data.resize(ACCOUNT_TYPE_OFFSET, 0);
+        data.push(ACCOUNT_TYPE_MINT);
+        for (extension_type, value) in extensions {
+            data.extend_from_slice(&extension_type.to_le_bytes());
+            data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+        data
+    }
+
+    #[test]
+    fn test_plain_mint_has_no_issues() {
+        let restrictions = MintRestrictions::parse(&base_mint(None)).unwrap();
+
+        assert_eq!(restrictions, MintRestrictions::default());
+        assert!(restrictions.issues().is_empty());
+    }
+
+    #[test]
+    fn test_freeze_authority_detected() {
+        let authority = Pubkey::new_unique();
+        let restrictions = MintRestrictions::parse(&base_mint(Some(authority))).unwrap();
+
+        assert_eq!(restrictions.freeze_authority, Some(authority));
+        assert_eq!(restrictions.issues().len(), 1);
+    }
+
+    #[test]
+    fn test_token_2022_extensions_detected() {
+        let mut transfer_fee = vec![0u8; 108];
+        transfer_fee[NEWER_TRANSFER_FEE_BPS_OFFSET..NEWER_TRANSFER_FEE_BPS_OFFSET + 2]
+            .copy_from_slice(&50u16.to_le_bytes());
+        let hook_program = Pubkey::new_unique();
+        let mut transfer_hook = vec![0u8; 64];
+        transfer_hook[TRANSFER_HOOK_PROGRAM_OFFSET..].copy_from_slice(hook_program.as_ref());
+
+        let data = with_extensions(
+            base_mint(None),
+            &[
+                (EXTENSION_TRANSFER_FEE_CONFIG, transfer_fee),
+                (EXTENSION_TRANSFER_HOOK, transfer_hook),
+                (EXTENSION_NON_TRANSFERABLE, vec![]),
+            ],
+        );
+        let restrictions = MintRestrictions::parse(&data).unwrap();
+
+        assert_eq!(restrictions.transfer_fee_bps, Some(50));
+        assert_eq!(restrictions.transfer_hook_program, Some(hook_program));
+        assert!(restrictions.non_transferable);
+        assert_eq!(restrictions.issues().len(), 3);
+    }
+
+    #[test]
+    fn test_unset_transfer_hook_ignored() {
+        let data = with_extensions(base_mint(None), &[(EXTENSION_TRANSFER_HOOK, vec![0u8; 64])]);
+        let restrictions = MintRestrictions::parse(&data).unwrap();
+
+        assert!(restrictions.transfer_hook_program.is_none());
+        assert!(restrictions.issues().is_empty());
+    }
+
+    #[test]
+    fn test_truncated_mint_rejected() {
+        assert!(MintRestrictions::parse(&[0u8; 10]).is_err());
+    }
+}
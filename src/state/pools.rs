@@ -1,11 +1,13 @@
 use crate::{
-    constants::SOL_MINT,
+    constants::{sol_mint, BASIS_POINTS_DENOMINATOR, SOL_DECIMALS, TOKEN_TRANSFERS_PER_ARBITRAGE},
     dex::{
+        lifinity::initializer::LifinityPool,
         meteora::{
             damm_initializer::{MeteoraDammPool},
             damm_v2_initializer::{MeteoraDammV2Pool},
             dlmm_initializer::{MeteoraDlmmPool},
         },
+        phoenix::initializer::PhoenixPool,
         pump::initializer::PumpPool,
         raydium::{
             initializer::RaydiumCpmmPool,
@@ -13,12 +15,18 @@ use crate::{
             clmm_initializer::RaydiumClmmPool,
         },
         solfi::initializer::SolfiPool,
+        traits::{DexPool, SwapDirection},
         vertigo::initializer::VertigoPool,
         whirlpool::initializer::WhirlpoolPool,
     },
+    pool::PooledObject,
+    swap_accounts::SwapAccountCache,
 };
+use solana_program::instruction::AccountMeta;
 use solana_program::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct MintPoolData {
@@ -36,14 +44,24 @@ pub struct MintPoolData {
     pub solfi_pools: Vec<SolfiPool>,
     pub meteora_damm_v2_pools: Vec<MeteoraDammV2Pool>,
     pub vertigo_pools: Vec<VertigoPool>,
+    pub lifinity_pools: Vec<LifinityPool>,
+    pub phoenix_pools: Vec<PhoenixPool>,
+    /// Token-2022 transfer fee charged on every token transfer (0 for standard SPL mints)
+    pub transfer_fee_bps: u16,
+    /// Read from the mint account on initialization; used to display token amounts
+    pub decimals: u8,
+    /// Direction each pool of a quoted route swaps in; empty for routes sized on-chain,
+    /// whose pools list every tick and bin array
+    pub route_directions: HashMap<Pubkey, SwapDirection>,
+    /// Static swap accounts of every pool, built by `cache_swap_accounts`
+    pub swap_account_cache: Arc<SwapAccountCache>,
 }
 
 impl MintPoolData {
     pub fn new(mint: &str, wallet_account: &str, token_program: Pubkey) -> anyhow::Result<Self> {
-        let sol_mint = Pubkey::from_str(SOL_MINT)?;
         let wallet_pk = Pubkey::from_str(wallet_account)?;
         let wallet_wsol_pk =
-            spl_associated_token_account::get_associated_token_address(&wallet_pk, &sol_mint);
+            spl_associated_token_account::get_associated_token_address(&wallet_pk, &sol_mint());
         Ok(Self {
             mint: Pubkey::from_str(mint)?,
             token_program,
@@ -59,16 +77,202 @@ impl MintPoolData {
             solfi_pools: Vec::new(),
             meteora_damm_v2_pools: Vec::new(),
             vertigo_pools: Vec::new(),
+            lifinity_pools: Vec::new(),
+            phoenix_pools: Vec::new(),
+            transfer_fee_bps: 0,
+            decimals: SOL_DECIMALS,
+            route_directions: HashMap::new(),
+            swap_account_cache: Arc::default(),
         })
     }
 
+    /// Record the directions of a buy-then-sell `route`: its first `buy_pools` pools buy
+    /// the mint and the others sell it
+    pub fn set_route(&mut self, route: &[Pubkey], buy_pools: usize) {
+        self.route_directions = route
+            .iter()
+            .enumerate()
+            .map(|(i, pool)| {
+                let direction = if i < buy_pools { SwapDirection::Buy } else { SwapDirection::Sell };
+                (*pool, direction)
+            })
+            .collect();
+    }
+
+    pub fn swap_direction(&self, pool: &Pubkey) -> Option<SwapDirection> {
+        self.route_directions.get(pool).copied()
+    }
+
+    /// Point the wallet accounts at `wallet`, e.g. after failing over to the backup
+    /// wallet. The cached swap accounts were built for the old wallet, so they're rebuilt.
+    pub fn set_wallet(&mut self, wallet: &Pubkey) {
+        self.wallet_account = *wallet;
+        self.wallet_wsol_account =
+            spl_associated_token_account::get_associated_token_address(wallet, &sol_mint());
+        if !self.swap_account_cache.is_empty() {
+            self.cache_swap_accounts();
+        }
+    }
+
+    /// Cache every pool's static swap accounts; call once the pools are initialized
+    pub fn cache_swap_accounts(&mut self) {
+        let cache = SwapAccountCache::build(&self.dex_pools(), &self.wallet_account);
+        self.swap_account_cache = Arc::new(cache);
+    }
+
+    /// `pool`'s swap accounts for `direction` (every tick and bin array for None), copied
+    /// from the cache into a reused vector
+    pub fn swap_accounts(
+        &self,
+        pool: &dyn DexPool,
+        wallet: &Pubkey,
+        direction: Option<SwapDirection>,
+    ) -> PooledObject<Vec<AccountMeta>> {
+        self.swap_account_cache.swap_accounts(pool, wallet, direction)
+    }
+
+    /// Raw amount of this mint's token in whole tokens
+    pub fn format_token_amount(&self, raw: u64) -> String {
+        format_token_amount(raw, self.decimals)
+    }
+
+    /// Realized profit of a round trip after the mint's transfer fee is withheld
+    pub fn expected_profit(&self, amount_in: u64, nominal_amount_out: u64) -> u64 {
+        profit_after_transfer_fee(amount_in, nominal_amount_out, self.transfer_fee_bps)
+    }
+
+    /// Lamports of a round trip's nominal output withheld by the mint's transfer fee
+    pub fn transfer_fees(&self, nominal_amount_out: u64) -> u64 {
+        nominal_amount_out - amount_after_transfer_fee(nominal_amount_out, self.transfer_fee_bps)
+    }
+
     // Helper methods are now handled by initializers directly adding to the vectors, 
     // or we can keep add methods if needed, but since we are refactoring refresh.rs 
     // to bulk-initialize, these add_* methods might become redundant or change signature.
     // For now, I'll keep the struct definition clean. The logic in refresh.rs will construct MintPoolData
     // and populate the vectors directly.
+
+    /// Sort every DEX's pools by pool address
+    ///
+    /// Initialization order depends on RPC completion order, so sorting keeps the
+    /// assembled swap accounts identical across runs of the same config.
+    pub fn sort_pools(&mut self) {
+        self.raydium_pools.sort_by_key(|p| p.pool_address());
+        self.raydium_cp_pools.sort_by_key(|p| p.pool_address());
+        self.pump_pools.sort_by_key(|p| p.pool_address());
+        self.dlmm_pairs.sort_by_key(|p| p.pool_address());
+        self.whirlpool_pools.sort_by_key(|p| p.pool_address());
+        self.raydium_clmm_pools.sort_by_key(|p| p.pool_address());
+        self.meteora_damm_pools.sort_by_key(|p| p.pool_address());
+        self.solfi_pools.sort_by_key(|p| p.pool_address());
+        self.meteora_damm_v2_pools.sort_by_key(|p| p.pool_address());
+        self.vertigo_pools.sort_by_key(|p| p.pool_address());
+        self.lifinity_pools.sort_by_key(|p| p.pool_address());
+        self.phoenix_pools.sort_by_key(|p| p.pool_address());
+    }
+
+    /// All pools across every DEX, in swap-account assembly order
+    pub fn dex_pools(&self) -> Vec<&dyn DexPool> {
+        let mut pools: Vec<&dyn DexPool> = Vec::new();
+        pools.extend(self.raydium_pools.iter().map(|p| p as &dyn DexPool));
+        pools.extend(self.raydium_cp_pools.iter().map(|p| p as &dyn DexPool));
+        pools.extend(self.pump_pools.iter().map(|p| p as &dyn DexPool));
+        pools.extend(self.dlmm_pairs.iter().map(|p| p as &dyn DexPool));
+        pools.extend(self.whirlpool_pools.iter().map(|p| p as &dyn DexPool));
+        pools.extend(self.raydium_clmm_pools.iter().map(|p| p as &dyn DexPool));
+        pools.extend(self.meteora_damm_pools.iter().map(|p| p as &dyn DexPool));
+        pools.extend(self.meteora_damm_v2_pools.iter().map(|p| p as &dyn DexPool));
+        pools.extend(self.solfi_pools.iter().map(|p| p as &dyn DexPool));
+        pools.extend(self.vertigo_pools.iter().map(|p| p as &dyn DexPool));
+        pools.extend(self.lifinity_pools.iter().map(|p| p as &dyn DexPool));
+        pools.extend(self.phoenix_pools.iter().map(|p| p as &dyn DexPool));
+        pools
+    }
+
+    /// `dex_pools`, mutably, in the same order
+    pub fn dex_pools_mut(&mut self) -> Vec<&mut dyn DexPool> {
+        let mut pools: Vec<&mut dyn DexPool> = Vec::new();
+        pools.extend(self.raydium_pools.iter_mut().map(|p| p as &mut dyn DexPool));
+        pools.extend(self.raydium_cp_pools.iter_mut().map(|p| p as &mut dyn DexPool));
+        pools.extend(self.pump_pools.iter_mut().map(|p| p as &mut dyn DexPool));
+        pools.extend(self.dlmm_pairs.iter_mut().map(|p| p as &mut dyn DexPool));
+        pools.extend(self.whirlpool_pools.iter_mut().map(|p| p as &mut dyn DexPool));
+        pools.extend(self.raydium_clmm_pools.iter_mut().map(|p| p as &mut dyn DexPool));
+        pools.extend(self.meteora_damm_pools.iter_mut().map(|p| p as &mut dyn DexPool));
+        pools.extend(self.meteora_damm_v2_pools.iter_mut().map(|p| p as &mut dyn DexPool));
+        pools.extend(self.solfi_pools.iter_mut().map(|p| p as &mut dyn DexPool));
+        pools.extend(self.vertigo_pools.iter_mut().map(|p| p as &mut dyn DexPool));
+        pools.extend(self.lifinity_pools.iter_mut().map(|p| p as &mut dyn DexPool));
+        pools.extend(self.phoenix_pools.iter_mut().map(|p| p as &mut dyn DexPool));
+        pools
+    }
+
+    /// Accounts the pools share with other pools of their DEX, without duplicates
+    pub fn shared_accounts(&self) -> Vec<Pubkey> {
+        let mut accounts: Vec<Pubkey> =
+            self.dex_pools().iter().flat_map(|pool| pool.shared_accounts()).collect();
+        accounts.sort();
+        accounts.dedup();
+        accounts
+    }
+
+    /// Keep only the pools whose address is in `keep`
+    pub fn retain_pools(&mut self, keep: &HashSet<Pubkey>) {
+        self.raydium_pools.retain(|p| keep.contains(&p.pool_address()));
+        self.raydium_cp_pools.retain(|p| keep.contains(&p.pool_address()));
+        self.pump_pools.retain(|p| keep.contains(&p.pool_address()));
+        self.dlmm_pairs.retain(|p| keep.contains(&p.pool_address()));
+        self.whirlpool_pools.retain(|p| keep.contains(&p.pool_address()));
+        self.raydium_clmm_pools.retain(|p| keep.contains(&p.pool_address()));
+        self.meteora_damm_pools.retain(|p| keep.contains(&p.pool_address()));
+        self.solfi_pools.retain(|p| keep.contains(&p.pool_address()));
+        self.meteora_damm_v2_pools.retain(|p| keep.contains(&p.pool_address()));
+        self.vertigo_pools.retain(|p| keep.contains(&p.pool_address()));
+        self.lifinity_pools.retain(|p| keep.contains(&p.pool_address()));
+        self.phoenix_pools.retain(|p| keep.contains(&p.pool_address()));
+        if !self.swap_account_cache.is_empty() {
+            self.cache_swap_accounts();
+        }
+    }
+}
+
+/// Deduct a transfer fee charged on each token-leg transfer from a round trip's output
+///
+/// The fee shrinks the intermediate token amount on every transfer, which scales the
+/// final output by the same ratio.
+pub fn profit_after_transfer_fee(amount_in: u64, nominal_amount_out: u64, fee_bps: u16) -> u64 {
+    amount_after_transfer_fee(nominal_amount_out, fee_bps).saturating_sub(amount_in)
 }
 
+/// Raw token amount in whole tokens, e.g. 1_500_000 with 6 decimals is "1.5"
+///
+/// Exact (no float rounding); trailing zeros of the fraction are dropped.
+pub fn format_token_amount(raw: u64, decimals: u8) -> String {
+    let digits = raw.to_string();
+    let decimals = decimals as usize;
+    if decimals == 0 {
+        return digits;
+    }
+    let padded = format!("{:0>width$}", digits, width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Round trip output left after the transfer fee on every token leg
+fn amount_after_transfer_fee(nominal_amount_out: u64, fee_bps: u16) -> u64 {
+    let fee_bps = (fee_bps as u64).min(BASIS_POINTS_DENOMINATOR);
+    let mut amount_out = nominal_amount_out as u128;
+    for _ in 0..TOKEN_TRANSFERS_PER_ARBITRAGE {
+        amount_out = amount_out * (BASIS_POINTS_DENOMINATOR - fee_bps) as u128
+            / BASIS_POINTS_DENOMINATOR as u128;
+    }
+    amount_out as u64
+}
 
 #[derive(Debug, Clone)]
 pub struct RaydiumCpPool {
@@ -178,10 +382,9 @@ pub struct MintPoolData {
 
 impl MintPoolData {
     pub fn new(mint: &str, wallet_account: &str, token_program: Pubkey) -> anyhow::Result<Self> {
-        let sol_mint = Pubkey::from_str(SOL_MINT)?;
         let wallet_pk = Pubkey::from_str(wallet_account)?;
         let wallet_wsol_pk =
-            spl_associated_token_account::get_associated_token_address(&wallet_pk, &sol_mint);
+            spl_associated_token_account::get_associated_token_address(&wallet_pk, &sol_mint());
         Ok(Self {
             mint: Pubkey::from_str(mint)?,
             token_program,
@@ -431,3 +634,80 @@ impl MintPoolData {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::instruction::AccountMeta;
+
+    fn raydium_pool(pool: Pubkey) -> RaydiumCpmmPool {
+        RaydiumCpmmPool {
+            pool,
+            token_vault: Pubkey::new_unique(),
+            sol_vault: Pubkey::new_unique(),
+            coin_mint: Pubkey::new_unique(),
+            pc_mint: Pubkey::new_unique(),
+        }
+    }
+
+    fn swap_accounts(data: &MintPoolData) -> Vec<AccountMeta> {
+        data.raydium_pools
+            .iter()
+            .flat_map(|p| p.get_swap_accounts(&data.wallet_account))
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_pools_is_deterministic() {
+        let mint = Pubkey::new_unique().to_string();
+        let wallet = Pubkey::new_unique().to_string();
+        let pools: Vec<_> = (0..5).map(|_| raydium_pool(Pubkey::new_unique())).collect();
+
+        let mut first = MintPoolData::new(&mint, &wallet, spl_token::ID).unwrap();
+        first.raydium_pools.extend(pools.iter().cloned());
+
+        let mut second = MintPoolData::new(&mint, &wallet, spl_token::ID).unwrap();
+        second.raydium_pools.extend(pools.iter().rev().cloned());
+
+        first.sort_pools();
+        second.sort_pools();
+
+        assert_eq!(swap_accounts(&first), swap_accounts(&second));
+    }
+
+    #[test]
+    fn test_expected_profit_deducts_transfer_fee() {
+        let mint = Pubkey::new_unique().to_string();
+        let wallet = Pubkey::new_unique().to_string();
+
+        let standard = MintPoolData::new(&mint, &wallet, spl_token::ID).unwrap();
+        assert_eq!(standard.expected_profit(1_000_000, 1_050_000), 50_000);
+
+        let mut token_2022 = MintPoolData::new(&mint, &wallet, spl_token::ID).unwrap();
+        token_2022.transfer_fee_bps = 100;
+        // 1_050_000 * 0.99 * 0.99 = 1_029_105
+        assert_eq!(token_2022.expected_profit(1_000_000, 1_050_000), 29_105);
+        // A thin nominal edge is wiped out entirely
+        assert_eq!(token_2022.expected_profit(1_000_000, 1_010_000), 0);
+    }
+
+    #[test]
+    fn test_format_token_amount() {
+        // 6 decimals, like USDC
+        assert_eq!(format_token_amount(1_500_000, 6), "1.5");
+        assert_eq!(format_token_amount(12_345_678, 6), "12.345678");
+        assert_eq!(format_token_amount(1, 6), "0.000001");
+        assert_eq!(format_token_amount(0, 6), "0");
+
+        // 9 decimals, like SOL
+        assert_eq!(format_token_amount(2_000_000_000, 9), "2");
+        assert_eq!(format_token_amount(50_000, 9), "0.00005");
+        assert_eq!(format_token_amount(u64::MAX, 9), "18446744073.709551615");
+
+        let mint = Pubkey::new_unique().to_string();
+        let wallet = Pubkey::new_unique().to_string();
+        let mut pool_data = MintPoolData::new(&mint, &wallet, spl_token::ID).unwrap();
+        pool_data.decimals = 6;
+        assert_eq!(pool_data.format_token_amount(2_500_000), "2.5");
+    }
+}
@@ -0,0 +1,134 @@
+/// Swap Account Cache
+///
+/// A pool's swap accounts only change when the pool is re-initialized, yet
+/// `get_swap_accounts` builds a fresh `Vec<AccountMeta>` for every pool on every loop
+/// iteration. `SwapAccountCache` builds each pool's static accounts once, undirected
+/// and for both swap directions, and hands them out copied into vectors taken from an
+/// `ObjectPool`, so the hot path reuses allocations. Wallet-derived accounts are
+/// appended per call through `DexPool::extend_wallet_swap_accounts`.
+
+use crate::dex::traits::{DexPool, SwapDirection};
+use crate::pool::{ObjectPool, PooledObject};
+use solana_program::instruction::AccountMeta;
+use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::fmt;
+
+type CacheKey = (Pubkey, Option<SwapDirection>);
+
+/// Static swap accounts of a mint's pools
+pub struct SwapAccountCache {
+    accounts: HashMap<CacheKey, Vec<AccountMeta>>,
+    vectors: ObjectPool<Vec<AccountMeta>>,
+}
+
+impl SwapAccountCache {
+    /// Cache the static swap accounts of `pools`, swapped by `wallet`
+    pub fn build(pools: &[&dyn DexPool], wallet: &Pubkey) -> Self {
+        let mut accounts = HashMap::with_capacity(pools.len() * 3);
+        for pool in pools {
+            for direction in [None, Some(SwapDirection::Buy), Some(SwapDirection::Sell)] {
+                let static_accounts = static_accounts(*pool, wallet, direction);
+                accounts.insert((pool.pool_address(), direction), static_accounts);
+            }
+        }
+        // Room for the longest list plus a few wallet accounts, so reused vectors never grow
+        let capacity = accounts.values().map(Vec::len).max().unwrap_or(0) + 4;
+        Self {
+            accounts,
+            vectors: ObjectPool::new(move || Vec::with_capacity(capacity), pools.len()),
+        }
+    }
+
+    /// Pools whose accounts are cached
+    pub fn len(&self) -> usize {
+        self.accounts.len() / 3
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// `pool`'s swap accounts for `direction` (every tick and bin array for None), in a
+    /// vector that goes back to the pool when dropped. Pools added after the cache was
+    /// built are read from the pool itself.
+    pub fn swap_accounts(
+        &self,
+        pool: &dyn DexPool,
+        wallet: &Pubkey,
+        direction: Option<SwapDirection>,
+    ) -> PooledObject<Vec<AccountMeta>> {
+        let mut accounts = self.vectors.acquire();
+        accounts.clear();
+        match self.accounts.get(&(pool.pool_address(), direction)) {
+            Some(cached) => accounts.extend_from_slice(cached),
+            None => accounts.extend(static_accounts(pool, wallet, direction)),
+        }
+        pool.extend_wallet_swap_accounts(wallet, &mut accounts);
+        accounts
+    }
+}
+
+impl Default for SwapAccountCache {
+    fn default() -> Self {
+        Self::build(&[], &Pubkey::default())
+    }
+}
+
+impl fmt::Debug for SwapAccountCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SwapAccountCache").field("pools", &self.len()).finish()
+    }
+}
+
+fn static_accounts(pool: &dyn DexPool, wallet: &Pubkey, direction: Option<SwapDirection>) -> Vec<AccountMeta> {
+    match direction {
+        Some(direction) => pool.get_swap_accounts_for(wallet, direction),
+        None => pool.get_swap_accounts(wallet),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::whirlpool::initializer::WhirlpoolPool;
+
+    fn whirlpool() -> WhirlpoolPool {
+        WhirlpoolPool {
+            pool: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            token_vault_a: Pubkey::new_unique(),
+            token_vault_b: Pubkey::new_unique(),
+            tick_arrays: (0..3).map(|_| Pubkey::new_unique()).collect(),
+            current_tick: 0,
+            token_mint_a: Pubkey::new_unique(),
+            token_mint_b: Pubkey::new_unique(),
+            liquidity: 0,
+            sqrt_price: 0,
+            adaptive_fee_rate: 0,
+        }
+    }
+
+    #[test]
+    fn test_cached_accounts_match_the_pool_and_reuse_vectors() {
+        let (cached, added) = (whirlpool(), whirlpool());
+        let wallet = Pubkey::new_unique();
+        let cache = SwapAccountCache::build(&[&cached], &wallet);
+        assert_eq!(cache.len(), 1);
+
+        for direction in [None, Some(SwapDirection::Buy), Some(SwapDirection::Sell)] {
+            let accounts = cache.swap_accounts(&cached, &wallet, direction);
+            assert_eq!(*accounts, static_accounts(&cached, &wallet, direction));
+        }
+        // Not cached, still correct
+        assert_eq!(*cache.swap_accounts(&added, &wallet, None), added.get_swap_accounts(&wallet));
+
+        // The vector handed out last is handed out again, holding only the new pool's accounts
+        let first = cache.swap_accounts(&cached, &wallet, None);
+        let address = first.as_ptr();
+        drop(first);
+        let second = cache.swap_accounts(&cached, &wallet, Some(SwapDirection::Buy));
+        assert_eq!(second.as_ptr(), address);
+        assert_eq!(*second, cached.get_swap_accounts_for(&wallet, SwapDirection::Buy));
+    }
+}
@@ -3,10 +3,107 @@
 /// Handles logging of historical trades and opportunities to SQLite.
 /// Critical for strategy backtesting and performance analysis.
 
+use crate::constants::DB_FAILURE_ERROR_THRESHOLD;
 use crate::error::BotResult;
+use crate::metrics::METRICS;
+use crate::opportunity::ArbitrageOpportunity;
+use crate::transaction::{FeeBreakdown, SendResult};
+use async_trait::async_trait;
 use sqlx::{sqlite::SqlitePoolOptions, Pool, Sqlite};
+use std::collections::{HashSet, VecDeque};
 use std::sync::Arc;
-use tracing::info;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+/// One row of the trades table
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TradeRecord {
+    pub timestamp: i64,
+    pub mint: String,
+    /// Estimated profit at send time
+    pub profit_lamports: i64,
+    /// Wallet balance change read back after confirmation, when reconciled
+    pub realized_profit_lamports: Option<i64>,
+    pub signature: String,
+    pub dexes: String,
+    pub input: i64,
+    pub output: i64,
+    /// Fees the trade paid (see `FeeBreakdown`); zero unless it was confirmed to have
+    /// landed, and in rows logged before fees were recorded
+    #[serde(default)]
+    pub base_fee_lamports: i64,
+    #[serde(default)]
+    pub priority_fee_lamports: i64,
+    #[serde(default)]
+    pub tip_lamports: i64,
+    #[serde(default)]
+    pub protocol_fee_lamports: i64,
+}
+
+impl TradeRecord {
+    /// Row stamped with the current time
+    pub fn new(
+        mint: &str,
+        profit: i64,
+        signature: &str,
+        dexes: &[String],
+        input: u64,
+        output: u64,
+    ) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp(),
+            mint: mint.to_string(),
+            profit_lamports: profit,
+            realized_profit_lamports: None,
+            signature: signature.to_string(),
+            dexes: dexes.join(","),
+            input: input as i64,
+            output: output as i64,
+            base_fee_lamports: 0,
+            priority_fee_lamports: 0,
+            tip_lamports: 0,
+            protocol_fee_lamports: 0,
+        }
+    }
+
+    pub fn with_realized_profit(mut self, realized_profit: Option<i64>) -> Self {
+        self.realized_profit_lamports = realized_profit;
+        self
+    }
+
+    pub fn with_fees(mut self, fees: &FeeBreakdown) -> Self {
+        self.base_fee_lamports = fees.base_fee_lamports as i64;
+        self.priority_fee_lamports = fees.priority_fee_lamports as i64;
+        self.tip_lamports = fees.tip_lamports as i64;
+        self.protocol_fee_lamports = fees.protocol_fee_lamports as i64;
+        self
+    }
+
+    /// Fees of `result` once it landed; a send not confirmed to have landed may never
+    /// be paid for, so its row records none
+    pub fn with_landed_fees(self, result: &SendResult) -> Self {
+        if result.landed {
+            self.with_fees(&result.fees)
+        } else {
+            self
+        }
+    }
+
+    pub fn fees(&self) -> FeeBreakdown {
+        FeeBreakdown {
+            base_fee_lamports: self.base_fee_lamports as u64,
+            priority_fee_lamports: self.priority_fee_lamports as u64,
+            tip_lamports: self.tip_lamports as u64,
+            protocol_fee_lamports: self.protocol_fee_lamports as u64,
+        }
+    }
+}
+
+/// Destination for trade rows
+#[async_trait]
+pub trait TradeSink: Send + Sync {
+    async fn write_trade(&self, record: &TradeRecord) -> BotResult<()>;
+}
 
 #[derive(Clone)]
 pub struct Database {
@@ -31,10 +128,15 @@ impl Database {
                 timestamp INTEGER NOT NULL,
                 mint TEXT NOT NULL,
                 profit_lamports INTEGER NOT NULL,
+                realized_profit_lamports INTEGER,
                 signature TEXT NOT NULL,
                 dexes TEXT NOT NULL,
                 input_amount INTEGER NOT NULL,
-                output_amount INTEGER NOT NULL
+                output_amount INTEGER NOT NULL,
+                base_fee_lamports INTEGER NOT NULL DEFAULT 0,
+                priority_fee_lamports INTEGER NOT NULL DEFAULT 0,
+                tip_lamports INTEGER NOT NULL DEFAULT 0,
+                protocol_fee_lamports INTEGER NOT NULL DEFAULT 0
             );
             CREATE INDEX IF NOT EXISTS idx_trades_timestamp ON trades(timestamp);
             CREATE INDEX IF NOT EXISTS idx_trades_mint ON trades(mint);
@@ -44,6 +146,29 @@ impl Database {
         .await
         .map_err(|e| crate::error::BotError::ConfigError(format!("Schema init failed: {}", e)))?;
 
+        // Databases created before profit reconciliation lack the realized column, and
+        // those created before fees were recorded lack the fee columns
+        for (column, definition) in [
+            ("realized_profit_lamports", "INTEGER"),
+            ("base_fee_lamports", "INTEGER NOT NULL DEFAULT 0"),
+            ("priority_fee_lamports", "INTEGER NOT NULL DEFAULT 0"),
+            ("tip_lamports", "INTEGER NOT NULL DEFAULT 0"),
+            ("protocol_fee_lamports", "INTEGER NOT NULL DEFAULT 0"),
+        ] {
+            let (has_column,): (i64,) =
+                sqlx::query_as("SELECT COUNT(*) FROM pragma_table_info('trades') WHERE name = ?")
+                    .bind(column)
+                    .fetch_one(&pool)
+                    .await
+                    .map_err(|e| crate::error::BotError::ConfigError(format!("Schema check failed: {}", e)))?;
+            if has_column == 0 {
+                sqlx::query(&format!("ALTER TABLE trades ADD COLUMN {} {}", column, definition))
+                    .execute(&pool)
+                    .await
+                    .map_err(|e| crate::error::BotError::ConfigError(format!("Schema migration failed: {}", e)))?;
+            }
+        }
+
         Ok(Self { pool })
     }
 
@@ -55,23 +180,35 @@ impl Database {
         dexes: &[String],
         input: u64,
         output: u64,
+        fees: &FeeBreakdown,
     ) -> BotResult<()> {
-        let timestamp = chrono::Utc::now().timestamp();
-        let dexes_str = dexes.join(",");
+        self.insert_trade(
+            &TradeRecord::new(mint, profit as i64, signature, dexes, input, output).with_fees(fees),
+        )
+        .await
+    }
 
+    /// Insert a row, keeping its original timestamp
+    pub async fn insert_trade(&self, record: &TradeRecord) -> BotResult<()> {
         sqlx::query(
             r#"
-            INSERT INTO trades (timestamp, mint, profit_lamports, signature, dexes, input_amount, output_amount)
-            VALUES (?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO trades (timestamp, mint, profit_lamports, realized_profit_lamports, signature, dexes, input_amount, output_amount,
+                base_fee_lamports, priority_fee_lamports, tip_lamports, protocol_fee_lamports)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#
         )
-        .bind(timestamp)
-        .bind(mint)
-        .bind(profit as i64)
-        .bind(signature)
-        .bind(dexes_str)
-        .bind(input as i64)
-        .bind(output as i64)
+        .bind(record.timestamp)
+        .bind(&record.mint)
+        .bind(record.profit_lamports)
+        .bind(record.realized_profit_lamports)
+        .bind(&record.signature)
+        .bind(&record.dexes)
+        .bind(record.input)
+        .bind(record.output)
+        .bind(record.base_fee_lamports)
+        .bind(record.priority_fee_lamports)
+        .bind(record.tip_lamports)
+        .bind(record.protocol_fee_lamports)
         .execute(&self.pool)
         .await
         .map_err(|e| crate::error::BotError::TransactionError(format!("Failed to log trade: {}", e)))?;
@@ -79,6 +216,102 @@ impl Database {
         Ok(())
     }
 
+    /// Log every send result that carries a real signature, returning how many were logged
+    pub async fn log_send_results(
+        &self,
+        mint: &str,
+        results: &[SendResult],
+        dexes: &[String],
+    ) -> BotResult<usize> {
+        let mut logged = 0;
+        for result in loggable_results(results) {
+            self.insert_trade(
+                &TradeRecord::new(
+                    mint,
+                    result.simulated_profit.unwrap_or(0) as i64,
+                    &result.signature.to_string(),
+                    dexes,
+                    0,
+                    0,
+                )
+                .with_realized_profit(result.realized_profit)
+                .with_landed_fees(result),
+            )
+            .await?;
+            logged += 1;
+        }
+        Ok(logged)
+    }
+
+    /// Log the send results of an opportunity with its route, sizing and net profit
+    /// (see `opportunity_record`)
+    pub async fn log_opportunity(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        net_profit: Option<i64>,
+        results: &[SendResult],
+    ) -> BotResult<usize> {
+        let mut logged = 0;
+        for result in loggable_results(results) {
+            self.insert_trade(&opportunity_record(opportunity, net_profit, result)).await?;
+            logged += 1;
+        }
+        Ok(logged)
+    }
+
+    /// Trades with `from_ts <= timestamp <= to_ts`, oldest first
+    pub async fn get_trades_between(&self, from_ts: i64, to_ts: i64) -> BotResult<Vec<TradeRecord>> {
+        let rows: Vec<(i64, String, i64, Option<i64>, String, String, i64, i64, i64, i64, i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT timestamp, mint, profit_lamports, realized_profit_lamports, signature, dexes, input_amount, output_amount,
+                base_fee_lamports, priority_fee_lamports, tip_lamports, protocol_fee_lamports
+            FROM trades
+            WHERE timestamp >= ? AND timestamp <= ?
+            ORDER BY timestamp ASC
+            "#
+        )
+        .bind(from_ts)
+        .bind(to_ts)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| crate::error::BotError::Other(anyhow::anyhow!("Failed to fetch trades: {}", e)))?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(
+                    timestamp,
+                    mint,
+                    profit_lamports,
+                    realized_profit_lamports,
+                    signature,
+                    dexes,
+                    input,
+                    output,
+                    base_fee_lamports,
+                    priority_fee_lamports,
+                    tip_lamports,
+                    protocol_fee_lamports,
+                )| {
+                    TradeRecord {
+                        timestamp,
+                        mint,
+                        profit_lamports,
+                        realized_profit_lamports,
+                        signature,
+                        dexes,
+                        input,
+                        output,
+                        base_fee_lamports,
+                        priority_fee_lamports,
+                        tip_lamports,
+                        protocol_fee_lamports,
+                    }
+                },
+            )
+            .collect())
+    }
+
     pub async fn get_total_profit(&self) -> BotResult<u64> {
         let result: (i64,) = sqlx::query_as("SELECT COALESCE(SUM(profit_lamports), 0) FROM trades")
             .fetch_one(&self.pool)
@@ -87,4 +320,373 @@ impl Database {
 
         Ok(result.0 as u64)
     }
+
+    /// Fees of every landed trade, by kind, since unconfirmed rows record none. A
+    /// signature sent through several endpoints is one transaction and counted once.
+    pub async fn get_total_fees(&self) -> BotResult<FeeBreakdown> {
+        let (base, priority, tip, protocol): (i64, i64, i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(base), 0), COALESCE(SUM(priority), 0), COALESCE(SUM(tip), 0), COALESCE(SUM(protocol), 0)
+            FROM (
+                SELECT MAX(base_fee_lamports) AS base, MAX(priority_fee_lamports) AS priority,
+                    MAX(tip_lamports) AS tip, MAX(protocol_fee_lamports) AS protocol
+                FROM trades
+                GROUP BY signature
+            )
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| crate::error::BotError::Other(anyhow::anyhow!("Failed to fetch fees: {}", e)))?;
+
+        Ok(FeeBreakdown {
+            base_fee_lamports: base as u64,
+            priority_fee_lamports: priority as u64,
+            tip_lamports: tip as u64,
+            protocol_fee_lamports: protocol as u64,
+        })
+    }
+}
+
+#[async_trait]
+impl TradeSink for Database {
+    async fn write_trade(&self, record: &TradeRecord) -> BotResult<()> {
+        self.insert_trade(record).await
+    }
+}
+
+struct TradeLogState {
+    consecutive_failures: u32,
+    pending: VecDeque<TradeRecord>,
+}
+
+/// Trade logging that never fails the caller
+///
+/// Failed writes are counted in `db_write_failures`, logged as warnings (errors after
+/// `DB_FAILURE_ERROR_THRESHOLD` in a row) and kept in a bounded buffer that is
+/// flushed, oldest first, on the next write once the database recovers.
+pub struct TradeLog<S: TradeSink = Database> {
+    sink: S,
+    buffer_capacity: usize,
+    state: Mutex<TradeLogState>,
+}
+
+impl<S: TradeSink> TradeLog<S> {
+    /// `buffer_capacity` of 0 drops failed writes instead of retrying them
+    pub fn new(sink: S, buffer_capacity: usize) -> Self {
+        Self {
+            sink,
+            buffer_capacity,
+            state: Mutex::new(TradeLogState {
+                consecutive_failures: 0,
+                pending: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Write a trade after any buffered ones
+    pub async fn record(&self, record: TradeRecord) {
+        let mut state = self.state.lock().await;
+        state.pending.push_back(record);
+
+        loop {
+            let result = match state.pending.front() {
+                Some(next) => self.sink.write_trade(next).await,
+                None => break,
+            };
+            match result {
+                Ok(()) => {
+                    state.pending.pop_front();
+                    if state.consecutive_failures > 0 {
+                        info!(
+                            "Database writes recovered after {} failures",
+                            state.consecutive_failures
+                        );
+                        state.consecutive_failures = 0;
+                    }
+                }
+                Err(e) => {
+                    state.consecutive_failures += 1;
+                    METRICS.inc_db_write_failure();
+                    if state.consecutive_failures >= DB_FAILURE_ERROR_THRESHOLD {
+                        error!(
+                            "Database write failed {} times in a row ({} trades pending): {}",
+                            state.consecutive_failures,
+                            state.pending.len(),
+                            e
+                        );
+                    } else {
+                        warn!("Database write failed ({} trades pending): {}", state.pending.len(), e);
+                    }
+                    break;
+                }
+            }
+        }
+
+        let overflow = state.pending.len().saturating_sub(self.buffer_capacity);
+        if overflow > 0 {
+            state.pending.drain(..overflow);
+            warn!("Dropped {} trade records that could not be written to the database", overflow);
+        }
+    }
+
+    /// Record the opportunity's send results that carry a real signature like
+    /// `Database::log_opportunity`, returning how many were recorded
+    pub async fn log_opportunity(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        net_profit: Option<i64>,
+        results: &[SendResult],
+    ) -> usize {
+        let mut recorded = 0;
+        for result in loggable_results(results) {
+            self.record(opportunity_record(opportunity, net_profit, result)).await;
+            recorded += 1;
+        }
+        recorded
+    }
+
+    /// Trades waiting for the database to recover
+    pub async fn pending(&self) -> usize {
+        self.state.lock().await.pending.len()
+    }
+}
+
+/// Row for one send of `opportunity`. `net_profit` is the quote's profit after fees (see
+/// `ArbitrageOpportunity::net_profit`); routes sized on-chain pass None and record what
+/// simulation reported, or 0 when they were not simulated.
+pub fn opportunity_record(
+    opportunity: &ArbitrageOpportunity,
+    net_profit: Option<i64>,
+    result: &SendResult,
+) -> TradeRecord {
+    TradeRecord::new(
+        &opportunity.mint.to_string(),
+        send_profit(net_profit, result),
+        &result.signature.to_string(),
+        &opportunity.route,
+        opportunity.input_amount,
+        opportunity.expected_output,
+    )
+    .with_realized_profit(result.realized_profit)
+    .with_landed_fees(result)
+}
+
+fn send_profit(net_profit: Option<i64>, result: &SendResult) -> i64 {
+    net_profit.or_else(|| result.simulated_net_profit()).unwrap_or(0)
+}
+
+/// Net profit of the landed sends among `results`, as `opportunity_record` books it, for
+/// `total_profit_lamports`. Each signature counts once however many endpoints accepted it.
+pub fn landed_profit(net_profit: Option<i64>, results: &[SendResult]) -> u64 {
+    let mut counted = HashSet::new();
+    loggable_results(results)
+        .filter(|result| result.landed && counted.insert(result.signature))
+        .map(|result| send_profit(net_profit, result).max(0) as u64)
+        .sum()
+}
+
+/// Send results worth persisting (placeholder signatures are skipped)
+pub fn loggable_results(results: &[SendResult]) -> impl Iterator<Item = &SendResult> {
+    results.iter().filter(|result| result.has_signature())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::BotError;
+    use solana_sdk::signature::Signature;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Fails every write while `down` is set
+    struct FlakySink {
+        down: AtomicBool,
+        written: std::sync::Mutex<Vec<TradeRecord>>,
+    }
+
+    #[async_trait]
+    impl TradeSink for FlakySink {
+        async fn write_trade(&self, record: &TradeRecord) -> BotResult<()> {
+            if self.down.load(Ordering::SeqCst) {
+                return Err(BotError::Other(anyhow::anyhow!("database is locked")));
+            }
+            self.written.lock().unwrap().push(record.clone());
+            Ok(())
+        }
+    }
+
+    fn record(signature: &str) -> TradeRecord {
+        TradeRecord::new("mint", 1_000, signature, &["Raydium".to_string()], 0, 0)
+    }
+
+    #[test]
+    fn test_loggable_results_skip_default_signature() {
+        let real = SendResult {
+            signature: Signature::new_unique(),
+            endpoint: "https://rpc-a".to_string(),
+            simulated_profit: Some(5_000),
+            landed: true,
+            realized_profit: None,
+            fees: FeeBreakdown::default(),
+        };
+        let placeholder = SendResult {
+            signature: Signature::default(),
+            endpoint: "https://rpc-b".to_string(),
+            simulated_profit: None,
+            landed: false,
+            realized_profit: None,
+            fees: FeeBreakdown::default(),
+        };
+        let results = vec![real.clone(), placeholder];
+
+        let logged: Vec<_> = loggable_results(&results).collect();
+        assert_eq!(logged, vec![&real]);
+        assert_eq!(logged[0].simulated_profit.unwrap_or(0), 5_000);
+    }
+
+    #[tokio::test]
+    async fn test_trade_log_flushes_buffer_after_recovery() {
+        let sink = FlakySink {
+            down: AtomicBool::new(true),
+            written: std::sync::Mutex::new(Vec::new()),
+        };
+        let log = TradeLog::new(sink, 2);
+
+        log.record(record("a")).await;
+        log.record(record("b")).await;
+        log.record(record("c")).await;
+        // Bounded: the oldest record is dropped
+        assert_eq!(log.pending().await, 2);
+        assert!(log.sink.written.lock().unwrap().is_empty());
+
+        log.sink.down.store(false, Ordering::SeqCst);
+        log.record(record("d")).await;
+
+        assert_eq!(log.pending().await, 0);
+        let signatures: Vec<String> = log
+            .sink
+            .written
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.signature.clone())
+            .collect();
+        assert_eq!(signatures, vec!["b", "c", "d"]);
+        assert_eq!(log.state.lock().await.consecutive_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_trade_log_records_net_profit() {
+        let log = TradeLog::new(
+            FlakySink {
+                down: AtomicBool::new(false),
+                written: std::sync::Mutex::new(Vec::new()),
+            },
+            0,
+        );
+        let opportunity = ArbitrageOpportunity::test_quote(0, 0);
+        let fees = FeeBreakdown {
+            base_fee_lamports: 5_000,
+            priority_fee_lamports: 15_000,
+            ..FeeBreakdown::default()
+        };
+        let simulated = SendResult {
+            signature: Signature::new_unique(),
+            endpoint: "https://rpc-a".to_string(),
+            simulated_profit: Some(50_000),
+            landed: false,
+            realized_profit: None,
+            fees,
+        };
+        let unsimulated = SendResult {
+            signature: Signature::new_unique(),
+            simulated_profit: None,
+            ..simulated.clone()
+        };
+        let results = [simulated, unsimulated];
+
+        // Sized on-chain: what simulation reported, less the fees
+        assert_eq!(log.log_opportunity(&opportunity, None, &results).await, 2);
+        // A quote's net profit applies to every send of the route
+        assert_eq!(log.log_opportunity(&opportunity, Some(12_000), &results).await, 2);
+
+        let profits: Vec<i64> = log.sink.written.lock().unwrap().iter().map(|r| r.profit_lamports).collect();
+        assert_eq!(profits, vec![30_000, 0, 12_000, 12_000]);
+    }
+
+    #[test]
+    fn test_landed_profit_counts_each_signature_once() {
+        let landed = SendResult {
+            signature: Signature::new_unique(),
+            endpoint: "https://rpc-a".to_string(),
+            simulated_profit: Some(50_000),
+            landed: true,
+            realized_profit: None,
+            fees: FeeBreakdown {
+                base_fee_lamports: 5_000,
+                ..FeeBreakdown::default()
+            },
+        };
+        let second_endpoint = SendResult {
+            endpoint: "https://rpc-b".to_string(),
+            ..landed.clone()
+        };
+        let unconfirmed = SendResult {
+            signature: Signature::new_unique(),
+            landed: false,
+            ..landed.clone()
+        };
+        let results = [landed, second_endpoint, unconfirmed];
+
+        assert_eq!(landed_profit(None, &results), 45_000);
+        assert_eq!(landed_profit(Some(12_000), &results), 12_000);
+        assert_eq!(landed_profit(Some(-3_000), &results), 0);
+    }
+
+    #[tokio::test]
+    async fn test_logged_fee_breakdown_adds_up() {
+        let path = std::env::temp_dir().join(format!("trade-fees-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let db = Database::new(&format!("sqlite://{}?mode=rwc", path.display())).await.unwrap();
+        let fees = FeeBreakdown {
+            base_fee_lamports: 5_000,
+            priority_fee_lamports: 60_000,
+            tip_lamports: 100_000,
+            protocol_fee_lamports: 7_500,
+        };
+        assert_eq!(fees.total(), 172_500);
+        let dexes = ["Raydium".to_string(), "Pump.fun".to_string()];
+        let landed = SendResult {
+            signature: Signature::new_unique(),
+            endpoint: "https://rpc-a".to_string(),
+            simulated_profit: Some(50_000),
+            landed: true,
+            realized_profit: None,
+            fees,
+        };
+        // The same signature accepted by a second endpoint pays once
+        let second_endpoint = SendResult {
+            endpoint: "https://rpc-b".to_string(),
+            ..landed.clone()
+        };
+        // Sent but never confirmed, so it may never have been paid for
+        let unconfirmed = SendResult {
+            signature: Signature::new_unique(),
+            landed: false,
+            ..landed.clone()
+        };
+        let logged = db
+            .log_send_results("mint", &[landed, second_endpoint, unconfirmed], &dexes)
+            .await
+            .unwrap();
+        assert_eq!(logged, 3);
+
+        let trades = db.get_trades_between(0, i64::MAX).await.unwrap();
+        assert_eq!(trades[0].fees(), fees);
+        assert_eq!(trades[2].fees(), FeeBreakdown::default());
+        let total = db.get_total_fees().await.unwrap();
+        assert_eq!(total, fees);
+        assert_eq!(total.total(), 172_500);
+        std::fs::remove_file(&path).unwrap();
+    }
 }
@@ -0,0 +1,142 @@
+/// Trade History Export
+///
+/// Writes trade rows from the database as CSV or JSON for spreadsheet analysis.
+
+use crate::database::TradeRecord;
+use crate::error::{BotError, BotResult};
+use std::io::Write;
+
+/// Output format for `export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// Write `trades` to `writer`, returning the number of rows written.
+/// CSV output always includes the header row, even for an empty range.
+pub fn export_trades<W: Write>(
+    trades: &[TradeRecord],
+    format: ExportFormat,
+    writer: W,
+) -> BotResult<usize> {
+    match format {
+        ExportFormat::Csv => {
+            let mut csv_writer = csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(writer);
+            csv_writer
+                .write_record([
+                    "timestamp",
+                    "mint",
+                    "profit_lamports",
+                    "realized_profit_lamports",
+                    "signature",
+                    "dexes",
+                    "input",
+                    "output",
+                    "base_fee_lamports",
+                    "priority_fee_lamports",
+                    "tip_lamports",
+                    "protocol_fee_lamports",
+                ])
+                .map_err(export_error)?;
+            for trade in trades {
+                csv_writer.serialize(trade).map_err(export_error)?;
+            }
+            csv_writer.flush()?;
+        }
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(writer, trades).map_err(export_error)?;
+        }
+    }
+
+    Ok(trades.len())
+}
+
+fn export_error(e: impl std::error::Error + Send + Sync + 'static) -> BotError {
+    BotError::Other(anyhow::Error::new(e).context("Failed to export trades"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trades() -> Vec<TradeRecord> {
+        vec![
+            TradeRecord {
+                timestamp: 1_700_000_000,
+                mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+                profit_lamports: 5_000,
+                realized_profit_lamports: Some(4_200),
+                signature: "sig-1".to_string(),
+                dexes: "Raydium CPMM,Meteora DLMM".to_string(),
+                input: 1_000_000,
+                output: 1_005_000,
+                base_fee_lamports: 5_000,
+                priority_fee_lamports: 40_000,
+                tip_lamports: 100_000,
+                protocol_fee_lamports: 5_012,
+            },
+            TradeRecord {
+                timestamp: 1_700_000_060,
+                mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+                profit_lamports: 0,
+                realized_profit_lamports: None,
+                signature: "sig-2".to_string(),
+                dexes: "Pump.fun".to_string(),
+                input: 0,
+                output: 0,
+                base_fee_lamports: 5_000,
+                priority_fee_lamports: 0,
+                tip_lamports: 0,
+                protocol_fee_lamports: 0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_csv_export_round_trip() {
+        let mut buffer = Vec::new();
+        assert_eq!(export_trades(&trades(), ExportFormat::Csv, &mut buffer).unwrap(), 2);
+
+        let mut reader = csv::Reader::from_reader(buffer.as_slice());
+        assert_eq!(
+            reader.headers().unwrap(),
+            vec![
+                "timestamp",
+                "mint",
+                "profit_lamports",
+                "realized_profit_lamports",
+                "signature",
+                "dexes",
+                "input",
+                "output",
+                "base_fee_lamports",
+                "priority_fee_lamports",
+                "tip_lamports",
+                "protocol_fee_lamports",
+            ]
+        );
+        let parsed: Vec<TradeRecord> = reader.deserialize().map(|row| row.unwrap()).collect();
+        assert_eq!(parsed, trades());
+    }
+
+    #[test]
+    fn test_json_export_round_trip() {
+        let mut buffer = Vec::new();
+        export_trades(&trades(), ExportFormat::Json, &mut buffer).unwrap();
+
+        let parsed: Vec<TradeRecord> = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(parsed, trades());
+    }
+
+    #[test]
+    fn test_empty_export_writes_header_only() {
+        let mut buffer = Vec::new();
+        assert_eq!(export_trades(&[], ExportFormat::Csv, &mut buffer).unwrap(), 0);
+
+        let text = String::from_utf8(buffer).unwrap();
+        assert_eq!(text.lines().count(), 1);
+    }
+}
@@ -0,0 +1,224 @@
+/// Trade Event Log
+///
+/// Appends each executed trade as one JSON line to a dedicated file, written from
+/// a background thread so sends never wait on disk and independent of the
+/// tracing subscriber's level.
+
+use crate::error::BotResult;
+use crate::transaction::SendResult;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use tracing::warn;
+
+/// One line of the trade log
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TradeEvent {
+    pub timestamp: i64,
+    pub mint: String,
+    pub signature: String,
+    /// The quote's profit after fees, or what simulation reported for a route sized
+    /// on-chain; null when the trade was neither quoted nor simulated
+    pub net_profit_lamports: Option<i64>,
+    pub route: Vec<String>,
+}
+
+impl TradeEvent {
+    /// Event stamped with the current time
+    pub fn new(mint: &str, signature: &str, net_profit_lamports: Option<i64>, route: &[String]) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp(),
+            mint: mint.to_string(),
+            signature: signature.to_string(),
+            net_profit_lamports,
+            route: route.to_vec(),
+        }
+    }
+}
+
+/// Non-blocking JSON-lines writer; dropping it flushes every queued event
+pub struct TradeEventWriter {
+    sender: Option<mpsc::Sender<TradeEvent>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TradeEventWriter {
+    /// Open `path` for appending. With `rotate`, an existing non-empty log is first
+    /// moved aside to `<path>.<unix timestamp>` so each run starts a fresh file.
+    pub fn open(path: &Path, rotate: bool) -> BotResult<Self> {
+        if rotate && path.metadata().map_or(false, |metadata| metadata.len() > 0) {
+            std::fs::rename(path, rotated_path(path, chrono::Utc::now().timestamp()))?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        let (sender, receiver) = mpsc::channel();
+        let thread = std::thread::spawn(move || write_events(file, receiver));
+        Ok(Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        })
+    }
+
+    /// Queue an event for writing
+    pub fn log(&self, event: TradeEvent) {
+        if let Some(sender) = &self.sender {
+            // Only fails if the writer thread died, which it already reported
+            let _ = sender.send(event);
+        }
+    }
+
+    /// Log every send result that carries a real signature, with the quoted route's
+    /// `net_profit` (None for a route sized on-chain)
+    pub fn log_send_results(&self, mint: &str, net_profit: Option<i64>, results: &[SendResult], route: &[String]) {
+        for result in crate::database::loggable_results(results) {
+            self.log(TradeEvent::new(
+                mint,
+                &result.signature.to_string(),
+                net_profit.or_else(|| result.simulated_net_profit()),
+                route,
+            ));
+        }
+    }
+}
+
+impl Drop for TradeEventWriter {
+    fn drop(&mut self) {
+        // Closing the channel lets the thread drain the queue and exit
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn rotated_path(path: &Path, timestamp: i64) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(format!(".{}", timestamp));
+    PathBuf::from(rotated)
+}
+
+fn write_events(file: File, receiver: mpsc::Receiver<TradeEvent>) {
+    let mut writer = BufWriter::new(file);
+    for event in receiver {
+        let written = serde_json::to_writer(&mut writer, &event)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writer.write_all(b"\n"))
+            .and_then(|_| writer.flush());
+        if let Err(e) = written {
+            warn!("Failed to write trade event for {}: {}", event.signature, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+
+    fn temp_log(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}.jsonl", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn read_events(path: &Path) -> Vec<TradeEvent> {
+        BufReader::new(File::open(path).unwrap())
+            .lines()
+            .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_trades_written_as_json_lines() {
+        let path = temp_log("trade-events");
+        let route = vec!["Pump.fun".to_string(), "Raydium CPMM".to_string()];
+        let events = vec![
+            TradeEvent::new("mint-a", "sig-1", Some(12_000), &route),
+            TradeEvent::new("mint-b", "sig-2", Some(-3_000), &route[..1]),
+            TradeEvent::new("mint-b", "sig-3", None, &route),
+        ];
+
+        let writer = TradeEventWriter::open(&path, false).unwrap();
+        for event in &events {
+            writer.log(event.clone());
+        }
+        drop(writer);
+        assert_eq!(read_events(&path), events);
+
+        // Appending keeps earlier lines
+        let writer = TradeEventWriter::open(&path, false).unwrap();
+        writer.log(events[0].clone());
+        drop(writer);
+        assert_eq!(read_events(&path).len(), 4);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_unsimulated_route_logs_no_profit() {
+        use crate::transaction::FeeBreakdown;
+        use solana_sdk::signature::Signature;
+
+        let path = temp_log("trade-events-profit");
+        let simulated = SendResult {
+            signature: Signature::new_unique(),
+            endpoint: "https://rpc-a".to_string(),
+            simulated_profit: Some(20_000),
+            landed: false,
+            realized_profit: None,
+            fees: FeeBreakdown {
+                base_fee_lamports: 5_000,
+                ..FeeBreakdown::default()
+            },
+        };
+        let unsimulated = SendResult {
+            signature: Signature::new_unique(),
+            simulated_profit: None,
+            ..simulated.clone()
+        };
+
+        let writer = TradeEventWriter::open(&path, false).unwrap();
+        writer.log_send_results("mint", None, &[simulated.clone(), unsimulated], &[]);
+        writer.log_send_results("mint", Some(7_000), &[simulated], &[]);
+        drop(writer);
+        let profits: Vec<Option<i64>> = read_events(&path)
+            .into_iter()
+            .map(|event| event.net_profit_lamports)
+            .collect();
+        assert_eq!(profits, vec![Some(15_000), None, Some(7_000)]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_rotate_moves_existing_log_aside() {
+        let path = temp_log("trade-events-rotate");
+        let event = TradeEvent::new("mint", "sig", Some(1), &[]);
+
+        let writer = TradeEventWriter::open(&path, true).unwrap();
+        writer.log(event.clone());
+        drop(writer);
+
+        let writer = TradeEventWriter::open(&path, true).unwrap();
+        drop(writer);
+        assert!(read_events(&path).is_empty());
+
+        let rotated: Vec<PathBuf> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|candidate| {
+                candidate != &path
+                    && candidate
+                        .to_string_lossy()
+                        .starts_with(&*path.to_string_lossy())
+            })
+            .collect();
+        assert_eq!(rotated.len(), 1);
+        assert_eq!(read_events(&rotated[0]), vec![event]);
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&rotated[0]).unwrap();
+    }
+}
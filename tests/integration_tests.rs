@@ -34,6 +34,8 @@ mod dex_initializer_integration_tests {
             sol_vault: Pubkey::new_unique(),
             coin_mint: Pubkey::new_unique(),
             pc_mint: Pubkey::new_unique(),
+            token_reserve: 0,
+            sol_reserve: 0,
         };
 
         assert_eq!(pool.dex_name(), "Raydium CPMM");